@@ -0,0 +1,291 @@
+use crate::errors::{AccumulatorError, Result};
+use crate::merkle_tree::{Proof, ProofNode, TreePath, TREE_DEPTH};
+use noah_algebra::{bn254::BN254Scalar, borrow::ToOwned, prelude::*};
+use noah_crypto::anemoi_jive::{AnemoiJive, AnemoiJive254, ANEMOI_JIVE_BN254_SALTS};
+
+/// Compute the hash of the children of a node at the level, as in the 3-ary merkle tree.
+fn hash_node(level: usize, children: &[BN254Scalar; 3]) -> BN254Scalar {
+    AnemoiJive254::eval_jive(
+        &[children[0], children[1]],
+        &[children[2], ANEMOI_JIVE_BN254_SALTS[level]],
+    )
+}
+
+/// Return the position among its siblings of the ancestor of the leaf at the level.
+fn position(uid: u64, level: usize) -> usize {
+    ((uid / 3u64.pow(level as u32)) % 3) as usize
+}
+
+/// Return the position of the direction in the branch.
+fn path_position(path: TreePath) -> usize {
+    match path {
+        TreePath::Left => 0,
+        TreePath::Middle => 1,
+        TreePath::Right => 2,
+    }
+}
+
+/// Return the number of leaves under a node at the level above the given one.
+fn group_size(level: usize) -> u64 {
+    3u64.pow(level as u32 + 1)
+}
+
+///
+/// MerkleFrontier is the rightmost path of the 3-ary merkle tree, which is enough
+/// to append new leaves and compute the new roots without the rest of the tree.
+///
+#[derive(Clone, Debug)]
+pub struct MerkleFrontier {
+    entry_count: u64,
+    // the children of the ancestors of the last leaf, from lower(leaf) to upper.
+    nodes: Vec<[BN254Scalar; 3]>,
+    root: BN254Scalar,
+}
+
+impl Default for MerkleFrontier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MerkleFrontier {
+    /// Generates the frontier of an empty tree.
+    pub fn new() -> MerkleFrontier {
+        MerkleFrontier {
+            entry_count: 0,
+            nodes: vec![[BN254Scalar::zero(); 3]; TREE_DEPTH],
+            root: BN254Scalar::zero(),
+        }
+    }
+
+    /// Recover the frontier from the proof of the last leaf of the tree.
+    pub fn from_proof(proof: &Proof) -> Result<MerkleFrontier> {
+        if proof.nodes.len() != TREE_DEPTH {
+            return Err(AccumulatorError::Message(
+                "tree depth is invalid for the frontier".to_owned(),
+            ));
+        }
+
+        let mut nodes = Vec::with_capacity(TREE_DEPTH);
+        for (level, node) in proof.nodes.iter().enumerate() {
+            let pos = position(proof.uid, level);
+            let children = [node.left, node.mid, node.right];
+            // the nodes on the right of the last leaf are empty
+            if path_position(node.path) != pos || children[pos + 1..].iter().any(|c| !c.is_zero()) {
+                return Err(AccumulatorError::Message(
+                    "the proof is not of the last leaf".to_owned(),
+                ));
+            }
+            nodes.push(children);
+        }
+
+        let root = hash_node(TREE_DEPTH - 1, &nodes[TREE_DEPTH - 1]);
+        if root != proof.root {
+            return Err(AccumulatorError::Message(
+                "the proof does not match its root".to_owned(),
+            ));
+        }
+
+        Ok(MerkleFrontier {
+            entry_count: proof.uid + 1,
+            nodes,
+            root,
+        })
+    }
+
+    /// add a new leaf and return the leaf uid.
+    pub fn add_commitment_hash(&mut self, hash: BN254Scalar) -> Result<u64> {
+        let uid = self.entry_count;
+        if uid >= 3u64.pow(TREE_DEPTH as u32) {
+            return Err(AccumulatorError::Message("the tree is full".to_owned()));
+        }
+
+        let mut hash = hash;
+        for (level, children) in self.nodes.iter_mut().enumerate() {
+            // the first leaf under a new node at the level above
+            if uid % group_size(level) == 0 {
+                *children = [BN254Scalar::zero(); 3];
+            }
+            children[position(uid, level)] = hash;
+            hash = hash_node(level, children);
+        }
+
+        self.root = hash;
+        self.entry_count += 1;
+        Ok(uid)
+    }
+
+    /// get tree current root
+    pub fn get_root(&self) -> BN254Scalar {
+        self.root
+    }
+
+    /// get the number of entries
+    pub fn entry_count(&self) -> u64 {
+        self.entry_count
+    }
+}
+
+///
+/// IncrementalWitness keeps the merkle proof of a leaf current as new leaves are added,
+/// so that a wallet can prove the membership of its records against the latest root
+/// without fetching the tree again.
+///
+/// # Example
+/// ```
+/// use noah_accumulators::incremental_witness::{IncrementalWitness, MerkleFrontier};
+/// use noah_accumulators::merkle_tree::verify;
+/// use noah_algebra::{bn254::BN254Scalar, One, Zero};
+///
+/// let mut frontier = MerkleFrontier::new();
+/// frontier.add_commitment_hash(BN254Scalar::one()).unwrap();
+/// let mut witness = IncrementalWitness::new(&frontier).unwrap();
+///
+/// witness.add_commitment_hash(BN254Scalar::zero()).unwrap();
+/// assert!(verify(BN254Scalar::one(), &witness.proof(0)));
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct IncrementalWitness {
+    uid: u64,
+    leaf: BN254Scalar,
+    // the children of the ancestors of the leaf, from lower(leaf) to upper.
+    nodes: Vec<[BN254Scalar; 3]>,
+    frontier: MerkleFrontier,
+}
+
+impl IncrementalWitness {
+    /// Start the witness of the last leaf added to the frontier.
+    pub fn new(frontier: &MerkleFrontier) -> Result<IncrementalWitness> {
+        if frontier.entry_count == 0 {
+            return Err(AccumulatorError::Message("the tree is empty".to_owned()));
+        }
+
+        let uid = frontier.entry_count - 1;
+        Ok(IncrementalWitness {
+            uid,
+            leaf: frontier.nodes[0][position(uid, 0)],
+            nodes: frontier.nodes.clone(),
+            frontier: frontier.clone(),
+        })
+    }
+
+    /// add a new leaf to the tree, after the leaf of the witness.
+    pub fn add_commitment_hash(&mut self, hash: BN254Scalar) -> Result<u64> {
+        let uid = self.frontier.add_commitment_hash(hash)?;
+        for (level, children) in self.nodes.iter_mut().enumerate() {
+            // the new leaf shares the ancestor at the level above with the leaf of the witness
+            if uid / group_size(level) == self.uid / group_size(level) {
+                *children = self.frontier.nodes[level];
+            }
+        }
+        Ok(uid)
+    }
+
+    /// get the uid of the leaf.
+    pub fn uid(&self) -> u64 {
+        self.uid
+    }
+
+    /// get the leaf hash.
+    pub fn leaf(&self) -> BN254Scalar {
+        self.leaf
+    }
+
+    /// get tree current root
+    pub fn get_root(&self) -> BN254Scalar {
+        self.frontier.root
+    }
+
+    /// get the number of entries
+    pub fn entry_count(&self) -> u64 {
+        self.frontier.entry_count
+    }
+
+    /// generate the leaf's merkle proof against the current root, which has the given version.
+    pub fn proof(&self, root_version: u64) -> Proof {
+        let nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(level, children)| ProofNode {
+                left: children[0],
+                mid: children[1],
+                right: children[2],
+                path: match position(self.uid, level) {
+                    0 => TreePath::Left,
+                    1 => TreePath::Middle,
+                    _ => TreePath::Right,
+                },
+            })
+            .collect();
+
+        Proof {
+            nodes,
+            root: self.frontier.root,
+            root_version,
+            uid: self.uid,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IncrementalWitness, MerkleFrontier};
+    use crate::merkle_tree::{verify, EphemeralMerkleTree, Proof};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    fn assert_same_proof(a: &Proof, b: &Proof) {
+        assert_eq!(a.uid, b.uid);
+        assert_eq!(a.root, b.root);
+        assert_eq!(a.nodes.len(), b.nodes.len());
+        for (x, y) in a.nodes.iter().zip(b.nodes.iter()) {
+            assert_eq!(
+                (x.left, x.mid, x.right, x.path),
+                (y.left, y.mid, y.right, y.path)
+            );
+        }
+    }
+
+    #[test]
+    fn test_incremental_witness() {
+        let mut prng = test_rng();
+        let mut tree = EphemeralMerkleTree::new().unwrap();
+        let mut frontier = MerkleFrontier::new();
+        assert!(IncrementalWitness::new(&frontier).is_err());
+
+        let mut witnesses = vec![];
+        for i in 0..40u64 {
+            let hash = BN254Scalar::random(&mut prng);
+            assert_eq!(tree.add_commitment_hash(hash).unwrap(), i);
+            assert_eq!(frontier.add_commitment_hash(hash).unwrap(), i);
+            assert_eq!(frontier.get_root(), tree.get_root().unwrap());
+
+            for witness in witnesses.iter_mut() {
+                witness.add_commitment_hash(hash).unwrap();
+            }
+            if [0, 2, 3, 8, 26, 27].contains(&i) {
+                witnesses.push(IncrementalWitness::new(&frontier).unwrap());
+            }
+        }
+
+        for witness in witnesses.iter() {
+            let proof = witness.proof(0);
+            assert!(verify(witness.leaf(), &proof));
+            assert_same_proof(&proof, &tree.generate_proof(witness.uid()).unwrap());
+        }
+
+        // the frontier is recovered from the proof of the last leaf
+        let proof = tree.generate_proof(tree.entry_count() - 1).unwrap();
+        let mut recovered = MerkleFrontier::from_proof(&proof).unwrap();
+        assert_eq!(recovered.entry_count(), frontier.entry_count());
+        assert_eq!(recovered.get_root(), frontier.get_root());
+        let hash = BN254Scalar::random(&mut prng);
+        recovered.add_commitment_hash(hash).unwrap();
+        tree.add_commitment_hash(hash).unwrap();
+        assert_eq!(recovered.get_root(), tree.get_root().unwrap());
+
+        let proof = tree.generate_proof(3).unwrap();
+        assert!(MerkleFrontier::from_proof(&proof).is_err());
+    }
+}
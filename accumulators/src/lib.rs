@@ -19,5 +19,11 @@
 /// The module for the Merkle tree implementation
 pub mod merkle_tree;
 
+/// The module for the Merkle tree with four or eight children per node
+pub mod nary_merkle_tree;
+
+/// The module for the merkle proofs that are kept current as new leaves are added
+pub mod incremental_witness;
+
 /// The module for error handling
 pub mod errors;
@@ -0,0 +1,241 @@
+use crate::errors::{AccumulatorError, Result};
+use noah_algebra::{bn254::BN254Scalar, borrow::ToOwned, collections::HashMap, prelude::*};
+use noah_crypto::anemoi_jive::{AnemoiJive, AnemoiJive254, ANEMOI_JIVE_BN254_SALTS};
+
+/// The supported numbers of children of a node in the N-ary Merkle tree.
+pub const NARY_MERKLE_TREE_ARITIES: [usize; 2] = [4, 8];
+
+/// Compute the hash of the children of a node at the level, counted from the leaves,
+/// as the Anemoi-Jive variable-length hash of the salt of the level and the children.
+pub fn hash_nary_children(level: usize, children: &[BN254Scalar]) -> BN254Scalar {
+    let mut input = Vec::with_capacity(children.len() + 1);
+    input.push(ANEMOI_JIVE_BN254_SALTS[level]);
+    input.extend_from_slice(children);
+    AnemoiJive254::eval_variable_length_hash(&input)
+}
+
+///
+/// NaryMerkleTree is an in-memory Merkle tree whose nodes have four or eight children,
+/// which, for the same capacity, has a path half or a third as long as the 3-ary tree.
+/// The empty nodes are zero.
+///
+/// # Example
+/// ```
+/// use noah_accumulators::nary_merkle_tree::{verify_nary, NaryMerkleTree};
+/// use noah_algebra::{bn254::BN254Scalar, One};
+///
+/// let mut tree = NaryMerkleTree::new(4, 10).unwrap();
+/// let uid = tree.add_commitment_hash(BN254Scalar::one()).unwrap();
+/// let proof = tree.generate_proof(uid).unwrap();
+/// assert!(verify_nary(BN254Scalar::one(), &proof));
+/// ```
+///
+pub struct NaryMerkleTree {
+    arity: usize,
+    depth: usize,
+    entry_count: u64,
+    // the non-empty nodes of each level, from the leaves to the root
+    levels: Vec<HashMap<u64, BN254Scalar>>,
+}
+
+impl NaryMerkleTree {
+    /// Generates a new NaryMerkleTree with the arity and the depth, i.e., the length of the paths.
+    pub fn new(arity: usize, depth: usize) -> Result<NaryMerkleTree> {
+        if !NARY_MERKLE_TREE_ARITIES.contains(&arity) {
+            return Err(AccumulatorError::Message(format!(
+                "unsupported arity {}",
+                arity
+            )));
+        }
+        if depth == 0
+            || depth > ANEMOI_JIVE_BN254_SALTS.len()
+            || (arity as u64).checked_pow(depth as u32).is_none()
+        {
+            return Err(AccumulatorError::Message(format!(
+                "unsupported depth {}",
+                depth
+            )));
+        }
+
+        Ok(NaryMerkleTree {
+            arity,
+            depth,
+            entry_count: 0,
+            levels: vec![HashMap::new(); depth + 1],
+        })
+    }
+
+    /// Return the number of children of a node.
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    /// Return the depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Return the maximal number of leaves.
+    pub fn capacity(&self) -> u64 {
+        (self.arity as u64).pow(self.depth as u32)
+    }
+
+    /// Return the number of the leaves.
+    pub fn entry_count(&self) -> u64 {
+        self.entry_count
+    }
+
+    fn get_node(&self, level: usize, index: u64) -> BN254Scalar {
+        self.levels[level]
+            .get(&index)
+            .copied()
+            .unwrap_or_else(BN254Scalar::zero)
+    }
+
+    fn get_children(&self, level: usize, parent: u64) -> Vec<BN254Scalar> {
+        let first = parent * self.arity as u64;
+        (0..self.arity as u64)
+            .map(|i| self.get_node(level, first + i))
+            .collect()
+    }
+
+    /// add a new leaf and return the leaf uid.
+    pub fn add_commitment_hash(&mut self, hash: BN254Scalar) -> Result<u64> {
+        if self.entry_count >= self.capacity() {
+            return Err(AccumulatorError::Message("the tree is full".to_owned()));
+        }
+
+        let uid = self.entry_count;
+        self.levels[0].insert(uid, hash);
+
+        let mut index = uid;
+        for level in 0..self.depth {
+            let parent = index / self.arity as u64;
+            let hash = hash_nary_children(level, &self.get_children(level, parent));
+            self.levels[level + 1].insert(parent, hash);
+            index = parent;
+        }
+
+        self.entry_count += 1;
+        Ok(uid)
+    }
+
+    /// Return the root of the tree.
+    pub fn get_root(&self) -> BN254Scalar {
+        self.get_node(self.depth, 0)
+    }
+
+    /// Generate the proof of the leaf.
+    pub fn generate_proof(&self, uid: u64) -> Result<NaryProof> {
+        if uid >= self.entry_count {
+            return Err(AccumulatorError::Message("uid not found".to_owned()));
+        }
+
+        let mut nodes = Vec::with_capacity(self.depth);
+        let mut index = uid;
+        for level in 0..self.depth {
+            let parent = index / self.arity as u64;
+            nodes.push(NaryProofNode {
+                children: self.get_children(level, parent),
+                position: (index % self.arity as u64) as usize,
+            });
+            index = parent;
+        }
+
+        Ok(NaryProof {
+            nodes,
+            root: self.get_root(),
+            uid,
+        })
+    }
+}
+
+/// check the proof of the leaf, against the root in the proof.
+pub fn verify_nary(leaf: BN254Scalar, proof: &NaryProof) -> bool {
+    if proof.nodes.is_empty() || proof.nodes.len() > ANEMOI_JIVE_BN254_SALTS.len() {
+        return false;
+    }
+    let arity = proof.nodes[0].children.len();
+    if !NARY_MERKLE_TREE_ARITIES.contains(&arity) {
+        return false;
+    }
+
+    let mut next = leaf;
+    for (level, node) in proof.nodes.iter().enumerate() {
+        if node.children.len() != arity || node.children.get(node.position) != Some(&next) {
+            return false;
+        }
+        next = hash_nary_children(level, &node.children);
+    }
+    next == proof.root
+}
+
+/// NaryMerkleTree Proof.
+#[derive(Clone, Debug)]
+pub struct NaryProof {
+    /// proof nodes, from lower(leaf) to upper.
+    pub nodes: Vec<NaryProofNode>,
+    /// current root.
+    pub root: BN254Scalar,
+    /// leaf's uid.
+    pub uid: u64,
+}
+
+/// NaryMerkleTree Proof Node, with all the children of the parent,
+/// including the node on the path, and the position of the latter.
+#[derive(Clone, Debug)]
+pub struct NaryProofNode {
+    /// the children of the parent.
+    pub children: Vec<BN254Scalar>,
+    /// the position of the node on the path among the children.
+    pub position: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_nary_children, verify_nary, NaryMerkleTree};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    #[test]
+    fn test_nary_merkle_tree() {
+        let mut prng = test_rng();
+        assert!(NaryMerkleTree::new(3, 10).is_err());
+        assert!(NaryMerkleTree::new(8, 0).is_err());
+        assert!(NaryMerkleTree::new(8, 22).is_err());
+
+        for arity in [4, 8] {
+            let mut tree = NaryMerkleTree::new(arity, 3).unwrap();
+            assert_eq!(tree.get_root(), BN254Scalar::zero());
+
+            let leaves = (0..arity + 2)
+                .map(|_| BN254Scalar::random(&mut prng))
+                .collect::<Vec<_>>();
+            for (i, leaf) in leaves.iter().enumerate() {
+                assert_eq!(tree.add_commitment_hash(*leaf).unwrap(), i as u64);
+            }
+            assert_eq!(tree.entry_count(), leaves.len() as u64);
+
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = tree.generate_proof(i as u64).unwrap();
+                assert_eq!(proof.nodes.len(), 3);
+                assert_eq!(proof.nodes[0].position, i % arity);
+                assert!(verify_nary(*leaf, &proof));
+                assert!(!verify_nary(leaf.add(&BN254Scalar::one()), &proof));
+            }
+            assert!(tree.generate_proof(leaves.len() as u64).is_err());
+
+            // the root is the hash of the children of the root
+            let proof = tree.generate_proof(0).unwrap();
+            assert_eq!(
+                tree.get_root(),
+                hash_nary_children(2, &proof.nodes[2].children)
+            );
+        }
+
+        let mut tree = NaryMerkleTree::new(4, 1).unwrap();
+        for _ in 0..4 {
+            tree.add_commitment_hash(BN254Scalar::one()).unwrap();
+        }
+        assert!(tree.add_commitment_hash(BN254Scalar::one()).is_err());
+    }
+}
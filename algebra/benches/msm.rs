@@ -1,5 +1,7 @@
 use ark_std::time::Instant;
+use noah_algebra::bls12_381::{BLSGt, BLSG2};
 use noah_algebra::bn254::BN254G1;
+use noah_algebra::traits::tune_msm_window_table;
 use noah_algebra::{bn254::BN254Scalar, prelude::*};
 
 fn main() {
@@ -27,4 +29,11 @@ fn main() {
         let _ = BN254G1::multi_exp(&scalars_ptr, &points_ptr);
     }
     println!("average time: {} s", start.elapsed().as_secs_f32() / 10f32);
+
+    // Measure the Pippenger window sizes of the groups without a dedicated MSM
+    let sizes = [16, 64, 256, 1024, 4096];
+    let table = tune_msm_window_table::<BLSG2, _>(&mut prng, &sizes).unwrap();
+    println!("BLS12-381 G2 window sizes: {:?}", table);
+    let table = tune_msm_window_table::<BLSGt, _>(&mut prng, &sizes).unwrap();
+    println!("BLS12-381 Gt window sizes: {:?}", table);
 }
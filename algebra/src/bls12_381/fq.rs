@@ -11,10 +11,8 @@ use ark_std::{
 use digest::{consts::U64, Digest};
 use num_bigint::BigUint;
 use num_traits::Num;
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for `ark_bls12_381::Fq`
-#[wasm_bindgen]
 #[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
 pub struct BLSFq(pub(crate) Fq);
 
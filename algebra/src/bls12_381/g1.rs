@@ -8,6 +8,7 @@ use ark_std::{
     vec::Vec,
 };
 use digest::{consts::U64, Digest};
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
 #[cfg(target_arch = "wasm32")]
@@ -43,7 +44,6 @@ pub async fn init_fast_msm_wasm() -> core::result::Result<(), JsValue> {
 }
 
 /// The wrapped struct for ark_bls12_381::G1Projective
-#[wasm_bindgen]
 #[derive(Copy, Default, Clone, PartialEq, Eq)]
 pub struct BLSG1(pub(crate) G1Projective);
 
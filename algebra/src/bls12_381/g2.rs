@@ -8,10 +8,8 @@ use ark_std::{
     vec::Vec,
 };
 use digest::{consts::U64, Digest};
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for `ark_bls12_381::G2Projective`
-#[wasm_bindgen]
 #[derive(Copy, Default, Clone, PartialEq, Eq)]
 pub struct BLSG2(pub(crate) G2Projective);
 
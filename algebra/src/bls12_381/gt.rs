@@ -2,16 +2,14 @@ use crate::bls12_381::{BLSPairingEngine, BLSScalar, BLSG1, BLSG2};
 use crate::prelude::*;
 use crate::traits::Pairing;
 use ark_bls12_381::{Bls12_381, Fq12Config};
-use ark_ec::pairing::PairingOutput;
-use ark_ff::{BigInteger, Fp12, PrimeField};
+use ark_ec::pairing::{MillerLoopOutput, Pairing as ArkPairing, PairingOutput};
+use ark_ff::{CyclotomicMultSubgroup, Field, Fp12, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 use ark_std::{vec::Vec, UniformRand};
 use digest::{consts::U64, Digest};
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for [`Fp12<ark_bls12_381::Fq12Parameters>`](https://docs.rs/ark-bls12-381/0.3.0/ark_bls12_381/fq12/struct.Fq12Parameters.html),
 /// which is the pairing result
-#[wasm_bindgen]
 #[derive(Copy, Default, Clone, PartialEq, Eq, Debug)]
 pub struct BLSGt(pub(crate) Fp12<Fq12Config>);
 
@@ -49,31 +47,22 @@ impl<'a> Sub<&'a BLSGt> for BLSGt {
 impl<'a> Mul<&'a BLSScalar> for BLSGt {
     type Output = BLSGt;
 
+    #[inline]
     fn mul(self, rhs: &'a BLSScalar) -> Self::Output {
-        let mut acc = Self::get_identity();
-
-        // This is a simple double-and-add implementation of group element
-        // multiplication, moving from most significant to least
-        // significant bit of the scalar.
-        //
-        // We skip the leading bit because it's always unset for Fq
-        // elements.
-        for bit in rhs
-            .0
-            .into_bigint()
-            .to_bytes_le()
-            .iter()
-            .rev()
-            .flat_map(|byte| (0..8).rev().map(move |i| ((byte >> i) & 1u8) == 1u8))
-            .skip(1)
-        {
-            acc = acc.double();
-            if bit {
-                acc = acc.add(&self)
-            }
-        }
+        // The exponentiation uses the cyclotomic squaring and the NAF of the scalar,
+        // as the inverse of an element in the target group is a conjugation.
+        Self(self.0.cyclotomic_exp(rhs.0.into_bigint()))
+    }
+}
 
-        acc
+impl BLSGt {
+    /// Check whether the element is in the cyclotomic subgroup of order `p^4 - p^2 + 1`,
+    /// i.e., `f^(p^4) * f = f^(p^2)`, which contains the target group, and where
+    /// the cyclotomic squaring and the conjugation as the inverse are correct.
+    pub fn is_in_cyclotomic_subgroup(&self) -> bool {
+        let mut lhs = self.0.frobenius_map(4);
+        lhs.mul_assign(&self.0);
+        lhs == self.0.frobenius_map(2)
     }
 }
 
@@ -109,7 +98,7 @@ impl Group for BLSGt {
 
     #[inline]
     fn double(&self) -> Self {
-        Self(self.0.mul(&self.0))
+        Self(self.0.cyclotomic_square())
     }
 
     #[inline]
@@ -146,18 +135,30 @@ impl Group for BLSGt {
 
     #[inline]
     fn from_compressed_bytes(bytes: &[u8]) -> Result<Self> {
-        let res = Fp12::<Fq12Config>::deserialize_with_mode(bytes, Compress::Yes, Validate::Yes)
-            .map_err(|_| AlgebraError::DeserializationError)?;
+        let res = Self(
+            Fp12::<Fq12Config>::deserialize_with_mode(bytes, Compress::Yes, Validate::Yes)
+                .map_err(|_| AlgebraError::DeserializationError)?,
+        );
 
-        Ok(Self(res))
+        if !res.is_in_cyclotomic_subgroup() {
+            return Err(AlgebraError::DeserializationError);
+        }
+        Ok(res)
     }
 
     #[inline]
     fn from_unchecked_bytes(bytes: &[u8]) -> Result<Self> {
-        let res = Fp12::<Fq12Config>::deserialize_with_mode(bytes, Compress::No, Validate::No)
-            .map_err(|_| AlgebraError::DeserializationError)?;
-
-        Ok(Self(res))
+        let res = Self(
+            Fp12::<Fq12Config>::deserialize_with_mode(bytes, Compress::No, Validate::No)
+                .map_err(|_| AlgebraError::DeserializationError)?,
+        );
+
+        // Unlike for the curve points, the membership is checked even here, since the
+        // cyclotomic squaring and the conjugation as the inverse are wrong outside of it.
+        if !res.is_in_cyclotomic_subgroup() {
+            return Err(AlgebraError::DeserializationError);
+        }
+        Ok(res)
     }
 
     #[inline]
@@ -171,7 +172,54 @@ impl Group for BLSGt {
     where
         D: Digest<OutputSize = U64> + Default,
     {
+        // The final exponentiation maps a random element into the target group.
         let mut prng = derive_prng_from_hash::<D>(hash);
-        Self(Fp12::<Fq12Config>::rand(&mut prng))
+        let f = Fp12::<Fq12Config>::rand(&mut prng);
+        Bls12_381::final_exponentiation(MillerLoopOutput(f))
+            .map(|g| Self(g.0))
+            .unwrap_or_else(Self::get_identity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bls12_381::{BLSGt, BLSScalar};
+    use crate::prelude::*;
+    use ark_bls12_381::Fq12Config;
+    use ark_ff::{BigInteger, Fp12, PrimeField};
+
+    #[test]
+    fn test_cyclotomic_arithmetic() {
+        let mut prng = test_rng();
+        let g = BLSGt::random(&mut prng);
+        assert!(g.is_in_cyclotomic_subgroup());
+
+        // the cyclotomic squaring agrees with the multiplication
+        assert_eq!(g.double(), g.add(&g));
+
+        // the exponentiation agrees with the double-and-add
+        let s = BLSScalar::random(&mut prng);
+        let mut expected = BLSGt::get_identity();
+        for bit in s.0.into_bigint().to_bits_be() {
+            expected = expected.add(&expected);
+            if bit {
+                expected = expected.add(&g);
+            }
+        }
+        assert_eq!(g.mul(&s), expected);
+        assert_eq!(g.mul(&s.neg()), expected.neg());
+        assert_eq!(g.mul(&BLSScalar::zero()), BLSGt::get_identity());
+
+        // the elements outside of the cyclotomic subgroup are rejected
+        let bytes = g.to_compressed_bytes();
+        assert_eq!(BLSGt::from_compressed_bytes(&bytes).unwrap(), g);
+        let f = BLSGt(Fp12::<Fq12Config>::rand(&mut prng));
+        assert!(!f.is_in_cyclotomic_subgroup());
+        assert!(BLSGt::from_compressed_bytes(&f.to_compressed_bytes()).is_err());
+        assert_eq!(
+            BLSGt::from_unchecked_bytes(&g.to_unchecked_bytes()).unwrap(),
+            g
+        );
+        assert!(BLSGt::from_unchecked_bytes(&f.to_unchecked_bytes()).is_err());
     }
 }
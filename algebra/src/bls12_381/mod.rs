@@ -116,6 +116,34 @@ mod bls12_381_groups_test {
         assert_eq!(gt_mapped_element, gt_base_computed.mul(&s2).mul(&s1));
     }
 
+    #[test]
+    fn multi_pairing() {
+        let mut prng = test_rng();
+        let a = (0..4).map(|_| BLSG1::random(&mut prng)).collect_vec();
+        let b = (0..4).map(|_| BLSG2::random(&mut prng)).collect_vec();
+
+        let expected = a
+            .iter()
+            .zip(b.iter())
+            .fold(BLSGt::get_identity(), |acc, (a, b)| {
+                acc.add(&BLSPairingEngine::pairing(a, b))
+            });
+        let pairs = a.iter().zip(b.iter()).collect_vec();
+        assert_eq!(BLSPairingEngine::multi_pairing(&pairs), expected);
+        assert_eq!(BLSPairingEngine::product_of_pairings(&a, &b), expected);
+        assert_eq!(BLSPairingEngine::multi_pairing(&[]), BLSGt::get_identity());
+
+        // e(s * P, Q) * e(-P, s * Q) = 1
+        let s = BLSScalar::random(&mut prng);
+        let p = a[0].mul(&s);
+        let q = b[0].mul(&s);
+        let neg_a = a[0].neg();
+        assert_eq!(
+            BLSPairingEngine::multi_pairing(&[(&p, &b[0]), (&neg_a, &q)]),
+            BLSGt::get_identity()
+        );
+    }
+
     #[test]
     fn curve_points_respresentation_of_g1() {
         let mut prng = test_rng();
@@ -25,9 +25,18 @@ impl Pairing for BLSPairingEngine {
     }
 
     #[inline]
-    fn product_of_pairings(a: &[Self::G1], b: &[Self::G2]) -> Self::Gt {
-        let c1: Vec<G1Prepared<_>> = a.iter().map(|x| x.0.into_affine().into()).collect();
-        let c2: Vec<G2Prepared<_>> = b.iter().map(|x| x.0.into_affine().into()).collect();
+    fn multi_pairing(pairs: &[(&Self::G1, &Self::G2)]) -> Self::Gt {
+        let a = pairs.iter().map(|(a, _)| a.0).collect::<Vec<_>>();
+        let b = pairs.iter().map(|(_, b)| b.0).collect::<Vec<_>>();
+        // The points are normalized with a single inversion for each group.
+        let c1: Vec<G1Prepared<_>> = CurveGroup::normalize_batch(&a)
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        let c2: Vec<G2Prepared<_>> = CurveGroup::normalize_batch(&b)
+            .into_iter()
+            .map(Into::into)
+            .collect();
         BLSGt(Bls12381pairing::multi_pairing(c1, c2).0)
     }
 }
@@ -13,10 +13,8 @@ use ark_std::{
 use digest::{consts::U64, Digest};
 use num_bigint::BigUint;
 use num_traits::Num;
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for `ark_bn254::Fr`
-#[wasm_bindgen]
 #[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
 pub struct BN254Scalar(pub(crate) Fr);
 
@@ -44,6 +42,11 @@ impl BN254Scalar {
     pub const fn new(is_positive: bool, limbs: &[u64]) -> Self {
         BN254Scalar(Fr::from_sign_and_limbs(is_positive, limbs))
     }
+
+    /// Return the 32 big-endian bytes used by the EVM precompiles.
+    pub fn to_evm_bytes(&self) -> Vec<u8> {
+        self.0.into_bigint().to_bytes_be()
+    }
 }
 
 impl From<BN254Scalar> for BigUint {
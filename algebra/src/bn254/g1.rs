@@ -1,4 +1,4 @@
-use crate::bn254::{BN254Fq, BN254Scalar};
+use crate::bn254::{fq_from_evm_bytes, fq_to_evm_bytes, BN254Fq, BN254Scalar, BN254_G1_EVM_LEN};
 use crate::prelude::*;
 use ark_bn254::{Fq, G1Affine, G1Projective};
 use ark_ec::{CurveGroup, Group as ArkGroup};
@@ -8,10 +8,8 @@ use ark_std::{
     vec::Vec,
 };
 use digest::{consts::U64, Digest};
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for ark_bn254::G1Projective
-#[wasm_bindgen]
 #[derive(Copy, Default, Clone, PartialEq, Eq)]
 pub struct BN254G1(pub(crate) G1Projective);
 
@@ -176,6 +174,35 @@ impl BN254G1 {
         }
     }
 
+    /// Return the encoding of the EVM precompiles, i.e., the big-endian `x` and `y`
+    /// of the affine point, with the identity encoded as zeros.
+    pub fn to_evm_bytes(&self) -> Vec<u8> {
+        let affine = self.0.into_affine();
+        if affine.infinity {
+            return vec![0u8; BN254_G1_EVM_LEN];
+        }
+        let mut bytes = fq_to_evm_bytes(&affine.x);
+        bytes.extend_from_slice(&fq_to_evm_bytes(&affine.y));
+        bytes
+    }
+
+    /// Construct from the encoding of the EVM precompiles, checking that the point is on the curve.
+    pub fn from_evm_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != BN254_G1_EVM_LEN {
+            return Err(AlgebraError::DeserializationError);
+        }
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(Self::get_identity());
+        }
+        let x = fq_from_evm_bytes(&bytes[..32])?;
+        let y = fq_from_evm_bytes(&bytes[32..])?;
+        let affine = G1Affine::new_unchecked(x, y);
+        if !affine.is_on_curve() || !affine.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(AlgebraError::DeserializationError);
+        }
+        Ok(Self(affine.into()))
+    }
+
     #[inline]
     fn common_multi_exp(scalars: &[&<Self as Group>::ScalarType], points: &[&Self]) -> Self {
         use ark_ec::VariableBaseMSM;
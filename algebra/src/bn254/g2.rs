@@ -1,6 +1,6 @@
-use crate::bn254::BN254Scalar;
+use crate::bn254::{fq_from_evm_bytes, fq_to_evm_bytes, BN254Scalar, BN254_G2_EVM_LEN};
 use crate::prelude::*;
-use ark_bn254::{G2Affine, G2Projective};
+use ark_bn254::{Fq2, G2Affine, G2Projective};
 use ark_ec::{AffineRepr, CurveGroup as ArkCurveGroup, Group as ArkGroup};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 use ark_std::{
@@ -8,10 +8,8 @@ use ark_std::{
     vec::Vec,
 };
 use digest::{consts::U64, Digest};
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for `ark_bn254::G2Projective`
-#[wasm_bindgen]
 #[derive(Copy, Default, Clone, PartialEq, Eq)]
 pub struct BN254G2(pub(crate) G2Projective);
 
@@ -149,3 +147,44 @@ impl<'a> MulAssign<&'a BN254Scalar> for BN254G2 {
         self.0.mul_assign(rhs.0)
     }
 }
+
+impl BN254G2 {
+    /// Return the encoding of the EVM precompiles, i.e., the big-endian `x` and `y`
+    /// of the affine point with the imaginary part of each coordinate first,
+    /// and the identity encoded as zeros.
+    pub fn to_evm_bytes(&self) -> Vec<u8> {
+        let affine = self.0.into_affine();
+        if affine.infinity {
+            return vec![0u8; BN254_G2_EVM_LEN];
+        }
+        let mut bytes = Vec::with_capacity(BN254_G2_EVM_LEN);
+        for fq in [affine.x.c1, affine.x.c0, affine.y.c1, affine.y.c0] {
+            bytes.extend_from_slice(&fq_to_evm_bytes(&fq));
+        }
+        bytes
+    }
+
+    /// Construct from the encoding of the EVM precompiles, checking that the point is on the curve
+    /// and in the prime-order subgroup.
+    pub fn from_evm_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != BN254_G2_EVM_LEN {
+            return Err(AlgebraError::DeserializationError);
+        }
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(Self::get_identity());
+        }
+        let x = Fq2::new(
+            fq_from_evm_bytes(&bytes[32..64])?,
+            fq_from_evm_bytes(&bytes[..32])?,
+        );
+        let y = Fq2::new(
+            fq_from_evm_bytes(&bytes[96..])?,
+            fq_from_evm_bytes(&bytes[64..96])?,
+        );
+        let affine = G2Affine::new_unchecked(x, y);
+        if !affine.is_on_curve() || !affine.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(AlgebraError::DeserializationError);
+        }
+        Ok(Self(affine.into()))
+    }
+}
@@ -2,16 +2,14 @@ use crate::bn254::{BN254PairingEngine, BN254Scalar, BN254G1, BN254G2};
 use crate::prelude::*;
 use crate::traits::Pairing;
 use ark_bn254::{Bn254, Fq12Config};
-use ark_ec::pairing::PairingOutput;
-use ark_ff::{BigInteger, Fp12, PrimeField};
+use ark_ec::pairing::{MillerLoopOutput, Pairing as ArkPairing, PairingOutput};
+use ark_ff::{CyclotomicMultSubgroup, Field, Fp12, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 use ark_std::{vec::Vec, UniformRand};
 use digest::{consts::U64, Digest};
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for [`Fp12<ark_bn254::Fq12Parameters>`](https://docs.rs/ark-bn254/0.3.0/ark_bn254/fq12/struct.Fq12Parameters.html),
 /// which is the pairing result
-#[wasm_bindgen]
 #[derive(Copy, Default, Clone, PartialEq, Eq, Debug)]
 pub struct BN254Gt(pub(crate) Fp12<Fq12Config>);
 
@@ -56,31 +54,20 @@ impl<'a> MulAssign<&'a BN254Scalar> for BN254Gt {
 impl<'a> Mul<&'a BN254Scalar> for BN254Gt {
     type Output = BN254Gt;
 
+    #[inline]
     fn mul(self, rhs: &'a BN254Scalar) -> Self::Output {
-        let mut acc = Self::get_identity();
-
-        // This is a simple double-and-add implementation of group element
-        // multiplication, moving from most significant to least
-        // significant bit of the scalar.
-        //
-        // We skip the leading bit because it's always unset for Fq
-        // elements.
-        for bit in rhs
-            .0
-            .into_bigint()
-            .to_bytes_le()
-            .iter()
-            .rev()
-            .flat_map(|byte| (0..8).rev().map(move |i| ((byte >> i) & 1u8) == 1u8))
-            .skip(1)
-        {
-            acc = acc.double();
-            if bit {
-                acc = acc.add(&self)
-            }
-        }
+        // The exponentiation uses the cyclotomic squaring and the NAF of the scalar,
+        // as the inverse of an element in the target group is a conjugation.
+        Self(self.0.cyclotomic_exp(rhs.0.into_bigint()))
+    }
+}
 
-        acc
+impl BN254Gt {
+    /// Check whether the element is in the cyclotomic subgroup of order `p^4 - p^2 + 1`.
+    pub fn is_in_cyclotomic_subgroup(&self) -> bool {
+        let mut lhs = self.0.frobenius_map(4);
+        lhs.mul_assign(&self.0);
+        lhs == self.0.frobenius_map(2)
     }
 }
 
@@ -109,7 +96,7 @@ impl Group for BN254Gt {
 
     #[inline]
     fn double(&self) -> Self {
-        Self(self.0.mul(&self.0))
+        Self(self.0.cyclotomic_square())
     }
 
     #[inline]
@@ -146,18 +133,30 @@ impl Group for BN254Gt {
 
     #[inline]
     fn from_compressed_bytes(bytes: &[u8]) -> Result<Self> {
-        let res = Fp12::<Fq12Config>::deserialize_with_mode(bytes, Compress::Yes, Validate::Yes)
-            .map_err(|_| AlgebraError::DeserializationError)?;
+        let res = Self(
+            Fp12::<Fq12Config>::deserialize_with_mode(bytes, Compress::Yes, Validate::Yes)
+                .map_err(|_| AlgebraError::DeserializationError)?,
+        );
 
-        Ok(Self(res))
+        if !res.is_in_cyclotomic_subgroup() {
+            return Err(AlgebraError::DeserializationError);
+        }
+        Ok(res)
     }
 
     #[inline]
     fn from_unchecked_bytes(bytes: &[u8]) -> Result<Self> {
-        let res = Fp12::<Fq12Config>::deserialize_with_mode(bytes, Compress::No, Validate::No)
-            .map_err(|_| AlgebraError::DeserializationError)?;
-
-        Ok(Self(res))
+        let res = Self(
+            Fp12::<Fq12Config>::deserialize_with_mode(bytes, Compress::No, Validate::No)
+                .map_err(|_| AlgebraError::DeserializationError)?,
+        );
+
+        // Unlike for the curve points, the membership is checked even here, since the
+        // cyclotomic squaring and the conjugation as the inverse are wrong outside of it.
+        if !res.is_in_cyclotomic_subgroup() {
+            return Err(AlgebraError::DeserializationError);
+        }
+        Ok(res)
     }
 
     #[inline]
@@ -171,7 +170,51 @@ impl Group for BN254Gt {
     where
         D: Digest<OutputSize = U64> + Default,
     {
+        // The final exponentiation maps a random element into the target group.
         let mut prng = derive_prng_from_hash::<D>(hash);
-        Self(Fp12::<Fq12Config>::rand(&mut prng))
+        let f = Fp12::<Fq12Config>::rand(&mut prng);
+        Bn254::final_exponentiation(MillerLoopOutput(f))
+            .map(|g| Self(g.0))
+            .unwrap_or_else(Self::get_identity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bn254::{BN254Gt, BN254Scalar};
+    use crate::prelude::*;
+    use ark_bn254::Fq12Config;
+    use ark_ff::{BigInteger, Fp12, PrimeField};
+
+    #[test]
+    fn test_cyclotomic_arithmetic() {
+        let mut prng = test_rng();
+        let g = BN254Gt::random(&mut prng);
+        assert!(g.is_in_cyclotomic_subgroup());
+
+        // the cyclotomic squaring agrees with the multiplication
+        assert_eq!(g.double(), g.add(&g));
+
+        // the exponentiation agrees with the double-and-add
+        let s = BN254Scalar::random(&mut prng);
+        let mut expected = BN254Gt::get_identity();
+        for bit in s.0.into_bigint().to_bits_be() {
+            expected = expected.add(&expected);
+            if bit {
+                expected = expected.add(&g);
+            }
+        }
+        assert_eq!(g.mul(&s), expected);
+        assert_eq!(g.mul(&s.neg()), expected.neg());
+
+        // the elements outside of the cyclotomic subgroup are rejected in either encoding
+        let f = BN254Gt(Fp12::<Fq12Config>::rand(&mut prng));
+        assert!(!f.is_in_cyclotomic_subgroup());
+        assert!(BN254Gt::from_compressed_bytes(&f.to_compressed_bytes()).is_err());
+        assert!(BN254Gt::from_unchecked_bytes(&f.to_unchecked_bytes()).is_err());
+        assert_eq!(
+            BN254Gt::from_unchecked_bytes(&g.to_unchecked_bytes()).unwrap(),
+            g
+        );
     }
 }
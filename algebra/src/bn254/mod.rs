@@ -1,6 +1,31 @@
+use crate::prelude::*;
+use ark_bn254::Fq;
+use ark_ff::{BigInteger, PrimeField};
+
 /// The number of bytes for a scalar value over BN254.
 pub const BN254_SCALAR_LEN: usize = 32;
 
+/// The number of bytes of a G1 point in the encoding of the EVM precompiles.
+pub const BN254_G1_EVM_LEN: usize = 64;
+
+/// The number of bytes of a G2 point in the encoding of the EVM precompiles.
+pub const BN254_G2_EVM_LEN: usize = 128;
+
+/// Parse a base field element from the 32 big-endian bytes of the EVM precompiles,
+/// rejecting the non-canonical encodings.
+fn fq_from_evm_bytes(bytes: &[u8]) -> Result<Fq> {
+    let fq = Fq::from_be_bytes_mod_order(bytes);
+    if fq.into_bigint().to_bytes_be() != bytes {
+        return Err(AlgebraError::DeserializationError);
+    }
+    Ok(fq)
+}
+
+/// Return the 32 big-endian bytes of a base field element for the EVM precompiles.
+fn fq_to_evm_bytes(fq: &Fq) -> Vec<u8> {
+    fq.into_bigint().to_bytes_be()
+}
+
 mod fr;
 pub use fr::*;
 
@@ -32,7 +57,10 @@ macro_rules! new_bn254_fr {
 mod bn254_groups_test {
     use crate::traits::Group;
     use crate::{
-        bn254::{BN254Fq, BN254Gt, BN254PairingEngine, BN254Scalar, BN254G1, BN254G2},
+        bn254::{
+            BN254Fq, BN254Gt, BN254PairingEngine, BN254Scalar, BN254G1, BN254G2, BN254_G1_EVM_LEN,
+            BN254_G2_EVM_LEN, BN254_SCALAR_LEN,
+        },
         prelude::*,
         traits::{
             group_tests::{test_scalar_operations, test_scalar_serialization},
@@ -42,6 +70,50 @@ mod bn254_groups_test {
     use ark_bn254::{G1Affine, G2Affine};
     use ark_ec::CurveGroup;
 
+    #[test]
+    fn evm_encoding() {
+        let mut prng = test_rng();
+
+        // the generator of G1 is (1, 2) in the EVM precompiles
+        let mut expected = [0u8; BN254_G1_EVM_LEN];
+        expected[31] = 1;
+        expected[63] = 2;
+        assert_eq!(BN254G1::get_base().to_evm_bytes(), expected);
+        assert_eq!(
+            BN254G1::get_identity().to_evm_bytes(),
+            [0u8; BN254_G1_EVM_LEN]
+        );
+
+        for _ in 0..10 {
+            let p = BN254G1::random(&mut prng);
+            assert_eq!(BN254G1::from_evm_bytes(&p.to_evm_bytes()).unwrap(), p);
+            let q = BN254G2::random(&mut prng);
+            assert_eq!(BN254G2::from_evm_bytes(&q.to_evm_bytes()).unwrap(), q);
+        }
+        assert_eq!(
+            BN254G1::from_evm_bytes(&[0u8; BN254_G1_EVM_LEN]).unwrap(),
+            BN254G1::get_identity()
+        );
+        assert_eq!(
+            BN254G2::from_evm_bytes(&[0u8; BN254_G2_EVM_LEN]).unwrap(),
+            BN254G2::get_identity()
+        );
+
+        // not on the curve, not canonical, or of a wrong length
+        let mut bad = BN254G1::get_base().to_evm_bytes();
+        bad[63] = 3;
+        assert!(BN254G1::from_evm_bytes(&bad).is_err());
+        let bad = [0xffu8; BN254_G1_EVM_LEN];
+        assert!(BN254G1::from_evm_bytes(&bad).is_err());
+        assert!(BN254G1::from_evm_bytes(&[0u8; 63]).is_err());
+
+        let s = BN254Scalar::from(258u32);
+        let mut expected = [0u8; BN254_SCALAR_LEN];
+        expected[30] = 1;
+        expected[31] = 2;
+        assert_eq!(s.to_evm_bytes(), expected);
+    }
+
     #[test]
     fn test_scalar_ops() {
         test_scalar_operations::<BN254Scalar>();
@@ -13,10 +13,8 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate
 use ark_std::{string::ToString, vec::Vec};
 use digest::consts::U64;
 use digest::Digest;
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for `ark_ed25519::EdwardsProjective`
-#[wasm_bindgen]
 #[derive(Clone, PartialEq, Debug, Copy, Default)]
 pub struct Ed25519Point(pub(crate) EdwardsProjective);
 
@@ -0,0 +1,167 @@
+use crate::prelude::*;
+use crate::traits::scalar_to_radix_2_power_w;
+#[cfg(feature = "std")]
+use std::{
+    any::{Any, TypeId},
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+/// The default window size of the fixed-base tables.
+pub const DEFAULT_FIXED_BASE_WINDOW: usize = 6;
+
+/// A precomputed table for the scalar multiplication of a fixed base, e.g., a generator of
+/// Pedersen commitments, which replaces all the doublings by lookups.
+///
+/// The scalar is split in signed digits of `w` bits, and the row `i` of the table holds
+/// `j * 2^(w * i) * base` for `j` in `[1, 2^(w - 1)]`, so that a multiplication is one
+/// addition or subtraction per digit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedBaseTable<G: Group> {
+    base: G,
+    window: usize,
+    table: Vec<Vec<G>>,
+}
+
+impl<G: Group> FixedBaseTable<G> {
+    /// Precompute the table of the base with the default window size.
+    pub fn new(base: &G) -> Self {
+        Self::build(base, DEFAULT_FIXED_BASE_WINDOW)
+    }
+
+    /// Precompute the table of the base with the window size, which must be within `[2, 7]`.
+    pub fn with_window(base: &G, window: usize) -> Result<Self> {
+        if !(2..=7).contains(&window) {
+            return Err(AlgebraError::ParameterError);
+        }
+        Ok(Self::build(base, window))
+    }
+
+    fn build(base: &G, window: usize) -> Self {
+        // The digits cover the little-endian u64 limbs of the scalar, plus the final carry.
+        let bits = (G::ScalarType::bytes_len() + 7) / 8 * 64;
+        let num_windows = (bits + window - 1) / window + 1;
+
+        let mut table = Vec::with_capacity(num_windows);
+        let mut row_base = *base;
+        for _ in 0..num_windows {
+            let mut row = Vec::with_capacity(1 << (window - 1));
+            let mut acc = row_base;
+            row.push(acc);
+            for _ in 1..(1 << (window - 1)) {
+                acc = acc.add(&row_base);
+                row.push(acc);
+            }
+            table.push(row);
+            for _ in 0..window {
+                row_base = row_base.double();
+            }
+        }
+
+        Self {
+            base: *base,
+            window,
+            table,
+        }
+    }
+
+    /// Return the base of the table.
+    pub fn base(&self) -> &G {
+        &self.base
+    }
+
+    /// Return the window size of the table.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Compute `scalar * base` with the table.
+    pub fn mul(&self, scalar: &G::ScalarType) -> G {
+        let digits = scalar_to_radix_2_power_w(scalar, self.window);
+        if digits.len() > self.table.len() {
+            return self.base.mul(scalar);
+        }
+
+        let mut acc = G::get_identity();
+        for (row, digit) in self.table.iter().zip(digits.iter()) {
+            if *digit > 0 {
+                acc.add_assign(&row[(*digit - 1) as usize]);
+            } else if *digit < 0 {
+                acc.sub_assign(&row[(-(*digit + 1)) as usize]);
+            }
+        }
+        acc
+    }
+}
+
+#[cfg(feature = "std")]
+/// The tables of the bases of the groups, built on the first use.
+static BASE_TABLES: RwLock<BTreeMap<TypeId, Box<dyn Any + Send + Sync>>> =
+    RwLock::new(BTreeMap::new());
+
+/// Compute `scalar * G::get_base()`, with a table of the base of the group built on the first use.
+pub fn mul_base<G: Group>(scalar: &G::ScalarType) -> G {
+    #[cfg(feature = "std")]
+    if let Some(table) = base_table::<G>() {
+        return table.mul(scalar);
+    }
+    G::get_base().mul(scalar)
+}
+
+#[cfg(feature = "std")]
+fn base_table<G: Group>() -> Option<Arc<FixedBaseTable<G>>> {
+    let cached = BASE_TABLES
+        .read()
+        .ok()?
+        .get(&TypeId::of::<G>())
+        .and_then(|table| table.downcast_ref::<Arc<FixedBaseTable<G>>>().cloned());
+    if cached.is_some() {
+        return cached;
+    }
+
+    let table = Arc::new(FixedBaseTable::new(&G::get_base()));
+    BASE_TABLES
+        .write()
+        .ok()?
+        .entry(TypeId::of::<G>())
+        .or_insert_with(|| Box::new(table.clone()));
+    Some(table)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls12_381::BLSG1;
+    use crate::fixed_base::{mul_base, FixedBaseTable};
+    use crate::prelude::*;
+    use crate::ristretto::RistrettoPoint;
+
+    fn check_fixed_base_table<G: Group>() {
+        let mut prng = test_rng();
+        let base = G::random(&mut prng);
+
+        for window in 2..=7 {
+            let table = FixedBaseTable::with_window(&base, window).unwrap();
+            assert_eq!(table.window(), window);
+            for _ in 0..8 {
+                let s = G::ScalarType::random(&mut prng);
+                assert_eq!(table.mul(&s), base.mul(&s));
+            }
+            assert_eq!(table.mul(&G::ScalarType::zero()), G::get_identity());
+            assert_eq!(table.mul(&G::ScalarType::one()), base);
+            let minus_one = G::ScalarType::one().neg();
+            assert_eq!(table.mul(&minus_one), base.neg());
+        }
+        assert!(FixedBaseTable::with_window(&base, 1).is_err());
+        assert!(FixedBaseTable::with_window(&base, 8).is_err());
+
+        let s = G::ScalarType::random(&mut prng);
+        assert_eq!(mul_base::<G>(&s), G::get_base().mul(&s));
+        assert_eq!(mul_base::<G>(&s), G::get_base().mul(&s));
+    }
+
+    #[test]
+    fn test_fixed_base_table() {
+        check_fixed_base_table::<RistrettoPoint>();
+        check_fixed_base_table::<BLSG1>();
+    }
+}
@@ -0,0 +1,340 @@
+use crate::goldilocks::GOLDILOCKS_SCALAR_LEN;
+use crate::prelude::*;
+use crate::traits::Domain;
+use ark_ff::{Field, Fp64, LegendreSymbol, MontBackend, MontConfig, PrimeField};
+use ark_std::{vec, vec::Vec};
+use digest::consts::U64;
+use digest::Digest;
+use num_bigint::BigUint;
+
+/// The Montgomery configuration of the Goldilocks field, of modulus `2^64 - 2^32 + 1`,
+/// which is only used for FFTs and square roots.
+#[derive(MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct GoldilocksConfig;
+
+/// The Goldilocks field in Montgomery form.
+pub type GoldilocksFr = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+/// The modulus `p = 2^64 - 2^32 + 1`.
+pub const GOLDILOCKS_MODULUS: u64 = 0xFFFF_FFFF_0000_0001;
+
+/// `2^64 - p = 2^32 - 1`, which is also `2^64` modulo `p`.
+const EPSILON: u64 = 0xFFFF_FFFF;
+
+/// The Goldilocks field, stored as the canonical u64 and reduced with the special form of `p`.
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Debug, Hash)]
+pub struct GoldilocksScalar(pub(crate) u64);
+
+impl GoldilocksScalar {
+    /// Return the canonical u64 of the field element.
+    #[inline]
+    pub fn to_canonical_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Reduce a u64 that is not necessarily canonical.
+    #[inline]
+    fn reduce64(x: u64) -> Self {
+        if x >= GOLDILOCKS_MODULUS {
+            Self(x - GOLDILOCKS_MODULUS)
+        } else {
+            Self(x)
+        }
+    }
+
+    /// Reduce a u128, using `2^64 = 2^32 - 1` and `2^96 = -1` modulo `p`.
+    #[inline]
+    fn reduce128(x: u128) -> Self {
+        let x_lo = x as u64;
+        let x_hi = (x >> 64) as u64;
+        let x_hi_hi = x_hi >> 32;
+        let x_hi_lo = x_hi & EPSILON;
+
+        let (mut t0, borrow) = x_lo.overflowing_sub(x_hi_hi);
+        if borrow {
+            // the wrap-around added 2^64, which is 2^32 - 1 too many
+            t0 -= EPSILON;
+        }
+        let t1 = x_hi_lo * EPSILON;
+        let (mut t2, carry) = t0.overflowing_add(t1);
+        if carry {
+            // the wrap-around dropped 2^64, which is 2^32 - 1
+            t2 = t2.wrapping_add(EPSILON);
+        }
+        Self::reduce64(t2)
+    }
+
+    #[inline]
+    fn to_ark(self) -> GoldilocksFr {
+        GoldilocksFr::from(self.0)
+    }
+
+    #[inline]
+    fn from_ark(field: GoldilocksFr) -> Self {
+        Self(field.into_bigint().0[0])
+    }
+}
+
+impl One for GoldilocksScalar {
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+}
+
+impl Zero for GoldilocksScalar {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Add for GoldilocksScalar {
+    type Output = GoldilocksScalar;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        let (sum, carry) = self.0.overflowing_add(rhs.0);
+        if carry {
+            // both are below p, so adding 2^64 - p back cannot overflow again
+            Self::reduce64(sum + EPSILON)
+        } else {
+            Self::reduce64(sum)
+        }
+    }
+}
+
+impl Mul for GoldilocksScalar {
+    type Output = GoldilocksScalar;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::reduce128((self.0 as u128) * (rhs.0 as u128))
+    }
+}
+
+impl Sum<GoldilocksScalar> for GoldilocksScalar {
+    #[inline]
+    fn sum<I: Iterator<Item = GoldilocksScalar>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<'a> Add<&'a GoldilocksScalar> for GoldilocksScalar {
+    type Output = GoldilocksScalar;
+
+    #[inline]
+    fn add(self, rhs: &Self) -> Self::Output {
+        self + *rhs
+    }
+}
+
+impl<'a> AddAssign<&'a GoldilocksScalar> for GoldilocksScalar {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        *self = *self + *rhs;
+    }
+}
+
+impl<'a> Mul<&'a GoldilocksScalar> for GoldilocksScalar {
+    type Output = GoldilocksScalar;
+
+    #[inline]
+    fn mul(self, rhs: &Self) -> Self::Output {
+        self * *rhs
+    }
+}
+
+impl<'a> MulAssign<&'a GoldilocksScalar> for GoldilocksScalar {
+    #[inline]
+    fn mul_assign(&mut self, rhs: &Self) {
+        *self = *self * *rhs;
+    }
+}
+
+impl<'a> Sub<&'a GoldilocksScalar> for GoldilocksScalar {
+    type Output = GoldilocksScalar;
+
+    #[inline]
+    fn sub(self, rhs: &Self) -> Self::Output {
+        let (diff, borrow) = self.0.overflowing_sub(rhs.0);
+        if borrow {
+            // the wrap-around added 2^64, which is 2^32 - 1 more than p
+            Self(diff - EPSILON)
+        } else {
+            Self(diff)
+        }
+    }
+}
+
+impl<'a> SubAssign<&'a GoldilocksScalar> for GoldilocksScalar {
+    #[inline]
+    fn sub_assign(&mut self, rhs: &Self) {
+        *self = self.sub(rhs);
+    }
+}
+
+impl<'a> Sum<&'a GoldilocksScalar> for GoldilocksScalar {
+    #[inline]
+    fn sum<I: Iterator<Item = &'a GoldilocksScalar>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl Neg for GoldilocksScalar {
+    type Output = GoldilocksScalar;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        if self.0 == 0 {
+            self
+        } else {
+            Self(GOLDILOCKS_MODULUS - self.0)
+        }
+    }
+}
+
+impl From<u32> for GoldilocksScalar {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Self(value as u64)
+    }
+}
+
+impl From<u64> for GoldilocksScalar {
+    #[inline]
+    fn from(value: u64) -> Self {
+        Self::reduce64(value)
+    }
+}
+
+impl From<GoldilocksScalar> for BigUint {
+    #[inline]
+    fn from(val: GoldilocksScalar) -> Self {
+        BigUint::from(val.0)
+    }
+}
+
+impl<'a> From<&'a BigUint> for GoldilocksScalar {
+    #[inline]
+    fn from(src: &BigUint) -> Self {
+        let reduced = src % BigUint::from(GOLDILOCKS_MODULUS);
+        Self(reduced.iter_u64_digits().next().unwrap_or(0))
+    }
+}
+
+impl Scalar for GoldilocksScalar {
+    #[inline]
+    fn random<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+        loop {
+            let x = rng.next_u64();
+            if x < GOLDILOCKS_MODULUS {
+                return Self(x);
+            }
+        }
+    }
+
+    #[inline]
+    fn from_hash<D>(hash: D) -> Self
+    where
+        D: Digest<OutputSize = U64> + Default,
+    {
+        let mut prng = derive_prng_from_hash::<D>(hash);
+        Self::random(&mut prng)
+    }
+
+    #[inline]
+    fn multiplicative_generator() -> Self {
+        Self(7)
+    }
+
+    #[inline]
+    fn capacity() -> usize {
+        63
+    }
+
+    #[inline]
+    fn get_field_size_le_bytes() -> Vec<u8> {
+        GOLDILOCKS_MODULUS.to_le_bytes().to_vec()
+    }
+
+    #[inline]
+    fn get_field_size_biguint() -> BigUint {
+        BigUint::from(GOLDILOCKS_MODULUS)
+    }
+
+    #[inline]
+    fn get_little_endian_u64(&self) -> Vec<u64> {
+        vec![self.0]
+    }
+
+    #[inline]
+    fn bytes_len() -> usize {
+        GOLDILOCKS_SCALAR_LEN
+    }
+
+    #[inline]
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_le_bytes().to_vec()
+    }
+
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() > Self::bytes_len() {
+            return Err(AlgebraError::DeserializationError);
+        }
+        let mut array = [0u8; GOLDILOCKS_SCALAR_LEN];
+        array[0..bytes.len()].copy_from_slice(bytes);
+
+        Ok(Self::reduce64(u64::from_le_bytes(array)))
+    }
+
+    #[inline]
+    fn inv(&self) -> Result<Self> {
+        if self.is_zero() {
+            return Err(AlgebraError::GroupInversionError);
+        }
+        Ok(self.pow(&[GOLDILOCKS_MODULUS - 2]))
+    }
+
+    #[inline]
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    #[inline]
+    fn legendre(&self) -> LegendreSymbol {
+        self.to_ark().legendre()
+    }
+
+    #[inline]
+    fn sqrt(&self) -> Option<Self> {
+        self.to_ark().sqrt().map(Self::from_ark)
+    }
+
+    #[inline]
+    fn double(&self) -> Self {
+        *self + *self
+    }
+}
+
+impl Domain for GoldilocksScalar {
+    type Field = GoldilocksFr;
+
+    #[inline]
+    fn get_field(&self) -> Self::Field {
+        self.to_ark()
+    }
+
+    #[inline]
+    fn from_field(field: Self::Field) -> Self {
+        Self::from_ark(field)
+    }
+}
@@ -0,0 +1,85 @@
+/// The number of bytes for a scalar value over the Goldilocks field
+pub const GOLDILOCKS_SCALAR_LEN: usize = 8;
+
+mod fr;
+pub use fr::*;
+
+#[cfg(test)]
+mod goldilocks_test {
+    use crate::{
+        goldilocks::{GoldilocksFr, GoldilocksScalar, GOLDILOCKS_MODULUS},
+        prelude::*,
+        traits::{
+            group_tests::{test_scalar_operations, test_scalar_serialization},
+            Domain,
+        },
+    };
+
+    #[test]
+    fn test_scalar_ops() {
+        test_scalar_operations::<GoldilocksScalar>();
+    }
+
+    #[test]
+    fn scalar_deser() {
+        test_scalar_serialization::<GoldilocksScalar>();
+    }
+
+    #[test]
+    fn scalar_from_to_bytes() {
+        let small_value = GoldilocksScalar::from(165747u32);
+        let small_value_bytes = small_value.to_bytes();
+        let expected_small_value_bytes: [u8; 8] = [115, 135, 2, 0, 0, 0, 0, 0];
+        assert_eq!(small_value_bytes, expected_small_value_bytes);
+
+        let small_value_from_bytes = GoldilocksScalar::from_bytes(&small_value_bytes).unwrap();
+        assert_eq!(small_value_from_bytes, small_value);
+    }
+
+    #[test]
+    fn test_reduction() {
+        // 2^64 = 2^32 - 1 modulo p
+        let two_to_32 = GoldilocksScalar::from(1u64 << 32);
+        assert_eq!(two_to_32.square(), two_to_32.sub(&GoldilocksScalar::one()));
+        assert_eq!(
+            GoldilocksScalar::from(u64::MAX),
+            GoldilocksScalar::from(0xFFFFFFFEu64)
+        );
+
+        // the generator is a quadratic non-residue
+        assert!(GoldilocksScalar::multiplicative_generator()
+            .legendre()
+            .is_qnr());
+    }
+
+    #[test]
+    fn test_against_montgomery_form() {
+        let mut prng = test_rng();
+        let edge_cases = [
+            GoldilocksScalar::zero(),
+            GoldilocksScalar::one(),
+            GoldilocksScalar::from(GOLDILOCKS_MODULUS - 1),
+            GoldilocksScalar::from(0xFFFF_FFFFu64),
+            GoldilocksScalar::from(1u64 << 32),
+        ];
+        let random_cases: Vec<GoldilocksScalar> = (0..100)
+            .map(|_| GoldilocksScalar::random(&mut prng))
+            .collect();
+
+        for a in edge_cases.iter().chain(random_cases.iter()) {
+            for b in edge_cases.iter().chain(random_cases.iter().take(10)) {
+                let (fa, fb): (GoldilocksFr, GoldilocksFr) = (a.get_field(), b.get_field());
+                assert_eq!(a.add(b), GoldilocksScalar::from_field(fa + fb));
+                assert_eq!(a.sub(b), GoldilocksScalar::from_field(fa - fb));
+                assert_eq!(a.mul(b), GoldilocksScalar::from_field(fa * fb));
+            }
+            assert_eq!(a.neg(), GoldilocksScalar::from_field(-a.get_field()));
+            if !a.is_zero() {
+                assert_eq!(a.mul(&a.inv().unwrap()), GoldilocksScalar::one());
+            }
+            if let Some(root) = a.square().sqrt() {
+                assert_eq!(root.square(), a.square());
+            }
+        }
+    }
+}
@@ -6,10 +6,8 @@ use ark_std::{vec, vec::Vec};
 use digest::{generic_array::typenum::U64, Digest};
 use num_bigint::BigUint;
 use num_traits::Num;
-use wasm_bindgen::prelude::wasm_bindgen;
 
 /// The wrapped struct for `ark_ed_on_bls12_381::Fr`
-#[wasm_bindgen]
 #[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Debug, Hash)]
 pub struct JubjubScalar(pub(crate) Fr);
 
@@ -51,18 +51,31 @@ pub mod ed25519;
 /// Module for the Ristretto group.
 pub mod ristretto;
 
+/// Module for the Goldilocks field, of modulus `2^64 - 2^32 + 1`.
+pub mod goldilocks;
+
 /// Module for error handling.
 pub mod errors;
 
 /// Module for traits.
 pub mod traits;
 
+/// Module for the backends of multi-scalar multiplication.
+pub mod msm;
+
+/// Module for the fixed-base scalar multiplication.
+pub mod fixed_base;
+
 /// Module for serialization of scalars and group elements.
 pub mod serialization;
 
 /// Module for utils.
 pub mod utils;
 
+/// Module for the WASM bindings, which wrap the algebra types for JavaScript.
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+
 /// Module for prelude.
 #[doc(hidden)]
 pub mod prelude;
@@ -0,0 +1,181 @@
+use crate::prelude::*;
+#[cfg(feature = "std")]
+use std::{
+    any::{Any, TypeId},
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+/// The trait for the backends of multi-scalar multiplication, which allows services to move
+/// the multi-scalar multiplications of the prover to the hardware without forking the prover.
+pub trait MsmBackend<G: Group>: Send + Sync {
+    /// The name of the backend.
+    fn name(&self) -> &'static str;
+
+    /// Compute the multi-scalar multiplication.
+    fn multi_exp(&self, scalars: &[&G::ScalarType], points: &[&G]) -> Result<G>;
+}
+
+/// The default backend, which runs the multi-scalar multiplication of the group on the CPU,
+/// i.e., the Pippenger algorithm or the dedicated implementation of the curve.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CpuMsmBackend;
+
+impl<G: Group> MsmBackend<G> for CpuMsmBackend {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn multi_exp(&self, scalars: &[&G::ScalarType], points: &[&G]) -> Result<G> {
+        if scalars.len() != points.len() {
+            return Err(AlgebraError::ParameterError);
+        }
+        Ok(G::multi_exp(scalars, points))
+    }
+}
+
+#[cfg(feature = "std")]
+/// The backends used in place of the default backend, by group.
+static MSM_BACKENDS: RwLock<BTreeMap<TypeId, Box<dyn Any + Send + Sync>>> =
+    RwLock::new(BTreeMap::new());
+
+/// Use the backend for the multi-scalar multiplications of the group in `msm`.
+#[cfg(feature = "std")]
+pub fn set_msm_backend<G: Group>(backend: Arc<dyn MsmBackend<G>>) -> Result<()> {
+    MSM_BACKENDS
+        .write()
+        .map_err(|_| AlgebraError::ParameterError)?
+        .insert(TypeId::of::<G>(), Box::new(backend));
+    Ok(())
+}
+
+/// Restore the default backend for the multi-scalar multiplications of the group.
+#[cfg(feature = "std")]
+pub fn clear_msm_backend<G: Group>() {
+    if let Ok(mut backends) = MSM_BACKENDS.write() {
+        backends.remove(&TypeId::of::<G>());
+    }
+}
+
+/// Return the name of the backend used for the multi-scalar multiplications of the group.
+pub fn msm_backend_name<G: Group>() -> &'static str {
+    #[cfg(feature = "std")]
+    if let Some(backend) = get_msm_backend::<G>() {
+        return backend.name();
+    }
+    MsmBackend::<G>::name(&CpuMsmBackend)
+}
+
+#[cfg(feature = "std")]
+fn get_msm_backend<G: Group>() -> Option<Arc<dyn MsmBackend<G>>> {
+    MSM_BACKENDS
+        .read()
+        .ok()?
+        .get(&TypeId::of::<G>())?
+        .downcast_ref::<Arc<dyn MsmBackend<G>>>()
+        .cloned()
+}
+
+/// Compute the multi-scalar multiplication with the backend of the group.
+pub fn msm<G: Group>(scalars: &[&G::ScalarType], points: &[&G]) -> Result<G> {
+    #[cfg(feature = "std")]
+    if let Some(backend) = get_msm_backend::<G>() {
+        return backend.multi_exp(scalars, points);
+    }
+    CpuMsmBackend.multi_exp(scalars, points)
+}
+
+/// The kernel of an external backend, which takes the scalars in little-endian bytes and the
+/// points in uncompressed bytes, and returns the result in uncompressed bytes, or `None` on failure.
+#[cfg(feature = "external-msm")]
+pub type ExternalMsmKernel = fn(scalars: &[Vec<u8>], points: &[Vec<u8>]) -> Option<Vec<u8>>;
+
+/// A backend that runs the multi-scalar multiplications through a kernel supplied by the caller,
+/// e.g., the bindings of a CUDA or Metal library, and falls back to the CPU for the small
+/// instances and whenever the kernel fails. The crate itself does not ship any kernel.
+#[cfg(feature = "external-msm")]
+#[derive(Clone, Copy, Debug)]
+pub struct ExternalMsmBackend {
+    kernel: ExternalMsmKernel,
+    min_size: usize,
+}
+
+#[cfg(feature = "external-msm")]
+impl ExternalMsmBackend {
+    /// Create a backend with the kernel, which is used for at least `min_size` points.
+    pub fn new(kernel: ExternalMsmKernel, min_size: usize) -> Self {
+        Self { kernel, min_size }
+    }
+}
+
+#[cfg(feature = "external-msm")]
+impl<G: Group> MsmBackend<G> for ExternalMsmBackend {
+    fn name(&self) -> &'static str {
+        "external"
+    }
+
+    fn multi_exp(&self, scalars: &[&G::ScalarType], points: &[&G]) -> Result<G> {
+        if scalars.len() != points.len() {
+            return Err(AlgebraError::ParameterError);
+        }
+        if scalars.len() >= self.min_size {
+            let scalars_bytes = scalars.iter().map(|s| s.to_bytes()).collect_vec();
+            let points_bytes = points.iter().map(|p| p.to_unchecked_bytes()).collect_vec();
+            if let Some(res) = (self.kernel)(&scalars_bytes, &points_bytes) {
+                return G::from_unchecked_bytes(&res);
+            }
+        }
+        CpuMsmBackend.multi_exp(scalars, points)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use crate::bls12_381::{BLSScalar, BLSG1};
+    use crate::msm::{clear_msm_backend, msm, msm_backend_name, set_msm_backend, MsmBackend};
+    use crate::prelude::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// A backend that counts its calls.
+    #[derive(Default)]
+    struct CountingBackend(AtomicUsize);
+
+    impl MsmBackend<BLSG1> for CountingBackend {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn multi_exp(&self, scalars: &[&BLSScalar], points: &[&BLSG1]) -> Result<BLSG1> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(BLSG1::multi_exp(scalars, points))
+        }
+    }
+
+    #[test]
+    fn test_msm_backend() {
+        let mut prng = test_rng();
+        let scalars = (0..16).map(|_| BLSScalar::random(&mut prng)).collect_vec();
+        let points = (0..16).map(|_| BLSG1::random(&mut prng)).collect_vec();
+        let scalars = scalars.iter().collect_vec();
+        let points = points.iter().collect_vec();
+        let expected = BLSG1::multi_exp(&scalars, &points);
+
+        assert_eq!(msm_backend_name::<BLSG1>(), "cpu");
+        assert_eq!(msm(&scalars, &points).unwrap(), expected);
+        assert!(msm(&scalars[1..], &points).is_err());
+
+        let backend = Arc::new(CountingBackend::default());
+        set_msm_backend::<BLSG1>(backend.clone()).unwrap();
+        assert_eq!(msm_backend_name::<BLSG1>(), "counting");
+        assert_eq!(msm(&scalars, &points).unwrap(), expected);
+        assert_eq!(backend.0.load(Ordering::SeqCst), 1);
+
+        clear_msm_backend::<BLSG1>();
+        assert_eq!(msm_backend_name::<BLSG1>(), "cpu");
+        assert_eq!(msm(&scalars, &points).unwrap(), expected);
+        assert_eq!(backend.0.load(Ordering::SeqCst), 1);
+    }
+}
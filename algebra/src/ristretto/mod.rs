@@ -1,3 +1,5 @@
+#[cfg(feature = "std")]
+use crate::fixed_base::FixedBaseTable;
 use crate::fmt::{Debug, Formatter};
 use crate::prelude::*;
 use crate::traits::PedersenCommitment;
@@ -524,6 +526,10 @@ impl PedersenCommitment<RistrettoPoint> for PedersenCommitmentRistretto {
     }
 
     fn commit(&self, value: RistrettoScalar, blinding: RistrettoScalar) -> RistrettoPoint {
+        #[cfg(feature = "std")]
+        if let Some((table, blinding_table)) = default_pedersen_tables(self) {
+            return table.mul(&value).add(&blinding_table.mul(&blinding));
+        }
         RistrettoPoint(
             curve25519_dalek::ristretto::RistrettoPoint::multiscalar_mul(
                 &[value.0, blinding.0],
@@ -533,6 +539,33 @@ impl PedersenCommitment<RistrettoPoint> for PedersenCommitmentRistretto {
     }
 }
 
+/// Return the fixed-base tables of the default generators, if the commitment uses them.
+#[cfg(feature = "std")]
+fn default_pedersen_tables(
+    pc_gens: &PedersenCommitmentRistretto,
+) -> Option<&'static (
+    FixedBaseTable<RistrettoPoint>,
+    FixedBaseTable<RistrettoPoint>,
+)> {
+    static TABLES: std::sync::OnceLock<(
+        FixedBaseTable<RistrettoPoint>,
+        FixedBaseTable<RistrettoPoint>,
+    )> = std::sync::OnceLock::new();
+
+    let tables = TABLES.get_or_init(|| {
+        let default_gens = PedersenCommitmentRistretto::default();
+        (
+            FixedBaseTable::new(&default_gens.B),
+            FixedBaseTable::new(&default_gens.B_blinding),
+        )
+    });
+    if tables.0.base() == &pc_gens.B && tables.1.base() == &pc_gens.B_blinding {
+        Some(tables)
+    } else {
+        None
+    }
+}
+
 impl From<&PedersenCommitmentRistretto> for bulletproofs::PedersenGens {
     fn from(rp: &PedersenCommitmentRistretto) -> Self {
         bulletproofs::PedersenGens {
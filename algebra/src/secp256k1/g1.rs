@@ -10,10 +10,8 @@ use ark_std::{
 };
 use digest::consts::U64;
 use digest::Digest;
-use wasm_bindgen::prelude::wasm_bindgen;
 
 /// The wrapped struct for `ark_secp256k1::Projective`
-#[wasm_bindgen]
 #[derive(Copy, Default, Clone, PartialEq, Eq, Hash)]
 pub struct SECP256K1G1(pub(crate) Projective);
 
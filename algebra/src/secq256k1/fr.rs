@@ -14,10 +14,8 @@ use digest::consts::U64;
 use digest::Digest;
 use num_bigint::BigUint;
 use num_traits::Num;
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for `ark_secq256k1::Fr`
-#[wasm_bindgen]
 #[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
 pub struct SECQ256K1Scalar(pub(crate) Fr);
 
@@ -9,10 +9,8 @@ use ark_std::{
 };
 use digest::consts::U64;
 use digest::Digest;
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for `ark_secq256k1::Projective`
-#[wasm_bindgen]
 #[derive(Copy, Default, Clone, PartialEq, Eq)]
 pub struct SECQ256K1G1(pub(crate) Projective);
 
@@ -3,6 +3,7 @@ use crate::{
     bls12_381::{BLSFq, BLSGt, BLSScalar, BLSG1, BLSG2},
     bn254::{BN254Fq, BN254Gt, BN254Scalar, BN254G1, BN254G2},
     ed25519::{Ed25519Point, Ed25519Scalar},
+    goldilocks::GoldilocksScalar,
     jubjub::{JubjubPoint, JubjubScalar},
     prelude::*,
     ristretto::{CompressedEdwardsY, CompressedRistretto, RistrettoPoint, RistrettoScalar},
@@ -43,6 +44,7 @@ to_from_bytes_scalar!(ZorroFq);
 to_from_bytes_scalar!(RistrettoScalar);
 to_from_bytes_scalar!(Ed25519Scalar);
 to_from_bytes_scalar!(BabyJubjubScalar);
+to_from_bytes_scalar!(GoldilocksScalar);
 
 impl NoahFromToBytes for CompressedRistretto {
     #[inline]
@@ -84,6 +86,7 @@ serialize_deserialize!(Ed25519Scalar);
 serialize_deserialize!(CompressedRistretto);
 serialize_deserialize!(CompressedEdwardsY);
 serialize_deserialize!(BabyJubjubScalar);
+serialize_deserialize!(GoldilocksScalar);
 
 macro_rules! to_from_bytes_group {
     ($g:ident) => {
@@ -1,10 +1,15 @@
+use crate::cfg_into_iter;
 use crate::prelude::*;
 use ark_ff::FftField;
 pub use ark_ff::LegendreSymbol;
 use ark_std::{fmt::Debug, vec, vec::Vec};
 use digest::{generic_array::typenum::U64, Digest};
 use num_bigint::BigUint;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::{any::TypeId, collections::BTreeMap, sync::RwLock, time::Instant};
 
 /// The trait for scalars
 pub trait Scalar:
@@ -82,6 +87,29 @@ pub trait Scalar:
     /// Return the modular inverse of the scalar if it exists
     fn inv(&self) -> Result<Self>;
 
+    /// Invert the scalars in place with Montgomery's trick, i.e., with one inversion and
+    /// `3 * (n - 1)` multiplications, and leave them unchanged if any of them is zero.
+    fn batch_invert(elems: &mut [Self]) -> Result<()> {
+        // prefix_prods[i] is the product of the scalars before the i-th one
+        let mut prefix_prods = Vec::with_capacity(elems.len());
+        let mut acc = Self::one();
+        for elem in elems.iter() {
+            if elem.is_zero() {
+                return Err(AlgebraError::GroupInversionError);
+            }
+            prefix_prods.push(acc);
+            acc.mul_assign(elem);
+        }
+
+        let mut acc_inv = acc.inv()?;
+        for (elem, prefix_prod) in elems.iter_mut().zip(prefix_prods.iter()).rev() {
+            let next_acc_inv = acc_inv.mul(&*elem);
+            *elem = acc_inv.mul(prefix_prod);
+            acc_inv = next_acc_inv;
+        }
+        Ok(())
+    }
+
     /// Return the square of the field element
     fn square(&self) -> Self;
 
@@ -150,6 +178,7 @@ pub trait Group:
     + for<'de> Deserialize<'de>
     + Send
     + Sync
+    + 'static
 {
     /// The scalar type
     type ScalarType: Scalar;
@@ -201,6 +230,14 @@ pub trait Group:
             pippenger(scalars, points).unwrap()
         }
     }
+
+    /// Compute the multiscalar multiplication with a sequence of group operations whose number
+    /// and order do not depend on the scalars. This is not constant time, as the table lookups
+    /// are indexed by the scalars and the group operations may depend on their operands.
+    #[inline]
+    fn multi_exp_fixed_window(scalars: &[&Self::ScalarType], points: &[&Self]) -> Self {
+        straus_fixed_window(scalars, points).unwrap()
+    }
 }
 
 /// Trait for Pedersen commitment.
@@ -231,7 +268,14 @@ pub trait Pairing {
     fn pairing(a: &Self::G1, b: &Self::G2) -> Self::Gt;
 
     /// The product of pairing operation
-    fn product_of_pairings(a: &[Self::G1], b: &[Self::G2]) -> Self::Gt;
+    fn product_of_pairings(a: &[Self::G1], b: &[Self::G2]) -> Self::Gt {
+        let pairs = a.iter().zip(b.iter()).collect::<Vec<_>>();
+        Self::multi_pairing(&pairs)
+    }
+
+    /// The product of the pairings of the pairs, which batches the Miller loops
+    /// and computes a single final exponentiation.
+    fn multi_pairing(pairs: &[(&Self::G1, &Self::G2)]) -> Self::Gt;
 }
 
 /// The trait for get x-coordinate and y-coordinate.
@@ -315,22 +359,194 @@ pub fn scalar_to_radix_2_power_w<S: Scalar>(scalar: &S, w: usize) -> Vec<i8> {
     digits
 }
 
+/// The window sizes of the Pippenger algorithm: the entry `(n, w)` uses the window `w` for
+/// at most `n` points, the entries are sorted by `n`, and the last window is used beyond.
+pub type MsmWindowTable = Vec<(usize, usize)>;
+
+/// The minimal window size of the Pippenger algorithm.
+pub const MIN_MSM_WINDOW: usize = 2;
+
+/// The maximal window size of the Pippenger algorithm, as the signed digits are `i8`.
+pub const MAX_MSM_WINDOW: usize = 7;
+
+/// The window tables that override the estimated window sizes, by group.
+#[cfg(feature = "std")]
+static MSM_WINDOW_TABLES: RwLock<BTreeMap<TypeId, MsmWindowTable>> = RwLock::new(BTreeMap::new());
+
+/// Check that the window table is sorted, and that the window sizes are supported.
+#[cfg(feature = "std")]
+fn check_msm_window_table(table: &[(usize, usize)]) -> Result<()> {
+    if table.is_empty()
+        || table.windows(2).any(|pair| pair[0].0 >= pair[1].0)
+        || table
+            .iter()
+            .any(|(_, w)| !(MIN_MSM_WINDOW..=MAX_MSM_WINDOW).contains(w))
+    {
+        return Err(AlgebraError::ParameterError);
+    }
+    Ok(())
+}
+
+/// Return the window size in the table for the number of points.
+#[cfg(feature = "std")]
+fn lookup_msm_window(table: &[(usize, usize)], size: usize) -> usize {
+    table
+        .iter()
+        .find(|(n, _)| size <= *n)
+        .or_else(|| table.last())
+        .map(|(_, w)| *w)
+        .unwrap_or(MIN_MSM_WINDOW)
+}
+
+/// Estimate the window size that minimizes the number of group operations of the Pippenger
+/// algorithm, i.e., `(bits / w + 1) * (size + 2^w + w)` for the bucket additions, the bucket sums,
+/// and the doublings between windows.
+pub fn estimate_msm_window(size: usize, scalar_bits: usize) -> usize {
+    (MIN_MSM_WINDOW..=MAX_MSM_WINDOW)
+        .min_by_key(|w| (scalar_bits / w + 1) * (size + (1 << w) + w))
+        .unwrap_or(MIN_MSM_WINDOW)
+}
+
+/// Override the window sizes of the Pippenger algorithm for the group, e.g., with a table
+/// from `tune_msm_window_table` measured at startup.
+#[cfg(feature = "std")]
+pub fn set_msm_window_table<G: Group>(table: MsmWindowTable) -> Result<()> {
+    check_msm_window_table(&table)?;
+    MSM_WINDOW_TABLES
+        .write()
+        .map_err(|_| AlgebraError::ParameterError)?
+        .insert(TypeId::of::<G>(), table);
+    Ok(())
+}
+
+/// Remove the override of the window sizes of the Pippenger algorithm for the group.
+#[cfg(feature = "std")]
+pub fn clear_msm_window_table<G: Group>() {
+    if let Ok(mut tables) = MSM_WINDOW_TABLES.write() {
+        tables.remove(&TypeId::of::<G>());
+    }
+}
+
+/// Return the window size of the Pippenger algorithm for the group and the number of points,
+/// from the table of the group if it is overridden, or from the estimate otherwise.
+pub fn msm_window_size<G: Group>(size: usize) -> usize {
+    #[cfg(feature = "std")]
+    if let Ok(tables) = MSM_WINDOW_TABLES.read() {
+        if let Some(table) = tables.get(&TypeId::of::<G>()) {
+            return lookup_msm_window(table, size);
+        }
+    }
+    estimate_msm_window(size, G::ScalarType::capacity() + 1)
+}
+
+/// Measure the Pippenger algorithm with every window size for each number of points in `sizes`,
+/// and return the table of the fastest window sizes.
+#[cfg(feature = "std")]
+pub fn tune_msm_window_table<G: Group, R: CryptoRng + RngCore>(
+    prng: &mut R,
+    sizes: &[usize],
+) -> Result<MsmWindowTable> {
+    let mut sizes = sizes.to_vec();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    let mut table = MsmWindowTable::new();
+    for size in sizes.into_iter().filter(|size| *size > 0) {
+        let scalars = (0..size).map(|_| G::ScalarType::random(prng)).collect_vec();
+        let points = (0..size).map(|_| G::random(prng)).collect_vec();
+        let scalars = scalars.iter().collect_vec();
+        let points = points.iter().collect_vec();
+
+        let mut best = (MIN_MSM_WINDOW, std::time::Duration::MAX);
+        for w in MIN_MSM_WINDOW..=MAX_MSM_WINDOW {
+            let start = Instant::now();
+            pippenger_with_window(&scalars, &points, w)?;
+            let elapsed = start.elapsed();
+            if elapsed < best.1 {
+                best = (w, elapsed);
+            }
+        }
+        table.push((size, best.0));
+    }
+    check_msm_window_table(&table)?;
+    Ok(table)
+}
+
+/// The window size of the fixed-window Straus algorithm, which divides the size of a limb.
+const STRAUS_WINDOW: usize = 4;
+
+/// Run the Straus algorithm with fixed windows to compute multiscalar multiplication.
+///
+/// Every window of every scalar is processed, including the zero windows which add the identity,
+/// so that the number and the order of the group operations only depend on the number of points
+/// and the scalar field. It is not constant time: the table lookups are indexed by the digits
+/// of the scalars, and the group operations may short-circuit on the identity.
+pub fn straus_fixed_window<G: Group>(scalars: &[&G::ScalarType], elems: &[&G]) -> Result<G> {
+    if scalars.len() != elems.len() {
+        return Err(AlgebraError::ParameterError);
+    }
+
+    let num_limbs = (G::ScalarType::bytes_len() + 7) / 8;
+    let num_windows = num_limbs * 64 / STRAUS_WINDOW;
+    let window_mask = (1u64 << STRAUS_WINDOW) - 1;
+
+    // tables[i][j] = j * elems[i], for j in [0, 2^w)
+    let tables = elems
+        .iter()
+        .map(|elem| {
+            let mut table = Vec::with_capacity(1 << STRAUS_WINDOW);
+            table.push(G::get_identity());
+            for j in 1..(1 << STRAUS_WINDOW) {
+                let next = table[j - 1].add(*elem);
+                table.push(next);
+            }
+            table
+        })
+        .collect_vec();
+
+    let digits = scalars
+        .iter()
+        .map(|scalar| {
+            let limbs = scalar.get_little_endian_u64();
+            (0..num_windows)
+                .map(|k| {
+                    let bit_offset = k * STRAUS_WINDOW;
+                    let limb = limbs.get(bit_offset / 64).copied().unwrap_or(0);
+                    ((limb >> (bit_offset % 64)) & window_mask) as usize
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let mut acc = G::get_identity();
+    for k in (0..num_windows).rev() {
+        for _ in 0..STRAUS_WINDOW {
+            acc = acc.double();
+        }
+        for (table, digits) in tables.iter().zip(digits.iter()) {
+            acc = acc.add(&table[digits[k]]);
+        }
+    }
+    Ok(acc)
+}
+
 /// Run the pippenger algorithm to compute multiscalar multiplication
 pub fn pippenger<G: Group>(scalars: &[&G::ScalarType], elems: &[&G]) -> Result<G> {
+    pippenger_with_window(scalars, elems, msm_window_size::<G>(scalars.len()))
+}
+
+/// Run the pippenger algorithm with the given window size.
+pub fn pippenger_with_window<G: Group>(
+    scalars: &[&G::ScalarType],
+    elems: &[&G],
+    w: usize,
+) -> Result<G> {
     let size = scalars.len();
 
-    if size == 0 {
+    if size == 0 || !(MIN_MSM_WINDOW..=MAX_MSM_WINDOW).contains(&w) {
         return Err(AlgebraError::ParameterError);
     }
 
-    let w = if size < 500 {
-        6
-    } else if size < 800 {
-        7
-    } else {
-        8
-    };
-
     let two_power_w: usize = 1 << w;
     let digits_vec: Vec<Vec<i8>> = scalars
         .iter()
@@ -344,41 +560,39 @@ pub fn pippenger<G: Group>(scalars: &[&G::ScalarType], elems: &[&G]) -> Result<G
         }
     }
 
-    // init all the buckets
-    let mut buckets: Vec<_> = (0..two_power_w / 2).map(|_| G::get_identity()).collect();
-
-    let mut cols = (0..digits_count).rev().map(|index| {
-        // empty each bucket
-        for b in buckets.iter_mut() {
-            *b = G::get_identity();
-        }
-        for (digits, elem) in digits_vec.iter().zip(elems) {
-            if index >= digits.len() {
-                continue;
-            }
-            let digit = digits[index];
-            if digit > 0 {
-                let b_index = (digit - 1) as usize;
-                buckets[b_index].add_assign(elem);
+    // the columns of digits are independent, and are summed in parallel with the feature `parallel`
+    let cols: Vec<G> = cfg_into_iter!(0..digits_count)
+        .map(|index| {
+            let mut buckets: Vec<_> = (0..two_power_w / 2).map(|_| G::get_identity()).collect();
+            for (digits, elem) in digits_vec.iter().zip(elems) {
+                if index >= digits.len() {
+                    continue;
+                }
+                let digit = digits[index];
+                if digit > 0 {
+                    let b_index = (digit - 1) as usize;
+                    buckets[b_index].add_assign(*elem);
+                }
+                if digit < 0 {
+                    let b_index = (-(digit + 1)) as usize;
+                    buckets[b_index].sub_assign(*elem);
+                }
             }
-            if digit < 0 {
-                let b_index = (-(digit + 1)) as usize;
-                buckets[b_index].sub_assign(elem);
+            let mut intermediate_sum = buckets[buckets.len() - 1];
+            let mut sum = buckets[buckets.len() - 1];
+            for i in (0..buckets.len() - 1).rev() {
+                intermediate_sum = intermediate_sum.add(&buckets[i]);
+                sum = sum.add(&intermediate_sum);
             }
-        }
-        let mut intermediate_sum = buckets[buckets.len() - 1];
-        let mut sum = buckets[buckets.len() - 1];
-        for i in (0..buckets.len() - 1).rev() {
-            intermediate_sum = intermediate_sum.add(&buckets[i]);
-            sum = sum.add(&intermediate_sum);
-        }
-        sum
-    });
+            sum
+        })
+        .collect();
 
-    let two_power_w_int = G::ScalarType::from(two_power_w as u64);
     // This unwrap is safe as the list of scalars is non empty at this point.
-    let hi_col = cols.next().unwrap();
-    let res = cols.fold(hi_col, |total, p| total.mul(&two_power_w_int).add(&p));
+    let (hi_col, lo_cols) = cols.split_last().unwrap();
+    let res = lo_cols.iter().rev().fold(*hi_col, |total, p| {
+        (0..w).fold(total, |acc, _| acc.double()).add(p)
+    });
     Ok(res)
 }
 
@@ -444,6 +658,15 @@ pub(crate) mod group_tests {
         let d = S::from(1u32);
         assert_eq!(c, d);
 
+        let mut elems = (1..10u32).map(|i| S::from(i * 7)).collect::<Vec<S>>();
+        let expected = elems.iter().map(|e| e.inv().unwrap()).collect::<Vec<S>>();
+        S::batch_invert(&mut elems).unwrap();
+        assert_eq!(elems, expected);
+        let mut elems = vec![S::from(3u32), S::zero(), S::from(5u32)];
+        assert!(S::batch_invert(&mut elems).is_err());
+        assert_eq!(elems, vec![S::from(3u32), S::zero(), S::from(5u32)]);
+        assert!(S::batch_invert(&mut []).is_ok());
+
         let a = S::from(3u32);
         let b = vec![20];
         let c = a.pow(&b[..]);
@@ -487,8 +710,16 @@ mod multi_exp_tests {
     use crate::bls12_381::BLSGt;
     use crate::bls12_381::BLSG1;
     use crate::bls12_381::BLSG2;
+    use crate::prelude::*;
     use crate::ristretto::RistrettoPoint;
-    use crate::traits::Group;
+    #[cfg(feature = "std")]
+    use crate::traits::{
+        clear_msm_window_table, msm_window_size, set_msm_window_table, tune_msm_window_table,
+    };
+    use crate::traits::{
+        estimate_msm_window, pippenger_with_window, straus_fixed_window, MAX_MSM_WINDOW,
+        MIN_MSM_WINDOW,
+    };
 
     #[test]
     fn test_multiexp_ristretto() {
@@ -507,6 +738,90 @@ mod multi_exp_tests {
         run_multiexp_test::<BLSGt>();
     }
 
+    #[test]
+    fn test_pippenger_window_sizes() {
+        let mut prng = test_rng();
+        let scalars = (0..50)
+            .map(|_| <BLSG1 as Group>::ScalarType::random(&mut prng))
+            .collect_vec();
+        let points = (0..50).map(|_| BLSG1::random(&mut prng)).collect_vec();
+        let scalars = scalars.iter().collect_vec();
+        let points = points.iter().collect_vec();
+
+        let expected = scalars
+            .iter()
+            .zip(points.iter())
+            .fold(BLSG1::get_identity(), |acc, (s, p)| acc.add(&p.mul(s)));
+        for w in MIN_MSM_WINDOW..=MAX_MSM_WINDOW {
+            assert_eq!(
+                pippenger_with_window(&scalars, &points, w).unwrap(),
+                expected
+            );
+        }
+        assert!(pippenger_with_window(&scalars, &points, MAX_MSM_WINDOW + 1).is_err());
+        assert!(pippenger_with_window(&scalars, &points, MIN_MSM_WINDOW - 1).is_err());
+
+        // the estimate grows with the number of points, but stays within the digits
+        assert!(estimate_msm_window(10, 256) <= estimate_msm_window(10_000, 256));
+        assert_eq!(estimate_msm_window(1 << 20, 256), MAX_MSM_WINDOW);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_msm_window_table_override() {
+        assert!(set_msm_window_table::<BLSGt>(vec![]).is_err());
+        assert!(set_msm_window_table::<BLSGt>(vec![(100, 3), (50, 4)]).is_err());
+        assert!(set_msm_window_table::<BLSGt>(vec![(100, 8)]).is_err());
+
+        set_msm_window_table::<BLSGt>(vec![(16, 2), (256, 3)]).unwrap();
+        assert_eq!(msm_window_size::<BLSGt>(1), 2);
+        assert_eq!(msm_window_size::<BLSGt>(16), 2);
+        assert_eq!(msm_window_size::<BLSGt>(17), 3);
+        assert_eq!(msm_window_size::<BLSGt>(100_000), 3);
+        // the other groups are not affected
+        assert_eq!(
+            msm_window_size::<BLSG2>(100_000),
+            estimate_msm_window(100_000, <BLSG2 as Group>::ScalarType::capacity() + 1)
+        );
+
+        clear_msm_window_table::<BLSGt>();
+        assert_eq!(
+            msm_window_size::<BLSGt>(100_000),
+            estimate_msm_window(100_000, <BLSGt as Group>::ScalarType::capacity() + 1)
+        );
+
+        let mut prng = test_rng();
+        let table = tune_msm_window_table::<RistrettoPoint, _>(&mut prng, &[8, 4, 8]).unwrap();
+        assert_eq!(table.iter().map(|(n, _)| *n).collect_vec(), vec![4, 8]);
+    }
+
+    #[test]
+    fn test_multiexp_fixed_window() {
+        run_multiexp_fixed_window_test::<RistrettoPoint>();
+        run_multiexp_fixed_window_test::<BLSG1>();
+        run_multiexp_fixed_window_test::<BLSGt>();
+    }
+
+    fn run_multiexp_fixed_window_test<G: Group>() {
+        let mut prng = test_rng();
+        assert_eq!(G::multi_exp_fixed_window(&[], &[]), G::get_identity());
+
+        let mut scalars = (0..10)
+            .map(|_| G::ScalarType::random(&mut prng))
+            .collect_vec();
+        scalars.push(G::ScalarType::zero());
+        scalars.push(G::ScalarType::one().neg());
+        let points = (0..12).map(|_| G::random(&mut prng)).collect_vec();
+        let scalars = scalars.iter().collect_vec();
+        let points = points.iter().collect_vec();
+
+        assert_eq!(
+            G::multi_exp_fixed_window(&scalars, &points),
+            G::multi_exp(&scalars, &points)
+        );
+        assert!(straus_fixed_window(&scalars[1..], &points).is_err());
+    }
+
     fn run_multiexp_test<G: Group>() {
         let g = G::multi_exp(&[], &[]);
         assert_eq!(g, G::get_identity());
@@ -8,6 +8,7 @@ use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
 use base64::Engine;
 use digest::generic_array::typenum::U64;
 use digest::Digest;
+use num_bigint::BigUint;
 use rand_chacha::ChaCha20Rng;
 
 const BASE64_PADDING_CONFIG: GeneralPurposeConfig =
@@ -123,6 +124,24 @@ pub fn u64_limbs_from_bytes(slice: &[u8]) -> Vec<u64> {
     r
 }
 
+/// Convert a scalar into another field, bound-checked: it fails with `ParameterError` when the
+/// canonical integer of the scalar is not smaller than the modulus of the target field, so that
+/// distinct scalars never convert to the same one.
+pub fn convert_scalar<F: Scalar, T: Scalar>(scalar: &F) -> Result<T> {
+    let n: BigUint = (*scalar).into();
+    if n >= T::get_field_size_biguint() {
+        return Err(AlgebraError::ParameterError);
+    }
+    Ok(T::from(&n))
+}
+
+/// Convert a scalar into another field by reducing its canonical integer modulo the target field,
+/// which maps distinct scalars to the same one when the source field is larger.
+pub fn convert_scalar_reduced<F: Scalar, T: Scalar>(scalar: &F) -> T {
+    let n: BigUint = (*scalar).into();
+    T::from(&n)
+}
+
 /// Save parameters to a file
 #[cfg(feature = "std")]
 pub fn save_to_file(params_ser: &[u8], out_filename: ark_std::path::PathBuf) {
@@ -145,8 +164,35 @@ macro_rules! not_matches {
 
 #[cfg(test)]
 mod test {
+    use crate::bn254::BN254Scalar;
+    use crate::prelude::*;
+    use crate::ristretto::RistrettoScalar;
     use ark_std::vec;
 
+    #[test]
+    fn test_convert_scalar() {
+        let mut prng = test_rng();
+
+        // the Ristretto scalar field is smaller than the BN254 scalar field
+        let x = RistrettoScalar::random(&mut prng);
+        let y: BN254Scalar = super::convert_scalar(&x).unwrap();
+        assert_eq!(y, super::convert_scalar_reduced(&x));
+        assert_eq!(super::convert_scalar::<_, RistrettoScalar>(&y).unwrap(), x);
+
+        let minus_one = RistrettoScalar::one().neg();
+        assert!(super::convert_scalar::<_, BN254Scalar>(&minus_one).is_ok());
+
+        // the BN254 scalars above the Ristretto modulus would wrap around
+        let z = BN254Scalar::one().neg();
+        assert!(super::convert_scalar::<_, RistrettoScalar>(&z).is_err());
+        let reduced: RistrettoScalar = super::convert_scalar_reduced(&z);
+        let n: BigUint = z.into();
+        assert_eq!(
+            reduced,
+            RistrettoScalar::from(&(n % RistrettoScalar::get_field_size_biguint()))
+        );
+    }
+
     #[test]
     fn test_shift_u8_vec() {
         let mut v = vec![0];
@@ -0,0 +1,73 @@
+use crate::{
+    baby_jubjub::BabyJubjubScalar,
+    bls12_381::{BLSFq, BLSGt, BLSScalar, BLSG1, BLSG2},
+    bn254::{BN254Fq, BN254Gt, BN254Scalar, BN254G1, BN254G2},
+    ed25519::{Ed25519Point, Ed25519Scalar},
+    jubjub::JubjubScalar,
+    prelude::*,
+    secp256k1::{SECP256K1Scalar, SECP256K1G1},
+    secq256k1::{SECQ256K1Scalar, SECQ256K1G1},
+    zorro::{ZorroFq, ZorroG1, ZorroScalar},
+};
+use ark_std::format;
+use wasm_bindgen::prelude::*;
+
+macro_rules! wasm_wrapper {
+    ($wrapper:ident, $t:ident) => {
+        #[doc = concat!("The WASM binding of `", stringify!($t), "`.")]
+        #[wasm_bindgen]
+        #[derive(Copy, Clone, Default, PartialEq, Eq)]
+        pub struct $wrapper(pub(crate) $t);
+
+        #[wasm_bindgen]
+        impl $wrapper {
+            /// Reconstruct from the bytes of `NoahFromToBytes`.
+            #[wasm_bindgen(js_name = fromBytes)]
+            pub fn from_bytes(bytes: &[u8]) -> core::result::Result<$wrapper, JsValue> {
+                $t::noah_from_bytes(bytes)
+                    .map(Self)
+                    .map_err(|e| JsValue::from_str(&format!("{}", e)))
+            }
+
+            /// Convert to the bytes of `NoahFromToBytes`.
+            #[wasm_bindgen(js_name = toBytes)]
+            pub fn to_bytes(&self) -> Vec<u8> {
+                self.0.noah_to_bytes()
+            }
+        }
+
+        impl From<$t> for $wrapper {
+            fn from(inner: $t) -> Self {
+                Self(inner)
+            }
+        }
+
+        impl From<$wrapper> for $t {
+            fn from(wrapper: $wrapper) -> Self {
+                wrapper.0
+            }
+        }
+    };
+}
+
+wasm_wrapper!(WasmBLSScalar, BLSScalar);
+wasm_wrapper!(WasmBLSFq, BLSFq);
+wasm_wrapper!(WasmBLSG1, BLSG1);
+wasm_wrapper!(WasmBLSG2, BLSG2);
+wasm_wrapper!(WasmBLSGt, BLSGt);
+wasm_wrapper!(WasmBN254Scalar, BN254Scalar);
+wasm_wrapper!(WasmBN254Fq, BN254Fq);
+wasm_wrapper!(WasmBN254G1, BN254G1);
+wasm_wrapper!(WasmBN254G2, BN254G2);
+wasm_wrapper!(WasmBN254Gt, BN254Gt);
+wasm_wrapper!(WasmJubjubScalar, JubjubScalar);
+wasm_wrapper!(WasmBabyJubjubScalar, BabyJubjubScalar);
+wasm_wrapper!(WasmSECQ256K1Scalar, SECQ256K1Scalar);
+wasm_wrapper!(WasmSECQ256K1G1, SECQ256K1G1);
+wasm_wrapper!(WasmSECP256K1Scalar, SECP256K1Scalar);
+wasm_wrapper!(WasmSECP256K1G1, SECP256K1G1);
+wasm_wrapper!(WasmZorroScalar, ZorroScalar);
+wasm_wrapper!(WasmZorroFq, ZorroFq);
+wasm_wrapper!(WasmZorroG1, ZorroG1);
+wasm_wrapper!(WasmEd25519Scalar, Ed25519Scalar);
+wasm_wrapper!(WasmEd25519Point, Ed25519Point);
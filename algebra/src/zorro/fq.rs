@@ -6,10 +6,8 @@ use digest::consts::U64;
 use digest::Digest;
 use num_bigint::BigUint;
 use num_traits::Num;
-use wasm_bindgen::prelude::*;
 
 /// The wrapped struct for `ark_bulletproofs::curve::zorro::Fq`
-#[wasm_bindgen]
 #[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Debug, Hash)]
 pub struct ZorroFq(pub(crate) Fq);
 
@@ -7,10 +7,8 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate
 use ark_std::vec::Vec;
 use digest::consts::U64;
 use digest::Digest;
-use wasm_bindgen::prelude::wasm_bindgen;
 
 /// The wrapped struct for `ark_bulletproofs::curve::zorro::G1Projective`
-#[wasm_bindgen]
 #[derive(Copy, Default, Clone, PartialEq, Eq)]
 pub struct ZorroG1(pub(crate) G1Projective);
 
@@ -0,0 +1,37 @@
+//! Compress the verifier parameters embedded with the `compressed_vk` feature.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(all(feature = "compressed_vk", not(feature = "no_vk")))]
+    compress_verifier_params();
+}
+
+/// The files of the verifier parameters in the `parameters` directory.
+#[cfg(all(feature = "compressed_vk", not(feature = "no_vk")))]
+const VERIFIER_PARAMS: &[&str] = &[
+    "transfer-vk-common.bin",
+    "transfer-vk-ed25519-specific.bin",
+    "transfer-vk-secp256k1-specific.bin",
+    "abar-to-bar-vk-ed25519.bin",
+    "abar-to-bar-vk-secp256k1.bin",
+    "bar-to-abar-vk.bin",
+    "ar-to-abar-vk.bin",
+    "abar-to-ar-vk-ed25519.bin",
+    "abar-to-ar-vk-secp256k1.bin",
+];
+
+#[cfg(all(feature = "compressed_vk", not(feature = "no_vk")))]
+fn compress_verifier_params() {
+    use std::{env, fs, path::PathBuf};
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    for file in VERIFIER_PARAMS.iter() {
+        let path = format!("parameters/{}", file);
+        println!("cargo:rerun-if-changed={}", path);
+
+        let bytes = fs::read(&path).unwrap();
+        let compressed = miniz_oxide::deflate::compress_to_vec(&bytes, 10);
+        fs::write(out_dir.join(format!("{}.deflate", file)), compressed).unwrap();
+    }
+}
@@ -0,0 +1,279 @@
+use crate::anon_xfr::abar_to_ar::{prove_abar_to_ar, ABAR_TO_AR_PLONK_PROOF_TRANSCRIPT};
+use crate::anon_xfr::address_folding_ed25519::{
+    create_address_folding_ed25519, prepare_verifier_input_ed25519, verify_address_folding_ed25519,
+};
+use crate::anon_xfr::address_folding_secp256k1::{
+    create_address_folding_secp256k1, prepare_verifier_input_secp256k1,
+    verify_address_folding_secp256k1,
+};
+use crate::anon_xfr::{
+    commit, nullify,
+    structs::{Nullifier, OpenAnonAssetRecord, PayerWitness},
+    AXfrAddressFoldingInstance, AXfrAddressFoldingWitness, AXfrPlonkPf,
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, SecretKey};
+use crate::parameters::params::{AXfrPCS, ProverParams, VerifierParams};
+use crate::parameters::schema::PublicInputsSchema;
+use crate::xfr::structs::AssetType;
+use digest::{consts::U64, Digest};
+use merlin::Transcript;
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use noah_plonk::plonk::verifier::verifier;
+
+/// The domain separator for burning anonymous assets, for address folding.
+const ABAR_BURN_FOLDING_PROOF_TRANSCRIPT: &[u8] = b"ABAR Burn Folding Proof";
+
+/// The maximal number of bytes of the destination of a burn.
+pub const MAX_ABAR_BURN_DESTINATION_SIZE: usize = 256;
+
+/// The note that burns an anonymous asset record and reveals its amount and asset type.
+///
+/// It proves the same statement as the anonymous-to-transparent note, and thus shares its
+/// parameters, but creates no record: the revealed amount leaves the pool, to be minted
+/// on another chain or redeemed with the issuer at the given destination.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AbarBurnNote {
+    /// The body part of the burn.
+    pub body: AbarBurnBody,
+    /// The Plonk proof (assuming non-malleability).
+    pub proof: AXfrPlonkPf,
+    /// The address folding instance.
+    pub folding_instance: AXfrAddressFoldingInstance,
+}
+
+/// The body of the burn.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AbarBurnBody {
+    /// input ABAR being burnt.
+    pub input: Nullifier,
+    /// The revealed amount.
+    pub amount: u64,
+    /// The revealed asset type.
+    pub asset_type: AssetType,
+    /// The Merkle root hash.
+    pub merkle_root: BN254Scalar,
+    /// The Merkle root version.
+    pub merkle_root_version: u64,
+    /// The destination of the burnt amount, e.g., an address on another chain, opaque to Noah.
+    pub destination: Vec<u8>,
+}
+
+/// Generate a note that burns an anonymous asset record, toward the destination.
+pub fn gen_abar_burn_note<
+    R: CryptoRng + RngCore,
+    D: Digest<OutputSize = U64> + Default,
+    PCS: AXfrPCS,
+>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    oabar: &OpenAnonAssetRecord,
+    abar_keypair: &KeyPair,
+    destination: &[u8],
+    hash: D,
+) -> Result<AbarBurnNote> {
+    if destination.len() > MAX_ABAR_BURN_DESTINATION_SIZE {
+        return Err(NoahError::ParameterError);
+    }
+
+    let mt_leaf_info = oabar
+        .mt_leaf_info
+        .as_ref()
+        .ok_or(NoahError::ParameterError)?;
+    let body = AbarBurnBody {
+        input: abar_nullifier(oabar, abar_keypair)?,
+        amount: oabar.amount,
+        asset_type: oabar.asset_type,
+        merkle_root: mt_leaf_info.root,
+        merkle_root_version: mt_leaf_info.root_version,
+        destination: destination.to_vec(),
+    };
+
+    let mut transcript = burn_folding_transcript(&body);
+    let (proof, folding_instance) =
+        prove_abar_reveal(prng, params, oabar, abar_keypair, &mut transcript, hash)?;
+
+    Ok(AbarBurnNote {
+        body,
+        proof,
+        folding_instance,
+    })
+}
+
+/// Verify the note that burns an anonymous asset record.
+/// The verifier parameters are those of anonymous-to-transparent.
+pub fn verify_abar_burn_note<D: Digest<OutputSize = U64> + Default>(
+    params: &VerifierParams,
+    note: &AbarBurnNote,
+    merkle_root: &BN254Scalar,
+    hash: D,
+) -> Result<()> {
+    if note.body.destination.len() > MAX_ABAR_BURN_DESTINATION_SIZE {
+        return Err(NoahError::ParameterError);
+    }
+
+    if *merkle_root != note.body.merkle_root {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    let mut transcript = burn_folding_transcript(&note.body);
+    verify_abar_reveal(
+        params,
+        &note.body.input,
+        merkle_root,
+        note.body.amount,
+        note.body.asset_type,
+        &note.folding_instance,
+        &note.proof,
+        &mut transcript,
+        hash,
+    )
+}
+
+/// The transcript of the address folding, which binds the burn to its destination.
+///
+/// The nullifier, the amount, and the asset type are already public inputs of the Plonk proof,
+/// but the destination is not, so it is absorbed here, where the address folding challenges,
+/// and thus the Plonk proof, depend on it.
+fn burn_folding_transcript(body: &AbarBurnBody) -> Transcript {
+    let mut transcript = Transcript::new(ABAR_BURN_FOLDING_PROOF_TRANSCRIPT);
+    transcript.append_message(b"nullifier", &body.input.to_bytes());
+    transcript.append_message(b"destination", &body.destination);
+    transcript
+}
+
+/// Compute the nullifier of the ABAR, which must be in the Merkle tree and owned by the key pair.
+pub(crate) fn abar_nullifier(oabar: &OpenAnonAssetRecord, keypair: &KeyPair) -> Result<Nullifier> {
+    let mt_leaf_info = oabar
+        .mt_leaf_info
+        .as_ref()
+        .ok_or(NoahError::ParameterError)?;
+    if keypair.get_pk() != oabar.pub_key {
+        return Err(NoahError::ParameterError);
+    }
+    let (nullifier, _) = nullify(
+        keypair,
+        oabar.amount,
+        oabar.asset_type.as_scalar(),
+        mt_leaf_info.uid,
+    )?;
+    Ok(nullifier)
+}
+
+/// Prove the spending of the ABAR with its amount and asset type revealed, as in
+/// anonymous-to-transparent, with the address folding over the transcript and the hash.
+pub(crate) fn prove_abar_reveal<
+    R: CryptoRng + RngCore,
+    D: Digest<OutputSize = U64> + Default,
+    PCS: AXfrPCS,
+>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    oabar: &OpenAnonAssetRecord,
+    keypair: &KeyPair,
+    transcript: &mut Transcript,
+    hash: D,
+) -> Result<(AXfrPlonkPf, AXfrAddressFoldingInstance)> {
+    let mt_leaf_info = oabar
+        .mt_leaf_info
+        .as_ref()
+        .ok_or(NoahError::ParameterError)?;
+    if keypair.get_pk() != oabar.pub_key {
+        return Err(NoahError::ParameterError);
+    }
+
+    let (_, nullifier_trace) = nullify(
+        keypair,
+        oabar.amount,
+        oabar.asset_type.as_scalar(),
+        mt_leaf_info.uid,
+    )?;
+    let (_, commitment_trace) = commit(
+        &keypair.get_pk(),
+        oabar.blind,
+        oabar.amount,
+        oabar.asset_type.as_scalar(),
+    )?;
+
+    let payers_witness = PayerWitness {
+        secret_key: keypair.get_sk(),
+        uid: mt_leaf_info.uid,
+        amount: oabar.amount,
+        asset_type: oabar.asset_type.as_scalar(),
+        path: mt_leaf_info.path.clone(),
+        blind: oabar.blind,
+    };
+
+    let (folding_instance, folding_witness) = match keypair.get_sk_ref() {
+        SecretKey::Secp256k1(_) => {
+            let (folding_instance, folding_witness) =
+                create_address_folding_secp256k1(prng, hash, transcript, keypair)?;
+            (
+                AXfrAddressFoldingInstance::Secp256k1(folding_instance),
+                AXfrAddressFoldingWitness::Secp256k1(folding_witness),
+            )
+        }
+        SecretKey::Ed25519(_) => {
+            let (folding_instance, folding_witness) =
+                create_address_folding_ed25519(prng, hash, transcript, keypair)?;
+            (
+                AXfrAddressFoldingInstance::Ed25519(folding_instance),
+                AXfrAddressFoldingWitness::Ed25519(folding_witness),
+            )
+        }
+    };
+
+    let proof = prove_abar_to_ar(
+        prng,
+        params,
+        &payers_witness,
+        &nullifier_trace,
+        &commitment_trace,
+        &folding_witness,
+    )?;
+    Ok((proof, folding_instance))
+}
+
+/// Verify the spending of an ABAR with its amount and asset type revealed.
+pub(crate) fn verify_abar_reveal<D: Digest<OutputSize = U64> + Default>(
+    params: &VerifierParams,
+    nullifier: &Nullifier,
+    merkle_root: &BN254Scalar,
+    amount: u64,
+    asset_type: AssetType,
+    folding_instance: &AXfrAddressFoldingInstance,
+    proof: &AXfrPlonkPf,
+    transcript: &mut Transcript,
+    hash: D,
+) -> Result<()> {
+    let address_folding_public_input = match folding_instance {
+        AXfrAddressFoldingInstance::Secp256k1(a) => {
+            let (beta, lambda) = verify_address_folding_secp256k1(hash, transcript, a)?;
+            prepare_verifier_input_secp256k1(a, &beta, &lambda)
+        }
+        AXfrAddressFoldingInstance::Ed25519(a) => {
+            let (beta, lambda) = verify_address_folding_ed25519(hash, transcript, a)?;
+            prepare_verifier_input_ed25519(a, &beta, &lambda)
+        }
+    };
+
+    let mut transcript = Transcript::new(ABAR_TO_AR_PLONK_PROOF_TRANSCRIPT);
+    let mut online_inputs = vec![
+        *nullifier,
+        *merkle_root,
+        BN254Scalar::from(amount),
+        asset_type.as_scalar(),
+    ];
+    online_inputs.extend_from_slice(&address_folding_public_input);
+    PublicInputsSchema::abar_to_ar(folding_instance.get_address_format())
+        .validate(&online_inputs)?;
+
+    Ok(verifier(
+        &mut transcript,
+        &params.shrunk_vk,
+        &params.shrunk_cs,
+        &params.verifier_params,
+        &online_inputs,
+        proof,
+    )?)
+}
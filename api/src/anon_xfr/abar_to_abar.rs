@@ -6,24 +6,28 @@ use crate::anon_xfr::address_folding_secp256k1::{
     create_address_folding_secp256k1, prepare_verifier_input_secp256k1,
     prove_address_folding_in_cs_secp256k1, verify_address_folding_secp256k1,
 };
+use crate::anon_xfr::asset_tracing::{verify_axfr_tracer_memos, AXfrTracerMemo};
 use crate::anon_xfr::{
     add_merkle_path_variables, check_asset_amount, check_inputs, check_roots, commit, commit_in_cs,
     compute_merkle_root_variables, nullify, nullify_in_cs,
     structs::{
-        AccElemVars, AnonAssetRecord, AxfrOwnerMemo, Commitment, MTNode, MTPath, Nullifier,
-        OpenAnonAssetRecord, PayeeWitness, PayeeWitnessVars, PayerWitness, PayerWitnessVars,
+        AccElemVars, AnonAssetRecord, AxfrOutputPayload, AxfrOwnerMemo, Commitment, MTNode, MTPath,
+        Nullifier, OpenAnonAssetRecord, PayeeWitness, PayeeWitnessVars, PayerWitness,
+        PayerWitnessVars,
     },
     AXfrAddressFoldingInstance, AXfrAddressFoldingWitness, AXfrPlonkPf, TurboPlonkCS, AMOUNT_LEN,
-    FEE_TYPE, MAX_AXFR_MEMO_SIZE, TREE_DEPTH,
+    FEE_TYPE, TREE_DEPTH,
 };
 use crate::errors::{NoahError, Result};
 use crate::keys::{KeyPair, PublicKey, PublicKeyInner, SecretKey};
-use crate::parameters::params::ProverParams;
+use crate::parameters::params::{AXfrPCS, ProverParams};
 use crate::parameters::params::{AddressFormat, VerifierParams};
+use crate::parameters::schema::PublicInputsSchema;
 use crate::parameters::{
     MAX_ANONYMOUS_RECORD_NUMBER_CONSOLIDATION_RECEIVER, MAX_ANONYMOUS_RECORD_NUMBER_ONE_INPUT,
     MAX_ANONYMOUS_RECORD_NUMBER_STANDARD,
 };
+use crate::serialization::{from_bincode, to_bincode};
 use digest::{consts::U64, Digest};
 use merlin::Transcript;
 use noah_algebra::bn254::BN254Scalar;
@@ -36,6 +40,7 @@ use noah_plonk::plonk::{
     prover::prover_with_lagrange,
     verifier::verifier,
 };
+use rand_chacha::ChaChaRng;
 #[cfg(feature = "parallel")]
 use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
@@ -43,10 +48,14 @@ use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelI
 const ANON_XFR_PLONK_PROOF_TRANSCRIPT: &[u8] = b"Anon Xfr Plonk Proof";
 /// The domain separator for anonymous transfer, for address folding.
 const ANON_XFR_FOLDING_PROOF_TRANSCRIPT: &[u8] = b"Anon Xfr Folding Proof";
+/// The domain separator for the digest of an anonymous transfer note.
+const ANON_XFR_NOTE_DIGEST_DOMAIN: &[u8] = b"Anon Xfr Note Digest";
 /// The domain separator for the number of inputs.
 const N_INPUTS_TRANSCRIPT: &[u8] = b"Number of input ABARs";
 /// The domain separator for the number of outputs.
 const N_OUTPUTS_TRANSCRIPT: &[u8] = b"Number of output ABARs";
+/// The domain separator for the seed of the shuffling of the outputs.
+const ANON_XFR_OUTPUTS_SHUFFLE_DOMAIN: &[u8] = b"Anon Xfr Outputs Shuffle";
 
 /// Anonymous transfer note.
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Eq)]
@@ -59,6 +68,155 @@ pub struct AXfrNote {
     pub folding_instance: AXfrAddressFoldingInstance,
 }
 
+/// The bincode layout of the notes before the tracer memos and the output payloads.
+type AXfrNoteV1 = (
+    (
+        Vec<Nullifier>,
+        Vec<AnonAssetRecord>,
+        BN254Scalar,
+        u64,
+        u32,
+        Vec<AxfrOwnerMemo>,
+    ),
+    AXfrPlonkPf,
+    AXfrAddressFoldingInstance,
+);
+
+/// The bincode layout of the notes with tracer memos, before the output payloads.
+type AXfrNoteV2 = (
+    (
+        Vec<Nullifier>,
+        Vec<AnonAssetRecord>,
+        BN254Scalar,
+        u64,
+        u32,
+        Vec<AxfrOwnerMemo>,
+        Vec<Option<AXfrTracerMemo>>,
+    ),
+    AXfrPlonkPf,
+    AXfrAddressFoldingInstance,
+);
+
+impl AXfrNote {
+    /// Compute the digest of the note, which uniquely identifies the note.
+    ///
+    /// A note is hashed in the layout from before the fields that it leaves empty were added,
+    /// so that the digest of a note without output payloads, or also without tracer memos,
+    /// does not change.
+    pub fn digest(&self) -> Result<[u8; 64]> {
+        let bytes = if !self.body.output_payloads.is_empty() {
+            to_bincode(self)?
+        } else if !self.body.tracer_memos.is_empty() {
+            self.to_bincode_v2()?
+        } else {
+            self.to_bincode_v1()?
+        };
+
+        let mut hasher = sha2::Sha512::new();
+        hasher.update(ANON_XFR_NOTE_DIGEST_DOMAIN);
+        hasher.update(&bytes);
+
+        let mut digest = [0u8; 64];
+        digest.copy_from_slice(&hasher.finalize());
+        Ok(digest)
+    }
+
+    /// Decode a note from bincode, which also accepts the notes encoded before the output
+    /// payloads, or also before the tracer memos, were added.
+    pub fn from_bincode(bytes: &[u8]) -> Result<Self> {
+        if let Ok(note) = from_bincode::<AXfrNote>(bytes) {
+            if to_bincode(&note)? == bytes {
+                return Ok(note);
+            }
+        }
+
+        if let Ok((
+            (inputs, outputs, merkle_root, merkle_root_version, fee, owner_memos, tracer_memos),
+            proof,
+            folding_instance,
+        )) = from_bincode::<AXfrNoteV2>(bytes)
+        {
+            let note = AXfrNote {
+                body: AXfrBody {
+                    inputs,
+                    outputs,
+                    merkle_root,
+                    merkle_root_version,
+                    fee,
+                    owner_memos,
+                    tracer_memos,
+                    output_payloads: vec![],
+                },
+                proof,
+                folding_instance,
+            };
+            if note.to_bincode_v2()? == bytes {
+                return Ok(note);
+            }
+        }
+
+        let (
+            (inputs, outputs, merkle_root, merkle_root_version, fee, owner_memos),
+            proof,
+            folding_instance,
+        ) = from_bincode::<AXfrNoteV1>(bytes)?;
+        let note = AXfrNote {
+            body: AXfrBody {
+                inputs,
+                outputs,
+                merkle_root,
+                merkle_root_version,
+                fee,
+                owner_memos,
+                tracer_memos: vec![],
+                output_payloads: vec![],
+            },
+            proof,
+            folding_instance,
+        };
+        if note.to_bincode_v1()? != bytes {
+            return Err(NoahError::DeserializationError);
+        }
+        Ok(note)
+    }
+
+    /// Encode the note in the layout of `AXfrNoteV1`, leaving out the tracer memos and the
+    /// output payloads.
+    fn to_bincode_v1(&self) -> Result<Vec<u8>> {
+        let body = &self.body;
+        to_bincode(&(
+            (
+                &body.inputs,
+                &body.outputs,
+                &body.merkle_root,
+                &body.merkle_root_version,
+                &body.fee,
+                &body.owner_memos,
+            ),
+            &self.proof,
+            &self.folding_instance,
+        ))
+    }
+
+    /// Encode the note in the layout of `AXfrNoteV2`, leaving out the output payloads.
+    fn to_bincode_v2(&self) -> Result<Vec<u8>> {
+        let body = &self.body;
+        to_bincode(&(
+            (
+                &body.inputs,
+                &body.outputs,
+                &body.merkle_root,
+                &body.merkle_root_version,
+                &body.fee,
+                &body.owner_memos,
+                &body.tracer_memos,
+            ),
+            &self.proof,
+            &self.folding_instance,
+        ))
+    }
+}
+
 /// Anonymous transfer pre-note without proofs and signatures.
 #[derive(Debug, Clone)]
 pub struct AXfrPreNote {
@@ -91,6 +249,12 @@ pub struct AXfrBody {
     pub fee: u32,
     /// The owner memos.
     pub owner_memos: Vec<AxfrOwnerMemo>,
+    /// The tracer memos, either empty or one for each output.
+    #[serde(default)]
+    pub tracer_memos: Vec<Option<AXfrTracerMemo>>,
+    /// The encrypted application-defined payloads, either empty or one for each output.
+    #[serde(default)]
+    pub output_payloads: Vec<Option<AxfrOutputPayload>>,
 }
 
 /// Build an anonymous transfer note without generating the proof.
@@ -138,7 +302,17 @@ pub fn init_anon_xfr_note(
         input_commitments_traces.push(commitment_trace);
     });
 
-    // 3. build proof
+    // 3. order the outputs canonically, so that the order chosen by the sender is not revealed
+    let commitments = outputs
+        .iter()
+        .map(|output| AnonAssetRecord::from_oabar(output).commitment)
+        .collect_vec();
+    let outputs = outputs_order(&nullifiers, &commitments)
+        .into_iter()
+        .map(|i| outputs[i].clone())
+        .collect_vec();
+
+    // 4. build proof
     let payers_secrets = inputs
         .iter()
         .map(|input| {
@@ -200,6 +374,8 @@ pub fn init_anon_xfr_note(
         merkle_root_version: mt_info_temp.root_version,
         fee,
         owner_memos: out_memos?,
+        tracer_memos: vec![],
+        output_payloads: vec![],
     };
 
     Ok(AXfrPreNote {
@@ -212,10 +388,90 @@ pub fn init_anon_xfr_note(
     })
 }
 
+/// Attach an application-defined payload, e.g., a deposit tag, to an output of an anonymous
+/// transfer pre-note, encrypted to the owner of the output. The output is given by its record,
+/// e.g., `AnonAssetRecord::from_oabar`, since the pre-note orders the outputs canonically.
+pub fn add_axfr_output_payload<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    pre_note: &mut AXfrPreNote,
+    output: &AnonAssetRecord,
+    payload: &[u8],
+) -> Result<()> {
+    let index = pre_note
+        .body
+        .outputs
+        .iter()
+        .position(|abar| abar == output)
+        .ok_or(NoahError::ParameterError)?;
+    let payee = &pre_note.witness.payees_witnesses[index];
+    let encrypted = AxfrOutputPayload::new(prng, &payee.public_key, payload)?;
+
+    let payloads = &mut pre_note.body.output_payloads;
+    if payloads.is_empty() {
+        payloads.resize(pre_note.body.outputs.len(), None);
+    }
+    payloads[index] = Some(encrypted);
+    Ok(())
+}
+
+/// Return the canonical order of the outputs, as the indices of their commitments.
+///
+/// The commitments are sorted and then shuffled by Fisher-Yates, with randomness derived from
+/// the nullifiers and the sorted commitments, so the order only depends on the set of outputs.
+pub(crate) fn outputs_order(nullifiers: &[Nullifier], commitments: &[Commitment]) -> Vec<usize> {
+    let mut order = (0..commitments.len()).collect_vec();
+    order.sort_by_key(|i| commitments[*i].to_bytes());
+
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(ANON_XFR_OUTPUTS_SHUFFLE_DOMAIN);
+    for nullifier in nullifiers.iter() {
+        hasher.update(nullifier.to_bytes());
+    }
+    for i in order.iter() {
+        hasher.update(commitments[*i].to_bytes());
+    }
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&hasher.finalize());
+    let mut prng = ChaChaRng::from_seed(seed);
+
+    for i in (1..order.len()).rev() {
+        let j = (prng.next_u64() % (i as u64 + 1)) as usize;
+        order.swap(i, j);
+    }
+    order
+}
+
+/// Check that the outputs of an anonymous transfer body are in the canonical order of
+/// `init_anon_xfr_note`.
+///
+/// This is not part of `verify_anon_xfr_note`, which still accepts the notes created before
+/// the outputs were shuffled; ledgers enforce it for new notes, from the height set by
+/// `NoteVerifyContext::check_outputs_order`.
+pub fn check_anon_xfr_outputs_order(body: &AXfrBody) -> Result<()> {
+    let commitments = body
+        .outputs
+        .iter()
+        .map(|output| output.commitment)
+        .collect_vec();
+    let order = outputs_order(&body.inputs, &commitments);
+    if order
+        .iter()
+        .enumerate()
+        .any(|(i, j)| commitments[i] != commitments[*j])
+    {
+        return Err(NoahError::AXfrVerificationError);
+    }
+    Ok(())
+}
+
 /// Build an anonymous transfer note without generating the proof.
-pub fn finish_anon_xfr_note<R: CryptoRng + RngCore, D: Digest<OutputSize = U64> + Default>(
+pub fn finish_anon_xfr_note<
+    R: CryptoRng + RngCore,
+    D: Digest<OutputSize = U64> + Default,
+    PCS: AXfrPCS,
+>(
     prng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     pre_note: AXfrPreNote,
     hash: D,
 ) -> Result<AXfrNote> {
@@ -266,40 +522,77 @@ pub fn finish_anon_xfr_note<R: CryptoRng + RngCore, D: Digest<OutputSize = U64>
     })
 }
 
-/// Verify an anonymous transfer note.
+/// Verify an anonymous transfer note, together with the tracer memos that it carries.
 pub fn verify_anon_xfr_note<D: Digest<OutputSize = U64> + Default>(
     params: &VerifierParams,
     note: &AXfrNote,
     merkle_root: &BN254Scalar,
     hash: D,
 ) -> Result<()> {
-    if *merkle_root != note.body.merkle_root {
-        return Err(NoahError::AXfrVerificationError);
-    }
+    let (pub_inputs, address_folding_public_input) =
+        prepare_anon_xfr_note_verification(note, merkle_root, hash)?;
+
+    verify_xfr(
+        params,
+        &pub_inputs,
+        &note.proof,
+        &address_folding_public_input,
+        note.folding_instance.get_address_format(),
+    )
+}
 
+/// Check the number and the sizes of the memos and the payloads of an anonymous transfer body.
+fn check_anon_xfr_body_sizes(body: &AXfrBody) -> Result<()> {
     // Check the memo size.
-    let max_memo_len = if note.body.inputs.len() == 1 {
+    let max_memo_len = if body.inputs.len() == 1 {
         MAX_ANONYMOUS_RECORD_NUMBER_ONE_INPUT
-    } else if note.body.inputs.len() > 1
-        && note.body.inputs.len() <= MAX_ANONYMOUS_RECORD_NUMBER_STANDARD
-    {
+    } else if body.inputs.len() > 1 && body.inputs.len() <= MAX_ANONYMOUS_RECORD_NUMBER_STANDARD {
         MAX_ANONYMOUS_RECORD_NUMBER_STANDARD
     } else {
         MAX_ANONYMOUS_RECORD_NUMBER_CONSOLIDATION_RECEIVER
     };
 
-    if note.body.owner_memos.len() != note.body.outputs.len()
-        || note.body.owner_memos.len() > max_memo_len
-    {
+    if body.owner_memos.len() != body.outputs.len() || body.owner_memos.len() > max_memo_len {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    for memo in body.owner_memos.iter() {
+        if memo.is_oversized() {
+            return Err(NoahError::AXfrVerificationError);
+        }
+    }
+
+    if !body.tracer_memos.is_empty() && body.tracer_memos.len() != body.outputs.len() {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    if !body.output_payloads.is_empty() && body.output_payloads.len() != body.outputs.len() {
         return Err(NoahError::AXfrVerificationError);
     }
 
-    for memo in note.body.owner_memos.iter() {
-        if memo.size() > MAX_AXFR_MEMO_SIZE {
+    for payload in body.output_payloads.iter().flatten() {
+        if payload.is_oversized() {
             return Err(NoahError::AXfrVerificationError);
         }
     }
 
+    Ok(())
+}
+
+/// Check the anonymous transfer note except for its Plonk proof, and return the public inputs
+/// and the address folding public inputs against which the Plonk proof is to be verified.
+pub(crate) fn prepare_anon_xfr_note_verification<D: Digest<OutputSize = U64> + Default>(
+    note: &AXfrNote,
+    merkle_root: &BN254Scalar,
+    hash: D,
+) -> Result<(AXfrPubInputs, Vec<BN254Scalar>)> {
+    if *merkle_root != note.body.merkle_root {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    check_anon_xfr_body_sizes(&note.body)?;
+    verify_axfr_tracer_memos(&note.body)?;
+
     let payees_commitments = note
         .body
         .outputs
@@ -326,12 +619,7 @@ pub fn verify_anon_xfr_note<D: Digest<OutputSize = U64> + Default>(
         }
     };
 
-    verify_xfr(
-        params,
-        &pub_inputs,
-        &note.proof,
-        &address_folding_public_input,
-    )
+    Ok((pub_inputs, address_folding_public_input))
 }
 
 /// Batch verify the anonymous transfer notes.
@@ -343,77 +631,27 @@ pub fn batch_verify_anon_xfr_note<D: Digest<OutputSize = U64> + Default + Sync +
     merkle_roots: &[&BN254Scalar],
     hashes: Vec<D>,
 ) -> Result<()> {
-    if merkle_roots
-        .par_iter()
-        .zip(notes)
-        .any(|(x, y)| **x != y.body.merkle_root)
+    if params.len() != notes.len()
+        || merkle_roots.len() != notes.len()
+        || hashes.len() != notes.len()
     {
         return Err(NoahError::AXfrVerificationError);
     }
 
-    // Check the memo size.
-    for note in notes.iter() {
-        let max_memo_len = if note.body.inputs.len() == 1 {
-            MAX_ANONYMOUS_RECORD_NUMBER_ONE_INPUT
-        } else if note.body.inputs.len() > 1
-            && note.body.inputs.len() <= MAX_ANONYMOUS_RECORD_NUMBER_STANDARD
-        {
-            MAX_ANONYMOUS_RECORD_NUMBER_STANDARD
-        } else {
-            MAX_ANONYMOUS_RECORD_NUMBER_CONSOLIDATION_RECEIVER
-        };
-
-        if note.body.owner_memos.len() != note.body.outputs.len()
-            || note.body.owner_memos.len() > max_memo_len
-        {
-            return Err(NoahError::AXfrVerificationError);
-        }
-
-        for memo in note.body.owner_memos.iter() {
-            if memo.size() > MAX_AXFR_MEMO_SIZE {
-                return Err(NoahError::AXfrVerificationError);
-            }
-        }
-    }
-
     let is_ok = params
         .par_iter()
         .zip(notes)
         .zip(merkle_roots)
         .zip(hashes)
         .map(|(((param, note), merkle_root), hash)| {
-            let payees_commitments = note
-                .body
-                .outputs
-                .iter()
-                .map(|output| output.commitment)
-                .collect();
-            let pub_inputs = AXfrPubInputs {
-                payers_inputs: note.body.inputs.clone(),
-                payees_commitments,
-                merkle_root: **merkle_root,
-                fee: note.body.fee,
-            };
-
-            let mut transcript = Transcript::new(ANON_XFR_FOLDING_PROOF_TRANSCRIPT);
-
-            let address_folding_public_input = match &note.folding_instance {
-                AXfrAddressFoldingInstance::Secp256k1(a) => {
-                    let (beta, lambda) =
-                        verify_address_folding_secp256k1(hash, &mut transcript, a)?;
-                    prepare_verifier_input_secp256k1(&a, &beta, &lambda)
-                }
-                AXfrAddressFoldingInstance::Ed25519(a) => {
-                    let (beta, lambda) = verify_address_folding_ed25519(hash, &mut transcript, a)?;
-                    prepare_verifier_input_ed25519(&a, &beta, &lambda)
-                }
-            };
-
+            let (pub_inputs, address_folding_public_input) =
+                prepare_anon_xfr_note_verification(note, merkle_root, hash)?;
             verify_xfr(
                 *param,
                 &pub_inputs,
                 &note.proof,
                 &address_folding_public_input,
+                note.folding_instance.get_address_format(),
             )
         })
         .all(|x| x.is_ok());
@@ -426,9 +664,9 @@ pub fn batch_verify_anon_xfr_note<D: Digest<OutputSize = U64> + Default + Sync +
 }
 
 /// Generate a Plonk proof for anonymous transfer.
-pub(crate) fn prove_xfr<R: CryptoRng + RngCore>(
+pub(crate) fn prove_xfr<R: CryptoRng + RngCore, PCS: AXfrPCS>(
     rng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     secret_inputs: &AXfrWitness,
     nullifiers_traces: &[AnemoiVLHTrace<BN254Scalar, 2, 14>],
     input_commitments_traces: &[AnemoiVLHTrace<BN254Scalar, 2, 14>],
@@ -455,6 +693,12 @@ pub(crate) fn prove_xfr<R: CryptoRng + RngCore>(
         folding_witness,
     );
     let witness = cs.get_and_clear_witness();
+    PublicInputsSchema::abar_to_abar(
+        secret_inputs.payers_witnesses.len(),
+        secret_inputs.payees_witnesses.len(),
+        folding_witness.get_address_format(),
+    )
+    .validate_witness(&cs, &witness)?;
 
     Ok(prover_with_lagrange(
         rng,
@@ -473,7 +717,27 @@ pub(crate) fn verify_xfr(
     pub_inputs: &AXfrPubInputs,
     proof: &AXfrPlonkPf,
     address_folding_public_input: &[BN254Scalar],
+    address_format: AddressFormat,
 ) -> Result<()> {
+    let (mut transcript, online_inputs) =
+        xfr_verifier_inputs(pub_inputs, address_folding_public_input, address_format)?;
+
+    Ok(verifier(
+        &mut transcript,
+        &params.shrunk_vk,
+        &params.shrunk_cs,
+        &params.verifier_params,
+        &online_inputs,
+        proof,
+    )?)
+}
+
+/// Prepare the transcript and the online inputs of the verifier of anonymous transfer.
+fn xfr_verifier_inputs(
+    pub_inputs: &AXfrPubInputs,
+    address_folding_public_input: &[BN254Scalar],
+    address_format: AddressFormat,
+) -> Result<(Transcript, Vec<BN254Scalar>)> {
     let mut transcript = Transcript::new(ANON_XFR_PLONK_PROOF_TRANSCRIPT);
     transcript.append_u64(N_INPUTS_TRANSCRIPT, pub_inputs.payers_inputs.len() as u64);
     transcript.append_u64(
@@ -483,15 +747,14 @@ pub(crate) fn verify_xfr(
 
     let mut online_inputs = pub_inputs.to_vec();
     online_inputs.extend_from_slice(address_folding_public_input);
+    PublicInputsSchema::abar_to_abar(
+        pub_inputs.payers_inputs.len(),
+        pub_inputs.payees_commitments.len(),
+        address_format,
+    )
+    .validate(&online_inputs)?;
 
-    Ok(verifier(
-        &mut transcript,
-        &params.shrunk_vk,
-        &params.shrunk_cs,
-        &params.verifier_params,
-        &online_inputs,
-        proof,
-    )?)
+    Ok((transcript, online_inputs))
 }
 
 /// The witness of an anonymous transfer.
@@ -508,6 +771,17 @@ pub struct AXfrWitness {
 impl AXfrWitness {
     /// Create a fake `AXfrWitness` for testing.
     pub fn fake(n_payers: usize, n_payees: usize, fee: u32, address_format: AddressFormat) -> Self {
+        Self::fake_with_depth(n_payers, n_payees, fee, address_format, TREE_DEPTH)
+    }
+
+    /// Create a fake `AXfrWitness` whose Merkle paths have the given depth.
+    pub fn fake_with_depth(
+        n_payers: usize,
+        n_payees: usize,
+        fee: u32,
+        address_format: AddressFormat,
+        tree_depth: usize,
+    ) -> Self {
         let elem_zero = BN254Scalar::zero();
 
         let node = MTNode {
@@ -524,7 +798,7 @@ impl AXfrWitness {
             uid: 0,
             amount: 0,
             asset_type: elem_zero,
-            path: MTPath::new(vec![node; TREE_DEPTH]),
+            path: MTPath::new(vec![node; tree_depth]),
             blind: elem_zero,
         };
 
@@ -1100,10 +1374,13 @@ mod tests {
         verify_address_folding_secp256k1,
     };
     use crate::anon_xfr::{
-        abar_to_abar::{asset_mixing, build_multi_xfr_cs, AXfrPubInputs, AXfrWitness},
+        abar_to_abar::{
+            asset_mixing, build_multi_xfr_cs, check_anon_xfr_outputs_order, outputs_order,
+            AXfrBody, AXfrPubInputs, AXfrWitness,
+        },
         add_merkle_path_variables, check_merkle_tree_validity, commit, commit_in_cs,
         compute_merkle_root_variables, nullify, nullify_in_cs,
-        structs::{AccElemVars, MTNode, MTPath, PayeeWitness, PayerWitness},
+        structs::{AccElemVars, AnonAssetRecord, MTNode, MTPath, PayeeWitness, PayerWitness},
         AXfrAddressFoldingWitness,
     };
     use crate::keys::KeyPair;
@@ -2246,4 +2523,48 @@ mod tests {
             assert!(verify.is_err());
         }
     }
+
+    #[test]
+    fn test_outputs_order() {
+        let mut prng = test_rng();
+        let nullifiers = (0..2).map(|_| BN254Scalar::random(&mut prng)).collect_vec();
+        let commitments = (0..6).map(|_| BN254Scalar::random(&mut prng)).collect_vec();
+
+        // the order is a permutation that only depends on the set of the outputs
+        let order = outputs_order(&nullifiers, &commitments);
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..6).collect_vec());
+        let mut reversed = commitments.clone();
+        reversed.reverse();
+        let canonical = order.iter().map(|i| commitments[*i]).collect_vec();
+        let canonical_from_reversed = outputs_order(&nullifiers, &reversed)
+            .iter()
+            .map(|i| reversed[*i])
+            .collect_vec();
+        assert_eq!(canonical, canonical_from_reversed);
+        assert_ne!(
+            outputs_order(&nullifiers[..1], &canonical),
+            (0..6).collect_vec()
+        );
+
+        let mut body = AXfrBody {
+            inputs: nullifiers,
+            outputs: canonical
+                .iter()
+                .map(|commitment| AnonAssetRecord {
+                    commitment: *commitment,
+                })
+                .collect(),
+            merkle_root: BN254Scalar::zero(),
+            merkle_root_version: 0,
+            fee: 0,
+            owner_memos: vec![],
+            tracer_memos: vec![],
+            output_payloads: vec![],
+        };
+        assert!(check_anon_xfr_outputs_order(&body).is_ok());
+        body.outputs.swap(0, 1);
+        assert!(check_anon_xfr_outputs_order(&body).is_err());
+    }
 }
@@ -14,8 +14,9 @@ use crate::anon_xfr::{
 };
 use crate::errors::{NoahError, Result};
 use crate::keys::{KeyPair, PublicKey, SecretKey};
-use crate::parameters::params::ProverParams;
 use crate::parameters::params::VerifierParams;
+use crate::parameters::params::{AXfrPCS, ProverParams};
+use crate::parameters::schema::PublicInputsSchema;
 use crate::xfr::{
     asset_record::{
         build_open_asset_record, AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
@@ -37,7 +38,7 @@ use noah_plonk::plonk::{
 use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 /// The domain separator for anonymous-to-transparent, for the Plonk proof.
-const ABAR_TO_AR_PLONK_PROOF_TRANSCRIPT: &[u8] = b"ABAR to AR Plonk Proof";
+pub(crate) const ABAR_TO_AR_PLONK_PROOF_TRANSCRIPT: &[u8] = b"ABAR to AR Plonk Proof";
 
 /// The domain separator for anonymous-to-transparent, for address folding.
 const ABAR_TO_AR_FOLDING_PROOF_TRANSCRIPT: &[u8] = b"ABAR to AR Folding Proof";
@@ -151,9 +152,13 @@ pub fn init_abar_to_ar_note<R: CryptoRng + RngCore>(
 }
 
 /// Finalize an anonymous-to-transparent note.
-pub fn finish_abar_to_ar_note<R: CryptoRng + RngCore, D: Digest<OutputSize = U64> + Default>(
+pub fn finish_abar_to_ar_note<
+    R: CryptoRng + RngCore,
+    D: Digest<OutputSize = U64> + Default,
+    PCS: AXfrPCS,
+>(
     prng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     pre_note: AbarToArPreNote,
     hash: D,
 ) -> Result<AbarToArNote> {
@@ -247,6 +252,8 @@ pub fn verify_abar_to_ar_note<D: Digest<OutputSize = U64> + Default>(
         payer_asset_type.as_scalar(),
     ];
     online_inputs.extend_from_slice(&address_folding_public_input);
+    PublicInputsSchema::abar_to_ar(note.folding_instance.get_address_format())
+        .validate(&online_inputs)?;
 
     Ok(verifier(
         &mut transcript,
@@ -318,6 +325,8 @@ pub fn batch_verify_abar_to_ar_note<D: Digest<OutputSize = U64> + Default + Sync
             online_inputs.push(BN254Scalar::from(payer_amount));
             online_inputs.push(payer_asset_type.as_scalar());
             online_inputs.extend_from_slice(&address_folding_public_input);
+            PublicInputsSchema::abar_to_ar(note.folding_instance.get_address_format())
+                .validate(&online_inputs)?;
 
             Ok(verifier(
                 &mut transcript,
@@ -336,9 +345,10 @@ pub fn batch_verify_abar_to_ar_note<D: Digest<OutputSize = U64> + Default + Sync
         Err(NoahError::AXfrVerificationError)
     }
 }
-fn prove_abar_to_ar<R: CryptoRng + RngCore>(
+
+pub(crate) fn prove_abar_to_ar<R: CryptoRng + RngCore, PCS: AXfrPCS>(
     rng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     payers_witness: &PayerWitness,
     nullifier_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
     input_commitment_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
@@ -353,6 +363,8 @@ fn prove_abar_to_ar<R: CryptoRng + RngCore>(
         folding_witness,
     );
     let witness = cs.get_and_clear_witness();
+    PublicInputsSchema::abar_to_ar(folding_witness.get_address_format())
+        .validate_witness(&cs, &witness)?;
 
     Ok(prover_with_lagrange(
         rng,
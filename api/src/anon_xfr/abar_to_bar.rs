@@ -14,8 +14,9 @@ use crate::anon_xfr::{
 };
 use crate::errors::{NoahError, Result};
 use crate::keys::{KeyPair, PublicKey, SecretKey};
-use crate::parameters::params::ProverParams;
 use crate::parameters::params::VerifierParams;
+use crate::parameters::params::{AXfrPCS, ProverParams};
+use crate::parameters::schema::PublicInputsSchema;
 use crate::xfr::structs::check_memo_size;
 use crate::xfr::{
     asset_record::{build_open_asset_record, AssetRecordType},
@@ -37,7 +38,7 @@ use noah_crypto::{
     field_simulation::{SimFr, SimFrParams, SimFrParamsBN254Ristretto},
 };
 use noah_plonk::plonk::{
-    constraint_system::{field_simulation::SimFrVar, TurboCS, VarIndex},
+    constraint_system::{field_simulation::SimFrVar, CircuitBuilder, TurboCS, VarIndex},
     prover::prover_with_lagrange,
     verifier::verifier,
 };
@@ -208,9 +209,13 @@ pub fn init_abar_to_bar_note<R: CryptoRng + RngCore>(
 }
 
 /// Finalize an anonymous-to-confidential note.
-pub fn finish_abar_to_bar_note<R: CryptoRng + RngCore, D: Digest<OutputSize = U64> + Default>(
+pub fn finish_abar_to_bar_note<
+    R: CryptoRng + RngCore,
+    D: Digest<OutputSize = U64> + Default,
+    PCS: AXfrPCS,
+>(
     prng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     pre_note: AbarToBarPreNote,
     hash: D,
 ) -> Result<AbarToBarNote> {
@@ -375,6 +380,8 @@ pub fn verify_abar_to_bar_note<D: Digest<OutputSize = U64> + Default>(
     online_inputs.extend_from_slice(&beta_lambda_sim_fr.limbs);
     online_inputs.extend_from_slice(&s1_plus_lambda_s2_sim_fr.limbs);
     online_inputs.extend_from_slice(&address_folding_public_input);
+    PublicInputsSchema::abar_to_bar(note.folding_instance.get_address_format())
+        .validate(&online_inputs)?;
 
     Ok(verifier(
         &mut transcript,
@@ -513,6 +520,8 @@ pub fn batch_verify_abar_to_bar_note<D: Digest<OutputSize = U64> + Default + Syn
             online_inputs.extend_from_slice(&beta_lambda_sim_fr.limbs);
             online_inputs.extend_from_slice(&s1_plus_lambda_s2_sim_fr.limbs);
             online_inputs.extend_from_slice(&address_folding_public_input);
+            PublicInputsSchema::abar_to_bar(note.folding_instance.get_address_format())
+                .validate(&online_inputs)?;
 
             Ok(verifier(
                 &mut transcript,
@@ -532,9 +541,9 @@ pub fn batch_verify_abar_to_bar_note<D: Digest<OutputSize = U64> + Default + Syn
     }
 }
 
-fn prove_abar_to_bar<R: CryptoRng + RngCore>(
+fn prove_abar_to_bar<R: CryptoRng + RngCore, PCS: AXfrPCS>(
     rng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     payers_witness: &PayerWitness,
     nullifier_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
     input_commitment_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
@@ -557,6 +566,8 @@ fn prove_abar_to_bar<R: CryptoRng + RngCore>(
         folding_witness,
     );
     let witness = cs.get_and_clear_witness();
+    PublicInputsSchema::abar_to_bar(folding_witness.get_address_format())
+        .validate_witness(&cs, &witness)?;
 
     Ok(prover_with_lagrange(
         rng,
@@ -749,10 +760,10 @@ pub fn build_abar_to_bar_cs(
     all_limbs.extend_from_slice(&b_sim_fr.limbs);
 
     let mut all_limbs_var = Vec::with_capacity(4 * SimFrParamsBN254Ristretto::NUM_OF_LIMBS);
-    all_limbs_var.extend_from_slice(&x_sim_fr_var.var);
-    all_limbs_var.extend_from_slice(&y_sim_fr_var.var);
-    all_limbs_var.extend_from_slice(&a_sim_fr_var.var);
-    all_limbs_var.extend_from_slice(&b_sim_fr_var.var);
+    all_limbs_var.extend(x_sim_fr_var.var.iter().map(|wire| cs.var(*wire)));
+    all_limbs_var.extend(y_sim_fr_var.var.iter().map(|wire| cs.var(*wire)));
+    all_limbs_var.extend(a_sim_fr_var.var.iter().map(|wire| cs.var(*wire)));
+    all_limbs_var.extend(b_sim_fr_var.var.iter().map(|wire| cs.var(*wire)));
 
     let mut compressed_limbs = Vec::with_capacity(5);
     let mut compressed_limbs_var = Vec::with_capacity(5);
@@ -840,10 +851,10 @@ pub fn build_abar_to_bar_cs(
     {
         let mut x_in_bls12_381 = cs.linear_combine(
             &[
-                x_sim_fr_var.var[0],
-                x_sim_fr_var.var[1],
-                x_sim_fr_var.var[2],
-                x_sim_fr_var.var[3],
+                cs.var(x_sim_fr_var.var[0]),
+                cs.var(x_sim_fr_var.var[1]),
+                cs.var(x_sim_fr_var.var[2]),
+                cs.var(x_sim_fr_var.var[3]),
             ],
             one,
             step_1,
@@ -853,8 +864,8 @@ pub fn build_abar_to_bar_cs(
         x_in_bls12_381 = cs.linear_combine(
             &[
                 x_in_bls12_381,
-                x_sim_fr_var.var[4],
-                x_sim_fr_var.var[5],
+                cs.var(x_sim_fr_var.var[4]),
+                cs.var(x_sim_fr_var.var[5]),
                 zero_var,
             ],
             one,
@@ -865,10 +876,10 @@ pub fn build_abar_to_bar_cs(
 
         let mut y_in_bls12_381 = cs.linear_combine(
             &[
-                y_sim_fr_var.var[0],
-                y_sim_fr_var.var[1],
-                y_sim_fr_var.var[2],
-                y_sim_fr_var.var[3],
+                cs.var(y_sim_fr_var.var[0]),
+                cs.var(y_sim_fr_var.var[1]),
+                cs.var(y_sim_fr_var.var[2]),
+                cs.var(y_sim_fr_var.var[3]),
             ],
             one,
             step_1,
@@ -878,8 +889,8 @@ pub fn build_abar_to_bar_cs(
         y_in_bls12_381 = cs.linear_combine(
             &[
                 y_in_bls12_381,
-                y_sim_fr_var.var[4],
-                y_sim_fr_var.var[5],
+                cs.var(y_sim_fr_var.var[4]),
+                cs.var(y_sim_fr_var.var[5]),
                 zero_var,
             ],
             one,
@@ -899,16 +910,20 @@ pub fn build_abar_to_bar_cs(
     cs.prepare_pi_variable(comm_var);
 
     for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(beta_sim_fr_var.var[i]);
+        let var = cs.var(beta_sim_fr_var.var[i]);
+        cs.prepare_pi_variable(var);
     }
     for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(lambda_sim_fr_var.var[i]);
+        let var = cs.var(lambda_sim_fr_var.var[i]);
+        cs.prepare_pi_variable(var);
     }
     for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(beta_lambda_sim_fr_var.var[i]);
+        let var = cs.var(beta_lambda_sim_fr_var.var[i]);
+        cs.prepare_pi_variable(var);
     }
     for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(s1_plus_lambda_s2_sim_fr_var.var[i]);
+        let var = cs.var(s1_plus_lambda_s2_sim_fr_var.var[i]);
+        cs.prepare_pi_variable(var);
     }
 
     match folding_witness {
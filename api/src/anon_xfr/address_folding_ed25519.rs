@@ -18,7 +18,7 @@ use noah_crypto::field_simulation::{
     SimFr, SimFrParams, SimFrParamsBN254Secq256k1, SimFrParamsBN254Zorro,
 };
 use noah_plonk::plonk::constraint_system::field_simulation::SimFrVar;
-use noah_plonk::plonk::constraint_system::VarIndex;
+use noah_plonk::plonk::constraint_system::{CircuitBuilder, VarIndex};
 use num_bigint::BigUint;
 use rand_core::{CryptoRng, RngCore};
 
@@ -463,13 +463,13 @@ pub fn prove_address_folding_in_cs_ed25519(
     // append all the data
     for (v, _) in query_vars.iter() {
         all_limbs.extend_from_slice(&v.val.limbs);
-        all_limbs_var.extend_from_slice(&v.var);
+        all_limbs_var.extend(v.var.iter().map(|wire| cs.var(*wire)));
     }
 
     // append all the corresponding randomizers
     for (_, v) in query_vars.iter() {
         all_limbs.extend_from_slice(&v.val.limbs);
-        all_limbs_var.extend_from_slice(&v.var);
+        all_limbs_var.extend(v.var.iter().map(|wire| cs.var(*wire)));
     }
 
     let mut compressed_limbs = Vec::new();
@@ -546,18 +546,21 @@ pub fn prove_address_folding_in_cs_ed25519(
 
     for fr_var in lambda_series_vars_skip_first.iter() {
         for i in 0..SimFrParamsBN254Zorro::NUM_OF_LIMBS {
-            cs.prepare_pi_variable(fr_var.var[i]);
+            let var = cs.var(fr_var.var[i]);
+            cs.prepare_pi_variable(var);
         }
     }
 
     for fr_var in beta_lambda_series_vars.iter() {
         for i in 0..SimFrParamsBN254Zorro::NUM_OF_LIMBS {
-            cs.prepare_pi_variable(fr_var.var[i]);
+            let var = cs.var(fr_var.var[i]);
+            cs.prepare_pi_variable(var);
         }
     }
 
     for i in 0..SimFrParamsBN254Zorro::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(combined_response_scalar_var.var[i]);
+        let var = cs.var(combined_response_scalar_var.var[i]);
+        cs.prepare_pi_variable(var);
     }
 
     Ok(())
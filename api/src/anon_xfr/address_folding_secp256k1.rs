@@ -18,7 +18,7 @@ use noah_crypto::delegated_schnorr::{
 };
 use noah_crypto::field_simulation::{SimFr, SimFrParams, SimFrParamsBN254Secq256k1};
 use noah_plonk::plonk::constraint_system::field_simulation::SimFrVar;
-use noah_plonk::plonk::constraint_system::VarIndex;
+use noah_plonk::plonk::constraint_system::{CircuitBuilder, VarIndex};
 use num_bigint::BigUint;
 use rand_core::{CryptoRng, RngCore};
 
@@ -470,13 +470,13 @@ pub fn prove_address_folding_in_cs_secp256k1(
     // append all the data
     for (v, _) in query_vars.iter() {
         all_limbs.extend_from_slice(&v.val.limbs);
-        all_limbs_var.extend_from_slice(&v.var);
+        all_limbs_var.extend(v.var.iter().map(|wire| cs.var(*wire)));
     }
 
     // append all the corresponding randomizers
     for (_, v) in query_vars.iter() {
         all_limbs.extend_from_slice(&v.val.limbs);
-        all_limbs_var.extend_from_slice(&v.var);
+        all_limbs_var.extend(v.var.iter().map(|wire| cs.var(*wire)));
     }
 
     let mut compressed_limbs = Vec::new();
@@ -555,18 +555,21 @@ pub fn prove_address_folding_in_cs_secp256k1(
 
     for fr_var in lambda_series_vars_skip_first.iter() {
         for i in 0..SimFrParamsBN254Secq256k1::NUM_OF_LIMBS {
-            cs.prepare_pi_variable(fr_var.var[i]);
+            let var = cs.var(fr_var.var[i]);
+            cs.prepare_pi_variable(var);
         }
     }
 
     for fr_var in beta_lambda_series_vars.iter() {
         for i in 0..SimFrParamsBN254Secq256k1::NUM_OF_LIMBS {
-            cs.prepare_pi_variable(fr_var.var[i]);
+            let var = cs.var(fr_var.var[i]);
+            cs.prepare_pi_variable(var);
         }
     }
 
     for i in 0..SimFrParamsBN254Secq256k1::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(combined_response_scalar_var.var[i]);
+        let var = cs.var(combined_response_scalar_var.var[i]);
+        cs.prepare_pi_variable(var);
     }
 
     Ok(())
@@ -1,15 +1,17 @@
 use crate::anon_xfr::{
     commit, commit_in_cs,
     structs::{
-        AnonAssetRecord, AxfrOwnerMemo, OpenAnonAssetRecordBuilder, PayeeWitness, PayeeWitnessVars,
+        AnonAssetRecord, AxfrOwnerMemo, Commitment, OpenAnonAssetRecord,
+        OpenAnonAssetRecordBuilder, PayeeWitness, PayeeWitnessVars,
     },
-    AXfrPlonkPf, TurboPlonkCS, MAX_AXFR_MEMO_SIZE,
+    AXfrPlonkPf, TurboPlonkCS,
 };
 use crate::errors::{NoahError, Result};
-use crate::keys::{KeyPair, PublicKey, PublicKeyInner, Signature};
-use crate::parameters::params::ProverParams;
+use crate::keys::{KeyPair, PublicKey, PublicKeyInner, SignableBody, Signature};
 use crate::parameters::params::VerifierParams;
-use crate::xfr::structs::{BlindAssetRecord, OpenAssetRecord};
+use crate::parameters::params::{AXfrPCS, ProverParams};
+use crate::parameters::schema::PublicInputsSchema;
+use crate::xfr::structs::{AssetType, BlindAssetRecord, OpenAssetRecord};
 use merlin::Transcript;
 use noah_algebra::{bn254::BN254Scalar, prelude::*};
 use noah_crypto::anemoi_jive::{AnemoiJive254, AnemoiVLHTrace};
@@ -44,10 +46,12 @@ pub struct ArToAbarBody {
     pub memo: AxfrOwnerMemo,
 }
 
+impl SignableBody for ArToAbarBody {}
+
 /// Generate a transparent-to-anonymous note.
-pub fn gen_ar_to_abar_note<R: CryptoRng + RngCore>(
+pub fn gen_ar_to_abar_note<R: CryptoRng + RngCore, PCS: AXfrPCS>(
     prng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     record: &OpenAssetRecord,
     bar_keypair: &KeyPair,
     abar_pubkey: &PublicKey,
@@ -55,8 +59,7 @@ pub fn gen_ar_to_abar_note<R: CryptoRng + RngCore>(
     // generate body
     let body = gen_ar_to_abar_body(prng, params, record, abar_pubkey)?;
 
-    let msg = bincode::serialize(&body).map_err(|_| NoahError::SerializationError)?;
-    let signature = bar_keypair.sign(&msg)?;
+    let signature = bar_keypair.sign(&body.signing_payload()?)?;
 
     let note = ArToAbarNote { body, signature };
     Ok(note)
@@ -64,15 +67,30 @@ pub fn gen_ar_to_abar_note<R: CryptoRng + RngCore>(
 
 /// Verify a transparent-to-anonymous note.
 pub fn verify_ar_to_abar_note(params: &VerifierParams, note: &ArToAbarNote) -> Result<()> {
+    verify_ar_to_abar_note_with(params, note, false)
+}
+
+/// Verify a transparent-to-anonymous note. With `full_evaluation`, the proof is verified
+/// even if the signature is invalid, and the first error is returned.
+pub(crate) fn verify_ar_to_abar_note_with(
+    params: &VerifierParams,
+    note: &ArToAbarNote,
+    full_evaluation: bool,
+) -> Result<()> {
     // Check the memo size.
-    if note.body.memo.size() > MAX_AXFR_MEMO_SIZE {
+    if note.body.memo.is_oversized() {
         return Err(NoahError::AXfrVerificationError);
     }
 
-    let msg = bincode::serialize(&note.body).map_err(|_| NoahError::SerializationError)?;
-    note.body.input.public_key.verify(&msg, &note.signature)?;
+    let signature_res = note
+        .body
+        .verify_signature(&note.body.input.public_key, &note.signature);
+    if !full_evaluation && signature_res.is_err() {
+        return signature_res;
+    }
 
-    verify_ar_to_abar_body(params, &note.body)
+    let body_res = verify_ar_to_abar_body(params, &note.body);
+    signature_res.and(body_res)
 }
 
 /// Batch verify the transparent-to-anonymous notes.
@@ -83,7 +101,7 @@ pub fn batch_verify_ar_to_abar_note(
 ) -> Result<()> {
     // Check the memo size.
     for note in notes.iter() {
-        if note.body.memo.size() > MAX_AXFR_MEMO_SIZE {
+        if note.body.memo.is_oversized() {
             return Err(NoahError::AXfrVerificationError);
         }
     }
@@ -91,8 +109,8 @@ pub fn batch_verify_ar_to_abar_note(
     let is_ok = notes
         .par_iter()
         .map(|note| {
-            let msg = bincode::serialize(&note.body).map_err(|_| NoahError::SerializationError)?;
-            note.body.input.public_key.verify(&msg, &note.signature)?;
+            note.body
+                .verify_signature(&note.body.input.public_key, &note.signature)?;
 
             verify_ar_to_abar_body(params, &note.body)
         })
@@ -106,9 +124,9 @@ pub fn batch_verify_ar_to_abar_note(
 }
 
 /// Generate the transparent-to-anonymous body.
-pub fn gen_ar_to_abar_body<R: CryptoRng + RngCore>(
+pub fn gen_ar_to_abar_body<R: CryptoRng + RngCore, PCS: AXfrPCS>(
     prng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     obar: &OpenAssetRecord,
     abar_pubkey: &PublicKey,
 ) -> Result<ArToAbarBody> {
@@ -122,26 +140,43 @@ pub fn gen_ar_to_abar_body<R: CryptoRng + RngCore>(
         .finalize(prng)?
         .build()?;
 
+    let proof = prove_ar_to_abar(prng, params, &oabar)?;
+
+    let body = ArToAbarBody {
+        input: obar.blind_asset_record.clone(),
+        output: AnonAssetRecord::from_oabar(&oabar),
+        proof,
+        memo: oabar.owner_memo.unwrap(),
+    };
+    Ok(body)
+}
+
+/// Prove that the commitment of the ABAR opens to its amount and asset type.
+pub(crate) fn prove_ar_to_abar<R: CryptoRng + RngCore, PCS: AXfrPCS>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    oabar: &OpenAnonAssetRecord,
+) -> Result<AXfrPlonkPf> {
     let payee_witness = PayeeWitness {
         amount: oabar.get_amount(),
         blind: oabar.blind,
         asset_type: oabar.asset_type.as_scalar(),
-        public_key: *abar_pubkey,
+        public_key: oabar.pub_key,
     };
 
     let (_, output_trace) = commit(
-        abar_pubkey,
+        &oabar.pub_key,
         oabar.blind,
         oabar.amount,
         oabar.asset_type.as_scalar(),
-    )
-    .unwrap();
+    )?;
 
     let mut transcript = Transcript::new(AR_TO_ABAR_PLONK_PROOF_TRANSCRIPT);
     let (mut cs, _) = build_ar_to_abar_cs(payee_witness, &output_trace);
     let witness = cs.get_and_clear_witness();
+    PublicInputsSchema::ar_to_abar().validate_witness(&cs, &witness)?;
 
-    let proof = prover_with_lagrange(
+    Ok(prover_with_lagrange(
         prng,
         &mut transcript,
         &params.pcs,
@@ -149,15 +184,7 @@ pub fn gen_ar_to_abar_body<R: CryptoRng + RngCore>(
         &params.cs,
         &params.prover_params,
         &witness,
-    )?;
-
-    let body = ArToAbarBody {
-        input: obar.blind_asset_record.clone(),
-        output: AnonAssetRecord::from_oabar(&oabar),
-        proof,
-        memo: oabar.owner_memo.unwrap(),
-    };
-    Ok(body)
+    )?)
 }
 
 /// Verify the transparent-to-anonymous body.
@@ -169,12 +196,30 @@ pub fn verify_ar_to_abar_body(params: &VerifierParams, body: &ArToAbarBody) -> R
     let amount = body.input.amount.get_amount().unwrap();
     let asset_type = body.input.asset_type.get_asset_type().unwrap();
 
+    verify_ar_to_abar_proof(
+        params,
+        amount,
+        asset_type,
+        &body.output.commitment,
+        &body.proof,
+    )
+}
+
+/// Verify the proof that the commitment opens to the amount and the asset type.
+pub(crate) fn verify_ar_to_abar_proof(
+    params: &VerifierParams,
+    amount: u64,
+    asset_type: AssetType,
+    commitment: &Commitment,
+    proof: &AXfrPlonkPf,
+) -> Result<()> {
     let mut transcript = Transcript::new(AR_TO_ABAR_PLONK_PROOF_TRANSCRIPT);
     let online_inputs: Vec<BN254Scalar> = vec![
         BN254Scalar::from(amount),
         asset_type.as_scalar(),
-        body.output.commitment,
+        *commitment,
     ];
+    PublicInputsSchema::ar_to_abar().validate(&online_inputs)?;
 
     Ok(verifier(
         &mut transcript,
@@ -182,7 +227,7 @@ pub fn verify_ar_to_abar_body(params: &VerifierParams, body: &ArToAbarBody) -> R
         &params.shrunk_cs,
         &params.verifier_params,
         &online_inputs,
-        &body.proof,
+        proof,
     )?)
 }
 
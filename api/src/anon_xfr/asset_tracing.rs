@@ -0,0 +1,542 @@
+use crate::anon_xfr::{
+    abar_to_abar::{AXfrBody, AXfrPreNote},
+    commit, commit_in_cs,
+    structs::{AnonAssetRecord, PayeeWitness},
+    AXfrPlonkPf, TurboPlonkCS,
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::{PublicKey, PublicKeyInner};
+use crate::parameters::params::{AXfrPCS, ProverParams, VerifierParams};
+use crate::parameters::schema::PublicInputsSchema;
+use crate::xfr::structs::AssetType;
+use merlin::Transcript;
+use noah_algebra::{
+    baby_jubjub::{BabyJubjubPoint, BabyJubjubScalar},
+    bn254::BN254Scalar,
+    prelude::*,
+};
+use noah_crypto::anemoi_jive::{
+    AnemoiJive, AnemoiJive254, AnemoiStreamCipherTrace, AnemoiVLHTrace,
+};
+use noah_crypto::doubly_snark_friendly::ecies_encryption::{ECIESCiphertext, ECIESDecryptionKey};
+use noah_plonk::plonk::{
+    constraint_system::TurboCS, prover::prover_with_lagrange, verifier::verifier,
+};
+
+/// The domain separator for asset tracing of anonymous records, for the Plonk proof.
+const ABAR_TRACING_PLONK_PROOF_TRANSCRIPT: &[u8] = b"ABAR Tracing Plonk Proof";
+
+/// The number of bits used to represent the BabyJubjub randomizer in the circuit.
+const BABY_JUBJUB_SCALAR_BITS: usize = 252;
+
+/// The number of field elements in a tracer memo:
+/// amount, asset type, public key type, and the three public key scalars.
+pub const AXFR_TRACER_MEMO_LEN: usize = 6;
+
+/// The encryption key of an asset tracer for anonymous transfers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AXfrTracerEncKey(pub BabyJubjubPoint);
+
+/// The decryption key of an asset tracer for anonymous transfers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AXfrTracerDecKey(pub BabyJubjubScalar);
+
+/// The key pair of an asset tracer for anonymous transfers.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AXfrTracerKeyPair {
+    /// The encryption key.
+    pub enc_key: AXfrTracerEncKey,
+    /// The decryption key.
+    pub dec_key: AXfrTracerDecKey,
+}
+
+impl AXfrTracerKeyPair {
+    /// Sample a new tracer key pair.
+    pub fn generate<R: CryptoRng + RngCore>(prng: &mut R) -> Self {
+        let dec_key = BabyJubjubScalar::random(prng);
+        let enc_key = BabyJubjubPoint::get_base().mul(&dec_key);
+        Self {
+            enc_key: AXfrTracerEncKey(enc_key),
+            dec_key: AXfrTracerDecKey(dec_key),
+        }
+    }
+}
+
+/// The tracer memo of an anonymous output, which encrypts the amount, the asset type,
+/// and the owner of the output to the tracer, together with a proof that
+/// the encrypted values are the ones hidden in the output commitment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AXfrTracerMemo {
+    /// The encryption key of the tracer.
+    pub enc_key: AXfrTracerEncKey,
+    /// The Diffie-Hellman key exchange point divided by the cofactor.
+    pub dh_point_div_by_cofactor: BabyJubjubPoint,
+    /// The ciphertext.
+    pub ciphertext: Vec<BN254Scalar>,
+    /// The proof that the ciphertext matches the output commitment.
+    pub proof: AXfrPlonkPf,
+}
+
+/// The information of an anonymous output recovered by the tracer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AXfrTracedRecord {
+    /// The amount.
+    pub amount: u64,
+    /// The asset type, in its scalar representation.
+    pub asset_type: BN254Scalar,
+    /// The type of the public key, `0` for secp256k1 and `1` for ed25519.
+    pub public_key_type: BN254Scalar,
+    /// The public key, in its scalar representation.
+    pub public_key_scalars: [BN254Scalar; 3],
+}
+
+impl AXfrTracedRecord {
+    /// Check if the traced record has the given asset type.
+    pub fn has_asset_type(&self, asset_type: &AssetType) -> bool {
+        self.asset_type == asset_type.as_scalar()
+    }
+
+    /// Check if the traced record is owned by the given public key.
+    pub fn is_owned_by(&self, public_key: &PublicKey) -> bool {
+        match (
+            public_key_type_scalar(public_key),
+            public_key.to_bn_scalars(),
+        ) {
+            (Ok(public_key_type), Ok(public_key_scalars)) => {
+                self.public_key_type == public_key_type
+                    && self.public_key_scalars == public_key_scalars
+            }
+            _ => false,
+        }
+    }
+}
+
+impl AXfrTracerMemo {
+    /// Decrypt the tracer memo with the decryption key of the tracer.
+    pub fn decrypt(&self, dec_key: &AXfrTracerDecKey) -> Result<AXfrTracedRecord> {
+        if self.ciphertext.len() != AXFR_TRACER_MEMO_LEN {
+            return Err(NoahError::DecryptionError);
+        }
+
+        let ciphertext = ECIESCiphertext::<BabyJubjubPoint> {
+            dh_point_div_by_cofactor: self.dh_point_div_by_cofactor,
+            ciphertext: self.ciphertext.clone(),
+        };
+        let plaintext = ECIESDecryptionKey::<BabyJubjubPoint>::from_raw(dec_key.0)
+            .decrypt::<AnemoiJive254>(&ciphertext)
+            .0;
+
+        let amount_limbs = plaintext[0].get_little_endian_u64();
+        if amount_limbs.iter().skip(1).any(|limb| *limb != 0) {
+            return Err(NoahError::DecryptionError);
+        }
+
+        Ok(AXfrTracedRecord {
+            amount: amount_limbs[0],
+            asset_type: plaintext[1],
+            public_key_type: plaintext[2],
+            public_key_scalars: [plaintext[3], plaintext[4], plaintext[5]],
+        })
+    }
+}
+
+/// Return the scalar representing the type of the public key.
+pub(crate) fn public_key_type_scalar(public_key: &PublicKey) -> Result<BN254Scalar> {
+    match public_key.0 {
+        PublicKeyInner::Ed25519(_) => Ok(BN254Scalar::one()),
+        PublicKeyInner::Secp256k1(_) => Ok(BN254Scalar::zero()),
+        PublicKeyInner::EthAddress(_) => Err(NoahError::ParameterError),
+    }
+}
+
+/// Compute the traces of the output commitment and of the stream cipher for the tracer memo.
+pub(crate) fn gen_abar_tracing_traces(
+    payee: &PayeeWitness,
+    enc_key: &AXfrTracerEncKey,
+    randomizer: &BabyJubjubScalar,
+) -> Result<(
+    AnemoiVLHTrace<BN254Scalar, 2, 14>,
+    AnemoiStreamCipherTrace<BN254Scalar, 2, 14>,
+)> {
+    let (_, commitment_trace) = commit(
+        &payee.public_key,
+        payee.blind,
+        payee.amount,
+        payee.asset_type,
+    )?;
+
+    let shared_point = enc_key.0.mul(randomizer);
+    let cipher_trace = AnemoiJive254::eval_stream_cipher_with_trace(
+        &[
+            BN254Scalar::zero(),
+            shared_point.get_x(),
+            shared_point.get_y(),
+        ],
+        AXFR_TRACER_MEMO_LEN,
+    );
+
+    Ok((commitment_trace, cipher_trace))
+}
+
+/// Generate the tracer memo for an anonymous output.
+pub fn gen_axfr_tracer_memo<R: CryptoRng + RngCore, PCS: AXfrPCS>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    payee: &PayeeWitness,
+    enc_key: &AXfrTracerEncKey,
+) -> Result<AXfrTracerMemo> {
+    let public_key_type = public_key_type_scalar(&payee.public_key)?;
+    let public_key_scalars = payee.public_key.to_bn_scalars()?;
+    let plaintext = [
+        BN254Scalar::from(payee.amount),
+        payee.asset_type,
+        public_key_type,
+        public_key_scalars[0],
+        public_key_scalars[1],
+        public_key_scalars[2],
+    ];
+
+    let randomizer = BabyJubjubScalar::random(prng);
+    let dh_point_div_by_cofactor = BabyJubjubPoint::get_point_div_by_cofactor().mul(&randomizer);
+    let (commitment_trace, cipher_trace) = gen_abar_tracing_traces(payee, enc_key, &randomizer)?;
+
+    let ciphertext = plaintext
+        .iter()
+        .zip(cipher_trace.output.iter())
+        .map(|(p, m)| *p + m)
+        .collect();
+
+    let mut transcript = Transcript::new(ABAR_TRACING_PLONK_PROOF_TRANSCRIPT);
+    let (mut cs, _) = build_abar_tracing_cs(
+        payee,
+        enc_key,
+        &randomizer,
+        &commitment_trace,
+        &cipher_trace,
+    )?;
+    let witness = cs.get_and_clear_witness();
+    PublicInputsSchema::abar_tracing().validate_witness(&cs, &witness)?;
+
+    let proof = prover_with_lagrange(
+        prng,
+        &mut transcript,
+        &params.pcs,
+        params.lagrange_pcs.as_ref(),
+        &params.cs,
+        &params.prover_params,
+        &witness,
+    )?;
+
+    Ok(AXfrTracerMemo {
+        enc_key: *enc_key,
+        dh_point_div_by_cofactor,
+        ciphertext,
+        proof,
+    })
+}
+
+/// Verify that the tracer memo matches the anonymous output.
+pub fn verify_axfr_tracer_memo(
+    params: &VerifierParams,
+    output: &AnonAssetRecord,
+    memo: &AXfrTracerMemo,
+) -> Result<()> {
+    if memo.ciphertext.len() != AXFR_TRACER_MEMO_LEN {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    let mut transcript = Transcript::new(ABAR_TRACING_PLONK_PROOF_TRANSCRIPT);
+    let mut online_inputs = vec![
+        output.commitment,
+        memo.enc_key.0.get_x(),
+        memo.enc_key.0.get_y(),
+        memo.dh_point_div_by_cofactor.get_x(),
+        memo.dh_point_div_by_cofactor.get_y(),
+    ];
+    online_inputs.extend_from_slice(&memo.ciphertext);
+    PublicInputsSchema::abar_tracing().validate(&online_inputs)?;
+
+    Ok(verifier(
+        &mut transcript,
+        &params.shrunk_vk,
+        &params.shrunk_cs,
+        &params.verifier_params,
+        &online_inputs,
+        &memo.proof,
+    )?)
+}
+
+/// Attach a tracer memo for each output of an anonymous transfer pre-note.
+pub fn add_axfr_tracer_memos<R: CryptoRng + RngCore, PCS: AXfrPCS>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    pre_note: &mut AXfrPreNote,
+    enc_key: &AXfrTracerEncKey,
+) -> Result<()> {
+    let mut tracer_memos = Vec::with_capacity(pre_note.witness.payees_witnesses.len());
+    for payee in pre_note.witness.payees_witnesses.iter() {
+        tracer_memos.push(Some(gen_axfr_tracer_memo(prng, params, payee, enc_key)?));
+    }
+    pre_note.body.tracer_memos = tracer_memos;
+    Ok(())
+}
+
+/// Verify that every output of an anonymous transfer body is traceable by the given tracer.
+pub fn verify_axfr_tracing(
+    params: &VerifierParams,
+    body: &AXfrBody,
+    enc_key: &AXfrTracerEncKey,
+) -> Result<()> {
+    if body.tracer_memos.len() != body.outputs.len() {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    for (output, memo) in body.outputs.iter().zip(body.tracer_memos.iter()) {
+        match memo {
+            Some(memo) if memo.enc_key == *enc_key => {
+                verify_axfr_tracer_memo(params, output, memo)?
+            }
+            _ => return Err(NoahError::AXfrVerificationError),
+        }
+    }
+    Ok(())
+}
+
+/// Verify the tracer memos that an anonymous transfer body carries, each against its output.
+/// The body is not required to carry any; `verify_axfr_tracing` checks that a given tracer
+/// can trace every output.
+pub(crate) fn verify_axfr_tracer_memos(body: &AXfrBody) -> Result<()> {
+    if body.tracer_memos.iter().all(Option::is_none) {
+        return Ok(());
+    }
+    if body.tracer_memos.len() != body.outputs.len() {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    let params = VerifierParams::get_abar_tracing()?;
+    for (output, memo) in body.outputs.iter().zip(body.tracer_memos.iter()) {
+        if let Some(memo) = memo {
+            verify_axfr_tracer_memo(params, output, memo)?;
+        }
+    }
+    Ok(())
+}
+
+/// Construct the constraint system proving that the tracer memo encrypts
+/// the amount, the asset type, and the public key in the output commitment.
+pub(crate) fn build_abar_tracing_cs(
+    payee: &PayeeWitness,
+    enc_key: &AXfrTracerEncKey,
+    randomizer: &BabyJubjubScalar,
+    commitment_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
+    cipher_trace: &AnemoiStreamCipherTrace<BN254Scalar, 2, 14>,
+) -> Result<(TurboPlonkCS, usize)> {
+    let mut cs = TurboCS::new();
+    cs.load_anemoi_jive_parameters::<AnemoiJive254>();
+
+    let amount_var = cs.new_variable(BN254Scalar::from(payee.amount));
+    let asset_type_var = cs.new_variable(payee.asset_type);
+    let blind_var = cs.new_variable(payee.blind);
+
+    let public_key_scalars = payee.public_key.to_bn_scalars()?;
+    let public_key_scalars_vars = [
+        cs.new_variable(public_key_scalars[0]),
+        cs.new_variable(public_key_scalars[1]),
+        cs.new_variable(public_key_scalars[2]),
+    ];
+
+    let public_key_type_var = match payee.public_key.0 {
+        PublicKeyInner::Ed25519(_) => cs.new_variable(BN254Scalar::one()),
+        PublicKeyInner::Secp256k1(_) => cs.new_variable(BN254Scalar::zero()),
+        PublicKeyInner::EthAddress(_) => return Err(NoahError::ParameterError),
+    };
+    cs.insert_boolean_gate(public_key_type_var);
+
+    // prepare the public input for the output commitment
+    let commitment_var = commit_in_cs(
+        &mut cs,
+        blind_var,
+        amount_var,
+        asset_type_var,
+        public_key_type_var,
+        &public_key_scalars_vars,
+        commitment_trace,
+    );
+    cs.prepare_pi_variable(commitment_var);
+
+    // prepare the public input for the encryption key of the tracer
+    let enc_key_var = cs.new_point_variable(enc_key.0);
+    cs.prepare_pi_variable(enc_key_var.get_x());
+    cs.prepare_pi_variable(enc_key_var.get_y());
+
+    // prepare the public input for the Diffie-Hellman point
+    let randomizer_var = cs.new_variable(BN254Scalar::from_bytes(&randomizer.to_bytes()).unwrap());
+    let dh_point_var = cs.const_base_scalar_mul(
+        BabyJubjubPoint::get_point_div_by_cofactor(),
+        randomizer_var,
+        BABY_JUBJUB_SCALAR_BITS,
+    );
+    cs.prepare_pi_variable(dh_point_var.get_x());
+    cs.prepare_pi_variable(dh_point_var.get_y());
+
+    // derive the mask from the shared point
+    let shared_point_var = cs.nonconst_base_scalar_mul(
+        enc_key_var,
+        enc_key.0,
+        randomizer_var,
+        BABY_JUBJUB_SCALAR_BITS,
+    );
+    let zero_var = cs.zero_var();
+    let mask_vars = cipher_trace
+        .output
+        .iter()
+        .map(|m| cs.new_variable(*m))
+        .collect::<Vec<_>>();
+    cs.anemoi_stream_cipher::<AnemoiJive254>(
+        cipher_trace,
+        &[zero_var, shared_point_var.get_x(), shared_point_var.get_y()],
+        &mask_vars,
+    );
+
+    // prepare the public input for the ciphertext
+    let plaintext_vars = [
+        amount_var,
+        asset_type_var,
+        public_key_type_var,
+        public_key_scalars_vars[0],
+        public_key_scalars_vars[1],
+        public_key_scalars_vars[2],
+    ];
+    for (plaintext_var, mask_var) in plaintext_vars.iter().zip(mask_vars.iter()) {
+        let ciphertext_var = cs.add(*plaintext_var, *mask_var);
+        cs.prepare_pi_variable(ciphertext_var);
+    }
+
+    // pad the number of constraints to power of two
+    cs.pad();
+
+    let n_constraints = cs.size;
+    Ok((cs, n_constraints))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::anon_xfr::{
+        asset_tracing::{
+            build_abar_tracing_cs, gen_abar_tracing_traces, gen_axfr_tracer_memo,
+            verify_axfr_tracer_memo, AXfrTracerKeyPair,
+        },
+        structs::{AnonAssetRecord, OpenAnonAssetRecordBuilder, PayeeWitness},
+    };
+    use crate::keys::KeyPair;
+    use crate::parameters::params::{ProverParams, VerifierParams};
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::xfr::structs::{AssetType, ASSET_TYPE_LENGTH};
+    use noah_algebra::{
+        baby_jubjub::{BabyJubjubPoint, BabyJubjubScalar},
+        bn254::BN254Scalar,
+        prelude::*,
+    };
+
+    #[test]
+    fn test_abar_tracing_cs() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+        let tracer_keypair = AXfrTracerKeyPair::generate(&mut prng);
+        let randomizer = BabyJubjubScalar::random(&mut prng);
+
+        let payee = PayeeWitness {
+            amount: 1234,
+            blind: BN254Scalar::random(&mut prng),
+            asset_type: AssetType([5u8; ASSET_TYPE_LENGTH]).as_scalar(),
+            public_key: keypair.get_pk(),
+        };
+        let (commitment_trace, cipher_trace) =
+            gen_abar_tracing_traces(&payee, &tracer_keypair.enc_key, &randomizer).unwrap();
+        let (mut cs, _) = build_abar_tracing_cs(
+            &payee,
+            &tracer_keypair.enc_key,
+            &randomizer,
+            &commitment_trace,
+            &cipher_trace,
+        )
+        .unwrap();
+        let witness = cs.get_and_clear_witness();
+
+        let mut online_inputs = vec![commitment_trace.output];
+        online_inputs.push(tracer_keypair.enc_key.0.get_x());
+        online_inputs.push(tracer_keypair.enc_key.0.get_y());
+        let dh_point = BabyJubjubPoint::get_point_div_by_cofactor().mul(&randomizer);
+        online_inputs.push(dh_point.get_x());
+        online_inputs.push(dh_point.get_y());
+        let public_key_scalars = payee.public_key.to_bn_scalars().unwrap();
+        let plaintext = [
+            BN254Scalar::from(payee.amount),
+            payee.asset_type,
+            BN254Scalar::zero(),
+            public_key_scalars[0],
+            public_key_scalars[1],
+            public_key_scalars[2],
+        ];
+        for (p, m) in plaintext.iter().zip(cipher_trace.output.iter()) {
+            online_inputs.push(*p + m);
+        }
+        assert!(cs.verify_witness(&witness, &online_inputs).is_ok());
+
+        // a ciphertext of another amount should be rejected
+        online_inputs[5] = online_inputs[5] + BN254Scalar::one();
+        assert!(cs.verify_witness(&witness, &online_inputs).is_err());
+    }
+
+    #[test]
+    fn test_axfr_tracer_memo() {
+        let mut prng = test_rng();
+        let params = ProverParams::gen_abar_tracing().unwrap();
+        let verifier_params = VerifierParams::get_abar_tracing().unwrap();
+        // the parameters are generated once
+        assert!(core::ptr::eq(
+            verifier_params,
+            VerifierParams::get_abar_tracing().unwrap()
+        ));
+
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let tracer_keypair = AXfrTracerKeyPair::generate(&mut prng);
+        let asset_type = AssetType([7u8; ASSET_TYPE_LENGTH]);
+
+        let oabar = OpenAnonAssetRecordBuilder::new()
+            .amount(100)
+            .asset_type(asset_type)
+            .pub_key(&receiver.get_pk())
+            .finalize(&mut prng)
+            .unwrap()
+            .build()
+            .unwrap();
+        let abar = AnonAssetRecord::from_oabar(&oabar);
+
+        let payee = PayeeWitness {
+            amount: oabar.get_amount(),
+            blind: oabar.blind,
+            asset_type: oabar.get_asset_type().as_scalar(),
+            public_key: receiver.get_pk(),
+        };
+        let memo =
+            gen_axfr_tracer_memo(&mut prng, &params, &payee, &tracer_keypair.enc_key).unwrap();
+        assert!(verify_axfr_tracer_memo(verifier_params, &abar, &memo).is_ok());
+
+        let record = memo.decrypt(&tracer_keypair.dec_key).unwrap();
+        assert_eq!(record.amount, 100);
+        assert!(record.has_asset_type(&asset_type));
+        assert!(record.is_owned_by(&receiver.get_pk()));
+
+        // the memo does not match other outputs
+        let other_abar = AnonAssetRecord {
+            commitment: abar.commitment + BN254Scalar::one(),
+        };
+        assert!(verify_axfr_tracer_memo(verifier_params, &other_abar, &memo).is_err());
+
+        // other tracers cannot decrypt the memo
+        let other_tracer = AXfrTracerKeyPair::generate(&mut prng);
+        let other_record = memo.decrypt(&other_tracer.dec_key);
+        assert!(other_record.is_err() || other_record.unwrap().amount != 100);
+    }
+}
@@ -1,12 +1,17 @@
 use crate::anon_xfr::{
     commit, commit_in_cs,
     structs::{AnonAssetRecord, AxfrOwnerMemo, OpenAnonAssetRecord, OpenAnonAssetRecordBuilder},
-    AXfrPlonkPf, TurboPlonkCS, MAX_AXFR_MEMO_SIZE, TWO_POW_32,
+    AXfrPlonkPf, TurboPlonkCS, TWO_POW_32,
+};
+use crate::cross_field_equality::{
+    cross_field_equality_in_cs, cross_field_equality_inputs, prehash_public_inputs,
+    prehash_public_inputs_in_cs,
 };
 use crate::errors::{NoahError, Result};
-use crate::keys::{KeyPair, PublicKey, PublicKeyInner, Signature};
-use crate::parameters::params::ProverParams;
+use crate::keys::{KeyPair, PublicKey, PublicKeyInner, SignableBody, Signature};
 use crate::parameters::params::VerifierParams;
+use crate::parameters::params::{AXfrPCS, ProverParams};
+use crate::parameters::schema::PublicInputsSchema;
 use crate::xfr::{
     asset_record::AssetRecordType,
     structs::{BlindAssetRecord, OpenAssetRecord, XfrAmount, XfrAssetType},
@@ -18,22 +23,23 @@ use noah_algebra::{
     ristretto::{PedersenCommitmentRistretto, RistrettoPoint, RistrettoScalar},
     traits::PedersenCommitment,
 };
-use noah_crypto::anemoi_jive::{AnemoiJive, AnemoiJive254, AnemoiVLHTrace};
+use noah_crypto::anemoi_jive::{AnemoiJive254, AnemoiVLHTrace};
 use noah_crypto::{
     delegated_schnorr::{prove_delegated_schnorr, verify_delegated_schnorr, DSInspection, DSProof},
-    field_simulation::{SimFr, SimFrParams, SimFrParamsBN254Ristretto},
+    field_simulation::SimFrParamsBN254Ristretto,
 };
 use noah_plonk::plonk::{
-    constraint_system::{field_simulation::SimFrVar, TurboCS},
-    prover::prover_with_lagrange,
-    verifier::verifier,
+    constraint_system::TurboCS, prover::prover_with_lagrange, verifier::verifier,
 };
-use num_bigint::BigUint;
 #[cfg(feature = "parallel")]
 use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 const BAR_TO_ABAR_PLONK_PROOF_TRANSCRIPT: &[u8] = b"BAR to ABAR Plonk Proof";
 
+/// The label of the parameters of the confidential-to-anonymous circuit whose inspection
+/// inputs are pre-hashed into one public input.
+pub(crate) const BAR_TO_ABAR_PREHASHED_LABEL: &str = "bar_to_abar_prehashed";
+
 /// A confidential-to-anonymous note.
 #[derive(Debug, Serialize, Deserialize, Eq, Clone, PartialEq)]
 pub struct BarToAbarNote {
@@ -59,14 +65,29 @@ pub struct BarToAbarBody {
     pub memo: AxfrOwnerMemo,
 }
 
+impl SignableBody for BarToAbarBody {}
+
 /// Generate confidential-to-anonymous note.
-pub fn gen_bar_to_abar_note<R: CryptoRng + RngCore>(
+pub fn gen_bar_to_abar_note<R: CryptoRng + RngCore, PCS: AXfrPCS>(
     prng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     record: &OpenAssetRecord,
     bar_keypair: &KeyPair,
     abar_pubkey: &PublicKey,
 ) -> Result<BarToAbarNote> {
+    let body = gen_bar_to_abar_body_unsigned(prng, params, record, abar_pubkey)?;
+    let signature = bar_keypair.sign(&body.signing_payload()?)?;
+    Ok(BarToAbarNote { body, signature })
+}
+
+/// Generate the confidential-to-anonymous body without signing it, so that the owner of the
+/// input can sign `body.signing_payload()` elsewhere.
+pub fn gen_bar_to_abar_body_unsigned<R: CryptoRng + RngCore, PCS: AXfrPCS>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    record: &OpenAssetRecord,
+    abar_pubkey: &PublicKey,
+) -> Result<BarToAbarBody> {
     // Reject confidential-to-anonymous note that actually has transparent input.
     // Should direct to ArToAbar.
     if record.get_record_type() == AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType {
@@ -75,18 +96,19 @@ pub fn gen_bar_to_abar_note<R: CryptoRng + RngCore>(
 
     let (open_abar, delegated_schnorr_proof, inspector_proof) =
         prove_bar_to_abar(prng, params, record, abar_pubkey)?;
-    let body = BarToAbarBody {
+    Ok(BarToAbarBody {
         input: record.blind_asset_record.clone(),
         output: AnonAssetRecord::from_oabar(&open_abar),
         proof: (delegated_schnorr_proof, inspector_proof),
         memo: open_abar.owner_memo.unwrap(),
-    };
-
-    let msg = bincode::serialize(&body).map_err(|_| NoahError::SerializationError)?;
-    let signature = bar_keypair.sign(&msg)?;
+    })
+}
 
-    let note = BarToAbarNote { body, signature };
-    Ok(note)
+/// Attach the signature of the owner of the input to the confidential-to-anonymous body,
+/// after checking it against the public key of the input.
+pub fn attach_signature(body: BarToAbarBody, signature: Signature) -> Result<BarToAbarNote> {
+    body.verify_signature(&body.input.public_key, &signature)?;
+    Ok(BarToAbarNote { body, signature })
 }
 
 /// Verify a confidential-to-anonymous note.
@@ -94,21 +116,35 @@ pub fn verify_bar_to_abar_note(
     params: &VerifierParams,
     note: &BarToAbarNote,
     bar_pub_key: &PublicKey,
+) -> Result<()> {
+    verify_bar_to_abar_note_with(params, note, bar_pub_key, false)
+}
+
+/// Verify a confidential-to-anonymous note. With `full_evaluation`, the signature is verified
+/// even if the proof is invalid, and the first error is returned.
+pub(crate) fn verify_bar_to_abar_note_with(
+    params: &VerifierParams,
+    note: &BarToAbarNote,
+    bar_pub_key: &PublicKey,
+    full_evaluation: bool,
 ) -> Result<()> {
     // Check the memo size.
-    if note.body.memo.size() > MAX_AXFR_MEMO_SIZE {
+    if note.body.memo.is_oversized() {
         return Err(NoahError::AXfrVerificationError);
     }
 
-    verify_bar_to_abar(
+    let proof_res = verify_bar_to_abar(
         params,
         &note.body.input,
         &note.body.output,
         &note.body.proof,
-    )?;
+    );
+    if !full_evaluation && proof_res.is_err() {
+        return proof_res;
+    }
 
-    let msg = bincode::serialize(&note.body).map_err(|_| NoahError::SerializationError)?;
-    bar_pub_key.verify(&msg, &note.signature)
+    let signature_res = note.body.verify_signature(bar_pub_key, &note.signature);
+    proof_res.and(signature_res)
 }
 
 /// Batch verify the confidential-to-anonymous notes.
@@ -120,7 +156,7 @@ pub fn batch_verify_bar_to_abar_note(
 ) -> Result<()> {
     // Check the memo size.
     for note in notes.iter() {
-        if note.body.memo.size() > MAX_AXFR_MEMO_SIZE {
+        if note.body.memo.is_oversized() {
             return Err(NoahError::AXfrVerificationError);
         }
     }
@@ -136,8 +172,7 @@ pub fn batch_verify_bar_to_abar_note(
                 &note.body.proof,
             )?;
 
-            let msg = bincode::serialize(&note.body).map_err(|_| NoahError::SerializationError)?;
-            bar_pub_key.verify(&msg, &note.signature)
+            note.body.verify_signature(bar_pub_key, &note.signature)
         })
         .all(|x| x.is_ok());
 
@@ -148,9 +183,9 @@ pub fn batch_verify_bar_to_abar_note(
     }
 }
 
-pub(crate) fn prove_bar_to_abar<R: CryptoRng + RngCore>(
+pub(crate) fn prove_bar_to_abar<R: CryptoRng + RngCore, PCS: AXfrPCS>(
     prng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     obar: &OpenAssetRecord,
     abar_pubkey: &PublicKey,
 ) -> Result<(
@@ -181,8 +216,8 @@ pub(crate) fn prove_bar_to_abar<R: CryptoRng + RngCore>(
     let point_p = pc_gens.commit(x, gamma);
     let point_q = pc_gens.commit(y, delta);
 
-    let x_in_bls12_381 = BN254Scalar::from(&BigUint::from_bytes_le(&x.to_bytes()));
-    let y_in_bls12_381 = BN254Scalar::from(&BigUint::from_bytes_le(&y.to_bytes()));
+    let x_in_bn254: BN254Scalar = convert_scalar(&x)?;
+    let y_in_bn254: BN254Scalar = convert_scalar(&y)?;
 
     let (comm, comm_trace) = commit(
         abar_pubkey,
@@ -216,8 +251,8 @@ pub(crate) fn prove_bar_to_abar<R: CryptoRng + RngCore>(
     let inspector_proof = prove_bar_to_abar_cs(
         prng,
         params,
-        x_in_bls12_381,
-        y_in_bls12_381,
+        x_in_bn254,
+        y_in_bn254,
         oabar.blind,
         abar_pubkey,
         &delegated_schnorr_proof,
@@ -292,9 +327,9 @@ pub(crate) fn verify_bar_to_abar(
 
 /// Generate the inspector's proof.
 #[allow(clippy::too_many_arguments)]
-pub(crate) fn prove_bar_to_abar_cs<R: CryptoRng + RngCore>(
+pub(crate) fn prove_bar_to_abar_cs<R: CryptoRng + RngCore, PCS: AXfrPCS>(
     rng: &mut R,
-    params: &ProverParams,
+    params: &ProverParams<PCS>,
     amount: BN254Scalar,
     asset_type: BN254Scalar,
     blind_hash: BN254Scalar,
@@ -305,6 +340,7 @@ pub(crate) fn prove_bar_to_abar_cs<R: CryptoRng + RngCore>(
     lambda: &RistrettoScalar,
     comm_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
 ) -> Result<AXfrPlonkPf> {
+    let prehash = params.label == BAR_TO_ABAR_PREHASHED_LABEL;
     let mut transcript = Transcript::new(BAR_TO_ABAR_PLONK_PROOF_TRANSCRIPT);
     let (mut cs, _) = build_bar_to_abar_cs(
         amount,
@@ -316,8 +352,10 @@ pub(crate) fn prove_bar_to_abar_cs<R: CryptoRng + RngCore>(
         beta,
         lambda,
         comm_trace,
-    );
+        prehash,
+    )?;
     let witness = cs.get_and_clear_witness();
+    bar_to_abar_schema(prehash).validate_witness(&cs, &witness)?;
 
     Ok(prover_with_lagrange(
         rng,
@@ -340,31 +378,22 @@ pub(crate) fn verify_inspection(
     lambda: &RistrettoScalar,
 ) -> Result<()> {
     let mut transcript = Transcript::new(BAR_TO_ABAR_PLONK_PROOF_TRANSCRIPT);
-    let mut online_inputs = Vec::with_capacity(2 + 3 * SimFrParamsBN254Ristretto::NUM_OF_LIMBS);
-    online_inputs.push(hash_comm);
-    online_inputs.push(proof_zk_part.inspection_comm);
-    let beta_sim_fr = SimFr::<BN254Scalar, SimFrParamsBN254Ristretto>::from(
-        &BigUint::from_bytes_le(&beta.to_bytes()),
-    );
-    let lambda_sim_fr = SimFr::<BN254Scalar, SimFrParamsBN254Ristretto>::from(
-        &BigUint::from_bytes_le(&lambda.to_bytes()),
-    );
-
-    let beta_lambda = *beta * lambda;
-    let beta_lambda_sim_fr = SimFr::<BN254Scalar, SimFrParamsBN254Ristretto>::from(
-        &BigUint::from_bytes_le(&beta_lambda.to_bytes()),
-    );
-
-    let s1_plus_lambda_s2 =
-        proof_zk_part.response_scalars[0].0 + proof_zk_part.response_scalars[1].0 * lambda;
-    let s1_plus_lambda_s2_sim_fr = SimFr::<BN254Scalar, SimFrParamsBN254Ristretto>::from(
-        &BigUint::from_bytes_le(&s1_plus_lambda_s2.to_bytes()),
-    );
-
-    online_inputs.extend_from_slice(&beta_sim_fr.limbs);
-    online_inputs.extend_from_slice(&lambda_sim_fr.limbs);
-    online_inputs.extend_from_slice(&beta_lambda_sim_fr.limbs);
-    online_inputs.extend_from_slice(&s1_plus_lambda_s2_sim_fr.limbs);
+    let mut online_inputs = vec![hash_comm];
+    online_inputs.extend(cross_field_equality_inputs::<
+        _,
+        _,
+        _,
+        SimFrParamsBN254Ristretto,
+    >(proof_zk_part, beta, lambda)?);
+    PublicInputsSchema::bar_to_abar().validate(&online_inputs)?;
+
+    // The pre-hashed circuit takes the hash of the inspection inputs in their place.
+    if params.label == BAR_TO_ABAR_PREHASHED_LABEL {
+        let hash = prehash_public_inputs::<_, AnemoiJive254>(&online_inputs[1..]);
+        online_inputs.truncate(1);
+        online_inputs.push(hash);
+        PublicInputsSchema::bar_to_abar_prehashed().validate(&online_inputs)?;
+    }
 
     Ok(verifier(
         &mut transcript,
@@ -376,7 +405,17 @@ pub(crate) fn verify_inspection(
     )?)
 }
 
-/// Construct the confidential-to-anonymous constraint system.
+/// Return the schema of the public inputs of the confidential-to-anonymous circuit.
+pub(crate) fn bar_to_abar_schema(prehash: bool) -> PublicInputsSchema {
+    if prehash {
+        PublicInputsSchema::bar_to_abar_prehashed()
+    } else {
+        PublicInputsSchema::bar_to_abar()
+    }
+}
+
+/// Construct the confidential-to-anonymous constraint system. With `prehash`, the public
+/// inputs of the inspection are absorbed into a single public input, their hash.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn build_bar_to_abar_cs(
     amount: BN254Scalar,
@@ -388,223 +427,33 @@ pub(crate) fn build_bar_to_abar_cs(
     beta: &RistrettoScalar,
     lambda: &RistrettoScalar,
     comm_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
-) -> (TurboPlonkCS, usize) {
+    prehash: bool,
+) -> Result<(TurboPlonkCS, usize)> {
     let mut cs = TurboCS::new();
     cs.load_anemoi_jive_parameters::<AnemoiJive254>();
 
-    let zero_var = cs.zero_var();
-
-    let zero = BN254Scalar::zero();
-    let one = BN254Scalar::one();
-    let step_1 = BN254Scalar::from(&BigUint::one().shl(SimFrParamsBN254Ristretto::BIT_PER_LIMB));
-    let step_2 =
-        BN254Scalar::from(&BigUint::one().shl(SimFrParamsBN254Ristretto::BIT_PER_LIMB * 2));
-    let step_3 =
-        BN254Scalar::from(&BigUint::one().shl(SimFrParamsBN254Ristretto::BIT_PER_LIMB * 3));
-    let step_4 =
-        BN254Scalar::from(&BigUint::one().shl(SimFrParamsBN254Ristretto::BIT_PER_LIMB * 4));
-    let step_5 =
-        BN254Scalar::from(&BigUint::one().shl(SimFrParamsBN254Ristretto::BIT_PER_LIMB * 5));
-
     // 1. Input commitment witnesses.
     let amount_var = cs.new_variable(amount);
     let at_var = cs.new_variable(asset_type);
     let blind_var = cs.new_variable(blind);
 
-    let public_key_scalars = pubkey.to_bn_scalars().unwrap();
+    let public_key_scalars = pubkey.to_bn_scalars()?;
     let public_key_scalars_vars = [
         cs.new_variable(public_key_scalars[0]),
         cs.new_variable(public_key_scalars[1]),
         cs.new_variable(public_key_scalars[2]),
     ];
 
-    // 2. Input witness x, y, a, b, r, public input comm, beta, s1, s2.
-    let x_sim_fr = SimFr::<BN254Scalar, SimFrParamsBN254Ristretto>::from(&BigUint::from_bytes_le(
-        &non_zk_state.committed_data_and_randomizer[0].0.to_bytes(),
-    ));
-    let y_sim_fr = SimFr::<BN254Scalar, SimFrParamsBN254Ristretto>::from(&BigUint::from_bytes_le(
-        &non_zk_state.committed_data_and_randomizer[1].0.to_bytes(),
-    ));
-    let a_sim_fr = SimFr::<BN254Scalar, SimFrParamsBN254Ristretto>::from(&BigUint::from_bytes_le(
-        &non_zk_state.committed_data_and_randomizer[0].1.to_bytes(),
-    ));
-    let b_sim_fr = SimFr::<BN254Scalar, SimFrParamsBN254Ristretto>::from(&BigUint::from_bytes_le(
-        &non_zk_state.committed_data_and_randomizer[1].1.to_bytes(),
-    ));
-    let comm = proof.inspection_comm;
-    let r = non_zk_state.r;
-
-    let beta_sim_fr = SimFr::from(&BigUint::from_bytes_le(&beta.to_bytes()));
-    let lambda_sim_fr = SimFr::from(&BigUint::from_bytes_le(&lambda.to_bytes()));
-
-    let beta_lambda = *beta * lambda;
-    let beta_lambda_sim_fr = SimFr::from(&BigUint::from_bytes_le(&beta_lambda.to_bytes()));
-
-    let s1_plus_lambda_s2 = proof.response_scalars[0].0 + proof.response_scalars[1].0 * lambda;
-    let s1_plus_lambda_s2_sim_fr =
-        SimFr::from(&BigUint::from_bytes_le(&s1_plus_lambda_s2.to_bytes()));
-
-    let (x_sim_fr_var, _) = SimFrVar::alloc_witness_bounded_total_bits(&mut cs, &x_sim_fr, 64);
-    let (y_sim_fr_var, _) = SimFrVar::alloc_witness_bounded_total_bits(&mut cs, &y_sim_fr, 240);
-    let (a_sim_fr_var, _) = SimFrVar::alloc_witness(&mut cs, &a_sim_fr);
-    let (b_sim_fr_var, _) = SimFrVar::alloc_witness(&mut cs, &b_sim_fr);
-    let comm_var = cs.new_variable(comm);
-    let r_var = cs.new_variable(r);
-    let beta_sim_fr_var = SimFrVar::alloc_input(&mut cs, &beta_sim_fr);
-    let lambda_sim_fr_var = SimFrVar::alloc_input(&mut cs, &lambda_sim_fr);
-    let beta_lambda_sim_fr_var = SimFrVar::alloc_input(&mut cs, &beta_lambda_sim_fr);
-    let s1_plus_lambda_s2_sim_fr_var = SimFrVar::alloc_input(&mut cs, &s1_plus_lambda_s2_sim_fr);
-
-    // 3. Merge the limbs for x, y, a, b.
-    let mut all_limbs = Vec::with_capacity(4 * SimFrParamsBN254Ristretto::NUM_OF_LIMBS);
-    all_limbs.extend_from_slice(&x_sim_fr.limbs);
-    all_limbs.extend_from_slice(&y_sim_fr.limbs);
-    all_limbs.extend_from_slice(&a_sim_fr.limbs);
-    all_limbs.extend_from_slice(&b_sim_fr.limbs);
-
-    let mut all_limbs_var = Vec::with_capacity(4 * SimFrParamsBN254Ristretto::NUM_OF_LIMBS);
-    all_limbs_var.extend_from_slice(&x_sim_fr_var.var);
-    all_limbs_var.extend_from_slice(&y_sim_fr_var.var);
-    all_limbs_var.extend_from_slice(&a_sim_fr_var.var);
-    all_limbs_var.extend_from_slice(&b_sim_fr_var.var);
-
-    let mut compressed_limbs = Vec::with_capacity(5);
-    let mut compressed_limbs_var = Vec::with_capacity(5);
-    for (limbs, limbs_var) in all_limbs.chunks(5).zip(all_limbs_var.chunks(5)) {
-        let mut sum = BigUint::zero();
-        for (i, limb) in limbs.iter().enumerate() {
-            sum.add_assign(
-                <BN254Scalar as Into<BigUint>>::into(*limb)
-                    .shl(SimFrParamsBN254Ristretto::BIT_PER_LIMB * i),
-            );
-        }
-        compressed_limbs.push(BN254Scalar::from(&sum));
-
-        let mut sum_var = {
-            let first_var = *limbs_var.first().unwrap_or(&zero_var);
-            let second_var = *limbs_var.get(1).unwrap_or(&zero_var);
-            let third_var = *limbs_var.get(2).unwrap_or(&zero_var);
-            let fourth_var = *limbs_var.get(3).unwrap_or(&zero_var);
-
-            cs.linear_combine(
-                &[first_var, second_var, third_var, fourth_var],
-                one,
-                step_1,
-                step_2,
-                step_3,
-            )
-        };
-
-        if limbs.len() == 5 {
-            let fifth_var = *limbs_var.get(4).unwrap_or(&zero_var);
-            sum_var = cs.linear_combine(
-                &[sum_var, fifth_var, zero_var, zero_var],
-                one,
-                step_4,
-                zero,
-                zero,
-            );
-        }
-
-        compressed_limbs_var.push(sum_var);
-    }
-
-    // 4. Open the inspector's state commitment.
-    {
-        let trace = AnemoiJive254::eval_variable_length_hash_with_trace(&[
-            compressed_limbs[0],
-            compressed_limbs[1],
-            compressed_limbs[2],
-            compressed_limbs[3],
-            compressed_limbs[4],
-            r,
-        ]);
-
-        cs.anemoi_variable_length_hash::<AnemoiJive254>(
-            &trace,
-            &[
-                compressed_limbs_var[0],
-                compressed_limbs_var[1],
-                compressed_limbs_var[2],
-                compressed_limbs_var[3],
-                compressed_limbs_var[4],
-                r_var,
-            ],
-            comm_var,
-        );
-    }
-
-    // 5. Perform the check in field simulation.
-    {
-        let beta_x_sim_fr_mul_var = beta_sim_fr_var.mul(&mut cs, &x_sim_fr_var);
-        let beta_lambda_y_sim_fr_mul_var = beta_lambda_sim_fr_var.mul(&mut cs, &y_sim_fr_var);
-        let lambda_b_sim_fr_mul_var = lambda_sim_fr_var.mul(&mut cs, &b_sim_fr_var);
-
-        let mut rhs = beta_x_sim_fr_mul_var.add(&mut cs, &beta_lambda_y_sim_fr_mul_var);
-        rhs = rhs.add(&mut cs, &lambda_b_sim_fr_mul_var);
-
-        let s1_plus_lambda_s2_minus_a_sim_fr_var =
-            s1_plus_lambda_s2_sim_fr_var.sub(&mut cs, &a_sim_fr_var);
-
-        let eqn = rhs.sub(&mut cs, &s1_plus_lambda_s2_minus_a_sim_fr_var);
-        eqn.enforce_zero(&mut cs);
-    }
-
-    // 6. Check x = amount_var and y = at_var.
-    {
-        let mut x_in_bls12_381 = cs.linear_combine(
-            &[
-                x_sim_fr_var.var[0],
-                x_sim_fr_var.var[1],
-                x_sim_fr_var.var[2],
-                x_sim_fr_var.var[3],
-            ],
-            one,
-            step_1,
-            step_2,
-            step_3,
-        );
-        x_in_bls12_381 = cs.linear_combine(
-            &[
-                x_in_bls12_381,
-                x_sim_fr_var.var[4],
-                x_sim_fr_var.var[5],
-                zero_var,
-            ],
-            one,
-            step_4,
-            step_5,
-            zero,
-        );
-
-        let mut y_in_bls12_381 = cs.linear_combine(
-            &[
-                y_sim_fr_var.var[0],
-                y_sim_fr_var.var[1],
-                y_sim_fr_var.var[2],
-                y_sim_fr_var.var[3],
-            ],
-            one,
-            step_1,
-            step_2,
-            step_3,
-        );
-        y_in_bls12_381 = cs.linear_combine(
-            &[
-                y_in_bls12_381,
-                y_sim_fr_var.var[4],
-                y_sim_fr_var.var[5],
-                zero_var,
-            ],
-            one,
-            step_4,
-            step_5,
-            zero,
-        );
-
-        cs.equal(x_in_bls12_381, amount_var);
-        cs.equal(y_in_bls12_381, at_var);
-    }
+    // 2. Check that the amount and the asset type equal the values in the Ristretto commitments.
+    let inspection_vars = cross_field_equality_in_cs::<
+        _,
+        AnemoiJive254,
+        _,
+        _,
+        SimFrParamsBN254Ristretto,
+    >(&mut cs, proof, non_zk_state, beta, lambda, &[64, 240])?;
+    cs.equal(inspection_vars.values[0], amount_var);
+    cs.equal(inspection_vars.values[1], at_var);
 
     let public_key_type = match pubkey.0 {
         PublicKeyInner::Ed25519(_) => cs.new_variable(BN254Scalar::one()),
@@ -613,7 +462,7 @@ pub(crate) fn build_bar_to_abar_cs(
     };
     cs.insert_boolean_gate(public_key_type);
 
-    // 7. Coin commitment
+    // 3. Coin commitment
     let coin_comm_var = commit_in_cs(
         &mut cs,
         blind_var,
@@ -626,31 +475,29 @@ pub(crate) fn build_bar_to_abar_cs(
 
     // prepare public inputs.
     cs.prepare_pi_variable(coin_comm_var);
-    cs.prepare_pi_variable(comm_var);
-
-    for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(beta_sim_fr_var.var[i]);
-    }
-    for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(lambda_sim_fr_var.var[i]);
-    }
-    for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(beta_lambda_sim_fr_var.var[i]);
-    }
-    for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS {
-        cs.prepare_pi_variable(s1_plus_lambda_s2_sim_fr_var.var[i]);
+    if prehash {
+        let hash_var = prehash_public_inputs_in_cs::<_, AnemoiJive254>(
+            &mut cs,
+            &inspection_vars.public_inputs,
+        );
+        cs.prepare_pi_variable(hash_var);
+    } else {
+        for var in inspection_vars.public_inputs.iter() {
+            cs.prepare_pi_variable(*var);
+        }
     }
 
     // pad the number of constraints to power of two.
     cs.pad();
 
     let n_constraints = cs.size;
-    (cs, n_constraints)
+    Ok((cs, n_constraints))
 }
 
 #[cfg(test)]
 mod test {
     use crate::anon_xfr::{bar_to_abar::BAR_TO_ABAR_PLONK_PROOF_TRANSCRIPT, commit};
+    use crate::cross_field_equality::prehash_public_inputs;
     use crate::keys::KeyPair;
     use crate::parameters::AddressFormat::SECP256K1;
     use crate::xfr::structs::AssetType;
@@ -730,7 +577,9 @@ mod test {
             &beta,
             &lambda,
             &output_commitment_trace,
-        );
+            false,
+        )
+        .unwrap();
         let witness = cs.get_and_clear_witness();
 
         let mut online_inputs = Vec::with_capacity(2 + 3 * SimFrParamsBN254Ristretto::NUM_OF_LIMBS);
@@ -761,6 +610,34 @@ mod test {
 
         // Check the constraints
         assert!(cs.verify_witness(&witness, &online_inputs).is_ok());
+
+        // The pre-hashed circuit takes the commitment and the hash of the other inputs.
+        let (mut prehashed_cs, _) = super::build_bar_to_abar_cs(
+            amount_bls12_381,
+            asset_type_bls12_381,
+            z_randomizer,
+            &pubkey,
+            &proof,
+            &non_zk_state,
+            &beta,
+            &lambda,
+            &output_commitment_trace,
+            true,
+        )
+        .unwrap();
+        let prehashed_witness = prehashed_cs.get_and_clear_witness();
+        let mut prehashed_inputs = vec![
+            z,
+            prehash_public_inputs::<_, AnemoiJive254>(&online_inputs[1..]),
+        ];
+        assert!(prehashed_cs
+            .verify_witness(&prehashed_witness, &prehashed_inputs)
+            .is_ok());
+        prehashed_inputs[1].add_assign(&BN254Scalar::one());
+        assert!(prehashed_cs
+            .verify_witness(&prehashed_witness, &prehashed_inputs)
+            .is_err());
+
         online_inputs[0].add_assign(&BN254Scalar::one());
         assert!(cs.verify_witness(&witness, &online_inputs).is_err());
     }
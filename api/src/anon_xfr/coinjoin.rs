@@ -0,0 +1,605 @@
+use crate::anon_xfr::{
+    abar_burn::{abar_nullifier, prove_abar_reveal, verify_abar_reveal},
+    ar_to_abar::{prove_ar_to_abar, verify_ar_to_abar_proof},
+    structs::{
+        AnonAssetRecord, AxfrOwnerMemo, Nullifier, OpenAnonAssetRecord, OpenAnonAssetRecordBuilder,
+    },
+    AXfrAddressFoldingInstance, AXfrPlonkPf,
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey};
+use crate::parameters::{
+    params::{AXfrPCS, ProverParams, VerifierParams},
+    AddressFormat::{self, ED25519, SECP256K1},
+};
+use crate::serialization::to_bincode;
+use crate::xfr::structs::AssetType;
+use digest::{consts::U64, Digest};
+use merlin::Transcript;
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use noah_crypto::bls_sig::{
+    bls_blind, bls_key_gen, bls_sign_blinded, bls_unblind, bls_verify, BlsBlindedMessage,
+    BlsBlindedSignature, BlsBlindingFactor, BlsPublicKey, BlsSecretKey, BlsSignature,
+};
+use sha2::Sha512;
+
+/// The domain separator for the registrations of the inputs, for address folding.
+const COINJOIN_REGISTRATION_TRANSCRIPT: &[u8] = b"Anon CoinJoin Registration";
+/// The domain separator for the inputs of the coinjoin note, for address folding.
+const COINJOIN_FOLDING_PROOF_TRANSCRIPT: &[u8] = b"Anon CoinJoin Folding Proof";
+/// The domain separator of the tokens that entitle to register an output.
+const COINJOIN_OUTPUT_TOKEN_DOMAIN: &[u8] = b"Anon CoinJoin Output Token";
+
+/// The minimal number of participants for the coordinator to close the input registration.
+pub const MIN_COINJOIN_PARTICIPANTS: usize = 2;
+
+/// The parameters of a coinjoin round, announced by the coordinator.
+///
+/// Every input and every output of the round is an ABAR of the same amount and asset type,
+/// so that the amounts do not link the inputs to the outputs.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CoinJoinRound {
+    /// The random identifier of the round.
+    pub round_id: [u8; 32],
+    /// The asset type.
+    pub asset_type: AssetType,
+    /// The amount of each input and output.
+    pub amount: u64,
+    /// The Merkle root hash at which the inputs are spent.
+    pub merkle_root: BN254Scalar,
+    /// The Merkle root version.
+    pub merkle_root_version: u64,
+    /// The key with which the coordinator blindly signs the output tokens of the round.
+    pub coordinator_key: BlsPublicKey,
+}
+
+/// The proof that an input of the round, given by its nullifier, is spent by its owner.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CoinJoinInputProof {
+    /// The Plonk proof, as in anonymous-to-transparent.
+    pub proof: AXfrPlonkPf,
+    /// The address folding instance.
+    pub folding_instance: AXfrAddressFoldingInstance,
+}
+
+/// An output of the round.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CoinJoinOutput {
+    /// The output ABAR.
+    pub output: AnonAssetRecord,
+    /// The owner memo of the output.
+    pub memo: AxfrOwnerMemo,
+    /// The proof that the output has the amount and the asset type of the round,
+    /// as in transparent-to-anonymous.
+    pub proof: AXfrPlonkPf,
+}
+
+/// The registration of an input, which the participant sends under its usual identity.
+///
+/// The output token is blinded, so the coordinator signs it without learning the output.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CoinJoinInputRegistration {
+    /// The nullifier of the input.
+    pub input: Nullifier,
+    /// The blinded output token.
+    pub blinded_token: BlsBlindedMessage,
+    /// The proof that the participant owns the input, bound to the round and the blinded token.
+    pub proof: CoinJoinInputProof,
+}
+
+/// What a participant keeps between the registration of its input and that of its output.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CoinJoinTicket {
+    /// The output to register.
+    output: CoinJoinOutput,
+    /// The factor that blinds the output token.
+    factor: BlsBlindingFactor,
+}
+
+/// The registration of an output, which the participant sends under a fresh identity,
+/// e.g., over a new network circuit, so that the coordinator cannot link it to an input.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CoinJoinOutputRegistration {
+    /// The output.
+    pub output: CoinJoinOutput,
+    /// The unblinded signature of the coordinator on the output token.
+    pub token: BlsSignature,
+}
+
+/// The body of a coinjoin.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CoinJoinBody {
+    /// The identifier of the round.
+    pub round_id: [u8; 32],
+    /// The asset type.
+    pub asset_type: AssetType,
+    /// The amount of each input and output.
+    pub amount: u64,
+    /// The Merkle root hash.
+    pub merkle_root: BN254Scalar,
+    /// The Merkle root version.
+    pub merkle_root_version: u64,
+    /// The nullifiers of the inputs, sorted by their encodings.
+    pub inputs: Vec<Nullifier>,
+    /// The outputs, sorted by the encodings of their commitments.
+    pub outputs: Vec<CoinJoinOutput>,
+}
+
+/// A coinjoin among several participants, each spending one input and receiving one output.
+///
+/// The Plonk circuit of an anonymous transfer proves all of its inputs under one key, so a
+/// single anonymous transfer note cannot spend the inputs of several participants without
+/// sharing their secret keys. Instead, each input is spent by a proof that reveals only the
+/// amount and the asset type of the round, and each output comes with a proof that it has
+/// them, so that the amounts are conserved. The proofs of the inputs are bound to the whole
+/// body, so that no input is spent unless every output is as registered.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CoinJoinNote {
+    /// The body of the coinjoin.
+    pub body: CoinJoinBody,
+    /// The proofs of the inputs, in the order of the inputs.
+    pub input_proofs: Vec<CoinJoinInputProof>,
+}
+
+/// The verifier parameters of the coinjoin notes.
+pub struct CoinJoinVerifierParams {
+    /// The anonymous-to-transparent parameters, for the inputs owned by secp256k1 keys.
+    pub secp256k1_input: VerifierParams,
+    /// The anonymous-to-transparent parameters, for the inputs owned by ed25519 keys.
+    pub ed25519_input: VerifierParams,
+    /// The transparent-to-anonymous parameters, for the outputs.
+    pub output: VerifierParams,
+}
+
+impl CoinJoinVerifierParams {
+    /// Obtain the verifier parameters of the coinjoin notes.
+    pub fn get() -> Result<Self> {
+        Ok(CoinJoinVerifierParams {
+            secp256k1_input: VerifierParams::get_abar_to_ar(SECP256K1)?,
+            ed25519_input: VerifierParams::get_abar_to_ar(ED25519)?,
+            output: VerifierParams::get_ar_to_abar()?,
+        })
+    }
+
+    fn input(&self, address_format: AddressFormat) -> &VerifierParams {
+        match address_format {
+            SECP256K1 => &self.secp256k1_input,
+            ED25519 => &self.ed25519_input,
+        }
+    }
+}
+
+/// Register an input of the round, and prepare an output of the round to the public key.
+///
+/// The input parameters are those of anonymous-to-transparent for the key pair, and the
+/// output parameters are those of transparent-to-anonymous.
+pub fn register_coinjoin_input<R: CryptoRng + RngCore, PCS: AXfrPCS>(
+    prng: &mut R,
+    input_params: &ProverParams<PCS>,
+    output_params: &ProverParams<PCS>,
+    round: &CoinJoinRound,
+    oabar: &OpenAnonAssetRecord,
+    keypair: &KeyPair,
+    output_pub_key: &PublicKey,
+) -> Result<(CoinJoinInputRegistration, CoinJoinTicket)> {
+    let mt_leaf_info = oabar
+        .mt_leaf_info
+        .as_ref()
+        .ok_or(NoahError::ParameterError)?;
+    if oabar.amount != round.amount
+        || oabar.asset_type != round.asset_type
+        || mt_leaf_info.root != round.merkle_root
+    {
+        return Err(NoahError::ParameterError);
+    }
+
+    let output_oabar = OpenAnonAssetRecordBuilder::new()
+        .amount(round.amount)
+        .asset_type(round.asset_type)
+        .pub_key(output_pub_key)
+        .finalize(prng)?
+        .build()?;
+    let output = CoinJoinOutput {
+        output: AnonAssetRecord::from_oabar(&output_oabar),
+        memo: output_oabar
+            .owner_memo
+            .clone()
+            .ok_or(NoahError::ParameterError)?,
+        proof: prove_ar_to_abar(prng, output_params, &output_oabar)?,
+    };
+
+    let (factor, blinded_token) = bls_blind(prng, &output_token(round, &output.output));
+
+    let input = abar_nullifier(oabar, keypair)?;
+    let mut transcript = registration_transcript(round, &input, &blinded_token);
+    let (proof, folding_instance) = prove_abar_reveal(
+        prng,
+        input_params,
+        oabar,
+        keypair,
+        &mut transcript,
+        Sha512::new(),
+    )?;
+
+    Ok((
+        CoinJoinInputRegistration {
+            input,
+            blinded_token,
+            proof: CoinJoinInputProof {
+                proof,
+                folding_instance,
+            },
+        },
+        CoinJoinTicket { output, factor },
+    ))
+}
+
+impl CoinJoinTicket {
+    /// Return the output to register.
+    pub fn output(&self) -> &CoinJoinOutput {
+        &self.output
+    }
+
+    /// Unblind the signature of the coordinator on the output token, for the output registration.
+    pub fn output_registration(
+        self,
+        round: &CoinJoinRound,
+        blinded_signature: &BlsBlindedSignature,
+    ) -> Result<CoinJoinOutputRegistration> {
+        let token = bls_unblind(
+            &round.coordinator_key,
+            &output_token(round, &self.output.output),
+            &self.factor,
+            blinded_signature,
+        )?;
+        Ok(CoinJoinOutputRegistration {
+            output: self.output,
+            token,
+        })
+    }
+}
+
+/// Prove the spending of the input of a participant in the body assembled by the coordinator,
+/// after checking that the body includes the input and pays the output of the participant.
+pub fn sign_coinjoin_input<
+    R: CryptoRng + RngCore,
+    D: Digest<OutputSize = U64> + Default,
+    PCS: AXfrPCS,
+>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    body: &CoinJoinBody,
+    oabar: &OpenAnonAssetRecord,
+    keypair: &KeyPair,
+    output: &AnonAssetRecord,
+    hash: D,
+) -> Result<CoinJoinInputProof> {
+    let input = abar_nullifier(oabar, keypair)?;
+    if oabar.amount != body.amount
+        || oabar.asset_type != body.asset_type
+        || oabar.mt_leaf_info.as_ref().map(|info| info.root) != Some(body.merkle_root)
+        || !body.inputs.contains(&input)
+        || !body.outputs.iter().any(|o| &o.output == output)
+    {
+        return Err(NoahError::ParameterError);
+    }
+
+    let mut transcript = note_transcript(body)?;
+    let (proof, folding_instance) =
+        prove_abar_reveal(prng, params, oabar, keypair, &mut transcript, hash)?;
+    Ok(CoinJoinInputProof {
+        proof,
+        folding_instance,
+    })
+}
+
+/// Verify a coinjoin note.
+pub fn verify_coinjoin_note<D: Digest<OutputSize = U64> + Default + Clone>(
+    params: &CoinJoinVerifierParams,
+    note: &CoinJoinNote,
+    merkle_root: &BN254Scalar,
+    hash: D,
+) -> Result<()> {
+    let body = &note.body;
+    if *merkle_root != body.merkle_root {
+        return Err(NoahError::AXfrVerificationError);
+    }
+    check_body(body)?;
+    if note.input_proofs.len() != body.inputs.len() {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    for output in body.outputs.iter() {
+        verify_output(params, body.amount, body.asset_type, output)?;
+    }
+
+    let transcript = note_transcript(body)?;
+    for (input, proof) in body.inputs.iter().zip(note.input_proofs.iter()) {
+        verify_abar_reveal(
+            params.input(proof.folding_instance.get_address_format()),
+            input,
+            merkle_root,
+            body.amount,
+            body.asset_type,
+            &proof.folding_instance,
+            &proof.proof,
+            &mut transcript.clone(),
+            hash.clone(),
+        )?;
+    }
+    Ok(())
+}
+
+/// The phase of a coinjoin round.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CoinJoinPhase {
+    /// The participants register their inputs.
+    InputRegistration,
+    /// The participants register their outputs.
+    OutputRegistration,
+    /// The participants prove the spending of their inputs in the body.
+    Signing,
+}
+
+/// The coordinator of a coinjoin round.
+///
+/// It learns the inputs and the outputs that join, but the blind signatures of the output
+/// tokens hide which input registered which output.
+pub struct CoinJoinCoordinator {
+    round: CoinJoinRound,
+    key: BlsSecretKey,
+    phase: CoinJoinPhase,
+    inputs: Vec<Nullifier>,
+    outputs: Vec<CoinJoinOutput>,
+    body: Option<CoinJoinBody>,
+    input_proofs: Vec<Option<CoinJoinInputProof>>,
+}
+
+impl CoinJoinCoordinator {
+    /// Start a round for the amount and the asset type, at the Merkle root.
+    pub fn new<R: CryptoRng + RngCore>(
+        prng: &mut R,
+        asset_type: AssetType,
+        amount: u64,
+        merkle_root: BN254Scalar,
+        merkle_root_version: u64,
+    ) -> Self {
+        let mut round_id = [0u8; 32];
+        prng.fill_bytes(&mut round_id);
+        let (key, coordinator_key) = bls_key_gen(prng);
+        CoinJoinCoordinator {
+            round: CoinJoinRound {
+                round_id,
+                asset_type,
+                amount,
+                merkle_root,
+                merkle_root_version,
+                coordinator_key,
+            },
+            key,
+            phase: CoinJoinPhase::InputRegistration,
+            inputs: vec![],
+            outputs: vec![],
+            body: None,
+            input_proofs: vec![],
+        }
+    }
+
+    /// Return the round.
+    pub fn round(&self) -> &CoinJoinRound {
+        &self.round
+    }
+
+    /// Return the phase of the round.
+    pub fn phase(&self) -> CoinJoinPhase {
+        self.phase
+    }
+
+    /// Verify the registration of an input, and blindly sign its output token.
+    pub fn register_input(
+        &mut self,
+        params: &CoinJoinVerifierParams,
+        registration: &CoinJoinInputRegistration,
+    ) -> Result<BlsBlindedSignature> {
+        if self.phase != CoinJoinPhase::InputRegistration
+            || self.inputs.contains(&registration.input)
+        {
+            return Err(NoahError::ParameterError);
+        }
+
+        let proof = &registration.proof;
+        let mut transcript = registration_transcript(
+            &self.round,
+            &registration.input,
+            &registration.blinded_token,
+        );
+        verify_abar_reveal(
+            params.input(proof.folding_instance.get_address_format()),
+            &registration.input,
+            &self.round.merkle_root,
+            self.round.amount,
+            self.round.asset_type,
+            &proof.folding_instance,
+            &proof.proof,
+            &mut transcript,
+            Sha512::new(),
+        )?;
+
+        self.inputs.push(registration.input);
+        Ok(bls_sign_blinded(&self.key, &registration.blinded_token))
+    }
+
+    /// Close the registration of the inputs.
+    pub fn close_input_registration(&mut self) -> Result<()> {
+        if self.phase != CoinJoinPhase::InputRegistration
+            || self.inputs.len() < MIN_COINJOIN_PARTICIPANTS
+        {
+            return Err(NoahError::ParameterError);
+        }
+        self.phase = CoinJoinPhase::OutputRegistration;
+        Ok(())
+    }
+
+    /// Verify the registration of an output, with its unblinded token.
+    pub fn register_output(
+        &mut self,
+        params: &CoinJoinVerifierParams,
+        registration: &CoinJoinOutputRegistration,
+    ) -> Result<()> {
+        let output = &registration.output;
+        if self.phase != CoinJoinPhase::OutputRegistration
+            || self.outputs.len() >= self.inputs.len()
+            || self.outputs.iter().any(|o| o.output == output.output)
+        {
+            return Err(NoahError::ParameterError);
+        }
+
+        bls_verify(
+            &self.round.coordinator_key,
+            &output_token(&self.round, &output.output),
+            &registration.token,
+        )?;
+        verify_output(params, self.round.amount, self.round.asset_type, output)?;
+
+        self.outputs.push(output.clone());
+        Ok(())
+    }
+
+    /// Close the registration of the outputs, and assemble the body for the participants
+    /// to sign. If some outputs are missing, the round fails and is to be started anew.
+    pub fn close_output_registration(&mut self) -> Result<&CoinJoinBody> {
+        if self.phase != CoinJoinPhase::OutputRegistration
+            || self.outputs.len() != self.inputs.len()
+        {
+            return Err(NoahError::ParameterError);
+        }
+
+        // the order of the registrations is not revealed
+        let mut inputs = self.inputs.clone();
+        inputs.sort_by_key(|input| input.to_bytes());
+        let mut outputs = self.outputs.clone();
+        outputs.sort_by_key(|output| output.output.commitment.to_bytes());
+
+        self.input_proofs = vec![None; inputs.len()];
+        self.phase = CoinJoinPhase::Signing;
+        Ok(self.body.insert(CoinJoinBody {
+            round_id: self.round.round_id,
+            asset_type: self.round.asset_type,
+            amount: self.round.amount,
+            merkle_root: self.round.merkle_root,
+            merkle_root_version: self.round.merkle_root_version,
+            inputs,
+            outputs,
+        }))
+    }
+
+    /// Verify and add the proof of an input in the body.
+    pub fn add_input_proof<D: Digest<OutputSize = U64> + Default>(
+        &mut self,
+        params: &CoinJoinVerifierParams,
+        input: &Nullifier,
+        proof: CoinJoinInputProof,
+        hash: D,
+    ) -> Result<()> {
+        let body = self.body.as_ref().ok_or(NoahError::ParameterError)?;
+        let index = body
+            .inputs
+            .iter()
+            .position(|x| x == input)
+            .ok_or(NoahError::ParameterError)?;
+
+        let mut transcript = note_transcript(body)?;
+        verify_abar_reveal(
+            params.input(proof.folding_instance.get_address_format()),
+            input,
+            &body.merkle_root,
+            body.amount,
+            body.asset_type,
+            &proof.folding_instance,
+            &proof.proof,
+            &mut transcript,
+            hash,
+        )?;
+
+        self.input_proofs[index] = Some(proof);
+        Ok(())
+    }
+
+    /// Assemble the note, once every input is signed.
+    pub fn finalize(self) -> Result<CoinJoinNote> {
+        let body = self.body.ok_or(NoahError::ParameterError)?;
+        let input_proofs = self
+            .input_proofs
+            .into_iter()
+            .collect::<Option<Vec<CoinJoinInputProof>>>()
+            .ok_or(NoahError::ParameterError)?;
+        Ok(CoinJoinNote { body, input_proofs })
+    }
+}
+
+/// Check that the body has as many inputs as outputs, all distinct and in order.
+fn check_body(body: &CoinJoinBody) -> Result<()> {
+    if body.inputs.is_empty()
+        || body.inputs.len() != body.outputs.len()
+        || body
+            .inputs
+            .windows(2)
+            .any(|w| w[0].to_bytes() >= w[1].to_bytes())
+        || body
+            .outputs
+            .windows(2)
+            .any(|w| w[0].output.commitment.to_bytes() >= w[1].output.commitment.to_bytes())
+    {
+        return Err(NoahError::AXfrVerificationError);
+    }
+    Ok(())
+}
+
+/// Verify that the output has the amount and the asset type.
+fn verify_output(
+    params: &CoinJoinVerifierParams,
+    amount: u64,
+    asset_type: AssetType,
+    output: &CoinJoinOutput,
+) -> Result<()> {
+    if output.memo.is_oversized() {
+        return Err(NoahError::AXfrVerificationError);
+    }
+    verify_ar_to_abar_proof(
+        &params.output,
+        amount,
+        asset_type,
+        &output.output.commitment,
+        &output.proof,
+    )
+}
+
+/// The message of the token that entitles to register the output in the round.
+fn output_token(round: &CoinJoinRound, output: &AnonAssetRecord) -> Vec<u8> {
+    let mut message = COINJOIN_OUTPUT_TOKEN_DOMAIN.to_vec();
+    message.extend_from_slice(&round.round_id);
+    message.extend_from_slice(&output.commitment.to_bytes());
+    message
+}
+
+/// The transcript of the address folding of the registration of an input, which binds
+/// the proof of ownership to the round and to the blinded output token.
+fn registration_transcript(
+    round: &CoinJoinRound,
+    input: &Nullifier,
+    blinded_token: &BlsBlindedMessage,
+) -> Transcript {
+    let mut transcript = Transcript::new(COINJOIN_REGISTRATION_TRANSCRIPT);
+    transcript.append_message(b"round", &round.round_id);
+    transcript.append_message(b"nullifier", &input.to_bytes());
+    transcript.append_message(b"blinded token", &blinded_token.0.to_compressed_bytes());
+    transcript
+}
+
+/// The transcript of the address folding of the inputs of the note, which binds each input
+/// to the whole body.
+fn note_transcript(body: &CoinJoinBody) -> Result<Transcript> {
+    let mut transcript = Transcript::new(COINJOIN_FOLDING_PROOF_TRANSCRIPT);
+    transcript.append_message(b"body", &to_bincode(body)?);
+    Ok(transcript)
+}
@@ -0,0 +1,211 @@
+use crate::anon_xfr::structs::AxfrOwnerMemo;
+use crate::errors::{NoahError, Result};
+use crate::keys::KeyPair;
+use digest::Digest;
+use noah_algebra::{
+    prelude::*,
+    ristretto::{RistrettoPoint, RistrettoScalar},
+};
+use sha2::{Sha256, Sha512};
+
+/// The number of keys of a detection secret key, which bounds the false positive rate
+/// of its detection keys from below by 2^{-24}.
+pub const MAX_DETECTION_PRECISION: usize = 24;
+
+/// The domain separator for deriving the detection secret key.
+const DETECTION_KEY_DOMAIN: &[u8] = b"Noah Detection Key";
+/// The domain separator for the bits of the flag.
+const DETECTION_BIT_DOMAIN: &[u8] = b"Noah Detection Flag Bit";
+/// The domain separator for the challenge of the flag.
+const DETECTION_CHALLENGE_DOMAIN: &[u8] = b"Noah Detection Flag Challenge";
+
+/// The secret key for fuzzy message detection, which the wallet keeps.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectionSecretKey(Vec<RistrettoScalar>);
+
+/// The public key for fuzzy message detection, which senders use to flag the outputs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectionPublicKey(Vec<RistrettoPoint>);
+
+/// The detection key, made of the first keys of the secret key, which the wallet hands out to
+/// a scanning server. It detects all the flags for the wallet, and those for others with
+/// probability 2^{-n} for a key of precision n, so the server learns only a superset of the
+/// outputs of the wallet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectionKey(Vec<RistrettoScalar>);
+
+/// The flag of an output for the detection public key of its receiver.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectionFlag {
+    /// The commitment to the randomness of the flag.
+    pub u: RistrettoPoint,
+    /// The response, which binds the bits to the commitment.
+    pub y: RistrettoScalar,
+    /// The bits, one per key of the detection public key.
+    pub bits: Vec<u8>,
+}
+
+/// An owner memo with the flag of its output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlaggedOwnerMemo {
+    /// The owner memo.
+    pub memo: AxfrOwnerMemo,
+    /// The flag for the receiver.
+    pub flag: DetectionFlag,
+}
+
+impl DetectionSecretKey {
+    /// Derive the detection secret key from the key pair of the wallet.
+    pub fn from_key_pair(key_pair: &KeyPair) -> Self {
+        let sk_bytes = key_pair.get_sk_ref().noah_to_bytes();
+        let keys = (0..MAX_DETECTION_PRECISION)
+            .map(|i| {
+                let mut hasher = Sha512::new();
+                hasher.update(DETECTION_KEY_DOMAIN);
+                hasher.update(&sk_bytes);
+                hasher.update((i as u32).to_le_bytes());
+                RistrettoScalar::from_hash(hasher)
+            })
+            .collect();
+        Self(keys)
+    }
+
+    /// Return the detection public key.
+    pub fn get_pk(&self) -> DetectionPublicKey {
+        let base = RistrettoPoint::get_base();
+        DetectionPublicKey(self.0.iter().map(|key| base.mul(key)).collect())
+    }
+
+    /// Return the detection key with the precision, i.e., a false positive rate of 2^{-precision}.
+    pub fn detection_key(&self, precision: usize) -> Result<DetectionKey> {
+        if precision > self.0.len() {
+            return Err(NoahError::ParameterError);
+        }
+        Ok(DetectionKey(self.0[..precision].to_vec()))
+    }
+}
+
+impl DetectionPublicKey {
+    /// Flag an output for the receiver.
+    pub fn flag<R: CryptoRng + RngCore>(&self, prng: &mut R) -> Result<DetectionFlag> {
+        if self.0.is_empty() || self.0.len() > MAX_DETECTION_PRECISION {
+            return Err(NoahError::ParameterError);
+        }
+
+        let base = RistrettoPoint::get_base();
+        let r = RistrettoScalar::random(prng);
+        let z = RistrettoScalar::random(prng);
+        let u = base.mul(&r);
+        let w = base.mul(&z);
+
+        let bits = self
+            .0
+            .iter()
+            .map(|pk| detection_bit(&u, &pk.mul(&r), &w) ^ 1)
+            .collect::<Vec<u8>>();
+
+        let m = challenge(&u, &bits);
+        let y = z.sub(&m).mul(&r.inv()?);
+
+        Ok(DetectionFlag { u, y, bits })
+    }
+}
+
+impl DetectionKey {
+    /// Return the precision of the detection key.
+    pub fn precision(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Test whether the flag may be for the wallet of the detection key.
+    pub fn test(&self, flag: &DetectionFlag) -> bool {
+        if flag.bits.len() < self.0.len() || flag.bits.iter().any(|bit| *bit > 1) {
+            return false;
+        }
+
+        let m = challenge(&flag.u, &flag.bits);
+        let w = RistrettoPoint::get_base().mul(&m).add(&flag.u.mul(&flag.y));
+
+        self.0
+            .iter()
+            .zip(flag.bits.iter())
+            .all(|(key, bit)| detection_bit(&flag.u, &flag.u.mul(key), &w) ^ bit == 1)
+    }
+
+    /// Return the indices of the memos whose flags the detection key detects.
+    pub fn detect(&self, memos: &[FlaggedOwnerMemo]) -> Vec<usize> {
+        memos
+            .iter()
+            .enumerate()
+            .filter(|(_, memo)| self.test(&memo.flag))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Compute a bit of the flag from the shared point.
+fn detection_bit(u: &RistrettoPoint, shared: &RistrettoPoint, w: &RistrettoPoint) -> u8 {
+    let mut hasher = Sha256::new();
+    hasher.update(DETECTION_BIT_DOMAIN);
+    hasher.update(u.to_compressed_bytes());
+    hasher.update(shared.to_compressed_bytes());
+    hasher.update(w.to_compressed_bytes());
+    hasher.finalize()[0] & 1
+}
+
+/// Compute the challenge that binds the bits of the flag to its commitment.
+fn challenge(u: &RistrettoPoint, bits: &[u8]) -> RistrettoScalar {
+    let mut hasher = Sha512::new();
+    hasher.update(DETECTION_CHALLENGE_DOMAIN);
+    hasher.update(u.to_compressed_bytes());
+    hasher.update(bits);
+    RistrettoScalar::from_hash(hasher)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anon_xfr::detection::{DetectionSecretKey, MAX_DETECTION_PRECISION};
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_detection_key() {
+        let mut prng = test_rng();
+        let alice = DetectionSecretKey::from_key_pair(&KeyPair::sample(&mut prng, SECP256K1));
+        let bob = DetectionSecretKey::from_key_pair(&KeyPair::sample(&mut prng, ED25519));
+
+        let alice_pk = alice.get_pk();
+        let flags = (0..64)
+            .map(|_| alice_pk.flag(&mut prng).unwrap())
+            .collect::<Vec<_>>();
+
+        // the flags for the wallet are always detected, at any precision
+        for precision in [0, 4, MAX_DETECTION_PRECISION] {
+            let dk = alice.detection_key(precision).unwrap();
+            assert_eq!(dk.precision(), precision);
+            assert!(flags.iter().all(|flag| dk.test(flag)));
+        }
+        assert!(alice.detection_key(MAX_DETECTION_PRECISION + 1).is_err());
+
+        // the flags for others are detected with probability 2^{-precision}
+        let bob_dk = bob.detection_key(MAX_DETECTION_PRECISION).unwrap();
+        assert!(flags.iter().all(|flag| !bob_dk.test(flag)));
+        let bob_dk = bob.detection_key(1).unwrap();
+        let n_false_positives = flags.iter().filter(|flag| bob_dk.test(flag)).count();
+        assert!(n_false_positives > 0 && n_false_positives < 64);
+
+        // the bits cannot be changed without breaking the challenge
+        let dk = alice.detection_key(MAX_DETECTION_PRECISION).unwrap();
+        let mut tampered = flags[0].clone();
+        tampered.bits[0] ^= 1;
+        assert!(!dk.test(&tampered));
+
+        // the derivation is deterministic
+        let key_pair = KeyPair::sample(&mut prng, SECP256K1);
+        assert_eq!(
+            DetectionSecretKey::from_key_pair(&key_pair),
+            DetectionSecretKey::from_key_pair(&key_pair)
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use crate::anon_xfr::{
+    abar_to_abar::AXfrNote,
+    asset_tracing::public_key_type_scalar,
+    commit, commit_in_cs,
+    structs::{AnonAssetRecord, OpenAnonAssetRecord, PayeeWitness},
+    AXfrPlonkPf, TurboPlonkCS,
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey, PublicKeyInner, Signature};
+use crate::parameters::params::{AXfrPCS, ProverParams, VerifierParams};
+use crate::parameters::schema::PublicInputsSchema;
+use crate::serialization::to_bincode;
+use crate::xfr::structs::AssetType;
+use merlin::Transcript;
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use noah_crypto::anemoi_jive::{AnemoiJive254, AnemoiVLHTrace};
+use noah_plonk::plonk::{
+    constraint_system::TurboCS, prover::prover_with_lagrange, verifier::verifier,
+};
+
+/// The domain separator for the disclosure of anonymous outputs, for the Plonk proof.
+const ABAR_DISCLOSURE_PLONK_PROOF_TRANSCRIPT: &[u8] = b"ABAR Disclosure Plonk Proof";
+/// The domain separator for the message signed by the discloser.
+const ABAR_DISCLOSURE_SIGNATURE_DOMAIN: &[u8] = b"ABAR Disclosure Signature";
+
+/// A field of an anonymous output that can be disclosed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AXfrDisclosedField {
+    /// The amount.
+    Amount,
+    /// The asset type.
+    AssetType,
+    /// The public key of the recipient.
+    Recipient,
+}
+
+/// The disclosed information of an output of an anonymous transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AXfrDisclosure {
+    /// The index of the output in the anonymous transfer.
+    pub output_index: u32,
+    /// The amount, if disclosed.
+    pub amount: Option<u64>,
+    /// The asset type, if disclosed.
+    pub asset_type: Option<AssetType>,
+    /// The public key of the recipient, if disclosed.
+    pub recipient: Option<PublicKey>,
+}
+
+/// A proof that discloses some fields of an output of an anonymous transfer to a third party,
+/// without revealing the blinding factor of the output or the other fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisclosureProof {
+    /// The disclosed information.
+    pub disclosure: AXfrDisclosure,
+    /// The public key of the discloser.
+    pub discloser: PublicKey,
+    /// The signature of the discloser over the note digest and the disclosure.
+    pub signature: Signature,
+    /// The proof that the disclosed fields are the ones in the output commitment.
+    pub proof: AXfrPlonkPf,
+}
+
+impl AXfrNote {
+    /// Disclose the chosen fields of one of the outputs of this note, given its opening.
+    /// The disclosure is signed by `keypair`, usually the sender, and bound to this note.
+    pub fn disclose<R: CryptoRng + RngCore, PCS: AXfrPCS>(
+        &self,
+        prng: &mut R,
+        params: &ProverParams<PCS>,
+        keypair: &KeyPair,
+        output: &OpenAnonAssetRecord,
+        fields: &[AXfrDisclosedField],
+    ) -> Result<DisclosureProof> {
+        let commitment = AnonAssetRecord::from_oabar(output).commitment;
+        let output_index = self
+            .body
+            .outputs
+            .iter()
+            .position(|abar| abar.commitment == commitment)
+            .ok_or(NoahError::ParameterError)?;
+
+        let disclosure = AXfrDisclosure {
+            output_index: output_index as u32,
+            amount: fields
+                .contains(&AXfrDisclosedField::Amount)
+                .then_some(output.get_amount()),
+            asset_type: fields
+                .contains(&AXfrDisclosedField::AssetType)
+                .then_some(output.get_asset_type()),
+            recipient: fields
+                .contains(&AXfrDisclosedField::Recipient)
+                .then_some(*output.pub_key_ref()),
+        };
+
+        let discloser = keypair.get_pk();
+        let digest = self.digest()?;
+        let signature = keypair.sign(&disclosure_message(&digest, &disclosure)?)?;
+
+        let payee = PayeeWitness {
+            amount: output.get_amount(),
+            blind: output.get_blind(),
+            asset_type: output.get_asset_type().as_scalar(),
+            public_key: *output.pub_key_ref(),
+        };
+        let (_, commitment_trace) = commit(
+            &payee.public_key,
+            payee.blind,
+            payee.amount,
+            payee.asset_type,
+        )?;
+
+        let mut transcript = disclosure_transcript(&digest, &disclosure, &discloser);
+        let (mut cs, _) =
+            build_abar_disclosure_cs(&payee, &disclosure_mask(&disclosure), &commitment_trace)?;
+        let witness = cs.get_and_clear_witness();
+        PublicInputsSchema::abar_disclosure().validate_witness(&cs, &witness)?;
+
+        let proof = prover_with_lagrange(
+            prng,
+            &mut transcript,
+            &params.pcs,
+            params.lagrange_pcs.as_ref(),
+            &params.cs,
+            &params.prover_params,
+            &witness,
+        )?;
+
+        Ok(DisclosureProof {
+            disclosure,
+            discloser,
+            signature,
+            proof,
+        })
+    }
+}
+
+/// Verify a disclosure proof for an output of the anonymous transfer note.
+pub fn verify_disclosure_proof(
+    params: &VerifierParams,
+    note: &AXfrNote,
+    disclosure_proof: &DisclosureProof,
+) -> Result<()> {
+    let disclosure = &disclosure_proof.disclosure;
+    let output = note
+        .body
+        .outputs
+        .get(disclosure.output_index as usize)
+        .ok_or(NoahError::AXfrVerificationError)?;
+
+    let digest = note.digest()?;
+    disclosure_proof.discloser.verify(
+        &disclosure_message(&digest, disclosure)?,
+        &disclosure_proof.signature,
+    )?;
+
+    let mask = disclosure_mask(disclosure);
+    let bit = |b: bool| {
+        if b {
+            BN254Scalar::one()
+        } else {
+            BN254Scalar::zero()
+        }
+    };
+
+    let mut online_inputs = vec![output.commitment];
+    online_inputs.push(bit(mask[0]));
+    online_inputs.push(BN254Scalar::from(disclosure.amount.unwrap_or_default()));
+    online_inputs.push(bit(mask[1]));
+    online_inputs.push(
+        disclosure
+            .asset_type
+            .map(|asset_type| asset_type.as_scalar())
+            .unwrap_or_default(),
+    );
+    online_inputs.push(bit(mask[2]));
+    match &disclosure.recipient {
+        Some(recipient) => {
+            online_inputs.push(public_key_type_scalar(recipient)?);
+            online_inputs.extend_from_slice(&recipient.to_bn_scalars()?);
+        }
+        None => online_inputs.extend_from_slice(&[BN254Scalar::zero(); 4]),
+    }
+    PublicInputsSchema::abar_disclosure().validate(&online_inputs)?;
+
+    let mut transcript = disclosure_transcript(&digest, disclosure, &disclosure_proof.discloser);
+    Ok(verifier(
+        &mut transcript,
+        &params.shrunk_vk,
+        &params.shrunk_cs,
+        &params.verifier_params,
+        &online_inputs,
+        &disclosure_proof.proof,
+    )?)
+}
+
+/// Return whether the amount, the asset type, and the recipient are disclosed.
+fn disclosure_mask(disclosure: &AXfrDisclosure) -> [bool; 3] {
+    [
+        disclosure.amount.is_some(),
+        disclosure.asset_type.is_some(),
+        disclosure.recipient.is_some(),
+    ]
+}
+
+/// Compute the message to be signed by the discloser.
+fn disclosure_message(digest: &[u8; 64], disclosure: &AXfrDisclosure) -> Result<Vec<u8>> {
+    let mut msg = ABAR_DISCLOSURE_SIGNATURE_DOMAIN.to_vec();
+    msg.extend_from_slice(digest);
+    msg.extend_from_slice(&to_bincode(disclosure)?);
+    Ok(msg)
+}
+
+/// Initialize the transcript of the Plonk proof, bound to the note and the discloser.
+fn disclosure_transcript(
+    digest: &[u8; 64],
+    disclosure: &AXfrDisclosure,
+    discloser: &PublicKey,
+) -> Transcript {
+    let mut transcript = Transcript::new(ABAR_DISCLOSURE_PLONK_PROOF_TRANSCRIPT);
+    transcript.append_message(b"note digest", digest);
+    transcript.append_u64(b"output index", disclosure.output_index as u64);
+    transcript.append_message(b"discloser", &discloser.noah_to_bytes());
+    transcript
+}
+
+/// Construct the constraint system proving that each disclosed field, multiplied by its mask bit,
+/// is the corresponding field in the output commitment.
+pub(crate) fn build_abar_disclosure_cs(
+    payee: &PayeeWitness,
+    mask: &[bool; 3],
+    commitment_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
+) -> Result<(TurboPlonkCS, usize)> {
+    let mut cs = TurboCS::new();
+    cs.load_anemoi_jive_parameters::<AnemoiJive254>();
+
+    let amount_var = cs.new_variable(BN254Scalar::from(payee.amount));
+    let asset_type_var = cs.new_variable(payee.asset_type);
+    let blind_var = cs.new_variable(payee.blind);
+
+    let public_key_scalars = payee.public_key.to_bn_scalars()?;
+    let public_key_scalars_vars = [
+        cs.new_variable(public_key_scalars[0]),
+        cs.new_variable(public_key_scalars[1]),
+        cs.new_variable(public_key_scalars[2]),
+    ];
+
+    let public_key_type_var = match payee.public_key.0 {
+        PublicKeyInner::Ed25519(_) => cs.new_variable(BN254Scalar::one()),
+        PublicKeyInner::Secp256k1(_) => cs.new_variable(BN254Scalar::zero()),
+        PublicKeyInner::EthAddress(_) => return Err(NoahError::ParameterError),
+    };
+    cs.insert_boolean_gate(public_key_type_var);
+
+    // prepare the public input for the output commitment
+    let commitment_var = commit_in_cs(
+        &mut cs,
+        blind_var,
+        amount_var,
+        asset_type_var,
+        public_key_type_var,
+        &public_key_scalars_vars,
+        commitment_trace,
+    );
+    cs.prepare_pi_variable(commitment_var);
+
+    // prepare the public inputs for the mask bits and the masked fields
+    let fields = [
+        vec![amount_var],
+        vec![asset_type_var],
+        vec![
+            public_key_type_var,
+            public_key_scalars_vars[0],
+            public_key_scalars_vars[1],
+            public_key_scalars_vars[2],
+        ],
+    ];
+    for (is_disclosed, field_vars) in mask.iter().zip(fields.iter()) {
+        let mask_var = if *is_disclosed {
+            cs.new_variable(BN254Scalar::one())
+        } else {
+            cs.new_variable(BN254Scalar::zero())
+        };
+        cs.insert_boolean_gate(mask_var);
+        cs.prepare_pi_variable(mask_var);
+
+        for field_var in field_vars.iter() {
+            let masked_var = cs.mul(mask_var, *field_var);
+            cs.prepare_pi_variable(masked_var);
+        }
+    }
+
+    // pad the number of constraints to power of two
+    cs.pad();
+
+    let n_constraints = cs.size;
+    Ok((cs, n_constraints))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::anon_xfr::{commit, disclosure::build_abar_disclosure_cs, structs::PayeeWitness};
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::ED25519;
+    use crate::xfr::structs::{AssetType, ASSET_TYPE_LENGTH};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    #[test]
+    fn test_abar_disclosure_cs() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, ED25519);
+
+        let payee = PayeeWitness {
+            amount: 1234,
+            blind: BN254Scalar::random(&mut prng),
+            asset_type: AssetType([5u8; ASSET_TYPE_LENGTH]).as_scalar(),
+            public_key: keypair.get_pk(),
+        };
+        let (commitment, commitment_trace) = commit(
+            &payee.public_key,
+            payee.blind,
+            payee.amount,
+            payee.asset_type,
+        )
+        .unwrap();
+
+        // disclose the amount and the recipient, but not the asset type
+        let (mut cs, _) =
+            build_abar_disclosure_cs(&payee, &[true, false, true], &commitment_trace).unwrap();
+        let witness = cs.get_and_clear_witness();
+
+        let public_key_scalars = payee.public_key.to_bn_scalars().unwrap();
+        let mut online_inputs = vec![
+            commitment,
+            BN254Scalar::one(),
+            BN254Scalar::from(payee.amount),
+            BN254Scalar::zero(),
+            BN254Scalar::zero(),
+            BN254Scalar::one(),
+            BN254Scalar::one(),
+        ];
+        online_inputs.extend_from_slice(&public_key_scalars);
+        assert!(cs.verify_witness(&witness, &online_inputs).is_ok());
+
+        // a wrong amount should be rejected
+        let mut wrong_inputs = online_inputs.clone();
+        wrong_inputs[2] = BN254Scalar::from(payee.amount + 1);
+        assert!(cs.verify_witness(&witness, &wrong_inputs).is_err());
+
+        // the hidden asset type cannot be claimed
+        let mut wrong_inputs = online_inputs;
+        wrong_inputs[4] = payee.asset_type;
+        assert!(cs.verify_witness(&witness, &wrong_inputs).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::anon_xfr::{
+    abar_to_abar::{verify_anon_xfr_note, AXfrNote},
+    FEE_TYPE,
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, Signature};
+use crate::parameters::params::VerifierParams;
+use crate::serialization::to_bincode;
+use crate::xfr::structs::BlindAssetRecord;
+use digest::{consts::U64, Digest};
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+/// The domain separator for the message signed by the fee payer.
+const AXFR_FEE_SPONSORSHIP_DOMAIN: &[u8] = b"Anon Xfr Fee Sponsorship";
+
+/// The sponsorship of the fee of an anonymous transfer by a third party.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Eq)]
+pub struct AXfrFeeSponsorship {
+    /// The transparent fee input of the fee payer.
+    pub fee_input: BlindAssetRecord,
+    /// The signature of the fee payer, bound to the digest of the sponsored note.
+    pub signature: Signature,
+}
+
+/// An anonymous transfer note whose fee is paid by a third party.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Eq)]
+pub struct SponsoredAXfrNote {
+    /// The anonymous transfer note.
+    pub note: AXfrNote,
+    /// The fee sponsorship.
+    pub sponsorship: AXfrFeeSponsorship,
+}
+
+impl AXfrFeeSponsorship {
+    /// Return the amount of fee paid by the fee payer.
+    pub fn get_fee_amount(&self) -> Result<u64> {
+        check_fee_input(&self.fee_input)?;
+        self.fee_input
+            .amount
+            .get_amount()
+            .ok_or(NoahError::ParameterError)
+    }
+}
+
+/// Check that the fee input is a transparent record of the fee type.
+fn check_fee_input(fee_input: &BlindAssetRecord) -> Result<()> {
+    if fee_input.amount.is_confidential() || fee_input.asset_type.is_confidential() {
+        return Err(NoahError::ParameterError);
+    }
+    if fee_input.asset_type.get_asset_type() != Some(FEE_TYPE) {
+        return Err(NoahError::ParameterError);
+    }
+    Ok(())
+}
+
+/// Compute the message to be signed by the fee payer.
+fn sponsorship_message(note: &AXfrNote, fee_input: &BlindAssetRecord) -> Result<Vec<u8>> {
+    let mut msg = AXFR_FEE_SPONSORSHIP_DOMAIN.to_vec();
+    msg.extend_from_slice(&note.digest()?);
+    msg.extend_from_slice(&to_bincode(fee_input)?);
+    Ok(msg)
+}
+
+/// Attach a fee sponsorship to an anonymous transfer note.
+pub fn sponsor_anon_xfr_note(
+    fee_payer: &KeyPair,
+    fee_input: &BlindAssetRecord,
+    note: AXfrNote,
+) -> Result<SponsoredAXfrNote> {
+    if fee_input.public_key != fee_payer.get_pk() {
+        return Err(NoahError::ParameterError);
+    }
+    check_fee_input(fee_input)?;
+
+    let msg = sponsorship_message(&note, fee_input)?;
+    let signature = fee_payer.sign(&msg)?;
+
+    Ok(SponsoredAXfrNote {
+        note,
+        sponsorship: AXfrFeeSponsorship {
+            fee_input: fee_input.clone(),
+            signature,
+        },
+    })
+}
+
+/// Verify an anonymous transfer note together with its fee sponsorship.
+/// The fee paid in the note and by the fee payer must cover `min_fee`.
+pub fn verify_sponsored_anon_xfr_note<D: Digest<OutputSize = U64> + Default>(
+    params: &VerifierParams,
+    sponsored_note: &SponsoredAXfrNote,
+    merkle_root: &BN254Scalar,
+    hash: D,
+    min_fee: u64,
+) -> Result<()> {
+    let SponsoredAXfrNote { note, sponsorship } = sponsored_note;
+
+    let sponsored_fee = sponsorship.get_fee_amount()?;
+    if sponsored_fee.saturating_add(note.body.fee as u64) < min_fee {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    let msg = sponsorship_message(note, &sponsorship.fee_input)?;
+    sponsorship
+        .fee_input
+        .public_key
+        .verify(&msg, &sponsorship.signature)?;
+
+    verify_anon_xfr_note(params, note, merkle_root, hash)
+}
@@ -4,7 +4,7 @@ use crate::keys::{KeyPair, PublicKey, PublicKeyInner, SecretKey};
 use crate::{
     anon_xfr::structs::{
         AccElemVars, AnonAssetRecord, AxfrOwnerMemo, MTPath, MerkleNodeVars, MerklePathVars,
-        OpenAnonAssetRecord,
+        NaryMTPath, NaryMerkleNodeVars, NaryMerklePathVars, OpenAnonAssetRecord,
     },
     xfr::structs::{AssetType, ASSET_TYPE_LENGTH},
 };
@@ -15,12 +15,19 @@ use noah_algebra::{
     collections::HashMap,
     prelude::*,
 };
-use noah_crypto::anemoi_jive::{
-    AnemoiJive, AnemoiJive254, AnemoiVLHTrace, JiveTrace, ANEMOI_JIVE_BN254_SALTS,
+use noah_crypto::{
+    anemoi_jive::{AnemoiJive, AnemoiJive254, AnemoiVLHTrace, JiveTrace, ANEMOI_JIVE_BN254_SALTS},
+    committing_aead::{
+        committing_decrypt, committing_encrypt, COMMITTING_AEAD_COMMITMENT_LEN,
+        COMMITTING_AEAD_NONCE_LEN, COMMITTING_AEAD_OVERHEAD,
+    },
 };
 use noah_plonk::{
     plonk::{
-        constraint_system::{TurboCS, VarIndex},
+        constraint_system::{
+            gadgets::{Gadget, OneHot},
+            TurboCS, VarIndex,
+        },
         indexer::PlonkPf,
     },
     poly_commit::kzg_poly_com::KZGCommitmentSchemeBN254,
@@ -34,6 +41,8 @@ use noah_algebra::{
 #[cfg(target_arch = "wasm32")]
 use {noah_plonk::plonk::prover::init_prover, wasm_bindgen::prelude::*};
 
+/// Module for burning anonymous assets with the amount and the asset type revealed.
+pub mod abar_burn;
 /// Module for general-purpose anonymous payment.
 pub mod abar_to_abar;
 /// Module for converting anonymous assets to transparent assets.
@@ -46,10 +55,31 @@ pub mod address_folding_ed25519;
 pub mod address_folding_secp256k1;
 /// Module for converting transparent assets to anonymous assets.
 pub mod ar_to_abar;
+/// Module for asset tracing of anonymous transfers.
+pub mod asset_tracing;
 /// Module for converting confidential assets to anonymous assets.
 pub mod bar_to_abar;
+/// Module for coinjoins of anonymous assets among several parties, through a coordinator.
+pub mod coinjoin;
+/// Module for detection keys, with which an untrusted server finds the outputs of a wallet.
+pub mod detection;
+/// Module for disclosing the outputs of anonymous transfers to third parties.
+pub mod disclosure;
+/// Module for third-party sponsorship of the fee of anonymous transfers.
+pub mod fee_sponsorship;
+/// Module for non-fungible anonymous records.
+pub mod nft;
+/// Module for standalone proofs about anonymous records.
+pub mod proofs;
+/// Module for scanning blocks for the anonymous records of a wallet.
+pub mod scan;
 /// Module for shared structures.
 pub mod structs;
+/// Module for atomic swaps between two anonymous parties.
+pub mod swap;
+/// Module for the threshold derivation of the nullifiers of anonymous assets, among
+/// semi-honest custodians.
+pub mod threshold;
 
 /// The asset type for FRA.
 const ASSET_TYPE_FRA: AssetType = AssetType([0; ASSET_TYPE_LENGTH]);
@@ -59,7 +89,30 @@ pub const FEE_TYPE: AssetType = ASSET_TYPE_FRA;
 pub const TWO_POW_32: u64 = 1 << 32;
 /// Restricting the maximum size of memo to 121.
 pub const MAX_AXFR_MEMO_SIZE: usize = 121;
-
+/// The prefix of the owner memos encrypted with `axfr_committing_encrypt`.
+pub const AXFR_COMMITTING_MEMO_PREFIX: [u8; 4] = [0xff, b'N', b'M', 2];
+/// Restricting the maximum size of a key-committing memo, which adds the prefix, the nonce,
+/// and the key commitment to a memo of `MAX_AXFR_MEMO_SIZE`.
+pub const MAX_AXFR_COMMITTING_MEMO_SIZE: usize = MAX_AXFR_MEMO_SIZE
+    + AXFR_COMMITTING_MEMO_PREFIX.len()
+    + COMMITTING_AEAD_NONCE_LEN
+    + COMMITTING_AEAD_COMMITMENT_LEN;
+/// The domain separator of the key of the key-committing memos.
+const AXFR_COMMITTING_MEMO_DOMAIN: &[u8] = b"Noah AXfr Committing Memo";
+/// Restricting the maximum size of the application-defined payload of an output.
+pub const MAX_AXFR_OUTPUT_PAYLOAD_SIZE: usize = 256;
+/// The prefix of the encrypted payloads of the outputs.
+pub const AXFR_OUTPUT_PAYLOAD_PREFIX: [u8; 4] = [0xff, b'N', b'P', 1];
+/// Restricting the maximum size of an encrypted payload, which adds the prefix, the ephemeral
+/// share, and the overhead of the key-committing encryption.
+pub const MAX_AXFR_ENCRYPTED_PAYLOAD_SIZE: usize = MAX_AXFR_OUTPUT_PAYLOAD_SIZE
+    + AXFR_OUTPUT_PAYLOAD_PREFIX.len()
+    + SECP256K1G1::COMPRESSED_LEN
+    + COMMITTING_AEAD_OVERHEAD;
+/// The domain separator of the key of the encrypted payloads of the outputs.
+const AXFR_OUTPUT_PAYLOAD_DOMAIN: &[u8] = b"Noah AXfr Output Payload";
+
+/// The constraint system of the circuits of the anonymous transfers, which are over BN254 only.
 pub(crate) type TurboPlonkCS = TurboCS<BN254Scalar>;
 
 use crate::parameters::params::AddressFormat;
@@ -76,6 +129,16 @@ pub enum AXfrAddressFoldingInstance {
     Ed25519(address_folding_ed25519::AXfrAddressFoldingInstanceEd25519),
 }
 
+impl AXfrAddressFoldingInstance {
+    /// Get the format type of the address.
+    pub fn get_address_format(&self) -> AddressFormat {
+        match self {
+            Self::Secp256k1(_) => AddressFormat::SECP256K1,
+            Self::Ed25519(_) => AddressFormat::ED25519,
+        }
+    }
+}
+
 /// The witness for address folding.
 pub enum AXfrAddressFoldingWitness {
     /// The Secp256k1 witness for address folding.
@@ -381,25 +444,16 @@ pub fn add_merkle_path_variables(cs: &mut TurboPlonkCS, path: MTPath) -> MerkleP
             is_right_child: cs.new_variable(BN254Scalar::from(node.is_right_child as u32)),
         })
         .collect();
-    // Boolean-constrain `is_left_child` and `is_right_child`
+    // Constrain exactly one of `is_left_child`, `is_mid_child`, and `is_right_child` to be one
     for node_var in path_vars.iter() {
-        let zero = BN254Scalar::zero();
-        let one = BN254Scalar::one();
-        cs.push_add_selectors(zero, one, one, one);
-        cs.push_mul_selectors(zero, zero);
-        cs.push_constant_selector(one.neg());
-        cs.push_ecc_selector(zero);
-        cs.push_out_selector(zero);
-
-        let zero_var = cs.zero_var();
-        cs.wiring[0].push(zero_var);
-        cs.wiring[1].push(node_var.is_left_child);
-        cs.wiring[2].push(node_var.is_mid_child);
-        cs.wiring[3].push(node_var.is_right_child);
-        cs.wiring[4].push(zero_var);
-        cs.finish_new_gate();
-
-        cs.attach_boolean_constraint_to_gate();
+        OneHot {
+            bits: [
+                node_var.is_left_child,
+                node_var.is_mid_child,
+                node_var.is_right_child,
+            ],
+        }
+        .build(cs);
     }
 
     MerklePathVars { nodes: path_vars }
@@ -494,6 +548,97 @@ pub fn compute_merkle_root_variables(
     node_var
 }
 
+/// Add the constraints of a path of a Merkle tree with four or eight children per node
+/// to the constraint system.
+pub fn add_nary_merkle_path_variables(
+    cs: &mut TurboPlonkCS,
+    path: NaryMTPath,
+) -> NaryMerklePathVars {
+    let one = BN254Scalar::one();
+    let zero_var = cs.zero_var();
+
+    let nodes = path
+        .nodes
+        .into_iter()
+        .map(|node| {
+            let children: Vec<VarIndex> = node
+                .children
+                .iter()
+                .map(|child| cs.new_variable(*child))
+                .collect();
+            let is_child: Vec<VarIndex> = (0..node.children.len())
+                .map(|i| {
+                    let bit = cs.new_variable(BN254Scalar::from((i == node.position) as u32));
+                    cs.insert_boolean_gate(bit);
+                    bit
+                })
+                .collect();
+
+            // Constrain exactly one of the bits to be one
+            let mut sum_var = zero_var;
+            for chunk in is_child.chunks(3) {
+                let mut wires = [sum_var, zero_var, zero_var, zero_var];
+                wires[1..=chunk.len()].copy_from_slice(chunk);
+                sum_var = cs.linear_combine(&wires, one, one, one, one);
+            }
+            cs.insert_constant_gate(sum_var, one);
+
+            NaryMerkleNodeVars { children, is_child }
+        })
+        .collect();
+
+    NaryMerklePathVars { nodes }
+}
+
+/// Compute the root of a Merkle tree with four or eight children per node, where the node
+/// at each level is the Anemoi-Jive variable-length hash of the salt of the level and the children.
+pub fn compute_nary_merkle_root_variables(
+    cs: &mut TurboPlonkCS,
+    elem: AccElemVars,
+    path_vars: &NaryMerklePathVars,
+) -> VarIndex {
+    let (uid, commitment) = (elem.uid, elem.commitment);
+    let zero = BN254Scalar::zero();
+    let one = BN254Scalar::one();
+    let zero_var = cs.zero_var();
+
+    let leaf_trace = AnemoiJive254::eval_variable_length_hash_with_trace(&[
+        cs.witness[uid],
+        cs.witness[commitment],
+    ]);
+    let mut node_var = cs.new_variable(leaf_trace.output);
+    cs.anemoi_variable_length_hash::<AnemoiJive254>(&leaf_trace, &[uid, commitment], node_var);
+    for (idx, path_node) in path_vars.nodes.iter().enumerate() {
+        // The child at the position is the present node: is_child * child - is_child * present = 0
+        for (child, is_child) in path_node.children.iter().zip(path_node.is_child.iter()) {
+            cs.push_add_selectors(zero, zero, zero, zero);
+            cs.push_mul_selectors(one, one.neg());
+            cs.push_constant_selector(zero);
+            cs.push_ecc_selector(zero);
+            cs.push_out_selector(zero);
+
+            cs.wiring[0].push(*is_child);
+            cs.wiring[1].push(*child);
+            cs.wiring[2].push(*is_child);
+            cs.wiring[3].push(node_var);
+            cs.wiring[4].push(zero_var);
+            cs.finish_new_gate();
+        }
+
+        let salt_var = cs.new_variable(ANEMOI_JIVE_BN254_SALTS[idx]);
+        cs.insert_constant_gate(salt_var, ANEMOI_JIVE_BN254_SALTS[idx]);
+
+        let mut input_var = vec![salt_var];
+        input_var.extend_from_slice(&path_node.children);
+        let input: Vec<BN254Scalar> = input_var.iter().map(|var| cs.witness[*var]).collect();
+        let trace = AnemoiJive254::eval_variable_length_hash_with_trace(&input);
+
+        node_var = cs.new_variable(trace.output);
+        cs.anemoi_variable_length_hash::<AnemoiJive254>(&trace, &input_var, node_var);
+    }
+    node_var
+}
+
 #[cfg(target_arch = "wasm32")]
 /// Init anon xfr
 pub async fn init_anon_xfr() -> core::result::Result<(), JsValue> {
@@ -506,37 +651,10 @@ pub fn axfr_hybrid_encrypt<R: CryptoRng + RngCore>(
     prng: &mut R,
     msg: &[u8],
 ) -> Result<Vec<u8>> {
-    let (mut bytes, hasher) = match pk.0 {
-        PublicKeyInner::Ed25519(_) => {
-            let pk = pk.to_ed25519()?;
-
-            let share_scalar = Ed25519Scalar::random(prng);
-            let share = Ed25519Point::get_base().mul(&share_scalar);
-
-            let bytes = share.to_compressed_bytes();
-
-            let dh = pk.mul(&share_scalar);
-
-            let mut hasher = sha2::Sha512::new();
-            hasher.update(&dh.to_compressed_bytes());
-            (bytes, hasher)
-        }
-        PublicKeyInner::Secp256k1(_) => {
-            let pk = pk.to_secp256k1()?;
-
-            let share_scalar = SECP256K1Scalar::random(prng);
-            let share = SECP256K1G1::get_base().mul(&share_scalar);
-
-            let bytes = share.to_compressed_bytes();
-            let dh = pk.mul(&share_scalar);
-
-            let mut hasher = sha2::Sha512::new();
-            hasher.update(&dh.to_compressed_bytes());
-            (bytes, hasher)
-        }
-        PublicKeyInner::EthAddress(_) => panic!("EthAddress not supported"),
-    };
+    let (mut bytes, dh) = axfr_dh_encap(pk, prng)?;
 
+    let mut hasher = sha2::Sha512::new();
+    hasher.update(&dh);
     let mut key = [0u8; 32];
     key.copy_from_slice(&hasher.finalize().as_slice()[0..32]);
 
@@ -568,37 +686,10 @@ pub fn axfr_hybrid_encrypt<R: CryptoRng + RngCore>(
 
 /// Hybrid decryption
 pub fn axfr_hybrid_decrypt(sk: &SecretKey, ctext: &[u8]) -> Result<Vec<u8>> {
-    let (share_len, hasher) = match sk {
-        SecretKey::Ed25519(_) => {
-            let sk = sk.to_ed25519()?;
-
-            let share_len = Ed25519Point::COMPRESSED_LEN;
-            if ctext.len() < share_len {
-                return Err(NoahError::DecryptionError);
-            }
-            let share = Ed25519Point::from_compressed_bytes(&ctext[..share_len])?;
-            let dh = share.mul(&sk);
-
-            let mut hasher = sha2::Sha512::new();
-            hasher.update(&dh.to_compressed_bytes());
-            (share_len, hasher)
-        }
-        SecretKey::Secp256k1(_) => {
-            let sk = sk.to_secp256k1()?;
-
-            let share_len = SECP256K1G1::COMPRESSED_LEN;
-            if ctext.len() < share_len {
-                return Err(NoahError::DecryptionError);
-            }
-            let share = SECP256K1G1::from_compressed_bytes(&ctext[..share_len])?;
-            let dh = share.mul(&sk);
-
-            let mut hasher = sha2::Sha512::new();
-            hasher.update(&dh.to_compressed_bytes());
-            (share_len, hasher)
-        }
-    };
+    let (share_len, dh) = axfr_dh_decap(sk, ctext)?;
 
+    let mut hasher = sha2::Sha512::new();
+    hasher.update(&dh);
     let mut key = [0u8; 32];
     key.copy_from_slice(&hasher.finalize().as_slice()[0..32]);
 
@@ -625,3 +716,217 @@ pub fn axfr_hybrid_decrypt(sk: &SecretKey, ctext: &[u8]) -> Result<Vec<u8>> {
     };
     Ok(res)
 }
+
+/// Hybrid encryption with XChaCha20Poly1305 and a commitment to the key, so that the
+/// ciphertext decrypts under a single key. The ciphertext starts with
+/// `AXFR_COMMITTING_MEMO_PREFIX`, followed by the ephemeral share.
+pub fn axfr_committing_encrypt<R: CryptoRng + RngCore>(
+    pk: &PublicKey,
+    prng: &mut R,
+    msg: &[u8],
+) -> Result<Vec<u8>> {
+    axfr_committing_encrypt_with(
+        &AXFR_COMMITTING_MEMO_PREFIX,
+        AXFR_COMMITTING_MEMO_DOMAIN,
+        pk,
+        prng,
+        msg,
+    )
+}
+
+/// Decrypt the output of `axfr_committing_encrypt`.
+pub fn axfr_committing_decrypt(sk: &SecretKey, ctext: &[u8]) -> Result<Vec<u8>> {
+    axfr_committing_decrypt_with(
+        &AXFR_COMMITTING_MEMO_PREFIX,
+        AXFR_COMMITTING_MEMO_DOMAIN,
+        sk,
+        ctext,
+    )
+}
+
+/// Encrypt an application-defined payload of an output as `axfr_committing_encrypt`, with
+/// `AXFR_OUTPUT_PAYLOAD_PREFIX` and a separate key, so that a payload and a memo cannot be
+/// swapped.
+pub fn axfr_payload_encrypt<R: CryptoRng + RngCore>(
+    pk: &PublicKey,
+    prng: &mut R,
+    msg: &[u8],
+) -> Result<Vec<u8>> {
+    axfr_committing_encrypt_with(
+        &AXFR_OUTPUT_PAYLOAD_PREFIX,
+        AXFR_OUTPUT_PAYLOAD_DOMAIN,
+        pk,
+        prng,
+        msg,
+    )
+}
+
+/// Decrypt the output of `axfr_payload_encrypt`.
+pub fn axfr_payload_decrypt(sk: &SecretKey, ctext: &[u8]) -> Result<Vec<u8>> {
+    axfr_committing_decrypt_with(
+        &AXFR_OUTPUT_PAYLOAD_PREFIX,
+        AXFR_OUTPUT_PAYLOAD_DOMAIN,
+        sk,
+        ctext,
+    )
+}
+
+fn axfr_committing_encrypt_with<R: CryptoRng + RngCore>(
+    prefix: &[u8],
+    domain: &[u8],
+    pk: &PublicKey,
+    prng: &mut R,
+    msg: &[u8],
+) -> Result<Vec<u8>> {
+    let (share, dh) = axfr_dh_encap(pk, prng)?;
+    let key = axfr_committing_key(domain, &share, &dh);
+
+    let mut bytes = prefix.to_vec();
+    bytes.extend_from_slice(&share);
+    let mut ctext =
+        committing_encrypt(prng, &key, &bytes, msg).map_err(|_| NoahError::EncryptionError)?;
+    bytes.append(&mut ctext);
+    Ok(bytes)
+}
+
+fn axfr_committing_decrypt_with(
+    prefix: &[u8],
+    domain: &[u8],
+    sk: &SecretKey,
+    ctext: &[u8],
+) -> Result<Vec<u8>> {
+    if !ctext.starts_with(prefix) {
+        return Err(NoahError::DecryptionError);
+    }
+    let prefix_len = prefix.len();
+    let (share_len, dh) = axfr_dh_decap(sk, &ctext[prefix_len..])?;
+    let (aad, ctext) = ctext.split_at(prefix_len + share_len);
+
+    let key = axfr_committing_key(domain, &aad[prefix_len..], &dh);
+    committing_decrypt(&key, aad, ctext).map_err(|_| NoahError::DecryptionError)
+}
+
+/// Derive the key of the key-committing encryption from the share and the Diffie-Hellman point.
+fn axfr_committing_key(domain: &[u8], share: &[u8], dh: &[u8]) -> [u8; 32] {
+    let mut hasher = sha2::Sha512::new();
+    hasher.update(domain);
+    hasher.update(share);
+    hasher.update(dh);
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&hasher.finalize().as_slice()[0..32]);
+    key
+}
+
+/// Sample an ephemeral share, and return it and the Diffie-Hellman point with the public key,
+/// both compressed.
+fn axfr_dh_encap<R: CryptoRng + RngCore>(
+    pk: &PublicKey,
+    prng: &mut R,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    match pk.0 {
+        PublicKeyInner::Ed25519(_) => {
+            let pk = pk.to_ed25519()?;
+
+            let share_scalar = Ed25519Scalar::random(prng);
+            let share = Ed25519Point::get_base().mul(&share_scalar);
+            let dh = pk.mul(&share_scalar);
+            Ok((share.to_compressed_bytes(), dh.to_compressed_bytes()))
+        }
+        PublicKeyInner::Secp256k1(_) => {
+            let pk = pk.to_secp256k1()?;
+
+            let share_scalar = SECP256K1Scalar::random(prng);
+            let share = SECP256K1G1::get_base().mul(&share_scalar);
+            let dh = pk.mul(&share_scalar);
+            Ok((share.to_compressed_bytes(), dh.to_compressed_bytes()))
+        }
+        PublicKeyInner::EthAddress(_) => Err(NoahError::ParameterError),
+    }
+}
+
+/// Read the ephemeral share at the start of the ciphertext, and return its length and the
+/// compressed Diffie-Hellman point with the secret key.
+fn axfr_dh_decap(sk: &SecretKey, ctext: &[u8]) -> Result<(usize, Vec<u8>)> {
+    match sk {
+        SecretKey::Ed25519(_) => {
+            let sk = sk.to_ed25519()?;
+
+            let share_len = Ed25519Point::COMPRESSED_LEN;
+            if ctext.len() < share_len {
+                return Err(NoahError::DecryptionError);
+            }
+            let share = Ed25519Point::from_compressed_bytes(&ctext[..share_len])?;
+            Ok((share_len, share.mul(&sk).to_compressed_bytes()))
+        }
+        SecretKey::Secp256k1(_) => {
+            let sk = sk.to_secp256k1()?;
+
+            let share_len = SECP256K1G1::COMPRESSED_LEN;
+            if ctext.len() < share_len {
+                return Err(NoahError::DecryptionError);
+            }
+            let share = SECP256K1G1::from_compressed_bytes(&ctext[..share_len])?;
+            Ok((share_len, share.mul(&sk).to_compressed_bytes()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anon_xfr::{
+        add_nary_merkle_path_variables, compute_nary_merkle_root_variables,
+        structs::{AccElemVars, NaryMTNode, NaryMTPath},
+        TurboPlonkCS,
+    };
+    use noah_accumulators::nary_merkle_tree::NaryMerkleTree;
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+    use noah_crypto::anemoi_jive::{AnemoiJive, AnemoiJive254};
+
+    #[test]
+    fn test_nary_merkle_root_variables() {
+        let mut prng = test_rng();
+        for (arity, depth) in [(4, 3), (8, 2)] {
+            let mut tree = NaryMerkleTree::new(arity, depth).unwrap();
+            let commitments = (0..arity + 1)
+                .map(|_| BN254Scalar::random(&mut prng))
+                .collect::<Vec<_>>();
+            for (uid, commitment) in commitments.iter().enumerate() {
+                let leaf = AnemoiJive254::eval_variable_length_hash(&[
+                    BN254Scalar::from(uid as u64),
+                    *commitment,
+                ]);
+                tree.add_commitment_hash(leaf).unwrap();
+            }
+
+            let uid = arity;
+            let proof = tree.generate_proof(uid as u64).unwrap();
+            let path = NaryMTPath::new(
+                proof
+                    .nodes
+                    .iter()
+                    .map(|node| NaryMTNode {
+                        children: node.children.clone(),
+                        position: node.position,
+                    })
+                    .collect(),
+            );
+
+            let mut cs = TurboPlonkCS::new();
+            let elem = AccElemVars {
+                uid: cs.new_variable(BN254Scalar::from(uid as u64)),
+                commitment: cs.new_variable(commitments[uid]),
+            };
+            let path_vars = add_nary_merkle_path_variables(&mut cs, path);
+            let position = path_vars.nodes[0].is_child[1];
+            let root_var = compute_nary_merkle_root_variables(&mut cs, elem, &path_vars);
+            assert_eq!(cs.witness[root_var], tree.get_root());
+
+            let mut witness = cs.get_and_clear_witness();
+            assert!(cs.verify_witness(&witness, &[]).is_ok());
+
+            // the node on the path is not at the claimed position
+            witness[position] = BN254Scalar::one();
+            assert!(cs.verify_witness(&witness, &[]).is_err());
+        }
+    }
+}
@@ -0,0 +1,784 @@
+use crate::anon_xfr::address_folding_ed25519::{
+    create_address_folding_ed25519, prepare_verifier_input_ed25519,
+    prove_address_folding_in_cs_ed25519, verify_address_folding_ed25519,
+};
+use crate::anon_xfr::address_folding_secp256k1::{
+    create_address_folding_secp256k1, prepare_verifier_input_secp256k1,
+    prove_address_folding_in_cs_secp256k1, verify_address_folding_secp256k1,
+};
+use crate::anon_xfr::{
+    abar_to_abar::add_payers_witnesses,
+    ar_to_abar::{verify_ar_to_abar_note, ArToAbarNote},
+    asset_tracing::public_key_type_scalar,
+    axfr_hybrid_encrypt, commit, commit_in_cs, compute_merkle_root_variables,
+    disclosure::build_abar_disclosure_cs,
+    nullify, nullify_in_cs,
+    structs::{
+        AccElemVars, AnonAssetRecord, AxfrOwnerMemo, Commitment, Nullifier, OpenAnonAssetRecord,
+        PayeeWitness, PayerWitness,
+    },
+    AXfrAddressFoldingInstance, AXfrAddressFoldingWitness, AXfrPlonkPf, TurboPlonkCS,
+    MAX_AXFR_MEMO_SIZE,
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey, SecretKey};
+use crate::parameters::params::{AXfrPCS, ProverParams, VerifierParams};
+use crate::parameters::schema::PublicInputsSchema;
+use crate::xfr::structs::AssetType;
+use digest::{consts::U64, Digest};
+use merlin::Transcript;
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use noah_crypto::anemoi_jive::{
+    AnemoiJive, AnemoiJive254, AnemoiVLHTrace, ANEMOI_JIVE_BN254_SALTS,
+};
+use noah_plonk::plonk::{
+    constraint_system::TurboCS, prover::prover_with_lagrange, verifier::verifier,
+};
+use sha2::{Sha256, Sha512};
+
+/// The domain separator for the transfer of non-fungible records, for the Plonk proof.
+const NFT_TRANSFER_PLONK_PROOF_TRANSCRIPT: &[u8] = b"NFT Transfer Plonk Proof";
+
+/// The domain separator for the transfer of non-fungible records, for address folding.
+const NFT_TRANSFER_FOLDING_PROOF_TRANSCRIPT: &[u8] = b"NFT Transfer Folding Proof";
+
+/// The domain separator of the mint tags.
+const NFT_MINT_TAG_DOMAIN: &[u8] = b"Noah NFT Mint Tag";
+
+/// The domain separator for the listings of non-fungible records, for the Plonk proof.
+const NFT_LISTING_PLONK_PROOF_TRANSCRIPT: &[u8] = b"NFT Listing Plonk Proof";
+
+/// The domain separator of the token IDs derived from the encrypted metadata.
+const NFT_METADATA_DOMAIN: &[u8] = b"Noah NFT Metadata";
+
+/// The amount of a non-fungible record.
+pub const NFT_AMOUNT: u64 = 1;
+
+/// The tag of a minted token, which the ledger records, like a nullifier, to reject
+/// a second mint of the same token.
+pub type NftMintTag = BN254Scalar;
+
+/// Compute the mint tag of the token, whose unique ID is the asset type of its record.
+pub fn nft_mint_tag(token_id: &AssetType) -> NftMintTag {
+    let mut hasher = Sha512::new();
+    hasher.update(NFT_MINT_TAG_DOMAIN);
+    hasher.update(token_id.0);
+    BN254Scalar::from_hash(hasher)
+}
+
+/// Verify a transparent-to-anonymous note that mints a non-fungible record, i.e.,
+/// one that converts a single unit of the token, and return the mint tag of the token.
+/// The caller rejects the note if the tag has already been recorded.
+pub fn verify_nft_mint_note(params: &VerifierParams, note: &ArToAbarNote) -> Result<NftMintTag> {
+    let amount = note.body.input.amount.get_amount();
+    let token_id = note.body.input.asset_type.get_asset_type();
+    let token_id = match (amount, token_id) {
+        (Some(NFT_AMOUNT), Some(token_id)) => token_id,
+        _ => return Err(NoahError::ParameterError),
+    };
+    verify_ar_to_abar_note(params, note)?;
+    Ok(nft_mint_tag(&token_id))
+}
+
+/// The note that transfers a non-fungible record anonymously.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NftTransferNote {
+    /// The body part of the transfer.
+    pub body: NftTransferBody,
+    /// The Plonk proof (assuming non-malleability).
+    pub proof: AXfrPlonkPf,
+    /// The address folding instance.
+    pub folding_instance: AXfrAddressFoldingInstance,
+}
+
+/// The note that transfers a non-fungible record anonymously, without proof.
+#[derive(Clone, Debug)]
+pub struct NftTransferPreNote {
+    /// The body part of the transfer.
+    pub body: NftTransferBody,
+    /// The witness of the input.
+    pub payer_witness: PayerWitness,
+    /// The witness of the output.
+    pub payee_witness: PayeeWitness,
+    /// The trace of the input commitment.
+    pub input_commitment_trace: AnemoiVLHTrace<BN254Scalar, 2, 14>,
+    /// The trace of the output commitment.
+    pub output_commitment_trace: AnemoiVLHTrace<BN254Scalar, 2, 14>,
+    /// The trace of the nullifier.
+    pub nullifier_trace: AnemoiVLHTrace<BN254Scalar, 2, 14>,
+    /// Input key pair.
+    pub input_keypair: KeyPair,
+}
+
+/// The body of the transfer of a non-fungible record.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NftTransferBody {
+    /// The nullifier of the input record.
+    pub input: Nullifier,
+    /// The output record, of the same token.
+    pub output: AnonAssetRecord,
+    /// The Merkle root hash.
+    pub merkle_root: BN254Scalar,
+    /// The Merkle root version.
+    pub merkle_root_version: u64,
+    /// The owner memo of the output.
+    pub owner_memo: AxfrOwnerMemo,
+}
+
+/// Build the transfer of a non-fungible record without generating the proof.
+/// Both records carry the amount of one and the same asset type, the ID of the token.
+pub fn init_nft_transfer_note(
+    input: &OpenAnonAssetRecord,
+    output: &OpenAnonAssetRecord,
+    input_keypair: &KeyPair,
+) -> Result<NftTransferPreNote> {
+    if input.mt_leaf_info.is_none() || input_keypair.get_pk() != input.pub_key {
+        return Err(NoahError::ParameterError);
+    }
+    if input.amount != NFT_AMOUNT
+        || output.amount != NFT_AMOUNT
+        || input.asset_type != output.asset_type
+    {
+        return Err(NoahError::ParameterError);
+    }
+    let owner_memo = output.owner_memo.clone().ok_or(NoahError::ParameterError)?;
+
+    let mt_leaf_info = input.mt_leaf_info.as_ref().unwrap();
+    let (nullifier, nullifier_trace) = nullify(
+        input_keypair,
+        input.amount,
+        input.asset_type.as_scalar(),
+        mt_leaf_info.uid,
+    )?;
+    let (_, input_commitment_trace) = commit(
+        &input_keypair.get_pk(),
+        input.blind,
+        input.amount,
+        input.asset_type.as_scalar(),
+    )?;
+    let (_, output_commitment_trace) = commit(
+        &output.pub_key,
+        output.blind,
+        output.amount,
+        output.asset_type.as_scalar(),
+    )?;
+
+    let payer_witness = PayerWitness {
+        secret_key: input_keypair.get_sk(),
+        uid: mt_leaf_info.uid,
+        amount: input.amount,
+        asset_type: input.asset_type.as_scalar(),
+        path: mt_leaf_info.path.clone(),
+        blind: input.blind,
+    };
+    let payee_witness = PayeeWitness {
+        amount: output.amount,
+        blind: output.blind,
+        asset_type: output.asset_type.as_scalar(),
+        public_key: output.pub_key,
+    };
+
+    let body = NftTransferBody {
+        input: nullifier,
+        output: AnonAssetRecord::from_oabar(output),
+        merkle_root: mt_leaf_info.root,
+        merkle_root_version: mt_leaf_info.root_version,
+        owner_memo,
+    };
+
+    Ok(NftTransferPreNote {
+        body,
+        payer_witness,
+        payee_witness,
+        input_commitment_trace,
+        output_commitment_trace,
+        nullifier_trace,
+        input_keypair: input_keypair.clone(),
+    })
+}
+
+/// Finalize the transfer of a non-fungible record.
+pub fn finish_nft_transfer_note<
+    R: CryptoRng + RngCore,
+    D: Digest<OutputSize = U64> + Default,
+    PCS: AXfrPCS,
+>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    pre_note: NftTransferPreNote,
+    hash: D,
+) -> Result<NftTransferNote> {
+    let NftTransferPreNote {
+        body,
+        payer_witness,
+        payee_witness,
+        input_commitment_trace,
+        output_commitment_trace,
+        nullifier_trace,
+        input_keypair,
+    } = pre_note;
+
+    let mut transcript = Transcript::new(NFT_TRANSFER_FOLDING_PROOF_TRANSCRIPT);
+
+    let (folding_instance, folding_witness) = match input_keypair.get_sk_ref() {
+        SecretKey::Secp256k1(_) => {
+            let (folding_instance, folding_witness) =
+                create_address_folding_secp256k1(prng, hash, &mut transcript, &input_keypair)?;
+            (
+                AXfrAddressFoldingInstance::Secp256k1(folding_instance),
+                AXfrAddressFoldingWitness::Secp256k1(folding_witness),
+            )
+        }
+        SecretKey::Ed25519(_) => {
+            let (folding_instance, folding_witness) =
+                create_address_folding_ed25519(prng, hash, &mut transcript, &input_keypair)?;
+            (
+                AXfrAddressFoldingInstance::Ed25519(folding_instance),
+                AXfrAddressFoldingWitness::Ed25519(folding_witness),
+            )
+        }
+    };
+
+    let mut transcript = Transcript::new(NFT_TRANSFER_PLONK_PROOF_TRANSCRIPT);
+    let (mut cs, _) = build_nft_transfer_cs(
+        &payer_witness,
+        &payee_witness,
+        &nullifier_trace,
+        &input_commitment_trace,
+        &output_commitment_trace,
+        &folding_witness,
+    );
+    let witness = cs.get_and_clear_witness();
+    PublicInputsSchema::nft_transfer(folding_witness.get_address_format())
+        .validate_witness(&cs, &witness)?;
+
+    let proof = prover_with_lagrange(
+        prng,
+        &mut transcript,
+        &params.pcs,
+        params.lagrange_pcs.as_ref(),
+        &params.cs,
+        &params.prover_params,
+        &witness,
+    )?;
+
+    Ok(NftTransferNote {
+        body,
+        proof,
+        folding_instance,
+    })
+}
+
+/// Verify the transfer of a non-fungible record.
+pub fn verify_nft_transfer_note<D: Digest<OutputSize = U64> + Default>(
+    params: &VerifierParams,
+    note: &NftTransferNote,
+    merkle_root: &BN254Scalar,
+    hash: D,
+) -> Result<()> {
+    if note.body.owner_memo.size() > MAX_AXFR_MEMO_SIZE {
+        return Err(NoahError::AXfrVerificationError);
+    }
+    if *merkle_root != note.body.merkle_root {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    let mut transcript = Transcript::new(NFT_TRANSFER_FOLDING_PROOF_TRANSCRIPT);
+
+    let address_folding_public_input = match &note.folding_instance {
+        AXfrAddressFoldingInstance::Secp256k1(a) => {
+            let (beta, lambda) = verify_address_folding_secp256k1(hash, &mut transcript, a)?;
+            prepare_verifier_input_secp256k1(a, &beta, &lambda)
+        }
+        AXfrAddressFoldingInstance::Ed25519(a) => {
+            let (beta, lambda) = verify_address_folding_ed25519(hash, &mut transcript, a)?;
+            prepare_verifier_input_ed25519(a, &beta, &lambda)
+        }
+    };
+
+    let mut transcript = Transcript::new(NFT_TRANSFER_PLONK_PROOF_TRANSCRIPT);
+    let mut online_inputs = vec![note.body.input, *merkle_root, note.body.output.commitment];
+    online_inputs.extend_from_slice(&address_folding_public_input);
+    PublicInputsSchema::nft_transfer(note.folding_instance.get_address_format())
+        .validate(&online_inputs)?;
+
+    Ok(verifier(
+        &mut transcript,
+        &params.shrunk_vk,
+        &params.shrunk_cs,
+        &params.verifier_params,
+        &online_inputs,
+        &note.proof,
+    )?)
+}
+
+/// Construct the constraint system for the transfer of a non-fungible record, from one input
+/// to one output. The amounts are fixed to one and the output keeps the asset type of the input,
+/// so the token is neither split nor duplicated.
+pub fn build_nft_transfer_cs(
+    payer_witness: &PayerWitness,
+    payee_witness: &PayeeWitness,
+    nullifier_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
+    input_commitment_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
+    output_commitment_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
+    folding_witness: &AXfrAddressFoldingWitness,
+) -> (TurboPlonkCS, usize) {
+    let mut cs = TurboCS::new();
+
+    cs.load_anemoi_jive_parameters::<AnemoiJive254>();
+
+    let payers_witnesses_vars = add_payers_witnesses(&mut cs, &[payer_witness]);
+    let payer_witness_var = &payers_witnesses_vars[0];
+
+    let keypair = folding_witness.keypair();
+    let public_key_scalars = keypair.get_pk().to_bn_scalars().unwrap();
+    let secret_key_scalars = keypair.get_sk().to_bn_scalars().unwrap();
+
+    let public_key_scalars_vars = [
+        cs.new_variable(public_key_scalars[0]),
+        cs.new_variable(public_key_scalars[1]),
+        cs.new_variable(public_key_scalars[2]),
+    ];
+    let secret_key_scalars_vars = [
+        cs.new_variable(secret_key_scalars[0]),
+        cs.new_variable(secret_key_scalars[1]),
+    ];
+
+    let pow_2_64 = BN254Scalar::from(u64::MAX).add(&BN254Scalar::one());
+    let zero = BN254Scalar::zero();
+    let one = BN254Scalar::one();
+    let zero_var = cs.zero_var();
+
+    let key_type = match keypair.get_sk() {
+        SecretKey::Ed25519(_) => cs.new_variable(BN254Scalar::one()),
+        SecretKey::Secp256k1(_) => cs.new_variable(BN254Scalar::zero()),
+    };
+
+    // the input is a single unit of the token
+    cs.insert_constant_gate(payer_witness_var.amount, BN254Scalar::from(NFT_AMOUNT));
+
+    // commitments
+    let com_abar_in_var = commit_in_cs(
+        &mut cs,
+        payer_witness_var.blind,
+        payer_witness_var.amount,
+        payer_witness_var.asset_type,
+        key_type,
+        &public_key_scalars_vars,
+        input_commitment_trace,
+    );
+
+    // prove pre-image of the nullifier
+    // 0 <= `amount` < 2^64, so we can encode (`uid`||`amount`) to `uid` * 2^64 + `amount`
+    let uid_amount = cs.linear_combine(
+        &[
+            payer_witness_var.uid,
+            payer_witness_var.amount,
+            zero_var,
+            zero_var,
+        ],
+        pow_2_64,
+        one,
+        zero,
+        zero,
+    );
+    let nullifier_var = nullify_in_cs(
+        &mut cs,
+        &secret_key_scalars_vars,
+        uid_amount,
+        payer_witness_var.asset_type,
+        key_type,
+        &public_key_scalars_vars,
+        nullifier_trace,
+    );
+
+    // Merkle path authentication
+    let acc_elem = AccElemVars {
+        uid: payer_witness_var.uid,
+        commitment: com_abar_in_var,
+    };
+
+    let (commitment, _) = commit(
+        &keypair.get_pk(),
+        payer_witness.blind,
+        payer_witness.amount,
+        payer_witness.asset_type,
+    )
+    .unwrap();
+    let leaf_trace = AnemoiJive254::eval_variable_length_hash_with_trace(&[
+        BN254Scalar::from(payer_witness.uid),
+        commitment,
+    ]);
+    let path_traces = payer_witness
+        .path
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, mt_node)| {
+            AnemoiJive254::eval_jive_with_trace(
+                &[mt_node.left, mt_node.mid],
+                &[mt_node.right, ANEMOI_JIVE_BN254_SALTS[i]],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let root_var = compute_merkle_root_variables(
+        &mut cs,
+        acc_elem,
+        &payer_witness_var.path,
+        &leaf_trace,
+        &path_traces,
+    );
+
+    // the output reuses the amount and the asset type of the input
+    let blind_out_var = cs.new_variable(payee_witness.blind);
+    let public_key_out_scalars = payee_witness.public_key.to_bn_scalars().unwrap();
+    let public_key_out_scalars_vars = [
+        cs.new_variable(public_key_out_scalars[0]),
+        cs.new_variable(public_key_out_scalars[1]),
+        cs.new_variable(public_key_out_scalars[2]),
+    ];
+    let public_key_out_type =
+        cs.new_variable(public_key_type_scalar(&payee_witness.public_key).unwrap());
+    cs.insert_boolean_gate(public_key_out_type);
+
+    let com_abar_out_var = commit_in_cs(
+        &mut cs,
+        blind_out_var,
+        payer_witness_var.amount,
+        payer_witness_var.asset_type,
+        public_key_out_type,
+        &public_key_out_scalars_vars,
+        output_commitment_trace,
+    );
+
+    // prepare public inputs variables
+    cs.prepare_pi_variable(nullifier_var);
+    cs.prepare_pi_variable(root_var);
+    cs.prepare_pi_variable(com_abar_out_var);
+
+    match folding_witness {
+        AXfrAddressFoldingWitness::Secp256k1(a) => prove_address_folding_in_cs_secp256k1(
+            &mut cs,
+            &public_key_scalars_vars,
+            &secret_key_scalars_vars,
+            a,
+        )
+        .unwrap(),
+        AXfrAddressFoldingWitness::Ed25519(a) => prove_address_folding_in_cs_ed25519(
+            &mut cs,
+            &public_key_scalars_vars,
+            &secret_key_scalars_vars,
+            a,
+        )
+        .unwrap(),
+    }
+
+    // pad the number of constraints to power of two
+    cs.pad();
+
+    let n_constraints = cs.size;
+    (cs, n_constraints)
+}
+
+/// Derive the token ID from the encrypted metadata of the token, so that the asset type
+/// committed in the record is the hash of the metadata ciphertext.
+pub fn nft_token_id(encrypted_metadata: &[u8]) -> AssetType {
+    let mut hasher = Sha256::new();
+    hasher.update(NFT_METADATA_DOMAIN);
+    hasher.update(encrypted_metadata);
+    AssetType(hasher.finalize().into())
+}
+
+/// Encrypt the metadata of a token to the public key, by the hybrid encryption of the memos,
+/// and return the ciphertext with the token ID that it hashes to.
+pub fn encrypt_nft_metadata<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    pk: &PublicKey,
+    metadata: &[u8],
+) -> Result<(Vec<u8>, AssetType)> {
+    let encrypted_metadata = axfr_hybrid_encrypt(pk, prng, metadata)?;
+    let token_id = nft_token_id(&encrypted_metadata);
+    Ok((encrypted_metadata, token_id))
+}
+
+/// A proof that the record committed on-chain is the token whose ID is the hash of
+/// the encrypted metadata, which a marketplace checks without decrypting the metadata.
+/// It is a disclosure proof of the asset type alone.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NftListingProof {
+    /// The commitment of the listed record.
+    pub commitment: Commitment,
+    /// The proof that the asset type in the commitment is the token ID.
+    pub proof: AXfrPlonkPf,
+}
+
+/// Initialize the transcript of the Plonk proof of a listing, bound to the token ID.
+fn nft_listing_transcript(commitment: &Commitment, token_id: &AssetType) -> Transcript {
+    let mut transcript = Transcript::new(NFT_LISTING_PLONK_PROOF_TRANSCRIPT);
+    transcript.append_message(b"commitment", &commitment.to_bytes());
+    transcript.append_message(b"token id", &token_id.0);
+    transcript
+}
+
+/// Prove that the non-fungible record is the token of the encrypted metadata,
+/// with the parameters of the disclosure circuit.
+pub fn prove_nft_listing<R: CryptoRng + RngCore, PCS: AXfrPCS>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    record: &OpenAnonAssetRecord,
+    encrypted_metadata: &[u8],
+) -> Result<NftListingProof> {
+    let token_id = nft_token_id(encrypted_metadata);
+    if record.get_amount() != NFT_AMOUNT || record.get_asset_type() != token_id {
+        return Err(NoahError::ParameterError);
+    }
+
+    let payee = PayeeWitness {
+        amount: record.get_amount(),
+        blind: record.get_blind(),
+        asset_type: token_id.as_scalar(),
+        public_key: *record.pub_key_ref(),
+    };
+    let (commitment, commitment_trace) = commit(
+        &payee.public_key,
+        payee.blind,
+        payee.amount,
+        payee.asset_type,
+    )?;
+
+    let mut transcript = nft_listing_transcript(&commitment, &token_id);
+    let (mut cs, _) = build_abar_disclosure_cs(&payee, &[false, true, false], &commitment_trace)?;
+    let witness = cs.get_and_clear_witness();
+    PublicInputsSchema::abar_disclosure().validate_witness(&cs, &witness)?;
+
+    let proof = prover_with_lagrange(
+        prng,
+        &mut transcript,
+        &params.pcs,
+        params.lagrange_pcs.as_ref(),
+        &params.cs,
+        &params.prover_params,
+        &witness,
+    )?;
+
+    Ok(NftListingProof { commitment, proof })
+}
+
+/// Verify that the listed record, whose commitment the caller has found on-chain,
+/// is the token of the encrypted metadata.
+pub fn verify_nft_listing(
+    params: &VerifierParams,
+    encrypted_metadata: &[u8],
+    listing: &NftListingProof,
+) -> Result<()> {
+    let token_id = nft_token_id(encrypted_metadata);
+    let zero = BN254Scalar::zero();
+    let one = BN254Scalar::one();
+
+    // only the mask bit and the field of the asset type are set
+    let online_inputs = vec![
+        listing.commitment,
+        zero,
+        zero,
+        one,
+        token_id.as_scalar(),
+        zero,
+        zero,
+        zero,
+        zero,
+        zero,
+    ];
+    PublicInputsSchema::abar_disclosure().validate(&online_inputs)?;
+
+    let mut transcript = nft_listing_transcript(&listing.commitment, &token_id);
+    Ok(verifier(
+        &mut transcript,
+        &params.shrunk_vk,
+        &params.shrunk_cs,
+        &params.verifier_params,
+        &online_inputs,
+        &listing.proof,
+    )?)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anon_xfr::address_folding_secp256k1::{
+        create_address_folding_secp256k1, prepare_verifier_input_secp256k1,
+        verify_address_folding_secp256k1,
+    };
+    use crate::anon_xfr::{
+        axfr_hybrid_decrypt, commit,
+        disclosure::build_abar_disclosure_cs,
+        nft::{
+            build_nft_transfer_cs, encrypt_nft_metadata, nft_mint_tag, nft_token_id, NFT_AMOUNT,
+            NFT_TRANSFER_FOLDING_PROOF_TRANSCRIPT,
+        },
+        nullify,
+        structs::{MTNode, MTPath, PayeeWitness, PayerWitness},
+        AXfrAddressFoldingWitness,
+    };
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::SECP256K1;
+    use crate::xfr::structs::{AssetType, ASSET_TYPE_LENGTH};
+    use digest::Digest;
+    use merlin::Transcript;
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+    use noah_crypto::anemoi_jive::{AnemoiJive, AnemoiJive254, ANEMOI_JIVE_BN254_SALTS};
+    use sha2::Sha512;
+
+    /// Check the witness of a transfer of `amount` units of the token, returning whether
+    /// it satisfies the circuit.
+    fn check_nft_transfer_cs(amount: u64) -> bool {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+        let receiver = KeyPair::sample(&mut prng, SECP256K1);
+        let token_id = AssetType([7u8; ASSET_TYPE_LENGTH]).as_scalar();
+
+        let uid = 3;
+        let blind = BN254Scalar::random(&mut prng);
+        let (commitment, input_commitment_trace) =
+            commit(&keypair.get_pk(), blind, amount, token_id).unwrap();
+
+        // a path where the record is the leftmost leaf, with the root it leads to
+        let mut merkle_root =
+            AnemoiJive254::eval_variable_length_hash(&[BN254Scalar::from(uid), commitment]);
+        let mut nodes = Vec::new();
+        for salt in ANEMOI_JIVE_BN254_SALTS.iter().take(3) {
+            let node = MTNode {
+                left: merkle_root,
+                mid: BN254Scalar::random(&mut prng),
+                right: BN254Scalar::random(&mut prng),
+                is_left_child: 1,
+                is_mid_child: 0,
+                is_right_child: 0,
+            };
+            merkle_root = AnemoiJive254::eval_jive(&[node.left, node.mid], &[node.right, *salt]);
+            nodes.push(node);
+        }
+
+        let payer_witness = PayerWitness {
+            secret_key: keypair.get_sk(),
+            uid,
+            amount,
+            asset_type: token_id,
+            path: MTPath::new(nodes),
+            blind,
+        };
+        let payee_witness = PayeeWitness {
+            amount,
+            blind: BN254Scalar::random(&mut prng),
+            asset_type: token_id,
+            public_key: receiver.get_pk(),
+        };
+
+        let (nullifier, nullifier_trace) = nullify(&keypair, amount, token_id, uid).unwrap();
+        let (output_commitment, output_commitment_trace) = commit(
+            &payee_witness.public_key,
+            payee_witness.blind,
+            amount,
+            token_id,
+        )
+        .unwrap();
+
+        let hash = Sha512::new_with_prefix(b"nft transfer test");
+        let mut transcript = Transcript::new(NFT_TRANSFER_FOLDING_PROOF_TRANSCRIPT);
+        let (folding_instance, folding_witness) =
+            create_address_folding_secp256k1(&mut prng, hash.clone(), &mut transcript, &keypair)
+                .unwrap();
+
+        let (mut cs, _) = build_nft_transfer_cs(
+            &payer_witness,
+            &payee_witness,
+            &nullifier_trace,
+            &input_commitment_trace,
+            &output_commitment_trace,
+            &AXfrAddressFoldingWitness::Secp256k1(folding_witness),
+        );
+        let witness = cs.get_and_clear_witness();
+
+        let mut transcript = Transcript::new(NFT_TRANSFER_FOLDING_PROOF_TRANSCRIPT);
+        let (beta, lambda) =
+            verify_address_folding_secp256k1(hash, &mut transcript, &folding_instance).unwrap();
+
+        let mut online_inputs = vec![nullifier, merkle_root, output_commitment];
+        online_inputs.extend_from_slice(&prepare_verifier_input_secp256k1(
+            &folding_instance,
+            &beta,
+            &lambda,
+        ));
+        cs.verify_witness(&witness, &online_inputs).is_ok()
+    }
+
+    #[test]
+    fn test_nft_transfer_cs() {
+        assert!(check_nft_transfer_cs(1));
+
+        // fungible amounts cannot go through the circuit
+        assert!(!check_nft_transfer_cs(2));
+    }
+
+    #[test]
+    fn test_nft_listing_cs() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+        let (encrypted_metadata, token_id) =
+            encrypt_nft_metadata(&mut prng, &keypair.get_pk(), b"ipfs://token/42").unwrap();
+        assert_eq!(
+            axfr_hybrid_decrypt(&keypair.get_sk(), &encrypted_metadata).unwrap(),
+            b"ipfs://token/42"
+        );
+
+        let payee = PayeeWitness {
+            amount: NFT_AMOUNT,
+            blind: BN254Scalar::random(&mut prng),
+            asset_type: token_id.as_scalar(),
+            public_key: keypair.get_pk(),
+        };
+        let (commitment, commitment_trace) = commit(
+            &payee.public_key,
+            payee.blind,
+            payee.amount,
+            payee.asset_type,
+        )
+        .unwrap();
+
+        let (mut cs, _) =
+            build_abar_disclosure_cs(&payee, &[false, true, false], &commitment_trace).unwrap();
+        let witness = cs.get_and_clear_witness();
+        let zero = BN254Scalar::zero();
+        let listed = |token_id: AssetType| {
+            vec![
+                commitment,
+                zero,
+                zero,
+                BN254Scalar::one(),
+                token_id.as_scalar(),
+                zero,
+                zero,
+                zero,
+                zero,
+                zero,
+            ]
+        };
+        assert!(cs
+            .verify_witness(&witness, &listed(nft_token_id(&encrypted_metadata)))
+            .is_ok());
+
+        // a listing with other metadata does not match the record
+        let mut other_metadata = encrypted_metadata.clone();
+        other_metadata[0] ^= 1;
+        assert!(cs
+            .verify_witness(&witness, &listed(nft_token_id(&other_metadata)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_nft_mint_tag() {
+        let a = AssetType([1u8; ASSET_TYPE_LENGTH]);
+        let b = AssetType([2u8; ASSET_TYPE_LENGTH]);
+        assert_eq!(nft_mint_tag(&a), nft_mint_tag(&a));
+        assert_ne!(nft_mint_tag(&a), nft_mint_tag(&b));
+    }
+}
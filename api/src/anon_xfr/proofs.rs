@@ -0,0 +1,245 @@
+use crate::anon_xfr::{
+    commit, commit_in_cs,
+    structs::{AnonAssetRecord, Commitment, OpenAnonAssetRecord, PayeeWitness},
+    AXfrPlonkPf, TurboPlonkCS, AMOUNT_LEN,
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::PublicKeyInner;
+use crate::parameters::params::{AXfrPCS, ProverParams, VerifierParams};
+use crate::parameters::schema::PublicInputsSchema;
+use merlin::Transcript;
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use noah_crypto::anemoi_jive::{AnemoiJive254, AnemoiVLHTrace};
+use noah_plonk::plonk::{
+    constraint_system::TurboCS, prover::prover_with_lagrange, verifier::verifier,
+};
+
+/// The domain separator for the amount range proof of anonymous records, for the Plonk proof.
+const ABAR_AMOUNT_RANGE_PLONK_PROOF_TRANSCRIPT: &[u8] = b"ABAR Amount Range Plonk Proof";
+
+/// A standalone proof that the commitment of an anonymous record hides an amount
+/// within `[lower, upper]`, e.g., for proofs of solvency.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmountRangeProof {
+    /// The lower bound of the amount, inclusive.
+    pub lower: u64,
+    /// The upper bound of the amount, inclusive.
+    pub upper: u64,
+    /// The proof that the amount in the commitment is within the bounds.
+    pub proof: AXfrPlonkPf,
+}
+
+/// Prove that the amount of the anonymous record is within `[lower, upper]`.
+pub fn prove_amount_range<R: CryptoRng + RngCore, PCS: AXfrPCS>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    oabar: &OpenAnonAssetRecord,
+    lower: u64,
+    upper: u64,
+) -> Result<AmountRangeProof> {
+    let amount = oabar.get_amount();
+    if lower > upper || amount < lower || amount > upper {
+        return Err(NoahError::ParameterError);
+    }
+
+    let payee = PayeeWitness {
+        amount,
+        blind: oabar.get_blind(),
+        asset_type: oabar.get_asset_type().as_scalar(),
+        public_key: *oabar.pub_key_ref(),
+    };
+    let (_, commitment_trace) = commit(
+        &payee.public_key,
+        payee.blind,
+        payee.amount,
+        payee.asset_type,
+    )?;
+
+    let mut transcript = Transcript::new(ABAR_AMOUNT_RANGE_PLONK_PROOF_TRANSCRIPT);
+    let (mut cs, _) = build_abar_amount_range_cs(&payee, lower, upper, &commitment_trace)?;
+    let witness = cs.get_and_clear_witness();
+    PublicInputsSchema::abar_amount_range().validate_witness(&cs, &witness)?;
+
+    let proof = prover_with_lagrange(
+        prng,
+        &mut transcript,
+        &params.pcs,
+        params.lagrange_pcs.as_ref(),
+        &params.cs,
+        &params.prover_params,
+        &witness,
+    )?;
+
+    Ok(AmountRangeProof {
+        lower,
+        upper,
+        proof,
+    })
+}
+
+/// Verify the amount range proof against the commitment of an anonymous record.
+pub fn verify_amount_range_proof(
+    params: &VerifierParams,
+    commitment: &Commitment,
+    range_proof: &AmountRangeProof,
+) -> Result<()> {
+    if range_proof.lower > range_proof.upper {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    let online_inputs = vec![
+        *commitment,
+        BN254Scalar::from(range_proof.lower),
+        BN254Scalar::from(range_proof.upper),
+    ];
+    PublicInputsSchema::abar_amount_range().validate(&online_inputs)?;
+
+    let mut transcript = Transcript::new(ABAR_AMOUNT_RANGE_PLONK_PROOF_TRANSCRIPT);
+    Ok(verifier(
+        &mut transcript,
+        &params.shrunk_vk,
+        &params.shrunk_cs,
+        &params.verifier_params,
+        &online_inputs,
+        &range_proof.proof,
+    )?)
+}
+
+impl AnonAssetRecord {
+    /// Verify that the amount hidden in this record is within the bounds of the range proof.
+    pub fn verify_amount_range(
+        &self,
+        params: &VerifierParams,
+        range_proof: &AmountRangeProof,
+    ) -> Result<()> {
+        verify_amount_range_proof(params, &self.commitment, range_proof)
+    }
+}
+
+/// Construct the constraint system proving that the amount in the commitment is within
+/// `[lower, upper]`, by range-checking `amount - lower` and `upper - amount`.
+pub(crate) fn build_abar_amount_range_cs(
+    payee: &PayeeWitness,
+    lower: u64,
+    upper: u64,
+    commitment_trace: &AnemoiVLHTrace<BN254Scalar, 2, 14>,
+) -> Result<(TurboPlonkCS, usize)> {
+    let mut cs = TurboCS::new();
+    cs.load_anemoi_jive_parameters::<AnemoiJive254>();
+
+    let amount_var = cs.new_variable(BN254Scalar::from(payee.amount));
+    let asset_type_var = cs.new_variable(payee.asset_type);
+    let blind_var = cs.new_variable(payee.blind);
+
+    let public_key_scalars = payee.public_key.to_bn_scalars()?;
+    let public_key_scalars_vars = [
+        cs.new_variable(public_key_scalars[0]),
+        cs.new_variable(public_key_scalars[1]),
+        cs.new_variable(public_key_scalars[2]),
+    ];
+
+    let public_key_type_var = match payee.public_key.0 {
+        PublicKeyInner::Ed25519(_) => cs.new_variable(BN254Scalar::one()),
+        PublicKeyInner::Secp256k1(_) => cs.new_variable(BN254Scalar::zero()),
+        PublicKeyInner::EthAddress(_) => return Err(NoahError::ParameterError),
+    };
+    cs.insert_boolean_gate(public_key_type_var);
+
+    // prepare the public input for the commitment
+    let commitment_var = commit_in_cs(
+        &mut cs,
+        blind_var,
+        amount_var,
+        asset_type_var,
+        public_key_type_var,
+        &public_key_scalars_vars,
+        commitment_trace,
+    );
+    cs.prepare_pi_variable(commitment_var);
+
+    // prepare the public inputs for the bounds
+    let lower_var = cs.new_variable(BN254Scalar::from(lower));
+    cs.prepare_pi_variable(lower_var);
+    let upper_var = cs.new_variable(BN254Scalar::from(upper));
+    cs.prepare_pi_variable(upper_var);
+
+    // lower <= amount <= upper, as both differences would otherwise wrap around the field
+    let above_lower_var = cs.sub(amount_var, lower_var);
+    cs.range_check(above_lower_var, AMOUNT_LEN);
+    let below_upper_var = cs.sub(upper_var, amount_var);
+    cs.range_check(below_upper_var, AMOUNT_LEN);
+
+    // pad the number of constraints to power of two
+    cs.pad();
+
+    let n_constraints = cs.size;
+    Ok((cs, n_constraints))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::anon_xfr::{commit, proofs::build_abar_amount_range_cs, structs::PayeeWitness};
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::SECP256K1;
+    use crate::xfr::structs::{AssetType, ASSET_TYPE_LENGTH};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    #[test]
+    fn test_abar_amount_range_cs() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+
+        let payee = PayeeWitness {
+            amount: 1500,
+            blind: BN254Scalar::random(&mut prng),
+            asset_type: AssetType([3u8; ASSET_TYPE_LENGTH]).as_scalar(),
+            public_key: keypair.get_pk(),
+        };
+        let (commitment, commitment_trace) = commit(
+            &payee.public_key,
+            payee.blind,
+            payee.amount,
+            payee.asset_type,
+        )
+        .unwrap();
+
+        // balance >= 1000
+        let (mut cs, _) =
+            build_abar_amount_range_cs(&payee, 1000, u64::MAX, &commitment_trace).unwrap();
+        let witness = cs.get_and_clear_witness();
+        let online_inputs = vec![
+            commitment,
+            BN254Scalar::from(1000u64),
+            BN254Scalar::from(u64::MAX),
+        ];
+        assert!(cs.verify_witness(&witness, &online_inputs).is_ok());
+
+        // the exact amount is a valid range
+        let (mut cs, _) =
+            build_abar_amount_range_cs(&payee, 1500, 1500, &commitment_trace).unwrap();
+        let witness = cs.get_and_clear_witness();
+        let online_inputs = vec![
+            commitment,
+            BN254Scalar::from(1500u64),
+            BN254Scalar::from(1500u64),
+        ];
+        assert!(cs.verify_witness(&witness, &online_inputs).is_ok());
+
+        // the amount is below the lower bound
+        let (mut cs, _) =
+            build_abar_amount_range_cs(&payee, 2000, u64::MAX, &commitment_trace).unwrap();
+        let witness = cs.get_and_clear_witness();
+        let online_inputs = vec![
+            commitment,
+            BN254Scalar::from(2000u64),
+            BN254Scalar::from(u64::MAX),
+        ];
+        assert!(cs.verify_witness(&witness, &online_inputs).is_err());
+
+        // the amount is above the upper bound
+        let (mut cs, _) = build_abar_amount_range_cs(&payee, 0, 1000, &commitment_trace).unwrap();
+        let witness = cs.get_and_clear_witness();
+        let online_inputs = vec![commitment, BN254Scalar::zero(), BN254Scalar::from(1000u64)];
+        assert!(cs.verify_witness(&witness, &online_inputs).is_err());
+    }
+}
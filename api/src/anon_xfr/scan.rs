@@ -0,0 +1,159 @@
+use crate::anon_xfr::structs::{
+    AnonAssetRecord, AxfrOwnerMemo, OpenAnonAssetRecord, OpenAnonAssetRecordBuilder,
+};
+use crate::keys::KeyPair;
+use noah_algebra::prelude::*;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+/// An anonymous record found by the scanner, which the key pair can spend.
+#[derive(Clone, Debug)]
+pub struct ScannedRecord {
+    /// The uid of the record, i.e., its position in the Merkle tree.
+    pub uid: u64,
+    /// The opened record.
+    pub oabar: OpenAnonAssetRecord,
+}
+
+/// A scanner of the anonymous records of the blocks, which tries to open each record with the
+/// key pair and keeps the uids of the records, assuming that the blocks are fed in the order
+/// in which their records are added to the Merkle tree.
+pub struct WalletScanner<'a> {
+    key_pair: &'a KeyPair,
+    next_uid: u64,
+}
+
+impl<'a> WalletScanner<'a> {
+    /// Create a scanner for the key pair, whose next record has the uid.
+    pub fn new(key_pair: &'a KeyPair, next_uid: u64) -> Self {
+        Self { key_pair, next_uid }
+    }
+
+    /// Return the uid of the next record to scan.
+    pub fn next_uid(&self) -> u64 {
+        self.next_uid
+    }
+
+    /// Scan the records of a block, with their owner memos, and return the records
+    /// that belong to the key pair, in the order of the block.
+    pub fn scan_block(&mut self, block: &[(AnonAssetRecord, AxfrOwnerMemo)]) -> Vec<ScannedRecord> {
+        let first_uid = self.next_uid;
+
+        #[cfg(not(feature = "parallel"))]
+        let found = block
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (abar, memo))| {
+                open_record(self.key_pair, abar, memo).map(|oabar| ScannedRecord {
+                    uid: first_uid + i as u64,
+                    oabar,
+                })
+            })
+            .collect();
+
+        #[cfg(feature = "parallel")]
+        let found = block
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, (abar, memo))| {
+                open_record(self.key_pair, abar, memo).map(|oabar| ScannedRecord {
+                    uid: first_uid + i as u64,
+                    oabar,
+                })
+            })
+            .collect();
+
+        self.next_uid += block.len() as u64;
+        found
+    }
+
+    /// Scan the blocks in order, and return the records that belong to the key pair.
+    pub fn scan_blocks(
+        &mut self,
+        blocks: &[&[(AnonAssetRecord, AxfrOwnerMemo)]],
+    ) -> Vec<ScannedRecord> {
+        let mut found = Vec::new();
+        for block in blocks.iter() {
+            found.extend(self.scan_block(block));
+        }
+        found
+    }
+}
+
+/// Try to open the record with the key pair, which fails for the records of others
+/// at the authentication of the memo, and for inconsistent memos at the commitment.
+fn open_record(
+    key_pair: &KeyPair,
+    abar: &AnonAssetRecord,
+    memo: &AxfrOwnerMemo,
+) -> Option<OpenAnonAssetRecord> {
+    OpenAnonAssetRecordBuilder::from_abar(abar, memo.clone(), key_pair)
+        .and_then(|builder| builder.build())
+        .ok()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anon_xfr::{
+        scan::WalletScanner,
+        structs::{AnonAssetRecord, OpenAnonAssetRecordBuilder},
+    };
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::xfr::structs::AssetType;
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_wallet_scanner() {
+        let mut prng = test_rng();
+        let alice = KeyPair::sample(&mut prng, SECP256K1);
+        let bob = KeyPair::sample(&mut prng, ED25519);
+
+        let mut blocks = vec![];
+        for owners in [vec![&alice, &bob, &bob], vec![&bob], vec![&alice, &alice]] {
+            let block = owners
+                .iter()
+                .enumerate()
+                .map(|(i, owner)| {
+                    let oabar = OpenAnonAssetRecordBuilder::new()
+                        .amount(10 + i as u64)
+                        .asset_type(AssetType::from_identical_byte(i as u8))
+                        .pub_key(&owner.get_pk())
+                        .finalize(&mut prng)
+                        .unwrap()
+                        .build()
+                        .unwrap();
+                    (
+                        AnonAssetRecord::from_oabar(&oabar),
+                        oabar.get_owner_memo().unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            blocks.push(block);
+        }
+        let blocks = blocks.iter().map(|b| b.as_slice()).collect::<Vec<_>>();
+
+        let mut scanner = WalletScanner::new(&alice, 100);
+        let found = scanner.scan_blocks(&blocks);
+        assert_eq!(scanner.next_uid(), 106);
+        assert_eq!(
+            found.iter().map(|r| r.uid).collect::<Vec<_>>(),
+            vec![100, 104, 105]
+        );
+        assert_eq!(found[2].oabar.get_amount(), 11);
+        assert_eq!(
+            found[2].oabar.get_asset_type(),
+            AssetType::from_identical_byte(1)
+        );
+
+        let mut scanner = WalletScanner::new(&bob, 0);
+        let found = scanner.scan_block(blocks[0]);
+        assert_eq!(found.iter().map(|r| r.uid).collect::<Vec<_>>(), vec![1, 2]);
+
+        // a memo that does not match its record is skipped
+        let mut tampered = blocks[1].to_vec();
+        tampered[0].0 = blocks[0][1].0.clone();
+        assert!(scanner.scan_block(&tampered).is_empty());
+    }
+}
@@ -1,4 +1,9 @@
-use crate::anon_xfr::{axfr_hybrid_decrypt, axfr_hybrid_encrypt, commit, decrypt_memo};
+use crate::anon_xfr::{
+    axfr_committing_decrypt, axfr_committing_encrypt, axfr_hybrid_decrypt, axfr_hybrid_encrypt,
+    axfr_payload_decrypt, axfr_payload_encrypt, commit, decrypt_memo, AXFR_COMMITTING_MEMO_PREFIX,
+    MAX_AXFR_COMMITTING_MEMO_SIZE, MAX_AXFR_ENCRYPTED_PAYLOAD_SIZE, MAX_AXFR_MEMO_SIZE,
+    MAX_AXFR_OUTPUT_PAYLOAD_SIZE,
+};
 use crate::errors::{NoahError, Result};
 use crate::keys::{KeyPair, PublicKey, SecretKey};
 use crate::parameters::params::AddressFormat::{ED25519, SECP256K1};
@@ -6,7 +11,8 @@ use crate::xfr::structs::AssetType;
 use noah_algebra::{bn254::BN254Scalar, prelude::*};
 use noah_plonk::plonk::constraint_system::VarIndex;
 use serde::Serialize;
-use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use {noah_algebra::wasm::WasmBN254Scalar, wasm_bindgen::prelude::*};
 
 /// The nullifier.
 pub type Nullifier = BN254Scalar;
@@ -16,14 +22,17 @@ pub type Commitment = BN254Scalar;
 pub type BlindFactor = BN254Scalar;
 
 /// A Merkle tree node.
-#[wasm_bindgen]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MTNode {
     /// The left child of its parent in a three-ary tree.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(skip))]
     pub left: BN254Scalar,
     /// The mid child of its parent in a three-ary tree.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(skip))]
     pub mid: BN254Scalar,
     /// The right child of its parent in a three-ary tree.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(skip))]
     pub right: BN254Scalar,
     /// Whether this node is the left child of the parent.
     pub is_left_child: u8,
@@ -34,13 +43,46 @@ pub struct MTNode {
 }
 
 /// Asset record to be put as leaves on the tree.
-#[wasm_bindgen]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AnonAssetRecord {
     /// The commitment.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(skip))]
     pub commitment: BN254Scalar,
 }
 
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+impl MTNode {
+    /// The left child, for WASM.
+    #[wasm_bindgen(getter, js_name = left)]
+    pub fn left_wasm(&self) -> WasmBN254Scalar {
+        self.left.into()
+    }
+
+    /// The mid child, for WASM.
+    #[wasm_bindgen(getter, js_name = mid)]
+    pub fn mid_wasm(&self) -> WasmBN254Scalar {
+        self.mid.into()
+    }
+
+    /// The right child, for WASM.
+    #[wasm_bindgen(getter, js_name = right)]
+    pub fn right_wasm(&self) -> WasmBN254Scalar {
+        self.right.into()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+impl AnonAssetRecord {
+    /// The commitment, for WASM.
+    #[wasm_bindgen(getter, js_name = commitment)]
+    pub fn commitment_wasm(&self) -> WasmBN254Scalar {
+        self.commitment.into()
+    }
+}
+
 impl AnonAssetRecord {
     /// Generate the anonymous asset record from the opened version.
     pub fn from_oabar(oabar: &OpenAnonAssetRecord) -> AnonAssetRecord {
@@ -142,6 +184,7 @@ impl OpenAnonAssetRecord {
 /// The builder for an opened anonymous asset record.
 pub struct OpenAnonAssetRecordBuilder {
     pub(crate) oabar: OpenAnonAssetRecord,
+    committing_memo: bool,
 }
 
 impl OpenAnonAssetRecordBuilder {
@@ -176,6 +219,12 @@ impl OpenAnonAssetRecordBuilder {
         self
     }
 
+    /// Encrypt the owner memo in the key-committing format, see `AxfrOwnerMemo::new_committing`.
+    pub fn key_committing_memo(mut self) -> Self {
+        self.committing_memo = true;
+        self
+    }
+
     /// Finalize builder:
     /// If built via constructor + builder methods, it samples commitment blinding and key randomization factor and
     /// creates associated owner memo.
@@ -191,7 +240,11 @@ impl OpenAnonAssetRecordBuilder {
         msg.extend_from_slice(&self.oabar.asset_type.0);
         msg.extend_from_slice(&self.oabar.blind.to_bytes());
 
-        self.oabar.owner_memo = Some(AxfrOwnerMemo::new(prng, &self.oabar.pub_key, &msg)?);
+        self.oabar.owner_memo = Some(if self.committing_memo {
+            AxfrOwnerMemo::new_committing(prng, &self.oabar.pub_key, &msg)?
+        } else {
+            AxfrOwnerMemo::new(prng, &self.oabar.pub_key, &msg)?
+        });
         Ok(self)
     }
 
@@ -205,6 +258,8 @@ impl OpenAnonAssetRecordBuilder {
 impl OpenAnonAssetRecordBuilder {
     /// Build an OpenAssetRecord from an BlindAssetRecord, opening keys, owner memo and decryption keys
     /// Return error if decrypted `owner_memo` is inconsistent with `record`
+    /// The memo can be in the legacy or the key-committing format, so that the records
+    /// created before the migration can still be opened.
     pub fn from_abar(
         record: &AnonAssetRecord,
         owner_memo: AxfrOwnerMemo,
@@ -251,6 +306,29 @@ impl MTPath {
     }
 }
 
+/// A node of an authentication path of a Merkle tree with four or eight children per node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NaryMTNode {
+    /// The children of the parent, including the node on the path.
+    pub children: Vec<BN254Scalar>,
+    /// The position of the node on the path among the children.
+    pub position: usize,
+}
+
+/// An authentication path of a Merkle tree with four or eight children per node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NaryMTPath {
+    /// A list of tree nodes.
+    pub nodes: Vec<NaryMTNode>,
+}
+
+impl NaryMTPath {
+    /// Create a new Merkle path.
+    pub fn new(nodes: Vec<NaryMTNode>) -> Self {
+        Self { nodes }
+    }
+}
+
 pub(crate) struct PayerWitnessVars {
     pub(crate) uid: VarIndex,
     pub(crate) amount: VarIndex,
@@ -289,6 +367,20 @@ pub struct MerklePathVars {
     pub nodes: Vec<MerkleNodeVars>,
 }
 
+/// The allocated variables for a node of a Merkle tree with four or eight children per node.
+pub struct NaryMerkleNodeVars {
+    /// The allocated variables for the children.
+    pub children: Vec<VarIndex>,
+    /// The allocated bits of which the one at the position of the node on the path is one.
+    pub is_child: Vec<VarIndex>,
+}
+
+/// The allocated variables for a path of a Merkle tree with four or eight children per node.
+pub struct NaryMerklePathVars {
+    /// The list of allocated Merkle tree nodes.
+    pub nodes: Vec<NaryMerkleNodeVars>,
+}
+
 /// The allocated variables for a Merkle tree leaf.
 pub struct AccElemVars {
     /// The ID of this commitment.
@@ -329,7 +421,7 @@ pub struct PayeeWitness {
 
 /// Information directed to secret key holder of a BlindAssetRecord
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
-pub struct AxfrOwnerMemo(CompactByteArray);
+pub struct AxfrOwnerMemo(pub(crate) CompactByteArray);
 
 impl AxfrOwnerMemo {
     /// Crate an encrypted memo using the public key.
@@ -338,27 +430,129 @@ impl AxfrOwnerMemo {
         pub_key: &PublicKey,
         msg: &[u8],
     ) -> Result<Self> {
-        let ctext = axfr_hybrid_encrypt(pub_key, prng, msg)?;
+        loop {
+            let ctext = axfr_hybrid_encrypt(pub_key, prng, msg)?;
+            // an ed25519 share may start with the prefix of the key-committing format, and the
+            // memo would then be read in that format
+            if !ctext.starts_with(&AXFR_COMMITTING_MEMO_PREFIX) {
+                return Ok(Self(CompactByteArray(ctext)));
+            }
+        }
+    }
+
+    /// Create a memo in the key-committing format, which only decrypts under the key it was
+    /// encrypted to, unlike the legacy format, where a ciphertext can be crafted to decrypt
+    /// under several keys.
+    pub fn new_committing<R: CryptoRng + RngCore>(
+        prng: &mut R,
+        pub_key: &PublicKey,
+        msg: &[u8],
+    ) -> Result<Self> {
+        let ctext = axfr_committing_encrypt(pub_key, prng, msg)?;
         Ok(Self(CompactByteArray(ctext)))
     }
 
-    /// Decrypt a memo using the viewing key.
+    /// Decrypt a memo using the viewing key, in the format given by its prefix.
     pub fn decrypt(&self, secret_key: &SecretKey) -> Result<Vec<u8>> {
-        axfr_hybrid_decrypt(secret_key, &self.0 .0)
+        if self.is_key_committing() {
+            axfr_committing_decrypt(secret_key, &self.0 .0)
+        } else {
+            axfr_hybrid_decrypt(secret_key, &self.0 .0)
+        }
+    }
+
+    /// Check if the memo is in the key-committing format, i.e., starts with its prefix,
+    /// which the memos in the legacy format never do.
+    pub fn is_key_committing(&self) -> bool {
+        self.0 .0.starts_with(&AXFR_COMMITTING_MEMO_PREFIX)
+    }
+
+    /// Re-encrypt a memo in the legacy format to the key pair in the key-committing format.
+    /// A memo already in the key-committing format is kept, once it decrypts under the key pair.
+    pub fn migrate<R: CryptoRng + RngCore>(
+        &self,
+        prng: &mut R,
+        key_pair: &KeyPair,
+    ) -> Result<Self> {
+        if self.is_key_committing() {
+            axfr_committing_decrypt(&key_pair.get_sk(), &self.0 .0)?;
+            return Ok(self.clone());
+        }
+        let msg = axfr_hybrid_decrypt(&key_pair.get_sk(), &self.0 .0)?;
+        Self::new_committing(prng, &key_pair.get_pk(), &msg)
     }
 
     /// Return the size of the memo.
     pub fn size(&self) -> usize {
         self.0 .0.len()
     }
+
+    /// Check if the memo is larger than allowed for its format. A memo with the prefix only
+    /// decrypts in the key-committing format, so it gets the limit of that format.
+    pub fn is_oversized(&self) -> bool {
+        if self.is_key_committing() {
+            self.size() > MAX_AXFR_COMMITTING_MEMO_SIZE
+        } else {
+            self.size() > MAX_AXFR_MEMO_SIZE
+        }
+    }
+}
+
+/// An application-defined payload of an output, e.g., a deposit tag, encrypted to the owner
+/// of the output. The payload is not an input of the proof, so it should be covered by the
+/// signature or the hash of the transaction that carries the note.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AxfrOutputPayload(pub(crate) CompactByteArray);
+
+impl AxfrOutputPayload {
+    /// Encrypt a payload of at most `MAX_AXFR_OUTPUT_PAYLOAD_SIZE` bytes to the public key.
+    pub fn new<R: CryptoRng + RngCore>(
+        prng: &mut R,
+        pub_key: &PublicKey,
+        payload: &[u8],
+    ) -> Result<Self> {
+        if payload.len() > MAX_AXFR_OUTPUT_PAYLOAD_SIZE {
+            return Err(NoahError::ParameterError);
+        }
+        let ctext = axfr_payload_encrypt(pub_key, prng, payload)?;
+        Ok(Self(CompactByteArray(ctext)))
+    }
+
+    /// Decrypt the payload using the viewing key.
+    pub fn decrypt(&self, secret_key: &SecretKey) -> Result<Vec<u8>> {
+        axfr_payload_decrypt(secret_key, &self.0 .0)
+    }
+
+    /// Return the size of the encrypted payload.
+    pub fn size(&self) -> usize {
+        self.0 .0.len()
+    }
+
+    /// Check if the encrypted payload is larger than allowed.
+    pub fn is_oversized(&self) -> bool {
+        self.size() > MAX_AXFR_ENCRYPTED_PAYLOAD_SIZE
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::anon_xfr::structs::PublicKey;
+    use crate::anon_xfr::{
+        axfr_dh_decap, axfr_hybrid_decrypt,
+        structs::{
+            AnonAssetRecord, AxfrOutputPayload, AxfrOwnerMemo, OpenAnonAssetRecordBuilder,
+            PublicKey,
+        },
+        AXFR_COMMITTING_MEMO_PREFIX, MAX_AXFR_COMMITTING_MEMO_SIZE,
+        MAX_AXFR_ENCRYPTED_PAYLOAD_SIZE, MAX_AXFR_MEMO_SIZE, MAX_AXFR_OUTPUT_PAYLOAD_SIZE,
+    };
+    use crate::errors::NoahError;
     use crate::keys::KeyPair;
-    use crate::parameters::AddressFormat::SECP256K1;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::xfr::structs::AssetType;
+    use aes_gcm::{aead::Aead, Aes256Gcm};
+    use digest::{generic_array::GenericArray, Digest, KeyInit};
     use noah_algebra::prelude::*;
+    use sha2::Sha512;
 
     #[test]
     fn test_axfr_pub_key_serialization() {
@@ -385,4 +579,135 @@ mod test {
         let reformed_key_pair = KeyPair::noah_from_bytes(bytes.as_slice()).unwrap();
         assert_eq!(keypair, reformed_key_pair);
     }
+
+    #[test]
+    fn test_key_committing_owner_memo() {
+        let mut prng = test_rng();
+        for address_format in [SECP256K1, ED25519] {
+            let keypair = KeyPair::sample(&mut prng, address_format);
+            let other = KeyPair::sample(&mut prng, address_format);
+            let msg = [7u8; 72];
+
+            let memo = AxfrOwnerMemo::new_committing(&mut prng, &keypair.get_pk(), &msg).unwrap();
+            assert!(memo.is_key_committing());
+            assert!(memo.size() <= MAX_AXFR_COMMITTING_MEMO_SIZE && !memo.is_oversized());
+            assert_eq!(memo.decrypt(&keypair.get_sk()).unwrap(), msg);
+            assert!(memo.decrypt(&other.get_sk()).is_err());
+
+            let mut tampered = memo.clone();
+            let last = tampered.size() - 1;
+            tampered.0 .0[last] ^= 1;
+            assert!(tampered.decrypt(&keypair.get_sk()).is_err());
+
+            // a legacy memo is migrated to the key-committing format
+            let legacy = AxfrOwnerMemo::new(&mut prng, &keypair.get_pk(), &msg).unwrap();
+            assert!(!legacy.is_key_committing());
+            assert!(legacy.size() <= MAX_AXFR_MEMO_SIZE);
+            let migrated = legacy.migrate(&mut prng, &keypair).unwrap();
+            assert!(migrated.is_key_committing());
+            assert_eq!(migrated.decrypt(&keypair.get_sk()).unwrap(), msg);
+            assert!(legacy.migrate(&mut prng, &other).is_err());
+
+            // the records are opened from memos in either format
+            for committing in [false, true] {
+                let mut builder = OpenAnonAssetRecordBuilder::new()
+                    .amount(10)
+                    .asset_type(AssetType::from_identical_byte(1))
+                    .pub_key(&keypair.get_pk());
+                if committing {
+                    builder = builder.key_committing_memo();
+                }
+                let oabar = builder.finalize(&mut prng).unwrap().build().unwrap();
+                let memo = oabar.get_owner_memo().unwrap();
+                assert_eq!(memo.is_key_committing(), committing);
+
+                let abar = AnonAssetRecord::from_oabar(&oabar);
+                let opened = OpenAnonAssetRecordBuilder::from_abar(&abar, memo, &keypair)
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                assert_eq!(opened, oabar);
+            }
+        }
+    }
+
+    #[test]
+    fn test_prefixed_legacy_owner_memo() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, ED25519);
+        let msg = [7u8; 72];
+
+        // a legacy memo whose share is ground to start with the prefix of the key-committing
+        // format is not decrypted in the legacy format, nor kept by the migration
+        let (share, dh) = loop {
+            let mut share = AXFR_COMMITTING_MEMO_PREFIX.to_vec();
+            share.extend_from_slice(&prng.gen::<[u8; 28]>());
+            if let Ok((_, dh)) = axfr_dh_decap(&keypair.get_sk(), &share) {
+                break (share, dh);
+            }
+        };
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&Sha512::digest(&dh)[0..32]);
+        let gcm = Aes256Gcm::new_from_slice(&key).unwrap();
+        let mut bytes = share;
+        bytes.extend(
+            gcm.encrypt(GenericArray::from_slice(&[0u8; 12]), &msg[..])
+                .unwrap(),
+        );
+        assert_eq!(axfr_hybrid_decrypt(&keypair.get_sk(), &bytes).unwrap(), msg);
+
+        let memo = AxfrOwnerMemo(CompactByteArray(bytes));
+        assert!(memo.is_key_committing());
+        assert!(memo.decrypt(&keypair.get_sk()).is_err());
+        assert!(memo.migrate(&mut prng, &keypair).is_err());
+
+        // the memos in the legacy format are never created with the prefix
+        for _ in 0..16 {
+            let legacy = AxfrOwnerMemo::new(&mut prng, &keypair.get_pk(), &msg).unwrap();
+            assert!(!legacy.is_key_committing());
+        }
+
+        // an ETH address has no key to encrypt to
+        let address = KeyPair::sample(&mut prng, SECP256K1)
+            .get_pk()
+            .to_eth_address()
+            .unwrap();
+        assert_eq!(
+            AxfrOwnerMemo::new_committing(&mut prng, &address, &msg),
+            Err(NoahError::ParameterError)
+        );
+        assert_eq!(
+            AxfrOutputPayload::new(&mut prng, &address, &msg),
+            Err(NoahError::ParameterError)
+        );
+    }
+
+    #[test]
+    fn test_output_payload() {
+        let mut prng = test_rng();
+        for address_format in [SECP256K1, ED25519] {
+            let keypair = KeyPair::sample(&mut prng, address_format);
+            let other = KeyPair::sample(&mut prng, address_format);
+            let tag = [9u8; MAX_AXFR_OUTPUT_PAYLOAD_SIZE];
+
+            let payload = AxfrOutputPayload::new(&mut prng, &keypair.get_pk(), &tag).unwrap();
+            assert!(payload.size() <= MAX_AXFR_ENCRYPTED_PAYLOAD_SIZE && !payload.is_oversized());
+            assert_eq!(payload.decrypt(&keypair.get_sk()).unwrap(), tag);
+            assert!(payload.decrypt(&other.get_sk()).is_err());
+            assert!(AxfrOutputPayload::new(
+                &mut prng,
+                &keypair.get_pk(),
+                &[0u8; MAX_AXFR_OUTPUT_PAYLOAD_SIZE + 1]
+            )
+            .is_err());
+
+            // a payload and an owner memo cannot be swapped
+            let memo = AxfrOwnerMemo::new_committing(&mut prng, &keypair.get_pk(), b"tag").unwrap();
+            assert!(AxfrOutputPayload(memo.0.clone())
+                .decrypt(&keypair.get_sk())
+                .is_err());
+            let payload = AxfrOutputPayload::new(&mut prng, &keypair.get_pk(), b"tag").unwrap();
+            assert!(AxfrOwnerMemo(payload.0).decrypt(&keypair.get_sk()).is_err());
+        }
+    }
 }
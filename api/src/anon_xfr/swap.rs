@@ -0,0 +1,664 @@
+use crate::anon_xfr::address_folding_ed25519::{
+    create_address_folding_ed25519, prepare_verifier_input_ed25519,
+    prove_address_folding_in_cs_ed25519, verify_address_folding_ed25519,
+};
+use crate::anon_xfr::address_folding_secp256k1::{
+    create_address_folding_secp256k1, prepare_verifier_input_secp256k1,
+    prove_address_folding_in_cs_secp256k1, verify_address_folding_secp256k1,
+};
+use crate::anon_xfr::{
+    abar_to_abar::{
+        add_payees_witnesses, add_payers_witnesses, asset_mixing, outputs_order, AXfrWitness,
+    },
+    check_asset_amount, check_inputs, check_roots, commit, commit_in_cs,
+    compute_merkle_root_variables, nullify, nullify_in_cs,
+    structs::{
+        AccElemVars, AnonAssetRecord, AxfrOwnerMemo, Nullifier, OpenAnonAssetRecord,
+        OpenAnonAssetRecordBuilder, PayeeWitness, PayerWitness,
+    },
+    AXfrAddressFoldingInstance, AXfrAddressFoldingWitness, AXfrPlonkPf, TurboPlonkCS, AMOUNT_LEN,
+    FEE_TYPE,
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, SecretKey};
+use crate::parameters::params::{AXfrPCS, AddressFormat, ProverParams, VerifierParams};
+use crate::parameters::schema::PublicInputsSchema;
+use crate::xfr::structs::AssetType;
+use digest::{consts::U64, Digest};
+use merlin::Transcript;
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use noah_crypto::anemoi_jive::{
+    AnemoiJive, AnemoiJive254, AnemoiVLHTrace, ANEMOI_JIVE_BN254_SALTS,
+};
+use noah_plonk::plonk::{
+    constraint_system::{TurboCS, VarIndex},
+    prover::prover_with_lagrange,
+    verifier::verifier,
+};
+
+/// The domain separator for the swaps, for the Plonk proof.
+const ANON_XFR_SWAP_PLONK_PROOF_TRANSCRIPT: &[u8] = b"Anon Xfr Swap Plonk Proof";
+/// The domain separator for the swaps, for address folding.
+const ANON_XFR_SWAP_FOLDING_PROOF_TRANSCRIPT: &[u8] = b"Anon Xfr Swap Folding Proof";
+/// The domain separator for the number of inputs of each party.
+const N_INPUTS_TRANSCRIPT: &[u8] = b"Number of input ABARs";
+/// The domain separator for the number of outputs.
+const N_OUTPUTS_TRANSCRIPT: &[u8] = b"Number of output ABARs";
+/// The domain separator for the party of an address folding.
+const PARTY_TRANSCRIPT: &[u8] = b"Party";
+
+/// What a party of a swap expects to receive from the other party.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AXfrSwapTerms {
+    /// The asset type.
+    pub asset_type: AssetType,
+    /// The minimal amount.
+    pub amount: u64,
+}
+
+/// An atomic exchange between two anonymous parties.
+///
+/// Each party spends its own inputs, of any asset types, and the outputs go to either party.
+/// A single Plonk proof covers the inputs of both parties and enforces the conservation of
+/// each asset type over all of them, and each party co-signs the swap by the address folding
+/// of its key, so the swap is valid only as a whole.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AXfrSwapNote {
+    /// The body of the swap.
+    pub body: AXfrSwapBody,
+    /// The Plonk proof (assuming non-malleability).
+    pub proof: AXfrPlonkPf,
+    /// The address folding instances, one for the key of each party.
+    pub folding_instances: [AXfrAddressFoldingInstance; 2],
+}
+
+/// The body of a swap.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AXfrSwapBody {
+    /// The inputs of each party, in terms of nullifiers.
+    pub inputs: [Vec<Nullifier>; 2],
+    /// The outputs, in terms of new anonymous asset records.
+    pub outputs: Vec<AnonAssetRecord>,
+    /// The Merkle tree root.
+    pub merkle_root: BN254Scalar,
+    /// An index of the Merkle tree root in the ledger.
+    pub merkle_root_version: u64,
+    /// The amount of fee.
+    pub fee: u32,
+    /// The owner memos.
+    pub owner_memos: Vec<AxfrOwnerMemo>,
+}
+
+/// The witness of a swap.
+#[derive(Debug, Clone)]
+pub struct AXfrSwapWitness {
+    /// The payers' witnesses of each party.
+    pub payers_witnesses: [Vec<PayerWitness>; 2],
+    /// The payees' witnesses.
+    pub payees_witnesses: Vec<PayeeWitness>,
+    /// The fee.
+    pub fee: u32,
+}
+
+/// A swap without the proof and the address foldings. It holds the secret keys of both
+/// parties, which the proof needs to derive their nullifiers.
+#[derive(Debug, Clone)]
+pub struct AXfrSwapPreNote {
+    /// The body of the swap.
+    pub body: AXfrSwapBody,
+    /// Witness.
+    pub witness: AXfrSwapWitness,
+    /// The traces of the input commitments of each party.
+    pub input_commitments_traces: [Vec<AnemoiVLHTrace<BN254Scalar, 2, 14>>; 2],
+    /// The traces of the output commitments.
+    pub output_commitments_traces: Vec<AnemoiVLHTrace<BN254Scalar, 2, 14>>,
+    /// The traces of the nullifiers of each party.
+    pub nullifiers_traces: [Vec<AnemoiVLHTrace<BN254Scalar, 2, 14>>; 2],
+    /// The key pair of each party.
+    pub input_keypairs: [KeyPair; 2],
+}
+
+impl AXfrSwapWitness {
+    /// Create a fake witness, for the constraint system of the given numbers of inputs
+    /// and outputs.
+    pub fn fake(
+        n_payers: [usize; 2],
+        n_payees: usize,
+        address_formats: [AddressFormat; 2],
+    ) -> Self {
+        let [payers_0, payers_1] = [0, 1]
+            .map(|i| AXfrWitness::fake(n_payers[i], 0, 0, address_formats[i]).payers_witnesses);
+        AXfrSwapWitness {
+            payers_witnesses: [payers_0, payers_1],
+            payees_witnesses: AXfrWitness::fake(0, n_payees, 0, address_formats[0])
+                .payees_witnesses,
+            fee: 0,
+        }
+    }
+}
+
+/// Check that the body pays the terms to the key pair, by opening its outputs to the key pair.
+/// Each party checks this before it lets the swap be finished.
+pub fn check_anon_xfr_swap_terms(
+    body: &AXfrSwapBody,
+    keypair: &KeyPair,
+    terms: &AXfrSwapTerms,
+) -> Result<()> {
+    let mut received = 0u64;
+    for (output, memo) in body.outputs.iter().zip(body.owner_memos.iter()) {
+        if let Ok(builder) = OpenAnonAssetRecordBuilder::from_abar(output, memo.clone(), keypair) {
+            let oabar = builder.build()?;
+            if oabar.get_asset_type() == terms.asset_type {
+                received = received.saturating_add(oabar.get_amount());
+            }
+        }
+    }
+
+    if received < terms.amount {
+        return Err(NoahError::ParameterError);
+    }
+    Ok(())
+}
+
+/// Build a swap without generating the proof, from the inputs of each party, spent by its
+/// key pair, and the outputs to both parties.
+pub fn init_anon_xfr_swap_note(
+    inputs: [&[OpenAnonAssetRecord]; 2],
+    outputs: &[OpenAnonAssetRecord],
+    fee: u32,
+    input_keypairs: [&KeyPair; 2],
+) -> Result<AXfrSwapPreNote> {
+    if inputs.iter().any(|inputs| inputs.is_empty()) || outputs.is_empty() {
+        return Err(NoahError::AXfrProverParamsError);
+    }
+    for (inputs, keypair) in inputs.iter().zip(input_keypairs.iter()) {
+        check_inputs(inputs, keypair)?;
+    }
+    let all_inputs = inputs.concat();
+    check_asset_amount(&all_inputs, outputs, fee)?;
+    check_roots(&all_inputs)?;
+
+    let mut nullifiers = [vec![], vec![]];
+    let mut nullifiers_traces = [vec![], vec![]];
+    let mut input_commitments_traces = [vec![], vec![]];
+    let mut payers_witnesses = [vec![], vec![]];
+    for (party, (inputs, keypair)) in inputs.iter().zip(input_keypairs).enumerate() {
+        for input in inputs.iter() {
+            let mt_leaf_info = input.mt_leaf_info.as_ref().unwrap();
+
+            let (nullifier, nullifier_trace) = nullify(
+                keypair,
+                input.amount,
+                input.asset_type.as_scalar(),
+                mt_leaf_info.uid,
+            )?;
+            nullifiers[party].push(nullifier);
+            nullifiers_traces[party].push(nullifier_trace);
+
+            let (_, commitment_trace) = commit(
+                &keypair.get_pk(),
+                input.blind,
+                input.amount,
+                input.asset_type.as_scalar(),
+            )?;
+            input_commitments_traces[party].push(commitment_trace);
+
+            payers_witnesses[party].push(PayerWitness {
+                secret_key: keypair.get_sk(),
+                uid: mt_leaf_info.uid,
+                amount: input.amount,
+                asset_type: input.asset_type.as_scalar(),
+                path: mt_leaf_info.path.clone(),
+                blind: input.blind,
+            });
+        }
+    }
+    if nullifiers[0]
+        .iter()
+        .any(|nullifier| nullifiers[1].contains(nullifier))
+    {
+        return Err(NoahError::ParameterError);
+    }
+
+    // order the outputs canonically, so that the order does not reveal whose they are
+    let commitments = outputs
+        .iter()
+        .map(|output| AnonAssetRecord::from_oabar(output).commitment)
+        .collect_vec();
+    let outputs = outputs_order(&nullifiers.concat(), &commitments)
+        .into_iter()
+        .map(|i| outputs[i].clone())
+        .collect_vec();
+
+    let payees_witnesses = outputs
+        .iter()
+        .map(|output| PayeeWitness {
+            amount: output.amount,
+            blind: output.blind,
+            asset_type: output.asset_type.as_scalar(),
+            public_key: output.pub_key,
+        })
+        .collect();
+    let output_commitments_traces = outputs
+        .iter()
+        .map(|output| {
+            commit(
+                &output.pub_key,
+                output.blind,
+                output.amount,
+                output.asset_type.as_scalar(),
+            )
+            .map(|(_, trace)| trace)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let owner_memos = outputs
+        .iter()
+        .map(|output| output.owner_memo.clone().ok_or(NoahError::ParameterError))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mt_leaf_info = all_inputs[0].mt_leaf_info.as_ref().unwrap();
+    let body = AXfrSwapBody {
+        inputs: nullifiers,
+        outputs: outputs.iter().map(AnonAssetRecord::from_oabar).collect(),
+        merkle_root: mt_leaf_info.root,
+        merkle_root_version: mt_leaf_info.root_version,
+        fee,
+        owner_memos,
+    };
+
+    Ok(AXfrSwapPreNote {
+        body,
+        witness: AXfrSwapWitness {
+            payers_witnesses,
+            payees_witnesses,
+            fee,
+        },
+        input_commitments_traces,
+        output_commitments_traces,
+        nullifiers_traces,
+        input_keypairs: [input_keypairs[0].clone(), input_keypairs[1].clone()],
+    })
+}
+
+/// Finish a swap, with the address folding of the key of each party over the hash.
+pub fn finish_anon_xfr_swap_note<
+    R: CryptoRng + RngCore,
+    D: Digest<OutputSize = U64> + Default + Clone,
+    PCS: AXfrPCS,
+>(
+    prng: &mut R,
+    params: &ProverParams<PCS>,
+    pre_note: AXfrSwapPreNote,
+    hash: D,
+) -> Result<AXfrSwapNote> {
+    let AXfrSwapPreNote {
+        body,
+        witness,
+        input_commitments_traces,
+        output_commitments_traces,
+        nullifiers_traces,
+        input_keypairs,
+    } = pre_note;
+
+    let mut folding_instances = vec![];
+    let mut folding_witnesses = vec![];
+    for (party, keypair) in input_keypairs.iter().enumerate() {
+        let mut transcript = swap_folding_transcript(party);
+        match keypair.get_sk_ref() {
+            SecretKey::Secp256k1(_) => {
+                let (folding_instance, folding_witness) =
+                    create_address_folding_secp256k1(prng, hash.clone(), &mut transcript, keypair)?;
+                folding_instances.push(AXfrAddressFoldingInstance::Secp256k1(folding_instance));
+                folding_witnesses.push(AXfrAddressFoldingWitness::Secp256k1(folding_witness));
+            }
+            SecretKey::Ed25519(_) => {
+                let (folding_instance, folding_witness) =
+                    create_address_folding_ed25519(prng, hash.clone(), &mut transcript, keypair)?;
+                folding_instances.push(AXfrAddressFoldingInstance::Ed25519(folding_instance));
+                folding_witnesses.push(AXfrAddressFoldingWitness::Ed25519(folding_witness));
+            }
+        }
+    }
+    let folding_witnesses = [&folding_witnesses[0], &folding_witnesses[1]];
+
+    let mut transcript = swap_plonk_transcript(
+        [
+            witness.payers_witnesses[0].len(),
+            witness.payers_witnesses[1].len(),
+        ],
+        witness.payees_witnesses.len(),
+    );
+    let (mut cs, _) = build_abar_swap_cs(
+        &witness,
+        FEE_TYPE.as_scalar(),
+        [&nullifiers_traces[0], &nullifiers_traces[1]],
+        [&input_commitments_traces[0], &input_commitments_traces[1]],
+        &output_commitments_traces,
+        folding_witnesses,
+    );
+    let cs_witness = cs.get_and_clear_witness();
+    PublicInputsSchema::abar_swap(
+        [
+            witness.payers_witnesses[0].len(),
+            witness.payers_witnesses[1].len(),
+        ],
+        witness.payees_witnesses.len(),
+        folding_witnesses.map(|w| w.get_address_format()),
+    )
+    .validate_witness(&cs, &cs_witness)?;
+
+    let proof = prover_with_lagrange(
+        prng,
+        &mut transcript,
+        &params.pcs,
+        params.lagrange_pcs.as_ref(),
+        &params.cs,
+        &params.prover_params,
+        &cs_witness,
+    )?;
+
+    let [folding_instance_0, folding_instance_1]: [AXfrAddressFoldingInstance; 2] =
+        folding_instances
+            .try_into()
+            .map_err(|_| NoahError::ParameterError)?;
+    Ok(AXfrSwapNote {
+        body,
+        proof,
+        folding_instances: [folding_instance_0, folding_instance_1],
+    })
+}
+
+/// Verify a swap.
+pub fn verify_anon_xfr_swap_note<D: Digest<OutputSize = U64> + Default + Clone>(
+    params: &VerifierParams,
+    note: &AXfrSwapNote,
+    merkle_root: &BN254Scalar,
+    hash: D,
+) -> Result<()> {
+    let body = &note.body;
+    if *merkle_root != body.merkle_root {
+        return Err(NoahError::AXfrVerificationError);
+    }
+    if body.inputs.iter().any(|inputs| inputs.is_empty())
+        || body.outputs.is_empty()
+        || body.owner_memos.len() != body.outputs.len()
+        || body.owner_memos.iter().any(|memo| memo.is_oversized())
+    {
+        return Err(NoahError::AXfrVerificationError);
+    }
+    if body.inputs[0]
+        .iter()
+        .any(|nullifier| body.inputs[1].contains(nullifier))
+    {
+        return Err(NoahError::AXfrVerificationError);
+    }
+
+    let mut online_inputs = body.inputs.concat();
+    online_inputs.push(*merkle_root);
+    online_inputs.extend(body.outputs.iter().map(|output| output.commitment));
+    online_inputs.push(BN254Scalar::from(body.fee));
+    for (party, folding_instance) in note.folding_instances.iter().enumerate() {
+        let mut transcript = swap_folding_transcript(party);
+        let address_folding_public_input = match folding_instance {
+            AXfrAddressFoldingInstance::Secp256k1(a) => {
+                let (beta, lambda) =
+                    verify_address_folding_secp256k1(hash.clone(), &mut transcript, a)?;
+                prepare_verifier_input_secp256k1(a, &beta, &lambda)
+            }
+            AXfrAddressFoldingInstance::Ed25519(a) => {
+                let (beta, lambda) =
+                    verify_address_folding_ed25519(hash.clone(), &mut transcript, a)?;
+                prepare_verifier_input_ed25519(a, &beta, &lambda)
+            }
+        };
+        online_inputs.extend_from_slice(&address_folding_public_input);
+    }
+
+    let n_payers = [body.inputs[0].len(), body.inputs[1].len()];
+    PublicInputsSchema::abar_swap(
+        n_payers,
+        body.outputs.len(),
+        [
+            note.folding_instances[0].get_address_format(),
+            note.folding_instances[1].get_address_format(),
+        ],
+    )
+    .validate(&online_inputs)?;
+
+    let mut transcript = swap_plonk_transcript(n_payers, body.outputs.len());
+    Ok(verifier(
+        &mut transcript,
+        &params.shrunk_vk,
+        &params.shrunk_cs,
+        &params.verifier_params,
+        &online_inputs,
+        &note.proof,
+    )?)
+}
+
+/// Initialize the transcript of the address folding of a party.
+fn swap_folding_transcript(party: usize) -> Transcript {
+    let mut transcript = Transcript::new(ANON_XFR_SWAP_FOLDING_PROOF_TRANSCRIPT);
+    transcript.append_u64(PARTY_TRANSCRIPT, party as u64);
+    transcript
+}
+
+/// Initialize the transcript of the Plonk proof of a swap.
+fn swap_plonk_transcript(n_payers: [usize; 2], n_payees: usize) -> Transcript {
+    let mut transcript = Transcript::new(ANON_XFR_SWAP_PLONK_PROOF_TRANSCRIPT);
+    for n in n_payers.iter() {
+        transcript.append_u64(N_INPUTS_TRANSCRIPT, *n as u64);
+    }
+    transcript.append_u64(N_OUTPUTS_TRANSCRIPT, n_payees as u64);
+    transcript
+}
+
+/// Construct the constraint system of a swap, which spends the inputs of each party under its
+/// own key, and mixes the inputs of both parties into the outputs.
+pub fn build_abar_swap_cs(
+    witness: &AXfrSwapWitness,
+    fee_type: BN254Scalar,
+    nullifiers_traces: [&[AnemoiVLHTrace<BN254Scalar, 2, 14>]; 2],
+    input_commitments_traces: [&[AnemoiVLHTrace<BN254Scalar, 2, 14>]; 2],
+    output_commitments_traces: &[AnemoiVLHTrace<BN254Scalar, 2, 14>],
+    folding_witnesses: [&AXfrAddressFoldingWitness; 2],
+) -> (TurboPlonkCS, usize) {
+    assert!(witness.payers_witnesses.iter().all(|w| !w.is_empty()));
+    assert_ne!(witness.payees_witnesses.len(), 0);
+
+    let mut cs = TurboCS::<BN254Scalar>::new();
+
+    cs.load_anemoi_jive_parameters::<AnemoiJive254>();
+
+    let payers_secrets = [0, 1].map(|party| {
+        add_payers_witnesses(
+            &mut cs,
+            &witness.payers_witnesses[party].iter().collect_vec(),
+        )
+    });
+    let payees_secrets = add_payees_witnesses(&mut cs, &witness.payees_witnesses);
+
+    let pow_2_64 = BN254Scalar::from(u64::MAX).add(&BN254Scalar::one());
+    let zero = BN254Scalar::zero();
+    let one = BN254Scalar::one();
+    let zero_var = cs.zero_var();
+    let mut root_var: Option<VarIndex> = None;
+    let mut keys_vars = vec![];
+
+    for (party, folding_witness) in folding_witnesses.iter().enumerate() {
+        let keypair = folding_witness.keypair();
+        let public_key_scalars = keypair.get_pk().to_bn_scalars().unwrap();
+        let secret_key_scalars = keypair.get_sk().to_bn_scalars().unwrap();
+
+        let public_key_scalars_vars = [
+            cs.new_variable(public_key_scalars[0]),
+            cs.new_variable(public_key_scalars[1]),
+            cs.new_variable(public_key_scalars[2]),
+        ];
+        let secret_key_scalars_vars = [
+            cs.new_variable(secret_key_scalars[0]),
+            cs.new_variable(secret_key_scalars[1]),
+        ];
+
+        let secret_key_type = match keypair.get_sk_ref() {
+            SecretKey::Ed25519(_) => BN254Scalar::one(),
+            SecretKey::Secp256k1(_) => BN254Scalar::zero(),
+        };
+        let secret_key_type_var = cs.new_variable(secret_key_type);
+        cs.insert_boolean_gate(secret_key_type_var);
+
+        for (((payer_witness_var, input_commitment_trace), nullifier_trace), payer_witness) in
+            payers_secrets[party]
+                .iter()
+                .zip(input_commitments_traces[party].iter())
+                .zip(nullifiers_traces[party].iter())
+                .zip(witness.payers_witnesses[party].iter())
+        {
+            // commitments.
+            let com_abar_in_var = commit_in_cs(
+                &mut cs,
+                payer_witness_var.blind,
+                payer_witness_var.amount,
+                payer_witness_var.asset_type,
+                secret_key_type_var,
+                &public_key_scalars_vars,
+                input_commitment_trace,
+            );
+
+            // prove pre-image of the nullifier.
+            // 0 <= `amount` < 2^64, so we can encode (`uid`||`amount`) to `uid` * 2^64 + `amount`.
+            let uid_amount = cs.linear_combine(
+                &[
+                    payer_witness_var.uid,
+                    payer_witness_var.amount,
+                    zero_var,
+                    zero_var,
+                ],
+                pow_2_64,
+                one,
+                zero,
+                zero,
+            );
+            let nullifier_var = nullify_in_cs(
+                &mut cs,
+                &secret_key_scalars_vars,
+                uid_amount,
+                payer_witness_var.asset_type,
+                secret_key_type_var,
+                &public_key_scalars_vars,
+                nullifier_trace,
+            );
+
+            // Merkle path authentication.
+            let acc_elem = AccElemVars {
+                uid: payer_witness_var.uid,
+                commitment: com_abar_in_var,
+            };
+            let (commitment, _) = commit(
+                &keypair.get_pk(),
+                payer_witness.blind,
+                payer_witness.amount,
+                payer_witness.asset_type,
+            )
+            .unwrap();
+            let leaf_trace = AnemoiJive254::eval_variable_length_hash_with_trace(&[
+                BN254Scalar::from(payer_witness.uid),
+                commitment,
+            ]);
+            let path_traces = payer_witness
+                .path
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(i, mt_node)| {
+                    AnemoiJive254::eval_jive_with_trace(
+                        &[mt_node.left, mt_node.mid],
+                        &[mt_node.right, ANEMOI_JIVE_BN254_SALTS[i]],
+                    )
+                })
+                .collect::<Vec<_>>();
+            let tmp_root_var = compute_merkle_root_variables(
+                &mut cs,
+                acc_elem,
+                &payer_witness_var.path,
+                &leaf_trace,
+                &path_traces,
+            );
+
+            cs.range_check(payer_witness_var.amount, AMOUNT_LEN);
+
+            // both parties spend at the same Merkle root.
+            if let Some(root) = root_var {
+                cs.equal(root, tmp_root_var);
+            } else {
+                root_var = Some(tmp_root_var);
+            }
+
+            cs.prepare_pi_variable(nullifier_var);
+        }
+
+        keys_vars.push((public_key_scalars_vars, secret_key_scalars_vars));
+    }
+    cs.prepare_pi_variable(root_var.unwrap()); // safe unwrap
+
+    for (payee, output_commitment_trace) in
+        payees_secrets.iter().zip(output_commitments_traces.iter())
+    {
+        let com_abar_out_var = commit_in_cs(
+            &mut cs,
+            payee.blind,
+            payee.amount,
+            payee.asset_type,
+            payee.public_key_type,
+            &payee.public_key_scalars,
+            output_commitment_trace,
+        );
+
+        cs.range_check(payee.amount, AMOUNT_LEN);
+
+        cs.prepare_pi_variable(com_abar_out_var);
+    }
+
+    // the conservation of each asset type is over the inputs of both parties.
+    let inputs: Vec<(VarIndex, VarIndex)> = payers_secrets
+        .iter()
+        .flatten()
+        .map(|payer| (payer.asset_type, payer.amount))
+        .collect();
+    let outputs: Vec<(VarIndex, VarIndex)> = payees_secrets
+        .iter()
+        .map(|payee| (payee.asset_type, payee.amount))
+        .collect();
+
+    let fee_var = cs.new_variable(BN254Scalar::from(witness.fee));
+    cs.prepare_pi_variable(fee_var);
+
+    for ((public_key_scalars_vars, secret_key_scalars_vars), folding_witness) in
+        keys_vars.iter().zip(folding_witnesses.iter())
+    {
+        match folding_witness {
+            AXfrAddressFoldingWitness::Secp256k1(a) => prove_address_folding_in_cs_secp256k1(
+                &mut cs,
+                public_key_scalars_vars,
+                secret_key_scalars_vars,
+                a,
+            )
+            .unwrap(),
+            AXfrAddressFoldingWitness::Ed25519(a) => prove_address_folding_in_cs_ed25519(
+                &mut cs,
+                public_key_scalars_vars,
+                secret_key_scalars_vars,
+                a,
+            )
+            .unwrap(),
+        }
+    }
+
+    asset_mixing(&mut cs, &inputs, &outputs, fee_type, fee_var);
+
+    // pad the number of constraints to power of two.
+    cs.pad();
+
+    let n_constraints = cs.size;
+    (cs, n_constraints)
+}
@@ -0,0 +1,507 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey, PublicKeyInner};
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use noah_crypto::anemoi_jive::{AnemoiJive, AnemoiJive254, ApplicableMDSMatrix, MDSMatrix};
+
+/// The number of lanes of the Anemoi state of the nullifier hash.
+const N: usize = 2;
+/// The number of rounds of the Anemoi permutation of the nullifier hash.
+const NUM_ROUNDS: usize = 14;
+/// The number of elements of the input of the nullifier hash.
+const NULLIFIER_INPUT_LEN: usize = 9;
+/// The number of Anemoi permutations of the nullifier hash.
+const NUM_PERMUTATIONS: usize = NULLIFIER_INPUT_LEN / (2 * N - 1);
+/// The number of square pairs consumed by one nullifier derivation.
+const NUM_SQUARE_PAIRS: usize = NUM_PERMUTATIONS * NUM_ROUNDS * N * 2;
+/// The number of inverse power tuples consumed by one nullifier derivation.
+const NUM_INVERSE_POWER_TUPLES: usize = NUM_PERMUTATIONS * NUM_ROUNDS * N;
+
+/// The share of a custodian of the key of ABARs, under a `t`-of-`n` Shamir secret sharing
+/// of the scalars of the secret key that enter the nullifiers.
+///
+/// The shares only derive nullifiers, e.g., for the custodians to watch for the spending of
+/// the ABARs: an anonymous transfer still needs the whole secret key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThresholdNullifierKeyShare {
+    /// The index of the custodian, from 1 to `n`.
+    pub index: u64,
+    /// The number of custodians needed to derive a nullifier.
+    pub threshold: usize,
+    /// The public key of the shared key.
+    pub public_key: PublicKey,
+    /// The shares of the scalars of the secret key.
+    pub(crate) secret_key_shares: [BN254Scalar; 2],
+}
+
+/// The correlated randomness of a custodian for deriving one nullifier, which is dealt
+/// ahead of time and must be used only once.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThresholdNullifierPreprocessing {
+    /// The index of the custodian, from 1 to `n`.
+    pub index: u64,
+    /// The shares of `(a, a^2)` for random `a`.
+    pub(crate) square_pairs: Vec<[BN254Scalar; 2]>,
+    /// The shares of `(u, r^alpha, u * r^alpha, r^{-1})` for random `u` and `r`.
+    pub(crate) inverse_power_tuples: Vec<[BN254Scalar; 4]>,
+}
+
+/// The channel between the custodians who jointly derive a nullifier.
+///
+/// The shares that go through the channel are not verified, and the protocol has no abort,
+/// so the security model is semi-honest: the custodians are assumed to follow the protocol,
+/// and one who deviates can make the nullifier wrong without being detected.
+pub trait ThresholdChannel {
+    /// Send the shares of the values being opened to every other custodian of the session,
+    /// and return the shares of all the custodians of the session, including this one.
+    fn exchange(&mut self, shares: &[BN254Scalar]) -> Result<Vec<Vec<BN254Scalar>>>;
+}
+
+/// Split the key into `num_custodians` shares, any `threshold` of which can
+/// jointly derive the nullifiers of the ABARs of the key.
+pub fn split_nullifier_key<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    keypair: &KeyPair,
+    threshold: usize,
+    num_custodians: usize,
+) -> Result<Vec<ThresholdNullifierKeyShare>> {
+    let secret_key_scalars = keypair.get_sk_ref().to_bn_scalars()?;
+    let shares = secret_key_scalars
+        .iter()
+        .map(|s| shamir_share(prng, s, threshold, num_custodians))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((0..num_custodians)
+        .map(|i| ThresholdNullifierKeyShare {
+            index: i as u64 + 1,
+            threshold,
+            public_key: keypair.get_pk(),
+            secret_key_shares: [shares[0][i], shares[1][i]],
+        })
+        .collect())
+}
+
+/// Deal the correlated randomness for deriving one nullifier among any `threshold`
+/// of the `num_custodians` custodians.
+/// Note: the dealer must be trusted not to collude with the custodians, but it never
+/// learns the key.
+pub fn gen_threshold_nullifier_preprocessing<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    threshold: usize,
+    num_custodians: usize,
+) -> Result<Vec<ThresholdNullifierPreprocessing>> {
+    let alpha = [AnemoiJive254::ALPHA as u64];
+
+    let mut res = (0..num_custodians)
+        .map(|i| ThresholdNullifierPreprocessing {
+            index: i as u64 + 1,
+            square_pairs: Vec::with_capacity(NUM_SQUARE_PAIRS),
+            inverse_power_tuples: Vec::with_capacity(NUM_INVERSE_POWER_TUPLES),
+        })
+        .collect_vec();
+
+    for _ in 0..NUM_SQUARE_PAIRS {
+        let a = BN254Scalar::random(prng);
+        let a_shares = shamir_share(prng, &a, threshold, num_custodians)?;
+        let a_sq_shares = shamir_share(prng, &a.square(), threshold, num_custodians)?;
+        for (i, preprocessing) in res.iter_mut().enumerate() {
+            preprocessing
+                .square_pairs
+                .push([a_shares[i], a_sq_shares[i]]);
+        }
+    }
+
+    for _ in 0..NUM_INVERSE_POWER_TUPLES {
+        let u = BN254Scalar::random(prng);
+        let r = BN254Scalar::random(prng);
+        let m = r.pow(&alpha);
+        let values = [u, m, u.mul(&m), r.inv()?];
+        let shares = values
+            .iter()
+            .map(|v| shamir_share(prng, v, threshold, num_custodians))
+            .collect::<Result<Vec<_>>>()?;
+        for (i, preprocessing) in res.iter_mut().enumerate() {
+            preprocessing.inverse_power_tuples.push([
+                shares[0][i],
+                shares[1][i],
+                shares[2][i],
+                shares[3][i],
+            ]);
+        }
+    }
+
+    Ok(res)
+}
+
+/// Jointly derive the nullifier of an ABAR of the shared key, as `nullify` would,
+/// with the custodians of `signers` who run this function at the same time.
+/// If the custodians follow the protocol (see `ThresholdChannel`), no custodian learns
+/// the secret key or anything about it other than the nullifier.
+pub fn derive_threshold_nullifier<C: ThresholdChannel>(
+    share: &ThresholdNullifierKeyShare,
+    preprocessing: ThresholdNullifierPreprocessing,
+    signers: &[u64],
+    amount: u64,
+    asset_type_scalar: BN254Scalar,
+    uid: u64,
+    channel: &mut C,
+) -> Result<BN254Scalar> {
+    let mut sorted = signers.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if sorted.len() != signers.len()
+        || sorted.contains(&0)
+        || signers.len() < share.threshold
+        || !signers.contains(&share.index)
+        || preprocessing.index != share.index
+        || preprocessing.square_pairs.len() != NUM_SQUARE_PAIRS
+        || preprocessing.inverse_power_tuples.len() != NUM_INVERSE_POWER_TUPLES
+    {
+        return Err(NoahError::ParameterError);
+    }
+
+    let mut session = AdditiveSession {
+        is_leader: sorted[0] == share.index,
+        num_signers: signers.len(),
+        lagrange: lagrange_coefficient(share.index, signers)?,
+        square_pairs: preprocessing.square_pairs.into_iter(),
+        inverse_power_tuples: preprocessing.inverse_power_tuples.into_iter(),
+        channel,
+    };
+
+    let pow_2_64 = BN254Scalar::from(u64::MAX).add(&BN254Scalar::from(1u32));
+    let uid_amount = BN254Scalar::from(uid)
+        .mul(&pow_2_64)
+        .add(&BN254Scalar::from(amount));
+    let address_format_number = match share.public_key.inner() {
+        PublicKeyInner::Ed25519(_) => BN254Scalar::one(),
+        PublicKeyInner::Secp256k1(_) => BN254Scalar::zero(),
+        PublicKeyInner::EthAddress(_) => return Err(NoahError::ParameterError),
+    };
+    let public_key_scalars = share.public_key.to_bn_scalars()?;
+
+    let input = [
+        session.constant(&BN254Scalar::zero()), /* protocol version number */
+        session.constant(&uid_amount),          /* uid and amount */
+        session.constant(&asset_type_scalar),   /* asset type */
+        session.constant(&address_format_number), /* address format number */
+        session.constant(&public_key_scalars[0]), /* public key */
+        session.constant(&public_key_scalars[1]), /* public key */
+        session.constant(&public_key_scalars[2]), /* public key */
+        share.secret_key_shares[0].mul(&session.lagrange), /* secret key */
+        share.secret_key_shares[1].mul(&session.lagrange), /* secret key */
+    ];
+
+    // The input fills the rate of the sponge exactly, so there is no padding.
+    let mut x = [BN254Scalar::zero(); N];
+    let mut y = [BN254Scalar::zero(); N];
+    for chunk in input.chunks_exact(2 * N - 1) {
+        for i in 0..N {
+            x[i] += &chunk[i];
+        }
+        for i in 0..(N - 1) {
+            y[i] += &chunk[N + i];
+        }
+        session.anemoi_permutation(&mut x, &mut y)?;
+    }
+
+    Ok(session.open(&[x[0]])?[0])
+}
+
+/// The state of a custodian in a session, where the shares of the signers are converted
+/// into additive shares, so that the values are opened by summing the shares.
+struct AdditiveSession<'a, C: ThresholdChannel> {
+    is_leader: bool,
+    num_signers: usize,
+    lagrange: BN254Scalar,
+    square_pairs: ark_std::vec::IntoIter<[BN254Scalar; 2]>,
+    inverse_power_tuples: ark_std::vec::IntoIter<[BN254Scalar; 4]>,
+    channel: &'a mut C,
+}
+
+impl<'a, C: ThresholdChannel> AdditiveSession<'a, C> {
+    /// Return the additive share of a public constant.
+    fn constant(&self, c: &BN254Scalar) -> BN254Scalar {
+        if self.is_leader {
+            *c
+        } else {
+            BN254Scalar::zero()
+        }
+    }
+
+    /// Open the shared values.
+    fn open(&mut self, shares: &[BN254Scalar]) -> Result<Vec<BN254Scalar>> {
+        let all_shares = self.channel.exchange(shares)?;
+        if all_shares.len() != self.num_signers
+            || all_shares.iter().any(|s| s.len() != shares.len())
+        {
+            return Err(NoahError::ParameterError);
+        }
+        Ok((0..shares.len())
+            .map(|i| {
+                all_shares
+                    .iter()
+                    .fold(BN254Scalar::zero(), |acc, s| acc.add(&s[i]))
+            })
+            .collect())
+    }
+
+    /// Return the next preprocessed square pair in additive shares.
+    fn next_square_pair(&mut self) -> Result<[BN254Scalar; 2]> {
+        let [a, a_sq] = self.square_pairs.next().ok_or(NoahError::ParameterError)?;
+        Ok([a.mul(&self.lagrange), a_sq.mul(&self.lagrange)])
+    }
+
+    /// Return the next preprocessed inverse power tuple in additive shares.
+    fn next_inverse_power_tuple(&mut self) -> Result<[BN254Scalar; 4]> {
+        let tuple = self
+            .inverse_power_tuples
+            .next()
+            .ok_or(NoahError::ParameterError)?;
+        Ok(tuple.map(|v| v.mul(&self.lagrange)))
+    }
+
+    /// Square the shared values with the square pairs: `v^2 = a^2 + 2 * d * a + d^2`
+    /// for the opened `d = v - a`.
+    fn square(&mut self, values: &[BN254Scalar; N]) -> Result<[BN254Scalar; N]> {
+        let mut pairs = [[BN254Scalar::zero(); 2]; N];
+        let mut masked = [BN254Scalar::zero(); N];
+        for i in 0..N {
+            pairs[i] = self.next_square_pair()?;
+            masked[i] = values[i].sub(&pairs[i][0]);
+        }
+        let d = self.open(&masked)?;
+
+        let mut res = [BN254Scalar::zero(); N];
+        for i in 0..N {
+            res[i] = pairs[i][1]
+                .add(&d[i].double().mul(&pairs[i][0]))
+                .add(&self.constant(&d[i].square()));
+        }
+        Ok(res)
+    }
+
+    /// Raise the shared values to the power of `alpha^{-1}`, by opening `c = v * r^alpha`
+    /// and returning `c^{1 / alpha} * r^{-1}`.
+    fn inverse_power(&mut self, values: &[BN254Scalar; N]) -> Result<[BN254Scalar; N]> {
+        let alpha_inv = AnemoiJive254::get_alpha_inv();
+
+        let mut tuples = [[BN254Scalar::zero(); 4]; N];
+        let mut masked = [BN254Scalar::zero(); N];
+        for i in 0..N {
+            tuples[i] = self.next_inverse_power_tuple()?;
+            masked[i] = values[i].sub(&tuples[i][0]);
+        }
+        let d = self.open(&masked)?;
+
+        // v * m = u * m + d * m
+        let mut products = [BN254Scalar::zero(); N];
+        for i in 0..N {
+            products[i] = tuples[i][2].add(&d[i].mul(&tuples[i][1]));
+        }
+        let c = self.open(&products)?;
+
+        let mut res = [BN254Scalar::zero(); N];
+        for i in 0..N {
+            res[i] = c[i].pow(&alpha_inv).mul(&tuples[i][3]);
+        }
+        Ok(res)
+    }
+
+    /// Apply the Anemoi permutation to the shared state, as `AnemoiJive::anemoi_permutation`.
+    fn anemoi_permutation(
+        &mut self,
+        x: &mut [BN254Scalar; N],
+        y: &mut [BN254Scalar; N],
+    ) -> Result<()> {
+        let mds = MDSMatrix::<BN254Scalar, N>(AnemoiJive254::MDS_MATRIX);
+        let generator = AnemoiJive254::GENERATOR;
+
+        for r in 0..NUM_ROUNDS {
+            for i in 0..N {
+                x[i] += &self.constant(&AnemoiJive254::ROUND_KEYS_X[r][i]);
+                y[i] += &self.constant(&AnemoiJive254::ROUND_KEYS_Y[r][i]);
+            }
+            mds.permute_in_place(x, y);
+            if AnemoiJive254::USE_PHT {
+                for i in 0..N {
+                    y[i] += &x[i];
+                    x[i] += &y[i];
+                }
+            }
+
+            let y_sq = self.square(y)?;
+            for i in 0..N {
+                x[i] -= &generator.mul(&y_sq[i]);
+            }
+            let x_inv_pow = self.inverse_power(x)?;
+            for i in 0..N {
+                y[i] -= &x_inv_pow[i];
+            }
+            let y_sq = self.square(y)?;
+            for i in 0..N {
+                x[i] += &generator
+                    .mul(&y_sq[i])
+                    .add(&self.constant(&AnemoiJive254::GENERATOR_INV));
+            }
+        }
+        mds.permute_in_place(x, y);
+        if AnemoiJive254::USE_PHT {
+            for i in 0..N {
+                y[i] += &x[i];
+                x[i] += &y[i];
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Share the secret among `num_custodians` with a random polynomial of degree `threshold - 1`,
+/// evaluated at `1, ..., num_custodians`.
+fn shamir_share<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    secret: &BN254Scalar,
+    threshold: usize,
+    num_custodians: usize,
+) -> Result<Vec<BN254Scalar>> {
+    if threshold == 0 || threshold > num_custodians {
+        return Err(NoahError::ParameterError);
+    }
+    let mut coefficients = vec![*secret];
+    for _ in 1..threshold {
+        coefficients.push(BN254Scalar::random(prng));
+    }
+    Ok((1..=num_custodians as u64)
+        .map(|i| {
+            let point = BN254Scalar::from(i);
+            coefficients
+                .iter()
+                .rev()
+                .fold(BN254Scalar::zero(), |acc, c| acc.mul(&point).add(c))
+        })
+        .collect())
+}
+
+/// Return the Lagrange coefficient of the custodian at zero over the signers.
+fn lagrange_coefficient(index: u64, signers: &[u64]) -> Result<BN254Scalar> {
+    let point = BN254Scalar::from(index);
+    let mut num = BN254Scalar::one();
+    let mut den = BN254Scalar::one();
+    for other in signers.iter().filter(|other| **other != index) {
+        let other = BN254Scalar::from(*other);
+        num.mul_assign(&other);
+        den.mul_assign(&other.sub(&point));
+    }
+    Ok(num.mul(&den.inv()?))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anon_xfr::{
+        nullify,
+        threshold::{
+            derive_threshold_nullifier, gen_threshold_nullifier_preprocessing, split_nullifier_key,
+            ThresholdChannel,
+        },
+    };
+    use crate::errors::Result;
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+    use std::sync::{Arc, Barrier, Mutex};
+
+    /// The custodians of a session, each running in its own thread.
+    struct LocalChannel {
+        slot: usize,
+        board: Arc<Mutex<Vec<Vec<BN254Scalar>>>>,
+        barrier: Arc<Barrier>,
+    }
+
+    impl ThresholdChannel for LocalChannel {
+        fn exchange(&mut self, shares: &[BN254Scalar]) -> Result<Vec<Vec<BN254Scalar>>> {
+            self.board.lock().unwrap()[self.slot] = shares.to_vec();
+            self.barrier.wait();
+            let res = self.board.lock().unwrap().clone();
+            self.barrier.wait();
+            Ok(res)
+        }
+    }
+
+    fn check_threshold_nullifier(keypair: &KeyPair, signers: &[u64]) {
+        let mut prng = test_rng();
+        let shares = split_nullifier_key(&mut prng, keypair, 3, 5).unwrap();
+        let preprocessing = gen_threshold_nullifier_preprocessing(&mut prng, 3, 5).unwrap();
+
+        let amount = 10u64;
+        let asset_type_scalar = BN254Scalar::from(7u32);
+        let uid = 123u64;
+        let (expected, _) = nullify(keypair, amount, asset_type_scalar, uid).unwrap();
+
+        let board = Arc::new(Mutex::new(vec![vec![]; signers.len()]));
+        let barrier = Arc::new(Barrier::new(signers.len()));
+        let nullifiers = std::thread::scope(|s| {
+            let handles = signers
+                .iter()
+                .enumerate()
+                .map(|(slot, index)| {
+                    let share = &shares[*index as usize - 1];
+                    let preprocessing = preprocessing[*index as usize - 1].clone();
+                    let mut channel = LocalChannel {
+                        slot,
+                        board: board.clone(),
+                        barrier: barrier.clone(),
+                    };
+                    s.spawn(move || {
+                        derive_threshold_nullifier(
+                            share,
+                            preprocessing,
+                            signers,
+                            amount,
+                            asset_type_scalar,
+                            uid,
+                            &mut channel,
+                        )
+                        .unwrap()
+                    })
+                })
+                .collect_vec();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect_vec()
+        });
+        for nullifier in nullifiers {
+            assert_eq!(nullifier, expected);
+        }
+    }
+
+    #[test]
+    fn test_threshold_nullifier() {
+        let mut prng = test_rng();
+        let ed25519 = KeyPair::sample(&mut prng, ED25519);
+        let secp256k1 = KeyPair::sample(&mut prng, SECP256K1);
+
+        check_threshold_nullifier(&ed25519, &[1, 3, 5]);
+        check_threshold_nullifier(&ed25519, &[2, 3, 4, 5]);
+        check_threshold_nullifier(&secp256k1, &[4, 2, 1]);
+
+        // fewer custodians than the threshold cannot start a session
+        let shares = split_nullifier_key(&mut prng, &ed25519, 3, 5).unwrap();
+        let mut preprocessing = gen_threshold_nullifier_preprocessing(&mut prng, 3, 5).unwrap();
+        let mut channel = LocalChannel {
+            slot: 0,
+            board: Arc::new(Mutex::new(vec![vec![]])),
+            barrier: Arc::new(Barrier::new(1)),
+        };
+        assert!(derive_threshold_nullifier(
+            &shares[0],
+            preprocessing.remove(0),
+            &[1, 2],
+            10,
+            BN254Scalar::zero(),
+            0,
+            &mut channel,
+        )
+        .is_err());
+        assert!(split_nullifier_key(&mut prng, &ed25519, 6, 5).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::anon_xfr::TREE_DEPTH;
+use crate::errors::{NoahError, Result};
+use noah_algebra::prelude::*;
+
+/// The number of leaves of the three-ary Merkle tree of `TREE_DEPTH`.
+pub fn tree_capacity() -> u64 {
+    3u64.pow(TREE_DEPTH as u32)
+}
+
+/// The statistics of the set of anonymous records of a chain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnonymitySetStats {
+    /// The number of records in the Merkle tree.
+    pub leaf_count: u64,
+    /// The number of the published nullifiers, one for each spent record.
+    pub nullifier_count: u64,
+    /// The fraction of the tree that is occupied.
+    pub occupancy: f64,
+    /// The fraction of the records that are spent.
+    pub spent_ratio: f64,
+    /// The number of the unspent records, which is the effective anonymity set of a spending.
+    pub unspent_count: u64,
+}
+
+impl AnonymitySetStats {
+    /// Compute the statistics from the number of records and the number of nullifiers.
+    /// The nullifiers seen by a wallet may be a subset of those of the chain, in which case
+    /// the spent ratio is a lower bound.
+    pub fn new(leaf_count: u64, nullifier_count: u64) -> Result<Self> {
+        if leaf_count > tree_capacity() || nullifier_count > leaf_count {
+            return Err(NoahError::ParameterError);
+        }
+        let ratio = |n: u64, d: u64| if d == 0 { 0f64 } else { n as f64 / d as f64 };
+        Ok(AnonymitySetStats {
+            leaf_count,
+            nullifier_count,
+            occupancy: ratio(leaf_count, tree_capacity()),
+            spent_ratio: ratio(nullifier_count, leaf_count),
+            unspent_count: leaf_count - nullifier_count,
+        })
+    }
+}
+
+/// The number of records inserted in each window of blocks, which wallets use to see how
+/// well the timing of a record is covered by others.
+///
+/// Every unspent record is a candidate of a spending, but an observer who knows when the
+/// spent record was likely created can narrow the candidates down to the records inserted
+/// around that time, which we call the cohort of the record.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InsertionHistogram {
+    window: u64,
+    counts: Vec<u64>,
+}
+
+impl InsertionHistogram {
+    /// Create an empty histogram with windows of `window` blocks.
+    pub fn new(window: u64) -> Result<Self> {
+        if window == 0 {
+            return Err(NoahError::ParameterError);
+        }
+        Ok(InsertionHistogram {
+            window,
+            counts: vec![],
+        })
+    }
+
+    /// Record the insertion of `count` records at the block height.
+    pub fn record(&mut self, height: u64, count: u64) {
+        let index = (height / self.window) as usize;
+        if self.counts.len() <= index {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += count;
+    }
+
+    /// Return the number of records in each window, starting from height zero.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Return the feedback on the cohort of a record inserted at the block height,
+    /// which includes the windows within `radius` of its window.
+    pub fn cohort(&self, height: u64, radius: u64) -> Result<CohortFeedback> {
+        let index = height / self.window;
+        if index as usize >= self.counts.len() {
+            return Err(NoahError::ParameterError);
+        }
+        let size_at = |index: u64| -> u64 {
+            let start = index.saturating_sub(radius) as usize;
+            let end = core::cmp::min(
+                index.saturating_add(radius).saturating_add(1),
+                self.counts.len() as u64,
+            ) as usize;
+            self.counts[start..end].iter().sum()
+        };
+
+        let cohort_size = size_at(index);
+        let smaller = (0..self.counts.len() as u64)
+            .filter(|i| size_at(*i) < cohort_size)
+            .count();
+        Ok(CohortFeedback {
+            cohort_size,
+            percentile: smaller as f64 / self.counts.len() as f64,
+        })
+    }
+}
+
+/// The feedback on the cohort of a record.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CohortFeedback {
+    /// The number of records inserted around the record, including itself.
+    pub cohort_size: u64,
+    /// The fraction of the windows whose cohorts are smaller than that of the record.
+    pub percentile: f64,
+}
+
+/// The thresholds under which a wallet should warn its user.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnonymityThresholds {
+    /// The minimal number of unspent records.
+    pub min_unspent_count: u64,
+    /// The maximal fraction of spent records.
+    pub max_spent_ratio: f64,
+    /// The minimal size of the cohort of a record.
+    pub min_cohort_size: u64,
+}
+
+impl Default for AnonymityThresholds {
+    fn default() -> Self {
+        AnonymityThresholds {
+            min_unspent_count: 1000,
+            max_spent_ratio: 0.9,
+            min_cohort_size: 50,
+        }
+    }
+}
+
+/// A reason that the effective anonymity is low.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnonymityWarning {
+    /// Too few records are unspent.
+    SmallUnspentSet(u64),
+    /// Too many records are spent.
+    HighSpentRatio(f64),
+    /// Too few records are inserted around the record.
+    SmallCohort(u64),
+}
+
+/// Return the warnings on the anonymity of spending a record, given the statistics of the
+/// anonymity set and optionally the feedback on the cohort of the record.
+pub fn assess_anonymity(
+    stats: &AnonymitySetStats,
+    cohort: Option<&CohortFeedback>,
+    thresholds: &AnonymityThresholds,
+) -> Vec<AnonymityWarning> {
+    let mut warnings = vec![];
+    if stats.unspent_count < thresholds.min_unspent_count {
+        warnings.push(AnonymityWarning::SmallUnspentSet(stats.unspent_count));
+    }
+    if stats.spent_ratio > thresholds.max_spent_ratio {
+        warnings.push(AnonymityWarning::HighSpentRatio(stats.spent_ratio));
+    }
+    if let Some(cohort) = cohort {
+        if cohort.cohort_size < thresholds.min_cohort_size {
+            warnings.push(AnonymityWarning::SmallCohort(cohort.cohort_size));
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anonymity::metrics::{
+        assess_anonymity, tree_capacity, AnonymitySetStats, AnonymityThresholds, AnonymityWarning,
+        InsertionHistogram,
+    };
+
+    #[test]
+    fn test_anonymity_metrics() {
+        assert_eq!(tree_capacity(), 847_288_609_443);
+        assert!(AnonymitySetStats::new(10, 11).is_err());
+        assert!(AnonymitySetStats::new(tree_capacity() + 1, 0).is_err());
+
+        let empty = AnonymitySetStats::new(0, 0).unwrap();
+        assert_eq!(empty.spent_ratio, 0f64);
+
+        let stats = AnonymitySetStats::new(2000, 1500).unwrap();
+        assert_eq!(stats.unspent_count, 500);
+        assert_eq!(stats.spent_ratio, 0.75);
+
+        let mut histogram = InsertionHistogram::new(10).unwrap();
+        assert!(InsertionHistogram::new(0).is_err());
+        histogram.record(0, 100);
+        histogram.record(15, 5);
+        histogram.record(25, 200);
+        histogram.record(45, 300);
+        assert_eq!(histogram.counts(), &[100, 5, 200, 0, 300]);
+        assert!(histogram.cohort(50, 0).is_err());
+
+        let lonely = histogram.cohort(15, 0).unwrap();
+        assert_eq!(lonely.cohort_size, 5);
+        assert_eq!(lonely.percentile, 0.2);
+        let wide = histogram.cohort(15, 1).unwrap();
+        assert_eq!(wide.cohort_size, 305);
+
+        let thresholds = AnonymityThresholds::default();
+        assert_eq!(
+            assess_anonymity(&stats, Some(&lonely), &thresholds),
+            vec![
+                AnonymityWarning::SmallUnspentSet(500),
+                AnonymityWarning::SmallCohort(5)
+            ]
+        );
+        let healthy = AnonymitySetStats::new(100_000, 5000).unwrap();
+        assert!(assess_anonymity(&healthy, Some(&wide), &thresholds).is_empty());
+        assert_eq!(
+            assess_anonymity(
+                &AnonymitySetStats::new(100_000, 95_000).unwrap(),
+                None,
+                &thresholds
+            ),
+            vec![AnonymityWarning::HighSpentRatio(0.95)]
+        );
+    }
+}
@@ -0,0 +1,2 @@
+/// Module for the statistics of the anonymity set of the anonymous records.
+pub mod metrics;
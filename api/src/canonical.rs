@@ -0,0 +1,549 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{PublicKey, Signature};
+use noah_algebra::{
+    bn254::{BN254Scalar, BN254G1},
+    prelude::*,
+    ristretto::{CompressedRistretto, RistrettoPoint, RistrettoScalar},
+};
+
+#[cfg(feature = "xfr")]
+use crate::xfr::structs::{
+    AssetType, BlindAssetRecord, XfrAmount, XfrAssetType, ASSET_TYPE_LENGTH,
+};
+
+#[cfg(feature = "anon_xfr")]
+use {
+    crate::anon_xfr::{
+        ar_to_abar::{ArToAbarBody, ArToAbarNote},
+        bar_to_abar::{BarToAbarBody, BarToAbarNote},
+        structs::{AnonAssetRecord, AxfrOwnerMemo},
+    },
+    noah_crypto::delegated_schnorr::DSProof,
+    noah_plonk::{plonk::indexer::PlonkProof, poly_commit::kzg_poly_com::KZGCommitment},
+};
+
+/// The version of the canonical serialization, which is the first byte of every encoding.
+pub const CANONICAL_VERSION: u8 = 1;
+
+/// A stable encoding of the notes and their parts, which does not depend on any serialization
+/// library: integers are little-endian of fixed width, byte strings and sequences are prefixed
+/// by their lengths as `u32`, enum variants and options are prefixed by a tag byte, and the
+/// fields of a struct follow their declaration order.
+pub trait CanonicalSerialize {
+    /// Append the encoding of the value to the buffer.
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()>;
+
+    /// Return the encoding of the value, prefixed by `CANONICAL_VERSION`.
+    fn canonical_serialize(&self) -> Result<Vec<u8>> {
+        let mut buf = vec![CANONICAL_VERSION];
+        self.canonical_write(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// The decoding of `CanonicalSerialize`, which only accepts the canonical encoding of a value.
+pub trait CanonicalDeserialize: Sized {
+    /// Read a value from the front of the reader, and advance the reader past it.
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self>;
+
+    /// Decode the output of `CanonicalSerialize::canonical_serialize`, rejecting other versions
+    /// and trailing bytes.
+    fn canonical_deserialize(bytes: &[u8]) -> Result<Self> {
+        let mut reader = bytes;
+        if u8::canonical_read(&mut reader)? != CANONICAL_VERSION {
+            return Err(NoahError::DeserializationError);
+        }
+        let res = Self::canonical_read(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(NoahError::DeserializationError);
+        }
+        Ok(res)
+    }
+}
+
+/// Append a byte string prefixed by its length.
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) -> Result<()> {
+    let len = u32::try_from(bytes.len()).map_err(|_| NoahError::SerializationError)?;
+    buf.extend_from_slice(&len.to_le_bytes());
+    buf.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// Read `len` bytes from the reader.
+fn read_exact<'a>(reader: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if reader.len() < len {
+        return Err(NoahError::DeserializationError);
+    }
+    let (head, tail) = reader.split_at(len);
+    *reader = tail;
+    Ok(head)
+}
+
+/// Read a byte string prefixed by its length.
+fn read_bytes<'a>(reader: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = u32::canonical_read(reader)? as usize;
+    read_exact(reader, len)
+}
+
+/// Decode a value from its bytes with `from_bytes`, and check that the bytes are the ones
+/// `to_bytes` would return, so that every value has exactly one encoding.
+fn read_canonical_bytes<T>(
+    reader: &mut &[u8],
+    from_bytes: impl Fn(&[u8]) -> Option<T>,
+    to_bytes: impl Fn(&T) -> Vec<u8>,
+) -> Result<T> {
+    let bytes = read_bytes(reader)?;
+    let value = from_bytes(bytes).ok_or(NoahError::DeserializationError)?;
+    if to_bytes(&value) != bytes {
+        return Err(NoahError::DeserializationError);
+    }
+    Ok(value)
+}
+
+macro_rules! canonical_int {
+    ($t:ty) => {
+        impl CanonicalSerialize for $t {
+            fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+                buf.extend_from_slice(&self.to_le_bytes());
+                Ok(())
+            }
+        }
+
+        impl CanonicalDeserialize for $t {
+            fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+                let mut bytes = [0u8; core::mem::size_of::<$t>()];
+                bytes.copy_from_slice(read_exact(reader, core::mem::size_of::<$t>())?);
+                Ok(<$t>::from_le_bytes(bytes))
+            }
+        }
+    };
+}
+
+canonical_int!(u8);
+canonical_int!(u32);
+canonical_int!(u64);
+
+macro_rules! canonical_scalar {
+    ($t:ty) => {
+        impl CanonicalSerialize for $t {
+            fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+                write_bytes(buf, &self.to_bytes())
+            }
+        }
+
+        impl CanonicalDeserialize for $t {
+            fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+                read_canonical_bytes(reader, |b| <$t>::from_bytes(b).ok(), |s| s.to_bytes())
+            }
+        }
+    };
+}
+
+canonical_scalar!(BN254Scalar);
+canonical_scalar!(RistrettoScalar);
+
+macro_rules! canonical_group {
+    ($t:ty) => {
+        impl CanonicalSerialize for $t {
+            fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+                write_bytes(buf, &self.to_compressed_bytes())
+            }
+        }
+
+        impl CanonicalDeserialize for $t {
+            fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+                read_canonical_bytes(
+                    reader,
+                    |b| <$t>::from_compressed_bytes(b).ok(),
+                    |p| p.to_compressed_bytes(),
+                )
+            }
+        }
+    };
+}
+
+canonical_group!(BN254G1);
+canonical_group!(RistrettoPoint);
+
+macro_rules! canonical_noah_bytes {
+    ($t:ty) => {
+        impl CanonicalSerialize for $t {
+            fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+                write_bytes(buf, &self.noah_to_bytes())
+            }
+        }
+
+        impl CanonicalDeserialize for $t {
+            fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+                read_canonical_bytes(
+                    reader,
+                    |b| <$t>::noah_from_bytes(b).ok(),
+                    |v| v.noah_to_bytes(),
+                )
+            }
+        }
+    };
+}
+
+canonical_noah_bytes!(PublicKey);
+canonical_noah_bytes!(Signature);
+
+impl CanonicalSerialize for CompressedRistretto {
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+        write_bytes(buf, &self.to_bytes())
+    }
+}
+
+impl CanonicalDeserialize for CompressedRistretto {
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+        let bytes = read_bytes(reader)?;
+        if bytes.len() != 32 {
+            return Err(NoahError::DeserializationError);
+        }
+        Ok(CompressedRistretto::from_slice(bytes))
+    }
+}
+
+impl<T: CanonicalSerialize> CanonicalSerialize for Vec<T> {
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+        let len = u32::try_from(self.len()).map_err(|_| NoahError::SerializationError)?;
+        len.canonical_write(buf)?;
+        for item in self.iter() {
+            item.canonical_write(buf)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: CanonicalDeserialize> CanonicalDeserialize for Vec<T> {
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+        let len = u32::canonical_read(reader)? as usize;
+        // every item takes at least one byte, which bounds the allocation
+        if len > reader.len() {
+            return Err(NoahError::DeserializationError);
+        }
+        let mut res = Vec::with_capacity(len);
+        for _ in 0..len {
+            res.push(T::canonical_read(reader)?);
+        }
+        Ok(res)
+    }
+}
+
+impl<T: CanonicalSerialize> CanonicalSerialize for Option<T> {
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+        match self {
+            None => 0u8.canonical_write(buf),
+            Some(v) => {
+                1u8.canonical_write(buf)?;
+                v.canonical_write(buf)
+            }
+        }
+    }
+}
+
+impl<T: CanonicalDeserialize> CanonicalDeserialize for Option<T> {
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+        match u8::canonical_read(reader)? {
+            0 => Ok(None),
+            1 => Ok(Some(T::canonical_read(reader)?)),
+            _ => Err(NoahError::DeserializationError),
+        }
+    }
+}
+
+impl<A: CanonicalSerialize, B: CanonicalSerialize> CanonicalSerialize for (A, B) {
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+        self.0.canonical_write(buf)?;
+        self.1.canonical_write(buf)
+    }
+}
+
+impl<A: CanonicalDeserialize, B: CanonicalDeserialize> CanonicalDeserialize for (A, B) {
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+        Ok((A::canonical_read(reader)?, B::canonical_read(reader)?))
+    }
+}
+
+#[cfg(feature = "xfr")]
+impl CanonicalSerialize for AssetType {
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+        write_bytes(buf, &self.0)
+    }
+}
+
+#[cfg(feature = "xfr")]
+impl CanonicalDeserialize for AssetType {
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+        let bytes = read_bytes(reader)?;
+        if bytes.len() != ASSET_TYPE_LENGTH {
+            return Err(NoahError::DeserializationError);
+        }
+        let mut res = [0u8; ASSET_TYPE_LENGTH];
+        res.copy_from_slice(bytes);
+        Ok(AssetType(res))
+    }
+}
+
+#[cfg(feature = "xfr")]
+impl CanonicalSerialize for XfrAmount {
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+        match self {
+            XfrAmount::Confidential(commitments) => {
+                0u8.canonical_write(buf)?;
+                commitments.canonical_write(buf)
+            }
+            XfrAmount::NonConfidential(amount) => {
+                1u8.canonical_write(buf)?;
+                amount.canonical_write(buf)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "xfr")]
+impl CanonicalDeserialize for XfrAmount {
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+        match u8::canonical_read(reader)? {
+            0 => Ok(XfrAmount::Confidential(
+                CanonicalDeserialize::canonical_read(reader)?,
+            )),
+            1 => Ok(XfrAmount::NonConfidential(u64::canonical_read(reader)?)),
+            _ => Err(NoahError::DeserializationError),
+        }
+    }
+}
+
+#[cfg(feature = "xfr")]
+impl CanonicalSerialize for XfrAssetType {
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+        match self {
+            XfrAssetType::Confidential(commitment) => {
+                0u8.canonical_write(buf)?;
+                commitment.canonical_write(buf)
+            }
+            XfrAssetType::NonConfidential(asset_type) => {
+                1u8.canonical_write(buf)?;
+                asset_type.canonical_write(buf)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "xfr")]
+impl CanonicalDeserialize for XfrAssetType {
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+        match u8::canonical_read(reader)? {
+            0 => Ok(XfrAssetType::Confidential(
+                CompressedRistretto::canonical_read(reader)?,
+            )),
+            1 => Ok(XfrAssetType::NonConfidential(AssetType::canonical_read(
+                reader,
+            )?)),
+            _ => Err(NoahError::DeserializationError),
+        }
+    }
+}
+
+/// Implement the canonical serialization of a struct as the sequence of its fields.
+macro_rules! canonical_struct {
+    ($t:ty, $($field:ident),+) => {
+        impl CanonicalSerialize for $t {
+            fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+                $(self.$field.canonical_write(buf)?;)+
+                Ok(())
+            }
+        }
+
+        impl CanonicalDeserialize for $t {
+            fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+                Ok(Self {
+                    $($field: CanonicalDeserialize::canonical_read(reader)?,)+
+                })
+            }
+        }
+    };
+}
+
+#[cfg(feature = "xfr")]
+canonical_struct!(BlindAssetRecord, amount, asset_type, public_key);
+
+#[cfg(feature = "anon_xfr")]
+canonical_struct!(AnonAssetRecord, commitment);
+
+#[cfg(feature = "anon_xfr")]
+impl CanonicalSerialize for AxfrOwnerMemo {
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+        write_bytes(buf, &self.0 .0)
+    }
+}
+
+#[cfg(feature = "anon_xfr")]
+impl CanonicalDeserialize for AxfrOwnerMemo {
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+        Ok(AxfrOwnerMemo(CompactByteArray(
+            read_bytes(reader)?.to_vec(),
+        )))
+    }
+}
+
+#[cfg(feature = "anon_xfr")]
+impl CanonicalSerialize for KZGCommitment<BN254G1> {
+    fn canonical_write(&self, buf: &mut Vec<u8>) -> Result<()> {
+        self.0.canonical_write(buf)
+    }
+}
+
+#[cfg(feature = "anon_xfr")]
+impl CanonicalDeserialize for KZGCommitment<BN254G1> {
+    fn canonical_read(reader: &mut &[u8]) -> Result<Self> {
+        Ok(KZGCommitment(BN254G1::canonical_read(reader)?))
+    }
+}
+
+#[cfg(feature = "anon_xfr")]
+canonical_struct!(
+    PlonkProof<KZGCommitment<BN254G1>, BN254Scalar>,
+    cm_w_vec,
+    cm_t_vec,
+    cm_z,
+    prk_3_poly_eval_zeta,
+    prk_4_poly_eval_zeta,
+    w_polys_eval_zeta,
+    w_polys_eval_zeta_omega,
+    z_eval_zeta_omega,
+    s_polys_eval_zeta,
+    opening_witness_zeta,
+    opening_witness_zeta_omega
+);
+
+#[cfg(feature = "anon_xfr")]
+canonical_struct!(
+    DSProof<BN254Scalar, RistrettoScalar, RistrettoPoint>,
+    inspection_comm,
+    randomizers,
+    response_scalars
+);
+
+#[cfg(feature = "anon_xfr")]
+canonical_struct!(ArToAbarBody, input, output, proof, memo);
+
+#[cfg(feature = "anon_xfr")]
+canonical_struct!(ArToAbarNote, body, signature);
+
+#[cfg(feature = "anon_xfr")]
+canonical_struct!(BarToAbarBody, input, output, proof, memo);
+
+#[cfg(feature = "anon_xfr")]
+canonical_struct!(BarToAbarNote, body, signature);
+
+#[cfg(all(test, feature = "anon_xfr"))]
+mod test {
+    use crate::anon_xfr::{
+        ar_to_abar::ArToAbarBody,
+        structs::{AnonAssetRecord, AxfrOwnerMemo},
+    };
+    use crate::canonical::{CanonicalDeserialize, CanonicalSerialize};
+    use crate::keys::{KeyPair, SignableBody};
+    use crate::parameters::AddressFormat::ED25519;
+    use crate::xfr::structs::{AssetType, BlindAssetRecord, XfrAmount, XfrAssetType};
+    use noah_algebra::{
+        bn254::{BN254Scalar, BN254G1},
+        prelude::*,
+    };
+    use noah_plonk::{plonk::indexer::PlonkProof, poly_commit::kzg_poly_com::KZGCommitment};
+
+    #[test]
+    fn test_canonical_fixture() {
+        // the ed25519 base point as the public key
+        let mut pk_bytes = vec![0x58u8];
+        pk_bytes.extend_from_slice(&[0x66u8; 31]);
+        let record = BlindAssetRecord {
+            amount: XfrAmount::NonConfidential(10),
+            asset_type: XfrAssetType::NonConfidential(AssetType([1u8; 32])),
+            public_key: NoahFromToBytes::noah_from_bytes(&pk_bytes).unwrap(),
+        };
+
+        let mut fixture = vec![1u8];
+        fixture.extend_from_slice(&[1, 10, 0, 0, 0, 0, 0, 0, 0]);
+        fixture.extend_from_slice(&[1, 32, 0, 0, 0]);
+        fixture.extend_from_slice(&[1u8; 32]);
+        fixture.extend_from_slice(&[32, 0, 0, 0]);
+        fixture.extend_from_slice(&pk_bytes);
+
+        assert_eq!(record.canonical_serialize().unwrap(), fixture);
+        assert_eq!(
+            BlindAssetRecord::canonical_deserialize(&fixture).unwrap(),
+            record
+        );
+
+        // other versions, trailing bytes, and truncated bytes are rejected
+        let mut other_version = fixture.clone();
+        other_version[0] = 2;
+        assert!(BlindAssetRecord::canonical_deserialize(&other_version).is_err());
+        let mut longer = fixture.clone();
+        longer.push(0);
+        assert!(BlindAssetRecord::canonical_deserialize(&longer).is_err());
+        assert!(BlindAssetRecord::canonical_deserialize(&fixture[..fixture.len() - 1]).is_err());
+        let mut unknown_tag = fixture;
+        unknown_tag[1] = 2;
+        assert!(BlindAssetRecord::canonical_deserialize(&unknown_tag).is_err());
+    }
+
+    #[test]
+    fn test_canonical_round_trip() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, ED25519);
+        let random_commitments = |prng: &mut _, n: usize| -> Vec<KZGCommitment<BN254G1>> {
+            (0..n)
+                .map(|_| KZGCommitment(BN254G1::random(prng)))
+                .collect()
+        };
+        let random_scalars = |prng: &mut _, n: usize| -> Vec<BN254Scalar> {
+            (0..n).map(|_| BN254Scalar::random(prng)).collect()
+        };
+
+        let body = ArToAbarBody {
+            input: BlindAssetRecord {
+                amount: XfrAmount::NonConfidential(10),
+                asset_type: XfrAssetType::NonConfidential(AssetType([1u8; 32])),
+                public_key: keypair.get_pk(),
+            },
+            output: AnonAssetRecord {
+                commitment: BN254Scalar::random(&mut prng),
+            },
+            proof: PlonkProof {
+                cm_w_vec: random_commitments(&mut prng, 5),
+                cm_t_vec: random_commitments(&mut prng, 5),
+                cm_z: KZGCommitment(BN254G1::random(&mut prng)),
+                prk_3_poly_eval_zeta: BN254Scalar::random(&mut prng),
+                prk_4_poly_eval_zeta: BN254Scalar::random(&mut prng),
+                w_polys_eval_zeta: random_scalars(&mut prng, 5),
+                w_polys_eval_zeta_omega: random_scalars(&mut prng, 3),
+                z_eval_zeta_omega: BN254Scalar::random(&mut prng),
+                s_polys_eval_zeta: random_scalars(&mut prng, 4),
+                opening_witness_zeta: KZGCommitment(BN254G1::random(&mut prng)),
+                opening_witness_zeta_omega: KZGCommitment(BN254G1::random(&mut prng)),
+            },
+            memo: AxfrOwnerMemo(CompactByteArray(vec![7u8; 100])),
+        };
+
+        let bytes = body.canonical_serialize().unwrap();
+        assert_eq!(ArToAbarBody::canonical_deserialize(&bytes).unwrap(), body);
+        assert_eq!(body.signing_payload().unwrap(), bytes);
+
+        // the signatures of the canonical message and of the legacy message are both accepted
+        let signature = keypair.sign(&body.signing_payload().unwrap()).unwrap();
+        assert!(body.verify_signature(&keypair.get_pk(), &signature).is_ok());
+        let legacy_signature = keypair
+            .sign(&body.legacy_signing_payload().unwrap())
+            .unwrap();
+        assert!(body
+            .verify_signature(&keypair.get_pk(), &legacy_signature)
+            .is_ok());
+        let other_signature = keypair.sign(b"other message").unwrap();
+        assert!(body
+            .verify_signature(&keypair.get_pk(), &other_signature)
+            .is_err());
+    }
+}
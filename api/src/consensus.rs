@@ -0,0 +1,1642 @@
+use crate::anon_xfr::{
+    abar_to_abar::{check_anon_xfr_outputs_order, verify_anon_xfr_note, AXfrNote},
+    abar_to_ar::{verify_abar_to_ar_note, AbarToArNote},
+    abar_to_bar::{verify_abar_to_bar_note, AbarToBarNote},
+    ar_to_abar::{verify_ar_to_abar_note_with, ArToAbarNote},
+    bar_to_abar::{verify_bar_to_abar_note_with, BarToAbarNote},
+    structs::{AnonAssetRecord, Nullifier},
+    AXfrAddressFoldingInstance,
+};
+use crate::errors::{NoahError, Result};
+use crate::parameters::{bulletproofs::BulletproofParams, params::VerifierParams, AddressFormat};
+use crate::serialization::to_bincode;
+use crate::xfr::{
+    batch_verify_xfr_notes_with,
+    structs::{BlindAssetRecord, XfrNote},
+    XfrNotePolicies,
+};
+use digest::Digest;
+use noah_algebra::{
+    bn254::BN254Scalar,
+    collections::{BTreeMap, BTreeSet},
+    prelude::*,
+};
+use rand_chacha::ChaChaRng;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Sha256, Sha512};
+
+#[cfg(feature = "parallel")]
+use {
+    rayon::prelude::{IntoParallelRefIterator, ParallelIterator},
+    std::sync::Mutex,
+};
+
+/// The domain separator for the seed of the verifier randomness.
+const VERIFY_NOTE_SEED_DOMAIN: &[u8] = b"Noah Verify Note Bytes Seed";
+/// The domain separator for the digest of a note.
+const NOTE_DIGEST_DOMAIN: &[u8] = b"Noah Note Digest";
+/// The domain separator for the digest of a bundle.
+const NOTE_BUNDLE_DIGEST_DOMAIN: &[u8] = b"Noah Note Bundle Digest";
+
+/// The maximal length of a serialized note accepted by `verify_note_bytes`.
+pub const MAX_NOTE_BYTES_LEN: usize = 1 << 20;
+
+/// The kind of a confidential transfer note, i.e., `XfrNote`.
+pub const NOTE_KIND_XFR: u32 = 1;
+/// The kind of an anonymous transfer note, i.e., `AXfrNote`.
+pub const NOTE_KIND_ABAR_TO_ABAR: u32 = 2;
+/// The kind of a transparent-to-anonymous note, i.e., `ArToAbarNote`.
+pub const NOTE_KIND_AR_TO_ABAR: u32 = 3;
+/// The kind of a confidential-to-anonymous note, i.e., `BarToAbarNote`.
+pub const NOTE_KIND_BAR_TO_ABAR: u32 = 4;
+/// The kind of an anonymous-to-transparent note, i.e., `AbarToArNote`.
+pub const NOTE_KIND_ABAR_TO_AR: u32 = 5;
+/// The kind of an anonymous-to-confidential note, i.e., `AbarToBarNote`.
+pub const NOTE_KIND_ABAR_TO_BAR: u32 = 6;
+
+/// The note is valid.
+pub const VERIFY_OK: u32 = 0;
+/// The kind of the note is unknown.
+pub const VERIFY_ERR_UNKNOWN_KIND: u32 = 1;
+/// The serialized note is empty or longer than `MAX_NOTE_BYTES_LEN`.
+pub const VERIFY_ERR_INVALID_LENGTH: u32 = 2;
+/// The bytes are not exactly the serialization of a note of the kind.
+pub const VERIFY_ERR_DESERIALIZATION: u32 = 3;
+/// The context misses the parameters or the Merkle root needed by the kind.
+pub const VERIFY_ERR_MISSING_CONTEXT: u32 = 4;
+/// A signature in the note is invalid.
+pub const VERIFY_ERR_SIGNATURE: u32 = 5;
+/// The Merkle root of the note does not match the context.
+pub const VERIFY_ERR_MERKLE_ROOT: u32 = 6;
+/// The amounts or the asset types of the note do not balance.
+pub const VERIFY_ERR_BALANCE: u32 = 7;
+/// A proof in the note is invalid.
+pub const VERIFY_ERR_PROOF: u32 = 8;
+/// The note is invalid for any other reason.
+pub const VERIFY_ERR_OTHER: u32 = 9;
+/// The acceptance of the kind of the note, or of its circuit, is disabled.
+pub const VERIFY_ERR_DISABLED: u32 = 10;
+/// A nullifier of the note is already spent in the state.
+pub const VERIFY_ERR_NULLIFIER_SPENT: u32 = 11;
+/// A nullifier of the note is also spent by an earlier note of the block.
+pub const VERIFY_ERR_NULLIFIER_CONFLICT: u32 = 12;
+/// The note is invalid, for a reason withheld under `NoteVerifyContext::normalize_errors`.
+pub const VERIFY_ERR_REJECTED: u32 = 13;
+
+/// The note kinds and the circuits whose acceptance is disabled, e.g., after a soundness bug
+/// is found, so that a chain can stop accepting them without shipping a new binary.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisabledProofTypes {
+    kinds: BTreeSet<u32>,
+    circuits: BTreeSet<String>,
+}
+
+impl DisabledProofTypes {
+    /// Create a set where everything is enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Disable the acceptance of the note kind.
+    pub fn disable_kind(&mut self, kind: u32) {
+        self.kinds.insert(kind);
+    }
+
+    /// Enable the acceptance of the note kind again.
+    pub fn enable_kind(&mut self, kind: u32) {
+        self.kinds.remove(&kind);
+    }
+
+    /// Disable the acceptance of the proofs for the circuit, identified by the label of its
+    /// verifier parameters, e.g., `abar_to_ar_secp256k1`.
+    pub fn disable_circuit(&mut self, label: &str) {
+        self.circuits.insert(String::from(label));
+    }
+
+    /// Enable the acceptance of the proofs for the circuit again.
+    pub fn enable_circuit(&mut self, label: &str) {
+        self.circuits.remove(label);
+    }
+
+    /// Check that the note kind is enabled.
+    pub fn check_kind(&self, kind: u32) -> Result<()> {
+        if self.kinds.contains(&kind) {
+            Err(NoahError::DisabledProofTypeError)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check that the circuit of the verifier parameters is enabled.
+    pub fn check_circuit(&self, params: &VerifierParams) -> Result<()> {
+        if self.circuits.contains(&params.label) {
+            Err(NoahError::DisabledProofTypeError)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The context for `verify_note_bytes`.
+pub struct NoteVerifyContext<'a> {
+    /// The verifier parameters, needed by the kinds involving anonymous records.
+    pub verifier_params: Option<&'a VerifierParams>,
+    /// The Bulletproof parameters, needed by `NOTE_KIND_XFR`.
+    pub bulletproof_params: Option<&'a mut BulletproofParams>,
+    /// The Merkle root that anonymous inputs are proven against.
+    pub merkle_root: Option<BN254Scalar>,
+    /// The bytes hashed into the address folding proof, e.g., the transaction without the note.
+    pub hash_input: &'a [u8],
+    /// The note kinds and the circuits that are disabled, if any.
+    pub disabled: Option<&'a DisabledProofTypes>,
+    /// Whether to evaluate all the checks of a note, e.g., its proofs even if its signatures
+    /// are invalid, and report any failure as `NoahError::VerificationError`, so that peers
+    /// cannot tell from the result or the time taken which of the checks failed.
+    pub normalize_errors: bool,
+    /// Whether to check that the outputs of anonymous transfers are in the canonical order of
+    /// `init_anon_xfr_note`. Ledgers set it from the height where they activate the check, so
+    /// that the notes accepted before, in the order chosen by their senders, still verify.
+    pub check_outputs_order: bool,
+}
+
+/// A record created by a note.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NoteOutput {
+    /// A confidential or transparent record.
+    Blind(BlindAssetRecord),
+    /// An anonymous record.
+    Anon(AnonAssetRecord),
+}
+
+/// The common interface of the notes, so that the pipelines of mempools and blocks
+/// can process notes of different kinds generically.
+pub trait NoteTrait: Serialize + DeserializeOwned {
+    /// The kind of the note, e.g., `NOTE_KIND_XFR`.
+    const KIND: u32;
+
+    /// Return the digest of the note, which uniquely identifies the note.
+    fn digest(&self) -> Result<[u8; 64]> {
+        let bytes = to_bincode(self)?;
+
+        let mut hasher = Sha512::new();
+        hasher.update(NOTE_DIGEST_DOMAIN);
+        hasher.update(Self::KIND.to_le_bytes());
+        hasher.update(&bytes);
+
+        let mut digest = [0u8; 64];
+        digest.copy_from_slice(&hasher.finalize());
+        Ok(digest)
+    }
+
+    /// Verify the note under the context.
+    fn verify(&self, ctx: &mut NoteVerifyContext<'_>) -> Result<()>;
+
+    /// Return the nullifiers of the anonymous records spent by the note.
+    fn nullifiers(&self) -> Vec<Nullifier>;
+
+    /// Return the records created by the note.
+    fn outputs(&self) -> Vec<NoteOutput>;
+
+    /// Return the Merkle root that the anonymous inputs of the note are proven against, if any.
+    fn merkle_root(&self) -> Option<BN254Scalar> {
+        None
+    }
+
+    /// Return the verifier parameters of the circuit of the note, if the kind has one.
+    fn verifier_params(&self) -> Result<Option<VerifierParams>> {
+        Ok(None)
+    }
+}
+
+impl NoteTrait for XfrNote {
+    const KIND: u32 = NOTE_KIND_XFR;
+
+    fn verify(&self, ctx: &mut NoteVerifyContext<'_>) -> Result<()> {
+        let params = ctx
+            .bulletproof_params
+            .as_deref_mut()
+            .ok_or(NoahError::MissingVerifierParamsError)?;
+        let policies =
+            XfrNotePolicies::empty_policies(self.body.inputs.len(), self.body.outputs.len());
+        let bytes = rmp_serde::to_vec(self).map_err(|_| NoahError::SerializationError)?;
+        let mut prng = seeded_prng(Self::KIND, &bytes);
+        batch_verify_xfr_notes_with(
+            &mut prng,
+            params,
+            &[self],
+            &[&policies.to_ref()],
+            ctx.normalize_errors,
+        )
+    }
+
+    fn nullifiers(&self) -> Vec<Nullifier> {
+        vec![]
+    }
+
+    fn outputs(&self) -> Vec<NoteOutput> {
+        self.body
+            .outputs
+            .iter()
+            .map(|output| NoteOutput::Blind(output.clone()))
+            .collect()
+    }
+}
+
+impl NoteTrait for AXfrNote {
+    const KIND: u32 = NOTE_KIND_ABAR_TO_ABAR;
+
+    fn digest(&self) -> Result<[u8; 64]> {
+        AXfrNote::digest(self)
+    }
+
+    fn verify(&self, ctx: &mut NoteVerifyContext<'_>) -> Result<()> {
+        let (params, merkle_root) = anon_context(ctx, &self.body.merkle_root)?;
+        if ctx.check_outputs_order {
+            check_anon_xfr_outputs_order(&self.body)?;
+        }
+        verify_anon_xfr_note(params, self, &merkle_root, folding_hash(ctx))
+    }
+
+    fn nullifiers(&self) -> Vec<Nullifier> {
+        self.body.inputs.clone()
+    }
+
+    fn outputs(&self) -> Vec<NoteOutput> {
+        self.body
+            .outputs
+            .iter()
+            .map(|output| NoteOutput::Anon(output.clone()))
+            .collect()
+    }
+
+    fn merkle_root(&self) -> Option<BN254Scalar> {
+        Some(self.body.merkle_root)
+    }
+
+    fn verifier_params(&self) -> Result<Option<VerifierParams>> {
+        VerifierParams::get_abar_to_abar(
+            self.body.inputs.len(),
+            self.body.outputs.len(),
+            address_format(&self.folding_instance),
+        )
+        .map(Some)
+    }
+}
+
+impl NoteTrait for ArToAbarNote {
+    const KIND: u32 = NOTE_KIND_AR_TO_ABAR;
+
+    fn verify(&self, ctx: &mut NoteVerifyContext<'_>) -> Result<()> {
+        let params = enabled_verifier_params(ctx)?;
+        verify_ar_to_abar_note_with(params, self, ctx.normalize_errors)
+    }
+
+    fn nullifiers(&self) -> Vec<Nullifier> {
+        vec![]
+    }
+
+    fn outputs(&self) -> Vec<NoteOutput> {
+        vec![NoteOutput::Anon(self.body.output.clone())]
+    }
+
+    fn verifier_params(&self) -> Result<Option<VerifierParams>> {
+        VerifierParams::get_ar_to_abar().map(Some)
+    }
+}
+
+impl NoteTrait for BarToAbarNote {
+    const KIND: u32 = NOTE_KIND_BAR_TO_ABAR;
+
+    fn verify(&self, ctx: &mut NoteVerifyContext<'_>) -> Result<()> {
+        let params = enabled_verifier_params(ctx)?;
+        verify_bar_to_abar_note_with(
+            params,
+            self,
+            &self.body.input.public_key,
+            ctx.normalize_errors,
+        )
+    }
+
+    fn nullifiers(&self) -> Vec<Nullifier> {
+        vec![]
+    }
+
+    fn outputs(&self) -> Vec<NoteOutput> {
+        vec![NoteOutput::Anon(self.body.output.clone())]
+    }
+
+    fn verifier_params(&self) -> Result<Option<VerifierParams>> {
+        VerifierParams::get_bar_to_abar().map(Some)
+    }
+}
+
+impl NoteTrait for AbarToArNote {
+    const KIND: u32 = NOTE_KIND_ABAR_TO_AR;
+
+    fn verify(&self, ctx: &mut NoteVerifyContext<'_>) -> Result<()> {
+        let (params, merkle_root) = anon_context(ctx, &self.body.merkle_root)?;
+        verify_abar_to_ar_note(params, self, &merkle_root, folding_hash(ctx))
+    }
+
+    fn nullifiers(&self) -> Vec<Nullifier> {
+        vec![self.body.input]
+    }
+
+    fn outputs(&self) -> Vec<NoteOutput> {
+        vec![NoteOutput::Blind(self.body.output.clone())]
+    }
+
+    fn merkle_root(&self) -> Option<BN254Scalar> {
+        Some(self.body.merkle_root)
+    }
+
+    fn verifier_params(&self) -> Result<Option<VerifierParams>> {
+        VerifierParams::get_abar_to_ar(address_format(&self.folding_instance)).map(Some)
+    }
+}
+
+impl NoteTrait for AbarToBarNote {
+    const KIND: u32 = NOTE_KIND_ABAR_TO_BAR;
+
+    fn verify(&self, ctx: &mut NoteVerifyContext<'_>) -> Result<()> {
+        let (params, merkle_root) = anon_context(ctx, &self.body.merkle_root)?;
+        verify_abar_to_bar_note(params, self, &merkle_root, folding_hash(ctx))
+    }
+
+    fn nullifiers(&self) -> Vec<Nullifier> {
+        vec![self.body.input]
+    }
+
+    fn outputs(&self) -> Vec<NoteOutput> {
+        vec![NoteOutput::Blind(self.body.output.clone())]
+    }
+
+    fn merkle_root(&self) -> Option<BN254Scalar> {
+        Some(self.body.merkle_root)
+    }
+
+    fn verifier_params(&self) -> Result<Option<VerifierParams>> {
+        VerifierParams::get_abar_to_bar(address_format(&self.folding_instance)).map(Some)
+    }
+}
+
+/// Verify a serialized note and return a fixed code, `VERIFY_OK` if the note is valid.
+///
+/// This entry point is meant for consensus-critical embedding: the note must be exactly
+/// the MessagePack serialization of the note, no longer than `MAX_NOTE_BYTES_LEN`, which also
+/// bounds the allocations while decoding; the randomness for batching is derived from the note
+/// itself; and the result depends only on `kind`, `bytes`, and `ctx`. Confidential transfers
+/// are verified without asset tracing policies.
+pub fn verify_note_bytes(kind: u32, bytes: &[u8], ctx: &mut NoteVerifyContext<'_>) -> u32 {
+    if !(NOTE_KIND_XFR..=NOTE_KIND_ABAR_TO_BAR).contains(&kind) {
+        return VERIFY_ERR_UNKNOWN_KIND;
+    }
+    if let Some(Err(e)) = ctx.disabled.map(|disabled| disabled.check_kind(kind)) {
+        return error_code(&e);
+    }
+    if bytes.is_empty() || bytes.len() > MAX_NOTE_BYTES_LEN {
+        return VERIFY_ERR_INVALID_LENGTH;
+    }
+
+    let res = match kind {
+        NOTE_KIND_XFR => decode_and_verify::<XfrNote>(bytes, ctx),
+        NOTE_KIND_ABAR_TO_ABAR => decode_and_verify::<AXfrNote>(bytes, ctx),
+        NOTE_KIND_AR_TO_ABAR => decode_and_verify::<ArToAbarNote>(bytes, ctx),
+        NOTE_KIND_BAR_TO_ABAR => decode_and_verify::<BarToAbarNote>(bytes, ctx),
+        NOTE_KIND_ABAR_TO_AR => decode_and_verify::<AbarToArNote>(bytes, ctx),
+        _ => decode_and_verify::<AbarToBarNote>(bytes, ctx),
+    };
+
+    match res {
+        Ok(()) => VERIFY_OK,
+        Err(e) => error_code(&e),
+    }
+}
+
+/// Map an error to its fixed code.
+pub fn error_code(e: &NoahError) -> u32 {
+    match e {
+        NoahError::DeserializationError
+        | NoahError::DecompressElementError
+        | NoahError::UnknownNoteVersionError => VERIFY_ERR_DESERIALIZATION,
+        NoahError::MissingVerifierParamsError | NoahError::AXfrVerifierParamsError => {
+            VERIFY_ERR_MISSING_CONTEXT
+        }
+        NoahError::SignatureError => VERIFY_ERR_SIGNATURE,
+        NoahError::DisabledProofTypeError => VERIFY_ERR_DISABLED,
+        NoahError::VerificationError => VERIFY_ERR_REJECTED,
+        NoahError::DuplicateBundleEntryError => VERIFY_ERR_NULLIFIER_CONFLICT,
+        NoahError::MerkleRootMismatchError => VERIFY_ERR_MERKLE_ROOT,
+        NoahError::XfrVerifyAssetAmountError | NoahError::XfrVerifyConfidentialAmountError => {
+            VERIFY_ERR_BALANCE
+        }
+        NoahError::Plonk(_)
+        | NoahError::Crypto(_)
+        | NoahError::R1CS(_)
+        | NoahError::Bulletproofs(_)
+        | NoahError::ArkR1CS(_)
+        | NoahError::ArkBulletproofs(_)
+        | NoahError::CommitmentVerificationError => VERIFY_ERR_PROOF,
+        _ => VERIFY_ERR_OTHER,
+    }
+}
+
+/// The state that all the notes of a block are verified against.
+#[derive(Clone, Debug, Default)]
+pub struct StateSnapshot {
+    merkle_roots: Vec<BN254Scalar>,
+    spent_nullifiers: BTreeSet<Vec<u8>>,
+}
+
+impl StateSnapshot {
+    /// Create a snapshot from the accepted Merkle roots and the spent nullifiers.
+    pub fn new(merkle_roots: Vec<BN254Scalar>, spent_nullifiers: &[Nullifier]) -> Self {
+        StateSnapshot {
+            merkle_roots,
+            spent_nullifiers: spent_nullifiers.iter().map(|n| n.to_bytes()).collect(),
+        }
+    }
+
+    /// Check if the Merkle root is accepted.
+    pub fn has_merkle_root(&self, merkle_root: &BN254Scalar) -> bool {
+        self.merkle_roots.contains(merkle_root)
+    }
+
+    /// Check if the nullifier is spent.
+    pub fn is_spent(&self, nullifier: &Nullifier) -> bool {
+        self.spent_nullifiers.contains(&nullifier.to_bytes())
+    }
+}
+
+/// A note of a block.
+#[derive(Clone, Copy, Debug)]
+pub struct BlockNote<'a> {
+    /// The kind of the note.
+    pub kind: u32,
+    /// The MessagePack serialization of the note.
+    pub bytes: &'a [u8],
+    /// The bytes hashed into the address folding proof of the note.
+    pub hash_input: &'a [u8],
+}
+
+/// The first invalid note of a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockVerifyError {
+    /// The index of the note in the block.
+    pub index: usize,
+    /// The code of the reason, e.g., `VERIFY_ERR_NULLIFIER_CONFLICT`.
+    pub reason: u32,
+}
+
+/// The verifier of the notes of a block against a snapshot of the state.
+pub struct BlockVerifier<'a> {
+    snapshot: &'a StateSnapshot,
+    bulletproof_params: &'a mut BulletproofParams,
+    disabled: Option<&'a DisabledProofTypes>,
+    normalize_errors: bool,
+    check_outputs_order: bool,
+}
+
+impl<'a> BlockVerifier<'a> {
+    /// Create a verifier of the blocks on the snapshot.
+    pub fn new(
+        snapshot: &'a StateSnapshot,
+        bulletproof_params: &'a mut BulletproofParams,
+        disabled: Option<&'a DisabledProofTypes>,
+    ) -> Self {
+        BlockVerifier {
+            snapshot,
+            bulletproof_params,
+            disabled,
+            normalize_errors: false,
+            check_outputs_order: false,
+        }
+    }
+
+    /// Set whether the notes are verified as under `NoteVerifyContext::normalize_errors`.
+    pub fn with_normalized_errors(mut self, normalize_errors: bool) -> Self {
+        self.normalize_errors = normalize_errors;
+        self
+    }
+
+    /// Set whether the notes are verified as under `NoteVerifyContext::check_outputs_order`.
+    pub fn with_outputs_order_check(mut self, check_outputs_order: bool) -> Self {
+        self.check_outputs_order = check_outputs_order;
+        self
+    }
+
+    /// Verify the notes of a block, and return the first invalid note in the order of the block.
+    ///
+    /// With the `parallel` feature, the notes are verified across threads, where the
+    /// confidential transfers share the Bulletproof parameters one at a time. The result does
+    /// not depend on the scheduling: the nullifiers spent twice within the block are only
+    /// checked afterwards, in the order of the block, so that the later note is the invalid one.
+    pub fn verify_block(
+        &mut self,
+        notes: &[BlockNote<'_>],
+    ) -> core::result::Result<(), BlockVerifyError> {
+        let snapshot = self.snapshot;
+        let disabled = self.disabled;
+        let normalize_errors = self.normalize_errors;
+        let check_outputs_order = self.check_outputs_order;
+
+        #[cfg(feature = "parallel")]
+        let results = {
+            let bulletproof_params = Mutex::new(&mut *self.bulletproof_params);
+            notes
+                .par_iter()
+                .map(|note| {
+                    if note.kind == NOTE_KIND_XFR {
+                        let mut params = bulletproof_params.lock().map_err(|_| VERIFY_ERR_OTHER)?;
+                        verify_block_note(
+                            note,
+                            snapshot,
+                            disabled,
+                            normalize_errors,
+                            check_outputs_order,
+                            Some(&mut **params),
+                        )
+                    } else {
+                        verify_block_note(
+                            note,
+                            snapshot,
+                            disabled,
+                            normalize_errors,
+                            check_outputs_order,
+                            None,
+                        )
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let results = notes
+            .iter()
+            .map(|note| {
+                verify_block_note(
+                    note,
+                    snapshot,
+                    disabled,
+                    normalize_errors,
+                    check_outputs_order,
+                    Some(&mut *self.bulletproof_params),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        first_block_failure(results)
+    }
+}
+
+/// Decode and verify a note of a block, and return its nullifiers.
+fn verify_block_note(
+    note: &BlockNote<'_>,
+    snapshot: &StateSnapshot,
+    disabled: Option<&DisabledProofTypes>,
+    normalize_errors: bool,
+    check_outputs_order: bool,
+    bulletproof_params: Option<&mut BulletproofParams>,
+) -> core::result::Result<Vec<Nullifier>, u32> {
+    match note.kind {
+        NOTE_KIND_XFR => verify_block_note_of::<XfrNote>(
+            note,
+            snapshot,
+            disabled,
+            normalize_errors,
+            check_outputs_order,
+            bulletproof_params,
+        ),
+        NOTE_KIND_ABAR_TO_ABAR => verify_block_note_of::<AXfrNote>(
+            note,
+            snapshot,
+            disabled,
+            normalize_errors,
+            check_outputs_order,
+            bulletproof_params,
+        ),
+        NOTE_KIND_AR_TO_ABAR => verify_block_note_of::<ArToAbarNote>(
+            note,
+            snapshot,
+            disabled,
+            normalize_errors,
+            check_outputs_order,
+            bulletproof_params,
+        ),
+        NOTE_KIND_BAR_TO_ABAR => verify_block_note_of::<BarToAbarNote>(
+            note,
+            snapshot,
+            disabled,
+            normalize_errors,
+            check_outputs_order,
+            bulletproof_params,
+        ),
+        NOTE_KIND_ABAR_TO_AR => verify_block_note_of::<AbarToArNote>(
+            note,
+            snapshot,
+            disabled,
+            normalize_errors,
+            check_outputs_order,
+            bulletproof_params,
+        ),
+        NOTE_KIND_ABAR_TO_BAR => verify_block_note_of::<AbarToBarNote>(
+            note,
+            snapshot,
+            disabled,
+            normalize_errors,
+            check_outputs_order,
+            bulletproof_params,
+        ),
+        _ => Err(VERIFY_ERR_UNKNOWN_KIND),
+    }
+}
+
+fn verify_block_note_of<T: NoteTrait>(
+    note: &BlockNote<'_>,
+    snapshot: &StateSnapshot,
+    disabled: Option<&DisabledProofTypes>,
+    normalize_errors: bool,
+    check_outputs_order: bool,
+    bulletproof_params: Option<&mut BulletproofParams>,
+) -> core::result::Result<Vec<Nullifier>, u32> {
+    if let Some(disabled) = disabled {
+        disabled.check_kind(T::KIND).map_err(|e| error_code(&e))?;
+    }
+    if note.bytes.is_empty() || note.bytes.len() > MAX_NOTE_BYTES_LEN {
+        return Err(VERIFY_ERR_INVALID_LENGTH);
+    }
+    let decoded = decode_note::<T>(note.bytes).map_err(|e| error_code(&e))?;
+
+    let nullifiers = decoded.nullifiers();
+    if nullifiers.iter().any(|n| snapshot.is_spent(n)) {
+        return Err(VERIFY_ERR_NULLIFIER_SPENT);
+    }
+    let merkle_root = decoded.merkle_root();
+    if let Some(merkle_root) = merkle_root {
+        if !snapshot.has_merkle_root(&merkle_root) {
+            return Err(VERIFY_ERR_MERKLE_ROOT);
+        }
+    }
+
+    let verifier_params = decoded.verifier_params().map_err(|e| error_code(&e))?;
+    let mut ctx = NoteVerifyContext {
+        verifier_params: verifier_params.as_ref(),
+        bulletproof_params,
+        merkle_root,
+        hash_input: note.hash_input,
+        disabled,
+        normalize_errors,
+        check_outputs_order,
+    };
+    let res = decoded.verify(&mut ctx);
+    let res = if normalize_errors {
+        normalize_error(res)
+    } else {
+        res
+    };
+    res.map_err(|e| error_code(&e))?;
+    Ok(nullifiers)
+}
+
+/// Return the first invalid note in the order of the block, where a note is also invalid
+/// if it spends a nullifier spent by an earlier note of the block.
+fn first_block_failure(
+    results: Vec<core::result::Result<Vec<Nullifier>, u32>>,
+) -> core::result::Result<(), BlockVerifyError> {
+    let mut spent = BTreeSet::new();
+    for (index, res) in results.into_iter().enumerate() {
+        let nullifiers = res.map_err(|reason| BlockVerifyError { index, reason })?;
+        for nullifier in nullifiers {
+            if !spent.insert(nullifier.to_bytes()) {
+                return Err(BlockVerifyError {
+                    index,
+                    reason: VERIFY_ERR_NULLIFIER_CONFLICT,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A note of a bundle, with its digest and its nullifiers.
+#[derive(Clone, Debug)]
+struct BundleEntry {
+    kind: u32,
+    bytes: Vec<u8>,
+    hash_input: Vec<u8>,
+    digest: [u8; 64],
+    nullifiers: Vec<Nullifier>,
+}
+
+/// A bundle of notes submitted together, e.g., by a relayer.
+///
+/// Once canonicalized, the notes are sorted by their digests, the nullifiers by their bytes,
+/// and no note or nullifier appears twice, so that the nodes receiving the notes in any order
+/// agree on the digest of the bundle, and a bundle cannot spend a record twice.
+#[derive(Clone, Debug, Default)]
+pub struct NoteBundle {
+    entries: Vec<BundleEntry>,
+    nullifiers: Vec<Nullifier>,
+    canonical: bool,
+}
+
+impl NoteBundle {
+    /// Create an empty bundle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a note to the bundle, with the bytes hashed into its address folding proof.
+    pub fn add_note<T: NoteTrait>(&mut self, note: &T, hash_input: &[u8]) -> Result<()> {
+        let bytes = rmp_serde::to_vec(note).map_err(|_| NoahError::SerializationError)?;
+        self.push_entry(BundleEntry {
+            kind: T::KIND,
+            bytes,
+            hash_input: hash_input.to_vec(),
+            digest: note.digest()?,
+            nullifiers: note.nullifiers(),
+        });
+        Ok(())
+    }
+
+    /// Decode a serialized note of the kind and add it to the bundle.
+    pub fn add_note_bytes(&mut self, kind: u32, bytes: &[u8], hash_input: &[u8]) -> Result<()> {
+        if bytes.is_empty() || bytes.len() > MAX_NOTE_BYTES_LEN {
+            return Err(NoahError::DeserializationError);
+        }
+        match kind {
+            NOTE_KIND_XFR => self.add_note(&decode_note::<XfrNote>(bytes)?, hash_input),
+            NOTE_KIND_ABAR_TO_ABAR => self.add_note(&decode_note::<AXfrNote>(bytes)?, hash_input),
+            NOTE_KIND_AR_TO_ABAR => self.add_note(&decode_note::<ArToAbarNote>(bytes)?, hash_input),
+            NOTE_KIND_BAR_TO_ABAR => {
+                self.add_note(&decode_note::<BarToAbarNote>(bytes)?, hash_input)
+            }
+            NOTE_KIND_ABAR_TO_AR => self.add_note(&decode_note::<AbarToArNote>(bytes)?, hash_input),
+            NOTE_KIND_ABAR_TO_BAR => {
+                self.add_note(&decode_note::<AbarToBarNote>(bytes)?, hash_input)
+            }
+            _ => Err(NoahError::ParameterError),
+        }
+    }
+
+    fn push_entry(&mut self, entry: BundleEntry) {
+        self.entries.push(entry);
+        self.canonical = false;
+    }
+
+    /// Sort the notes and the nullifiers into the canonical order, and reject the bundle if
+    /// a note or a nullifier appears twice.
+    pub fn canonicalize(&mut self) -> Result<()> {
+        self.entries.sort_by(|a, b| a.digest.cmp(&b.digest));
+        if self
+            .entries
+            .windows(2)
+            .any(|pair| pair[0].digest == pair[1].digest)
+        {
+            return Err(NoahError::DuplicateBundleEntryError);
+        }
+
+        let mut nullifiers = self
+            .entries
+            .iter()
+            .flat_map(|entry| entry.nullifiers.iter().map(|n| (n.to_bytes(), *n)))
+            .collect_vec();
+        nullifiers.sort_by(|a, b| a.0.cmp(&b.0));
+        if nullifiers.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(NoahError::DuplicateBundleEntryError);
+        }
+
+        self.nullifiers = nullifiers.into_iter().map(|(_, n)| n).collect();
+        self.canonical = true;
+        Ok(())
+    }
+
+    /// Check if the bundle is canonicalized, and has not changed since.
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Return the number of notes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if there are no notes.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the digests of the notes, in the order of the bundle.
+    pub fn note_digests(&self) -> Vec<[u8; 64]> {
+        self.entries.iter().map(|entry| entry.digest).collect()
+    }
+
+    /// Return the nullifiers spent by the bundle, in the canonical order.
+    pub fn nullifiers(&self) -> Result<&[Nullifier]> {
+        if !self.canonical {
+            return Err(NoahError::InconsistentStructureError);
+        }
+        Ok(&self.nullifiers)
+    }
+
+    /// Return the notes, in the order of the bundle, for `BlockVerifier::verify_block`.
+    pub fn block_notes(&self) -> Vec<BlockNote<'_>> {
+        self.entries
+            .iter()
+            .map(|entry| BlockNote {
+                kind: entry.kind,
+                bytes: &entry.bytes,
+                hash_input: &entry.hash_input,
+            })
+            .collect()
+    }
+
+    /// Return the digest of the canonical bundle, which binds the notes in their order,
+    /// the bytes hashed into their proofs, and the nullifiers.
+    pub fn digest(&self) -> Result<[u8; 64]> {
+        if !self.canonical {
+            return Err(NoahError::InconsistentStructureError);
+        }
+
+        let mut hasher = Sha512::new();
+        hasher.update(NOTE_BUNDLE_DIGEST_DOMAIN);
+        hasher.update((self.entries.len() as u64).to_le_bytes());
+        for entry in self.entries.iter() {
+            hasher.update(entry.kind.to_le_bytes());
+            hasher.update(entry.digest);
+            hasher.update((entry.hash_input.len() as u64).to_le_bytes());
+            hasher.update(&entry.hash_input);
+        }
+        hasher.update((self.nullifiers.len() as u64).to_le_bytes());
+        for nullifier in self.nullifiers.iter() {
+            hasher.update(nullifier.to_bytes());
+        }
+
+        let mut digest = [0u8; 64];
+        digest.copy_from_slice(&hasher.finalize());
+        Ok(digest)
+    }
+}
+
+/// The identifier of a pending note in the mempool, e.g., the digest of the note.
+pub type PendingNoteId = [u8; 64];
+
+/// A nullifier that is already reserved by another pending note, or already spent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    /// The nullifier in conflict.
+    pub nullifier: Nullifier,
+    /// The pending note holding the nullifier, or `None` if it is spent in the snapshot,
+    /// or repeated within the note itself.
+    pub holder: Option<PendingNoteId>,
+}
+
+/// The index of the nullifiers reserved by the pending notes of a mempool, which detects
+/// prospective double-spends before the notes are included in a block.
+#[derive(Clone, Debug, Default)]
+pub struct NullifierIndex {
+    holders: BTreeMap<Vec<u8>, PendingNoteId>,
+    reserved: BTreeMap<PendingNoteId, Vec<Nullifier>>,
+}
+
+impl NullifierIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve the nullifiers for the pending note, or return the first conflict, in which
+    /// case nothing is reserved.
+    /// Reserving again for a pending note already in the index is a no-op.
+    pub fn try_reserve(
+        &mut self,
+        id: PendingNoteId,
+        nullifiers: &[Nullifier],
+    ) -> core::result::Result<(), Conflict> {
+        if self.reserved.contains_key(&id) {
+            return Ok(());
+        }
+
+        let mut seen = BTreeSet::new();
+        for nullifier in nullifiers {
+            let key = nullifier.to_bytes();
+            if let Some(holder) = self.holders.get(&key) {
+                return Err(Conflict {
+                    nullifier: *nullifier,
+                    holder: Some(*holder),
+                });
+            }
+            if !seen.insert(key) {
+                return Err(Conflict {
+                    nullifier: *nullifier,
+                    holder: None,
+                });
+            }
+        }
+
+        for key in seen {
+            self.holders.insert(key, id);
+        }
+        self.reserved.insert(id, nullifiers.to_vec());
+        Ok(())
+    }
+
+    /// Like `try_reserve`, and also reject the nullifiers already spent in the snapshot.
+    pub fn try_reserve_against(
+        &mut self,
+        snapshot: &StateSnapshot,
+        id: PendingNoteId,
+        nullifiers: &[Nullifier],
+    ) -> core::result::Result<(), Conflict> {
+        if let Some(nullifier) = nullifiers.iter().find(|n| snapshot.is_spent(n)) {
+            return Err(Conflict {
+                nullifier: *nullifier,
+                holder: None,
+            });
+        }
+        self.try_reserve(id, nullifiers)
+    }
+
+    /// Reserve the nullifiers of the note under its digest, which is returned.
+    pub fn try_reserve_note<T: NoteTrait>(
+        &mut self,
+        note: &T,
+    ) -> Result<core::result::Result<PendingNoteId, Conflict>> {
+        let id = note.digest()?;
+        Ok(self.try_reserve(id, &note.nullifiers()).map(|_| id))
+    }
+
+    /// Return the pending note holding the nullifier.
+    pub fn holder(&self, nullifier: &Nullifier) -> Option<PendingNoteId> {
+        self.holders.get(&nullifier.to_bytes()).copied()
+    }
+
+    /// Check if the pending note has reserved its nullifiers.
+    pub fn contains(&self, id: &PendingNoteId) -> bool {
+        self.reserved.contains_key(id)
+    }
+
+    /// Return the number of the pending notes.
+    pub fn len(&self) -> usize {
+        self.reserved.len()
+    }
+
+    /// Check if there are no pending notes.
+    pub fn is_empty(&self) -> bool {
+        self.reserved.is_empty()
+    }
+
+    /// Evict the pending note, e.g., when it is dropped from the mempool or included in a block,
+    /// and release its nullifiers. Return the released nullifiers.
+    pub fn evict(&mut self, id: &PendingNoteId) -> Vec<Nullifier> {
+        let nullifiers = self.reserved.remove(id).unwrap_or_default();
+        for nullifier in nullifiers.iter() {
+            self.holders.remove(&nullifier.to_bytes());
+        }
+        nullifiers
+    }
+
+    /// Evict the pending notes that spend a nullifier spent in the snapshot, which can no
+    /// longer be included once the block is committed. Return the evicted notes.
+    pub fn evict_spent(&mut self, snapshot: &StateSnapshot) -> Vec<PendingNoteId> {
+        let stale = self
+            .reserved
+            .iter()
+            .filter(|(_, nullifiers)| nullifiers.iter().any(|n| snapshot.is_spent(n)))
+            .map(|(id, _)| *id)
+            .collect_vec();
+        for id in stale.iter() {
+            self.evict(id);
+        }
+        stale
+    }
+}
+
+/// Decode a note from its MessagePack serialization, rejecting trailing bytes.
+pub(crate) fn decode_note<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    let mut reader = bytes;
+    let note = {
+        let mut de = rmp_serde::Deserializer::new(&mut reader);
+        T::deserialize(&mut de).map_err(|_| NoahError::DeserializationError)?
+    };
+    if !reader.is_empty() {
+        return Err(NoahError::DeserializationError);
+    }
+    Ok(note)
+}
+
+/// Decode a note of the kind and verify it.
+fn decode_and_verify<T: NoteTrait>(bytes: &[u8], ctx: &mut NoteVerifyContext<'_>) -> Result<()> {
+    let res = decode_note::<T>(bytes)?.verify(ctx);
+    if ctx.normalize_errors {
+        normalize_error(res)
+    } else {
+        res
+    }
+}
+
+/// Report any failure of the verification of a decoded note as `NoahError::VerificationError`,
+/// except for a missing or disabled context, which does not depend on the note.
+fn normalize_error(res: Result<()>) -> Result<()> {
+    match res {
+        Ok(())
+        | Err(NoahError::MissingVerifierParamsError)
+        | Err(NoahError::DisabledProofTypeError) => res,
+        Err(_) => Err(NoahError::VerificationError),
+    }
+}
+
+/// Return the verifier parameters, checking that their circuit is enabled.
+fn enabled_verifier_params<'a>(ctx: &NoteVerifyContext<'a>) -> Result<&'a VerifierParams> {
+    let params = ctx
+        .verifier_params
+        .ok_or(NoahError::MissingVerifierParamsError)?;
+    if let Some(disabled) = ctx.disabled {
+        disabled.check_circuit(params)?;
+    }
+    Ok(params)
+}
+
+/// Return the verifier parameters and the Merkle root for the kinds spending anonymous records,
+/// checking that the note spends against the Merkle root of the context.
+fn anon_context<'a>(
+    ctx: &NoteVerifyContext<'a>,
+    note_merkle_root: &BN254Scalar,
+) -> Result<(&'a VerifierParams, BN254Scalar)> {
+    let params = enabled_verifier_params(ctx)?;
+    let merkle_root = ctx
+        .merkle_root
+        .ok_or(NoahError::MissingVerifierParamsError)?;
+    if merkle_root != *note_merkle_root {
+        return Err(NoahError::MerkleRootMismatchError);
+    }
+    Ok((params, merkle_root))
+}
+
+/// Return the hash for the address folding proof.
+fn folding_hash(ctx: &NoteVerifyContext<'_>) -> Sha512 {
+    let mut hash = Sha512::new();
+    hash.update(ctx.hash_input);
+    hash
+}
+
+/// Return the address format of the spending key of the address folding instance.
+fn address_format(folding_instance: &AXfrAddressFoldingInstance) -> AddressFormat {
+    match folding_instance {
+        AXfrAddressFoldingInstance::Secp256k1(_) => AddressFormat::SECP256K1,
+        AXfrAddressFoldingInstance::Ed25519(_) => AddressFormat::ED25519,
+    }
+}
+
+/// Derive the verifier randomness from the note, so that the verification is reproducible.
+pub(crate) fn seeded_prng(kind: u32, bytes: &[u8]) -> ChaChaRng {
+    let mut hasher = Sha256::new();
+    hasher.update(VERIFY_NOTE_SEED_DOMAIN);
+    hasher.update(kind.to_le_bytes());
+    hasher.update(bytes);
+
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&hasher.finalize());
+    ChaChaRng::from_seed(seed)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::consensus::{
+        error_code, first_block_failure, verify_note_bytes, BlockNote, BlockVerifier,
+        BlockVerifyError, BundleEntry, Conflict, DisabledProofTypes, NoteBundle, NoteOutput,
+        NoteTrait, NoteVerifyContext, NullifierIndex, StateSnapshot, NOTE_KIND_ABAR_TO_ABAR,
+        NOTE_KIND_XFR, VERIFY_ERR_DESERIALIZATION, VERIFY_ERR_DISABLED, VERIFY_ERR_INVALID_LENGTH,
+        VERIFY_ERR_MERKLE_ROOT, VERIFY_ERR_MISSING_CONTEXT, VERIFY_ERR_NULLIFIER_CONFLICT,
+        VERIFY_ERR_OTHER, VERIFY_ERR_REJECTED, VERIFY_ERR_SIGNATURE, VERIFY_ERR_UNKNOWN_KIND,
+        VERIFY_OK,
+    };
+    use crate::errors::NoahError;
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::SECP256K1;
+    use crate::parameters::{bulletproofs::BulletproofParams, params::VerifierParams};
+    use crate::xfr::{
+        asset_record::AssetRecordType,
+        gen_xfr_note,
+        structs::{AssetRecord, AssetRecordTemplate, AssetType},
+    };
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+    use rmp_serde::Serializer;
+    use serde::Serialize;
+
+    fn to_msgpack<T: Serialize>(value: &T) -> Vec<u8> {
+        let mut bytes = vec![];
+        value.serialize(&mut Serializer::new(&mut bytes)).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_error_code() {
+        assert_eq!(
+            error_code(&NoahError::MerkleRootMismatchError),
+            VERIFY_ERR_MERKLE_ROOT
+        );
+        // the anonymous notes fail with this error for many reasons besides the Merkle root
+        assert_eq!(
+            error_code(&NoahError::AXfrVerificationError),
+            VERIFY_ERR_OTHER
+        );
+    }
+
+    #[test]
+    fn test_verify_note_bytes() {
+        let mut prng = test_rng();
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::ConfidentialAmount_ConfidentialAssetType;
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let receiver = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let input = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            receiver.get_pk(),
+        );
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+
+        let note = gen_xfr_note(&mut prng, &[input], &[output], &[&sender]).unwrap();
+        let bytes = to_msgpack(&note);
+
+        let mut params = BulletproofParams::default();
+        let mut ctx = NoteVerifyContext {
+            verifier_params: None,
+            bulletproof_params: Some(&mut params),
+            merkle_root: None,
+            hash_input: &[],
+            disabled: None,
+            normalize_errors: false,
+            check_outputs_order: false,
+        };
+
+        // the result is the same every time
+        for _ in 0..2 {
+            assert_eq!(
+                verify_note_bytes(NOTE_KIND_XFR, &bytes, &mut ctx),
+                VERIFY_OK
+            );
+        }
+
+        assert_eq!(
+            verify_note_bytes(0, &bytes, &mut ctx),
+            VERIFY_ERR_UNKNOWN_KIND
+        );
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &[], &mut ctx),
+            VERIFY_ERR_INVALID_LENGTH
+        );
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &bytes[..bytes.len() - 1], &mut ctx),
+            VERIFY_ERR_DESERIALIZATION
+        );
+
+        // trailing bytes are not canonical
+        let mut longer_bytes = bytes.clone();
+        longer_bytes.push(0u8);
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &longer_bytes, &mut ctx),
+            VERIFY_ERR_DESERIALIZATION
+        );
+
+        // a note signed by someone else
+        let mut forged_note = note;
+        forged_note.multisig.signatures[0] = receiver.sign(b"forged").unwrap();
+        let forged_bytes = to_msgpack(&forged_note);
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &forged_bytes, &mut ctx),
+            VERIFY_ERR_SIGNATURE
+        );
+
+        // an anonymous note needs the verifier parameters
+        assert_ne!(
+            verify_note_bytes(NOTE_KIND_ABAR_TO_ABAR, &bytes, &mut ctx),
+            VERIFY_OK
+        );
+        let mut ctx = NoteVerifyContext {
+            verifier_params: None,
+            bulletproof_params: None,
+            merkle_root: None,
+            hash_input: &[],
+            disabled: None,
+            normalize_errors: false,
+            check_outputs_order: false,
+        };
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &bytes, &mut ctx),
+            VERIFY_ERR_MISSING_CONTEXT
+        );
+    }
+
+    /// A pipeline stage that is generic over the kinds of notes.
+    fn admit<N: NoteTrait>(
+        note: &N,
+        ctx: &mut NoteVerifyContext<'_>,
+        seen: &mut Vec<[u8; 64]>,
+    ) -> bool {
+        let digest = note.digest().unwrap();
+        if seen.contains(&digest) || note.verify(ctx).is_err() {
+            return false;
+        }
+        seen.push(digest);
+        true
+    }
+
+    #[test]
+    fn test_note_trait() {
+        let mut prng = test_rng();
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let input = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let note = gen_xfr_note(&mut prng, &[input], &[output], &[&sender]).unwrap();
+
+        assert!(note.nullifiers().is_empty());
+        assert_eq!(
+            note.outputs(),
+            vec![NoteOutput::Blind(note.body.outputs[0].clone())]
+        );
+
+        let mut params = BulletproofParams::default();
+        let mut ctx = NoteVerifyContext {
+            verifier_params: None,
+            bulletproof_params: Some(&mut params),
+            merkle_root: None,
+            hash_input: &[],
+            disabled: None,
+            normalize_errors: false,
+            check_outputs_order: false,
+        };
+        let mut seen = vec![];
+        assert!(admit(&note, &mut ctx, &mut seen));
+        // the same note is not admitted twice
+        assert!(!admit(&note, &mut ctx, &mut seen));
+
+        // the trait agrees with the verification of the bytes
+        let mut forged_note = note.clone();
+        forged_note.multisig.signatures[0] = sender.sign(b"forged").unwrap();
+        assert_ne!(forged_note.digest().unwrap(), note.digest().unwrap());
+        assert!(!admit(&forged_note, &mut ctx, &mut seen));
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &to_msgpack(&forged_note), &mut ctx),
+            VERIFY_ERR_SIGNATURE
+        );
+    }
+
+    #[test]
+    fn test_normalize_errors() {
+        let mut prng = test_rng();
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let input = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let note = gen_xfr_note(&mut prng, &[input], &[output], &[&sender]).unwrap();
+        let bytes = to_msgpack(&note);
+
+        let mut forged_note = note.clone();
+        forged_note.multisig.signatures[0] = sender.sign(b"forged").unwrap();
+        let forged_bytes = to_msgpack(&forged_note);
+
+        let mut params = BulletproofParams::default();
+        let mut ctx = NoteVerifyContext {
+            verifier_params: None,
+            bulletproof_params: Some(&mut params),
+            merkle_root: None,
+            hash_input: &[],
+            disabled: None,
+            normalize_errors: true,
+            check_outputs_order: false,
+        };
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &bytes, &mut ctx),
+            VERIFY_OK
+        );
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &forged_bytes, &mut ctx),
+            VERIFY_ERR_REJECTED
+        );
+        assert_eq!(forged_note.verify(&mut ctx), Err(NoahError::SignatureError));
+
+        // the failures before the verification of the note are still reported
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &bytes[..bytes.len() - 1], &mut ctx),
+            VERIFY_ERR_DESERIALIZATION
+        );
+        ctx.bulletproof_params = None;
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &bytes, &mut ctx),
+            VERIFY_ERR_MISSING_CONTEXT
+        );
+    }
+
+    #[test]
+    fn test_disabled_proof_types() {
+        let mut prng = test_rng();
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let input = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let note = gen_xfr_note(&mut prng, &[input], &[output], &[&sender]).unwrap();
+        let bytes = to_msgpack(&note);
+
+        let mut disabled = DisabledProofTypes::new();
+        disabled.disable_kind(NOTE_KIND_XFR);
+
+        let mut params = BulletproofParams::default();
+        let mut ctx = NoteVerifyContext {
+            verifier_params: None,
+            bulletproof_params: Some(&mut params),
+            merkle_root: None,
+            hash_input: &[],
+            disabled: Some(&disabled),
+            normalize_errors: false,
+            check_outputs_order: false,
+        };
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &bytes, &mut ctx),
+            VERIFY_ERR_DISABLED
+        );
+        // even a malformed note of a disabled kind reports that the kind is disabled
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &bytes[1..], &mut ctx),
+            VERIFY_ERR_DISABLED
+        );
+
+        let mut enabled = disabled.clone();
+        enabled.enable_kind(NOTE_KIND_XFR);
+        ctx.disabled = Some(&enabled);
+        assert_eq!(
+            verify_note_bytes(NOTE_KIND_XFR, &bytes, &mut ctx),
+            VERIFY_OK
+        );
+
+        // a circuit is disabled by the label of its verifier parameters
+        let verifier_params = VerifierParams::get_ar_to_abar().unwrap();
+        let mut disabled = DisabledProofTypes::new();
+        assert!(disabled.check_circuit(&verifier_params).is_ok());
+        disabled.disable_circuit(&verifier_params.label);
+        assert_eq!(
+            disabled.check_circuit(&verifier_params),
+            Err(NoahError::DisabledProofTypeError)
+        );
+        disabled.enable_circuit(&verifier_params.label);
+        assert!(disabled.check_circuit(&verifier_params).is_ok());
+    }
+
+    #[test]
+    fn test_block_verifier() {
+        let mut prng = test_rng();
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let mut notes = vec![];
+        for _ in 0..4 {
+            let input =
+                AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+            let output =
+                AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+            notes.push(gen_xfr_note(&mut prng, &[input], &[output], &[&sender]).unwrap());
+        }
+        notes[2].multisig.signatures[0] = sender.sign(b"forged").unwrap();
+        let bytes = notes.iter().map(to_msgpack).collect_vec();
+        let block = bytes
+            .iter()
+            .map(|bytes| BlockNote {
+                kind: NOTE_KIND_XFR,
+                bytes,
+                hash_input: &[],
+            })
+            .collect_vec();
+
+        let snapshot = StateSnapshot::new(vec![], &[]);
+        let mut params = BulletproofParams::default();
+        let mut verifier = BlockVerifier::new(&snapshot, &mut params, None);
+        assert!(verifier.verify_block(&block[..2]).is_ok());
+        assert_eq!(
+            verifier.verify_block(&block),
+            Err(BlockVerifyError {
+                index: 2,
+                reason: VERIFY_ERR_SIGNATURE
+            })
+        );
+
+        // with the failures normalized, the reason is withheld
+        let mut other_params = BulletproofParams::default();
+        let mut normalizing =
+            BlockVerifier::new(&snapshot, &mut other_params, None).with_normalized_errors(true);
+        assert_eq!(
+            normalizing.verify_block(&block),
+            Err(BlockVerifyError {
+                index: 2,
+                reason: VERIFY_ERR_REJECTED
+            })
+        );
+        // the first failure is reported, whatever fails later
+        let mut block = block;
+        block[1].kind = 0;
+        assert_eq!(
+            verifier.verify_block(&block),
+            Err(BlockVerifyError {
+                index: 1,
+                reason: VERIFY_ERR_UNKNOWN_KIND
+            })
+        );
+
+        // a nullifier spent twice invalidates the later note
+        let a = BN254Scalar::from(1u32);
+        let b = BN254Scalar::from(2u32);
+        assert!(first_block_failure(vec![Ok(vec![a]), Ok(vec![b]), Ok(vec![])]).is_ok());
+        assert_eq!(
+            first_block_failure(vec![
+                Ok(vec![a]),
+                Ok(vec![b]),
+                Ok(vec![a]),
+                Err(VERIFY_ERR_SIGNATURE)
+            ]),
+            Err(BlockVerifyError {
+                index: 2,
+                reason: VERIFY_ERR_NULLIFIER_CONFLICT
+            })
+        );
+        assert_eq!(
+            first_block_failure(vec![Ok(vec![a, a])]),
+            Err(BlockVerifyError {
+                index: 0,
+                reason: VERIFY_ERR_NULLIFIER_CONFLICT
+            })
+        );
+
+        let snapshot = StateSnapshot::new(vec![b], &[a]);
+        assert!(snapshot.is_spent(&a) && !snapshot.is_spent(&b));
+        assert!(snapshot.has_merkle_root(&b) && !snapshot.has_merkle_root(&a));
+    }
+
+    #[test]
+    fn test_nullifier_index() {
+        let [a, b, c, d] = [1u32, 2, 3, 4].map(BN254Scalar::from);
+        let mut index = NullifierIndex::new();
+
+        assert!(index.try_reserve([1u8; 64], &[a, b]).is_ok());
+        assert!(index.try_reserve([1u8; 64], &[a, b]).is_ok());
+        assert_eq!(
+            index.try_reserve([2u8; 64], &[c, b]),
+            Err(Conflict {
+                nullifier: b,
+                holder: Some([1u8; 64])
+            })
+        );
+        // nothing is reserved for a conflicting note
+        assert_eq!(index.holder(&c), None);
+        assert_eq!(
+            index.try_reserve([2u8; 64], &[c, c]),
+            Err(Conflict {
+                nullifier: c,
+                holder: None
+            })
+        );
+        assert!(index.try_reserve([2u8; 64], &[c]).is_ok());
+        assert_eq!(index.len(), 2);
+
+        let snapshot = StateSnapshot::new(vec![], &[d]);
+        assert_eq!(
+            index.try_reserve_against(&snapshot, [3u8; 64], &[d]),
+            Err(Conflict {
+                nullifier: d,
+                holder: None
+            })
+        );
+
+        // once the first note is evicted, its nullifiers are free again
+        assert_eq!(index.evict(&[1u8; 64]), vec![a, b]);
+        assert!(!index.contains(&[1u8; 64]));
+        assert!(index.try_reserve([3u8; 64], &[b]).is_ok());
+
+        // a committed block spending c evicts the second note
+        let snapshot = StateSnapshot::new(vec![], &[c]);
+        assert_eq!(index.evict_spent(&snapshot), vec![[2u8; 64]]);
+        assert_eq!(index.holder(&c), None);
+        assert_eq!(index.holder(&b), Some([3u8; 64]));
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_note_bundle() {
+        let mut prng = test_rng();
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let mut notes = vec![];
+        for _ in 0..3 {
+            let input =
+                AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+            let output =
+                AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+            notes.push(gen_xfr_note(&mut prng, &[input], &[output], &[&sender]).unwrap());
+        }
+
+        // the nodes receiving the notes in any order agree on the bundle
+        let mut bundle = NoteBundle::new();
+        for note in notes.iter() {
+            bundle.add_note(note, b"tx").unwrap();
+        }
+        assert!(bundle.digest().is_err());
+        bundle.canonicalize().unwrap();
+        let digest = bundle.digest().unwrap();
+
+        let mut other = NoteBundle::new();
+        for note in notes.iter().rev() {
+            other
+                .add_note_bytes(NOTE_KIND_XFR, &to_msgpack(note), b"tx")
+                .unwrap();
+        }
+        other.canonicalize().unwrap();
+        assert_eq!(other.digest().unwrap(), digest);
+        assert_eq!(other.note_digests(), bundle.note_digests());
+        let mut digests = other.note_digests();
+        digests.sort();
+        assert_eq!(other.note_digests(), digests);
+
+        let snapshot = StateSnapshot::new(vec![], &[]);
+        let mut params = BulletproofParams::default();
+        let mut verifier = BlockVerifier::new(&snapshot, &mut params, None);
+        assert!(verifier.verify_block(&bundle.block_notes()).is_ok());
+
+        // adding a note undoes the canonicalization, and a note cannot appear twice
+        other.add_note(&notes[0], b"tx").unwrap();
+        assert!(!other.is_canonical());
+        assert_eq!(
+            other.canonicalize(),
+            Err(NoahError::DuplicateBundleEntryError)
+        );
+
+        // a nullifier cannot be spent twice within the bundle
+        let [a, b, c] = [1u32, 2, 3].map(BN254Scalar::from);
+        let entry = |digest: u8, nullifiers: Vec<BN254Scalar>| BundleEntry {
+            kind: NOTE_KIND_ABAR_TO_ABAR,
+            bytes: vec![],
+            hash_input: vec![],
+            digest: [digest; 64],
+            nullifiers,
+        };
+        let mut bundle = NoteBundle::new();
+        bundle.push_entry(entry(2, vec![c, a]));
+        bundle.push_entry(entry(1, vec![b]));
+        bundle.canonicalize().unwrap();
+        assert_eq!(bundle.nullifiers().unwrap(), &[a, b, c]);
+        assert_eq!(bundle.note_digests(), vec![[1u8; 64], [2u8; 64]]);
+
+        bundle.push_entry(entry(3, vec![b]));
+        assert!(bundle.nullifiers().is_err());
+        assert_eq!(
+            bundle.canonicalize(),
+            Err(NoahError::DuplicateBundleEntryError)
+        );
+    }
+}
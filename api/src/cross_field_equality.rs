@@ -0,0 +1,450 @@
+use crate::errors::{NoahError, Result};
+use merlin::Transcript;
+use noah_algebra::{prelude::*, traits::PedersenCommitment};
+use noah_crypto::{
+    anemoi_jive::AnemoiJive,
+    delegated_schnorr::{prove_delegated_schnorr, verify_delegated_schnorr, DSInspection, DSProof},
+    field_simulation::{SimFr, SimFrParams},
+};
+use noah_plonk::plonk::constraint_system::{field_simulation::SimFrVar, TurboCS, VarIndex};
+use num_bigint::BigUint;
+
+/// The variables of the cross-field equality in a constraint system.
+#[derive(Debug, Clone)]
+pub struct CrossFieldEqualityVars {
+    /// The committed values over the field of the constraint system, in the order of the commitments.
+    pub values: Vec<VarIndex>,
+    /// The variables to be prepared as public inputs, in the order of `cross_field_equality_inputs`.
+    pub public_inputs: Vec<VarIndex>,
+}
+
+/// Prove that the values in Pedersen commitments over the group `G`, given with their blinding
+/// factors, are equal to values in a constraint system over the field `F`. Return the delegated
+/// Schnorr proof, the inspector's state, which is opened in the constraint system with the Anemoi
+/// hash `H`, and the challenges `beta` and `lambda`.
+pub fn prove_cross_field_equality<
+    F: Scalar,
+    H: AnemoiJive<F, 2, 14>,
+    R: CryptoRng + RngCore,
+    S: Scalar,
+    G: Group<ScalarType = S>,
+    P: SimFrParams<F>,
+    PC: PedersenCommitment<G>,
+>(
+    prng: &mut R,
+    values: &[(S, S)],
+    pc_gens: &PC,
+    commitments: &[G],
+    transcript: &mut Transcript,
+) -> Result<(DSProof<F, S, G>, DSInspection<F, S, G>, S, S)> {
+    if values.is_empty() || values.len() != commitments.len() {
+        return Err(NoahError::ParameterError);
+    }
+    Ok(prove_delegated_schnorr::<F, H, R, S, G, P, PC>(
+        prng,
+        &values.to_vec(),
+        pc_gens,
+        &commitments.to_vec(),
+        transcript,
+    )?)
+}
+
+/// Verify the delegated Schnorr proof for the commitments, and return the public inputs
+/// with which the constraint system must then be verified.
+pub fn verify_cross_field_equality<
+    F: Scalar,
+    S: Scalar,
+    G: Group<ScalarType = S>,
+    P: SimFrParams<F>,
+    PC: PedersenCommitment<G>,
+>(
+    pc_gens: &PC,
+    commitments: &[G],
+    proof: &DSProof<F, S, G>,
+    transcript: &mut Transcript,
+) -> Result<Vec<F>> {
+    if commitments.is_empty() || proof.response_scalars.len() != commitments.len() {
+        return Err(NoahError::ParameterError);
+    }
+    let (beta, lambda) =
+        verify_delegated_schnorr(pc_gens, &commitments.to_vec(), proof, transcript)?;
+    cross_field_equality_inputs::<F, S, G, P>(proof, &beta, &lambda)
+}
+
+/// Return the challenges in the order of the public inputs: `beta`, `lambda^i` for `i >= 1`,
+/// `beta * lambda^i` for `i >= 1`, and the combined response `sum_i lambda^i * s_i`.
+fn challenge_series<S: Scalar>(n: usize, beta: &S, lambda: &S, responses: &[(S, S)]) -> Vec<S> {
+    let mut lambda_series = Vec::with_capacity(n);
+    let mut power = S::one();
+    for _ in 0..n {
+        lambda_series.push(power);
+        power.mul_assign(lambda);
+    }
+
+    let mut series = Vec::with_capacity(2 * n);
+    series.push(*beta);
+    series.extend_from_slice(&lambda_series[1..]);
+    series.extend(lambda_series[1..].iter().map(|l| beta.mul(l)));
+
+    let mut combined_response = S::zero();
+    for ((s, _), l) in responses.iter().zip(lambda_series.iter()) {
+        combined_response.add_assign(&s.mul(l));
+    }
+    series.push(combined_response);
+    series
+}
+
+/// Convert a scalar of `S` into its simulated representation over `F`.
+fn to_sim_fr<F: Scalar, S: Scalar, P: SimFrParams<F>>(v: &S) -> SimFr<F, P> {
+    SimFr::from(&<S as Into<BigUint>>::into(*v))
+}
+
+/// Compute the public inputs of the cross-field equality: the inspector's state commitment,
+/// followed by the limbs of `beta`, of `lambda^i` and of `beta * lambda^i` for `i >= 1`,
+/// and of the combined response.
+pub fn cross_field_equality_inputs<
+    F: Scalar,
+    S: Scalar,
+    G: Group<ScalarType = S>,
+    P: SimFrParams<F>,
+>(
+    proof: &DSProof<F, S, G>,
+    beta: &S,
+    lambda: &S,
+) -> Result<Vec<F>> {
+    let n = proof.response_scalars.len();
+    if n == 0 {
+        return Err(NoahError::ParameterError);
+    }
+
+    let series = challenge_series(n, beta, lambda, &proof.response_scalars);
+    let mut inputs = Vec::with_capacity(1 + series.len() * P::NUM_OF_LIMBS);
+    inputs.push(proof.inspection_comm);
+    for v in series.iter() {
+        inputs.extend_from_slice(&to_sim_fr::<F, S, P>(v).limbs);
+    }
+    Ok(inputs)
+}
+
+/// Pre-hash a group of public inputs into one input with the Anemoi hash `H`, as the verifier
+/// of a circuit that absorbs them with `prehash_public_inputs_in_cs`.
+pub fn prehash_public_inputs<F: Scalar, H: AnemoiJive<F, 2, 14>>(inputs: &[F]) -> F {
+    H::eval_variable_length_hash(inputs)
+}
+
+/// Absorb the variables into their Anemoi hash in the constraint system, which must have
+/// loaded the parameters of `H`, and return the variable of the hash, to be prepared as the
+/// single public input in place of the variables.
+///
+/// The variables become witnesses, bound to the values of the verifier by the collision
+/// resistance of the hash, and the multi-scalar multiplication of the verifier over the
+/// public inputs shrinks from the size of the group to one.
+pub fn prehash_public_inputs_in_cs<F: Scalar, H: AnemoiJive<F, 2, 14>>(
+    cs: &mut TurboCS<F>,
+    vars: &[VarIndex],
+) -> VarIndex {
+    let values = vars.iter().map(|var| cs.witness[*var]).collect::<Vec<_>>();
+    let trace = H::eval_variable_length_hash_with_trace(&values);
+    let hash_var = cs.new_variable(trace.output);
+    cs.anemoi_variable_length_hash::<H>(&trace, vars, hash_var);
+    hash_var
+}
+
+/// Combine the limbs into one variable, the limb `i` being weighted by `2^(bit_per_limb * i)`.
+fn combine_limbs_in_cs<F: Scalar>(
+    cs: &mut TurboCS<F>,
+    limbs_var: &[VarIndex],
+    bit_per_limb: usize,
+) -> VarIndex {
+    let zero_var = cs.zero_var();
+    let zero = F::zero();
+    let one = F::one();
+    let step = |i: usize| F::from(&BigUint::one().shl(bit_per_limb * i));
+
+    let mut sum_var = cs.linear_combine(
+        &[
+            *limbs_var.first().unwrap_or(&zero_var),
+            *limbs_var.get(1).unwrap_or(&zero_var),
+            *limbs_var.get(2).unwrap_or(&zero_var),
+            *limbs_var.get(3).unwrap_or(&zero_var),
+        ],
+        one,
+        step(1),
+        step(2),
+        step(3),
+    );
+
+    if limbs_var.len() > 4 {
+        for (j, chunk) in limbs_var[4..].chunks(3).enumerate() {
+            let mut wires = [sum_var, zero_var, zero_var, zero_var];
+            let mut coefs = [one, zero, zero, zero];
+            for (k, limb_var) in chunk.iter().enumerate() {
+                wires[k + 1] = *limb_var;
+                coefs[k + 1] = step(4 + 3 * j + k);
+            }
+            sum_var = cs.linear_combine(&wires, coefs[0], coefs[1], coefs[2], coefs[3]);
+        }
+    }
+    sum_var
+}
+
+/// Add the cross-field equality to the constraint system, which must have loaded the
+/// parameters of `H`. The value `i` is bounded by `value_bits[i]` bits, which must be less
+/// than the capacity of `F`, so that it is recomposed into one variable over `F`.
+///
+/// The returned public inputs are not prepared, so that the caller may place them
+/// among its own. The field simulation has a limited surfeit, which bounds the number
+/// of values to a few; more values should be split into several instances.
+pub fn cross_field_equality_in_cs<
+    F: Scalar,
+    H: AnemoiJive<F, 2, 14>,
+    S: Scalar,
+    G: Group<ScalarType = S>,
+    P: SimFrParams<F>,
+>(
+    cs: &mut TurboCS<F>,
+    proof: &DSProof<F, S, G>,
+    inspection: &DSInspection<F, S, G>,
+    beta: &S,
+    lambda: &S,
+    value_bits: &[usize],
+) -> Result<CrossFieldEqualityVars> {
+    let n = inspection.committed_data_and_randomizer.len();
+    if n == 0
+        || proof.response_scalars.len() != n
+        || value_bits.len() != n
+        || value_bits.iter().any(|bits| *bits >= F::capacity())
+    {
+        return Err(NoahError::ParameterError);
+    }
+
+    // 1. Allocate the values, the randomizers, the state commitment and the challenges.
+    let values_sim_fr = inspection
+        .committed_data_and_randomizer
+        .iter()
+        .map(|(v, _)| to_sim_fr::<F, S, P>(v))
+        .collect::<Vec<_>>();
+    let randomizers_sim_fr = inspection
+        .committed_data_and_randomizer
+        .iter()
+        .map(|(_, a)| to_sim_fr::<F, S, P>(a))
+        .collect::<Vec<_>>();
+
+    let values_sim_fr_var = values_sim_fr
+        .iter()
+        .zip(value_bits.iter())
+        .map(|(v, bits)| SimFrVar::alloc_witness_bounded_total_bits(cs, v, *bits).0)
+        .collect::<Vec<_>>();
+    let randomizers_sim_fr_var = randomizers_sim_fr
+        .iter()
+        .map(|a| SimFrVar::alloc_witness(cs, a).0)
+        .collect::<Vec<_>>();
+    let comm_var = cs.new_variable(proof.inspection_comm);
+    let r_var = cs.new_variable(inspection.r);
+
+    let series = challenge_series(n, beta, lambda, &proof.response_scalars);
+    let series_sim_fr_var = series
+        .iter()
+        .map(|v| SimFrVar::alloc_input(cs, &to_sim_fr::<F, S, P>(v)))
+        .collect::<Vec<SimFrVar<F, P>>>();
+
+    // 2. Compress the limbs of the values and the randomizers.
+    let mut all_limbs = Vec::with_capacity(2 * n * P::NUM_OF_LIMBS);
+    let mut all_limbs_var = Vec::with_capacity(2 * n * P::NUM_OF_LIMBS);
+    for (v, v_var) in values_sim_fr.iter().chain(randomizers_sim_fr.iter()).zip(
+        values_sim_fr_var
+            .iter()
+            .chain(randomizers_sim_fr_var.iter()),
+    ) {
+        all_limbs.extend_from_slice(&v.limbs);
+        all_limbs_var.extend(v_var.var.iter().map(|wire| cs.var(*wire)));
+    }
+
+    let num_limbs_compressed = F::capacity() / P::BIT_PER_LIMB;
+    let mut compressed_limbs = Vec::new();
+    let mut compressed_limbs_var = Vec::new();
+    for (limbs, limbs_var) in all_limbs
+        .chunks(num_limbs_compressed)
+        .zip(all_limbs_var.chunks(num_limbs_compressed))
+    {
+        let mut sum = BigUint::zero();
+        for (i, limb) in limbs.iter().enumerate() {
+            sum.add_assign(<F as Into<BigUint>>::into(*limb).shl(P::BIT_PER_LIMB * i));
+        }
+        compressed_limbs.push(F::from(&sum));
+        compressed_limbs_var.push(combine_limbs_in_cs(cs, limbs_var, P::BIT_PER_LIMB));
+    }
+
+    // 3. Open the inspector's state commitment.
+    compressed_limbs.push(inspection.r);
+    compressed_limbs_var.push(r_var);
+    let trace = H::eval_variable_length_hash_with_trace(&compressed_limbs);
+    cs.anemoi_variable_length_hash::<H>(&trace, &compressed_limbs_var, comm_var);
+
+    // 4. Check sum_i beta * lambda^i * x_i + sum_{i >= 1} lambda^i * a_i = sum_i lambda^i * s_i - a_0
+    // in field simulation.
+    {
+        let beta_lambda_series_var =
+            core::iter::once(&series_sim_fr_var[0]).chain(series_sim_fr_var[n..2 * n - 1].iter());
+        let lambda_series_var = series_sim_fr_var[1..n].iter();
+
+        let mut terms = Vec::with_capacity(2 * n - 1);
+        for (beta_lambda_var, x_var) in beta_lambda_series_var.zip(values_sim_fr_var.iter()) {
+            terms.push(beta_lambda_var.mul(cs, x_var));
+        }
+        for (lambda_var, a_var) in lambda_series_var.zip(randomizers_sim_fr_var[1..].iter()) {
+            terms.push(lambda_var.mul(cs, a_var));
+        }
+
+        let mut rhs = terms[0].clone();
+        for term in terms[1..].iter() {
+            rhs = rhs.add(cs, term);
+        }
+
+        let combined_response_minus_a_var =
+            series_sim_fr_var[2 * n - 1].sub(cs, &randomizers_sim_fr_var[0]);
+
+        let eqn = rhs.sub(cs, &combined_response_minus_a_var);
+        eqn.enforce_zero(cs);
+    }
+
+    // 5. Recompose the values over the field of the constraint system.
+    let values = values_sim_fr_var
+        .iter()
+        .map(|v_var| {
+            let limbs_var = v_var
+                .var
+                .iter()
+                .map(|wire| cs.var(*wire))
+                .collect::<Vec<_>>();
+            combine_limbs_in_cs(cs, &limbs_var, P::BIT_PER_LIMB)
+        })
+        .collect::<Vec<_>>();
+
+    let mut public_inputs = Vec::with_capacity(1 + series_sim_fr_var.len() * P::NUM_OF_LIMBS);
+    public_inputs.push(comm_var);
+    for v_var in series_sim_fr_var.iter() {
+        public_inputs.extend(v_var.var.iter().map(|wire| cs.var(*wire)));
+    }
+
+    Ok(CrossFieldEqualityVars {
+        values,
+        public_inputs,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cross_field_equality::{
+        cross_field_equality_in_cs, prove_cross_field_equality, verify_cross_field_equality,
+    };
+    use merlin::Transcript;
+    use noah_algebra::{
+        bls12_381::BLSScalar,
+        prelude::*,
+        ristretto::{PedersenCommitmentRistretto, RistrettoScalar},
+        traits::PedersenCommitment,
+    };
+    use noah_crypto::{anemoi_jive::AnemoiJive381, field_simulation::SimFrParamsBLSRistretto};
+    use noah_plonk::plonk::constraint_system::TurboCS;
+
+    #[test]
+    fn test_cross_field_equality() {
+        let mut prng = test_rng();
+        let pc_gens = PedersenCommitmentRistretto::default();
+
+        let amount = 71u64;
+        let tag = RistrettoScalar::from(123456789u64);
+        let values = vec![
+            (
+                RistrettoScalar::from(amount),
+                RistrettoScalar::random(&mut prng),
+            ),
+            (tag, RistrettoScalar::random(&mut prng)),
+        ];
+        let commitments = values
+            .iter()
+            .map(|(v, r)| pc_gens.commit(*v, *r))
+            .collect::<Vec<_>>();
+
+        let (proof, inspection, beta, lambda) = prove_cross_field_equality::<
+            BLSScalar,
+            AnemoiJive381,
+            _,
+            _,
+            _,
+            SimFrParamsBLSRistretto,
+            _,
+        >(
+            &mut prng,
+            &values,
+            &pc_gens,
+            &commitments,
+            &mut Transcript::new(b"Test"),
+        )
+        .unwrap();
+        let inputs = verify_cross_field_equality::<_, _, _, SimFrParamsBLSRistretto, _>(
+            &pc_gens,
+            &commitments,
+            &proof,
+            &mut Transcript::new(b"Test"),
+        )
+        .unwrap();
+
+        let mut cs = TurboCS::<BLSScalar>::new();
+        cs.load_anemoi_jive_parameters::<AnemoiJive381>();
+        let vars = cross_field_equality_in_cs::<_, AnemoiJive381, _, _, SimFrParamsBLSRistretto>(
+            &mut cs,
+            &proof,
+            &inspection,
+            &beta,
+            &lambda,
+            &[64, 240],
+        )
+        .unwrap();
+        for var in vars.public_inputs.iter() {
+            cs.prepare_pi_variable(*var);
+        }
+
+        let amount_var = cs.new_variable(BLSScalar::from(amount));
+        let tag_var = cs.new_variable(BLSScalar::from(123456789u64));
+        cs.equal(vars.values[0], amount_var);
+        cs.equal(vars.values[1], tag_var);
+        cs.pad();
+
+        let witness_values = cs.get_and_clear_witness();
+        assert!(cs.verify_witness(&witness_values, &inputs).is_ok());
+
+        // the public inputs of other commitments are rejected
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[1].add_assign(&BLSScalar::one());
+        assert!(cs.verify_witness(&witness_values, &wrong_inputs).is_err());
+
+        // the proof is bound to the commitments
+        let mut wrong_commitments = commitments.clone();
+        wrong_commitments.swap(0, 1);
+        assert!(
+            verify_cross_field_equality::<_, _, _, SimFrParamsBLSRistretto, _>(
+                &pc_gens,
+                &wrong_commitments,
+                &proof,
+                &mut Transcript::new(b"Test"),
+            )
+            .is_err()
+        );
+
+        // the values must fit in their bounds
+        let mut cs = TurboCS::<BLSScalar>::new();
+        assert!(
+            cross_field_equality_in_cs::<_, AnemoiJive381, _, _, SimFrParamsBLSRistretto>(
+                &mut cs,
+                &proof,
+                &inspection,
+                &beta,
+                &lambda,
+                &[64],
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use crate::anon_xfr::{
+    abar_to_abar::AXfrNote, abar_to_bar::AbarToBarNote, bar_to_abar::BarToAbarNote,
+};
+use crate::consensus::{decode_note, seeded_prng, NoteTrait, NoteVerifyContext};
+use crate::errors::{NoahError, Result};
+use crate::xfr::{
+    legacy::{verify_legacy_xfr_note, LegacyXfrNote},
+    structs::XfrNote,
+    XfrNotePolicies,
+};
+use noah_algebra::prelude::*;
+
+/// The version of the on-chain format of a note, which is the first byte of its envelope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NoteVersion(pub u8);
+
+impl NoteVersion {
+    /// The format of zei v0.1, which only exists for `XfrNote`.
+    pub const LEGACY: NoteVersion = NoteVersion(0);
+    /// The current format.
+    pub const V1: NoteVersion = NoteVersion(1);
+}
+
+/// The notes that can be wrapped in a `VersionedNote`.
+pub trait VersionedNoteType: NoteTrait {
+    /// The version of the notes created by this library.
+    const CURRENT_VERSION: NoteVersion = NoteVersion::V1;
+
+    /// The versions that are accepted, which include the current version.
+    const ACCEPTED_VERSIONS: &'static [NoteVersion] = &[NoteVersion::V1];
+
+    /// Decode the note from its MessagePack serialization in the format of the accepted version,
+    /// and upgrade it to the current format.
+    fn decode_payload(version: NoteVersion, payload: &[u8]) -> Result<Self> {
+        if version != Self::CURRENT_VERSION {
+            return Err(NoahError::UnknownNoteVersionError);
+        }
+        decode_note(payload)
+    }
+
+    /// Verify the note in the format of the accepted version.
+    fn verify_payload(
+        version: NoteVersion,
+        payload: &[u8],
+        ctx: &mut NoteVerifyContext<'_>,
+    ) -> Result<()> {
+        Self::decode_payload(version, payload)?.verify(ctx)
+    }
+}
+
+impl VersionedNoteType for XfrNote {
+    const ACCEPTED_VERSIONS: &'static [NoteVersion] = &[NoteVersion::LEGACY, NoteVersion::V1];
+
+    fn decode_payload(version: NoteVersion, payload: &[u8]) -> Result<Self> {
+        match version {
+            NoteVersion::LEGACY => Ok(decode_note::<LegacyXfrNote>(payload)?.into()),
+            NoteVersion::V1 => decode_note(payload),
+            _ => Err(NoahError::UnknownNoteVersionError),
+        }
+    }
+
+    fn verify_payload(
+        version: NoteVersion,
+        payload: &[u8],
+        ctx: &mut NoteVerifyContext<'_>,
+    ) -> Result<()> {
+        if version != NoteVersion::LEGACY {
+            return Self::decode_payload(version, payload)?.verify(ctx);
+        }
+
+        // The signatures of a legacy note are over the legacy body, so it is verified as is.
+        let note = decode_note::<LegacyXfrNote>(payload)?;
+        let params = ctx
+            .bulletproof_params
+            .as_deref_mut()
+            .ok_or(NoahError::MissingVerifierParamsError)?;
+        let policies =
+            XfrNotePolicies::empty_policies(note.body.inputs.len(), note.body.outputs.len());
+        let mut prng = seeded_prng(Self::KIND, payload);
+        verify_legacy_xfr_note(&mut prng, params, &note, &policies.to_ref())
+    }
+}
+
+impl VersionedNoteType for AXfrNote {}
+
+impl VersionedNoteType for BarToAbarNote {}
+
+impl VersionedNoteType for AbarToBarNote {}
+
+/// The envelope of a note, which is serialized as the version byte followed by
+/// the MessagePack serialization of the note in the format of that version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionedNote<T> {
+    /// The version that the note was serialized in.
+    pub version: NoteVersion,
+    /// The note, upgraded to the current format.
+    pub note: T,
+}
+
+impl<T: VersionedNoteType> VersionedNote<T> {
+    /// Wrap a note in the current format.
+    pub fn new(note: T) -> Self {
+        VersionedNote {
+            version: T::CURRENT_VERSION,
+            note,
+        }
+    }
+
+    /// Serialize the envelope. Only the envelopes of the current version can be serialized,
+    /// since the notes of the older versions have been upgraded.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        if self.version != T::CURRENT_VERSION {
+            return Err(NoahError::SerializationError);
+        }
+        let mut bytes = vec![self.version.0];
+        bytes.extend_from_slice(
+            &rmp_serde::to_vec(&self.note).map_err(|_| NoahError::SerializationError)?,
+        );
+        Ok(bytes)
+    }
+
+    /// Deserialize the envelope of an accepted version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (version, payload) = split_version::<T>(bytes)?;
+        Ok(VersionedNote {
+            version,
+            note: T::decode_payload(version, payload)?,
+        })
+    }
+
+    /// Return the note.
+    pub fn into_inner(self) -> T {
+        self.note
+    }
+}
+
+/// Split the version byte from the payload, rejecting the versions that are not accepted.
+fn split_version<T: VersionedNoteType>(bytes: &[u8]) -> Result<(NoteVersion, &[u8])> {
+    let (version, payload) = bytes.split_first().ok_or(NoahError::DeserializationError)?;
+    let version = NoteVersion(*version);
+    if !T::ACCEPTED_VERSIONS.contains(&version) {
+        return Err(NoahError::UnknownNoteVersionError);
+    }
+    Ok((version, payload))
+}
+
+/// Verify the serialized envelope of a note of the kind under the context,
+/// and return the version of the note.
+pub fn verify_versioned_note<T: VersionedNoteType>(
+    bytes: &[u8],
+    ctx: &mut NoteVerifyContext<'_>,
+) -> Result<NoteVersion> {
+    if let Some(disabled) = ctx.disabled {
+        disabled.check_kind(T::KIND)?;
+    }
+    let (version, payload) = split_version::<T>(bytes)?;
+    T::verify_payload(version, payload, ctx)?;
+    Ok(version)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anon_xfr::abar_to_abar::AXfrNote;
+    use crate::consensus::NoteVerifyContext;
+    use crate::envelope::{verify_versioned_note, NoteVersion, VersionedNote};
+    use crate::errors::NoahError;
+    use crate::keys::{KeyPair, SignatureList};
+    use crate::parameters::{bulletproofs::BulletproofParams, AddressFormat::ED25519};
+    use crate::xfr::{
+        asset_record::AssetRecordType,
+        gen_xfr_note,
+        legacy::{legacy_xfr_body_auth_message, LegacyOwnerMemo, LegacyXfrBody, LegacyXfrNote},
+        structs::{AssetRecord, AssetRecordTemplate, AssetType, XfrNote},
+    };
+    use noah_algebra::{prelude::*, ristretto::CompressedEdwardsY};
+    use noah_crypto::hybrid_encryption::NoahHybridCiphertext;
+
+    #[test]
+    fn test_versioned_note() {
+        let mut prng = test_rng();
+        let mut params = BulletproofParams::default();
+        let sender = KeyPair::sample(&mut prng, ED25519);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::ConfidentialAmount_ConfidentialAssetType;
+
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let input = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            receiver.get_pk(),
+        );
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let note = gen_xfr_note(&mut prng, &[input], &[output], &[&sender]).unwrap();
+
+        let mut ctx = NoteVerifyContext {
+            verifier_params: None,
+            bulletproof_params: Some(&mut params),
+            merkle_root: None,
+            hash_input: &[],
+            disabled: None,
+            normalize_errors: false,
+            check_outputs_order: false,
+        };
+
+        // the current version
+        let bytes = VersionedNote::new(note.clone()).to_bytes().unwrap();
+        assert_eq!(bytes[0], NoteVersion::V1.0);
+        let decoded = VersionedNote::<XfrNote>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.into_inner(), note);
+        assert_eq!(
+            verify_versioned_note::<XfrNote>(&bytes, &mut ctx).unwrap(),
+            NoteVersion::V1
+        );
+
+        // the legacy version is still accepted
+        let owners_memos = note
+            .body
+            .owners_memos
+            .iter()
+            .map(|memo| {
+                memo.as_ref().map(|memo| LegacyOwnerMemo {
+                    blind_share: CompressedEdwardsY::from_slice(&memo.blind_share_bytes.0),
+                    lock: NoahHybridCiphertext::noah_from_bytes(&memo.lock_bytes.0).unwrap(),
+                })
+            })
+            .collect_vec();
+        let body = LegacyXfrBody {
+            inputs: note.body.inputs.clone(),
+            outputs: note.body.outputs.clone(),
+            proofs: note.body.proofs.clone(),
+            asset_tracing_memos: note.body.asset_tracing_memos.clone(),
+            owners_memos,
+        };
+        let message = legacy_xfr_body_auth_message(&body).unwrap();
+        let legacy_note = LegacyXfrNote {
+            body,
+            multisig: SignatureList::sign(&[&sender], &message).unwrap(),
+        };
+        let mut legacy_bytes = vec![NoteVersion::LEGACY.0];
+        legacy_bytes.extend_from_slice(&rmp_serde::to_vec(&legacy_note).unwrap());
+        assert_eq!(
+            verify_versioned_note::<XfrNote>(&legacy_bytes, &mut ctx).unwrap(),
+            NoteVersion::LEGACY
+        );
+        let decoded = VersionedNote::<XfrNote>::from_bytes(&legacy_bytes).unwrap();
+        assert_eq!(decoded.version, NoteVersion::LEGACY);
+        assert_eq!(decoded.note.body, note.body);
+        assert!(decoded.to_bytes().is_err());
+
+        // unknown versions are rejected explicitly
+        let mut unknown = bytes.clone();
+        unknown[0] = 2;
+        assert_eq!(
+            VersionedNote::<XfrNote>::from_bytes(&unknown),
+            Err(NoahError::UnknownNoteVersionError)
+        );
+        assert_eq!(
+            verify_versioned_note::<XfrNote>(&unknown, &mut ctx),
+            Err(NoahError::UnknownNoteVersionError)
+        );
+        assert_eq!(
+            VersionedNote::<AXfrNote>::from_bytes(&legacy_bytes),
+            Err(NoahError::UnknownNoteVersionError)
+        );
+        assert_eq!(
+            VersionedNote::<XfrNote>::from_bytes(&[]),
+            Err(NoahError::DeserializationError)
+        );
+    }
+}
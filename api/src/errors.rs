@@ -3,6 +3,7 @@ use ark_std::{boxed::Box, error, fmt, format};
 use bulletproofs::{r1cs::R1CSError, ProofError};
 use noah_algebra::prelude::AlgebraError;
 use noah_crypto::errors::CryptoError;
+#[cfg(feature = "anon_xfr")]
 use noah_plonk::errors::PlonkError;
 
 pub(crate) type Result<T> = core::result::Result<T, NoahError>;
@@ -12,6 +13,7 @@ pub(crate) type Result<T> = core::result::Result<T, NoahError>;
 pub enum NoahError {
     Algebra(AlgebraError),
     Crypto(CryptoError),
+    #[cfg(feature = "anon_xfr")]
     Plonk(PlonkError),
     R1CS(R1CSError),
     Bulletproofs(ProofError),
@@ -43,6 +45,18 @@ pub enum NoahError {
     XfrVerifyAssetTracingIdentityError,
     XfrVerifyConfidentialAmountError,
     RangeProofProveError,
+    DisabledProofTypeError,
+    UnknownNoteVersionError,
+    ParamsFetchError,
+    ParamsIntegrityError,
+    CeremonyVerificationError,
+    PublicInputsSchemaError,
+    CircuitHashMismatchError,
+    ParamsMismatch,
+    ProvingReceiptError,
+    VerificationError,
+    DuplicateBundleEntryError,
+    MerkleRootMismatchError,
 }
 
 impl fmt::Display for NoahError {
@@ -51,6 +65,7 @@ impl fmt::Display for NoahError {
         f.write_str(match self {
             Algebra(e) => Box::leak(format!("Algebra: {}", e).into_boxed_str()),
             Crypto(e) => Box::leak(format!("Crypto: {}", e).into_boxed_str()),
+            #[cfg(feature = "anon_xfr")]
             Plonk(e) => Box::leak(format!("Plonk: {}", e).into_boxed_str()),
             R1CS(e) => Box::leak(format!("R1CS: {}", e).into_boxed_str()),
             Bulletproofs(e) => Box::leak(format!("Bulletproofs: {}", e).into_boxed_str()),
@@ -81,7 +96,19 @@ impl fmt::Display for NoahError {
             XfrVerifyAssetTracingAssetAmountError => "Asset Tracking error. Asset commitment and asset ciphertext do not match",
             XfrVerifyAssetTracingIdentityError => "Asset Tracking error. Identity reveal proof does not hold",
             XfrVerifyConfidentialAmountError => "Invalid amount in non confidential asset transfer",
-            RangeProofProveError => "Could not create range proof due to incorrect input or parameters"
+            RangeProofProveError => "Could not create range proof due to incorrect input or parameters",
+            DisabledProofTypeError => "The acceptance of this note type or circuit is disabled",
+            UnknownNoteVersionError => "The version of the note is unknown",
+            ParamsFetchError => "Could not download the parameters from any of the configured URLs",
+            ParamsIntegrityError => "The parameters do not match the hash pinned in the manifest",
+            CeremonyVerificationError => "The SRS ceremony transcript failed verification",
+            PublicInputsSchemaError => "The public inputs do not match the schema of the circuit",
+            CircuitHashMismatchError => "The parameters were generated for a different circuit",
+            ParamsMismatch => "The parameters do not match the constraint system of the circuit",
+            ProvingReceiptError => "The proving receipt does not match the job or the proof",
+            VerificationError => "The note failed verification",
+            DuplicateBundleEntryError => "The bundle contains a note or a nullifier twice",
+            MerkleRootMismatchError => "The Merkle root of the note is not the expected one"
         })
     }
 }
@@ -105,9 +132,13 @@ impl From<CryptoError> for NoahError {
     }
 }
 
+#[cfg(feature = "anon_xfr")]
 impl From<PlonkError> for NoahError {
     fn from(e: PlonkError) -> NoahError {
-        NoahError::Plonk(e)
+        match e {
+            PlonkError::ParamsMismatch => NoahError::ParamsMismatch,
+            e => NoahError::Plonk(e),
+        }
     }
 }
 
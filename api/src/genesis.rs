@@ -0,0 +1,330 @@
+use crate::anon_xfr::structs::{AnonAssetRecord, AxfrOwnerMemo, OpenAnonAssetRecordBuilder};
+use crate::errors::{NoahError, Result};
+use crate::keys::PublicKey;
+use crate::xfr::{
+    asset_record::AssetRecordType,
+    structs::{AssetRecord, AssetRecordTemplate, AssetType, BlindAssetRecord, OwnerMemo},
+};
+use digest::Digest;
+use noah_algebra::{
+    bn254::BN254Scalar, collections::BTreeMap, prelude::*, ristretto::RistrettoScalar,
+};
+use sha2::{Sha256, Sha512};
+
+/// The domain separator of the commitment to the seed of a genesis.
+const GENESIS_SEED_COMMITMENT_DOMAIN: &[u8] = b"Noah Genesis Seed Commitment";
+/// The domain separator of the randomness of the allocations of a genesis.
+const GENESIS_ALLOCATION_PRNG_DOMAIN: &[u8] = b"Noah Genesis Allocation";
+
+/// The kind of record of an allocation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenesisRecordKind {
+    /// An anonymous record.
+    Anonymous,
+    /// A blind asset record of the given confidentiality.
+    Blind(AssetRecordType),
+}
+
+/// An allocation of the genesis.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisAllocation {
+    /// The address of the owner.
+    pub address: PublicKey,
+    /// The amount.
+    pub amount: u64,
+    /// The asset type.
+    pub asset_type: AssetType,
+    /// The kind of record.
+    pub kind: GenesisRecordKind,
+}
+
+/// Parse the allocations, one per line as `address,amount,asset_type[,kind]`, where the address
+/// and the asset type are in base64, and the kind is `abar` (the default), `bar` for a fully
+/// confidential record, or `transparent`. Empty lines and lines starting with `#` are skipped.
+pub fn parse_allocations(spec: &str) -> Result<Vec<GenesisAllocation>> {
+    let mut allocations = vec![];
+    for line in spec.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = line.split(',').map(str::trim).collect_vec();
+        if fields.len() != 3 && fields.len() != 4 {
+            return Err(NoahError::DeserializationError);
+        }
+
+        let address = PublicKey::noah_from_bytes(&b64dec(fields[0])?)?;
+        let amount = fields[1]
+            .parse::<u64>()
+            .map_err(|_| NoahError::DeserializationError)?;
+        let asset_type = AssetType::noah_from_bytes(&b64dec(fields[2])?)?;
+        let kind = match fields.get(3).copied().unwrap_or("abar") {
+            "abar" => GenesisRecordKind::Anonymous,
+            "bar" => {
+                GenesisRecordKind::Blind(AssetRecordType::ConfidentialAmount_ConfidentialAssetType)
+            }
+            "transparent" => GenesisRecordKind::Blind(
+                AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
+            ),
+            _ => return Err(NoahError::DeserializationError),
+        };
+
+        allocations.push(GenesisAllocation {
+            address,
+            amount,
+            asset_type,
+            kind,
+        });
+    }
+    Ok(allocations)
+}
+
+/// A record of the genesis, with its owner memo.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenesisRecord {
+    /// An anonymous record.
+    Anonymous {
+        /// The record.
+        abar: AnonAssetRecord,
+        /// The owner memo.
+        memo: AxfrOwnerMemo,
+    },
+    /// A blind asset record.
+    Blind {
+        /// The record.
+        bar: BlindAssetRecord,
+        /// The owner memo, absent for a transparent record.
+        memo: Option<OwnerMemo>,
+    },
+}
+
+/// The opening of a record of the genesis.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenesisOpening {
+    /// The opening of an anonymous record.
+    Anonymous {
+        /// The blinding factor of the commitment.
+        blind: BN254Scalar,
+    },
+    /// The opening of a blind asset record.
+    Blind {
+        /// The blinding factors of the amount.
+        amount_blinds: (RistrettoScalar, RistrettoScalar),
+        /// The blinding factor of the asset type.
+        type_blind: RistrettoScalar,
+    },
+}
+
+/// The records of a genesis, to be included in the first block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisSet {
+    /// The identifier of the chain.
+    pub chain_id: String,
+    /// The commitment to the seed of the randomness.
+    pub seed_commitment: [u8; 32],
+    /// The records, in the order of the allocations.
+    pub records: Vec<GenesisRecord>,
+    /// The total amount of each asset type.
+    pub supply: Vec<(AssetType, u64)>,
+}
+
+/// The data kept in escrow for the audit of a genesis. The seed regenerates the whole genesis,
+/// so it is given to the auditors, or published once the allocations need not stay confidential.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisEscrow {
+    /// The seed of the randomness.
+    pub seed: [u8; 32],
+    /// The openings of the records, in the order of the allocations.
+    pub openings: Vec<GenesisOpening>,
+}
+
+/// Generate the records of the allocations for the chain, with the randomness derived from
+/// the seed, so that the same allocations and seed give the same genesis.
+pub fn generate_genesis(
+    chain_id: &str,
+    allocations: &[GenesisAllocation],
+    seed: &[u8; 32],
+) -> Result<(GenesisSet, GenesisEscrow)> {
+    let mut supply = BTreeMap::<AssetType, u64>::new();
+    let mut records = Vec::with_capacity(allocations.len());
+    let mut openings = Vec::with_capacity(allocations.len());
+
+    for (i, allocation) in allocations.iter().enumerate() {
+        let total = supply.entry(allocation.asset_type).or_insert(0);
+        *total = total
+            .checked_add(allocation.amount)
+            .ok_or(NoahError::ParameterError)?;
+
+        let mut hasher = Sha512::new();
+        hasher.update(GENESIS_ALLOCATION_PRNG_DOMAIN);
+        hasher.update((chain_id.len() as u64).to_le_bytes());
+        hasher.update(chain_id.as_bytes());
+        hasher.update(seed);
+        hasher.update((i as u64).to_le_bytes());
+        let mut prng = derive_prng_from_hash::<Sha512>(hasher);
+
+        match allocation.kind {
+            GenesisRecordKind::Anonymous => {
+                let oabar = OpenAnonAssetRecordBuilder::new()
+                    .amount(allocation.amount)
+                    .asset_type(allocation.asset_type)
+                    .pub_key(&allocation.address)
+                    .finalize(&mut prng)?
+                    .build()?;
+                records.push(GenesisRecord::Anonymous {
+                    abar: AnonAssetRecord::from_oabar(&oabar),
+                    memo: oabar.get_owner_memo().ok_or(NoahError::ParameterError)?,
+                });
+                openings.push(GenesisOpening::Anonymous {
+                    blind: oabar.get_blind(),
+                });
+            }
+            GenesisRecordKind::Blind(record_type) => {
+                let template = AssetRecordTemplate::with_no_asset_tracing(
+                    allocation.amount,
+                    allocation.asset_type,
+                    record_type,
+                    allocation.address,
+                );
+                let record = AssetRecord::from_template_no_identity_tracing(&mut prng, &template)?;
+                records.push(GenesisRecord::Blind {
+                    bar: record.open_asset_record.blind_asset_record,
+                    memo: record.owner_memo,
+                });
+                openings.push(GenesisOpening::Blind {
+                    amount_blinds: record.open_asset_record.amount_blinds,
+                    type_blind: record.open_asset_record.type_blind,
+                });
+            }
+        }
+    }
+
+    let genesis = GenesisSet {
+        chain_id: String::from(chain_id),
+        seed_commitment: seed_commitment(chain_id, seed),
+        records,
+        supply: supply.into_iter().collect(),
+    };
+    let escrow = GenesisEscrow {
+        seed: *seed,
+        openings,
+    };
+    Ok((genesis, escrow))
+}
+
+/// Audit a genesis against the allocations and the escrow, by checking that the escrowed seed
+/// is the committed one and that it regenerates the same records, supply, and openings.
+pub fn audit_genesis(
+    allocations: &[GenesisAllocation],
+    genesis: &GenesisSet,
+    escrow: &GenesisEscrow,
+) -> Result<()> {
+    if genesis.seed_commitment != seed_commitment(&genesis.chain_id, &escrow.seed) {
+        return Err(NoahError::VerificationError);
+    }
+    let (expected_genesis, expected_escrow) =
+        generate_genesis(&genesis.chain_id, allocations, &escrow.seed)?;
+    if expected_genesis != *genesis || expected_escrow != *escrow {
+        return Err(NoahError::VerificationError);
+    }
+    Ok(())
+}
+
+fn seed_commitment(chain_id: &str, seed: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(GENESIS_SEED_COMMITMENT_DOMAIN);
+    hasher.update((chain_id.len() as u64).to_le_bytes());
+    hasher.update(chain_id.as_bytes());
+    hasher.update(seed);
+    let mut commitment = [0u8; 32];
+    commitment.copy_from_slice(&hasher.finalize());
+    commitment
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anon_xfr::structs::OpenAnonAssetRecordBuilder;
+    use crate::genesis::{
+        audit_genesis, generate_genesis, parse_allocations, GenesisRecord, GenesisRecordKind,
+    };
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::xfr::{
+        asset_record::{open_blind_asset_record, AssetRecordType},
+        structs::AssetType,
+    };
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_genesis() {
+        let mut prng = test_rng();
+        let alice = KeyPair::sample(&mut prng, ED25519);
+        let bob = KeyPair::sample(&mut prng, SECP256K1);
+        let fra = AssetType::from_identical_byte(0);
+        let usd = AssetType::from_identical_byte(1);
+
+        let spec = format!(
+            "# address,amount,asset_type,kind\n{},100,{}\n\n{},200,{},bar\n{},300,{},transparent\n",
+            b64enc(&alice.get_pk().noah_to_bytes()),
+            b64enc(&fra.noah_to_bytes()),
+            b64enc(&bob.get_pk().noah_to_bytes()),
+            b64enc(&fra.noah_to_bytes()),
+            b64enc(&alice.get_pk().noah_to_bytes()),
+            b64enc(&usd.noah_to_bytes()),
+        );
+        let allocations = parse_allocations(&spec).unwrap();
+        assert_eq!(allocations.len(), 3);
+        assert_eq!(allocations[0].kind, GenesisRecordKind::Anonymous);
+        assert_eq!(
+            allocations[1].kind,
+            GenesisRecordKind::Blind(AssetRecordType::ConfidentialAmount_ConfidentialAssetType)
+        );
+        assert!(parse_allocations("a,b").is_err());
+        assert!(parse_allocations(&format!(
+            "{},1,{},nft",
+            b64enc(&alice.get_pk().noah_to_bytes()),
+            b64enc(&fra.noah_to_bytes())
+        ))
+        .is_err());
+
+        let seed = [7u8; 32];
+        let (genesis, escrow) = generate_genesis("test-chain", &allocations, &seed).unwrap();
+        assert_eq!(genesis.supply, vec![(fra, 300), (usd, 300)]);
+
+        // the genesis is reproducible from the seed, and different for another chain or seed
+        assert_eq!(
+            generate_genesis("test-chain", &allocations, &seed).unwrap(),
+            (genesis.clone(), escrow.clone())
+        );
+        assert_ne!(
+            generate_genesis("other-chain", &allocations, &seed)
+                .unwrap()
+                .0
+                .records,
+            genesis.records
+        );
+        audit_genesis(&allocations, &genesis, &escrow).unwrap();
+        let mut wrong_escrow = escrow.clone();
+        wrong_escrow.seed = [8u8; 32];
+        assert!(audit_genesis(&allocations, &genesis, &wrong_escrow).is_err());
+        assert!(audit_genesis(&allocations[1..], &genesis, &escrow).is_err());
+
+        // the owners open their records
+        match &genesis.records[0] {
+            GenesisRecord::Anonymous { abar, memo } => {
+                let oabar = OpenAnonAssetRecordBuilder::from_abar(abar, memo.clone(), &alice)
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                assert_eq!(oabar.get_amount(), 100);
+            }
+            _ => panic!("the first record is anonymous"),
+        }
+        match &genesis.records[1] {
+            GenesisRecord::Blind { bar, memo } => {
+                let oar = open_blind_asset_record(bar, memo, &bob).unwrap();
+                assert_eq!((oar.amount, oar.asset_type), (200, fra));
+            }
+            _ => panic!("the second record is blind"),
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{PublicKey, PublicKeyInner};
+use ed25519_dalek::PublicKey as Ed25519PublicKey;
+use libsecp256k1::PublicKey as Secp256k1PublicKey;
+use noah_algebra::prelude::*;
+use noah_crypto::hybrid_encryption::XPublicKey;
+
+/// The multicodec of the ed25519 public keys.
+pub const MULTICODEC_ED25519_PUB: u64 = 0xed;
+/// The multicodec of the compressed secp256k1 public keys.
+pub const MULTICODEC_SECP256K1_PUB: u64 = 0xe7;
+/// The multicodec of the x25519 public keys.
+pub const MULTICODEC_X25519_PUB: u64 = 0xec;
+
+/// The prefix of the `did:key` identifiers.
+pub const DID_KEY_PREFIX: &str = "did:key:";
+/// The multibase prefix of base58btc.
+const MULTIBASE_BASE58BTC: char = 'z';
+/// The alphabet of base58btc.
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The contexts of the DID documents.
+const DID_CONTEXTS: [&str; 2] = [
+    "https://www.w3.org/ns/did/v1",
+    "https://w3id.org/security/multikey/v1",
+];
+/// The type of the verification methods, which carry their key as a multibase fingerprint.
+const MULTIKEY_TYPE: &str = "Multikey";
+
+/// A public key with a stable fingerprint, the multibase (base58btc) encoding of its
+/// multicodec-prefixed bytes, as in `did:key`.
+pub trait KeyFingerprint {
+    /// Return the multicodec of the key and its bytes.
+    fn multicodec(&self) -> Result<(u64, Vec<u8>)>;
+
+    /// Return the fingerprint of the key.
+    fn fingerprint(&self) -> Result<String> {
+        let (codec, bytes) = self.multicodec()?;
+        let mut prefixed = encode_varint(codec);
+        prefixed.extend_from_slice(&bytes);
+
+        let mut res = String::new();
+        res.push(MULTIBASE_BASE58BTC);
+        res.push_str(&base58_encode(&prefixed));
+        Ok(res)
+    }
+
+    /// Return the `did:key` identifier of the key.
+    fn to_did_key(&self) -> Result<String> {
+        let mut res = String::from(DID_KEY_PREFIX);
+        res.push_str(&self.fingerprint()?);
+        Ok(res)
+    }
+}
+
+/// The fingerprints of the keys of transparent and anonymous transfers, which share a type.
+/// Ethereum addresses are hashes, not keys, and have no fingerprint.
+impl KeyFingerprint for PublicKey {
+    fn multicodec(&self) -> Result<(u64, Vec<u8>)> {
+        match self.inner() {
+            PublicKeyInner::Ed25519(pk) => Ok((MULTICODEC_ED25519_PUB, pk.as_bytes().to_vec())),
+            PublicKeyInner::Secp256k1(pk) => {
+                Ok((MULTICODEC_SECP256K1_PUB, pk.serialize_compressed().to_vec()))
+            }
+            PublicKeyInner::EthAddress(_) => Err(NoahError::ParameterError),
+        }
+    }
+}
+
+/// The fingerprints of the encryption keys of the asset tracers.
+impl KeyFingerprint for XPublicKey {
+    fn multicodec(&self) -> Result<(u64, Vec<u8>)> {
+        Ok((MULTICODEC_X25519_PUB, self.noah_to_bytes()))
+    }
+}
+
+/// Parse a fingerprint, or a `did:key` identifier, into its multicodec and its key bytes.
+pub fn parse_fingerprint(s: &str) -> Result<(u64, Vec<u8>)> {
+    let s = s.strip_prefix(DID_KEY_PREFIX).unwrap_or(s);
+    let encoded = s
+        .strip_prefix(MULTIBASE_BASE58BTC)
+        .ok_or(NoahError::DeserializationError)?;
+    let bytes = base58_decode(encoded)?;
+    let (codec, len) = decode_varint(&bytes)?;
+    Ok((codec, bytes[len..].to_vec()))
+}
+
+/// Parse the fingerprint of a key of transparent or anonymous transfers.
+pub fn public_key_from_fingerprint(s: &str) -> Result<PublicKey> {
+    match parse_fingerprint(s)? {
+        (MULTICODEC_ED25519_PUB, bytes) => Ok(PublicKey(PublicKeyInner::Ed25519(
+            Ed25519PublicKey::from_bytes(&bytes).map_err(|_| NoahError::DeserializationError)?,
+        ))),
+        (MULTICODEC_SECP256K1_PUB, bytes) => {
+            let mut pk_bytes = [0u8; 33];
+            if bytes.len() != pk_bytes.len() {
+                return Err(NoahError::DeserializationError);
+            }
+            pk_bytes.copy_from_slice(&bytes);
+            Ok(PublicKey(PublicKeyInner::Secp256k1(
+                Secp256k1PublicKey::parse_compressed(&pk_bytes)
+                    .map_err(|_| NoahError::DeserializationError)?,
+            )))
+        }
+        _ => Err(NoahError::DeserializationError),
+    }
+}
+
+/// A capability of a wallet, advertised in its DID document.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WalletCapability {
+    /// Transparent transfers.
+    TransparentTransfer,
+    /// Confidential transfers, with hidden amounts or asset types.
+    ConfidentialTransfer,
+    /// Anonymous transfers.
+    AnonymousTransfer,
+    /// The decryption of the tracing memos of an asset.
+    AssetTracing,
+}
+
+/// A verification method of a DID document.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct VerificationMethod {
+    /// The identifier, the DID of the document followed by the fingerprint as fragment.
+    pub id: String,
+    /// The type, always `Multikey`.
+    #[serde(rename = "type")]
+    pub method_type: String,
+    /// The DID of the controller.
+    pub controller: String,
+    /// The fingerprint of the key.
+    #[serde(rename = "publicKeyMultibase")]
+    pub public_key_multibase: String,
+}
+
+/// A DID document describing the public keys and the capabilities of a wallet, to be
+/// exported as JSON, e.g., with `serde_json`.
+///
+/// The DID is the `did:key` of the controlling key, so the document can be checked against
+/// its identifier, and the other keys are listed as additional verification methods.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DidDocument {
+    /// The JSON-LD contexts.
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    /// The DID.
+    pub id: String,
+    /// The keys.
+    #[serde(rename = "verificationMethod")]
+    pub verification_method: Vec<VerificationMethod>,
+    /// The keys that sign transfers.
+    pub authentication: Vec<String>,
+    /// The keys that sign statements, e.g., credentials.
+    #[serde(rename = "assertionMethod")]
+    pub assertion_method: Vec<String>,
+    /// The keys that receive encrypted memos.
+    #[serde(rename = "keyAgreement")]
+    pub key_agreement: Vec<String>,
+    /// The capabilities of the wallet.
+    pub capabilities: Vec<WalletCapability>,
+}
+
+impl DidDocument {
+    /// Create the document of a wallet controlled by the key, able to make transparent and
+    /// confidential transfers.
+    pub fn new(controller: &PublicKey) -> Result<Self> {
+        let mut doc = DidDocument {
+            context: DID_CONTEXTS.iter().map(|c| String::from(*c)).collect(),
+            id: controller.to_did_key()?,
+            verification_method: vec![],
+            authentication: vec![],
+            assertion_method: vec![],
+            key_agreement: vec![],
+            capabilities: vec![
+                WalletCapability::TransparentTransfer,
+                WalletCapability::ConfidentialTransfer,
+            ],
+        };
+        let id = doc.add_verification_method(controller)?;
+        doc.authentication.push(id.clone());
+        doc.assertion_method.push(id);
+        doc.add_memo_key(controller)?;
+        Ok(doc)
+    }
+
+    /// Add the key of the anonymous transfers of the wallet.
+    pub fn with_anonymous_key(mut self, pk: &PublicKey) -> Result<Self> {
+        let id = self.add_verification_method(pk)?;
+        if !self.authentication.contains(&id) {
+            self.authentication.push(id);
+        }
+        self.add_memo_key(pk)?;
+        self.add_capability(WalletCapability::AnonymousTransfer);
+        Ok(self)
+    }
+
+    /// Add the encryption key of an asset tracer held by the wallet.
+    pub fn with_tracer_key(mut self, key: &XPublicKey) -> Result<Self> {
+        let id = self.add_verification_method(key)?;
+        self.key_agreement.push(id);
+        self.add_capability(WalletCapability::AssetTracing);
+        Ok(self)
+    }
+
+    /// Add a verification method for the key, unless listed already, and return its identifier.
+    fn add_verification_method<K: KeyFingerprint>(&mut self, key: &K) -> Result<String> {
+        let fingerprint = key.fingerprint()?;
+        let mut id = self.id.clone();
+        id.push('#');
+        id.push_str(&fingerprint);
+
+        if !self.verification_method.iter().any(|m| m.id == id) {
+            self.verification_method.push(VerificationMethod {
+                id: id.clone(),
+                method_type: String::from(MULTIKEY_TYPE),
+                controller: self.id.clone(),
+                public_key_multibase: fingerprint,
+            });
+        }
+        Ok(id)
+    }
+
+    /// Add the x25519 key of the owner memos of an ed25519 key; secp256k1 keys have none.
+    fn add_memo_key(&mut self, pk: &PublicKey) -> Result<()> {
+        if let PublicKeyInner::Ed25519(_) = pk.inner() {
+            let x_pk = XPublicKey::noah_from_bytes(&pk.to_hpke_public_key()?)?;
+            let id = self.add_verification_method(&x_pk)?;
+            if !self.key_agreement.contains(&id) {
+                self.key_agreement.push(id);
+            }
+        }
+        Ok(())
+    }
+
+    fn add_capability(&mut self, capability: WalletCapability) {
+        if !self.capabilities.contains(&capability) {
+            self.capabilities.push(capability);
+        }
+    }
+}
+
+/// Encode an unsigned varint, as used by multicodec.
+fn encode_varint(mut x: u64) -> Vec<u8> {
+    let mut res = vec![];
+    while x >= 0x80 {
+        res.push((x as u8 & 0x7f) | 0x80);
+        x >>= 7;
+    }
+    res.push(x as u8);
+    res
+}
+
+/// Decode an unsigned varint, and return it with the number of bytes read.
+fn decode_varint(bytes: &[u8]) -> Result<(u64, usize)> {
+    let mut x = 0u64;
+    for (i, b) in bytes.iter().take(9).enumerate() {
+        x |= ((b & 0x7f) as u64) << (7 * i);
+        if b & 0x80 == 0 {
+            return Ok((x, i + 1));
+        }
+    }
+    Err(NoahError::DeserializationError)
+}
+
+fn base58_encode(bytes: &[u8]) -> String {
+    // the digits, in base 58, least significant first
+    let mut digits: Vec<u8> = vec![];
+    for b in bytes {
+        let mut carry = *b as u32;
+        for d in digits.iter_mut() {
+            carry += (*d as u32) << 8;
+            *d = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    let mut res = String::with_capacity(zeros + digits.len());
+    for _ in 0..zeros {
+        res.push(BASE58_ALPHABET[0] as char);
+    }
+    for d in digits.iter().rev() {
+        res.push(BASE58_ALPHABET[*d as usize] as char);
+    }
+    res
+}
+
+fn base58_decode(s: &str) -> Result<Vec<u8>> {
+    // the bytes, least significant first
+    let mut bytes: Vec<u8> = vec![];
+    for c in s.bytes() {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .ok_or(NoahError::DeserializationError)? as u32;
+        for b in bytes.iter_mut() {
+            carry += (*b as u32) * 58;
+            *b = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let zeros = s.bytes().take_while(|c| *c == BASE58_ALPHABET[0]).count();
+    let mut res = vec![0u8; zeros];
+    res.extend(bytes.iter().rev());
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::identity::{
+        base58_decode, base58_encode, parse_fingerprint, public_key_from_fingerprint, DidDocument,
+        KeyFingerprint, WalletCapability, MULTICODEC_X25519_PUB,
+    };
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use noah_algebra::prelude::*;
+    use noah_crypto::hybrid_encryption::{XPublicKey, XSecretKey};
+
+    #[test]
+    fn test_base58() {
+        assert_eq!(base58_encode(b"hello world"), "StV1DL6CwTryKyV");
+        assert_eq!(base58_encode(&[0, 0, 1]), "112");
+        assert_eq!(base58_decode("StV1DL6CwTryKyV").unwrap(), b"hello world");
+        assert_eq!(base58_decode("112").unwrap(), vec![0, 0, 1]);
+        assert!(base58_decode("0OIl").is_err());
+    }
+
+    #[test]
+    fn test_fingerprints() {
+        let mut prng = test_rng();
+        let ed25519 = KeyPair::sample(&mut prng, ED25519).get_pk();
+        let secp256k1 = KeyPair::sample(&mut prng, SECP256K1).get_pk();
+        let x25519 = XPublicKey::from(&XSecretKey::new(&mut prng));
+
+        // the prefixes of the multicodecs show in the fingerprints
+        assert!(ed25519.fingerprint().unwrap().starts_with("z6Mk"));
+        assert!(secp256k1.fingerprint().unwrap().starts_with("zQ3s"));
+        assert!(x25519.fingerprint().unwrap().starts_with("z6LS"));
+
+        for pk in [ed25519, secp256k1] {
+            assert_eq!(
+                public_key_from_fingerprint(&pk.fingerprint().unwrap()).unwrap(),
+                pk
+            );
+            assert_eq!(
+                public_key_from_fingerprint(&pk.to_did_key().unwrap()).unwrap(),
+                pk
+            );
+        }
+        assert_eq!(
+            parse_fingerprint(&x25519.fingerprint().unwrap()).unwrap(),
+            (MULTICODEC_X25519_PUB, x25519.noah_to_bytes())
+        );
+        assert!(public_key_from_fingerprint(&x25519.fingerprint().unwrap()).is_err());
+
+        let address = KeyPair::sample_address(&mut prng).get_pk();
+        assert!(address.fingerprint().is_err());
+    }
+
+    #[test]
+    fn test_did_document() {
+        let mut prng = test_rng();
+        let controller = KeyPair::sample(&mut prng, SECP256K1).get_pk();
+        let anon = KeyPair::sample(&mut prng, ED25519).get_pk();
+        let tracer = XPublicKey::from(&XSecretKey::new(&mut prng));
+
+        let doc = DidDocument::new(&controller)
+            .unwrap()
+            .with_anonymous_key(&anon)
+            .unwrap()
+            .with_tracer_key(&tracer)
+            .unwrap();
+        assert_eq!(doc.id, controller.to_did_key().unwrap());
+        // the controller, the anonymous key, its memo key, and the tracer key
+        assert_eq!(doc.verification_method.len(), 4);
+        assert_eq!(doc.authentication.len(), 2);
+        assert_eq!(doc.key_agreement.len(), 2);
+        assert!(doc
+            .capabilities
+            .contains(&WalletCapability::AnonymousTransfer));
+        assert!(doc.capabilities.contains(&WalletCapability::AssetTracing));
+
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["@context"][0], "https://www.w3.org/ns/did/v1");
+        assert_eq!(json["verificationMethod"][0]["type"], "Multikey");
+        assert_eq!(
+            json["verificationMethod"][1]["publicKeyMultibase"],
+            anon.fingerprint().unwrap().as_str()
+        );
+        assert_eq!(json["capabilities"][2], "anonymousTransfer");
+        let doc2: DidDocument = serde_json::from_value(json).unwrap();
+        assert_eq!(doc, doc2);
+    }
+}
@@ -0,0 +1,608 @@
+use crate::anon_xfr::{
+    abar_to_abar::AXfrNote, abar_to_ar::AbarToArNote, abar_to_bar::AbarToBarNote,
+    ar_to_abar::ArToAbarNote, bar_to_abar::BarToAbarNote, structs::AnonAssetRecord, AXfrPlonkPf,
+};
+use crate::consensus::{
+    decode_note, NOTE_KIND_ABAR_TO_ABAR, NOTE_KIND_ABAR_TO_AR, NOTE_KIND_ABAR_TO_BAR,
+    NOTE_KIND_AR_TO_ABAR, NOTE_KIND_BAR_TO_ABAR, NOTE_KIND_XFR,
+};
+use crate::errors::{NoahError, Result};
+use crate::xfr::structs::{BlindAssetRecord, XfrNote};
+use core::fmt;
+use noah_algebra::prelude::*;
+
+/// The kind of a confidential record, i.e., `BlindAssetRecord`.
+pub const DESCRIBE_KIND_BLIND_ASSET_RECORD: u32 = 101;
+/// The kind of an anonymous record, i.e., `AnonAssetRecord`.
+pub const DESCRIBE_KIND_ANON_ASSET_RECORD: u32 = 102;
+/// The kind of the Plonk proof of the anonymous notes.
+pub const DESCRIBE_KIND_PLONK_PROOF: u32 = 103;
+
+/// The maximal nesting of the values, beyond which the bytes are reported as malformed.
+const MAX_DESCRIBE_DEPTH: usize = 64;
+/// The number of bytes of a binary or string value shown in the description.
+const MAX_SHOWN_BYTES: usize = 32;
+
+/// The names of the fields of a structure, which `rmp_serde` serializes as an array.
+#[derive(Clone, Copy)]
+enum Schema {
+    Any,
+    Struct(&'static [(&'static str, Schema)]),
+    Seq(&'static Schema),
+}
+
+const BAR: Schema = Schema::Struct(&[
+    ("amount", Schema::Any),
+    ("asset_type", Schema::Any),
+    ("public_key", Schema::Any),
+]);
+const ABAR: Schema = Schema::Struct(&[("commitment", Schema::Any)]);
+const PLONK_PROOF: Schema = Schema::Struct(&[
+    ("cm_w_vec", Schema::Any),
+    ("cm_t_vec", Schema::Any),
+    ("cm_z", Schema::Any),
+    ("prk_3_poly_eval_zeta", Schema::Any),
+    ("prk_4_poly_eval_zeta", Schema::Any),
+    ("w_polys_eval_zeta", Schema::Any),
+    ("w_polys_eval_zeta_omega", Schema::Any),
+    ("z_eval_zeta_omega", Schema::Any),
+    ("s_polys_eval_zeta", Schema::Any),
+    ("opening_witness_zeta", Schema::Any),
+    ("opening_witness_zeta_omega", Schema::Any),
+]);
+
+const XFR_NOTE: Schema = Schema::Struct(&[
+    (
+        "body",
+        Schema::Struct(&[
+            ("inputs", Schema::Seq(&BAR)),
+            ("outputs", Schema::Seq(&BAR)),
+            ("proofs", Schema::Any),
+            ("asset_tracing_memos", Schema::Any),
+            ("owners_memos", Schema::Any),
+        ]),
+    ),
+    ("multisig", Schema::Any),
+]);
+const AXFR_NOTE: Schema = Schema::Struct(&[
+    (
+        "body",
+        Schema::Struct(&[
+            ("inputs", Schema::Any),
+            ("outputs", Schema::Seq(&ABAR)),
+            ("merkle_root", Schema::Any),
+            ("merkle_root_version", Schema::Any),
+            ("fee", Schema::Any),
+            ("owner_memos", Schema::Any),
+            ("tracer_memos", Schema::Any),
+            ("output_payloads", Schema::Any),
+        ]),
+    ),
+    ("proof", PLONK_PROOF),
+    ("folding_instance", Schema::Any),
+]);
+const AR_TO_ABAR_NOTE: Schema = Schema::Struct(&[
+    (
+        "body",
+        Schema::Struct(&[
+            ("input", BAR),
+            ("output", ABAR),
+            ("proof", PLONK_PROOF),
+            ("memo", Schema::Any),
+        ]),
+    ),
+    ("signature", Schema::Any),
+]);
+const BAR_TO_ABAR_NOTE: Schema = Schema::Struct(&[
+    (
+        "body",
+        Schema::Struct(&[
+            ("input", BAR),
+            ("output", ABAR),
+            (
+                "proof",
+                Schema::Struct(&[
+                    ("delegated_schnorr_proof", Schema::Any),
+                    ("plonk_proof", PLONK_PROOF),
+                ]),
+            ),
+            ("memo", Schema::Any),
+        ]),
+    ),
+    ("signature", Schema::Any),
+]);
+const ABAR_TO_AR_NOTE: Schema = Schema::Struct(&[
+    (
+        "body",
+        Schema::Struct(&[
+            ("input", Schema::Any),
+            ("output", BAR),
+            ("merkle_root", Schema::Any),
+            ("merkle_root_version", Schema::Any),
+            ("memo", Schema::Any),
+        ]),
+    ),
+    ("proof", PLONK_PROOF),
+    ("folding_instance", Schema::Any),
+]);
+const ABAR_TO_BAR_NOTE: Schema = Schema::Struct(&[
+    (
+        "body",
+        Schema::Struct(&[
+            ("input", Schema::Any),
+            ("output", BAR),
+            ("delegated_schnorr_proof", Schema::Any),
+            ("merkle_root", Schema::Any),
+            ("merkle_root_version", Schema::Any),
+            ("memo", Schema::Any),
+        ]),
+    ),
+    ("proof", PLONK_PROOF),
+    ("folding_instance", Schema::Any),
+]);
+
+/// A value of the MessagePack serialization, at its offset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DescribedField {
+    /// The offset of the value, including its header.
+    pub offset: usize,
+    /// The length of the value, including its header and, for arrays and maps, their elements.
+    pub len: usize,
+    /// The nesting of the value.
+    pub depth: usize,
+    /// The path of the value, e.g., `body.outputs[0].commitment`.
+    pub path: String,
+    /// The type and the content of the value, e.g., `uint 7` or `array(3)`.
+    pub value: String,
+}
+
+/// The place where the serialization stops being valid MessagePack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MalformedAt {
+    /// The offset of the first invalid byte.
+    pub offset: usize,
+    /// The path of the value that is invalid.
+    pub path: String,
+    /// What is wrong at the offset.
+    pub reason: &'static str,
+}
+
+/// The annotated layout of serialized bytes, as returned by `describe_bytes`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BytesDescription {
+    /// The kind of the bytes.
+    pub kind: u32,
+    /// The name of the type of the kind.
+    pub kind_name: &'static str,
+    /// The length of the bytes.
+    pub len: usize,
+    /// The values, in the order of their offsets.
+    pub fields: Vec<DescribedField>,
+    /// Where the bytes are malformed, if they are, in which case the fields stop there.
+    pub malformed: Option<MalformedAt>,
+    /// Whether the bytes decode as the type of the kind, which well-formed MessagePack
+    /// with the wrong shape or invalid group elements does not.
+    pub decodes: bool,
+}
+
+impl BytesDescription {
+    /// Return the path, or the name of the kind for the top-level value.
+    fn name_of<'a>(&'a self, path: &'a str) -> &'a str {
+        if path.is_empty() {
+            self.kind_name
+        } else {
+            path
+        }
+    }
+}
+
+impl fmt::Display for BytesDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} (kind {}), {} bytes, {}",
+            self.kind_name,
+            self.kind,
+            self.len,
+            if self.decodes {
+                "decodes"
+            } else {
+                "does not decode"
+            }
+        )?;
+        for field in self.fields.iter() {
+            writeln!(
+                f,
+                "{:08x} {:>8}  {:indent$}{}: {}",
+                field.offset,
+                field.len,
+                "",
+                self.name_of(&field.path),
+                field.value,
+                indent = 2 * field.depth
+            )?;
+        }
+        if let Some(malformed) = &self.malformed {
+            writeln!(
+                f,
+                "{:08x} malformed at {}: {}",
+                malformed.offset,
+                self.name_of(&malformed.path),
+                malformed.reason
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Describe the MessagePack serialization of a note, one of the `NOTE_KIND_*` kinds, a record,
+/// or a proof, one of the `DESCRIBE_KIND_*` kinds, with the offset, length, and path of each
+/// value. Malformed bytes are described up to the first invalid byte, which is reported.
+pub fn describe_bytes(kind: u32, bytes: &[u8]) -> Result<BytesDescription> {
+    let (kind_name, schema, decodes) = match kind {
+        NOTE_KIND_XFR => ("XfrNote", XFR_NOTE, decode_note::<XfrNote>(bytes).is_ok()),
+        NOTE_KIND_ABAR_TO_ABAR => (
+            "AXfrNote",
+            AXFR_NOTE,
+            decode_note::<AXfrNote>(bytes).is_ok(),
+        ),
+        NOTE_KIND_AR_TO_ABAR => (
+            "ArToAbarNote",
+            AR_TO_ABAR_NOTE,
+            decode_note::<ArToAbarNote>(bytes).is_ok(),
+        ),
+        NOTE_KIND_BAR_TO_ABAR => (
+            "BarToAbarNote",
+            BAR_TO_ABAR_NOTE,
+            decode_note::<BarToAbarNote>(bytes).is_ok(),
+        ),
+        NOTE_KIND_ABAR_TO_AR => (
+            "AbarToArNote",
+            ABAR_TO_AR_NOTE,
+            decode_note::<AbarToArNote>(bytes).is_ok(),
+        ),
+        NOTE_KIND_ABAR_TO_BAR => (
+            "AbarToBarNote",
+            ABAR_TO_BAR_NOTE,
+            decode_note::<AbarToBarNote>(bytes).is_ok(),
+        ),
+        DESCRIBE_KIND_BLIND_ASSET_RECORD => (
+            "BlindAssetRecord",
+            BAR,
+            decode_note::<BlindAssetRecord>(bytes).is_ok(),
+        ),
+        DESCRIBE_KIND_ANON_ASSET_RECORD => (
+            "AnonAssetRecord",
+            ABAR,
+            decode_note::<AnonAssetRecord>(bytes).is_ok(),
+        ),
+        DESCRIBE_KIND_PLONK_PROOF => (
+            "PlonkProof",
+            PLONK_PROOF,
+            decode_note::<AXfrPlonkPf>(bytes).is_ok(),
+        ),
+        _ => return Err(NoahError::ParameterError),
+    };
+
+    let mut walker = Walker {
+        bytes,
+        pos: 0,
+        fields: vec![],
+    };
+    let mut malformed =
+        walker
+            .walk(schema, String::new(), 0)
+            .err()
+            .map(|(offset, path, reason)| MalformedAt {
+                offset,
+                path,
+                reason,
+            });
+    if malformed.is_none() && walker.pos < bytes.len() {
+        malformed = Some(MalformedAt {
+            offset: walker.pos,
+            path: String::new(),
+            reason: "trailing bytes",
+        });
+    }
+
+    Ok(BytesDescription {
+        kind,
+        kind_name,
+        len: bytes.len(),
+        fields: walker.fields,
+        malformed,
+        decodes,
+    })
+}
+
+/// Dump the bytes in hex, sixteen per line, with the offset of each line and the printable
+/// characters, to be read alongside the offsets of `describe_bytes`.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (i, line) in bytes.chunks(16).enumerate() {
+        let hex = line.iter().map(|b| format!("{:02x}", b)).join(" ");
+        let ascii = line
+            .iter()
+            .map(|b| {
+                if b.is_ascii_graphic() || *b == b' ' {
+                    *b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+        out.push_str(&format!("{:08x}  {:<47}  |{}|\n", i * 16, hex, ascii));
+    }
+    out
+}
+
+/// A walker of MessagePack values, which records each value it reads.
+struct Walker<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    fields: Vec<DescribedField>,
+}
+
+/// The offset and the path of the malformed value, and what is wrong with it.
+type WalkError = (usize, String, &'static str);
+
+impl<'a> Walker<'a> {
+    fn take(&mut self, n: usize, path: &str) -> core::result::Result<&'a [u8], WalkError> {
+        if self.bytes.len() - self.pos < n {
+            return Err((self.pos, path.to_owned(), "unexpected end of bytes"));
+        }
+        let taken = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(taken)
+    }
+
+    fn read_uint(&mut self, n: usize, path: &str) -> core::result::Result<u64, WalkError> {
+        let taken = self.take(n, path)?;
+        Ok(taken.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+    }
+
+    fn walk(
+        &mut self,
+        schema: Schema,
+        path: String,
+        depth: usize,
+    ) -> core::result::Result<(), WalkError> {
+        if depth > MAX_DESCRIBE_DEPTH {
+            return Err((self.pos, path, "too deeply nested"));
+        }
+
+        let offset = self.pos;
+        let marker = self.take(1, &path)?[0];
+        let (value, container) = match marker {
+            0x00..=0x7f => (format!("uint {}", marker), None),
+            0xe0..=0xff => (format!("int {}", marker as i8), None),
+            0x80..=0x8f => (String::new(), Some((true, (marker & 0x0f) as u64))),
+            0x90..=0x9f => (String::new(), Some((false, (marker & 0x0f) as u64))),
+            0xa0..=0xbf => (self.read_str((marker & 0x1f) as usize, &path)?, None),
+            0xc0 => ("nil".to_owned(), None),
+            0xc1 => return Err((offset, path, "invalid marker 0xc1")),
+            0xc2 => ("false".to_owned(), None),
+            0xc3 => ("true".to_owned(), None),
+            0xc4..=0xc6 => {
+                let n = self.read_uint(1 << (marker - 0xc4), &path)? as usize;
+                (self.read_bin(n, &path)?, None)
+            }
+            0xc7..=0xc9 => {
+                let n = self.read_uint(1 << (marker - 0xc7), &path)? as usize;
+                (self.read_ext(n, &path)?, None)
+            }
+            0xca => (
+                format!("f32 {}", f32::from_bits(self.read_uint(4, &path)? as u32)),
+                None,
+            ),
+            0xcb => (
+                format!("f64 {}", f64::from_bits(self.read_uint(8, &path)?)),
+                None,
+            ),
+            0xcc..=0xcf => (
+                format!("uint {}", self.read_uint(1 << (marker - 0xcc), &path)?),
+                None,
+            ),
+            0xd0..=0xd3 => {
+                let n = 1 << (marker - 0xd0);
+                let v = self.read_uint(n, &path)?;
+                // sign-extend the big-endian integer of n bytes
+                let shift = 64 - 8 * n as u32;
+                (format!("int {}", ((v << shift) as i64) >> shift), None)
+            }
+            0xd4..=0xd8 => (self.read_ext(1 << (marker - 0xd4), &path)?, None),
+            0xd9..=0xdb => {
+                let n = self.read_uint(1 << (marker - 0xd9), &path)? as usize;
+                (self.read_str(n, &path)?, None)
+            }
+            0xdc | 0xdd => {
+                let n = self.read_uint(2 << (marker - 0xdc), &path)?;
+                (String::new(), Some((false, n)))
+            }
+            0xde | 0xdf => {
+                let n = self.read_uint(2 << (marker - 0xde), &path)?;
+                (String::new(), Some((true, n)))
+            }
+        };
+
+        let index = self.fields.len();
+        self.fields.push(DescribedField {
+            offset,
+            len: self.pos - offset,
+            depth,
+            path: path.clone(),
+            value,
+        });
+
+        if let Some((is_map, count)) = container {
+            self.fields[index].value = if is_map {
+                format!("map({})", count)
+            } else {
+                format!("array({})", count)
+            };
+            // each element takes at least one byte, so a malformed count ends the walk
+            for i in 0..count {
+                if is_map {
+                    let key_path = format!("{}{{key {}}}", path, i);
+                    self.walk(Schema::Any, key_path, depth + 1)?;
+                    let key = &self.fields[self.fields.len() - 1];
+                    let name = if key.value.starts_with("uint ") || key.value.starts_with("str ") {
+                        key.value.split_once(' ').map(|(_, k)| k.to_owned())
+                    } else {
+                        None
+                    };
+                    let value_path = match name {
+                        Some(name) => child_path(&path, &name),
+                        None => format!("{}{{value {}}}", path, i),
+                    };
+                    self.walk(Schema::Any, value_path, depth + 1)?;
+                } else {
+                    let (name, child) = match schema {
+                        Schema::Struct(names) if (i as usize) < names.len() => {
+                            (child_path(&path, names[i as usize].0), names[i as usize].1)
+                        }
+                        Schema::Seq(child) => (format!("{}[{}]", path, i), *child),
+                        _ => (format!("{}[{}]", path, i), Schema::Any),
+                    };
+                    self.walk(child, name, depth + 1)?;
+                }
+            }
+            self.fields[index].len = self.pos - offset;
+        }
+        Ok(())
+    }
+
+    fn read_bin(&mut self, n: usize, path: &str) -> core::result::Result<String, WalkError> {
+        let taken = self.take(n, path)?;
+        Ok(format!("bin({}) {}", n, shown_hex(taken)))
+    }
+
+    fn read_ext(&mut self, n: usize, path: &str) -> core::result::Result<String, WalkError> {
+        let ext_type = self.take(1, path)?[0] as i8;
+        let taken = self.take(n, path)?;
+        Ok(format!(
+            "ext({}, type {}) {}",
+            n,
+            ext_type,
+            shown_hex(taken)
+        ))
+    }
+
+    fn read_str(&mut self, n: usize, path: &str) -> core::result::Result<String, WalkError> {
+        let taken = self.take(n, path)?;
+        match core::str::from_utf8(taken) {
+            Ok(s) if n <= MAX_SHOWN_BYTES => Ok(format!("str {}", s)),
+            Ok(s) => {
+                let end = (0..=MAX_SHOWN_BYTES)
+                    .rev()
+                    .find(|i| s.is_char_boundary(*i))
+                    .unwrap_or(0);
+                Ok(format!("str({}) {}..", n, &s[..end]))
+            }
+            Err(_) => Ok(format!("str({}) invalid utf-8 {}", n, shown_hex(taken))),
+        }
+    }
+}
+
+/// Return the path of a named child, without a leading dot at the top level.
+fn child_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// Return the hex of the first bytes of a value.
+fn shown_hex(bytes: &[u8]) -> String {
+    let shown = bytes[..bytes.len().min(MAX_SHOWN_BYTES)]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    if bytes.len() > MAX_SHOWN_BYTES {
+        format!("{}..", shown)
+    } else {
+        shown
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::consensus::NOTE_KIND_XFR;
+    use crate::inspect::{describe_bytes, hex_dump, DESCRIBE_KIND_BLIND_ASSET_RECORD};
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::SECP256K1;
+    use crate::xfr::{
+        asset_record::AssetRecordType,
+        gen_xfr_note,
+        structs::{AssetRecord, AssetRecordTemplate, AssetType},
+    };
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_describe_bytes() {
+        let mut prng = test_rng();
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::ConfidentialAmount_NonConfidentialAssetType;
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let input = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let bar = output.open_asset_record.blind_asset_record.clone();
+        let note = gen_xfr_note(&mut prng, &[input], &[output], &[&sender]).unwrap();
+        let bytes = rmp_serde::to_vec(&note).unwrap();
+
+        let description = describe_bytes(NOTE_KIND_XFR, &bytes).unwrap();
+        assert!(description.decodes);
+        assert!(description.malformed.is_none());
+        assert_eq!(description.fields[0].offset, 0);
+        assert_eq!(description.fields[0].len, bytes.len());
+        for path in ["body", "body.outputs[0].public_key", "multisig"] {
+            assert!(description.fields.iter().any(|field| field.path == path));
+        }
+        let field = description
+            .fields
+            .iter()
+            .find(|field| field.path == "body.inputs")
+            .unwrap();
+        assert_eq!(field.value, "array(1)");
+        assert!(description.to_string().contains("body.outputs[0].amount"));
+
+        // the offsets of a record are the same as within the note
+        let record_bytes = rmp_serde::to_vec(&bar).unwrap();
+        let description = describe_bytes(DESCRIBE_KIND_BLIND_ASSET_RECORD, &record_bytes).unwrap();
+        assert!(description.decodes);
+        assert_eq!(description.fields[0].len, record_bytes.len());
+
+        // malformed bytes are described up to the first invalid byte
+        let description = describe_bytes(NOTE_KIND_XFR, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(!description.decodes);
+        let malformed = description.malformed.unwrap();
+        assert_eq!(malformed.reason, "unexpected end of bytes");
+        assert!(malformed.path.starts_with("multisig"));
+
+        let mut longer_bytes = bytes.clone();
+        longer_bytes.push(0xc1);
+        let description = describe_bytes(NOTE_KIND_XFR, &longer_bytes).unwrap();
+        assert!(!description.decodes);
+        assert_eq!(description.malformed.unwrap().offset, bytes.len());
+
+        let description = describe_bytes(NOTE_KIND_XFR, &[0x92, 0xc1]).unwrap();
+        let malformed = description.malformed.unwrap();
+        assert_eq!((malformed.offset, malformed.path.as_str()), (1, "body"));
+        assert_eq!(malformed.reason, "invalid marker 0xc1");
+
+        assert!(describe_bytes(0, &bytes).is_err());
+
+        let dump = hex_dump(&bytes);
+        assert_eq!(dump.lines().count(), (bytes.len() + 15) / 16);
+        assert!(dump.starts_with("00000000  "));
+    }
+}
@@ -1,6 +1,8 @@
+use crate::canonical::CanonicalSerialize;
 use crate::errors::{NoahError, Result};
-use crate::parameters::params::AddressFormat;
-use crate::parameters::params::AddressFormat::{ED25519, SECP256K1};
+use crate::parameters::AddressFormat;
+use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+use crate::serialization::to_bincode;
 use ark_ff::{BigInteger, PrimeField};
 use ark_std::borrow::ToOwned;
 use curve25519_dalek::edwards::CompressedEdwardsY;
@@ -25,7 +27,9 @@ use noah_algebra::{
     secp256k1::{SECP256K1Scalar, SECP256K1G1},
 };
 use serde::Serialize;
+use sha2::Sha512;
 use sha3::Keccak256;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
 /// The length of the secret key.
@@ -70,7 +74,7 @@ impl KeyType {
 }
 
 #[derive(Clone, Copy, Debug)]
-#[wasm_bindgen]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 /// The public key wrapper for anon/confidential transfer, for WASM compatability.
 pub struct PublicKey(pub(crate) PublicKeyInner);
 
@@ -193,6 +197,33 @@ impl PublicKey {
         }
     }
 
+    /// Return the ETH address of the secp256k1 key, in the checksummed format of EIP-55.
+    pub fn to_eth_address_string(&self) -> Result<String> {
+        match self.to_eth_address()?.inner() {
+            PublicKeyInner::EthAddress(address) => Ok(eth_address_to_checksum_string(address)),
+            _ => Err(NoahError::ParameterError),
+        }
+    }
+
+    /// Parse an ETH address in the hexadecimal format, e.g., `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed`.
+    pub fn from_eth_address_str(s: &str) -> Result<Self> {
+        Ok(PublicKey(PublicKeyInner::EthAddress(eth_address_from_str(
+            s,
+        )?)))
+    }
+
+    /// Return the X25519 form of the ed25519 key, as the recipient key of HPKE of RFC 9180.
+    pub fn to_hpke_public_key(&self) -> Result<[u8; 32]> {
+        match self.inner() {
+            PublicKeyInner::Ed25519(pk) => Ok(CompressedEdwardsY(pk.to_bytes())
+                .decompress()
+                .ok_or(NoahError::DecompressElementError)?
+                .to_montgomery()
+                .to_bytes()),
+            _ => Err(NoahError::ParameterError),
+        }
+    }
+
     /// Change to algebra secp256k1 Point
     pub fn to_secp256k1(&self) -> Result<SECP256K1G1> {
         match self.inner() {
@@ -412,6 +443,19 @@ impl SecretKey {
         }
     }
 
+    /// Return the X25519 secret key of the ed25519 key, i.e., the clamped secret scalar,
+    /// which opens the HPKE ciphertexts to the key of `PublicKey::to_hpke_public_key`.
+    pub fn to_hpke_secret_key(&self) -> Result<[u8; 32]> {
+        match self {
+            SecretKey::Ed25519(sk) => {
+                let mut res = [0u8; 32];
+                res.copy_from_slice(&ExpandedSecretKey::from(sk).to_bytes()[..32]);
+                Ok(res)
+            }
+            _ => Err(NoahError::ParameterError),
+        }
+    }
+
     /// Change to algebra secp256k1 Point
     pub fn to_secp256k1(&self) -> Result<SECP256K1Scalar> {
         match self {
@@ -515,7 +559,7 @@ impl SecretKey {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-#[wasm_bindgen]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 /// The keypair for confidential transfer.
 pub struct KeyPair {
     /// The public key.
@@ -755,19 +799,138 @@ impl SignatureList {
 
     /// Verify a list of signature.
     pub fn verify(&self, pubkeys: &[&PublicKey], message: &[u8]) -> Result<()> {
+        for (pk, sig) in self.pair_with(pubkeys)? {
+            pk.verify(message, sig)?;
+        }
+        Ok(())
+    }
+
+    /// Pair each signature with the public key that should have produced it.
+    pub(crate) fn pair_with<'a>(
+        &'a self,
+        pubkeys: &[&'a PublicKey],
+    ) -> Result<Vec<(&'a PublicKey, &'a Signature)>> {
         if pubkeys.len() != self.signatures.len() {
             return Err(NoahError::SignatureError);
         }
         // sort the key pairs based on alphabetical order of their public keys
         let mut sorted = pubkeys.to_owned();
         sorted.sort_unstable_by_key(|k| k.noah_to_bytes());
-        for (pk, sig) in sorted.iter().zip(self.signatures.iter()) {
-            pk.verify(message, sig)?;
+        Ok(sorted.into_iter().zip(self.signatures.iter()).collect())
+    }
+}
+
+/// A note body whose signature can be computed outside of the host, e.g., by a hardware wallet,
+/// over a stable encoding of the body.
+pub trait SignableBody: Serialize + CanonicalSerialize {
+    /// Return the message to sign, which is the canonical serialization of the body.
+    fn signing_payload(&self) -> Result<Vec<u8>> {
+        self.canonical_serialize()
+    }
+
+    /// Return the message signed before the canonical serialization, which is the `bincode`
+    /// encoding of the body.
+    fn legacy_signing_payload(&self) -> Result<Vec<u8>> {
+        to_bincode(self)
+    }
+
+    /// Verify the signature of the body, accepting the signatures of the legacy message.
+    fn verify_signature(&self, pub_key: &PublicKey, signature: &Signature) -> Result<()> {
+        if pub_key.verify(&self.signing_payload()?, signature).is_ok() {
+            return Ok(());
+        }
+        pub_key.verify(&self.legacy_signing_payload()?, signature)
+    }
+}
+
+/// Batch-verify signatures, each over its own message.
+///
+/// The Ed25519 signatures are checked together in one multi-exponentiation with random weights,
+/// using the cofactored equation `8 * (S * B - R - k * A) = 0`. If the batch fails, they are
+/// verified one by one, so that an invalid signature is rejected as `PublicKey::verify` would.
+/// The other signatures are always verified one by one.
+pub fn batch_verify_signatures<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    instances: &[(&PublicKey, &[u8], &Signature)],
+) -> Result<()> {
+    let mut ed25519_instances = vec![];
+    for (pk, message, signature) in instances.iter() {
+        match (pk.inner(), signature) {
+            (PublicKeyInner::Ed25519(pk), Signature::Ed25519(sig)) => {
+                ed25519_instances.push((pk, *message, sig))
+            }
+            _ => pk.verify(message, signature)?,
         }
+    }
+
+    if batch_verify_ed25519(prng, &ed25519_instances).is_err() {
+        for (pk, message, sig) in ed25519_instances {
+            pk.verify(message, sig)
+                .map_err(|_| NoahError::SignatureError)?;
+        }
+    }
+    Ok(())
+}
+
+/// Batch-verify Ed25519 signatures with the cofactored verification equation.
+fn batch_verify_ed25519<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    instances: &[(&Ed25519PublicKey, &[u8], &Ed25519Signature)],
+) -> Result<()> {
+    if instances.is_empty() {
+        return Ok(());
+    }
+
+    let mut scalars = Vec::with_capacity(2 * instances.len() + 1);
+    let mut points = Vec::with_capacity(2 * instances.len() + 1);
+    let mut weighted_s = Ed25519Scalar::zero();
+    for (pk, message, sig) in instances.iter() {
+        let sig_bytes = sig.to_bytes();
+        let (r_bytes, s_bytes) = sig_bytes.split_at(32);
+
+        // S must be canonical.
+        let s = Ed25519Scalar::from_bytes(s_bytes)?;
+        if s.to_bytes() != s_bytes {
+            return Err(NoahError::SignatureError);
+        }
+
+        let mut hasher = Sha512::new();
+        hasher.update(r_bytes);
+        hasher.update(pk.as_bytes());
+        hasher.update(message);
+        let k = ed25519_scalar_from_wide_bytes(&hasher.finalize())?;
+
+        let z = Ed25519Scalar::random(prng);
+        weighted_s = weighted_s.add(&z.mul(&s));
+        scalars.push(z);
+        points.push(decompress_ed25519_point(r_bytes)?);
+        scalars.push(z.mul(&k));
+        points.push(convert_ed25519_pk_to_algebra(pk)?);
+    }
+    scalars.push(Ed25519Scalar::zero().sub(&weighted_s));
+    points.push(Ed25519Point::get_base());
+
+    let check =
+        Ed25519Point::multi_exp(&scalars.iter().collect_vec(), &points.iter().collect_vec());
+    if check.double().double().double() == Ed25519Point::get_identity() {
         Ok(())
+    } else {
+        Err(NoahError::SignatureError)
     }
 }
 
+/// Reduce 64 little-endian bytes modulo the order of the Ed25519 group.
+fn ed25519_scalar_from_wide_bytes(bytes: &[u8]) -> Result<Ed25519Scalar> {
+    // `from_bytes` reduces at most 32 bytes, so combine the halves with 2^256 = (2^128)^2.
+    let mut two_128 = [0u8; 17];
+    two_128[16] = 1;
+    let two_128 = Ed25519Scalar::from_bytes(&two_128)?;
+
+    let low = Ed25519Scalar::from_bytes(&bytes[..32])?;
+    let high = Ed25519Scalar::from_bytes(&bytes[32..])?;
+    Ok(low.add(&high.mul(&two_128).mul(&two_128)))
+}
+
 /// Function helper for get recovery id from u64.
 pub fn recovery_id_from_u64(v: u64) -> u8 {
     match v {
@@ -790,6 +953,55 @@ pub fn convert_libsecp256k1_public_key_to_address(pk: &Secp256k1PublicKey) -> [u
     bytes
 }
 
+/// Encode an ETH address in the checksummed format of EIP-55, where a letter is capitalized
+/// if the corresponding nibble of the Keccak-256 hash of the lowercase address is at least 8.
+pub fn eth_address_to_checksum_string(address: &[u8; 20]) -> String {
+    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+    let lowercase = address
+        .iter()
+        .flat_map(|b| [HEX_CHARS[(b >> 4) as usize], HEX_CHARS[(b & 0x0f) as usize]])
+        .collect_vec();
+    let hash = Keccak256::digest(&lowercase);
+
+    let mut res = String::from("0x");
+    for (i, c) in lowercase.iter().enumerate() {
+        let nibble = if i % 2 == 0 {
+            hash[i / 2] >> 4
+        } else {
+            hash[i / 2] & 0x0f
+        };
+        if nibble >= 8 {
+            res.push(c.to_ascii_uppercase() as char);
+        } else {
+            res.push(*c as char);
+        }
+    }
+    res
+}
+
+/// Parse an ETH address in the hexadecimal format, with or without the `0x` prefix.
+/// The checksum of EIP-55 is checked unless the address is all lowercase or all uppercase.
+pub fn eth_address_from_str(s: &str) -> Result<[u8; 20]> {
+    let hex_str = s.strip_prefix("0x").unwrap_or(s);
+    if hex_str.len() != 40 {
+        return Err(NoahError::DeserializationError);
+    }
+
+    let mut address = [0u8; 20];
+    for (i, chunk) in hex_str.as_bytes().chunks(2).enumerate() {
+        let pair = core::str::from_utf8(chunk).map_err(|_| NoahError::DeserializationError)?;
+        address[i] = u8::from_str_radix(pair, 16).map_err(|_| NoahError::DeserializationError)?;
+    }
+
+    let is_lowercase = hex_str.bytes().all(|c| !c.is_ascii_uppercase());
+    let is_uppercase = hex_str.bytes().all(|c| !c.is_ascii_lowercase());
+    if !is_lowercase && !is_uppercase && eth_address_to_checksum_string(&address)[2..] != *hex_str {
+        return Err(NoahError::ParameterError);
+    }
+    Ok(address)
+}
+
 fn convert_point_libsecp256k1_to_algebra(
     pk: &Secp256k1PublicKey,
 ) -> core::result::Result<SECP256K1G1, AlgebraError> {
@@ -835,8 +1047,18 @@ fn convert_ed25519_sk_to_algebra(
 fn convert_ed25519_pk_to_algebra(
     pk: &Ed25519PublicKey,
 ) -> core::result::Result<Ed25519Point, AlgebraError> {
-    let y = CompressedEdwardsY(pk.to_bytes());
-    let p = y.decompress().unwrap();
+    decompress_ed25519_point(pk.as_bytes())
+}
+
+fn decompress_ed25519_point(bytes: &[u8]) -> core::result::Result<Ed25519Point, AlgebraError> {
+    let mut compressed = [0u8; 32];
+    if bytes.len() != compressed.len() {
+        return Err(AlgebraError::DecompressElementError);
+    }
+    compressed.copy_from_slice(bytes);
+    let p = CompressedEdwardsY(compressed)
+        .decompress()
+        .ok_or(AlgebraError::DecompressElementError)?;
 
     let recip = p.Z.invert();
     let x = &p.X * &recip;
@@ -925,6 +1147,47 @@ mod test {
         kp.pub_key.verify(b"message", &sign).unwrap();
     }
 
+    #[test]
+    fn eth_address_checksum() {
+        // the test vectors of EIP-55
+        for address in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let pk = PublicKey::from_eth_address_str(address).unwrap();
+            assert_eq!(pk.to_eth_address_string().unwrap(), address);
+
+            let bytes = eth_address_from_str(address).unwrap();
+            assert_eq!(
+                eth_address_from_str(&address.to_lowercase()).unwrap(),
+                bytes
+            );
+            assert_eq!(
+                eth_address_from_str(&address[2..].to_uppercase()).unwrap(),
+                bytes
+            );
+        }
+
+        // a wrong checksum, a wrong length, or a non-hexadecimal character
+        assert!(eth_address_from_str("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err());
+        assert!(eth_address_from_str("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA").is_err());
+        assert!(eth_address_from_str("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg").is_err());
+
+        // the address of a secp256k1 key
+        let mut prng = test_rng();
+        let kp = KeyPair::sample(&mut prng, SECP256K1);
+        let address = kp.get_pk().to_eth_address_string().unwrap();
+        assert_eq!(
+            PublicKey::from_eth_address_str(&address).unwrap(),
+            kp.get_pk().to_eth_address().unwrap()
+        );
+
+        let kp = KeyPair::sample(&mut prng, ED25519);
+        assert!(kp.get_pk().to_eth_address_string().is_err());
+    }
+
     #[test]
     fn convert_secp256k1_key() {
         let mut prng = test_rng();
@@ -1001,4 +1264,53 @@ mod test {
             "Multisignature should have verify correctly even when keylist is unordered"
         );
     }
+
+    #[test]
+    fn batch_signatures() {
+        let mut prng = test_rng();
+        let messages = (0..8u8).map(|i| vec![i; 10 * i as usize]).collect_vec();
+        let keypairs = (0..8)
+            .map(|i| KeyPair::sample(&mut prng, if i % 4 == 0 { SECP256K1 } else { ED25519 }))
+            .collect_vec();
+        let sigs = keypairs
+            .iter()
+            .zip(messages.iter())
+            .map(|(kp, msg)| kp.sign(msg).unwrap())
+            .collect_vec();
+        let mut instances = keypairs
+            .iter()
+            .zip(messages.iter())
+            .zip(sigs.iter())
+            .map(|((kp, msg), sig)| (kp.get_pk_ref(), msg.as_slice(), sig))
+            .collect_vec();
+        assert!(batch_verify_signatures(&mut prng, &instances).is_ok());
+        assert!(batch_verify_signatures(&mut prng, &[]).is_ok());
+
+        // the Ed25519 signatures pass the batch equation itself, not only the fallback
+        let ed25519_instances = instances
+            .iter()
+            .filter_map(|(pk, msg, sig)| match (pk.inner(), sig) {
+                (PublicKeyInner::Ed25519(pk), Signature::Ed25519(sig)) => Some((pk, *msg, sig)),
+                _ => None,
+            })
+            .collect_vec();
+        assert_eq!(ed25519_instances.len(), 6);
+        assert!(batch_verify_ed25519(&mut prng, &ed25519_instances).is_ok());
+
+        // one Ed25519 signature over another message
+        let wrong_message = b"another message".to_vec();
+        instances[1].1 = wrong_message.as_slice();
+        assert_eq!(
+            batch_verify_signatures(&mut prng, &instances),
+            Err(NoahError::SignatureError)
+        );
+
+        // one secp256k1 signature by another key
+        instances[1].1 = messages[1].as_slice();
+        instances[4].0 = keypairs[0].get_pk_ref();
+        assert_eq!(
+            batch_verify_signatures(&mut prng, &instances),
+            Err(NoahError::SignatureError)
+        );
+    }
 }
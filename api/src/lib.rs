@@ -9,7 +9,7 @@
 #![deny(unused_comparisons, bare_trait_objects, unused_must_use)]
 #![doc(html_logo_url = "https://avatars.githubusercontent.com/u/74745723?s=200&v=4")]
 #![doc(html_playground_url = "https://play.rust-lang.org")]
-#![forbid(unsafe_code)]
+#![deny(unsafe_code)]
 #![warn(
     unused,
     future_incompatible,
@@ -22,24 +22,68 @@
 #[macro_use]
 extern crate serde_derive;
 
+#[cfg(feature = "anon_xfr")]
 #[macro_use]
 extern crate lazy_static;
 
 /// The wrapper for anonymous credentials.
 pub mod anon_creds;
 /// Module for anonymous transfer.
+#[cfg(feature = "anon_xfr")]
 pub mod anon_xfr;
+/// Module for the health of the anonymity set.
+#[cfg(feature = "anon_xfr")]
+pub mod anonymity;
+/// Module for the canonical serialization of notes.
+pub mod canonical;
+/// Module for the deterministic verification of serialized notes, for consensus.
+#[cfg(feature = "anon_xfr")]
+pub mod consensus;
+/// Module for the proofs that values committed over one group equal values in a circuit over another field.
+#[cfg(feature = "anon_xfr")]
+pub mod cross_field_equality;
+/// Module for the versioned envelopes of notes.
+#[cfg(feature = "anon_xfr")]
+pub mod envelope;
 /// Module for error handling
 pub mod errors;
+/// Module for generating the confidential allocations of the genesis of a chain.
+#[cfg(feature = "anon_xfr")]
+pub mod genesis;
+/// Module for the fingerprints of the public keys and the DID documents of wallets.
+pub mod identity;
+/// Module for the introspection of serialized notes.
+#[cfg(feature = "anon_xfr")]
+pub mod inspect;
 /// Module for anonymous and confidential keys
 pub mod keys;
+/// Module for the descriptors of outsourced proving jobs and their receipts.
+#[cfg(feature = "anon_xfr")]
+pub mod marketplace;
+/// Module for the commitments to the memos of blocks with per-memo openings.
+#[cfg(feature = "anon_xfr")]
+pub mod memo_registry;
 /// Module for next-generation anonymous transfer.
+#[cfg(feature = "anon_xfr")]
 pub mod nextgen;
 /// The wrapper of the parameters.
 pub mod parameters;
+/// Module for downloading and verifying the parameters.
+#[cfg(feature = "net")]
+pub mod params_fetch;
+/// Module for the protobuf messages of the notes.
+#[cfg(feature = "proto")]
+pub mod proto;
 /// Module for serialization.
 pub mod serialization;
+/// Module for generating the parameters of the circuits over a given SRS.
+#[cfg(feature = "anon_xfr")]
+pub mod setup;
+/// Module for the helpers of wallets.
+#[cfg(feature = "anon_xfr")]
+pub mod wallet;
 /// Module for confidential transfer.
+#[cfg(feature = "xfr")]
 pub mod xfr;
 
 pub use errors::NoahError;
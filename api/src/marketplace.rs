@@ -0,0 +1,184 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey, Signature};
+use crate::serialization::to_bincode;
+use crate::setup::{circuit_hash, Circuit};
+use ark_std::string::String;
+use noah_algebra::prelude::*;
+use noah_plonk::plonk::constraint_system::ConstraintSystem;
+use sha2::{Digest, Sha256};
+
+/// The domain separator of the digests of the proving jobs.
+const PROVING_JOB_DOMAIN: &[u8] = b"Noah Proving Job";
+
+/// The domain separator of the digests of the proofs.
+const PROVING_PROOF_DOMAIN: &[u8] = b"Noah Proving Proof";
+
+/// The domain separator of the messages signed in the proving receipts.
+const PROVING_RECEIPT_DOMAIN: &[u8] = b"Noah Proving Receipt";
+
+/// The descriptor of a proof to be produced by a third-party prover, from which the provers
+/// price the work by the size of the circuit.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProvingJob {
+    /// The label of the circuit.
+    pub label: String,
+    /// The hash of the constraint system of the circuit, as in the generated parameters.
+    pub circuit_hash: [u8; 32],
+    /// The number of gates of the circuit.
+    pub size: u64,
+    /// The time, e.g., a block height, by which the proof must be produced.
+    pub deadline: u64,
+    /// The price offered for the proof.
+    pub price: u64,
+    /// The nonce of the requester, which tells apart the jobs for the same circuit.
+    pub nonce: [u8; 32],
+}
+
+impl ProvingJob {
+    /// Describe a job for a proof of the circuit.
+    pub fn new(circuit: &Circuit, deadline: u64, price: u64, nonce: [u8; 32]) -> Result<Self> {
+        let (label, cs) = circuit.build_cs()?;
+        Ok(Self {
+            label,
+            circuit_hash: circuit_hash(&cs)?,
+            size: cs.size() as u64,
+            deadline,
+            price,
+            nonce,
+        })
+    }
+
+    /// Compute the digest of the job, which the receipts refer to.
+    pub fn digest(&self) -> Result<[u8; 32]> {
+        let mut hasher = Sha256::new();
+        hasher.update(PROVING_JOB_DOMAIN);
+        hasher.update(to_bincode(self)?);
+        Ok(hasher.finalize().into())
+    }
+}
+
+/// Compute the digest of the proof, which the receipts refer to.
+pub fn proof_digest<T: Serialize>(proof: &T) -> Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    hasher.update(PROVING_PROOF_DOMAIN);
+    hasher.update(to_bincode(proof)?);
+    Ok(hasher.finalize().into())
+}
+
+/// The receipt of a prover for the proof of a job, which attributes the proof to the prover.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProvingReceipt {
+    /// The digest of the job.
+    pub job_digest: [u8; 32],
+    /// The digest of the proof.
+    pub proof_digest: [u8; 32],
+    /// The time at which the proof was produced, in the unit of the deadline.
+    pub completed_at: u64,
+    /// The public key of the prover.
+    pub prover: PublicKey,
+    /// The signature of the prover.
+    pub signature: Signature,
+}
+
+/// Return the message signed in a receipt.
+fn receipt_message(
+    job_digest: &[u8; 32],
+    proof_digest: &[u8; 32],
+    completed_at: u64,
+    prover: &PublicKey,
+) -> Result<Vec<u8>> {
+    let mut message = PROVING_RECEIPT_DOMAIN.to_vec();
+    message.extend_from_slice(&to_bincode(&(
+        job_digest,
+        proof_digest,
+        completed_at,
+        prover,
+    ))?);
+    Ok(message)
+}
+
+impl ProvingReceipt {
+    /// Sign the receipt for the proof of the job with the key pair of the prover.
+    pub fn sign<T: Serialize>(
+        keypair: &KeyPair,
+        job: &ProvingJob,
+        proof: &T,
+        completed_at: u64,
+    ) -> Result<Self> {
+        let job_digest = job.digest()?;
+        let proof_digest = proof_digest(proof)?;
+        let prover = keypair.get_pk();
+        let signature = keypair.sign(&receipt_message(
+            &job_digest,
+            &proof_digest,
+            completed_at,
+            &prover,
+        )?)?;
+        Ok(Self {
+            job_digest,
+            proof_digest,
+            completed_at,
+            prover,
+            signature,
+        })
+    }
+
+    /// Check that the receipt is signed by its prover for the proof of the job,
+    /// produced by the deadline. The proof itself is verified separately.
+    pub fn verify<T: Serialize>(&self, job: &ProvingJob, proof: &T) -> Result<()> {
+        if self.job_digest != job.digest()?
+            || self.proof_digest != proof_digest(proof)?
+            || self.completed_at > job.deadline
+        {
+            return Err(NoahError::ProvingReceiptError);
+        }
+        self.prover.verify(
+            &receipt_message(
+                &self.job_digest,
+                &self.proof_digest,
+                self.completed_at,
+                &self.prover,
+            )?,
+            &self.signature,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::keys::KeyPair;
+    use crate::marketplace::{ProvingJob, ProvingReceipt};
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::setup::Circuit;
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_proving_receipt() {
+        let mut prng = test_rng();
+        let job = ProvingJob::new(&Circuit::ArToAbar, 100, 10, [1u8; 32]).unwrap();
+        assert_eq!(job.label, "ar_to_abar");
+        assert!(job.size > 0);
+
+        let proof = vec![7u8; 64];
+        for address_format in [SECP256K1, ED25519] {
+            let keypair = KeyPair::sample(&mut prng, address_format);
+            let receipt = ProvingReceipt::sign(&keypair, &job, &proof, 90).unwrap();
+            assert!(receipt.verify(&job, &proof).is_ok());
+
+            // the receipt is bound to the proof and to the job
+            assert!(receipt.verify(&job, &vec![8u8; 64]).is_err());
+            let mut other_job = job.clone();
+            other_job.nonce = [2u8; 32];
+            assert!(receipt.verify(&other_job, &proof).is_err());
+
+            // the attribution cannot be moved to another prover
+            let mut stolen = receipt.clone();
+            stolen.prover = KeyPair::sample(&mut prng, address_format).get_pk();
+            assert!(stolen.verify(&job, &proof).is_err());
+
+            // the proofs produced after the deadline are rejected
+            let late = ProvingReceipt::sign(&keypair, &job, &proof, 101).unwrap();
+            assert!(late.verify(&job, &proof).is_err());
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::errors::Result;
+use crate::serialization::to_bincode;
+use noah_algebra::{
+    bn254::{BN254PairingEngine, BN254Scalar, BN254G1},
+    prelude::*,
+};
+use noah_plonk::{
+    kzg::{KZGParams, KZGVectorCommitment},
+    poly_commit::kzg_poly_com::KZGOpenProof,
+};
+use sha2::{Digest, Sha512};
+
+/// The domain separator of the digests of the memos.
+const MEMO_DIGEST_DOMAIN: &[u8] = b"Noah Memo Registry";
+
+/// The commitment to the memos of a block, e.g., the `OwnerMemo`s or the `AxfrOwnerMemo`s
+/// in the order of the outputs.
+pub type MemosCommitment = KZGVectorCommitment<BN254G1>;
+
+/// The proof that a memo is at a position of the committed memos.
+pub type MemoProof = KZGOpenProof<BN254G1>;
+
+/// Compute the digest of the memo, which is the entry of the memo in the committed vector.
+pub fn memo_digest<T: Serialize>(memo: &T) -> Result<BN254Scalar> {
+    let mut hasher = Sha512::new();
+    hasher.update(MEMO_DIGEST_DOMAIN);
+    hasher.update(&to_bincode(memo)?);
+    Ok(BN254Scalar::from_hash(hasher))
+}
+
+/// Commit to the memos of a block. The number of memos is bounded by the degree of the parameters.
+pub fn commit_memos<T: Serialize>(
+    params: &KZGParams<BN254PairingEngine>,
+    memos: &[T],
+) -> Result<MemosCommitment> {
+    let digests = memos.iter().map(memo_digest).collect::<Result<Vec<_>>>()?;
+    Ok(params.commit_vector(&digests)?)
+}
+
+/// Prove the memo at the index of the memos, for a light client that fetches only this memo.
+pub fn open_memo<T: Serialize>(
+    params: &KZGParams<BN254PairingEngine>,
+    memos: &[T],
+    index: usize,
+) -> Result<MemoProof> {
+    let digests = memos.iter().map(memo_digest).collect::<Result<Vec<_>>>()?;
+    Ok(params.open_vector(&digests, index)?)
+}
+
+/// Check that the memo, as fetched from an untrusted server, is the one at the index
+/// of the committed memos. Only the verifier part of the parameters is needed.
+pub fn verify_memo<T: Serialize>(
+    params: &KZGParams<BN254PairingEngine>,
+    commitment: &MemosCommitment,
+    index: usize,
+    memo: &T,
+    proof: &MemoProof,
+) -> Result<()> {
+    Ok(params.verify_vector(commitment, index, &memo_digest(memo)?, proof)?)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anon_xfr::structs::AxfrOwnerMemo;
+    use crate::keys::KeyPair;
+    use crate::memo_registry::{commit_memos, open_memo, verify_memo};
+    use crate::parameters::AddressFormat::SECP256K1;
+    use noah_algebra::{bn254::BN254PairingEngine, prelude::*};
+    use noah_plonk::{kzg::KZGParams, poly_commit::kzg_poly_com::KZGCommitmentSchemeBN254};
+
+    #[test]
+    fn test_memo_registry() {
+        let mut prng = test_rng();
+        let params =
+            KZGParams::<BN254PairingEngine>::from_srs(KZGCommitmentSchemeBN254::new(8, &mut prng))
+                .unwrap();
+        let verifier_params = params.verifier_params();
+
+        let memos = (0..3u8)
+            .map(|i| {
+                let keypair = KeyPair::sample(&mut prng, SECP256K1);
+                AxfrOwnerMemo::new(&mut prng, &keypair.get_pk(), &[i; 32]).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let commitment = commit_memos(&params, &memos).unwrap();
+
+        let proof = open_memo(&params, &memos, 1).unwrap();
+        assert!(verify_memo(&verifier_params, &commitment, 1, &memos[1], &proof).is_ok());
+
+        // a server cannot return another memo, or the memo at another position
+        assert!(verify_memo(&verifier_params, &commitment, 1, &memos[2], &proof).is_err());
+        assert!(verify_memo(&verifier_params, &commitment, 2, &memos[1], &proof).is_err());
+    }
+}
@@ -1,10 +1,9 @@
 use crate::errors::{NoahError, Result};
-use crate::parameters::params::{
-    BULLET_PROOF_RANGE, DEFAULT_BP_NUM_GENS, MAX_CONFIDENTIAL_RECORD_NUMBER,
-};
 use crate::parameters::{
     BULLETPROOF_CURVE25519_URS, BULLETPROOF_SECQ256K1_URS, BULLETPROOF_ZORRO_URS,
+    BULLET_PROOF_RANGE, DEFAULT_BP_NUM_GENS, MAX_CONFIDENTIAL_RECORD_NUMBER,
 };
+use crate::serialization::from_bincode;
 use ark_serialize::{CanonicalDeserialize, Compress, Validate};
 use bulletproofs::BulletproofGens;
 use noah_algebra::secq256k1::Secq256k1BulletproofGens;
@@ -25,8 +24,7 @@ impl BulletproofURS for BulletproofParams {
     fn load() -> Result<BulletproofParams> {
         let urs = BULLETPROOF_CURVE25519_URS.ok_or(NoahError::MissingSRSError)?;
 
-        let pp: BulletproofParams =
-            bincode::deserialize(urs).map_err(|_| NoahError::DeserializationError)?;
+        let pp: BulletproofParams = from_bincode(urs)?;
         Ok(pp)
     }
 
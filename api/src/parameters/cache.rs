@@ -0,0 +1,66 @@
+use noah_algebra::collections::BTreeMap;
+use std::sync::RwLock;
+
+lazy_static! {
+    static ref PARAMS_CACHE: RwLock<BTreeMap<String, &'static [u8]>> = RwLock::new(BTreeMap::new());
+}
+
+/// The parameters installed at runtime, which are used in place of the parameters
+/// that are not compiled into the crate (e.g., with the `no_srs` feature).
+///
+/// The entries are named after the files in the `parameters` directory and,
+/// like the compiled-in parameters, live until the end of the program.
+pub struct ParamsCache;
+
+impl ParamsCache {
+    /// The name of the SRS.
+    pub const SRS: &'static str = "srs-padding.bin";
+
+    /// The name of the verifier parameters for confidential to anonymous.
+    pub const BAR_TO_ABAR_VK: &'static str = "bar-to-abar-vk.bin";
+
+    /// The name of the Lagrange format of the SRS for the size.
+    pub fn lagrange_name(size: usize) -> String {
+        format!("lagrange-srs-{}.bin", size)
+    }
+
+    /// Install the parameters under the name, and return them. An entry that is already
+    /// installed is kept, so the parameters handed out before never change.
+    pub fn install(name: &str, bytes: Vec<u8>) -> &'static [u8] {
+        let mut cache = PARAMS_CACHE.write().unwrap();
+        *cache
+            .entry(name.to_string())
+            .or_insert_with(|| Box::leak(bytes.into_boxed_slice()))
+    }
+
+    /// Return the parameters installed under the name.
+    pub fn get(name: &str) -> Option<&'static [u8]> {
+        PARAMS_CACHE.read().unwrap().get(name).copied()
+    }
+
+    /// Check if the parameters are installed under the name.
+    pub fn contains(name: &str) -> bool {
+        PARAMS_CACHE.read().unwrap().contains_key(name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parameters::cache::ParamsCache;
+
+    #[test]
+    fn test_params_cache() {
+        let name = "test-params-cache.bin";
+        assert!(!ParamsCache::contains(name));
+        assert_eq!(ParamsCache::get(name), None);
+
+        assert_eq!(ParamsCache::install(name, vec![1, 2, 3]), &[1, 2, 3]);
+        assert_eq!(ParamsCache::get(name), Some(&[1u8, 2, 3][..]));
+
+        // the installed entry is not replaced
+        assert_eq!(ParamsCache::install(name, vec![4]), &[1, 2, 3]);
+        assert_eq!(ParamsCache::get(name), Some(&[1u8, 2, 3][..]));
+
+        assert_eq!(ParamsCache::lagrange_name(4096), "lagrange-srs-4096.bin");
+    }
+}
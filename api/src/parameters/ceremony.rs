@@ -0,0 +1,337 @@
+use crate::errors::{NoahError, Result};
+use digest::Digest;
+use noah_algebra::{prelude::*, traits::Pairing};
+use noah_plonk::poly_commit::kzg_poly_com::KZGCommitmentScheme;
+use sha2::{Sha256, Sha512};
+
+/// The domain separator of the ceremony transcript.
+const CEREMONY_DOMAIN: &[u8] = b"Noah KZG SRS Ceremony";
+
+/// A contribution to the ceremony, which multiplies the secret of the SRS by `tau`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Contribution<G1: Group, G2: Group> {
+    /// The first power of the secret in G1 after this contribution.
+    pub s_g1: G1,
+    /// `tau` times the base of G1.
+    pub tau_g1: G1,
+    /// `tau` times the base of G2.
+    pub tau_g2: G2,
+    /// The commitment of the Schnorr proof of knowledge of `tau`.
+    pub pok_commitment: G1,
+    /// The response of the Schnorr proof of knowledge of `tau`.
+    pub pok_response: G1::ScalarType,
+    /// The hash of the SRS after this contribution.
+    pub srs_hash: [u8; 32],
+    /// The hash of the transcript up to and including this contribution.
+    pub transcript_hash: [u8; 32],
+}
+
+/// A powers-of-tau ceremony for the KZG SRS.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Ceremony<P: Pairing> {
+    /// The current SRS.
+    pub srs: KZGCommitmentScheme<P>,
+    /// The contributions so far, in order.
+    pub contributions: Vec<Contribution<P::G1, P::G2>>,
+}
+
+impl<P: Pairing> Ceremony<P> {
+    /// Start a ceremony for polynomials up to `max_degree`, where the secret is one.
+    pub fn new(max_degree: usize) -> Self {
+        let srs = KZGCommitmentScheme {
+            public_parameter_group_1: vec![P::G1::get_base(); max_degree + 1],
+            public_parameter_group_2: vec![P::G2::get_base(); 2],
+        };
+        Self {
+            srs,
+            contributions: vec![],
+        }
+    }
+
+    /// Return the hash of the transcript so far.
+    pub fn transcript_hash(&self) -> [u8; 32] {
+        match self.contributions.last() {
+            Some(contribution) => contribution.transcript_hash,
+            None => initial_transcript_hash(self.srs.public_parameter_group_1.len()),
+        }
+    }
+
+    /// Compute a contribution on top of the current SRS, and return the updated SRS with it.
+    /// The randomness is dropped when this function returns.
+    pub fn contribute<R: CryptoRng + RngCore>(
+        &self,
+        prng: &mut R,
+    ) -> Result<(KZGCommitmentScheme<P>, Contribution<P::G1, P::G2>)> {
+        let tau = P::ScalarField::random(prng);
+        if tau.is_zero() {
+            return Err(NoahError::ParameterError);
+        }
+
+        let mut public_parameter_group_1 = self.srs.public_parameter_group_1.clone();
+        let mut power = P::ScalarField::one();
+        for elem in public_parameter_group_1.iter_mut() {
+            *elem = *elem * &power;
+            power = power * &tau;
+        }
+        let public_parameter_group_2 = vec![
+            self.srs.public_parameter_group_2[0],
+            self.srs.public_parameter_group_2[1] * &tau,
+        ];
+        let srs = KZGCommitmentScheme {
+            public_parameter_group_1,
+            public_parameter_group_2,
+        };
+
+        let tau_g1 = P::G1::get_base() * &tau;
+        let tau_g2 = P::G2::get_base() * &tau;
+        let prev_hash = self.transcript_hash();
+
+        let r = P::ScalarField::random(prng);
+        let pok_commitment = P::G1::get_base() * &r;
+        let challenge =
+            pok_challenge::<P::G1, P::G2>(&prev_hash, &tau_g1, &tau_g2, &pok_commitment);
+        let pok_response = r + &(challenge * &tau);
+
+        let srs_hash = srs_hash(&srs)?;
+        let mut contribution = Contribution {
+            s_g1: srs.public_parameter_group_1[1],
+            tau_g1,
+            tau_g2,
+            pok_commitment,
+            pok_response,
+            srs_hash,
+            transcript_hash: [0u8; 32],
+        };
+        contribution.transcript_hash = contribution_hash(&prev_hash, &contribution);
+
+        Ok((srs, contribution))
+    }
+
+    /// Verify a contribution that updates the current SRS to `srs`, and, if valid, apply it.
+    pub fn update(
+        &mut self,
+        srs: KZGCommitmentScheme<P>,
+        contribution: Contribution<P::G1, P::G2>,
+    ) -> Result<()> {
+        let prev_s_g1 = self.srs.public_parameter_group_1[1];
+        verify_contribution::<P>(&self.transcript_hash(), &prev_s_g1, &contribution)?;
+
+        if srs.public_parameter_group_1.len() != self.srs.public_parameter_group_1.len()
+            || srs.public_parameter_group_1[1] != contribution.s_g1
+            || srs_hash(&srs)? != contribution.srs_hash
+        {
+            return Err(NoahError::CeremonyVerificationError);
+        }
+        verify_srs(&srs)?;
+
+        self.srs = srs;
+        self.contributions.push(contribution);
+        Ok(())
+    }
+
+    /// Verify the whole transcript, from the initial SRS to the current SRS.
+    pub fn verify(&self) -> Result<()> {
+        let mut prev_hash = initial_transcript_hash(self.srs.public_parameter_group_1.len());
+        let mut prev_s_g1 = P::G1::get_base();
+        for contribution in self.contributions.iter() {
+            verify_contribution::<P>(&prev_hash, &prev_s_g1, contribution)?;
+            prev_hash = contribution.transcript_hash;
+            prev_s_g1 = contribution.s_g1;
+        }
+
+        if self.srs.public_parameter_group_1[1] != prev_s_g1 {
+            return Err(NoahError::CeremonyVerificationError);
+        }
+        if let Some(contribution) = self.contributions.last() {
+            if srs_hash(&self.srs)? != contribution.srs_hash {
+                return Err(NoahError::CeremonyVerificationError);
+            }
+        }
+        verify_srs(&self.srs)
+    }
+
+    /// Return the SRS produced by the ceremony, after verifying the whole transcript.
+    pub fn into_srs(self) -> Result<KZGCommitmentScheme<P>> {
+        if self.contributions.is_empty() {
+            return Err(NoahError::CeremonyVerificationError);
+        }
+        self.verify()?;
+        Ok(self.srs)
+    }
+}
+
+/// Verify a contribution against the previous transcript hash and the previous first power in G1.
+pub fn verify_contribution<P: Pairing>(
+    prev_hash: &[u8; 32],
+    prev_s_g1: &P::G1,
+    contribution: &Contribution<P::G1, P::G2>,
+) -> Result<()> {
+    let g1 = P::G1::get_base();
+    let g2 = P::G2::get_base();
+
+    if contribution.tau_g1 == P::G1::get_identity() {
+        return Err(NoahError::CeremonyVerificationError);
+    }
+
+    // The proof of knowledge of `tau`.
+    let challenge = pok_challenge::<P::G1, P::G2>(
+        prev_hash,
+        &contribution.tau_g1,
+        &contribution.tau_g2,
+        &contribution.pok_commitment,
+    );
+    if g1 * &contribution.pok_response
+        != contribution.pok_commitment + &(contribution.tau_g1 * &challenge)
+    {
+        return Err(NoahError::CeremonyVerificationError);
+    }
+
+    // The same `tau` in G1 and G2, and the new secret is the previous one times `tau`.
+    let tau_eval = P::multi_pairing(&[(&contribution.tau_g1, &g2), (&-g1, &contribution.tau_g2)]);
+    let s_eval = P::multi_pairing(&[
+        (&contribution.s_g1, &g2),
+        (&-*prev_s_g1, &contribution.tau_g2),
+    ]);
+    if tau_eval != P::Gt::get_identity() || s_eval != P::Gt::get_identity() {
+        return Err(NoahError::CeremonyVerificationError);
+    }
+
+    if contribution_hash(prev_hash, contribution) != contribution.transcript_hash {
+        return Err(NoahError::CeremonyVerificationError);
+    }
+    Ok(())
+}
+
+/// Verify that the SRS consists of consecutive powers of the same secret,
+/// by checking a random linear combination of the consecutive pairs with one pairing product.
+pub fn verify_srs<P: Pairing>(srs: &KZGCommitmentScheme<P>) -> Result<()> {
+    let g1s = &srs.public_parameter_group_1;
+    let g2s = &srs.public_parameter_group_2;
+    if g1s.len() < 2 || g2s.len() != 2 {
+        return Err(NoahError::CeremonyVerificationError);
+    }
+    if g1s[0] != P::G1::get_base() || g2s[0] != P::G2::get_base() || g1s[1] == P::G1::get_identity()
+    {
+        return Err(NoahError::CeremonyVerificationError);
+    }
+
+    let mut hasher = Sha512::new();
+    hasher.update(CEREMONY_DOMAIN);
+    hasher.update(b"SRS");
+    hasher.update(srs_hash(srs)?);
+    let rho = P::ScalarField::from_hash(hasher);
+
+    let n = g1s.len() - 1;
+    let mut rho_powers = Vec::with_capacity(n);
+    let mut power = P::ScalarField::one();
+    for _ in 0..n {
+        rho_powers.push(power);
+        power = power * &rho;
+    }
+    let rho_powers_ref = rho_powers.iter().collect::<Vec<_>>();
+
+    let lower = P::G1::multi_exp(&rho_powers_ref, &g1s[..n].iter().collect::<Vec<_>>());
+    let upper = P::G1::multi_exp(&rho_powers_ref, &g1s[1..].iter().collect::<Vec<_>>());
+
+    let eval = P::multi_pairing(&[(&upper, &g2s[0]), (&-lower, &g2s[1])]);
+    if eval != P::Gt::get_identity() {
+        return Err(NoahError::CeremonyVerificationError);
+    }
+    Ok(())
+}
+
+fn initial_transcript_hash(len: usize) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(CEREMONY_DOMAIN);
+    hasher.update((len as u64).to_le_bytes());
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&hasher.finalize());
+    hash
+}
+
+fn srs_hash<P: Pairing>(srs: &KZGCommitmentScheme<P>) -> Result<[u8; 32]> {
+    let bytes = srs
+        .to_unchecked_bytes()
+        .map_err(|_| NoahError::SerializationError)?;
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Sha256::digest(&bytes));
+    Ok(hash)
+}
+
+fn pok_challenge<G1: Group, G2: Group>(
+    prev_hash: &[u8; 32],
+    tau_g1: &G1,
+    tau_g2: &G2,
+    pok_commitment: &G1,
+) -> G1::ScalarType {
+    let mut hasher = Sha512::new();
+    hasher.update(CEREMONY_DOMAIN);
+    hasher.update(b"PoK");
+    hasher.update(prev_hash);
+    hasher.update(tau_g1.to_compressed_bytes());
+    hasher.update(tau_g2.to_compressed_bytes());
+    hasher.update(pok_commitment.to_compressed_bytes());
+    G1::ScalarType::from_hash(hasher)
+}
+
+fn contribution_hash<G1: Group, G2: Group>(
+    prev_hash: &[u8; 32],
+    contribution: &Contribution<G1, G2>,
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(CEREMONY_DOMAIN);
+    hasher.update(prev_hash);
+    hasher.update(contribution.s_g1.to_compressed_bytes());
+    hasher.update(contribution.tau_g1.to_compressed_bytes());
+    hasher.update(contribution.tau_g2.to_compressed_bytes());
+    hasher.update(contribution.pok_commitment.to_compressed_bytes());
+    hasher.update(contribution.pok_response.to_bytes());
+    hasher.update(contribution.srs_hash);
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&hasher.finalize());
+    hash
+}
+
+#[cfg(test)]
+mod test {
+    use super::Ceremony;
+    use noah_algebra::{bn254::BN254PairingEngine, prelude::*};
+
+    #[test]
+    fn test_ceremony() {
+        let mut prng = test_rng();
+        let mut ceremony = Ceremony::<BN254PairingEngine>::new(16);
+        assert!(ceremony.verify().is_ok());
+
+        for _ in 0..3 {
+            let (srs, contribution) = ceremony.contribute(&mut prng).unwrap();
+            ceremony.update(srs, contribution).unwrap();
+        }
+        assert_eq!(ceremony.contributions.len(), 3);
+        assert!(ceremony.verify().is_ok());
+
+        // A contribution with a forged transcript hash is rejected.
+        let (srs, mut contribution) = ceremony.contribute(&mut prng).unwrap();
+        contribution.transcript_hash[0] ^= 1;
+        assert!(ceremony.update(srs, contribution).is_err());
+
+        // An SRS that does not match its contribution is rejected.
+        let (mut srs, contribution) = ceremony.contribute(&mut prng).unwrap();
+        srs.public_parameter_group_1[5] = srs.public_parameter_group_1[4];
+        assert!(ceremony.update(srs, contribution).is_err());
+
+        // Tampering with the SRS breaks the verification of the whole transcript.
+        ceremony.srs.public_parameter_group_1[3] =
+            ceremony.srs.public_parameter_group_1[3].double();
+        assert!(ceremony.verify().is_err());
+
+        let mut prng = test_rng();
+        let mut ceremony = Ceremony::<BN254PairingEngine>::new(16);
+        let (srs, contribution) = ceremony.contribute(&mut prng).unwrap();
+        ceremony.update(srs, contribution).unwrap();
+        assert!(ceremony.into_srs().is_ok());
+    }
+}
@@ -0,0 +1,375 @@
+use crate::errors::{NoahError, Result};
+use crate::parameters::params::ProverParams;
+use crate::serialization::{from_bincode, to_bincode};
+use core::ops::Range;
+use memmap2::Mmap;
+use merlin::Transcript;
+use noah_algebra::{
+    bn254::{BN254Scalar, BN254G1, BN254G2},
+    msm::msm,
+    prelude::*,
+};
+use noah_plonk::errors::PlonkError;
+use noah_plonk::poly_commit::{
+    field_polynomial::FpPolynomial,
+    kzg_poly_com::{KZGCommitment, KZGCommitmentSchemeBN254},
+    pcs::PolyComScheme,
+};
+use std::{fs::File, io::Write, path::Path, sync::Arc};
+
+/// The magic bytes at the start of the memory-mapped prover parameters.
+const MMAP_MAGIC: &[u8; 8] = b"NOAHPP01";
+
+/// The number of sections, i.e., the label, the SRS, the Lagrange bases,
+/// the constraint system, and the TurboPlonk proving key, in this order.
+const NUM_SECTIONS: usize = 5;
+
+/// The length of the header, i.e., the magic bytes and the offset and length of each section.
+const HEADER_LEN: usize = MMAP_MAGIC.len() + NUM_SECTIONS * 16;
+
+/// The number of SRS elements that are read into memory at a time.
+pub const SRS_PAGE_LEN: usize = 1024;
+
+/// The KZG commitment scheme over BN254, whose SRS in G1 stays in the memory-mapped file
+/// and is read page by page when committing.
+pub struct MmapKZGCommitmentScheme {
+    mmap: Arc<Mmap>,
+    /// The offset of the first SRS element in G1.
+    offset: usize,
+    /// The number of SRS elements in G1.
+    len: usize,
+    /// The first SRS element in G1 and the SRS in G2, which are all the verification needs.
+    verifier: KZGCommitmentSchemeBN254,
+}
+
+impl MmapKZGCommitmentScheme {
+    /// Open the SRS in the section, which is in the format of `KZGCommitmentScheme::to_unchecked_bytes`.
+    fn open(mmap: Arc<Mmap>, section: Range<usize>) -> Result<Self> {
+        let bytes = &mmap[section.clone()];
+        if bytes.len() < 8 {
+            return Err(NoahError::DeserializationError);
+        }
+        let len_1 = u32::from_le_bytes(bytes[0..4].try_into().unwrap()) as usize;
+        let len_2 = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        let n_1 = BN254G1::unchecked_size();
+        let n_2 = BN254G2::unchecked_size();
+        if len_1 == 0 || len_2 != 2 || bytes.len() != 8 + n_1 * len_1 + n_2 * len_2 {
+            return Err(NoahError::DeserializationError);
+        }
+
+        let offset = section.start + 8;
+        let g1_0 = BN254G1::from_unchecked_bytes(&mmap[offset..offset + n_1])
+            .map_err(|_| NoahError::DeserializationError)?;
+        let g2_offset = offset + n_1 * len_1;
+        let mut public_parameter_group_2 = Vec::with_capacity(2);
+        for i in 0..2 {
+            let start = g2_offset + n_2 * i;
+            public_parameter_group_2.push(
+                BN254G2::from_unchecked_bytes(&mmap[start..start + n_2])
+                    .map_err(|_| NoahError::DeserializationError)?,
+            );
+        }
+
+        Ok(Self {
+            mmap,
+            offset,
+            len: len_1,
+            verifier: KZGCommitmentSchemeBN254 {
+                public_parameter_group_1: vec![g1_0],
+                public_parameter_group_2,
+            },
+        })
+    }
+
+    /// Read the SRS elements in G1 with indices in the range.
+    pub fn read_range(
+        &self,
+        range: Range<usize>,
+    ) -> core::result::Result<Vec<BN254G1>, PlonkError> {
+        if range.end > self.len {
+            return Err(PlonkError::DegreeError);
+        }
+        let n = BN254G1::unchecked_size();
+        range
+            .map(|i| {
+                let start = self.offset + n * i;
+                BN254G1::from_unchecked_bytes(&self.mmap[start..start + n])
+                    .map_err(PlonkError::from)
+            })
+            .collect()
+    }
+}
+
+impl PolyComScheme for MmapKZGCommitmentScheme {
+    type Field = BN254Scalar;
+    type Commitment = KZGCommitment<BN254G1>;
+
+    fn max_degree(&self) -> usize {
+        self.len - 1
+    }
+
+    fn commit(
+        &self,
+        polynomial: &FpPolynomial<BN254Scalar>,
+    ) -> core::result::Result<Self::Commitment, PlonkError> {
+        let coefs = polynomial.get_coefs_ref();
+        let degree = polynomial.degree();
+
+        if degree + 1 > self.len {
+            return Err(PlonkError::DegreeError);
+        }
+
+        let coefs = &coefs[..core::cmp::min(coefs.len(), degree + 1)];
+        let mut commitment = BN254G1::get_identity();
+        for (i, page) in coefs.chunks(SRS_PAGE_LEN).enumerate() {
+            let start = i * SRS_PAGE_LEN;
+            let points = self.read_range(start..start + page.len())?;
+
+            let scalars_ref = page.iter().collect::<Vec<_>>();
+            let points_ref = points.iter().collect::<Vec<_>>();
+            commitment += &msm(&scalars_ref, &points_ref)?;
+        }
+
+        Ok(KZGCommitment(commitment))
+    }
+
+    fn eval(&self, polynomial: &FpPolynomial<BN254Scalar>, point: &BN254Scalar) -> BN254Scalar {
+        polynomial.eval(point)
+    }
+
+    fn prove(
+        &self,
+        polynomial: &FpPolynomial<BN254Scalar>,
+        point: &BN254Scalar,
+        max_degree: usize,
+    ) -> core::result::Result<Self::Commitment, PlonkError> {
+        let eval = polynomial.eval(point);
+
+        if polynomial.degree() > max_degree {
+            return Err(PlonkError::DegreeError);
+        }
+
+        let nominator = polynomial.sub(&FpPolynomial::from_coefs(vec![eval]));
+        let vanishing_poly = FpPolynomial::from_coefs(vec![point.neg(), BN254Scalar::one()]);
+        let (q_poly, r_poly) = nominator.div_rem(&vanishing_poly);
+
+        if !r_poly.is_zero() {
+            return Err(PlonkError::PCSProveEvalError);
+        }
+
+        self.commit(&q_poly)
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        degree: usize,
+        point: &BN254Scalar,
+        value: &BN254Scalar,
+        proof: &Self::Commitment,
+    ) -> core::result::Result<(), PlonkError> {
+        self.verifier
+            .verify(commitment, degree, point, value, proof)
+    }
+
+    fn apply_blind_factors(
+        &self,
+        commitment: &Self::Commitment,
+        blinds: &[BN254Scalar],
+        zeroing_degree: usize,
+    ) -> Self::Commitment {
+        let lower = self.read_range(0..blinds.len()).unwrap();
+        let upper = self
+            .read_range(zeroing_degree..zeroing_degree + blinds.len())
+            .unwrap();
+
+        let mut commitment = commitment.0;
+        for ((blind, lower), upper) in blinds.iter().zip(lower.iter()).zip(upper.iter()) {
+            commitment += &(*lower * blind);
+            commitment += &(*upper * &blind.neg());
+        }
+        KZGCommitment(commitment)
+    }
+
+    fn batch_verify_diff_points(
+        &self,
+        transcript: &mut Transcript,
+        cm_vec: &[Self::Commitment],
+        degree: usize,
+        point_vec: &[BN254Scalar],
+        eval_vec: &[BN254Scalar],
+        proofs: &[Self::Commitment],
+        challenge: &BN254Scalar,
+    ) -> core::result::Result<(), PlonkError> {
+        self.verifier.batch_verify_diff_points(
+            transcript, cm_vec, degree, point_vec, eval_vec, proofs, challenge,
+        )
+    }
+
+    fn shrink_to_verifier_only(&self) -> Self {
+        Self {
+            mmap: self.mmap.clone(),
+            offset: self.offset,
+            len: 1,
+            verifier: self.verifier.shrink_to_verifier_only(),
+        }
+    }
+}
+
+impl ProverParams {
+    /// Save the prover parameters in the layout that `ProverParams::open_mmap` maps.
+    pub fn save_mmap<T: AsRef<Path>>(&self, path: T) -> Result<()> {
+        let sections = [
+            self.label.as_bytes().to_vec(),
+            self.pcs.to_unchecked_bytes()?,
+            match &self.lagrange_pcs {
+                Some(lagrange_pcs) => lagrange_pcs.to_unchecked_bytes()?,
+                None => vec![],
+            },
+            to_bincode(&self.cs)?,
+            to_bincode(&self.prover_params)?,
+        ];
+
+        let mut header = MMAP_MAGIC.to_vec();
+        let mut offset = HEADER_LEN;
+        for section in sections.iter() {
+            header.extend_from_slice(&(offset as u64).to_le_bytes());
+            header.extend_from_slice(&(section.len() as u64).to_le_bytes());
+            offset += section.len();
+        }
+
+        let mut file = File::create(path).map_err(|_| NoahError::SerializationError)?;
+        file.write_all(&header)
+            .map_err(|_| NoahError::SerializationError)?;
+        for section in sections.iter() {
+            file.write_all(section)
+                .map_err(|_| NoahError::SerializationError)?;
+        }
+        Ok(())
+    }
+
+    /// Map the prover parameters saved by `ProverParams::save_mmap`. The SRS and the Lagrange bases
+    /// stay in the file and are paged in on demand, while the constraint system and the proving key
+    /// are read into memory.
+    pub fn open_mmap<T: AsRef<Path>>(path: T) -> Result<ProverParams<MmapKZGCommitmentScheme>> {
+        let file = File::open(path).map_err(|_| NoahError::DeserializationError)?;
+        // SAFETY: the file is only read through the mapping, and the parameters files are not
+        // modified while they are in use, which is the same assumption as loading them.
+        #[allow(unsafe_code)]
+        let mmap =
+            Arc::new(unsafe { Mmap::map(&file) }.map_err(|_| NoahError::DeserializationError)?);
+
+        if mmap.len() < HEADER_LEN || &mmap[..MMAP_MAGIC.len()] != MMAP_MAGIC {
+            return Err(NoahError::DeserializationError);
+        }
+        let mut sections = Vec::with_capacity(NUM_SECTIONS);
+        for i in 0..NUM_SECTIONS {
+            let entry = MMAP_MAGIC.len() + 16 * i;
+            let offset = u64::from_le_bytes(mmap[entry..entry + 8].try_into().unwrap()) as usize;
+            let len = u64::from_le_bytes(mmap[entry + 8..entry + 16].try_into().unwrap()) as usize;
+            if offset.checked_add(len).map_or(true, |end| end > mmap.len()) {
+                return Err(NoahError::DeserializationError);
+            }
+            sections.push(offset..offset + len);
+        }
+
+        let label = String::from_utf8(mmap[sections[0].clone()].to_vec())
+            .map_err(|_| NoahError::DeserializationError)?;
+        let pcs = MmapKZGCommitmentScheme::open(mmap.clone(), sections[1].clone())?;
+        let lagrange_pcs = if sections[2].is_empty() {
+            None
+        } else {
+            Some(MmapKZGCommitmentScheme::open(
+                mmap.clone(),
+                sections[2].clone(),
+            )?)
+        };
+        let cs = from_bincode(&mmap[sections[3].clone()])?;
+        let prover_params = from_bincode(&mmap[sections[4].clone()])?;
+
+        Ok(ProverParams {
+            label,
+            pcs,
+            lagrange_pcs,
+            cs,
+            prover_params,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parameters::params::ProverParams;
+    use crate::serialization::to_bincode;
+    use merlin::Transcript;
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+    use noah_plonk::plonk::{
+        constraint_system::{ConstraintSystem, TurboCS},
+        prover::prover_with_lagrange,
+        verifier::verifier,
+    };
+    use noah_plonk::poly_commit::{
+        field_polynomial::FpPolynomial, kzg_poly_com::KZGCommitmentSchemeBN254, pcs::PolyComScheme,
+    };
+
+    #[test]
+    fn test_mmap_prover_params() {
+        let mut prng = test_rng();
+
+        // x * y = z, with z public
+        let mut cs = TurboCS::<BN254Scalar>::new();
+        let x = cs.new_variable(BN254Scalar::from(3u32));
+        let y = cs.new_variable(BN254Scalar::from(7u32));
+        let z = cs.mul(x, y);
+        cs.prepare_pi_variable(z);
+        cs.pad();
+
+        let pcs = KZGCommitmentSchemeBN254::new(cs.size() + 3, &mut prng);
+        let params = ProverParams::from_cs(String::from("test"), cs, pcs, None, None).unwrap();
+
+        let path = std::env::temp_dir().join("noah-test-mmap-prover-params.bin");
+        params.save_mmap(&path).unwrap();
+        let mut mmap_params = ProverParams::open_mmap(&path).unwrap();
+        assert_eq!(mmap_params.label, params.label);
+        assert!(mmap_params.lagrange_pcs.is_none());
+        assert_eq!(
+            to_bincode(&mmap_params.prover_params).unwrap(),
+            to_bincode(&params.prover_params).unwrap()
+        );
+
+        let poly = FpPolynomial::from_coefs(vec![
+            BN254Scalar::from(2u32),
+            BN254Scalar::from(3u32),
+            BN254Scalar::from(6u32),
+        ]);
+        assert_eq!(
+            mmap_params.pcs.commit(&poly).unwrap(),
+            params.pcs.commit(&poly).unwrap()
+        );
+
+        let witness = mmap_params.cs.get_and_clear_witness();
+        let proof = prover_with_lagrange(
+            &mut prng,
+            &mut Transcript::new(b"test"),
+            &mmap_params.pcs,
+            mmap_params.lagrange_pcs.as_ref(),
+            &mmap_params.cs,
+            &mmap_params.prover_params,
+            &witness,
+        )
+        .unwrap();
+
+        let verifier_params = params.prover_params.get_verifier_params_ref();
+        assert!(verifier(
+            &mut Transcript::new(b"test"),
+            &params.pcs.shrink_to_verifier_only(),
+            &params.cs.shrink_to_verifier_only(),
+            verifier_params,
+            &[BN254Scalar::from(21u32)],
+            &proof,
+        )
+        .is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,12 +1,59 @@
+#[cfg(all(
+    feature = "anon_xfr",
+    feature = "compressed_vk",
+    not(feature = "no_vk")
+))]
+use ark_std::boxed::Box;
+#[cfg(feature = "anon_xfr")]
 use noah_algebra::collections::BTreeMap;
 
 /// Parameters for Bulletproofs.
 pub mod bulletproofs;
 
 /// Definitions and constructions for prover and verifier parameters.
+#[cfg(feature = "anon_xfr")]
 pub mod params;
+#[cfg(feature = "anon_xfr")]
 pub use params::*;
 
+/// The memory-mapped prover parameters, which read the SRS on demand.
+#[cfg(feature = "mmap")]
+pub mod mmap;
+
+/// The cache of the parameters installed at runtime.
+#[cfg(all(feature = "anon_xfr", feature = "std"))]
+pub mod cache;
+
+/// The ceremony for the KZG SRS, with contributions, updates, and their verification.
+#[cfg(feature = "anon_xfr")]
+pub mod ceremony;
+
+/// The schemas of the public inputs of the circuits.
+#[cfg(feature = "anon_xfr")]
+pub mod schema;
+
+/// The registry of the verifier parameters, with rotations scheduled at heights.
+#[cfg(feature = "anon_xfr")]
+pub mod registry;
+
+/// The range in the Bulletproofs range check.
+pub const BULLET_PROOF_RANGE: usize = 32;
+/// The maximal number
+pub const MAX_CONFIDENTIAL_RECORD_NUMBER: usize = 128;
+/// The default number of Bulletproofs generators
+pub const DEFAULT_BP_NUM_GENS: usize = 256;
+/// The number of the Bulletproofs(over the Secq256k1 curve) generators needed for anonymous transfer.
+pub const ANON_XFR_BP_GENS_LEN: usize = 2048;
+
+/// The address format.
+#[derive(Copy, Clone)]
+pub enum AddressFormat {
+    /// Secp256k1 address
+    SECP256K1,
+    /// Ed25519 address
+    ED25519,
+}
+
 #[cfg(not(feature = "no_urs"))]
 /// The Bulletproofs(over the Curve25519 curve) URS.
 pub static BULLETPROOF_CURVE25519_URS: Option<&'static [u8]> = Some(include_bytes!(
@@ -36,112 +83,134 @@ pub static BULLETPROOF_ZORRO_URS: Option<&'static [u8]> =
 /// The Bulletproofs(over the Secq256k1 curve) URS.
 pub static BULLETPROOF_SECQ256K1_URS: Option<&'static [u8]> = None;
 
-#[cfg(not(feature = "no_srs"))]
+#[cfg(all(feature = "anon_xfr", not(feature = "no_srs")))]
 /// The SRS.
 pub static SRS: Option<&'static [u8]> = Some(include_bytes!("../../parameters/srs-padding.bin"));
 
-#[cfg(feature = "no_srs")]
+#[cfg(all(feature = "anon_xfr", feature = "no_srs"))]
 /// The SRS.
 pub static SRS: Option<&'static [u8]> = None;
 
-#[cfg(not(feature = "no_vk"))]
-/// The common part of the verifier parameters for anonymous transfer.
-pub static ABAR_TO_ABAR_VERIFIER_COMMON_PARAMS: Option<&'static [u8]> =
-    Some(include_bytes!("../../parameters/transfer-vk-common.bin"));
+/// Embed the verifier parameters from the file in the `parameters` directory.
+///
+/// With the `compressed_vk` feature, the build script compresses the file, and
+/// the parameters are decompressed on first use.
+#[cfg(feature = "anon_xfr")]
+macro_rules! embed_verifier_params {
+    ($(#[$doc:meta])* $name:ident, $file:literal) => {
+        #[cfg(feature = "no_vk")]
+        lazy_static! {
+            $(#[$doc])*
+            pub static ref $name: Option<&'static [u8]> = None;
+        }
+
+        #[cfg(all(not(feature = "no_vk"), not(feature = "compressed_vk")))]
+        lazy_static! {
+            $(#[$doc])*
+            pub static ref $name: Option<&'static [u8]> =
+                Some(include_bytes!(concat!("../../parameters/", $file)));
+        }
+
+        #[cfg(all(not(feature = "no_vk"), feature = "compressed_vk"))]
+        lazy_static! {
+            $(#[$doc])*
+            pub static ref $name: Option<&'static [u8]> = decompress_verifier_params(
+                include_bytes!(concat!(env!("OUT_DIR"), "/", $file, ".deflate"))
+            );
+        }
+    };
+}
 
-#[cfg(feature = "no_vk")]
-/// The common part of the verifier parameters for anonymous transfer.
-pub static ABAR_TO_ABAR_VERIFIER_COMMON_PARAMS: Option<&'static [u8]> = None;
+/// Decompress the verifier parameters, which live until the end of the program.
+#[cfg(all(
+    feature = "anon_xfr",
+    feature = "compressed_vk",
+    not(feature = "no_vk")
+))]
+fn decompress_verifier_params(bytes: &[u8]) -> Option<&'static [u8]> {
+    let params = miniz_oxide::inflate::decompress_to_vec(bytes).ok()?;
+    Some(Box::leak(params.into_boxed_slice()))
+}
 
-#[cfg(not(feature = "no_vk"))]
-/// The specific part of the verifier parameters for ed25519 anonymous transfer.
-pub static ABAR_TO_ABAR_VERIFIER_ED25519_SPECIFIC_PARAMS: Option<&'static [u8]> = Some(
-    include_bytes!("../../parameters/transfer-vk-ed25519-specific.bin"),
+#[cfg(feature = "anon_xfr")]
+embed_verifier_params!(
+    /// The common part of the verifier parameters for anonymous transfer.
+    ABAR_TO_ABAR_VERIFIER_COMMON_PARAMS,
+    "transfer-vk-common.bin"
 );
 
-#[cfg(not(feature = "no_vk"))]
-/// The specific part of the verifier parameters for secp256k1 anonymous transfer.
-pub static ABAR_TO_ABAR_VERIFIER_SECP256K1_SPECIFIC_PARAMS: Option<&'static [u8]> = Some(
-    include_bytes!("../../parameters/transfer-vk-secp256k1-specific.bin"),
+#[cfg(feature = "anon_xfr")]
+embed_verifier_params!(
+    /// The specific part of the verifier parameters for ed25519 anonymous transfer.
+    ABAR_TO_ABAR_VERIFIER_ED25519_SPECIFIC_PARAMS,
+    "transfer-vk-ed25519-specific.bin"
 );
 
-#[cfg(feature = "no_vk")]
-/// The specific part of the verifier parameters for ed25519 anonymous transfer.
-pub static ABAR_TO_ABAR_VERIFIER_ED25519_SPECIFIC_PARAMS: Option<&'static [u8]> = None;
+#[cfg(feature = "anon_xfr")]
+embed_verifier_params!(
+    /// The specific part of the verifier parameters for secp256k1 anonymous transfer.
+    ABAR_TO_ABAR_VERIFIER_SECP256K1_SPECIFIC_PARAMS,
+    "transfer-vk-secp256k1-specific.bin"
+);
 
-#[cfg(feature = "no_vk")]
-/// The specific part of the verifier parameters for secp256k1 anonymous transfer.
-pub static ABAR_TO_ABAR_VERIFIER_SECP256K1_SPECIFIC_PARAMS: Option<&'static [u8]> = None;
+#[cfg(feature = "anon_xfr")]
+embed_verifier_params!(
+    /// The verifier parameters for ed25519 anonymous to confidential.
+    ABAR_TO_BAR_ED25519_VERIFIER_PARAMS,
+    "abar-to-bar-vk-ed25519.bin"
+);
 
-#[cfg(not(feature = "no_vk"))]
-/// The verifier parameters for ed25519 anonymous to confidential.
-pub static ABAR_TO_BAR_ED25519_VERIFIER_PARAMS: Option<&'static [u8]> = Some(include_bytes!(
-    "../../parameters/abar-to-bar-vk-ed25519.bin"
-));
+#[cfg(feature = "anon_xfr")]
+embed_verifier_params!(
+    /// The verifier parameters for secp256k1 anonymous to confidential.
+    ABAR_TO_BAR_SECP256K1_VERIFIER_PARAMS,
+    "abar-to-bar-vk-secp256k1.bin"
+);
 
-#[cfg(feature = "no_vk")]
-/// The verifier parameters for anonymous to confidential.
-pub static ABAR_TO_BAR_ED25519_VERIFIER_PARAMS: Option<&'static [u8]> = None;
+#[cfg(feature = "anon_xfr")]
+embed_verifier_params!(
+    /// The verifier parameters for confidential to anonymous.
+    BAR_TO_ABAR_VERIFIER_PARAMS,
+    "bar-to-abar-vk.bin"
+);
 
-#[cfg(not(feature = "no_vk"))]
-/// The verifier parameters for secp256k1 anonymous to confidential.
-pub static ABAR_TO_BAR_SECP256K1_VERIFIER_PARAMS: Option<&'static [u8]> = Some(include_bytes!(
-    "../../parameters/abar-to-bar-vk-secp256k1.bin"
-));
+#[cfg(feature = "anon_xfr")]
+embed_verifier_params!(
+    /// The verifier parameters for transparent to anonymous.
+    AR_TO_ABAR_VERIFIER_PARAMS,
+    "ar-to-abar-vk.bin"
+);
 
-#[cfg(feature = "no_vk")]
-/// The verifier parameters for anonymous to confidential.
-pub static ABAR_TO_BAR_SECP256K1_VERIFIER_PARAMS: Option<&'static [u8]> = None;
-
-#[cfg(not(feature = "no_vk"))]
-/// The verifier parameters for confidential to anonymous.
-pub static BAR_TO_ABAR_VERIFIER_PARAMS: Option<&'static [u8]> =
-    Some(include_bytes!("../../parameters/bar-to-abar-vk.bin"));
-
-#[cfg(feature = "no_vk")]
-/// The verifier parameters for confidential to anonymous.
-pub static BAR_TO_ABAR_VERIFIER_PARAMS: Option<&'static [u8]> = None;
-
-#[cfg(not(feature = "no_vk"))]
-/// The verifier parameters for transparent to anonymous.
-pub static AR_TO_ABAR_VERIFIER_PARAMS: Option<&'static [u8]> =
-    Some(include_bytes!("../../parameters/ar-to-abar-vk.bin"));
-
-#[cfg(feature = "no_vk")]
-/// The verifier parameters for transparent to anonymous.
-pub static AR_TO_ABAR_VERIFIER_PARAMS: Option<&'static [u8]> = None;
-
-#[cfg(not(feature = "no_vk"))]
-/// The verifier parameters for ed25519 anonymous to transparent.
-pub static ABAR_TO_AR_ED25519_VERIFIER_PARAMS: Option<&'static [u8]> =
-    Some(include_bytes!("../../parameters/abar-to-ar-vk-ed25519.bin"));
-
-#[cfg(feature = "no_vk")]
-/// The verifier parameters for ed25519 anonymous to transparent.
-pub static ABAR_TO_AR_ED25519_VERIFIER_PARAMS: Option<&'static [u8]> = None;
-
-#[cfg(not(feature = "no_vk"))]
-/// The verifier parameters for secp256k1 anonymous to transparent.
-pub static ABAR_TO_AR_SECP256K1_VERIFIER_PARAMS: Option<&'static [u8]> = Some(include_bytes!(
-    "../../parameters/abar-to-ar-vk-secp256k1.bin"
-));
+#[cfg(feature = "anon_xfr")]
+embed_verifier_params!(
+    /// The verifier parameters for ed25519 anonymous to transparent.
+    ABAR_TO_AR_ED25519_VERIFIER_PARAMS,
+    "abar-to-ar-vk-ed25519.bin"
+);
 
-#[cfg(feature = "no_vk")]
-/// The verifier parameters for secp256k1 anonymous to transparent.
-pub static ABAR_TO_AR_SECP256K1_VERIFIER_PARAMS: Option<&'static [u8]> = None;
+#[cfg(feature = "anon_xfr")]
+embed_verifier_params!(
+    /// The verifier parameters for secp256k1 anonymous to transparent.
+    ABAR_TO_AR_SECP256K1_VERIFIER_PARAMS,
+    "abar-to-ar-vk-secp256k1.bin"
+);
 
-#[cfg(feature = "no_srs")]
+#[cfg(all(feature = "anon_xfr", feature = "no_srs"))]
 lazy_static! {
     /// The Lagrange format of the SRS.
     pub static ref LAGRANGE_BASES: BTreeMap<usize, &'static [u8]> = BTreeMap::default();
 }
 
-#[cfg(not(feature = "no_srs"))]
+#[cfg(all(feature = "anon_xfr", not(feature = "no_srs")))]
 static LAGRANGE_BASE_4096: &[u8] = include_bytes!("../../parameters/lagrange-srs-4096.bin");
-#[cfg(all(not(feature = "no_srs"), not(feature = "lightweight")))]
+#[cfg(all(
+    feature = "anon_xfr",
+    not(feature = "no_srs"),
+    not(feature = "lightweight")
+))]
 static LAGRANGE_BASE_8192: &[u8] = include_bytes!("../../parameters/lagrange-srs-8192.bin");
 
-#[cfg(not(feature = "no_srs"))]
+#[cfg(all(feature = "anon_xfr", not(feature = "no_srs")))]
 lazy_static! {
     /// The Lagrange format of the SRS.
     pub static ref LAGRANGE_BASES: BTreeMap<usize, &'static [u8]> = {
@@ -2,13 +2,24 @@ use crate::anon_xfr::abar_to_abar::{build_multi_xfr_cs, AXfrWitness};
 use crate::anon_xfr::abar_to_ar::build_abar_to_ar_cs;
 use crate::anon_xfr::abar_to_bar::build_abar_to_bar_cs;
 use crate::anon_xfr::ar_to_abar::build_ar_to_abar_cs;
-use crate::anon_xfr::bar_to_abar::build_bar_to_abar_cs;
-use crate::anon_xfr::structs::{MTNode, MTPath, PayeeWitness, PayerWitness};
-use crate::anon_xfr::{
-    commit, nullify, AXfrAddressFoldingWitness, TurboPlonkCS, FEE_TYPE, TREE_DEPTH,
+use crate::anon_xfr::asset_tracing::{
+    build_abar_tracing_cs, gen_abar_tracing_traces, AXfrTracerKeyPair,
+};
+use crate::anon_xfr::bar_to_abar::{
+    bar_to_abar_schema, build_bar_to_abar_cs, BAR_TO_ABAR_PREHASHED_LABEL,
 };
+use crate::anon_xfr::disclosure::build_abar_disclosure_cs;
+use crate::anon_xfr::nft::{build_nft_transfer_cs, NFT_AMOUNT};
+use crate::anon_xfr::proofs::build_abar_amount_range_cs;
+use crate::anon_xfr::structs::{MTNode, MTPath, PayeeWitness, PayerWitness};
+use crate::anon_xfr::swap::{build_abar_swap_cs, AXfrSwapWitness};
+use crate::anon_xfr::{commit, nullify, AXfrAddressFoldingWitness, FEE_TYPE, TREE_DEPTH};
 use crate::errors::{NoahError, Result};
 use crate::keys::KeyPair;
+#[cfg(feature = "std")]
+use crate::parameters::cache::ParamsCache;
+use crate::parameters::schema::PublicInputsSchema;
+pub use crate::parameters::AddressFormat;
 use crate::parameters::AddressFormat::{ED25519, SECP256K1};
 use crate::parameters::{
     ABAR_TO_ABAR_VERIFIER_COMMON_PARAMS, ABAR_TO_ABAR_VERIFIER_ED25519_SPECIFIC_PARAMS,
@@ -17,23 +28,24 @@ use crate::parameters::{
     ABAR_TO_BAR_SECP256K1_VERIFIER_PARAMS, AR_TO_ABAR_VERIFIER_PARAMS, BAR_TO_ABAR_VERIFIER_PARAMS,
     LAGRANGE_BASES, SRS,
 };
+pub use crate::parameters::{
+    ANON_XFR_BP_GENS_LEN, BULLET_PROOF_RANGE, DEFAULT_BP_NUM_GENS, MAX_CONFIDENTIAL_RECORD_NUMBER,
+};
+use crate::serialization::from_bincode;
 use ark_std::{collections::BTreeMap, format};
-use noah_algebra::bn254::{BN254Scalar, BN254G1};
+use noah_algebra::baby_jubjub::BabyJubjubScalar;
+use noah_algebra::bn254::{BN254Scalar, BN254G1, BN254G2};
 use noah_algebra::prelude::*;
 use noah_algebra::ristretto::{RistrettoPoint, RistrettoScalar};
 use noah_crypto::delegated_schnorr::{DSInspectionBN254Ristretto, DSProofBN254Ristretto};
-use noah_plonk::plonk::constraint_system::ConstraintSystem;
-use noah_plonk::plonk::indexer::{indexer_with_lagrange, PlonkPK, PlonkVK};
-use noah_plonk::poly_commit::kzg_poly_com::KZGCommitmentSchemeBN254;
+use noah_plonk::plonk::constraint_system::{turbo::N_WIRES_PER_GATE, ConstraintSystem, TurboCS};
+use noah_plonk::plonk::indexer::{indexer_with_lagrange, CircuitId, PlonkPK, PlonkVK};
+use noah_plonk::poly_commit::kzg_poly_com::{KZGCommitment, KZGCommitmentSchemeBN254};
 use noah_plonk::poly_commit::pcs::PolyComScheme;
 use num_traits::Zero;
 use rand_chacha::ChaChaRng;
 use rand_core::SeedableRng;
 
-/// The range in the Bulletproofs range check.
-pub const BULLET_PROOF_RANGE: usize = 32;
-/// The maximal number
-pub const MAX_CONFIDENTIAL_RECORD_NUMBER: usize = 128;
 /// The maximal number of inputs and outputs supported by this setup program, for standard payments.
 pub const MAX_ANONYMOUS_RECORD_NUMBER_STANDARD: usize = 6;
 /// The maximal number of inputs supported by this setup program, for consolidation.
@@ -42,49 +54,111 @@ pub const MAX_ANONYMOUS_RECORD_NUMBER_CONSOLIDATION_SENDER: usize = 7;
 pub const MAX_ANONYMOUS_RECORD_NUMBER_CONSOLIDATION_RECEIVER: usize = 3;
 /// The maximal number of outputs supported by this setup program, for airport.
 pub const MAX_ANONYMOUS_RECORD_NUMBER_ONE_INPUT: usize = 20;
-/// The default number of Bulletproofs generators
-pub const DEFAULT_BP_NUM_GENS: usize = 256;
-/// The number of the Bulletproofs(over the Secq256k1 curve) generators needed for anonymous transfer.
-pub const ANON_XFR_BP_GENS_LEN: usize = 2048;
 
 #[derive(Serialize, Deserialize)]
-/// The verifier parameters.
-pub struct VerifierParams {
+/// The verifier parameters, over the curve of the polynomial commitment scheme, which is BN254
+/// for all the circuits of the anonymous transfers (see `ProverParams`).
+pub struct VerifierParams<PCS: PolyComScheme = KZGCommitmentSchemeBN254> {
     /// A label that describes the prover parameters.
     pub label: String,
     /// The shrunk version of the polynomial commitment scheme.
-    pub shrunk_vk: KZGCommitmentSchemeBN254,
+    pub shrunk_vk: PCS,
     /// The shrunk version of the constraint system.
-    pub shrunk_cs: TurboPlonkCS,
+    pub shrunk_cs: TurboCS<PCS::Field>,
     /// The TurboPlonk verifying key.
-    pub verifier_params: PlonkVK<KZGCommitmentSchemeBN254>,
+    pub verifier_params: PlonkVK<PCS>,
 }
 
 #[derive(Serialize, Deserialize)]
 /// The common part of the verifier parameters.
-pub struct VerifierParamsSplitCommon {
+pub struct VerifierParamsSplitCommon<PCS: PolyComScheme = KZGCommitmentSchemeBN254> {
     /// The shrunk version of the polynomial commitment scheme.
-    pub shrunk_pcs: KZGCommitmentSchemeBN254,
+    pub shrunk_pcs: PCS,
 }
 
 #[derive(Serialize, Deserialize)]
 /// The specific part of the verifier parameters.
-pub struct VerifierParamsSplitSpecific {
+pub struct VerifierParamsSplitSpecific<PCS: PolyComScheme = KZGCommitmentSchemeBN254> {
     /// A label that describes the prover parameters.
     pub label: String,
     /// The shrunk version of the constraint system.
-    pub shrunk_cs: TurboPlonkCS,
+    pub shrunk_cs: TurboCS<PCS::Field>,
     /// The verifier parameters.
-    pub verifier_params: PlonkVK<KZGCommitmentSchemeBN254>,
+    pub verifier_params: PlonkVK<PCS>,
 }
 
-/// The address format.
-#[derive(Copy, Clone)]
-pub enum AddressFormat {
-    /// Secp256k1 address
-    SECP256K1,
-    /// Ed25519 address
-    ED25519,
+impl<PCS: PolyComScheme> ProverParams<PCS> {
+    /// Index the constraint system of a circuit over the curve of the polynomial commitment scheme,
+    /// reusing the verifier parameters if they are given.
+    pub fn from_cs(
+        label: String,
+        cs: TurboCS<PCS::Field>,
+        pcs: PCS,
+        lagrange_pcs: Option<PCS>,
+        verifier_params: Option<PlonkVK<PCS>>,
+    ) -> Result<Self> {
+        let prover_params =
+            indexer_with_lagrange(&cs, &pcs, lagrange_pcs.as_ref(), verifier_params)?;
+
+        Ok(ProverParams {
+            label,
+            pcs,
+            lagrange_pcs,
+            cs,
+            prover_params,
+        })
+    }
+
+    /// Return the identifier of the circuit that the parameters were generated for.
+    pub fn circuit_id(&self) -> CircuitId {
+        self.prover_params.circuit_id()
+    }
+}
+
+impl ProverParams {
+    /// Obtain the parameters of a custom circuit, whose constraint system must be padded,
+    /// against the SRS if it is given, or against the shared SRS of the shipped circuits.
+    ///
+    /// The shared SRS supports up to 8192 constraints. The label
+    /// identifies the circuit, e.g., in the `ParamsRegistry`, and should not be the one of a
+    /// shipped circuit.
+    pub fn from_circuit(
+        label: &str,
+        cs: TurboCS<BN254Scalar>,
+        srs: Option<KZGCommitmentSchemeBN254>,
+    ) -> Result<ProverParams> {
+        let cs_size = cs.size();
+        if !cs_size.is_power_of_two() {
+            return Err(NoahError::ParameterError);
+        }
+
+        let (pcs, lagrange_pcs) = match srs {
+            Some(srs) => (srs, None),
+            None => (load_srs_params(cs_size)?, load_lagrange_params(cs_size)),
+        };
+
+        ProverParams::from_cs(String::from(label), cs, pcs, lagrange_pcs, None)
+    }
+}
+
+impl<PCS: PolyComScheme> VerifierParams<PCS> {
+    /// Return the identifier of the circuit that the parameters were generated for.
+    pub fn circuit_id(&self) -> CircuitId {
+        self.verifier_params.circuit_id()
+    }
+}
+
+impl VerifierParams {
+    /// Obtain the verifier parameters of a custom circuit, as `ProverParams::from_circuit`.
+    pub fn from_circuit(
+        label: &str,
+        cs: TurboCS<BN254Scalar>,
+        srs: Option<KZGCommitmentSchemeBN254>,
+    ) -> Result<VerifierParams> {
+        Ok(VerifierParams::from(ProverParams::from_circuit(
+            label, cs, srs,
+        )?))
+    }
 }
 
 impl ProverParams {
@@ -94,12 +168,70 @@ impl ProverParams {
         n_payees: usize,
         address_format: AddressFormat,
     ) -> Result<ProverParams> {
-        let label = match address_format {
+        let (label, cs) = Self::abar_to_abar_cs(n_payers, n_payees, address_format)?;
+
+        let cs_size = cs.size();
+        let pcs = load_srs_params(cs_size)?;
+        let lagrange_pcs = load_lagrange_params(cs_size);
+
+        let verifier_params =
+            if let Ok(v) = VerifierParams::load_abar_to_abar(n_payers, n_payees, address_format) {
+                Some(v.verifier_params)
+            } else {
+                None
+            };
+
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, verifier_params)
+    }
+
+    /// Obtain the parameters for anonymous transfer over a Merkle tree of the given depth,
+    /// e.g., a shallow one for small test chains, which makes the circuit smaller.
+    /// The depth is at most `TREE_DEPTH`, for which these are the usual parameters.
+    pub fn gen_abar_to_abar_with_depth(
+        n_payers: usize,
+        n_payees: usize,
+        address_format: AddressFormat,
+        tree_depth: usize,
+    ) -> Result<ProverParams> {
+        if tree_depth == TREE_DEPTH {
+            return Self::gen_abar_to_abar(n_payers, n_payees, address_format);
+        }
+        let (label, cs) =
+            Self::abar_to_abar_cs_with_depth(n_payers, n_payees, address_format, tree_depth)?;
+        Self::from_circuit(&label, cs, None)
+    }
+
+    /// Build the constraint system for the anonymous transfer, with its label.
+    pub(crate) fn abar_to_abar_cs(
+        n_payers: usize,
+        n_payees: usize,
+        address_format: AddressFormat,
+    ) -> Result<(String, TurboCS<BN254Scalar>)> {
+        Self::abar_to_abar_cs_with_depth(n_payers, n_payees, address_format, TREE_DEPTH)
+    }
+
+    /// Build the constraint system for the anonymous transfer over a Merkle tree of the depth,
+    /// with its label, which mentions the depth unless it is `TREE_DEPTH`.
+    pub(crate) fn abar_to_abar_cs_with_depth(
+        n_payers: usize,
+        n_payees: usize,
+        address_format: AddressFormat,
+        tree_depth: usize,
+    ) -> Result<(String, TurboCS<BN254Scalar>)> {
+        if tree_depth == 0 || tree_depth > TREE_DEPTH {
+            return Err(NoahError::ParameterError);
+        }
+
+        let mut label = match address_format {
             SECP256K1 => format!("abar_to_abar_{}_to_{}_secp256k1", n_payees, n_payers),
             ED25519 => format!("abar_to_abar_{}_to_{}_ed25519", n_payees, n_payers),
         };
+        if tree_depth != TREE_DEPTH {
+            label.push_str(&format!("_depth_{}", tree_depth));
+        }
 
-        let fake_witness = AXfrWitness::fake(n_payers, n_payees, 0, address_format);
+        let fake_witness =
+            AXfrWitness::fake_with_depth(n_payers, n_payees, 0, address_format, tree_depth);
 
         let mut nullifiers_traces = Vec::new();
         let mut input_commitments_traces = Vec::new();
@@ -140,39 +272,56 @@ impl ProverParams {
             &output_commitments_traces,
             &AXfrAddressFoldingWitness::default(address_format),
         );
+        PublicInputsSchema::abar_to_abar(n_payers, n_payees, address_format).check_cs(&cs)?;
+
+        Ok((label, cs))
+    }
+
+    /// Obtain the parameters for confidential to anonymous.
+    pub fn gen_bar_to_abar() -> Result<ProverParams> {
+        let (label, cs) = Self::bar_to_abar_cs()?;
 
         let cs_size = cs.size();
         let pcs = load_srs_params(cs_size)?;
         let lagrange_pcs = load_lagrange_params(cs_size);
 
-        let verifier_params =
-            if let Ok(v) = VerifierParams::load_abar_to_abar(n_payers, n_payees, address_format) {
-                Some(v.verifier_params)
-            } else {
-                None
-            };
+        let verifier_params = if let Ok(vk) = VerifierParams::load_bar_to_abar() {
+            Some(vk.verifier_params)
+        } else {
+            None
+        };
 
-        let prover_params =
-            indexer_with_lagrange(&cs, &pcs, lagrange_pcs.as_ref(), verifier_params).unwrap();
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, verifier_params)
+    }
 
-        Ok(ProverParams {
-            label,
-            pcs,
-            lagrange_pcs,
-            cs,
-            prover_params,
-        })
+    /// Obtain the parameters for confidential to anonymous, with the public inputs of the
+    /// inspection pre-hashed into one, which shrinks the work of the verifier. No verifier
+    /// parameters are shipped for this circuit, and the notes are verified with the circuit
+    /// of the parameters, e.g., after a rotation in the `ParamsRegistry`.
+    pub fn gen_bar_to_abar_prehashed() -> Result<ProverParams> {
+        let (label, cs) = Self::bar_to_abar_cs_with(true)?;
+        Self::from_circuit(&label, cs, None)
     }
 
-    /// Obtain the parameters for confidential to anonymous.
-    pub fn gen_bar_to_abar() -> Result<ProverParams> {
-        let label = String::from("bar_to_abar");
+    /// Build the constraint system for confidential to anonymous, with its label.
+    pub(crate) fn bar_to_abar_cs() -> Result<(String, TurboCS<BN254Scalar>)> {
+        Self::bar_to_abar_cs_with(false)
+    }
+
+    /// Build the constraint system for confidential to anonymous, with the public inputs of
+    /// the inspection pre-hashed or not, with its label.
+    pub(crate) fn bar_to_abar_cs_with(prehash: bool) -> Result<(String, TurboCS<BN254Scalar>)> {
+        let label = if prehash {
+            String::from(BAR_TO_ABAR_PREHASHED_LABEL)
+        } else {
+            String::from("bar_to_abar")
+        };
         let zero = BN254Scalar::zero();
 
         let proof = DSProofBN254Ristretto {
             inspection_comm: Default::default(),
-            randomizers: vec![RistrettoPoint::default(); 3],
-            response_scalars: vec![(RistrettoScalar::default(), RistrettoScalar::default()); 3],
+            randomizers: vec![RistrettoPoint::default(); 2],
+            response_scalars: vec![(RistrettoScalar::default(), RistrettoScalar::default()); 2],
         };
 
         let non_zk_state = DSInspectionBN254Ristretto {
@@ -181,7 +330,7 @@ impl ProverParams {
                     RistrettoScalar::default(),
                     RistrettoScalar::default()
                 );
-                3
+                2
             ],
             r: BN254Scalar::default(),
             group_phantom: Default::default(),
@@ -207,32 +356,33 @@ impl ProverParams {
             &beta,
             &lambda,
             &output_commitment_trace,
-        );
+            prehash,
+        )?;
+        bar_to_abar_schema(prehash).check_cs(&cs)?;
+
+        Ok((label, cs))
+    }
+
+    /// Obtain the parameters for anonymous to confidential.
+    pub fn gen_abar_to_bar(address_format: AddressFormat) -> Result<ProverParams> {
+        let (label, cs) = Self::abar_to_bar_cs(address_format)?;
 
         let cs_size = cs.size();
         let pcs = load_srs_params(cs_size)?;
         let lagrange_pcs = load_lagrange_params(cs_size);
 
-        let verifier_params = if let Ok(vk) = VerifierParams::load_bar_to_abar() {
-            Some(vk.verifier_params)
-        } else {
-            None
+        let verifier_params = match VerifierParams::load_abar_to_bar(address_format).ok() {
+            Some(v) => Some(v.verifier_params),
+            None => None,
         };
 
-        let prover_params =
-            indexer_with_lagrange(&cs, &pcs, lagrange_pcs.as_ref(), verifier_params).unwrap();
-
-        Ok(ProverParams {
-            label,
-            pcs,
-            lagrange_pcs,
-            cs,
-            prover_params,
-        })
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, verifier_params)
     }
 
-    /// Obtain the parameters for anonymous to confidential.
-    pub fn gen_abar_to_bar(address_format: AddressFormat) -> Result<ProverParams> {
+    /// Build the constraint system for anonymous to confidential, with its label.
+    pub(crate) fn abar_to_bar_cs(
+        address_format: AddressFormat,
+    ) -> Result<(String, TurboCS<BN254Scalar>)> {
         let label = match address_format {
             SECP256K1 => String::from("abar_to_bar_secp256k1"),
             ED25519 => String::from("abar_to_bar_ed25519"),
@@ -307,30 +457,29 @@ impl ProverParams {
             &lambda,
             &AXfrAddressFoldingWitness::default(address_format),
         );
+        PublicInputsSchema::abar_to_bar(address_format).check_cs(&cs)?;
+
+        Ok((label, cs))
+    }
+
+    /// Obtain the parameters for transparent to anonymous.
+    pub fn gen_ar_to_abar() -> Result<ProverParams> {
+        let (label, cs) = Self::ar_to_abar_cs()?;
 
         let cs_size = cs.size();
         let pcs = load_srs_params(cs_size)?;
         let lagrange_pcs = load_lagrange_params(cs_size);
 
-        let verifier_params = match VerifierParams::load_abar_to_bar(address_format).ok() {
+        let verifier_params = match VerifierParams::load_ar_to_abar().ok() {
             Some(v) => Some(v.verifier_params),
             None => None,
         };
 
-        let prover_params =
-            indexer_with_lagrange(&cs, &pcs, lagrange_pcs.as_ref(), verifier_params).unwrap();
-
-        Ok(ProverParams {
-            label,
-            pcs,
-            lagrange_pcs,
-            cs,
-            prover_params,
-        })
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, verifier_params)
     }
 
-    /// Obtain the parameters for transparent to anonymous.
-    pub fn gen_ar_to_abar() -> Result<ProverParams> {
+    /// Build the constraint system for transparent to anonymous, with its label.
+    pub(crate) fn ar_to_abar_cs() -> Result<(String, TurboCS<BN254Scalar>)> {
         let label = String::from("ar_to_abar");
 
         let elem_zero = BN254Scalar::zero();
@@ -354,30 +503,31 @@ impl ProverParams {
             dummy_payee.asset_type,
         )?;
         let (cs, _) = build_ar_to_abar_cs(dummy_payee, &input_commitment_trace);
+        PublicInputsSchema::ar_to_abar().check_cs(&cs)?;
+
+        Ok((label, cs))
+    }
+
+    /// Obtain the parameters for anonymous to transparent.
+    pub fn gen_abar_to_ar(address_format: AddressFormat) -> Result<ProverParams> {
+        let (label, cs) = Self::abar_to_ar_cs(address_format)?;
 
         let cs_size = cs.size();
         let pcs = load_srs_params(cs_size)?;
         let lagrange_pcs = load_lagrange_params(cs_size);
 
-        let verifier_params = match VerifierParams::load_ar_to_abar().ok() {
+        let verifier_params = match VerifierParams::load_abar_to_ar(address_format).ok() {
             Some(v) => Some(v.verifier_params),
             None => None,
         };
 
-        let prover_params =
-            indexer_with_lagrange(&cs, &pcs, lagrange_pcs.as_ref(), verifier_params).unwrap();
-
-        Ok(ProverParams {
-            label,
-            pcs,
-            lagrange_pcs,
-            cs,
-            prover_params,
-        })
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, verifier_params)
     }
 
-    /// Obtain the parameters for anonymous to transparent.
-    pub fn gen_abar_to_ar(address_format: AddressFormat) -> Result<ProverParams> {
+    /// Build the constraint system for anonymous to transparent, with its label.
+    pub(crate) fn abar_to_ar_cs(
+        address_format: AddressFormat,
+    ) -> Result<(String, TurboCS<BN254Scalar>)> {
         let label = match address_format {
             SECP256K1 => String::from("abar_to_ar_secp256k1"),
             ED25519 => String::from("abar_to_ar_ed25519"),
@@ -426,26 +576,306 @@ impl ProverParams {
             &input_commitment_trace,
             &AXfrAddressFoldingWitness::default(address_format),
         );
+        PublicInputsSchema::abar_to_ar(address_format).check_cs(&cs)?;
+
+        Ok((label, cs))
+    }
+
+    /// Obtain the parameters for asset tracing of anonymous records.
+    pub fn gen_abar_tracing() -> Result<ProverParams> {
+        let (label, cs) = Self::abar_tracing_cs()?;
 
         let cs_size = cs.size();
         let pcs = load_srs_params(cs_size)?;
         let lagrange_pcs = load_lagrange_params(cs_size);
 
-        let verifier_params = match VerifierParams::load_abar_to_ar(address_format).ok() {
-            Some(v) => Some(v.verifier_params),
-            None => None,
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, None)
+    }
+
+    /// Build the constraint system for asset tracing of anonymous records, with its label.
+    pub(crate) fn abar_tracing_cs() -> Result<(String, TurboCS<BN254Scalar>)> {
+        let label = String::from("abar_tracing");
+
+        let elem_zero = BN254Scalar::zero();
+
+        // It's okay to choose a fixed seed to build CS.
+        let mut prng = ChaChaRng::from_seed([0u8; 32]);
+
+        // It's okay to choose a fixed address format.
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+        let tracer_keypair = AXfrTracerKeyPair::generate(&mut prng);
+        let randomizer = BabyJubjubScalar::random(&mut prng);
+
+        let dummy_payee = PayeeWitness {
+            amount: 0,
+            blind: elem_zero,
+            asset_type: elem_zero,
+            public_key: keypair.get_pk(),
         };
+        let (commitment_trace, cipher_trace) =
+            gen_abar_tracing_traces(&dummy_payee, &tracer_keypair.enc_key, &randomizer)?;
+        let (cs, _) = build_abar_tracing_cs(
+            &dummy_payee,
+            &tracer_keypair.enc_key,
+            &randomizer,
+            &commitment_trace,
+            &cipher_trace,
+        )?;
+        PublicInputsSchema::abar_tracing().check_cs(&cs)?;
 
-        let prover_params =
-            indexer_with_lagrange(&cs, &pcs, lagrange_pcs.as_ref(), verifier_params).unwrap();
+        Ok((label, cs))
+    }
 
-        Ok(ProverParams {
-            label,
-            pcs,
-            lagrange_pcs,
-            cs,
-            prover_params,
-        })
+    /// Obtain the parameters for disclosing the outputs of anonymous transfers.
+    pub fn gen_abar_disclosure() -> Result<ProverParams> {
+        let (label, cs) = Self::abar_disclosure_cs()?;
+
+        let cs_size = cs.size();
+        let pcs = load_srs_params(cs_size)?;
+        let lagrange_pcs = load_lagrange_params(cs_size);
+
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, None)
+    }
+
+    /// Build the constraint system for disclosing the outputs of anonymous transfers, with its label.
+    pub(crate) fn abar_disclosure_cs() -> Result<(String, TurboCS<BN254Scalar>)> {
+        let label = String::from("abar_disclosure");
+
+        let elem_zero = BN254Scalar::zero();
+
+        // It's okay to choose a fixed seed to build CS.
+        let mut prng = ChaChaRng::from_seed([0u8; 32]);
+
+        // It's okay to choose a fixed address format.
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+
+        let dummy_payee = PayeeWitness {
+            amount: 0,
+            blind: elem_zero,
+            asset_type: elem_zero,
+            public_key: keypair.get_pk(),
+        };
+        let (_, commitment_trace) = commit(
+            &dummy_payee.public_key,
+            dummy_payee.blind,
+            dummy_payee.amount,
+            dummy_payee.asset_type,
+        )?;
+        let (cs, _) = build_abar_disclosure_cs(&dummy_payee, &[true; 3], &commitment_trace)?;
+        PublicInputsSchema::abar_disclosure().check_cs(&cs)?;
+
+        Ok((label, cs))
+    }
+
+    /// Obtain the parameters for the amount range proofs of anonymous records.
+    pub fn gen_abar_amount_range() -> Result<ProverParams> {
+        let (label, cs) = Self::abar_amount_range_cs()?;
+
+        let cs_size = cs.size();
+        let pcs = load_srs_params(cs_size)?;
+        let lagrange_pcs = load_lagrange_params(cs_size);
+
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, None)
+    }
+
+    /// Build the constraint system for the amount range proofs of anonymous records, with its label.
+    pub(crate) fn abar_amount_range_cs() -> Result<(String, TurboCS<BN254Scalar>)> {
+        let label = String::from("abar_amount_range");
+
+        let elem_zero = BN254Scalar::zero();
+
+        // It's okay to choose a fixed seed to build CS.
+        let mut prng = ChaChaRng::from_seed([0u8; 32]);
+
+        // It's okay to choose a fixed address format.
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+
+        let dummy_payee = PayeeWitness {
+            amount: 0,
+            blind: elem_zero,
+            asset_type: elem_zero,
+            public_key: keypair.get_pk(),
+        };
+        let (_, commitment_trace) = commit(
+            &dummy_payee.public_key,
+            dummy_payee.blind,
+            dummy_payee.amount,
+            dummy_payee.asset_type,
+        )?;
+        let (cs, _) = build_abar_amount_range_cs(&dummy_payee, 0, 0, &commitment_trace)?;
+        PublicInputsSchema::abar_amount_range().check_cs(&cs)?;
+
+        Ok((label, cs))
+    }
+
+    /// Obtain the parameters for the transfer of non-fungible records.
+    pub fn gen_nft_transfer(address_format: AddressFormat) -> Result<ProverParams> {
+        let (label, cs) = Self::nft_transfer_cs(address_format)?;
+
+        let cs_size = cs.size();
+        let pcs = load_srs_params(cs_size)?;
+        let lagrange_pcs = load_lagrange_params(cs_size);
+
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, None)
+    }
+
+    /// Build the constraint system for the transfer of non-fungible records, with its label.
+    pub(crate) fn nft_transfer_cs(
+        address_format: AddressFormat,
+    ) -> Result<(String, TurboCS<BN254Scalar>)> {
+        let label = match address_format {
+            SECP256K1 => String::from("nft_transfer_secp256k1"),
+            ED25519 => String::from("nft_transfer_ed25519"),
+        };
+
+        let elem_zero = BN254Scalar::zero();
+
+        // It's okay to choose a fixed seed to build CS.
+        let mut prng = ChaChaRng::from_seed([0u8; 32]);
+
+        let node = MTNode {
+            left: elem_zero,
+            mid: elem_zero,
+            right: elem_zero,
+            is_left_child: 0,
+            is_mid_child: 0,
+            is_right_child: 0,
+        };
+
+        let keypair = KeyPair::sample(&mut prng, address_format);
+        let payer_secret = PayerWitness {
+            secret_key: keypair.get_sk(),
+            uid: 0,
+            amount: NFT_AMOUNT,
+            asset_type: elem_zero,
+            path: MTPath::new(vec![node.clone(); TREE_DEPTH]),
+            blind: elem_zero,
+        };
+        let dummy_payee = PayeeWitness {
+            amount: NFT_AMOUNT,
+            blind: elem_zero,
+            asset_type: elem_zero,
+            public_key: keypair.get_pk(),
+        };
+        let (_, nullifier_trace) = nullify(
+            &keypair,
+            payer_secret.amount,
+            payer_secret.asset_type,
+            payer_secret.uid,
+        )?;
+        let (_, input_commitment_trace) = commit(
+            &keypair.get_pk(),
+            payer_secret.blind,
+            payer_secret.amount,
+            payer_secret.asset_type,
+        )?;
+        let (_, output_commitment_trace) = commit(
+            &dummy_payee.public_key,
+            dummy_payee.blind,
+            dummy_payee.amount,
+            dummy_payee.asset_type,
+        )?;
+
+        let (cs, _) = build_nft_transfer_cs(
+            &payer_secret,
+            &dummy_payee,
+            &nullifier_trace,
+            &input_commitment_trace,
+            &output_commitment_trace,
+            &AXfrAddressFoldingWitness::default(address_format),
+        );
+        PublicInputsSchema::nft_transfer(address_format).check_cs(&cs)?;
+
+        Ok((label, cs))
+    }
+
+    /// Obtain the parameters for the swaps between two anonymous parties, for the given
+    /// numbers of inputs of each party and the given number of outputs.
+    pub fn gen_abar_swap(
+        n_payers: [usize; 2],
+        n_payees: usize,
+        address_formats: [AddressFormat; 2],
+    ) -> Result<ProverParams> {
+        let (label, cs) = Self::abar_swap_cs(n_payers, n_payees, address_formats)?;
+
+        let cs_size = cs.size();
+        let pcs = load_srs_params(cs_size)?;
+        let lagrange_pcs = load_lagrange_params(cs_size);
+
+        ProverParams::from_cs(label, cs, pcs, lagrange_pcs, None)
+    }
+
+    /// Build the constraint system for the swaps between two anonymous parties, with its label.
+    pub(crate) fn abar_swap_cs(
+        n_payers: [usize; 2],
+        n_payees: usize,
+        address_formats: [AddressFormat; 2],
+    ) -> Result<(String, TurboCS<BN254Scalar>)> {
+        if n_payers.contains(&0) || n_payees == 0 {
+            return Err(NoahError::ParameterError);
+        }
+
+        let format_name = |address_format: AddressFormat| match address_format {
+            SECP256K1 => "secp256k1",
+            ED25519 => "ed25519",
+        };
+        let label = format!(
+            "abar_swap_{}_{}_{}_{}_to_{}",
+            n_payers[0],
+            format_name(address_formats[0]),
+            n_payers[1],
+            format_name(address_formats[1]),
+            n_payees
+        );
+
+        let fake_witness = AXfrSwapWitness::fake(n_payers, n_payees, address_formats);
+
+        let mut nullifiers_traces = [vec![], vec![]];
+        let mut input_commitments_traces = [vec![], vec![]];
+        for (party, payers_witnesses) in fake_witness.payers_witnesses.iter().enumerate() {
+            for payer_witness in payers_witnesses.iter() {
+                let keypair = payer_witness.secret_key.clone().into_keypair();
+                let (_, trace) = nullify(
+                    &keypair,
+                    payer_witness.amount,
+                    payer_witness.asset_type,
+                    payer_witness.uid,
+                )?;
+                nullifiers_traces[party].push(trace);
+
+                let (_, trace) = commit(
+                    &keypair.get_pk(),
+                    payer_witness.blind,
+                    payer_witness.amount,
+                    payer_witness.asset_type,
+                )?;
+                input_commitments_traces[party].push(trace);
+            }
+        }
+
+        let mut output_commitments_traces = Vec::new();
+        for payee_witness in fake_witness.payees_witnesses.iter() {
+            let (_, trace) = commit(
+                &payee_witness.public_key,
+                payee_witness.blind,
+                payee_witness.amount,
+                payee_witness.asset_type,
+            )?;
+            output_commitments_traces.push(trace);
+        }
+
+        let folding_witnesses = address_formats.map(AXfrAddressFoldingWitness::default);
+        let (cs, _) = build_abar_swap_cs(
+            &fake_witness,
+            FEE_TYPE.as_scalar(),
+            [&nullifiers_traces[0], &nullifiers_traces[1]],
+            [&input_commitments_traces[0], &input_commitments_traces[1]],
+            &output_commitments_traces,
+            [&folding_witnesses[0], &folding_witnesses[1]],
+        );
+        PublicInputsSchema::abar_swap(n_payers, n_payees, address_formats).check_cs(&cs)?;
+
+        Ok((label, cs))
     }
 }
 
@@ -475,6 +905,25 @@ impl VerifierParams {
         }
     }
 
+    /// Obtain the verifier parameters for anonymous transfer over a Merkle tree of the given depth.
+    pub fn get_abar_to_abar_with_depth(
+        n_payers: usize,
+        n_payees: usize,
+        address_format: AddressFormat,
+        tree_depth: usize,
+    ) -> Result<VerifierParams> {
+        if tree_depth == TREE_DEPTH {
+            return Self::get_abar_to_abar(n_payers, n_payees, address_format);
+        }
+        let prover_params = ProverParams::gen_abar_to_abar_with_depth(
+            n_payers,
+            n_payees,
+            address_format,
+            tree_depth,
+        )?;
+        Ok(VerifierParams::from(prover_params))
+    }
+
     /// Load the verifier parameters from prepare.
     pub fn load_abar_to_abar(
         n_payers: usize,
@@ -482,8 +931,8 @@ impl VerifierParams {
         address_format: AddressFormat,
     ) -> Result<VerifierParams> {
         let verifier_specific_params = match address_format {
-            SECP256K1 => ABAR_TO_ABAR_VERIFIER_SECP256K1_SPECIFIC_PARAMS,
-            ED25519 => ABAR_TO_ABAR_VERIFIER_ED25519_SPECIFIC_PARAMS,
+            SECP256K1 => *ABAR_TO_ABAR_VERIFIER_SECP256K1_SPECIFIC_PARAMS,
+            ED25519 => *ABAR_TO_ABAR_VERIFIER_ED25519_SPECIFIC_PARAMS,
         };
 
         let label = match address_format {
@@ -492,21 +941,17 @@ impl VerifierParams {
         };
 
         match (
-            ABAR_TO_ABAR_VERIFIER_COMMON_PARAMS,
+            *ABAR_TO_ABAR_VERIFIER_COMMON_PARAMS,
             verifier_specific_params,
         ) {
             (Some(c_bytes), Some(s_bytes)) => {
-                let common: VerifierParamsSplitCommon =
-                    bincode::deserialize(c_bytes).map_err(|_| NoahError::DeserializationError)?;
-                let specials: BTreeMap<(usize, usize), Vec<u8>> =
-                    bincode::deserialize(s_bytes).unwrap();
+                let common: VerifierParamsSplitCommon = from_bincode(c_bytes)?;
+                let specials: BTreeMap<(usize, usize), Vec<u8>> = from_bincode(s_bytes).unwrap();
                 let special_bytes = specials.get(&(n_payers, n_payees));
                 if special_bytes.is_none() {
                     return Err(NoahError::DeserializationError);
                 }
-                let special: VerifierParamsSplitSpecific =
-                    bincode::deserialize(special_bytes.unwrap())
-                        .map_err(|_| NoahError::DeserializationError)?;
+                let special: VerifierParamsSplitSpecific = from_bincode(special_bytes.unwrap())?;
 
                 if special.label != label {
                     return Err(NoahError::AXfrVerifierParamsError);
@@ -537,12 +982,12 @@ impl VerifierParams {
     /// Obtain the parameters for anonymous to confidential from prepare.
     pub fn load_abar_to_bar(address_format: AddressFormat) -> Result<VerifierParams> {
         let bytes = match address_format {
-            SECP256K1 => ABAR_TO_BAR_SECP256K1_VERIFIER_PARAMS,
-            ED25519 => ABAR_TO_BAR_ED25519_VERIFIER_PARAMS,
+            SECP256K1 => *ABAR_TO_BAR_SECP256K1_VERIFIER_PARAMS,
+            ED25519 => *ABAR_TO_BAR_ED25519_VERIFIER_PARAMS,
         };
 
         if let Some(bytes) = bytes {
-            let verifier_params = bincode::deserialize::<VerifierParams>(bytes);
+            let verifier_params = from_bincode::<VerifierParams>(bytes);
             if let Ok(verifier_params) = verifier_params {
                 let label = match address_format {
                     SECP256K1 => String::from("abar_to_bar_secp256k1"),
@@ -573,10 +1018,17 @@ impl VerifierParams {
         }
     }
 
+    /// Obtain the parameters for confidential to anonymous, with the public inputs of the
+    /// inspection pre-hashed into one.
+    pub fn get_bar_to_abar_prehashed() -> Result<VerifierParams> {
+        let prover_params = ProverParams::gen_bar_to_abar_prehashed()?;
+        Ok(VerifierParams::from(prover_params))
+    }
+
     /// Obtain the parameters for confidential to anonymous from prepare.
     pub fn load_bar_to_abar() -> Result<VerifierParams> {
-        if let Some(bytes) = BAR_TO_ABAR_VERIFIER_PARAMS {
-            let verifier_params = bincode::deserialize::<VerifierParams>(bytes);
+        if let Some(bytes) = *BAR_TO_ABAR_VERIFIER_PARAMS {
+            let verifier_params = from_bincode::<VerifierParams>(bytes);
             if let Ok(verifier_params) = verifier_params {
                 if verifier_params.label != *"bar_to_abar" {
                     Err(NoahError::MissingVerifierParamsError)
@@ -604,8 +1056,8 @@ impl VerifierParams {
 
     /// Obtain the parameters for transparent to anonymous from prepare.
     pub fn load_ar_to_abar() -> Result<VerifierParams> {
-        if let Some(bytes) = AR_TO_ABAR_VERIFIER_PARAMS {
-            let verifier_params = bincode::deserialize::<VerifierParams>(bytes);
+        if let Some(bytes) = *AR_TO_ABAR_VERIFIER_PARAMS {
+            let verifier_params = from_bincode::<VerifierParams>(bytes);
             if let Ok(verifier_params) = verifier_params {
                 if verifier_params.label != *"ar_to_abar" {
                     Err(NoahError::MissingVerifierParamsError)
@@ -634,12 +1086,12 @@ impl VerifierParams {
     /// Obtain the parameters for anonymous to transparent from prepare.
     pub fn load_abar_to_ar(address_format: AddressFormat) -> Result<VerifierParams> {
         let bytes = match address_format {
-            SECP256K1 => ABAR_TO_AR_SECP256K1_VERIFIER_PARAMS,
-            ED25519 => ABAR_TO_AR_ED25519_VERIFIER_PARAMS,
+            SECP256K1 => *ABAR_TO_AR_SECP256K1_VERIFIER_PARAMS,
+            ED25519 => *ABAR_TO_AR_ED25519_VERIFIER_PARAMS,
         };
 
         if let Some(bytes) = bytes {
-            let verifier_params = bincode::deserialize::<VerifierParams>(bytes);
+            let verifier_params = from_bincode::<VerifierParams>(bytes);
             if let Ok(verifier_params) = verifier_params {
                 let label = match address_format {
                     SECP256K1 => String::from("abar_to_ar_secp256k1"),
@@ -659,8 +1111,93 @@ impl VerifierParams {
         }
     }
 
+    /// Obtain the parameters for asset tracing of anonymous records, which are generated
+    /// on the first call and shared afterwards.
+    pub fn get_abar_tracing() -> Result<&'static VerifierParams> {
+        lazy_static! {
+            static ref ABAR_TRACING_VERIFIER_PARAMS: Result<VerifierParams> =
+                ProverParams::gen_abar_tracing().map(VerifierParams::from);
+        }
+        ABAR_TRACING_VERIFIER_PARAMS.as_ref().map_err(Clone::clone)
+    }
+
+    /// Obtain the parameters for disclosing the outputs of anonymous transfers.
+    pub fn get_abar_disclosure() -> Result<VerifierParams> {
+        let prover_params = ProverParams::gen_abar_disclosure()?;
+        Ok(VerifierParams::from(prover_params))
+    }
+
+    /// Obtain the parameters for the amount range proofs of anonymous records.
+    pub fn get_abar_amount_range() -> Result<VerifierParams> {
+        let prover_params = ProverParams::gen_abar_amount_range()?;
+        Ok(VerifierParams::from(prover_params))
+    }
+
+    /// Obtain the parameters for the transfer of non-fungible records.
+    pub fn get_nft_transfer(address_format: AddressFormat) -> Result<VerifierParams> {
+        let prover_params = ProverParams::gen_nft_transfer(address_format)?;
+        Ok(VerifierParams::from(prover_params))
+    }
+
+    /// Obtain the parameters for the swaps between two anonymous parties.
+    pub fn get_abar_swap(
+        n_payers: [usize; 2],
+        n_payees: usize,
+        address_formats: [AddressFormat; 2],
+    ) -> Result<VerifierParams> {
+        let prover_params = ProverParams::gen_abar_swap(n_payers, n_payees, address_formats)?;
+        Ok(VerifierParams::from(prover_params))
+    }
+
+    /// Extract the verifier parameters from serialized prover parameters, which reads only
+    /// the verification key material and skips over the rest without validating it.
+    pub fn load(bytes: &[u8]) -> Result<VerifierParams> {
+        let view: ProverParamsVerifierView = from_bincode(bytes)?;
+        let pcs = KZGCommitmentSchemeBN254 {
+            public_parameter_group_1: vec![view.pcs.public_parameter_group_1.0],
+            public_parameter_group_2: view.pcs.public_parameter_group_2,
+        };
+
+        let mut cs = TurboCS::new();
+        cs.num_vars = view.cs.num_vars;
+        cs.size = view.cs.size;
+
+        Ok(VerifierParams {
+            label: view.label,
+            shrunk_vk: pcs.shrink_to_verifier_only(),
+            shrunk_cs: cs.shrink_to_verifier_only(),
+            verifier_params: view.prover_params.verifier_params,
+        })
+    }
+
+    /// Obtain the parameters for confidential to anonymous without generating the prover parameters,
+    /// from the compiled-in verifier parameters or the ones installed in the `ParamsCache`.
+    pub fn bar_to_abar_params_compact() -> Result<VerifierParams> {
+        match Self::load_bar_to_abar() {
+            Ok(vk) => Ok(vk),
+            #[cfg(feature = "std")]
+            Err(NoahError::MissingVerifierParamsError) => {
+                let bytes = ParamsCache::get(ParamsCache::BAR_TO_ABAR_VK)
+                    .ok_or(NoahError::MissingVerifierParamsError)?;
+                let verifier_params: VerifierParams = from_bincode(bytes)?;
+                if verifier_params.label != *"bar_to_abar" {
+                    return Err(NoahError::MissingVerifierParamsError);
+                }
+                Ok(verifier_params)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<PCS: PolyComScheme> VerifierParams<PCS> {
     /// Split the verifier parameters to the common part and the sspecific part.
-    pub fn split(self) -> Result<(VerifierParamsSplitCommon, VerifierParamsSplitSpecific)> {
+    pub fn split(
+        self,
+    ) -> Result<(
+        VerifierParamsSplitCommon<PCS>,
+        VerifierParamsSplitSpecific<PCS>,
+    )> {
         Ok((
             VerifierParamsSplitCommon {
                 shrunk_pcs: self.shrunk_vk.shrink_to_verifier_only(),
@@ -674,8 +1211,8 @@ impl VerifierParams {
     }
 }
 
-impl From<ProverParams> for VerifierParams {
-    fn from(params: ProverParams) -> Self {
+impl<PCS: PolyComScheme> From<ProverParams<PCS>> for VerifierParams<PCS> {
+    fn from(params: ProverParams<PCS>) -> Self {
         VerifierParams {
             label: params.label,
             shrunk_vk: params.pcs.shrink_to_verifier_only(),
@@ -686,29 +1223,189 @@ impl From<ProverParams> for VerifierParams {
 }
 
 #[derive(Serialize, Deserialize)]
-/// The prover parameters.
-pub struct ProverParams {
+/// The prover parameters, over the curve of the polynomial commitment scheme.
+///
+/// Only the parameters and their indexing in `from_cs` are generic over the curve. The circuits
+/// of the anonymous transfers, i.e., the `build_*_cs` functions, are built over BN254 only, which
+/// is the default, as their gadgets are: Anemoi-Jive over BN254 and the simulated fields of the
+/// address folding. The other curves serve the circuits that callers build themselves.
+pub struct ProverParams<PCS: PolyComScheme = KZGCommitmentSchemeBN254> {
     /// A label that describes the prover parameters.
     pub label: String,
     /// The full SRS for the polynomial commitment scheme.
-    pub pcs: KZGCommitmentSchemeBN254,
+    pub pcs: PCS,
     /// The Lagrange basis format of SRS.
-    pub lagrange_pcs: Option<KZGCommitmentSchemeBN254>,
+    pub lagrange_pcs: Option<PCS>,
     /// The constraint system.
-    pub cs: TurboPlonkCS,
+    pub cs: TurboCS<PCS::Field>,
     /// The TurboPlonk proving key.
-    pub prover_params: PlonkPK<KZGCommitmentSchemeBN254>,
+    pub prover_params: PlonkPK<PCS>,
+}
+
+/// The polynomial commitment schemes that the anonymous transfers can be proven with, i.e.,
+/// KZG over BN254, whether the SRS is in memory or memory-mapped.
+pub trait AXfrPCS: PolyComScheme<Field = BN254Scalar, Commitment = KZGCommitment<BN254G1>> {}
+
+impl<T: PolyComScheme<Field = BN254Scalar, Commitment = KZGCommitment<BN254G1>>> AXfrPCS for T {}
+
+/// The view of serialized prover parameters that keeps only the verification key material.
+/// The fields follow the layout of `ProverParams`, and the fields after the verifying key
+/// in the proving key are never read.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct ProverParamsVerifierView {
+    label: String,
+    pcs: KZGVerifierView,
+    lagrange_pcs: Option<KZGSkipped>,
+    cs: TurboCSVerifierView,
+    prover_params: PlonkPKVerifierView,
+}
+
+#[derive(Deserialize)]
+struct KZGVerifierView {
+    public_parameter_group_1: FirstOf<BN254G1>,
+    public_parameter_group_2: Vec<BN254G2>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct KZGSkipped {
+    public_parameter_group_1: Vec<Skipped>,
+    public_parameter_group_2: Vec<Skipped>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct TurboCSVerifierView {
+    selectors: Vec<Vec<Skipped>>,
+    wiring: [Vec<usize>; N_WIRES_PER_GATE],
+    anemoi_preprocessed_round_keys_x: [[Skipped; 2]; 14],
+    anemoi_preprocessed_round_keys_y: [[Skipped; 2]; 14],
+    anemoi_generator: Skipped,
+    anemoi_generator_inv: Skipped,
+    anemoi_constraints_indices: Vec<usize>,
+    num_vars: usize,
+    size: usize,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct FpPolynomialSkipped {
+    coefs: Vec<Skipped>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct PlonkPKVerifierView {
+    q_polys: Vec<FpPolynomialSkipped>,
+    s_polys: Vec<FpPolynomialSkipped>,
+    qb_poly: FpPolynomialSkipped,
+    q_prk_polys: Vec<FpPolynomialSkipped>,
+    permutation: Vec<usize>,
+    verifier_params: PlonkVK<KZGCommitmentSchemeBN254>,
+}
+
+/// A serialized scalar or group element that is skipped over.
+struct Skipped;
+
+impl<'de> Deserialize<'de> for Skipped {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        struct SkippedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for SkippedVisitor {
+            type Value = Skipped;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                formatter.write_str("bytes")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(
+                self,
+                _: &[u8],
+            ) -> core::result::Result<Skipped, E> {
+                Ok(Skipped)
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(
+                self,
+                _: Vec<u8>,
+            ) -> core::result::Result<Skipped, E> {
+                Ok(Skipped)
+            }
+        }
+
+        deserializer.deserialize_bytes(SkippedVisitor)
+    }
+}
+
+/// The first element of a serialized sequence, where the other elements are skipped over.
+struct FirstOf<T>(T);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for FirstOf<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        struct FirstOfVisitor<T>(core::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> serde::de::Visitor<'de> for FirstOfVisitor<T> {
+            type Value = FirstOf<T>;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                formatter.write_str("a non-empty sequence")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> core::result::Result<FirstOf<T>, A::Error> {
+                let first = seq
+                    .next_element()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+                while seq.next_element::<Skipped>()?.is_some() {}
+                Ok(FirstOf(first))
+            }
+        }
+
+        deserializer.deserialize_seq(FirstOfVisitor(core::marker::PhantomData))
+    }
+}
+
+/// Return the compiled-in SRS, or the one installed in the `ParamsCache`.
+fn srs_bytes() -> Option<&'static [u8]> {
+    #[cfg(feature = "std")]
+    {
+        SRS.or_else(|| ParamsCache::get(ParamsCache::SRS))
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        SRS
+    }
+}
+
+/// Return the compiled-in Lagrange bases, or the ones installed in the `ParamsCache`.
+fn lagrange_bytes(size: usize) -> Option<&'static [u8]> {
+    let bytes = LAGRANGE_BASES.get(&size).copied();
+    #[cfg(feature = "std")]
+    {
+        bytes.or_else(|| ParamsCache::get(&ParamsCache::lagrange_name(size)))
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        bytes
+    }
 }
 
 fn load_lagrange_params(size: usize) -> Option<KZGCommitmentSchemeBN254> {
-    match LAGRANGE_BASES.get(&size) {
+    match lagrange_bytes(size) {
         None => None,
         Some(bytes) => KZGCommitmentSchemeBN254::from_unchecked_bytes(bytes).ok(),
     }
 }
 
 fn load_srs_params(size: usize) -> Result<KZGCommitmentSchemeBN254> {
-    let srs = SRS.ok_or(NoahError::MissingSRSError)?;
+    let srs = srs_bytes().ok_or(NoahError::MissingSRSError)?;
 
     let KZGCommitmentSchemeBN254 {
         public_parameter_group_1,
@@ -738,48 +1435,286 @@ fn load_srs_params(size: usize) -> Result<KZGCommitmentSchemeBN254> {
 
 #[cfg(test)]
 mod test {
+    use crate::anon_xfr::TREE_DEPTH;
+    use crate::errors::NoahError;
     use crate::parameters::params::load_srs_params;
     use crate::parameters::params::AddressFormat::{ED25519, SECP256K1};
     use crate::parameters::params::ProverParams;
     use crate::parameters::params::VerifierParams;
+    use crate::serialization::{from_bincode, to_bincode};
+    use merlin::Transcript;
     use noah_algebra::{
+        bls12_381::BLSScalar,
         bn254::{BN254Scalar, BN254G1},
         prelude::*,
     };
-    use noah_plonk::poly_commit::{field_polynomial::FpPolynomial, pcs::PolyComScheme};
+    use noah_plonk::plonk::{
+        constraint_system::{ConstraintSystem, TurboCS},
+        prover::prover_with_lagrange,
+        verifier::verifier,
+    };
+    use noah_plonk::poly_commit::{
+        field_polynomial::FpPolynomial,
+        kzg_poly_com::{KZGCommitmentSchemeBLS, KZGCommitmentSchemeBN254},
+        pcs::PolyComScheme,
+    };
 
     #[test]
     fn test_params_serialization() {
         let params = ProverParams::gen_abar_to_abar(1, 1, SECP256K1).unwrap();
 
-        let v = bincode::serialize(&params).unwrap();
-        let params_de: ProverParams = bincode::deserialize(&v).unwrap();
-        let v2 = bincode::serialize(&params_de).unwrap();
+        let v = to_bincode(&params).unwrap();
+        let params_de: ProverParams = from_bincode(&v).unwrap();
+        let v2 = to_bincode(&params_de).unwrap();
         assert_eq!(v, v2);
 
         let params = ProverParams::gen_abar_to_abar(1, 1, ED25519).unwrap();
 
-        let v = bincode::serialize(&params).unwrap();
-        let params_de: ProverParams = bincode::deserialize(&v).unwrap();
-        let v2 = bincode::serialize(&params_de).unwrap();
+        let v = to_bincode(&params).unwrap();
+        let params_de: ProverParams = from_bincode(&v).unwrap();
+        let v2 = to_bincode(&params_de).unwrap();
         assert_eq!(v, v2);
     }
 
     #[test]
     fn test_vk_params_serialization() {
         let params = VerifierParams::get_abar_to_abar(3, 3, SECP256K1).unwrap();
-        let v = bincode::serialize(&params).unwrap();
-        let params_de: VerifierParams = bincode::deserialize(&v).unwrap();
-        let v2 = bincode::serialize(&params_de).unwrap();
+        let v = to_bincode(&params).unwrap();
+        let params_de: VerifierParams = from_bincode(&v).unwrap();
+        let v2 = to_bincode(&params_de).unwrap();
         assert_eq!(v, v2);
 
         let params = VerifierParams::get_abar_to_abar(3, 3, ED25519).unwrap();
-        let v = bincode::serialize(&params).unwrap();
-        let params_de: VerifierParams = bincode::deserialize(&v).unwrap();
-        let v2 = bincode::serialize(&params_de).unwrap();
+        let v = to_bincode(&params).unwrap();
+        let params_de: VerifierParams = from_bincode(&v).unwrap();
+        let v2 = to_bincode(&params_de).unwrap();
         assert_eq!(v, v2);
     }
 
+    #[test]
+    fn test_bar_to_abar_prehashed_cs() {
+        let (label, cs) = ProverParams::bar_to_abar_cs().unwrap();
+        let (prehashed_label, prehashed_cs) = ProverParams::bar_to_abar_cs_with(true).unwrap();
+        assert_eq!(label, "bar_to_abar");
+        assert_eq!(prehashed_label, "bar_to_abar_prehashed");
+        assert_eq!(prehashed_cs.public_vars_witness_indices().len(), 2);
+        assert!(
+            prehashed_cs.public_vars_witness_indices().len()
+                < cs.public_vars_witness_indices().len()
+        );
+    }
+
+    #[test]
+    fn test_abar_to_abar_cs_with_depth() {
+        let (label, cs) = ProverParams::abar_to_abar_cs(1, 1, SECP256K1).unwrap();
+        let (shallow_label, shallow_cs) =
+            ProverParams::abar_to_abar_cs_with_depth(1, 1, SECP256K1, 4).unwrap();
+        assert_eq!(label, "abar_to_abar_1_to_1_secp256k1");
+        assert_eq!(shallow_label, "abar_to_abar_1_to_1_secp256k1_depth_4");
+        assert!(shallow_cs.size() < cs.size());
+
+        assert!(ProverParams::abar_to_abar_cs_with_depth(1, 1, SECP256K1, 0).is_err());
+        assert!(ProverParams::abar_to_abar_cs_with_depth(1, 1, SECP256K1, TREE_DEPTH + 1).is_err());
+    }
+
+    #[test]
+    fn test_params_from_circuit() {
+        let mut prng = test_rng();
+
+        // x * y = z, with z public, over the shared SRS
+        let build_cs = || {
+            let mut cs = TurboCS::<BN254Scalar>::new();
+            let x = cs.new_variable(BN254Scalar::from(3u32));
+            let y = cs.new_variable(BN254Scalar::from(7u32));
+            let z = cs.mul(x, y);
+            cs.prepare_pi_variable(z);
+            cs.pad();
+            cs
+        };
+
+        let mut params = ProverParams::from_circuit("custom", build_cs(), None).unwrap();
+        assert_eq!(params.label, "custom");
+        let witness = params.cs.get_and_clear_witness();
+        let proof = prover_with_lagrange(
+            &mut prng,
+            &mut Transcript::new(b"custom"),
+            &params.pcs,
+            params.lagrange_pcs.as_ref(),
+            &params.cs,
+            &params.prover_params,
+            &witness,
+        )
+        .unwrap();
+
+        let params = VerifierParams::from_circuit("custom", build_cs(), None).unwrap();
+        let online_inputs = [BN254Scalar::from(21u32)];
+        assert!(verifier(
+            &mut Transcript::new(b"custom"),
+            &params.shrunk_vk,
+            &params.shrunk_cs,
+            &params.verifier_params,
+            &online_inputs,
+            &proof,
+        )
+        .is_ok());
+        assert!(verifier(
+            &mut Transcript::new(b"custom"),
+            &params.shrunk_vk,
+            &params.shrunk_cs,
+            &params.verifier_params,
+            &[BN254Scalar::from(22u32)],
+            &proof,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_params_over_another_curve() {
+        let mut prng = test_rng();
+
+        // x * y = z, with z public, over BLS12-381
+        let mut cs = TurboCS::<BLSScalar>::new();
+        let x = cs.new_variable(BLSScalar::from(3u32));
+        let y = cs.new_variable(BLSScalar::from(7u32));
+        let z = cs.mul(x, y);
+        cs.prepare_pi_variable(z);
+        cs.pad();
+
+        let pcs = KZGCommitmentSchemeBLS::new(cs.size() + 3, &mut prng);
+        let mut params = ProverParams::from_cs(String::from("test"), cs, pcs, None, None).unwrap();
+        let witness = params.cs.get_and_clear_witness();
+
+        let proof = prover_with_lagrange(
+            &mut prng,
+            &mut Transcript::new(b"test"),
+            &params.pcs,
+            params.lagrange_pcs.as_ref(),
+            &params.cs,
+            &params.prover_params,
+            &witness,
+        )
+        .unwrap();
+
+        let params = VerifierParams::from(params);
+        let bytes = to_bincode(&params).unwrap();
+        let params: VerifierParams<KZGCommitmentSchemeBLS> = from_bincode(&bytes).unwrap();
+
+        let online_inputs = [BLSScalar::from(21u32)];
+        assert!(verifier(
+            &mut Transcript::new(b"test"),
+            &params.shrunk_vk,
+            &params.shrunk_cs,
+            &params.verifier_params,
+            &online_inputs,
+            &proof,
+        )
+        .is_ok());
+
+        let online_inputs = [BLSScalar::from(20u32)];
+        assert!(verifier(
+            &mut Transcript::new(b"test"),
+            &params.shrunk_vk,
+            &params.shrunk_cs,
+            &params.verifier_params,
+            &online_inputs,
+            &proof,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_load_verifier_params() {
+        let mut prng = test_rng();
+
+        let mut cs = TurboCS::<BN254Scalar>::new();
+        let x = cs.new_variable(BN254Scalar::from(3u32));
+        let y = cs.new_variable(BN254Scalar::from(7u32));
+        let z = cs.mul(x, y);
+        cs.prepare_pi_variable(z);
+        cs.pad();
+
+        let pcs = KZGCommitmentSchemeBN254::new(cs.size() + 3, &mut prng);
+        let lagrange_pcs = KZGCommitmentSchemeBN254::new(cs.size() + 3, &mut prng);
+        let params =
+            ProverParams::from_cs(String::from("test"), cs, pcs, Some(lagrange_pcs), None).unwrap();
+        let bytes = to_bincode(&params).unwrap();
+
+        let loaded = VerifierParams::load(&bytes).unwrap();
+        let expected = VerifierParams::from(params);
+        assert_eq!(to_bincode(&loaded).unwrap(), to_bincode(&expected).unwrap());
+
+        assert!(VerifierParams::load(&bytes[..16]).is_err());
+    }
+
+    #[test]
+    fn test_params_mismatch() {
+        let mut prng = test_rng();
+
+        // x * y = z, with z public
+        let mut cs_a = TurboCS::<BN254Scalar>::new();
+        let x = cs_a.new_variable(BN254Scalar::from(3u32));
+        let y = cs_a.new_variable(BN254Scalar::from(7u32));
+        let z = cs_a.mul(x, y);
+        cs_a.prepare_pi_variable(z);
+        cs_a.pad();
+
+        // x * y = z and z + x = w, with z and w public
+        let mut cs_b = TurboCS::<BN254Scalar>::new();
+        let x = cs_b.new_variable(BN254Scalar::from(3u32));
+        let y = cs_b.new_variable(BN254Scalar::from(7u32));
+        let z = cs_b.mul(x, y);
+        let w = cs_b.add(z, x);
+        cs_b.prepare_pi_variable(z);
+        cs_b.prepare_pi_variable(w);
+        cs_b.pad();
+        let witness_b = cs_b.get_and_clear_witness();
+
+        let pcs = KZGCommitmentSchemeBN254::new(cs_b.size() + 3, &mut prng);
+        let mut params_a =
+            ProverParams::from_cs(String::from("a"), cs_a, pcs.clone(), None, None).unwrap();
+        let params_b = ProverParams::from_cs(String::from("b"), cs_b, pcs, None, None).unwrap();
+        let _ = params_a.cs.get_and_clear_witness();
+        assert_ne!(params_a.circuit_id(), params_b.circuit_id());
+
+        // the witness of one circuit against the parameters of another
+        let res = prover_with_lagrange(
+            &mut prng,
+            &mut Transcript::new(b"test"),
+            &params_b.pcs,
+            params_b.lagrange_pcs.as_ref(),
+            &params_a.cs,
+            &params_a.prover_params,
+            &witness_b,
+        );
+        assert_eq!(NoahError::from(res.unwrap_err()), NoahError::ParamsMismatch);
+
+        let proof = prover_with_lagrange(
+            &mut prng,
+            &mut Transcript::new(b"test"),
+            &params_b.pcs,
+            params_b.lagrange_pcs.as_ref(),
+            &params_b.cs,
+            &params_b.prover_params,
+            &witness_b,
+        )
+        .unwrap();
+
+        let id_a = params_a.circuit_id();
+        let verifier_params_a = VerifierParams::from(params_a);
+        assert_eq!(verifier_params_a.circuit_id(), id_a);
+
+        let online_inputs = [BN254Scalar::from(21u32), BN254Scalar::from(24u32)];
+        let res = verifier(
+            &mut Transcript::new(b"test"),
+            &verifier_params_a.shrunk_vk,
+            &verifier_params_a.shrunk_cs,
+            &verifier_params_a.verifier_params,
+            &online_inputs,
+            &proof,
+        );
+        assert_eq!(NoahError::from(res.unwrap_err()), NoahError::ParamsMismatch);
+    }
+
     #[test]
     fn test_crs_commit() {
         let pcs = load_srs_params(16).unwrap();
@@ -802,4 +1737,20 @@ mod test {
         }
         assert_eq!(expected_committed_value, commitment.0);
     }
+
+    #[cfg(all(feature = "compressed_vk", not(feature = "no_vk")))]
+    #[test]
+    fn test_compressed_verifier_params() {
+        use crate::parameters::{AR_TO_ABAR_VERIFIER_PARAMS, BAR_TO_ABAR_VERIFIER_PARAMS};
+
+        assert_eq!(
+            *AR_TO_ABAR_VERIFIER_PARAMS,
+            Some(&include_bytes!("../../parameters/ar-to-abar-vk.bin")[..])
+        );
+        assert_eq!(
+            *BAR_TO_ABAR_VERIFIER_PARAMS,
+            Some(&include_bytes!("../../parameters/bar-to-abar-vk.bin")[..])
+        );
+        assert!(VerifierParams::load_ar_to_abar().is_ok());
+    }
 }
@@ -0,0 +1,141 @@
+use crate::errors::{NoahError, Result};
+use crate::parameters::params::VerifierParams;
+use noah_algebra::{collections::BTreeMap, prelude::*};
+
+/// A registry of the verifier parameters of the circuits, where the parameters of a circuit
+/// can be rotated at scheduled heights.
+///
+/// The parameters activated at height `H` verify the proofs at heights `H` and above, until
+/// the next rotation, so that a circuit upgrade takes effect at a height decided on-chain.
+#[derive(Default)]
+pub struct ParamsRegistry {
+    schedules: BTreeMap<String, BTreeMap<u64, VerifierParams>>,
+}
+
+impl ParamsRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedule the verifier parameters of the circuit to be activated at the height.
+    pub fn schedule_rotation(
+        &mut self,
+        circuit: &str,
+        activation_height: u64,
+        params: VerifierParams,
+    ) -> Result<()> {
+        let schedule = self.schedules.entry(String::from(circuit)).or_default();
+        if schedule.contains_key(&activation_height) {
+            return Err(NoahError::ParameterError);
+        }
+        schedule.insert(activation_height, params);
+        Ok(())
+    }
+
+    /// Cancel the rotation of the circuit scheduled at the height, returning its parameters.
+    pub fn cancel_rotation(
+        &mut self,
+        circuit: &str,
+        activation_height: u64,
+    ) -> Result<VerifierParams> {
+        let schedule = self
+            .schedules
+            .get_mut(circuit)
+            .ok_or(NoahError::ParameterError)?;
+        let params = schedule
+            .remove(&activation_height)
+            .ok_or(NoahError::ParameterError)?;
+        if schedule.is_empty() {
+            self.schedules.remove(circuit);
+        }
+        Ok(params)
+    }
+
+    /// Select the verifier parameters of the circuit that are active at the height.
+    pub fn select_params(&self, circuit: &str, height: u64) -> Result<&VerifierParams> {
+        self.schedules
+            .get(circuit)
+            .and_then(|schedule| schedule.range(..=height).next_back())
+            .map(|(_, params)| params)
+            .ok_or(NoahError::MissingVerifierParamsError)
+    }
+
+    /// Return the heights at which the parameters of the circuit are rotated, in order.
+    pub fn activation_heights(&self, circuit: &str) -> Vec<u64> {
+        self.schedules
+            .get(circuit)
+            .map(|schedule| schedule.keys().copied().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::errors::NoahError;
+    use crate::parameters::{params::VerifierParams, registry::ParamsRegistry};
+
+    #[test]
+    fn test_select_params_by_height() {
+        let mut registry = ParamsRegistry::new();
+        assert!(registry.select_params("ar_to_abar", 0).is_err());
+
+        // The parameters of another circuit stand in for an upgraded circuit.
+        registry
+            .schedule_rotation("ar_to_abar", 10, VerifierParams::get_ar_to_abar().unwrap())
+            .unwrap();
+        registry
+            .schedule_rotation(
+                "ar_to_abar",
+                100,
+                VerifierParams::get_bar_to_abar().unwrap(),
+            )
+            .unwrap();
+        assert_eq!(
+            registry.schedule_rotation(
+                "ar_to_abar",
+                100,
+                VerifierParams::get_ar_to_abar().unwrap()
+            ),
+            Err(NoahError::ParameterError)
+        );
+        assert_eq!(registry.activation_heights("ar_to_abar"), vec![10, 100]);
+
+        // nothing is active before the first activation
+        assert_eq!(
+            registry.select_params("ar_to_abar", 9).err(),
+            Some(NoahError::MissingVerifierParamsError)
+        );
+        assert_eq!(
+            registry.select_params("ar_to_abar", 10).unwrap().label,
+            "ar_to_abar"
+        );
+        assert_eq!(
+            registry.select_params("ar_to_abar", 99).unwrap().label,
+            "ar_to_abar"
+        );
+        assert_eq!(
+            registry.select_params("ar_to_abar", 100).unwrap().label,
+            "bar_to_abar"
+        );
+        assert_eq!(
+            registry
+                .select_params("ar_to_abar", u64::MAX)
+                .unwrap()
+                .label,
+            "bar_to_abar"
+        );
+        assert!(registry.select_params("bar_to_abar", 100).is_err());
+
+        // a cancelled rotation never activates
+        assert_eq!(
+            registry.cancel_rotation("ar_to_abar", 100).unwrap().label,
+            "bar_to_abar"
+        );
+        assert_eq!(
+            registry.select_params("ar_to_abar", 100).unwrap().label,
+            "ar_to_abar"
+        );
+        assert!(registry.cancel_rotation("ar_to_abar", 100).is_err());
+    }
+}
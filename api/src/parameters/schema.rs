@@ -0,0 +1,341 @@
+use crate::anon_xfr::asset_tracing::AXFR_TRACER_MEMO_LEN;
+use crate::errors::{NoahError, Result};
+use crate::parameters::AddressFormat;
+use ark_std::vec::Vec;
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use noah_crypto::field_simulation::{
+    SimFrParams, SimFrParamsBN254Ristretto, SimFrParamsBN254Secq256k1, SimFrParamsBN254Zorro,
+};
+use noah_plonk::plonk::constraint_system::{ConstraintSystem, TurboCS};
+
+/// The type of a public input, which restricts the values it may take.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PublicInputType {
+    /// Any scalar, e.g., a commitment, a nullifier, or a Merkle tree root.
+    Scalar,
+    /// An integer that fits in 64 bits, e.g., an amount.
+    U64,
+    /// Either zero or one.
+    Bit,
+}
+
+impl PublicInputType {
+    /// Check if the value is of this type.
+    pub fn check(&self, value: &BN254Scalar) -> bool {
+        match self {
+            PublicInputType::Scalar => true,
+            PublicInputType::U64 => value.to_bytes()[8..].iter().all(|b| *b == 0),
+            PublicInputType::Bit => value.is_zero() || value.is_one(),
+        }
+    }
+}
+
+/// A group of consecutive public inputs that share a name and a type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicInputsEntry {
+    /// The name of the public inputs.
+    pub name: &'static str,
+    /// The type of the public inputs.
+    pub ty: PublicInputType,
+    /// The number of the public inputs.
+    pub count: usize,
+}
+
+/// The declared layout of the public inputs of a circuit, in the order the circuit
+/// prepares them, against which the prover-built and verifier-built inputs are checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PublicInputsSchema {
+    /// The entries of the schema.
+    pub entries: Vec<PublicInputsEntry>,
+}
+
+impl PublicInputsSchema {
+    /// Create an empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `count` public inputs with the name and the type.
+    pub fn with(mut self, name: &'static str, ty: PublicInputType, count: usize) -> Self {
+        self.entries.push(PublicInputsEntry { name, ty, count });
+        self
+    }
+
+    /// Append the entries of another schema.
+    pub fn extend(mut self, other: PublicInputsSchema) -> Self {
+        self.entries.extend(other.entries);
+        self
+    }
+
+    /// Return the total number of public inputs.
+    pub fn len(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+
+    /// Return true if the schema declares no public inputs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the name of the public input at the index.
+    pub fn name_of(&self, mut index: usize) -> Option<&'static str> {
+        for entry in self.entries.iter() {
+            if index < entry.count {
+                return Some(entry.name);
+            }
+            index -= entry.count;
+        }
+        None
+    }
+
+    /// Check that the constraint system prepares as many public inputs as the schema declares.
+    pub fn check_cs(&self, cs: &TurboCS<BN254Scalar>) -> Result<()> {
+        if cs.public_vars_witness_indices().len() != self.len() {
+            return Err(NoahError::PublicInputsSchemaError);
+        }
+        Ok(())
+    }
+
+    /// Check that the public inputs match the schema, in the number and in the types.
+    pub fn validate(&self, inputs: &[BN254Scalar]) -> Result<()> {
+        if inputs.len() != self.len() {
+            return Err(NoahError::PublicInputsSchemaError);
+        }
+
+        let mut inputs = inputs.iter();
+        for entry in self.entries.iter() {
+            for value in inputs.by_ref().take(entry.count) {
+                if !entry.ty.check(value) {
+                    return Err(NoahError::PublicInputsSchemaError);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the public inputs that the prover obtains from the witness match the schema.
+    pub fn validate_witness(
+        &self,
+        cs: &TurboCS<BN254Scalar>,
+        witness: &[BN254Scalar],
+    ) -> Result<()> {
+        let mut inputs = Vec::with_capacity(self.len());
+        for index in cs.public_vars_witness_indices().iter() {
+            inputs.push(
+                *witness
+                    .get(*index)
+                    .ok_or(NoahError::PublicInputsSchemaError)?,
+            );
+        }
+        self.validate(&inputs)
+    }
+
+    /// The public inputs of the address folding, which follow those of the circuits that spend.
+    pub fn address_folding(address_format: AddressFormat) -> Self {
+        let limbs = match address_format {
+            AddressFormat::SECP256K1 => SimFrParamsBN254Secq256k1::NUM_OF_LIMBS,
+            AddressFormat::ED25519 => SimFrParamsBN254Zorro::NUM_OF_LIMBS,
+        };
+        Self::new()
+            .with("inspection_comm", PublicInputType::Scalar, 1)
+            .with("lambda_series_limbs", PublicInputType::Scalar, 2 * limbs)
+            .with(
+                "beta_lambda_series_limbs",
+                PublicInputType::Scalar,
+                3 * limbs,
+            )
+            .with("combined_response_limbs", PublicInputType::Scalar, limbs)
+    }
+
+    /// The public inputs of the delegated Schnorr inspection over Ristretto.
+    fn ristretto_inspection() -> Self {
+        let limbs = SimFrParamsBN254Ristretto::NUM_OF_LIMBS;
+        Self::new()
+            .with("inspection_comm", PublicInputType::Scalar, 1)
+            .with("beta_limbs", PublicInputType::Scalar, limbs)
+            .with("lambda_limbs", PublicInputType::Scalar, limbs)
+            .with("beta_lambda_limbs", PublicInputType::Scalar, limbs)
+            .with("s1_plus_lambda_s2_limbs", PublicInputType::Scalar, limbs)
+    }
+
+    /// The public inputs of the anonymous transfer circuit.
+    pub fn abar_to_abar(n_payers: usize, n_payees: usize, address_format: AddressFormat) -> Self {
+        Self::new()
+            .with("nullifiers", PublicInputType::Scalar, n_payers)
+            .with("merkle_root", PublicInputType::Scalar, 1)
+            .with("output_commitments", PublicInputType::Scalar, n_payees)
+            .with("fee", PublicInputType::U64, 1)
+            .extend(Self::address_folding(address_format))
+    }
+
+    /// The public inputs of the confidential-to-anonymous circuit.
+    pub fn bar_to_abar() -> Self {
+        Self::new()
+            .with("output_commitment", PublicInputType::Scalar, 1)
+            .extend(Self::ristretto_inspection())
+    }
+
+    /// The public inputs of the confidential-to-anonymous circuit whose inspection inputs
+    /// are pre-hashed into one.
+    pub fn bar_to_abar_prehashed() -> Self {
+        Self::new()
+            .with("output_commitment", PublicInputType::Scalar, 1)
+            .with("inspection_inputs_hash", PublicInputType::Scalar, 1)
+    }
+
+    /// The public inputs of the anonymous-to-confidential circuit.
+    pub fn abar_to_bar(address_format: AddressFormat) -> Self {
+        Self::new()
+            .with("nullifier", PublicInputType::Scalar, 1)
+            .with("merkle_root", PublicInputType::Scalar, 1)
+            .extend(Self::ristretto_inspection())
+            .extend(Self::address_folding(address_format))
+    }
+
+    /// The public inputs of the transparent-to-anonymous circuit.
+    pub fn ar_to_abar() -> Self {
+        Self::new()
+            .with("amount", PublicInputType::U64, 1)
+            .with("asset_type", PublicInputType::Scalar, 1)
+            .with("output_commitment", PublicInputType::Scalar, 1)
+    }
+
+    /// The public inputs of the anonymous-to-transparent circuit.
+    pub fn abar_to_ar(address_format: AddressFormat) -> Self {
+        Self::new()
+            .with("nullifier", PublicInputType::Scalar, 1)
+            .with("merkle_root", PublicInputType::Scalar, 1)
+            .with("amount", PublicInputType::U64, 1)
+            .with("asset_type", PublicInputType::Scalar, 1)
+            .extend(Self::address_folding(address_format))
+    }
+
+    /// The public inputs of the asset tracing circuit.
+    pub fn abar_tracing() -> Self {
+        Self::new()
+            .with("commitment", PublicInputType::Scalar, 1)
+            .with("enc_key", PublicInputType::Scalar, 2)
+            .with("dh_point", PublicInputType::Scalar, 2)
+            .with("ciphertext", PublicInputType::Scalar, AXFR_TRACER_MEMO_LEN)
+    }
+
+    /// The public inputs of the disclosure circuit.
+    pub fn abar_disclosure() -> Self {
+        Self::new()
+            .with("commitment", PublicInputType::Scalar, 1)
+            .with("amount_mask", PublicInputType::Bit, 1)
+            .with("amount", PublicInputType::U64, 1)
+            .with("asset_type_mask", PublicInputType::Bit, 1)
+            .with("asset_type", PublicInputType::Scalar, 1)
+            .with("recipient_mask", PublicInputType::Bit, 1)
+            .with("recipient_type", PublicInputType::Bit, 1)
+            .with("recipient", PublicInputType::Scalar, 3)
+    }
+
+    /// The public inputs of the circuit for the transfer of non-fungible records.
+    pub fn nft_transfer(address_format: AddressFormat) -> Self {
+        Self::new()
+            .with("nullifier", PublicInputType::Scalar, 1)
+            .with("merkle_root", PublicInputType::Scalar, 1)
+            .with("output_commitment", PublicInputType::Scalar, 1)
+            .extend(Self::address_folding(address_format))
+    }
+
+    /// The public inputs of the swap circuit, which spends the inputs of two parties.
+    pub fn abar_swap(
+        n_payers: [usize; 2],
+        n_payees: usize,
+        address_formats: [AddressFormat; 2],
+    ) -> Self {
+        Self::new()
+            .with("first_nullifiers", PublicInputType::Scalar, n_payers[0])
+            .with("second_nullifiers", PublicInputType::Scalar, n_payers[1])
+            .with("merkle_root", PublicInputType::Scalar, 1)
+            .with("output_commitments", PublicInputType::Scalar, n_payees)
+            .with("fee", PublicInputType::U64, 1)
+            .extend(Self::address_folding(address_formats[0]))
+            .extend(Self::address_folding(address_formats[1]))
+    }
+
+    /// The public inputs of the amount range circuit.
+    pub fn abar_amount_range() -> Self {
+        Self::new()
+            .with("commitment", PublicInputType::Scalar, 1)
+            .with("lower", PublicInputType::U64, 1)
+            .with("upper", PublicInputType::U64, 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parameters::schema::{PublicInputType, PublicInputsSchema};
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    #[test]
+    fn test_public_inputs_schema() {
+        let schema = PublicInputsSchema::ar_to_abar();
+        assert_eq!(schema.len(), 3);
+        assert_eq!(schema.name_of(1), Some("asset_type"));
+        assert_eq!(schema.name_of(3), None);
+
+        let inputs = vec![
+            BN254Scalar::from(10u64),
+            BN254Scalar::one(),
+            BN254Scalar::one(),
+        ];
+        assert!(schema.validate(&inputs).is_ok());
+
+        // a missing input is rejected
+        assert!(schema.validate(&inputs[..2]).is_err());
+
+        // an amount beyond 64 bits is rejected
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[0] = BN254Scalar::from(u64::MAX) + BN254Scalar::one();
+        assert!(schema.validate(&wrong_inputs).is_err());
+
+        let schema = PublicInputsSchema::new().with("mask", PublicInputType::Bit, 1);
+        assert!(schema.validate(&[BN254Scalar::from(2u32)]).is_err());
+
+        assert_eq!(
+            PublicInputsSchema::abar_to_abar(2, 3, SECP256K1).len(),
+            7 + PublicInputsSchema::address_folding(SECP256K1).len()
+        );
+        assert_eq!(
+            PublicInputsSchema::abar_swap([1, 2], 2, [ED25519, SECP256K1]).len(),
+            6 + PublicInputsSchema::address_folding(ED25519).len()
+                + PublicInputsSchema::address_folding(SECP256K1).len()
+        );
+    }
+
+    #[test]
+    fn test_public_inputs_schema_of_circuits() {
+        use crate::anon_xfr::{ar_to_abar::build_ar_to_abar_cs, commit, structs::PayeeWitness};
+        use crate::keys::KeyPair;
+
+        let mut prng = test_rng();
+        let payee = PayeeWitness {
+            amount: 10,
+            blind: BN254Scalar::random(&mut prng),
+            asset_type: BN254Scalar::random(&mut prng),
+            public_key: KeyPair::sample(&mut prng, SECP256K1).get_pk(),
+        };
+        let (_, trace) = commit(
+            &payee.public_key,
+            payee.blind,
+            payee.amount,
+            payee.asset_type,
+        )
+        .unwrap();
+        let (mut cs, _) = build_ar_to_abar_cs(payee, &trace);
+        let schema = PublicInputsSchema::ar_to_abar();
+        assert!(schema.check_cs(&cs).is_ok());
+
+        let witness = cs.get_and_clear_witness();
+        assert!(schema.validate_witness(&cs, &witness).is_ok());
+        assert!(PublicInputsSchema::abar_amount_range()
+            .with("extra", PublicInputType::Scalar, 1)
+            .check_cs(&cs)
+            .is_err());
+    }
+}
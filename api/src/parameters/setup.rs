@@ -8,8 +8,11 @@
 use ark_bulletproofs::BulletproofGens as BulletproofGensOverSecq256k1;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 use bulletproofs::BulletproofGens;
+use noah::parameters::ceremony::Ceremony;
 use noah::parameters::{AddressFormat, SRS};
-use noah_algebra::bn254::BN254G1;
+use noah::serialization::{from_bincode, to_bincode};
+use noah::setup::{self, Circuit};
+use noah_algebra::bn254::{BN254PairingEngine, BN254G1};
 use noah_algebra::secq256k1::{PedersenCommitmentSecq256k1, Secq256k1BulletproofGens};
 use noah_algebra::utils::save_to_file;
 use noah_plonk::poly_commit::kzg_poly_com::KZGCommitmentSchemeBN254;
@@ -66,6 +69,18 @@ enum Actions {
     /// Cut the SRS, adapt to Lagrange, and only save the minimum 2^11, 2^12, and 2^13 padding
     CUT_SRS { directory: PathBuf },
 
+    /// Starts an SRS ceremony for polynomials up to the maximal degree
+    CEREMONY_INIT { max_degree: usize, file: PathBuf },
+
+    /// Adds a contribution with fresh randomness to the SRS ceremony
+    CEREMONY_CONTRIBUTE { file: PathBuf },
+
+    /// Verifies the SRS ceremony and saves the resulting SRS
+    CEREMONY_FINALIZE { file: PathBuf, directory: PathBuf },
+
+    /// Generates the prover and verifier parameters of every circuit over the given SRS
+    GEN { srs: PathBuf, directory: PathBuf },
+
     /// Generates all necessary parameters
     ALL { directory: PathBuf },
 }
@@ -103,6 +118,14 @@ fn main() {
 
         CUT_SRS { directory } => cut_srs(directory),
 
+        CEREMONY_INIT { max_degree, file } => ceremony_init(max_degree, file),
+
+        CEREMONY_CONTRIBUTE { file } => ceremony_contribute(file),
+
+        CEREMONY_FINALIZE { file, directory } => ceremony_finalize(file, directory),
+
+        GEN { srs, directory } => gen_with_srs(srs, directory),
+
         ALL { directory } => gen_all(directory),
     };
 }
@@ -121,7 +144,7 @@ fn gen_transfer_vk(directory: PathBuf, address_format: AddressFormat) {
 
     let transfer_params = VerifierParams::get_abar_to_abar(1, 1, address_format).unwrap();
     let (common, _) = transfer_params.split().unwrap();
-    let common_ser = bincode::serialize(&common).unwrap();
+    let common_ser = to_bincode(&common).unwrap();
 
     let mut common_path = directory.clone();
     common_path.push("transfer-vk-common.bin");
@@ -151,7 +174,7 @@ fn gen_transfer_vk(directory: PathBuf, address_format: AddressFormat) {
             specials_sync
                 .lock()
                 .unwrap()
-                .insert((*i, *j), bincode::serialize(&special).unwrap());
+                .insert((*i, *j), to_bincode(&special).unwrap());
         });
     });
 
@@ -174,7 +197,7 @@ fn gen_transfer_vk(directory: PathBuf, address_format: AddressFormat) {
             specials_sync
                 .lock()
                 .unwrap()
-                .insert((*i, *j), bincode::serialize(&special).unwrap());
+                .insert((*i, *j), to_bincode(&special).unwrap());
         });
     });
 
@@ -183,7 +206,7 @@ fn gen_transfer_vk(directory: PathBuf, address_format: AddressFormat) {
         specials.insert(*idx, v.clone());
     }
 
-    let specials_ser = bincode::serialize(&specials).unwrap();
+    let specials_ser = to_bincode(&specials).unwrap();
     let mut specials_path = directory.clone();
     match address_format {
         SECP256K1 => specials_path.push("transfer-vk-secp256k1-specific.bin"),
@@ -202,12 +225,12 @@ fn gen_abar_to_bar_vk(path: PathBuf) {
         "the size of the constraint system for ABAR TO BAR for secp256k1: {}",
         node_params.shrunk_cs.size
     );
-    let bytes = bincode::serialize(&node_params).unwrap();
+    let bytes = to_bincode(&node_params).unwrap();
     new_path.push("abar-to-bar-vk-secp256k1.bin");
     save_to_file(&bytes, new_path);
 
     let start = std::time::Instant::now();
-    let _n: VerifierParams = bincode::deserialize(&bytes).unwrap();
+    let _n: VerifierParams = from_bincode(&bytes).unwrap();
     let elapsed = start.elapsed();
     println!("Deserialize time: {:.2?}", elapsed);
 
@@ -219,12 +242,12 @@ fn gen_abar_to_bar_vk(path: PathBuf) {
         "the size of the constraint system for ABAR TO BAR for ed25519: {}",
         node_params.shrunk_cs.size
     );
-    let bytes = bincode::serialize(&node_params).unwrap();
+    let bytes = to_bincode(&node_params).unwrap();
     new_path.push("abar-to-bar-vk-ed25519.bin");
     save_to_file(&bytes, new_path);
 
     let start = std::time::Instant::now();
-    let _n: VerifierParams = bincode::deserialize(&bytes).unwrap();
+    let _n: VerifierParams = from_bincode(&bytes).unwrap();
     let elapsed = start.elapsed();
     println!("Deserialize time: {:.2?}", elapsed);
 }
@@ -239,12 +262,12 @@ fn gen_bar_to_abar_vk(mut path: PathBuf) {
         "the size of the constraint system for BAR TO ABAR: {}",
         node_params.shrunk_cs.size
     );
-    let bytes = bincode::serialize(&node_params).unwrap();
+    let bytes = to_bincode(&node_params).unwrap();
     path.push("bar-to-abar-vk.bin");
     save_to_file(&bytes, path);
 
     let start = std::time::Instant::now();
-    let _n: VerifierParams = bincode::deserialize(&bytes).unwrap();
+    let _n: VerifierParams = from_bincode(&bytes).unwrap();
     let elapsed = start.elapsed();
     println!("Deserialize time: {:.2?}", elapsed);
 }
@@ -259,12 +282,12 @@ fn gen_ar_to_abar_vk(mut path: PathBuf) {
         "the size of the constraint system for AR TO ABAR: {}",
         node_params.shrunk_cs.size
     );
-    let bytes = bincode::serialize(&node_params).unwrap();
+    let bytes = to_bincode(&node_params).unwrap();
     path.push("ar-to-abar-vk.bin");
     save_to_file(&bytes, path);
 
     let start = std::time::Instant::now();
-    let _n: VerifierParams = bincode::deserialize(&bytes).unwrap();
+    let _n: VerifierParams = from_bincode(&bytes).unwrap();
     let elapsed = start.elapsed();
     println!("Deserialize time: {:.2?}", elapsed);
 }
@@ -279,12 +302,12 @@ fn gen_abar_to_ar_vk(path: PathBuf) {
         "the size of the constraint system for ABAR TO AR for secp256k1: {}",
         node_params.shrunk_cs.size
     );
-    let bytes = bincode::serialize(&node_params).unwrap();
+    let bytes = to_bincode(&node_params).unwrap();
     new_path.push("abar-to-ar-vk-secp256k1.bin");
     save_to_file(&bytes, new_path);
 
     let start = std::time::Instant::now();
-    let _n: VerifierParams = bincode::deserialize(&bytes).unwrap();
+    let _n: VerifierParams = from_bincode(&bytes).unwrap();
     let elapsed = start.elapsed();
     println!("Deserialize time: {:.2?}", elapsed);
 
@@ -296,12 +319,12 @@ fn gen_abar_to_ar_vk(path: PathBuf) {
         "the size of the constraint system for ABAR TO AR for ed25519: {}",
         node_params.shrunk_cs.size
     );
-    let bytes = bincode::serialize(&node_params).unwrap();
+    let bytes = to_bincode(&node_params).unwrap();
     new_path.push("abar-to-ar-vk-ed25519.bin");
     save_to_file(&bytes, new_path);
 
     let start = std::time::Instant::now();
-    let _n: VerifierParams = bincode::deserialize(&bytes).unwrap();
+    let _n: VerifierParams = from_bincode(&bytes).unwrap();
     let elapsed = start.elapsed();
     println!("Deserialize time: {:.2?}", elapsed);
 }
@@ -311,12 +334,12 @@ fn gen_bulletproof_curve25519_urs(mut path: PathBuf) {
     println!("Generating Bulletproof(over the Curve25519 curve) uniform reference string ...");
 
     let pp = BulletproofParams::default();
-    let bytes = bincode::serialize(&pp).unwrap();
+    let bytes = to_bincode(&pp).unwrap();
     path.push("bulletproof-curve25519-urs.bin");
     save_to_file(&bytes, path);
 
     let start = std::time::Instant::now();
-    let _n: BulletproofParams = bincode::deserialize(&bytes).unwrap();
+    let _n: BulletproofParams = from_bincode(&bytes).unwrap();
     let elapsed = start.elapsed();
     println!("Deserialize time: {:.2?}", elapsed);
 }
@@ -388,6 +411,72 @@ fn cut_srs(mut path: PathBuf) {
     save_to_file(&bytes, path);
 }
 
+// cargo run --release --features="gen no_vk" --bin gen-params ceremony-init 8195 "./ceremony.bin"
+fn ceremony_init(max_degree: usize, file: PathBuf) {
+    let ceremony = Ceremony::<BN254PairingEngine>::new(max_degree);
+    save_to_file(&to_bincode(&ceremony).unwrap(), file);
+}
+
+// cargo run --release --features="gen no_vk" --bin gen-params ceremony-contribute "./ceremony.bin"
+fn ceremony_contribute(file: PathBuf) {
+    let bytes = std::fs::read(&file).unwrap();
+    let mut ceremony: Ceremony<BN254PairingEngine> = from_bincode(&bytes).unwrap();
+
+    let mut prng = ChaChaRng::from_entropy();
+    let (srs, contribution) = ceremony.contribute(&mut prng).unwrap();
+    ceremony.update(srs, contribution).unwrap();
+    println!(
+        "Contribution #{}: {}",
+        ceremony.contributions.len(),
+        to_hex(&ceremony.transcript_hash())
+    );
+
+    save_to_file(&to_bincode(&ceremony).unwrap(), file);
+}
+
+// cargo run --release --features="gen no_vk" --bin gen-params ceremony-finalize "./ceremony.bin" "./parameters"
+fn ceremony_finalize(file: PathBuf, mut directory: PathBuf) {
+    let bytes = std::fs::read(&file).unwrap();
+    let ceremony: Ceremony<BN254PairingEngine> = from_bincode(&bytes).unwrap();
+    for (i, contribution) in ceremony.contributions.iter().enumerate() {
+        println!(
+            "Contribution #{}: {}",
+            i + 1,
+            to_hex(&contribution.transcript_hash)
+        );
+    }
+
+    let srs = ceremony.into_srs().unwrap();
+    directory.push("srs.bin");
+    save_to_file(&srs.to_unchecked_bytes().unwrap(), directory);
+}
+
+// cargo run --release --features="gen no_vk" --bin gen-params gen "./parameters/srs.bin" "./parameters"
+fn gen_with_srs(srs: PathBuf, directory: PathBuf) {
+    let bytes = std::fs::read(&srs).unwrap();
+    let srs = KZGCommitmentSchemeBN254::from_unchecked_bytes(&bytes).unwrap();
+
+    let circuits = Circuit::all();
+    circuits.par_iter().for_each(|circuit| {
+        let files = setup::gen(&[*circuit], &srs).unwrap();
+        for file in files {
+            println!("Generated the parameters for {}", file.label);
+
+            let mut path = directory.clone();
+            path.push(format!("{}-pp.bin", file.label));
+            save_to_file(&file.prover_params, path);
+
+            let mut path = directory.clone();
+            path.push(format!("{}-vp.bin", file.label));
+            save_to_file(&file.verifier_params, path);
+        }
+    });
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 // cargo run --release --features="gen no_vk" --bin gen-params all "./parameters"
 fn gen_all(directory: PathBuf) {
     gen_transfer_vk(directory.clone(), SECP256K1);
@@ -0,0 +1,232 @@
+use crate::errors::{NoahError, Result};
+use crate::parameters::cache::ParamsCache;
+use digest::Digest;
+use sha2::Sha256;
+use std::io::Read;
+
+/// An entry of the manifest of the parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// The name of the parameters, which is also the name of the file to download.
+    pub name: &'static str,
+    /// The length of the parameters in bytes.
+    pub len: usize,
+    /// The SHA-256 hash of the parameters, in hex.
+    pub sha256: &'static str,
+}
+
+/// The manifest of the parameters that can be downloaded, pinned to the parameters
+/// in the `parameters` directory that this version of the crate is built against.
+pub const PARAMS_MANIFEST: &[ManifestEntry] = &[
+    ManifestEntry {
+        name: "srs-padding.bin",
+        len: 131912,
+        sha256: "5ee03150de4a0bec48b59198c6b3c95312c8c664609760fee643393074cfe788",
+    },
+    ManifestEntry {
+        name: "lagrange-srs-4096.bin",
+        len: 262152,
+        sha256: "68b13e6c9e524fa52043377f2c65aca60ee7cf25954acd32c36e8b107fcdf9d1",
+    },
+    ManifestEntry {
+        name: "lagrange-srs-8192.bin",
+        len: 524296,
+        sha256: "a494a533266fdb786bc9a68724bebcd11fc7c27ee1460566f779927322c0efc5",
+    },
+];
+
+/// Return the entry of the manifest for the parameters.
+pub fn manifest_entry(name: &str) -> Result<&'static ManifestEntry> {
+    PARAMS_MANIFEST
+        .iter()
+        .find(|entry| entry.name == name)
+        .ok_or(NoahError::ParameterError)
+}
+
+/// Check that the parameters match the length and the hash pinned in the manifest.
+pub fn verify_against_manifest(name: &str, bytes: &[u8]) -> Result<()> {
+    let entry = manifest_entry(name)?;
+    if bytes.len() != entry.len {
+        return Err(NoahError::ParamsIntegrityError);
+    }
+    let digest = Sha256::digest(bytes);
+    let hex = digest
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    if hex != entry.sha256 {
+        return Err(NoahError::ParamsIntegrityError);
+    }
+    Ok(())
+}
+
+/// The transport that downloads the parameters.
+pub trait ParamsTransport {
+    /// Download the bytes at the URL, reading at most `max_len` bytes.
+    fn get(&self, url: &str, max_len: usize) -> Result<Vec<u8>>;
+}
+
+/// The transport over HTTP(S).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HttpTransport;
+
+impl ParamsTransport for HttpTransport {
+    fn get(&self, url: &str, max_len: usize) -> Result<Vec<u8>> {
+        let response = ureq::get(url)
+            .call()
+            .map_err(|_| NoahError::ParamsFetchError)?;
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .take(max_len as u64)
+            .read_to_end(&mut bytes)
+            .map_err(|_| NoahError::ParamsFetchError)?;
+        Ok(bytes)
+    }
+}
+
+/// The fetcher that downloads the parameters from a set of mirrors, verifies them
+/// against the manifest, and installs them into the `ParamsCache`.
+pub struct ParamsFetcher<T: ParamsTransport = HttpTransport> {
+    urls: Vec<String>,
+    transport: T,
+}
+
+impl ParamsFetcher<HttpTransport> {
+    /// Create a fetcher over HTTP(S) from the base URLs of the mirrors, in the order of preference.
+    pub fn new(urls: Vec<String>) -> Self {
+        Self::with_transport(urls, HttpTransport)
+    }
+}
+
+impl<T: ParamsTransport> ParamsFetcher<T> {
+    /// Create a fetcher from the base URLs of the mirrors and the transport.
+    pub fn with_transport(urls: Vec<String>, transport: T) -> Self {
+        ParamsFetcher { urls, transport }
+    }
+
+    /// Download the parameters from the first mirror that serves the pinned parameters.
+    pub fn fetch(&self, name: &str) -> Result<Vec<u8>> {
+        let entry = manifest_entry(name)?;
+        let mut err = NoahError::ParamsFetchError;
+        for base in self.urls.iter() {
+            let url = format!("{}/{}", base.trim_end_matches('/'), entry.name);
+            match self
+                .transport
+                .get(&url, entry.len + 1)
+                .and_then(|bytes| verify_against_manifest(name, &bytes).map(|_| bytes))
+            {
+                Ok(bytes) => return Ok(bytes),
+                Err(e) => err = e,
+            }
+        }
+        Err(err)
+    }
+
+    /// Download and install the parameters, unless they are already installed.
+    pub fn install(&self, name: &str) -> Result<&'static [u8]> {
+        if let Some(bytes) = ParamsCache::get(name) {
+            return Ok(bytes);
+        }
+        let bytes = self.fetch(name)?;
+        Ok(ParamsCache::install(name, bytes))
+    }
+
+    /// Download and install the SRS and its Lagrange bases, which are needed to build the prover parameters.
+    pub fn install_prover_params(&self) -> Result<()> {
+        for entry in PARAMS_MANIFEST.iter() {
+            self.install(entry.name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::errors::{NoahError, Result};
+    use crate::parameters::cache::ParamsCache;
+    use crate::params_fetch::{
+        verify_against_manifest, ParamsFetcher, ParamsTransport, PARAMS_MANIFEST,
+    };
+    use std::cell::RefCell;
+
+    /// The transport that serves the files in the `parameters` directory, and records the requests.
+    struct LocalTransport {
+        requests: RefCell<Vec<String>>,
+    }
+
+    impl ParamsTransport for LocalTransport {
+        fn get(&self, url: &str, max_len: usize) -> Result<Vec<u8>> {
+            self.requests.borrow_mut().push(url.to_string());
+            let (mirror, name) = url.rsplit_once('/').unwrap();
+            let path = format!("{}/parameters/{}", env!("CARGO_MANIFEST_DIR"), name);
+            let mut bytes = std::fs::read(path).map_err(|_| NoahError::ParamsFetchError)?;
+            match mirror {
+                "https://good" => {}
+                "https://corrupted" => bytes[0] ^= 1,
+                _ => return Err(NoahError::ParamsFetchError),
+            }
+            bytes.truncate(max_len);
+            Ok(bytes)
+        }
+    }
+
+    #[test]
+    fn test_params_fetch() {
+        let name = PARAMS_MANIFEST[0].name;
+        let path = format!("{}/parameters/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let srs = std::fs::read(path).unwrap();
+
+        verify_against_manifest(name, &srs).unwrap();
+        assert_eq!(
+            verify_against_manifest(name, &srs[1..]),
+            Err(NoahError::ParamsIntegrityError)
+        );
+        assert_eq!(
+            verify_against_manifest("unknown.bin", &srs),
+            Err(NoahError::ParameterError)
+        );
+
+        let transport = LocalTransport {
+            requests: RefCell::new(vec![]),
+        };
+        let urls = vec![
+            "https://offline/".to_string(),
+            "https://corrupted".to_string(),
+            "https://good/".to_string(),
+        ];
+        let fetcher = ParamsFetcher::with_transport(urls, transport);
+
+        // the mirrors are tried in order until one serves the pinned parameters
+        assert_eq!(fetcher.fetch(name).unwrap(), srs);
+        assert_eq!(
+            *fetcher.transport.requests.borrow(),
+            vec![
+                format!("https://offline/{}", name),
+                format!("https://corrupted/{}", name),
+                format!("https://good/{}", name),
+            ]
+        );
+
+        // the corrupted parameters are never returned
+        let fetcher = ParamsFetcher::with_transport(
+            vec!["https://corrupted".to_string()],
+            LocalTransport {
+                requests: RefCell::new(vec![]),
+            },
+        );
+        assert_eq!(fetcher.fetch(name), Err(NoahError::ParamsIntegrityError));
+
+        // the installed parameters are not downloaded again
+        let fetcher = ParamsFetcher::with_transport(
+            vec!["https://good".to_string()],
+            LocalTransport {
+                requests: RefCell::new(vec![]),
+            },
+        );
+        assert_eq!(fetcher.install(name).unwrap(), &srs[..]);
+        assert_eq!(ParamsCache::get(name).unwrap(), &srs[..]);
+        assert_eq!(fetcher.install(name).unwrap(), &srs[..]);
+        assert_eq!(fetcher.transport.requests.borrow().len(), 1);
+    }
+}
@@ -0,0 +1,739 @@
+use crate::anon_xfr::{
+    abar_to_abar::{AXfrBody as NoahAXfrBody, AXfrNote as NoahAXfrNote},
+    abar_to_ar::{AbarToArBody as NoahAbarToArBody, AbarToArNote as NoahAbarToArNote},
+    abar_to_bar::{AbarToBarBody as NoahAbarToBarBody, AbarToBarNote as NoahAbarToBarNote},
+    ar_to_abar::{ArToAbarBody as NoahArToAbarBody, ArToAbarNote as NoahArToAbarNote},
+    bar_to_abar::{BarToAbarBody as NoahBarToAbarBody, BarToAbarNote as NoahBarToAbarNote},
+    structs::AnonAssetRecord as NoahAnonAssetRecord,
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::{PublicKey, Signature, SignatureList};
+use crate::serialization::{from_bincode, to_bincode};
+use crate::xfr::structs::{
+    AssetType, BlindAssetRecord as NoahBlindAssetRecord, XfrAmount, XfrAssetType,
+    XfrBody as NoahXfrBody, XfrNote as NoahXfrNote, ASSET_TYPE_LENGTH,
+};
+use noah_algebra::{bn254::BN254Scalar, prelude::*, ristretto::CompressedRistretto};
+
+/// The commitments of the low and high 32 bits of a confidential amount.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ConfidentialAmount {
+    /// The compressed commitment of the low 32 bits.
+    #[prost(bytes = "vec", tag = "1")]
+    pub low: Vec<u8>,
+    /// The compressed commitment of the high 32 bits.
+    #[prost(bytes = "vec", tag = "2")]
+    pub high: Vec<u8>,
+}
+
+/// The message of `BlindAssetRecord`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BlindAssetRecord {
+    /// The amount.
+    #[prost(oneof = "blind_asset_record::Amount", tags = "1, 2")]
+    pub amount: Option<blind_asset_record::Amount>,
+    /// The asset type.
+    #[prost(oneof = "blind_asset_record::AssetType", tags = "3, 4")]
+    pub asset_type: Option<blind_asset_record::AssetType>,
+    /// The public key.
+    #[prost(bytes = "vec", tag = "5")]
+    pub public_key: Vec<u8>,
+}
+
+/// The oneof fields of `BlindAssetRecord`.
+pub mod blind_asset_record {
+    /// The amount.
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Amount {
+        /// Transparent amount.
+        #[prost(uint64, tag = "1")]
+        NonConfidentialAmount(u64),
+        /// Confidential amount.
+        #[prost(message, tag = "2")]
+        ConfidentialAmount(super::ConfidentialAmount),
+    }
+
+    /// The asset type.
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum AssetType {
+        /// Transparent asset type.
+        #[prost(bytes, tag = "3")]
+        NonConfidentialAssetType(Vec<u8>),
+        /// Confidential asset type.
+        #[prost(bytes, tag = "4")]
+        ConfidentialAssetType(Vec<u8>),
+    }
+}
+
+/// The message of `AnonAssetRecord`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AnonAssetRecord {
+    /// The commitment.
+    #[prost(bytes = "vec", tag = "1")]
+    pub commitment: Vec<u8>,
+}
+
+/// The message of `XfrBody`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct XfrBody {
+    /// The inputs.
+    #[prost(message, repeated, tag = "1")]
+    pub inputs: Vec<BlindAssetRecord>,
+    /// The outputs.
+    #[prost(message, repeated, tag = "2")]
+    pub outputs: Vec<BlindAssetRecord>,
+    /// The bincode of the proofs.
+    #[prost(bytes = "vec", tag = "3")]
+    pub proofs: Vec<u8>,
+    /// The bincode of the tracing memos of each input and output.
+    #[prost(bytes = "vec", repeated, tag = "4")]
+    pub asset_tracing_memos: Vec<Vec<u8>>,
+    /// The bincode of the owner memo of each output.
+    #[prost(bytes = "vec", repeated, tag = "5")]
+    pub owners_memos: Vec<Vec<u8>>,
+}
+
+/// The message of `XfrNote`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct XfrNote {
+    /// The body.
+    #[prost(message, optional, tag = "1")]
+    pub body: Option<XfrBody>,
+    /// The signatures of the inputs.
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub multisig: Vec<Vec<u8>>,
+}
+
+/// The message of `AXfrBody`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AXfrBody {
+    /// The nullifiers.
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub inputs: Vec<Vec<u8>>,
+    /// The outputs.
+    #[prost(message, repeated, tag = "2")]
+    pub outputs: Vec<AnonAssetRecord>,
+    /// The Merkle root.
+    #[prost(bytes = "vec", tag = "3")]
+    pub merkle_root: Vec<u8>,
+    /// The version of the Merkle root.
+    #[prost(uint64, tag = "4")]
+    pub merkle_root_version: u64,
+    /// The fee.
+    #[prost(uint32, tag = "5")]
+    pub fee: u32,
+    /// The bincode of the owner memo of each output.
+    #[prost(bytes = "vec", repeated, tag = "6")]
+    pub owner_memos: Vec<Vec<u8>>,
+    /// The bincode of the tracer memos.
+    #[prost(bytes = "vec", repeated, tag = "7")]
+    pub tracer_memos: Vec<Vec<u8>>,
+    /// The bincode of the encrypted payloads of the outputs.
+    #[prost(bytes = "vec", repeated, tag = "8")]
+    pub output_payloads: Vec<Vec<u8>>,
+}
+
+/// The message of `AXfrNote`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AXfrNote {
+    /// The body.
+    #[prost(message, optional, tag = "1")]
+    pub body: Option<AXfrBody>,
+    /// The bincode of the Plonk proof.
+    #[prost(bytes = "vec", tag = "2")]
+    pub proof: Vec<u8>,
+    /// The bincode of the address folding instance.
+    #[prost(bytes = "vec", tag = "3")]
+    pub folding_instance: Vec<u8>,
+}
+
+/// The message of `ArToAbarBody`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ArToAbarBody {
+    /// The input.
+    #[prost(message, optional, tag = "1")]
+    pub input: Option<BlindAssetRecord>,
+    /// The output.
+    #[prost(message, optional, tag = "2")]
+    pub output: Option<AnonAssetRecord>,
+    /// The bincode of the Plonk proof.
+    #[prost(bytes = "vec", tag = "3")]
+    pub proof: Vec<u8>,
+    /// The bincode of the owner memo.
+    #[prost(bytes = "vec", tag = "4")]
+    pub memo: Vec<u8>,
+}
+
+/// The message of `ArToAbarNote`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ArToAbarNote {
+    /// The body.
+    #[prost(message, optional, tag = "1")]
+    pub body: Option<ArToAbarBody>,
+    /// The signature.
+    #[prost(bytes = "vec", tag = "2")]
+    pub signature: Vec<u8>,
+}
+
+/// The message of `BarToAbarBody`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BarToAbarBody {
+    /// The input.
+    #[prost(message, optional, tag = "1")]
+    pub input: Option<BlindAssetRecord>,
+    /// The output.
+    #[prost(message, optional, tag = "2")]
+    pub output: Option<AnonAssetRecord>,
+    /// The bincode of the delegated Schnorr proof and the Plonk proof.
+    #[prost(bytes = "vec", tag = "3")]
+    pub proof: Vec<u8>,
+    /// The bincode of the owner memo.
+    #[prost(bytes = "vec", tag = "4")]
+    pub memo: Vec<u8>,
+}
+
+/// The message of `BarToAbarNote`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BarToAbarNote {
+    /// The body.
+    #[prost(message, optional, tag = "1")]
+    pub body: Option<BarToAbarBody>,
+    /// The signature.
+    #[prost(bytes = "vec", tag = "2")]
+    pub signature: Vec<u8>,
+}
+
+/// The message of `AbarToArBody`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AbarToArBody {
+    /// The nullifier.
+    #[prost(bytes = "vec", tag = "1")]
+    pub input: Vec<u8>,
+    /// The output.
+    #[prost(message, optional, tag = "2")]
+    pub output: Option<BlindAssetRecord>,
+    /// The Merkle root.
+    #[prost(bytes = "vec", tag = "3")]
+    pub merkle_root: Vec<u8>,
+    /// The version of the Merkle root.
+    #[prost(uint64, tag = "4")]
+    pub merkle_root_version: u64,
+    /// The bincode of the owner memo.
+    #[prost(bytes = "vec", tag = "5")]
+    pub memo: Vec<u8>,
+}
+
+/// The message of `AbarToArNote`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AbarToArNote {
+    /// The body.
+    #[prost(message, optional, tag = "1")]
+    pub body: Option<AbarToArBody>,
+    /// The bincode of the Plonk proof.
+    #[prost(bytes = "vec", tag = "2")]
+    pub proof: Vec<u8>,
+    /// The bincode of the address folding instance.
+    #[prost(bytes = "vec", tag = "3")]
+    pub folding_instance: Vec<u8>,
+}
+
+/// The message of `AbarToBarBody`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AbarToBarBody {
+    /// The nullifier.
+    #[prost(bytes = "vec", tag = "1")]
+    pub input: Vec<u8>,
+    /// The output.
+    #[prost(message, optional, tag = "2")]
+    pub output: Option<BlindAssetRecord>,
+    /// The bincode of the delegated Schnorr proof.
+    #[prost(bytes = "vec", tag = "3")]
+    pub delegated_schnorr_proof: Vec<u8>,
+    /// The Merkle root.
+    #[prost(bytes = "vec", tag = "4")]
+    pub merkle_root: Vec<u8>,
+    /// The version of the Merkle root.
+    #[prost(uint64, tag = "5")]
+    pub merkle_root_version: u64,
+    /// The bincode of the owner memo.
+    #[prost(bytes = "vec", tag = "6")]
+    pub memo: Vec<u8>,
+}
+
+/// The message of `AbarToBarNote`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AbarToBarNote {
+    /// The body.
+    #[prost(message, optional, tag = "1")]
+    pub body: Option<AbarToBarBody>,
+    /// The bincode of the Plonk proof.
+    #[prost(bytes = "vec", tag = "2")]
+    pub proof: Vec<u8>,
+    /// The bincode of the address folding instance.
+    #[prost(bytes = "vec", tag = "3")]
+    pub folding_instance: Vec<u8>,
+}
+
+fn to_compressed_ristretto(bytes: &[u8]) -> Result<CompressedRistretto> {
+    if bytes.len() != 32 {
+        return Err(NoahError::DeserializationError);
+    }
+    Ok(CompressedRistretto::from_slice(bytes))
+}
+
+fn to_scalar(bytes: &[u8]) -> Result<BN254Scalar> {
+    BN254Scalar::from_bytes(bytes).map_err(|_| NoahError::DeserializationError)
+}
+
+fn required<T>(field: Option<T>) -> Result<T> {
+    field.ok_or(NoahError::DeserializationError)
+}
+
+impl From<&NoahBlindAssetRecord> for BlindAssetRecord {
+    fn from(record: &NoahBlindAssetRecord) -> Self {
+        let amount = match &record.amount {
+            XfrAmount::NonConfidential(amount) => {
+                blind_asset_record::Amount::NonConfidentialAmount(*amount)
+            }
+            XfrAmount::Confidential((low, high)) => {
+                blind_asset_record::Amount::ConfidentialAmount(ConfidentialAmount {
+                    low: low.to_bytes().to_vec(),
+                    high: high.to_bytes().to_vec(),
+                })
+            }
+        };
+        let asset_type = match &record.asset_type {
+            XfrAssetType::NonConfidential(asset_type) => {
+                blind_asset_record::AssetType::NonConfidentialAssetType(asset_type.0.to_vec())
+            }
+            XfrAssetType::Confidential(commitment) => {
+                blind_asset_record::AssetType::ConfidentialAssetType(commitment.to_bytes().to_vec())
+            }
+        };
+        BlindAssetRecord {
+            amount: Some(amount),
+            asset_type: Some(asset_type),
+            public_key: record.public_key.noah_to_bytes(),
+        }
+    }
+}
+
+impl TryFrom<BlindAssetRecord> for NoahBlindAssetRecord {
+    type Error = NoahError;
+
+    fn try_from(record: BlindAssetRecord) -> Result<Self> {
+        let amount = match required(record.amount)? {
+            blind_asset_record::Amount::NonConfidentialAmount(amount) => {
+                XfrAmount::NonConfidential(amount)
+            }
+            blind_asset_record::Amount::ConfidentialAmount(amount) => XfrAmount::Confidential((
+                to_compressed_ristretto(&amount.low)?,
+                to_compressed_ristretto(&amount.high)?,
+            )),
+        };
+        let asset_type = match required(record.asset_type)? {
+            blind_asset_record::AssetType::NonConfidentialAssetType(bytes) => {
+                if bytes.len() != ASSET_TYPE_LENGTH {
+                    return Err(NoahError::DeserializationError);
+                }
+                let mut asset_type = [0u8; ASSET_TYPE_LENGTH];
+                asset_type.copy_from_slice(&bytes);
+                XfrAssetType::NonConfidential(AssetType(asset_type))
+            }
+            blind_asset_record::AssetType::ConfidentialAssetType(bytes) => {
+                XfrAssetType::Confidential(to_compressed_ristretto(&bytes)?)
+            }
+        };
+        Ok(NoahBlindAssetRecord {
+            amount,
+            asset_type,
+            public_key: PublicKey::noah_from_bytes(&record.public_key)?,
+        })
+    }
+}
+
+impl From<&NoahAnonAssetRecord> for AnonAssetRecord {
+    fn from(record: &NoahAnonAssetRecord) -> Self {
+        AnonAssetRecord {
+            commitment: record.commitment.to_bytes(),
+        }
+    }
+}
+
+impl TryFrom<AnonAssetRecord> for NoahAnonAssetRecord {
+    type Error = NoahError;
+
+    fn try_from(record: AnonAssetRecord) -> Result<Self> {
+        Ok(NoahAnonAssetRecord {
+            commitment: to_scalar(&record.commitment)?,
+        })
+    }
+}
+
+impl TryFrom<&NoahXfrNote> for XfrNote {
+    type Error = NoahError;
+
+    fn try_from(note: &NoahXfrNote) -> Result<Self> {
+        let body = &note.body;
+        Ok(XfrNote {
+            body: Some(XfrBody {
+                inputs: body.inputs.iter().map(BlindAssetRecord::from).collect(),
+                outputs: body.outputs.iter().map(BlindAssetRecord::from).collect(),
+                proofs: to_bincode(&body.proofs)?,
+                asset_tracing_memos: body
+                    .asset_tracing_memos
+                    .iter()
+                    .map(to_bincode)
+                    .collect::<Result<_>>()?,
+                owners_memos: body
+                    .owners_memos
+                    .iter()
+                    .map(to_bincode)
+                    .collect::<Result<_>>()?,
+            }),
+            multisig: note
+                .multisig
+                .signatures
+                .iter()
+                .map(|signature| signature.noah_to_bytes())
+                .collect(),
+        })
+    }
+}
+
+impl TryFrom<XfrNote> for NoahXfrNote {
+    type Error = NoahError;
+
+    fn try_from(note: XfrNote) -> Result<Self> {
+        let body = required(note.body)?;
+        Ok(NoahXfrNote {
+            body: NoahXfrBody {
+                inputs: body
+                    .inputs
+                    .into_iter()
+                    .map(TryFrom::try_from)
+                    .collect::<Result<_>>()?,
+                outputs: body
+                    .outputs
+                    .into_iter()
+                    .map(TryFrom::try_from)
+                    .collect::<Result<_>>()?,
+                proofs: from_bincode(&body.proofs)?,
+                asset_tracing_memos: body
+                    .asset_tracing_memos
+                    .iter()
+                    .map(|bytes| from_bincode(bytes))
+                    .collect::<Result<_>>()?,
+                owners_memos: body
+                    .owners_memos
+                    .iter()
+                    .map(|bytes| from_bincode(bytes))
+                    .collect::<Result<_>>()?,
+            },
+            multisig: SignatureList {
+                signatures: note
+                    .multisig
+                    .iter()
+                    .map(|bytes| Signature::noah_from_bytes(bytes))
+                    .collect::<core::result::Result<_, _>>()?,
+            },
+        })
+    }
+}
+
+impl TryFrom<&NoahAXfrNote> for AXfrNote {
+    type Error = NoahError;
+
+    fn try_from(note: &NoahAXfrNote) -> Result<Self> {
+        let body = &note.body;
+        Ok(AXfrNote {
+            body: Some(AXfrBody {
+                inputs: body.inputs.iter().map(|n| n.to_bytes()).collect(),
+                outputs: body.outputs.iter().map(AnonAssetRecord::from).collect(),
+                merkle_root: body.merkle_root.to_bytes(),
+                merkle_root_version: body.merkle_root_version,
+                fee: body.fee,
+                owner_memos: body
+                    .owner_memos
+                    .iter()
+                    .map(to_bincode)
+                    .collect::<Result<_>>()?,
+                tracer_memos: body
+                    .tracer_memos
+                    .iter()
+                    .map(to_bincode)
+                    .collect::<Result<_>>()?,
+                output_payloads: body
+                    .output_payloads
+                    .iter()
+                    .map(to_bincode)
+                    .collect::<Result<_>>()?,
+            }),
+            proof: to_bincode(&note.proof)?,
+            folding_instance: to_bincode(&note.folding_instance)?,
+        })
+    }
+}
+
+impl TryFrom<AXfrNote> for NoahAXfrNote {
+    type Error = NoahError;
+
+    fn try_from(note: AXfrNote) -> Result<Self> {
+        let body = required(note.body)?;
+        Ok(NoahAXfrNote {
+            body: NoahAXfrBody {
+                inputs: body
+                    .inputs
+                    .iter()
+                    .map(|bytes| to_scalar(bytes))
+                    .collect::<Result<_>>()?,
+                outputs: body
+                    .outputs
+                    .into_iter()
+                    .map(TryFrom::try_from)
+                    .collect::<Result<_>>()?,
+                merkle_root: to_scalar(&body.merkle_root)?,
+                merkle_root_version: body.merkle_root_version,
+                fee: body.fee,
+                owner_memos: body
+                    .owner_memos
+                    .iter()
+                    .map(|bytes| from_bincode(bytes))
+                    .collect::<Result<_>>()?,
+                tracer_memos: body
+                    .tracer_memos
+                    .iter()
+                    .map(|bytes| from_bincode(bytes))
+                    .collect::<Result<_>>()?,
+                output_payloads: body
+                    .output_payloads
+                    .iter()
+                    .map(|bytes| from_bincode(bytes))
+                    .collect::<Result<_>>()?,
+            },
+            proof: from_bincode(&note.proof)?,
+            folding_instance: from_bincode(&note.folding_instance)?,
+        })
+    }
+}
+
+impl TryFrom<&NoahArToAbarNote> for ArToAbarNote {
+    type Error = NoahError;
+
+    fn try_from(note: &NoahArToAbarNote) -> Result<Self> {
+        let body = &note.body;
+        Ok(ArToAbarNote {
+            body: Some(ArToAbarBody {
+                input: Some((&body.input).into()),
+                output: Some((&body.output).into()),
+                proof: to_bincode(&body.proof)?,
+                memo: to_bincode(&body.memo)?,
+            }),
+            signature: note.signature.noah_to_bytes(),
+        })
+    }
+}
+
+impl TryFrom<ArToAbarNote> for NoahArToAbarNote {
+    type Error = NoahError;
+
+    fn try_from(note: ArToAbarNote) -> Result<Self> {
+        let body = required(note.body)?;
+        Ok(NoahArToAbarNote {
+            body: NoahArToAbarBody {
+                input: required(body.input)?.try_into()?,
+                output: required(body.output)?.try_into()?,
+                proof: from_bincode(&body.proof)?,
+                memo: from_bincode(&body.memo)?,
+            },
+            signature: Signature::noah_from_bytes(&note.signature)?,
+        })
+    }
+}
+
+impl TryFrom<&NoahBarToAbarNote> for BarToAbarNote {
+    type Error = NoahError;
+
+    fn try_from(note: &NoahBarToAbarNote) -> Result<Self> {
+        let body = &note.body;
+        Ok(BarToAbarNote {
+            body: Some(BarToAbarBody {
+                input: Some((&body.input).into()),
+                output: Some((&body.output).into()),
+                proof: to_bincode(&body.proof)?,
+                memo: to_bincode(&body.memo)?,
+            }),
+            signature: note.signature.noah_to_bytes(),
+        })
+    }
+}
+
+impl TryFrom<BarToAbarNote> for NoahBarToAbarNote {
+    type Error = NoahError;
+
+    fn try_from(note: BarToAbarNote) -> Result<Self> {
+        let body = required(note.body)?;
+        Ok(NoahBarToAbarNote {
+            body: NoahBarToAbarBody {
+                input: required(body.input)?.try_into()?,
+                output: required(body.output)?.try_into()?,
+                proof: from_bincode(&body.proof)?,
+                memo: from_bincode(&body.memo)?,
+            },
+            signature: Signature::noah_from_bytes(&note.signature)?,
+        })
+    }
+}
+
+impl TryFrom<&NoahAbarToArNote> for AbarToArNote {
+    type Error = NoahError;
+
+    fn try_from(note: &NoahAbarToArNote) -> Result<Self> {
+        let body = &note.body;
+        Ok(AbarToArNote {
+            body: Some(AbarToArBody {
+                input: body.input.to_bytes(),
+                output: Some((&body.output).into()),
+                merkle_root: body.merkle_root.to_bytes(),
+                merkle_root_version: body.merkle_root_version,
+                memo: to_bincode(&body.memo)?,
+            }),
+            proof: to_bincode(&note.proof)?,
+            folding_instance: to_bincode(&note.folding_instance)?,
+        })
+    }
+}
+
+impl TryFrom<AbarToArNote> for NoahAbarToArNote {
+    type Error = NoahError;
+
+    fn try_from(note: AbarToArNote) -> Result<Self> {
+        let body = required(note.body)?;
+        Ok(NoahAbarToArNote {
+            body: NoahAbarToArBody {
+                input: to_scalar(&body.input)?,
+                output: required(body.output)?.try_into()?,
+                merkle_root: to_scalar(&body.merkle_root)?,
+                merkle_root_version: body.merkle_root_version,
+                memo: from_bincode(&body.memo)?,
+            },
+            proof: from_bincode(&note.proof)?,
+            folding_instance: from_bincode(&note.folding_instance)?,
+        })
+    }
+}
+
+impl TryFrom<&NoahAbarToBarNote> for AbarToBarNote {
+    type Error = NoahError;
+
+    fn try_from(note: &NoahAbarToBarNote) -> Result<Self> {
+        let body = &note.body;
+        Ok(AbarToBarNote {
+            body: Some(AbarToBarBody {
+                input: body.input.to_bytes(),
+                output: Some((&body.output).into()),
+                delegated_schnorr_proof: to_bincode(&body.delegated_schnorr_proof)?,
+                merkle_root: body.merkle_root.to_bytes(),
+                merkle_root_version: body.merkle_root_version,
+                memo: to_bincode(&body.memo)?,
+            }),
+            proof: to_bincode(&note.proof)?,
+            folding_instance: to_bincode(&note.folding_instance)?,
+        })
+    }
+}
+
+impl TryFrom<AbarToBarNote> for NoahAbarToBarNote {
+    type Error = NoahError;
+
+    fn try_from(note: AbarToBarNote) -> Result<Self> {
+        let body = required(note.body)?;
+        Ok(NoahAbarToBarNote {
+            body: NoahAbarToBarBody {
+                input: to_scalar(&body.input)?,
+                output: required(body.output)?.try_into()?,
+                delegated_schnorr_proof: from_bincode(&body.delegated_schnorr_proof)?,
+                merkle_root: to_scalar(&body.merkle_root)?,
+                merkle_root_version: body.merkle_root_version,
+                memo: from_bincode(&body.memo)?,
+            },
+            proof: from_bincode(&note.proof)?,
+            folding_instance: from_bincode(&note.folding_instance)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::proto;
+    use crate::xfr::{
+        asset_record::AssetRecordType,
+        gen_xfr_note,
+        structs::{AssetRecord, AssetRecordTemplate, AssetType, BlindAssetRecord, XfrNote},
+    };
+    use noah_algebra::prelude::*;
+    use prost::Message;
+
+    #[test]
+    fn test_proto_xfr_note() {
+        let mut prng = test_rng();
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        for (i, record_type) in [
+            AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let asset_type = AssetType::from_identical_byte(i as u8);
+            let template = AssetRecordTemplate::with_no_asset_tracing(
+                10,
+                asset_type,
+                record_type,
+                sender.get_pk(),
+            );
+            inputs.push(
+                AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap(),
+            );
+            let template = AssetRecordTemplate::with_no_asset_tracing(
+                10,
+                asset_type,
+                record_type,
+                receiver.get_pk(),
+            );
+            outputs.push(
+                AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap(),
+            );
+        }
+        let note = gen_xfr_note(&mut prng, &inputs, &outputs, &[&sender, &sender]).unwrap();
+
+        let message = proto::XfrNote::try_from(&note).unwrap();
+        let bytes = message.encode_to_vec();
+        let decoded = proto::XfrNote::decode(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, message);
+        assert_eq!(XfrNote::try_from(decoded).unwrap(), note);
+
+        // the records keep their structure
+        for record in note.body.inputs.iter().chain(note.body.outputs.iter()) {
+            let message = proto::BlindAssetRecord::from(record);
+            assert_eq!(&BlindAssetRecord::try_from(message).unwrap(), record);
+        }
+
+        // missing or malformed fields are rejected
+        let mut missing_body = message.clone();
+        missing_body.body = None;
+        assert!(XfrNote::try_from(missing_body).is_err());
+        let mut bad_key = message.clone();
+        bad_key.body.as_mut().unwrap().inputs[0].public_key = vec![1, 2, 3];
+        assert!(XfrNote::try_from(bad_key).is_err());
+        let mut bad_proofs = message;
+        bad_proofs.body.as_mut().unwrap().proofs.truncate(10);
+        assert!(XfrNote::try_from(bad_proofs).is_err());
+    }
+}
@@ -1,12 +1,14 @@
-use crate::{
-    keys::{KeyPair, PublicKey, SecretKey, Signature},
-    xfr::structs::{AssetType, ASSET_TYPE_LENGTH},
-};
+use crate::errors::NoahError;
+use crate::keys::{KeyPair, PublicKey, SecretKey, Signature};
+#[cfg(feature = "xfr")]
+use crate::xfr::structs::{AssetType, ASSET_TYPE_LENGTH};
 use noah_algebra::prelude::*;
-use serde::Serializer;
+use serde::{de::DeserializeOwned, Serialize, Serializer};
 
+#[cfg(feature = "xfr")]
 type Result<T> = core::result::Result<T, AlgebraError>;
 
+#[cfg(feature = "xfr")]
 impl NoahFromToBytes for AssetType {
     fn noah_to_bytes(&self) -> Vec<u8> {
         self.0.to_vec()
@@ -31,12 +33,146 @@ serialize_deserialize!(KeyPair);
 
 serialize_deserialize!(Signature);
 
-#[cfg(test)]
+/// Serialize an object in the bincode format of the parameter files,
+/// which is the fixed-width little-endian format of bincode 1.
+pub fn to_bincode<T: Serialize>(value: &T) -> core::result::Result<Vec<u8>, NoahError> {
+    bincode::serde::encode_to_vec(value, bincode::config::legacy())
+        .map_err(|_| NoahError::SerializationError)
+}
+
+/// Deserialize an object from the bincode format of the parameter files.
+pub fn from_bincode<T: DeserializeOwned>(bytes: &[u8]) -> core::result::Result<T, NoahError> {
+    bincode::serde::decode_from_slice(bytes, bincode::config::legacy())
+        .map(|(value, _)| value)
+        .map_err(|_| NoahError::DeserializationError)
+}
+
+/// Implement Borsh for a type through its `NoahFromToBytes`, as a Borsh byte vector.
+#[cfg(feature = "borsh")]
+macro_rules! borsh_from_noah_bytes {
+    ($t:ty) => {
+        impl borsh::BorshSerialize for $t {
+            fn serialize<W: borsh::maybestd::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> borsh::maybestd::io::Result<()> {
+                borsh::BorshSerialize::serialize(&self.noah_to_bytes(), writer)
+            }
+        }
+
+        impl borsh::BorshDeserialize for $t {
+            fn deserialize_reader<R: borsh::maybestd::io::Read>(
+                reader: &mut R,
+            ) -> borsh::maybestd::io::Result<Self> {
+                let bytes: Vec<u8> = borsh::BorshDeserialize::deserialize_reader(reader)?;
+                <$t>::noah_from_bytes(&bytes).map_err(|_| {
+                    borsh::maybestd::io::Error::new(
+                        borsh::maybestd::io::ErrorKind::InvalidData,
+                        stringify!($t),
+                    )
+                })
+            }
+        }
+    };
+}
+
+/// Implement Borsh for a type through its bincode serialization, as a Borsh byte vector,
+/// so that the types holding curve points and proofs need no Borsh support of their own.
+#[cfg(feature = "borsh")]
+macro_rules! borsh_from_bincode {
+    ($t:ty) => {
+        impl borsh::BorshSerialize for $t {
+            fn serialize<W: borsh::maybestd::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> borsh::maybestd::io::Result<()> {
+                let bytes = $crate::serialization::to_bincode(self).map_err(|_| {
+                    borsh::maybestd::io::Error::new(
+                        borsh::maybestd::io::ErrorKind::InvalidData,
+                        stringify!($t),
+                    )
+                })?;
+                borsh::BorshSerialize::serialize(&bytes, writer)
+            }
+        }
+
+        impl borsh::BorshDeserialize for $t {
+            fn deserialize_reader<R: borsh::maybestd::io::Read>(
+                reader: &mut R,
+            ) -> borsh::maybestd::io::Result<Self> {
+                let bytes: Vec<u8> = borsh::BorshDeserialize::deserialize_reader(reader)?;
+                $crate::serialization::from_bincode(&bytes).map_err(|_| {
+                    borsh::maybestd::io::Error::new(
+                        borsh::maybestd::io::ErrorKind::InvalidData,
+                        stringify!($t),
+                    )
+                })
+            }
+        }
+    };
+}
+
+#[cfg(feature = "borsh")]
+borsh_from_noah_bytes!(SecretKey);
+#[cfg(feature = "borsh")]
+borsh_from_noah_bytes!(PublicKey);
+#[cfg(feature = "borsh")]
+borsh_from_noah_bytes!(KeyPair);
+#[cfg(feature = "borsh")]
+borsh_from_noah_bytes!(Signature);
+
+#[cfg(all(feature = "borsh", feature = "xfr"))]
+mod borsh_xfr {
+    use crate::xfr::structs::{
+        AssetType, BlindAssetRecord, OwnerMemo, TracerMemo, XfrAmount, XfrAssetType, XfrBody,
+        XfrNote, XfrProofs,
+    };
+
+    borsh_from_noah_bytes!(AssetType);
+    borsh_from_bincode!(XfrAmount);
+    borsh_from_bincode!(XfrAssetType);
+    borsh_from_bincode!(BlindAssetRecord);
+    borsh_from_bincode!(OwnerMemo);
+    borsh_from_bincode!(TracerMemo);
+    borsh_from_bincode!(XfrProofs);
+    borsh_from_bincode!(XfrBody);
+    borsh_from_bincode!(XfrNote);
+}
+
+#[cfg(all(feature = "borsh", feature = "anon_xfr"))]
+mod borsh_anon_xfr {
+    use crate::anon_xfr::{
+        abar_to_abar::{AXfrBody, AXfrNote},
+        abar_to_ar::{AbarToArBody, AbarToArNote},
+        abar_to_bar::{AbarToBarBody, AbarToBarNote},
+        ar_to_abar::{ArToAbarBody, ArToAbarNote},
+        bar_to_abar::{BarToAbarBody, BarToAbarNote},
+        structs::{AnonAssetRecord, AxfrOwnerMemo, MTLeafInfo},
+        AXfrAddressFoldingInstance,
+    };
+
+    borsh_from_bincode!(AnonAssetRecord);
+    borsh_from_bincode!(AxfrOwnerMemo);
+    borsh_from_bincode!(MTLeafInfo);
+    borsh_from_bincode!(AXfrAddressFoldingInstance);
+    borsh_from_bincode!(AXfrBody);
+    borsh_from_bincode!(AXfrNote);
+    borsh_from_bincode!(AbarToArBody);
+    borsh_from_bincode!(AbarToArNote);
+    borsh_from_bincode!(AbarToBarBody);
+    borsh_from_bincode!(AbarToBarNote);
+    borsh_from_bincode!(ArToAbarBody);
+    borsh_from_bincode!(ArToAbarNote);
+    borsh_from_bincode!(BarToAbarBody);
+    borsh_from_bincode!(BarToAbarNote);
+}
+
+#[cfg(all(test, feature = "xfr"))]
 mod test {
     use crate::keys::{KeyPair, PublicKey, PublicKeyInner, SecretKey, Signature};
     use crate::parameters::AddressFormat::SECP256K1;
     use crate::ristretto::CompressedRistretto;
-    use crate::serialization::NoahFromToBytes;
+    use crate::serialization::{from_bincode, to_bincode, NoahFromToBytes};
     use crate::xfr::{
         asset_tracer::RecordDataEncKey,
         structs::{BlindAssetRecord, OpenAssetRecord, XfrAmount, XfrAssetType},
@@ -50,6 +186,16 @@ mod test {
     use rmp_serde::{Deserializer, Serializer};
     use serde::{de::Deserialize, ser::Serialize};
 
+    #[test]
+    fn bincode_legacy_layout() {
+        // the variant index is a u32, and the length of a string is a u64, as in bincode 1
+        let amt = XfrAmount::NonConfidential(5);
+        let bytes = to_bincode(&amt).unwrap();
+        assert_eq!(bytes, [1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, b'5']);
+        assert_eq!(from_bincode::<XfrAmount>(&bytes).unwrap(), amt);
+        assert!(from_bincode::<XfrAmount>(&bytes[..4]).is_err());
+    }
+
     #[test]
     fn xfr_amount_u64_to_string_serde() {
         let amt = XfrAmount::NonConfidential(1844674407370955161);
@@ -256,4 +402,57 @@ mod test {
             panic!("Failed to deserialize PublicKey from JSON");
         }
     }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn borsh_round_trip() {
+        use crate::xfr::{
+            asset_record::AssetRecordType,
+            gen_xfr_note,
+            structs::{AssetRecord, AssetRecordTemplate, AssetType, XfrNote},
+        };
+        use borsh::{BorshDeserialize, BorshSerialize};
+
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+        let signature = keypair.sign(b"message").unwrap();
+
+        let bytes = keypair.try_to_vec().unwrap();
+        assert_eq!(KeyPair::try_from_slice(&bytes).unwrap(), keypair);
+        let bytes = keypair.get_pk().try_to_vec().unwrap();
+        assert_eq!(PublicKey::try_from_slice(&bytes).unwrap(), keypair.get_pk());
+        let bytes = signature.try_to_vec().unwrap();
+        assert_eq!(Signature::try_from_slice(&bytes).unwrap(), signature);
+
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            AssetType::from_identical_byte(0u8),
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+            keypair.get_pk(),
+        );
+        let record = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let note = gen_xfr_note(&mut prng, &[record.clone()], &[record], &[&keypair]).unwrap();
+        let bytes = note.try_to_vec().unwrap();
+        assert_eq!(XfrNote::try_from_slice(&bytes).unwrap(), note);
+        assert!(XfrNote::try_from_slice(&bytes[..bytes.len() - 1]).is_err());
+
+        // mutated encodings are either rejected or decoded into notes that round-trip
+        for _ in 0..1000 {
+            let mut mutated = bytes.clone();
+            for _ in 0..prng.gen_range(1..4) {
+                let index = prng.gen_range(0..mutated.len());
+                mutated[index] = prng.gen();
+            }
+            if let Ok(decoded) = XfrNote::try_from_slice(&mutated) {
+                let encoded = decoded.try_to_vec().unwrap();
+                assert_eq!(XfrNote::try_from_slice(&encoded).unwrap(), decoded);
+            }
+        }
+        for _ in 0..1000 {
+            let len = prng.gen_range(0..256);
+            let random = (0..len).map(|_| prng.gen()).collect::<Vec<u8>>();
+            let _ = PublicKey::try_from_slice(&random);
+            let _ = XfrNote::try_from_slice(&random);
+        }
+    }
 }
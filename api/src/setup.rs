@@ -0,0 +1,366 @@
+use crate::errors::{NoahError, Result};
+use crate::parameters::params::{
+    ProverParams, VerifierParams, MAX_ANONYMOUS_RECORD_NUMBER_CONSOLIDATION_RECEIVER,
+    MAX_ANONYMOUS_RECORD_NUMBER_CONSOLIDATION_SENDER, MAX_ANONYMOUS_RECORD_NUMBER_ONE_INPUT,
+    MAX_ANONYMOUS_RECORD_NUMBER_STANDARD,
+};
+use crate::parameters::AddressFormat::{self, ED25519, SECP256K1};
+use crate::serialization::{from_bincode, to_bincode};
+use ark_std::{string::String, vec::Vec};
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use noah_plonk::plonk::constraint_system::{ConstraintSystem, TurboCS};
+use noah_plonk::poly_commit::kzg_poly_com::KZGCommitmentSchemeBN254;
+use rand_chacha::ChaChaRng;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+
+/// The circuits whose parameters are generated.
+#[derive(Clone, Copy)]
+pub enum Circuit {
+    /// The anonymous transfer with the numbers of inputs and outputs.
+    AbarToAbar {
+        /// The number of inputs.
+        n_payers: usize,
+        /// The number of outputs.
+        n_payees: usize,
+        /// The address format of the inputs.
+        address_format: AddressFormat,
+    },
+    /// The confidential-to-anonymous transfer.
+    BarToAbar,
+    /// The anonymous-to-confidential transfer.
+    AbarToBar(AddressFormat),
+    /// The transparent-to-anonymous transfer.
+    ArToAbar,
+    /// The anonymous-to-transparent transfer.
+    AbarToAr(AddressFormat),
+    /// The asset tracing of anonymous records.
+    AbarTracing,
+    /// The disclosure of the outputs of anonymous transfers.
+    AbarDisclosure,
+    /// The amount range proofs of anonymous records.
+    AbarAmountRange,
+    /// The transfer of non-fungible anonymous records.
+    NftTransfer(AddressFormat),
+}
+
+impl Circuit {
+    /// Return all the circuits, with every supported number of inputs and outputs of
+    /// anonymous transfers in both address formats.
+    pub fn all() -> Vec<Circuit> {
+        let mut circuits = Vec::new();
+        for address_format in [SECP256K1, ED25519] {
+            for n_payers in 1..=MAX_ANONYMOUS_RECORD_NUMBER_CONSOLIDATION_SENDER {
+                let max_payees = if n_payers == 1 {
+                    MAX_ANONYMOUS_RECORD_NUMBER_ONE_INPUT
+                } else if n_payers <= MAX_ANONYMOUS_RECORD_NUMBER_STANDARD {
+                    MAX_ANONYMOUS_RECORD_NUMBER_STANDARD
+                } else {
+                    MAX_ANONYMOUS_RECORD_NUMBER_CONSOLIDATION_RECEIVER
+                };
+                for n_payees in 1..=max_payees {
+                    circuits.push(Circuit::AbarToAbar {
+                        n_payers,
+                        n_payees,
+                        address_format,
+                    });
+                }
+            }
+            circuits.push(Circuit::AbarToBar(address_format));
+            circuits.push(Circuit::AbarToAr(address_format));
+            circuits.push(Circuit::NftTransfer(address_format));
+        }
+        circuits.extend_from_slice(&[
+            Circuit::BarToAbar,
+            Circuit::ArToAbar,
+            Circuit::AbarTracing,
+            Circuit::AbarDisclosure,
+            Circuit::AbarAmountRange,
+        ]);
+        circuits
+    }
+
+    /// Build the constraint system of the circuit, with its label.
+    pub fn build_cs(&self) -> Result<(String, TurboCS<BN254Scalar>)> {
+        match *self {
+            Circuit::AbarToAbar {
+                n_payers,
+                n_payees,
+                address_format,
+            } => ProverParams::abar_to_abar_cs(n_payers, n_payees, address_format),
+            Circuit::BarToAbar => ProverParams::bar_to_abar_cs(),
+            Circuit::AbarToBar(address_format) => ProverParams::abar_to_bar_cs(address_format),
+            Circuit::ArToAbar => ProverParams::ar_to_abar_cs(),
+            Circuit::AbarToAr(address_format) => ProverParams::abar_to_ar_cs(address_format),
+            Circuit::AbarTracing => ProverParams::abar_tracing_cs(),
+            Circuit::AbarDisclosure => ProverParams::abar_disclosure_cs(),
+            Circuit::AbarAmountRange => ProverParams::abar_amount_range_cs(),
+            Circuit::NftTransfer(address_format) => ProverParams::nft_transfer_cs(address_format),
+        }
+    }
+}
+
+/// Compute the hash of the constraint system, which covers everything but the witness.
+pub fn circuit_hash(cs: &TurboCS<BN254Scalar>) -> Result<[u8; 32]> {
+    let bytes = to_bincode(&(
+        &cs.selectors,
+        &cs.wiring,
+        &cs.anemoi_preprocessed_round_keys_x,
+        &cs.anemoi_preprocessed_round_keys_y,
+        &cs.anemoi_generator,
+        &cs.anemoi_generator_inv,
+        &cs.anemoi_constraints_indices,
+        cs.num_vars,
+        cs.size,
+        &cs.public_vars_constraint_indices,
+        &cs.public_vars_witness_indices,
+        &cs.boolean_constraint_indices,
+    ))?;
+    Ok(Sha256::digest(&bytes).into())
+}
+
+/// The parameters with the hash of the circuit that they were generated for.
+#[derive(Serialize, Deserialize)]
+pub struct GeneratedParams<T> {
+    /// The label of the circuit.
+    pub label: String,
+    /// The hash of the constraint system of the circuit.
+    pub circuit_hash: [u8; 32],
+    /// The parameters.
+    pub params: T,
+}
+
+/// The serialized parameters of a circuit.
+pub struct GeneratedFiles {
+    /// The label of the circuit.
+    pub label: String,
+    /// The serialized prover parameters.
+    pub prover_params: Vec<u8>,
+    /// The serialized verifier parameters.
+    pub verifier_params: Vec<u8>,
+}
+
+/// Generate the prover parameters of the circuit over the SRS. The generation is deterministic,
+/// so the same SRS always gives the same parameters.
+pub fn gen_prover_params(
+    circuit: &Circuit,
+    srs: &KZGCommitmentSchemeBN254,
+) -> Result<GeneratedParams<ProverParams>> {
+    let (label, cs) = circuit.build_cs()?;
+    let circuit_hash = circuit_hash(&cs)?;
+
+    let max_len = cs.size() + 3;
+    if srs.public_parameter_group_1.len() < max_len || srs.public_parameter_group_2.len() < 2 {
+        return Err(NoahError::ParameterError);
+    }
+    let pcs = KZGCommitmentSchemeBN254 {
+        public_parameter_group_1: srs.public_parameter_group_1[..max_len].to_vec(),
+        public_parameter_group_2: srs.public_parameter_group_2[..2].to_vec(),
+    };
+
+    let params = ProverParams::from_cs(label.clone(), cs, pcs, None, None)?;
+    Ok(GeneratedParams {
+        label,
+        circuit_hash,
+        params,
+    })
+}
+
+/// Generate and serialize the prover and verifier parameters of the circuits over the SRS.
+pub fn gen(circuits: &[Circuit], srs: &KZGCommitmentSchemeBN254) -> Result<Vec<GeneratedFiles>> {
+    let mut files = Vec::with_capacity(circuits.len());
+    for circuit in circuits.iter() {
+        let GeneratedParams {
+            label,
+            circuit_hash,
+            params,
+        } = gen_prover_params(circuit, srs)?;
+
+        let prover_params = to_bincode(&GeneratedParams {
+            label: label.clone(),
+            circuit_hash,
+            params: &params,
+        })?;
+        let verifier_params = to_bincode(&GeneratedParams {
+            label: label.clone(),
+            circuit_hash,
+            params: VerifierParams::from(params),
+        })?;
+
+        files.push(GeneratedFiles {
+            label,
+            prover_params,
+            verifier_params,
+        });
+    }
+    Ok(files)
+}
+
+/// Deserialize the generated parameters and check that they were generated for the circuit
+/// as it is built now.
+fn load<T: DeserializeOwned>(
+    bytes: &[u8],
+    circuit: &Circuit,
+) -> Result<(GeneratedParams<T>, TurboCS<BN254Scalar>)> {
+    let generated: GeneratedParams<T> = from_bincode(bytes)?;
+    let (label, cs) = circuit.build_cs()?;
+    if generated.label != label || generated.circuit_hash != circuit_hash(&cs)? {
+        return Err(NoahError::CircuitHashMismatchError);
+    }
+    Ok((generated, cs))
+}
+
+/// Load the prover parameters generated by `gen`, checking them against the circuit.
+pub fn load_prover_params(bytes: &[u8], circuit: &Circuit) -> Result<ProverParams> {
+    let (generated, _) = load::<ProverParams>(bytes, circuit)?;
+    if circuit_hash(&generated.params.cs)? != generated.circuit_hash {
+        return Err(NoahError::CircuitHashMismatchError);
+    }
+    Ok(generated.params)
+}
+
+/// Load the verifier parameters generated by `gen`, checking them against the circuit.
+pub fn load_verifier_params(bytes: &[u8], circuit: &Circuit) -> Result<VerifierParams> {
+    let (generated, cs) = load::<VerifierParams>(bytes, circuit)?;
+    if generated.params.shrunk_cs.size() != cs.size() {
+        return Err(NoahError::CircuitHashMismatchError);
+    }
+    Ok(generated.params)
+}
+
+/// The seed of the SRS of the test parameters. Anyone can recompute the trapdoor from it,
+/// so the test parameters must never be used outside of tests.
+const TEST_PARAMS_SEED: [u8; 32] = *b"Noah insecure test params seed!!";
+
+/// The test parameters of circuits, over an SRS sampled from a fixed seed, which is small
+/// enough to check in and make the integration tests independent of the distributed parameters.
+#[derive(Serialize, Deserialize)]
+pub struct TestParamsBundle {
+    /// The insecure SRS, which is as long as the largest circuit needs.
+    pub srs: KZGCommitmentSchemeBN254,
+    /// The labels of the circuits with their verifier parameters, serialized as by `gen`.
+    pub verifier_params: Vec<(String, Vec<u8>)>,
+}
+
+/// Produce the serialized test parameters of all the circuits. The result is deterministic.
+pub fn bundle_test_params() -> Result<Vec<u8>> {
+    bundle_test_params_for(&Circuit::all())
+}
+
+/// Produce the serialized test parameters of the circuits. The result is deterministic.
+pub fn bundle_test_params_for(circuits: &[Circuit]) -> Result<Vec<u8>> {
+    let mut max_size = 0;
+    for circuit in circuits.iter() {
+        let (_, cs) = circuit.build_cs()?;
+        max_size = core::cmp::max(max_size, cs.size());
+    }
+
+    let mut prng = ChaChaRng::from_seed(TEST_PARAMS_SEED);
+    let srs = KZGCommitmentSchemeBN254::new(max_size + 3, &mut prng);
+
+    let verifier_params = gen(circuits, &srs)?
+        .into_iter()
+        .map(|files| (files.label, files.verifier_params))
+        .collect();
+
+    to_bincode(&TestParamsBundle {
+        srs,
+        verifier_params,
+    })
+}
+
+/// The test parameters mounted from the artifact of `bundle_test_params`.
+pub struct TestParams {
+    bundle: TestParamsBundle,
+}
+
+impl TestParams {
+    /// Mount the serialized test parameters.
+    pub fn mount(bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            bundle: from_bincode(bytes)?,
+        })
+    }
+
+    /// Return the labels of the circuits in the test parameters.
+    pub fn labels(&self) -> Vec<String> {
+        self.bundle
+            .verifier_params
+            .iter()
+            .map(|(label, _)| label.clone())
+            .collect()
+    }
+
+    /// Generate the prover parameters of the circuit over the insecure SRS, which match
+    /// the verifier parameters of the circuit in the test parameters.
+    pub fn prover_params(&self, circuit: &Circuit) -> Result<ProverParams> {
+        Ok(gen_prover_params(circuit, &self.bundle.srs)?.params)
+    }
+
+    /// Return the verifier parameters of the circuit, checking them against the circuit.
+    pub fn verifier_params(&self, circuit: &Circuit) -> Result<VerifierParams> {
+        let (label, _) = circuit.build_cs()?;
+        let (_, bytes) = self
+            .bundle
+            .verifier_params
+            .iter()
+            .find(|(l, _)| *l == label)
+            .ok_or(NoahError::MissingVerifierParamsError)?;
+        load_verifier_params(bytes, circuit)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parameters::params::VerifierParams;
+    use crate::serialization::to_bincode;
+    use crate::setup::{
+        bundle_test_params_for, gen, load_prover_params, load_verifier_params, Circuit, TestParams,
+    };
+    use noah_algebra::prelude::*;
+    use noah_plonk::plonk::constraint_system::ConstraintSystem;
+    use noah_plonk::poly_commit::kzg_poly_com::KZGCommitmentSchemeBN254;
+
+    #[test]
+    fn test_gen_and_load() {
+        let mut prng = test_rng();
+        let (_, cs) = Circuit::ArToAbar.build_cs().unwrap();
+        let srs = KZGCommitmentSchemeBN254::new(cs.size() + 3, &mut prng);
+
+        let files = gen(&[Circuit::ArToAbar], &srs).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].label, "ar_to_abar");
+
+        // the generation is deterministic
+        let again = gen(&[Circuit::ArToAbar], &srs).unwrap();
+        assert_eq!(files[0].prover_params, again[0].prover_params);
+        assert_eq!(files[0].verifier_params, again[0].verifier_params);
+
+        assert!(load_prover_params(&files[0].prover_params, &Circuit::ArToAbar).is_ok());
+        assert!(load_verifier_params(&files[0].verifier_params, &Circuit::ArToAbar).is_ok());
+
+        // the parameters of another circuit are detected
+        assert!(load_verifier_params(&files[0].verifier_params, &Circuit::BarToAbar).is_err());
+        assert!(load_prover_params(&files[0].prover_params, &Circuit::AbarAmountRange).is_err());
+    }
+
+    #[test]
+    fn test_bundle_test_params() {
+        let circuits = [Circuit::ArToAbar, Circuit::AbarAmountRange];
+        let bytes = bundle_test_params_for(&circuits).unwrap();
+        assert_eq!(bytes, bundle_test_params_for(&circuits).unwrap());
+
+        let params = TestParams::mount(&bytes).unwrap();
+        assert_eq!(params.labels(), vec!["ar_to_abar", "abar_amount_range"]);
+
+        for circuit in circuits.iter() {
+            let verifier_params = params.verifier_params(circuit).unwrap();
+            let prover_params = params.prover_params(circuit).unwrap();
+            assert_eq!(
+                to_bincode(&verifier_params).unwrap(),
+                to_bincode(&VerifierParams::from(prover_params)).unwrap()
+            );
+        }
+        assert!(params.verifier_params(&Circuit::BarToAbar).is_err());
+    }
+}
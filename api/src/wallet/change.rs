@@ -0,0 +1,250 @@
+use crate::anon_xfr::{
+    abar_to_abar::AXfrBody,
+    structs::{AnonAssetRecord, OpenAnonAssetRecord, OpenAnonAssetRecordBuilder},
+};
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey};
+use crate::xfr::structs::AssetType;
+use noah_algebra::{
+    collections::{BTreeMap, BTreeSet},
+    prelude::*,
+};
+
+/// A record in the local snapshot of a wallet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WalletRecord {
+    /// The opened record.
+    pub oabar: OpenAnonAssetRecord,
+    /// Whether the record is the change of a note built by the wallet.
+    pub is_change: bool,
+}
+
+/// The local snapshot of the records of a wallet, indexed by their commitments.
+#[derive(Clone, Debug, Default)]
+pub struct WalletSnapshot {
+    records: BTreeMap<Vec<u8>, WalletRecord>,
+}
+
+impl WalletSnapshot {
+    /// Create an empty snapshot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert an opened record, replacing the record of the same commitment.
+    pub fn insert(&mut self, oabar: OpenAnonAssetRecord, is_change: bool) {
+        let key = AnonAssetRecord::from_oabar(&oabar).commitment.to_bytes();
+        self.records.insert(key, WalletRecord { oabar, is_change });
+    }
+
+    /// Mark the record as change, and return false if the record is not in the snapshot.
+    pub fn mark_change(&mut self, abar: &AnonAssetRecord) -> bool {
+        match self.records.get_mut(&abar.commitment.to_bytes()) {
+            Some(record) => {
+                record.is_change = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Return the record of the commitment.
+    pub fn get(&self, abar: &AnonAssetRecord) -> Option<&WalletRecord> {
+        self.records.get(&abar.commitment.to_bytes())
+    }
+
+    /// Return the records that are change.
+    pub fn change_records(&self) -> Vec<&WalletRecord> {
+        self.records.values().filter(|r| r.is_change).collect()
+    }
+
+    /// Return the number of the records.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Check if there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+/// Return the outputs of an anonymous transfer built by the wallet that are change,
+/// i.e., those whose owner memos open under the key pair of the sender, with their indices.
+pub fn detect_change_outputs(
+    body: &AXfrBody,
+    keypair: &KeyPair,
+) -> Vec<(usize, OpenAnonAssetRecord)> {
+    body.outputs
+        .iter()
+        .zip(body.owner_memos.iter())
+        .enumerate()
+        .filter_map(|(i, (abar, memo))| {
+            OpenAnonAssetRecordBuilder::from_abar(abar, memo.clone(), keypair)
+                .and_then(|builder| builder.build())
+                .ok()
+                .map(|oabar| (i, oabar))
+        })
+        .collect()
+}
+
+/// Insert the change outputs of an anonymous transfer built by the wallet into the snapshot,
+/// marked as change, and return their indices.
+pub fn mark_change_outputs(
+    snapshot: &mut WalletSnapshot,
+    body: &AXfrBody,
+    keypair: &KeyPair,
+) -> Vec<usize> {
+    detect_change_outputs(body, keypair)
+        .into_iter()
+        .map(|(i, oabar)| {
+            snapshot.insert(oabar, true);
+            i
+        })
+        .collect()
+}
+
+/// Split the change amount into `n` positive amounts uniformly at random, so that the amounts
+/// of the change outputs do not reveal which output is the change.
+pub fn split_change_amount<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    amount: u64,
+    n: usize,
+) -> Result<Vec<u64>> {
+    if n == 0 || n as u64 > amount {
+        return Err(NoahError::ParameterError);
+    }
+
+    // the n - 1 distinct cut points in (0, amount)
+    let mut cuts = BTreeSet::new();
+    while cuts.len() < n - 1 {
+        cuts.insert(prng.gen_range(1..amount));
+    }
+
+    let mut amounts = Vec::with_capacity(n);
+    let mut last = 0u64;
+    for cut in cuts.into_iter().chain(core::iter::once(amount)) {
+        amounts.push(cut - last);
+        last = cut;
+    }
+    Ok(amounts)
+}
+
+/// Build `n` change outputs to the public key that sum to the change amount,
+/// with the amount split by `split_change_amount`.
+pub fn build_change_outputs<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    amount: u64,
+    asset_type: AssetType,
+    pub_key: &PublicKey,
+    n: usize,
+) -> Result<Vec<OpenAnonAssetRecord>> {
+    split_change_amount(prng, amount, n)?
+        .into_iter()
+        .map(|amount| {
+            OpenAnonAssetRecordBuilder::new()
+                .amount(amount)
+                .asset_type(asset_type)
+                .pub_key(pub_key)
+                .finalize(prng)?
+                .build()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anon_xfr::{
+        abar_to_abar::AXfrBody,
+        structs::{AnonAssetRecord, OpenAnonAssetRecordBuilder},
+    };
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::wallet::change::{
+        build_change_outputs, mark_change_outputs, split_change_amount, WalletSnapshot,
+    };
+    use crate::xfr::structs::AssetType;
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    #[test]
+    fn test_split_change_amount() {
+        let mut prng = test_rng();
+        assert!(split_change_amount(&mut prng, 10, 0).is_err());
+        assert!(split_change_amount(&mut prng, 2, 3).is_err());
+        assert_eq!(split_change_amount(&mut prng, 10, 1).unwrap(), vec![10]);
+        assert_eq!(split_change_amount(&mut prng, 3, 3).unwrap(), vec![1, 1, 1]);
+
+        for _ in 0..100 {
+            let amounts = split_change_amount(&mut prng, 1000, 4).unwrap();
+            assert_eq!(amounts.len(), 4);
+            assert_eq!(amounts.iter().sum::<u64>(), 1000);
+            assert!(amounts.iter().all(|amount| *amount > 0));
+        }
+        // the split is randomized
+        assert_ne!(
+            split_change_amount(&mut prng, 1000, 4).unwrap(),
+            split_change_amount(&mut prng, 1000, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_change_outputs() {
+        let mut prng = test_rng();
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let asset_type = AssetType::from_identical_byte(1u8);
+
+        let payment = OpenAnonAssetRecordBuilder::new()
+            .amount(70)
+            .asset_type(asset_type)
+            .pub_key(&receiver.get_pk())
+            .finalize(&mut prng)
+            .unwrap()
+            .build()
+            .unwrap();
+        let change = build_change_outputs(&mut prng, 30, asset_type, &sender.get_pk(), 2).unwrap();
+        assert_eq!(change.iter().map(|o| o.get_amount()).sum::<u64>(), 30);
+
+        let outputs = vec![change[0].clone(), payment, change[1].clone()];
+        let body = AXfrBody {
+            inputs: vec![BN254Scalar::random(&mut prng)],
+            outputs: outputs.iter().map(AnonAssetRecord::from_oabar).collect(),
+            merkle_root: BN254Scalar::zero(),
+            merkle_root_version: 0,
+            fee: 0,
+            owner_memos: outputs
+                .iter()
+                .map(|o| o.get_owner_memo().unwrap())
+                .collect(),
+            tracer_memos: vec![],
+            output_payloads: vec![],
+        };
+
+        let mut snapshot = WalletSnapshot::new();
+        assert_eq!(
+            mark_change_outputs(&mut snapshot, &body, &sender),
+            vec![0, 2]
+        );
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot.change_records().len(), 2);
+        assert!(snapshot.get(&body.outputs[0]).unwrap().is_change);
+        assert!(snapshot.get(&body.outputs[1]).is_none());
+        assert!(!snapshot.mark_change(&body.outputs[1]));
+
+        // the receiver does not see any change
+        let mut receiver_snapshot = WalletSnapshot::new();
+        assert!(mark_change_outputs(&mut receiver_snapshot, &body, &receiver).is_empty());
+        let oabar = OpenAnonAssetRecordBuilder::from_abar(
+            &body.outputs[1],
+            body.owner_memos[1].clone(),
+            &receiver,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        receiver_snapshot.insert(oabar, false);
+        assert!(receiver_snapshot.change_records().is_empty());
+        assert!(receiver_snapshot.mark_change(&body.outputs[1]));
+        assert_eq!(receiver_snapshot.change_records().len(), 1);
+    }
+}
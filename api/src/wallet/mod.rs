@@ -0,0 +1,4 @@
+/// Module for the detection of the change outputs and the splitting of the change.
+pub mod change;
+/// Module for the tracking of the information leaked by the conversions of a wallet.
+pub mod privacy;
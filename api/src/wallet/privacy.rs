@@ -0,0 +1,350 @@
+use crate::xfr::structs::{AssetType, BlindAssetRecord};
+use noah_algebra::{collections::BTreeMap, prelude::*};
+
+/// An operation of a wallet, as seen by an observer of the ledger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrivacyOperation {
+    /// An anonymous transfer, which reveals nothing about the balances.
+    AnonTransfer,
+    /// A conversion of a transparent or confidential record into an anonymous record
+    /// (`ar_to_abar`, `bar_to_abar`), which links the address of the sender to the pool.
+    Shield {
+        /// The asset type of the record.
+        asset_type: AssetType,
+        /// The amount of the record.
+        amount: u64,
+        /// Whether the amount is visible on the ledger.
+        amount_revealed: bool,
+    },
+    /// A conversion of an anonymous record into a transparent or confidential record
+    /// (`abar_to_ar`, `abar_to_bar`), or a burn, which links the address of the receiver.
+    Unshield {
+        /// The asset type of the record.
+        asset_type: AssetType,
+        /// The amount of the record.
+        amount: u64,
+        /// Whether the amount is visible on the ledger.
+        amount_revealed: bool,
+    },
+}
+
+impl PrivacyOperation {
+    /// Return the shielding of a record, whose amount is revealed unless it is confidential.
+    pub fn shield_record(bar: &BlindAssetRecord, asset_type: AssetType, amount: u64) -> Self {
+        PrivacyOperation::Shield {
+            asset_type,
+            amount,
+            amount_revealed: !bar.amount.is_confidential(),
+        }
+    }
+
+    /// Return the unshielding into a record, whose amount is revealed unless it is confidential.
+    pub fn unshield_record(bar: &BlindAssetRecord, asset_type: AssetType, amount: u64) -> Self {
+        PrivacyOperation::Unshield {
+            asset_type,
+            amount,
+            amount_revealed: !bar.amount.is_confidential(),
+        }
+    }
+}
+
+/// What an observer learned about the balance of the wallet in one asset type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssetExposure {
+    /// The total of the revealed amounts that entered the pool.
+    pub shielded: u64,
+    /// The total of the revealed amounts that left the pool.
+    pub unshielded: u64,
+    /// The revealed amounts that entered the pool and have not been matched by an exit.
+    pub unmatched_shields: Vec<u64>,
+    /// The number of conversions whose amounts are hidden.
+    pub hidden_conversions: u64,
+}
+
+impl AssetExposure {
+    /// Return the balance that an observer attributes to the wallet from the revealed amounts,
+    /// or `None` if a hidden amount makes it an estimate only.
+    pub fn observed_balance(&self) -> Option<u64> {
+        if self.hidden_conversions > 0 {
+            return None;
+        }
+        Some(self.shielded.saturating_sub(self.unshielded))
+    }
+}
+
+/// The limits past which a wallet should warn its user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrivacyBudget {
+    /// The maximal number of conversions linking the addresses of the wallet to the pool.
+    pub max_linked_conversions: u64,
+}
+
+impl Default for PrivacyBudget {
+    fn default() -> Self {
+        PrivacyBudget {
+            max_linked_conversions: 10,
+        }
+    }
+}
+
+/// A reason that a planned operation degrades the privacy of the wallet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrivacyWarning {
+    /// The operation links an address of the wallet to the pool.
+    LinksAddress,
+    /// The operation reveals the amount.
+    RevealsAmount(u64),
+    /// The revealed amount leaving the pool equals an amount that entered it, which links both.
+    MatchesShieldedAmount(u64),
+    /// After the operation, the balance attributed by an observer is the actual balance.
+    RevealsBalance(u64),
+    /// The operation exceeds the budget of linked conversions.
+    ExceedsBudget(u64),
+}
+
+impl PrivacyWarning {
+    /// Return the weight of the warning in the score of an operation.
+    pub fn severity(&self) -> u32 {
+        match self {
+            PrivacyWarning::LinksAddress => 1,
+            PrivacyWarning::RevealsAmount(_) => 1,
+            PrivacyWarning::ExceedsBudget(_) => 2,
+            PrivacyWarning::MatchesShieldedAmount(_) => 3,
+            PrivacyWarning::RevealsBalance(_) => 3,
+        }
+    }
+}
+
+/// The assessment of a planned operation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PrivacyAssessment {
+    /// The sum of the severities of the warnings, zero if the operation leaks nothing.
+    pub score: u32,
+    /// The warnings.
+    pub warnings: Vec<PrivacyWarning>,
+}
+
+/// The tracker of the information that the operations of a wallet leaked about its balances.
+#[derive(Clone, Debug, Default)]
+pub struct PrivacyTracker {
+    linked_conversions: u64,
+    exposures: BTreeMap<AssetType, AssetExposure>,
+}
+
+impl PrivacyTracker {
+    /// Create a tracker of a wallet that has not leaked anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of conversions linking the addresses of the wallet to the pool.
+    pub fn linked_conversions(&self) -> u64 {
+        self.linked_conversions
+    }
+
+    /// Return the exposure of the asset type.
+    pub fn exposure(&self, asset_type: &AssetType) -> AssetExposure {
+        self.exposures.get(asset_type).cloned().unwrap_or_default()
+    }
+
+    /// Record an operation submitted by the wallet.
+    pub fn record(&mut self, op: &PrivacyOperation) {
+        if let Some((asset_type, conversion)) = Conversion::of(op) {
+            self.linked_conversions += 1;
+            conversion.apply(self.exposures.entry(asset_type).or_default());
+        }
+    }
+
+    /// Assess a planned operation, given the actual anonymous balance of the wallet in the
+    /// asset type of the operation before it.
+    pub fn assess(
+        &self,
+        op: &PrivacyOperation,
+        balance: u64,
+        budget: &PrivacyBudget,
+    ) -> PrivacyAssessment {
+        let (asset_type, conversion) = match Conversion::of(op) {
+            Some(c) => c,
+            None => return PrivacyAssessment::default(),
+        };
+
+        let mut warnings = vec![PrivacyWarning::LinksAddress];
+        if self.linked_conversions >= budget.max_linked_conversions {
+            warnings.push(PrivacyWarning::ExceedsBudget(self.linked_conversions + 1));
+        }
+        if conversion.amount_revealed {
+            warnings.push(PrivacyWarning::RevealsAmount(conversion.amount));
+        }
+
+        let mut exposure = self.exposure(&asset_type);
+        if conversion.amount_revealed
+            && !conversion.shield
+            && exposure.unmatched_shields.contains(&conversion.amount)
+        {
+            warnings.push(PrivacyWarning::MatchesShieldedAmount(conversion.amount));
+        }
+
+        conversion.apply(&mut exposure);
+        let balance_after = if conversion.shield {
+            balance.saturating_add(conversion.amount)
+        } else {
+            balance.saturating_sub(conversion.amount)
+        };
+        if exposure.observed_balance() == Some(balance_after) {
+            warnings.push(PrivacyWarning::RevealsBalance(balance_after));
+        }
+
+        PrivacyAssessment {
+            score: warnings.iter().map(PrivacyWarning::severity).sum(),
+            warnings,
+        }
+    }
+}
+
+/// A conversion between anonymous and non-anonymous records.
+struct Conversion {
+    amount: u64,
+    amount_revealed: bool,
+    shield: bool,
+}
+
+impl Conversion {
+    fn of(op: &PrivacyOperation) -> Option<(AssetType, Conversion)> {
+        match *op {
+            PrivacyOperation::AnonTransfer => None,
+            PrivacyOperation::Shield {
+                asset_type,
+                amount,
+                amount_revealed,
+            } => Some((
+                asset_type,
+                Conversion {
+                    amount,
+                    amount_revealed,
+                    shield: true,
+                },
+            )),
+            PrivacyOperation::Unshield {
+                asset_type,
+                amount,
+                amount_revealed,
+            } => Some((
+                asset_type,
+                Conversion {
+                    amount,
+                    amount_revealed,
+                    shield: false,
+                },
+            )),
+        }
+    }
+
+    fn apply(&self, exposure: &mut AssetExposure) {
+        if !self.amount_revealed {
+            exposure.hidden_conversions += 1;
+        } else if self.shield {
+            exposure.shielded = exposure.shielded.saturating_add(self.amount);
+            exposure.unmatched_shields.push(self.amount);
+        } else {
+            exposure.unshielded = exposure.unshielded.saturating_add(self.amount);
+            if let Some(pos) = exposure
+                .unmatched_shields
+                .iter()
+                .position(|a| *a == self.amount)
+            {
+                exposure.unmatched_shields.remove(pos);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::wallet::privacy::{PrivacyBudget, PrivacyOperation, PrivacyTracker, PrivacyWarning};
+    use crate::xfr::structs::AssetType;
+
+    #[test]
+    fn test_privacy_tracker() {
+        let asset_type = AssetType::from_identical_byte(1u8);
+        let other_asset_type = AssetType::from_identical_byte(2u8);
+        let shield = |amount| PrivacyOperation::Shield {
+            asset_type,
+            amount,
+            amount_revealed: true,
+        };
+        let unshield = |amount| PrivacyOperation::Unshield {
+            asset_type,
+            amount,
+            amount_revealed: true,
+        };
+        let budget = PrivacyBudget::default();
+        let mut tracker = PrivacyTracker::new();
+
+        // anonymous transfers leak nothing
+        let assessment = tracker.assess(&PrivacyOperation::AnonTransfer, 0, &budget);
+        assert_eq!(assessment.score, 0);
+        assert!(assessment.warnings.is_empty());
+
+        // shielding into an empty wallet reveals the balance
+        assert_eq!(
+            tracker.assess(&shield(100), 0, &budget).warnings,
+            vec![
+                PrivacyWarning::LinksAddress,
+                PrivacyWarning::RevealsAmount(100),
+                PrivacyWarning::RevealsBalance(100),
+            ]
+        );
+        tracker.record(&shield(100));
+        tracker.record(&shield(40));
+        assert_eq!(tracker.linked_conversions(), 2);
+        assert_eq!(tracker.exposure(&asset_type).observed_balance(), Some(140));
+
+        // unshielding a shielded amount links both, unless the wallet received more anonymously
+        let assessment = tracker.assess(&unshield(40), 500, &budget);
+        assert_eq!(
+            assessment.warnings,
+            vec![
+                PrivacyWarning::LinksAddress,
+                PrivacyWarning::RevealsAmount(40),
+                PrivacyWarning::MatchesShieldedAmount(40),
+            ]
+        );
+        assert_eq!(assessment.score, 5);
+        assert!(tracker
+            .assess(&unshield(40), 140, &budget)
+            .warnings
+            .contains(&PrivacyWarning::RevealsBalance(100)));
+        tracker.record(&unshield(40));
+        assert!(!tracker
+            .assess(&unshield(40), 100, &budget)
+            .warnings
+            .contains(&PrivacyWarning::MatchesShieldedAmount(40)));
+
+        // hidden amounts only link the addresses
+        let hidden = PrivacyOperation::Shield {
+            asset_type: other_asset_type,
+            amount: 70,
+            amount_revealed: false,
+        };
+        assert_eq!(
+            tracker.assess(&hidden, 0, &budget).warnings,
+            vec![PrivacyWarning::LinksAddress]
+        );
+        tracker.record(&hidden);
+        assert_eq!(tracker.exposure(&other_asset_type).observed_balance(), None);
+
+        // the budget of linked conversions
+        let tight = PrivacyBudget {
+            max_linked_conversions: 5,
+        };
+        assert!(!tracker
+            .assess(&hidden, 70, &tight)
+            .warnings
+            .contains(&PrivacyWarning::ExceedsBudget(5)));
+        tracker.record(&hidden);
+        assert!(tracker
+            .assess(&hidden, 140, &tight)
+            .warnings
+            .contains(&PrivacyWarning::ExceedsBudget(6)));
+    }
+}
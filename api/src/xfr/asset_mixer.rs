@@ -7,11 +7,11 @@ use bulletproofs::{
 };
 use merlin::Transcript;
 use noah_algebra::{
+    collections::BTreeSet,
     prelude::*,
     ristretto::{CompressedRistretto, RistrettoScalar},
 };
 use noah_crypto::bulletproofs::mix::{mix, MixCommitment, MixValue};
-use wasm_bindgen::__rt::std::collections::HashSet;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 /// The asset mixing proof.
@@ -80,14 +80,14 @@ pub fn prove_asset_mixing<R: CryptoRng + RngCore>(
     let (in_values, in_blinds) = extract_values_and_blinds(inputs);
     let (out_values, out_blinds) = extract_values_and_blinds(outputs);
 
-    let mut in_set = HashSet::new();
+    let mut in_set = BTreeSet::new();
     for in_value in in_values.iter() {
-        in_set.insert(in_value.asset_type.0);
+        in_set.insert(in_value.asset_type.to_bytes());
     }
 
-    let mut out_set = HashSet::new();
+    let mut out_set = BTreeSet::new();
     for out_value in out_values.iter() {
-        out_set.insert(out_value.asset_type.0);
+        out_set.insert(out_value.asset_type.to_bytes());
     }
     if in_set != out_set {
         return Err(NoahError::ParameterError);
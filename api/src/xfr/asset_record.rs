@@ -5,13 +5,15 @@ use crate::anon_creds::{
 use crate::errors::{NoahError, Result};
 use crate::keys::{KeyPair, PublicKey};
 use crate::xfr::structs::{
-    AssetRecord, AssetRecordTemplate, AssetType, BlindAssetRecord, OpenAssetRecord, OwnerMemo,
-    TracerMemo, TracingPolicies, XfrAmount, XfrAssetType,
+    AssetRecord, AssetRecordTemplate, AssetTracerDecKeys, AssetType, BlindAssetRecord,
+    CommitteeMemo, OpenAssetRecord, OwnerMemo, OwnerMemoVersion, RecordOpening, TracerMemo,
+    TracingPolicies, ViewerMemo, XfrAmount, XfrAssetType,
 };
 use noah_algebra::{
     prelude::*,
     ristretto::{PedersenCommitmentRistretto, RistrettoScalar},
 };
+use noah_crypto::threshold_encryption::{DecryptionShare, ThresholdEncKey};
 
 /// AssetRecord confidentiality flags. Indicated if amount and/or asset type should be confidential.
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -294,6 +296,7 @@ impl AssetRecordTemplate {
             public_key: address,
             asset_record_type,
             asset_tracing_policies: TracingPolicies::new(),
+            owner_memo_version: OwnerMemoVersion::Legacy,
         }
     }
 
@@ -314,6 +317,19 @@ impl AssetRecordTemplate {
         template.asset_tracing_policies = policies;
         template
     }
+
+    /// Pad the plaintext of the lock of the owner memo to the given size, which must be in
+    /// `OWNER_MEMO_PADDING_BUCKETS`, so that the size of the memo does not reveal which fields
+    /// of the record are confidential. Padding requires an ed25519 address.
+    pub fn with_owner_memo_padding(mut self, size: usize) -> Result<AssetRecordTemplate> {
+        let version = OwnerMemoVersion::PaddedHpke(size);
+        version.padded_size()?;
+        if self.public_key.to_hpke_public_key().is_err() {
+            return Err(NoahError::ParameterError);
+        }
+        self.owner_memo_version = version;
+        Ok(self)
+    }
 }
 
 fn sample_blind_asset_record<R: CryptoRng + RngCore>(
@@ -339,9 +355,13 @@ fn sample_blind_asset_record<R: CryptoRng + RngCore>(
             ),
 
             AssetRecordType::ConfidentialAmount_NonConfidentialAssetType => {
-                let (owner_memo, amount_blinds) =
-                    OwnerMemo::from_amount(prng, asset_record.amount, &asset_record.public_key)
-                        .unwrap(); // safe unwrap
+                let (owner_memo, amount_blinds) = OwnerMemo::from_amount_with_version(
+                    prng,
+                    asset_record.amount,
+                    &asset_record.public_key,
+                    asset_record.owner_memo_version,
+                )
+                .unwrap(); // safe unwrap
 
                 (
                     XfrAmount::from_blinds(
@@ -358,10 +378,11 @@ fn sample_blind_asset_record<R: CryptoRng + RngCore>(
             }
 
             AssetRecordType::NonConfidentialAmount_ConfidentialAssetType => {
-                let (owner_memo, asset_type_blind) = OwnerMemo::from_asset_type(
+                let (owner_memo, asset_type_blind) = OwnerMemo::from_asset_type_with_version(
                     prng,
                     &asset_record.asset_type,
                     &asset_record.public_key,
+                    asset_record.owner_memo_version,
                 )
                 .unwrap(); //safe unwrap
 
@@ -376,11 +397,12 @@ fn sample_blind_asset_record<R: CryptoRng + RngCore>(
 
             AssetRecordType::ConfidentialAmount_ConfidentialAssetType => {
                 let (owner_memo, amount_blinds, asset_type_blind) =
-                    OwnerMemo::from_amount_and_asset_type(
+                    OwnerMemo::from_amount_and_asset_type_with_version(
                         prng,
                         asset_record.amount,
                         &asset_record.asset_type,
                         &asset_record.public_key,
+                        asset_record.owner_memo_version,
                     )
                     .unwrap(); //safe unwrap
                 (
@@ -562,6 +584,61 @@ pub fn open_blind_asset_record(
     })
 }
 
+/// Open a blind asset record with the decryption shares of at least t members of the committee
+/// that the `CommitteeMemo` is locked to.
+pub fn open_blind_asset_record_by_committee(
+    input: &BlindAssetRecord,
+    committee_memo: &CommitteeMemo,
+    committee: &ThresholdEncKey,
+    shares: &[DecryptionShare],
+) -> Result<OpenAssetRecord> {
+    open_blind_asset_record_with_opening(input, committee_memo.combine(committee, shares)?)
+}
+
+/// Open a blind asset record with the `ViewerMemo` forwarded to the viewer by the recipient,
+/// after verifying the proof of the memo, and checking that the forwarded opening matches
+/// the commitments of the record.
+pub fn open_blind_asset_record_by_viewer<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    input: &BlindAssetRecord,
+    viewer_memo: &ViewerMemo,
+    dec_key: &AssetTracerDecKeys,
+) -> Result<OpenAssetRecord> {
+    viewer_memo.verify(prng, input)?;
+    open_blind_asset_record_with_opening(input, viewer_memo.decrypt(dec_key)?)
+}
+
+/// Open a blind asset record with an opening, checking it against the commitments of the record.
+fn open_blind_asset_record_with_opening(
+    input: &BlindAssetRecord,
+    opening: RecordOpening,
+) -> Result<OpenAssetRecord> {
+    let (amount, asset_type, amount_blinds, type_blind) = opening;
+
+    let pc_gens = PedersenCommitmentRistretto::default();
+    let expected_amount = if input.amount.is_confidential() {
+        XfrAmount::from_blinds(&pc_gens, amount, &amount_blinds.0, &amount_blinds.1)
+    } else {
+        XfrAmount::NonConfidential(amount)
+    };
+    let expected_asset_type = if input.asset_type.is_confidential() {
+        XfrAssetType::from_blind(&pc_gens, &asset_type, &type_blind)
+    } else {
+        XfrAssetType::NonConfidential(asset_type)
+    };
+    if input.amount != expected_amount || input.asset_type != expected_asset_type {
+        return Err(NoahError::ParameterError);
+    }
+
+    Ok(OpenAssetRecord {
+        blind_asset_record: input.clone(),
+        amount,
+        amount_blinds,
+        asset_type,
+        type_blind,
+    })
+}
+
 /// Helper function to generate assemble asset record from templates.
 fn build_record_input_from_template<R: CryptoRng + RngCore>(
     prng: &mut R,
@@ -608,15 +685,18 @@ fn build_record_input_from_template<R: CryptoRng + RngCore>(
 #[cfg(feature = "xfr-tracing")]
 #[cfg(test)]
 mod test {
-    use super::{build_blind_asset_record, build_open_asset_record, open_blind_asset_record};
+    use super::{
+        build_blind_asset_record, build_open_asset_record, open_blind_asset_record,
+        open_blind_asset_record_by_committee, open_blind_asset_record_by_viewer,
+    };
     use crate::errors::NoahError;
     use crate::keys::KeyPair;
-    use crate::parameters::AddressFormat::SECP256K1;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
     use crate::xfr::{
         asset_record::AssetRecordType,
         structs::{
-            AssetRecordTemplate, AssetTracerKeyPair, AssetType, OpenAssetRecord, TracingPolicies,
-            TracingPolicy, XfrAmount, XfrAssetType,
+            AssetRecordTemplate, AssetTracerKeyPair, AssetType, CommitteeMemo, OpenAssetRecord,
+            TracingPolicies, TracingPolicy, XfrAmount, XfrAssetType,
         },
         tests::{create_xfr, gen_key_pair_vec},
     };
@@ -625,6 +705,7 @@ mod test {
         ristretto::{PedersenCommitmentRistretto, RistrettoScalar},
         traits::PedersenCommitment,
     };
+    use noah_crypto::threshold_encryption::threshold_key_gen;
 
     fn do_test_build_open_asset_record(record_type: AssetRecordType, asset_tracing: bool) {
         let mut prng = test_rng();
@@ -990,4 +1071,179 @@ mod test {
             "Expect error as asset type and amount are confidential"
         );
     }
+
+    #[test]
+    fn test_open_blind_asset_record_by_committee() {
+        let mut prng = test_rng();
+        let pc_gens = PedersenCommitmentRistretto::default();
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+        let (committee, key_shares) = threshold_key_gen(&mut prng, 2, 3).unwrap();
+
+        for record_type in [
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+            AssetRecordType::ConfidentialAmount_NonConfidentialAssetType,
+            AssetRecordType::NonConfidentialAmount_ConfidentialAssetType,
+        ] {
+            let ar = AssetRecordTemplate::with_no_asset_tracing(
+                100u64,
+                AssetType(prng.gen()),
+                record_type,
+                keypair.pub_key,
+            );
+            let (open_ar, _, owner_memo) =
+                build_open_asset_record(&mut prng, &pc_gens, &ar, vec![]);
+            let committee_memo = CommitteeMemo::new(&mut prng, &committee, &open_ar);
+
+            let shares = key_shares
+                .iter()
+                .map(|key_share| committee_memo.decrypt_share(&mut prng, key_share))
+                .collect_vec();
+            for share in shares.iter() {
+                committee_memo.verify_share(&committee, share).unwrap();
+            }
+
+            let opened = open_blind_asset_record_by_committee(
+                &open_ar.blind_asset_record,
+                &committee_memo,
+                &committee,
+                &shares[1..],
+            )
+            .unwrap();
+            let expected =
+                open_blind_asset_record(&open_ar.blind_asset_record, &owner_memo, &keypair)
+                    .unwrap();
+            assert_eq!(opened, expected);
+
+            // a single member cannot open the record
+            assert!(open_blind_asset_record_by_committee(
+                &open_ar.blind_asset_record,
+                &committee_memo,
+                &committee,
+                &shares[..1],
+            )
+            .is_err());
+
+            // the recipient locks the opening to the committee through the owner memo
+            let owner_memo = owner_memo.unwrap();
+            let committee_memo = owner_memo
+                .lock_to_committee(&mut prng, &keypair, &open_ar.blind_asset_record, &committee)
+                .unwrap();
+            let shares = key_shares[..2]
+                .iter()
+                .map(|key_share| committee_memo.decrypt_share(&mut prng, key_share))
+                .collect_vec();
+            let opened = open_blind_asset_record_by_committee(
+                &open_ar.blind_asset_record,
+                &committee_memo,
+                &committee,
+                &shares,
+            )
+            .unwrap();
+            assert_eq!(opened, expected);
+
+            // but only with the keys that the memo opens the record with
+            let other_keypair = KeyPair::sample(&mut prng, SECP256K1);
+            assert!(owner_memo
+                .lock_to_committee(
+                    &mut prng,
+                    &other_keypair,
+                    &open_ar.blind_asset_record,
+                    &committee
+                )
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_open_blind_asset_record_by_viewer() {
+        let mut prng = test_rng();
+        let pc_gens = PedersenCommitmentRistretto::default();
+        let keypair = KeyPair::sample(&mut prng, SECP256K1);
+        let other = KeyPair::sample(&mut prng, ED25519);
+        let viewer = AssetTracerKeyPair::generate(&mut prng);
+        let other_viewer = AssetTracerKeyPair::generate(&mut prng);
+
+        for record_type in [
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+            AssetRecordType::ConfidentialAmount_NonConfidentialAssetType,
+            AssetRecordType::NonConfidentialAmount_ConfidentialAssetType,
+        ] {
+            let ar = AssetRecordTemplate::with_no_asset_tracing(
+                100u64,
+                AssetType(prng.gen()),
+                record_type,
+                keypair.pub_key,
+            );
+            let (open_ar, _, owner_memo) =
+                build_open_asset_record(&mut prng, &pc_gens, &ar, vec![]);
+            let owner_memo = owner_memo.unwrap();
+            let record = &open_ar.blind_asset_record;
+
+            let viewer_memo = owner_memo
+                .add_viewer(&mut prng, &keypair, record, &viewer.enc_key)
+                .unwrap();
+            assert!(viewer_memo.verify(&mut prng, record).is_ok());
+            let opened =
+                open_blind_asset_record_by_viewer(&mut prng, record, &viewer_memo, &viewer.dec_key)
+                    .unwrap();
+            assert_eq!(opened, open_ar);
+            assert!(open_blind_asset_record_by_viewer(
+                &mut prng,
+                record,
+                &viewer_memo,
+                &other_viewer.dec_key
+            )
+            .is_err());
+
+            // the memo cannot be forwarded by someone else, nor for another record
+            assert!(owner_memo
+                .add_viewer(&mut prng, &other, record, &viewer.enc_key)
+                .is_err());
+            let (other_ar, _, other_memo) =
+                build_open_asset_record(&mut prng, &pc_gens, &ar, vec![]);
+            let other_record = &other_ar.blind_asset_record;
+            assert!(owner_memo
+                .add_viewer(&mut prng, &keypair, other_record, &viewer.enc_key)
+                .is_err());
+            // a memo forwarded for another record does not verify against the record
+            assert!(viewer_memo.verify(&mut prng, other_record).is_err());
+            assert!(open_blind_asset_record_by_viewer(
+                &mut prng,
+                other_record,
+                &viewer_memo,
+                &viewer.dec_key
+            )
+            .is_err());
+
+            // the locks must match what the owner memo encrypts
+            let other_viewer_memo = other_memo
+                .unwrap()
+                .add_viewer(&mut prng, &keypair, other_record, &viewer.enc_key)
+                .unwrap();
+            let mut bad_memo = viewer_memo.clone();
+            if record.amount.is_confidential() {
+                bad_memo.lock_amount = other_viewer_memo.lock_amount.clone();
+            } else {
+                bad_memo.lock_asset_type = other_viewer_memo.lock_asset_type.clone();
+            }
+            assert!(bad_memo.verify(&mut prng, record).is_err());
+            let mut bad_memo = viewer_memo.clone();
+            bad_memo.proof = other_viewer_memo.proof.clone();
+            assert!(bad_memo.verify(&mut prng, record).is_err());
+            let mut bad_memo = viewer_memo.clone();
+            bad_memo.lock_asset_type = None;
+            bad_memo.lock_amount = None;
+            assert!(bad_memo.verify(&mut prng, record).is_err());
+            // the opening of another record is rejected even with a valid proof
+            let mut bad_memo = viewer_memo.clone();
+            bad_memo.lock_info = other_viewer_memo.lock_info.clone();
+            assert!(open_blind_asset_record_by_viewer(
+                &mut prng,
+                record,
+                &bad_memo,
+                &viewer.dec_key
+            )
+            .is_err());
+        }
+    }
 }
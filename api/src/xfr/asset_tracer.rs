@@ -1,7 +1,7 @@
 use crate::anon_creds::{Attr, AttributeCiphertext};
 use crate::errors::{NoahError, Result};
 use crate::xfr::structs::{
-    AssetTracerDecKeys, AssetTracerEncKeys, AssetType, TracerMemo, ASSET_TYPE_LENGTH,
+    AssetTracerDecKeys, AssetTracerEncKeys, AssetType, CommitteeMemo, TracerMemo, ASSET_TYPE_LENGTH,
 };
 use noah_algebra::{
     bn254::{BN254Scalar, BN254G1},
@@ -13,6 +13,9 @@ use noah_crypto::{
         elgamal_encrypt, elgamal_partial_decrypt, ElGamalCiphertext, ElGamalDecKey, ElGamalEncKey,
     },
     hybrid_encryption::{hybrid_decrypt_with_x25519_secret_key, hybrid_encrypt_x25519},
+    threshold_encryption::{
+        combine_decryption_shares, threshold_encrypt, DecryptionShare, ThresholdEncKey,
+    },
 };
 
 /// The encryption key for the record data.
@@ -79,36 +82,67 @@ impl TracerMemo {
     /// Decrypts the asset tracer memo:
     /// Returns NoahError:BogusAssetTracerMemo in case decrypted values are inconsistents
     pub fn decrypt(&self, dec_key: &AssetTracerDecKeys) -> Result<DecryptedAssetMemo> {
-        let mut plaintext =
+        let plaintext =
+            hybrid_decrypt_with_x25519_secret_key(&self.lock_info, &dec_key.lock_info_dec_key);
+        let decrypted = self.parse_lock_info(plaintext)?;
+        self.check_decrypted(dec_key, &decrypted)?;
+        Ok(decrypted)
+    }
+
+    /// Lock what the memo encrypts to a t-of-n committee, after checking it with the keys
+    /// of the tracer, so that the committee can trace the record without a single decryptor.
+    pub fn lock_to_committee<R: CryptoRng + RngCore>(
+        &self,
+        prng: &mut R,
+        dec_key: &AssetTracerDecKeys,
+        committee: &ThresholdEncKey,
+    ) -> Result<CommitteeMemo> {
+        let plaintext =
             hybrid_decrypt_with_x25519_secret_key(&self.lock_info, &dec_key.lock_info_dec_key);
+        let decrypted = self.parse_lock_info(plaintext.clone())?;
+        self.check_decrypted(dec_key, &decrypted)?;
+        Ok(CommitteeMemo {
+            lock: threshold_encrypt(prng, committee, &plaintext),
+        })
+    }
 
-        // decrypt and sanitize amount
+    /// Decrypt what the memo encrypts with the decryption shares of at least t members of
+    /// the committee that the `CommitteeMemo` locks it to.
+    ///
+    /// Unlike `decrypt`, the values are not checked against the ciphertexts of the memo,
+    /// which needs the keys of the tracer, but only against the shares.
+    pub fn decrypt_by_committee(
+        &self,
+        committee_memo: &CommitteeMemo,
+        committee: &ThresholdEncKey,
+        shares: &[DecryptionShare],
+    ) -> Result<DecryptedAssetMemo> {
+        let plaintext = combine_decryption_shares(committee, &committee_memo.lock, shares)?;
+        self.parse_lock_info(plaintext)
+    }
+
+    // Split the plaintext of the lock info into the amount, the asset type, and the attributes.
+    fn parse_lock_info(&self, mut plaintext: Vec<u8>) -> Result<DecryptedAssetMemo> {
         let amount = if self.lock_amount.is_some() {
             if plaintext.len() < 2 * U32_BYTES {
                 return Err(NoahError::BogusAssetTracerMemo);
             }
             let amount_low = u8_be_slice_to_u32(&plaintext[0..U32_BYTES]);
             let amount_high = u8_be_slice_to_u32(&plaintext[U32_BYTES..2 * U32_BYTES]);
-            let amount = (amount_low as u64) + ((amount_high as u64) << 32);
-            self.verify_amount(&dec_key.record_data_dec_key, amount)?;
             plaintext = plaintext.split_off(2 * U32_BYTES);
-            Some(amount)
+            Some((amount_low as u64) + ((amount_high as u64) << 32))
         } else {
             None
         };
 
-        // decrypt and sanitize asset type
         let asset_type = if self.lock_asset_type.is_some() {
             if plaintext.len() < ASSET_TYPE_LENGTH {
                 return Err(NoahError::BogusAssetTracerMemo);
             }
             let mut asset_type = [0u8; ASSET_TYPE_LENGTH];
             asset_type.copy_from_slice(&plaintext[0..ASSET_TYPE_LENGTH]);
-            let asset_type = AssetType(asset_type);
-
-            self.verify_asset_type(&dec_key.record_data_dec_key, &asset_type)?;
             plaintext = plaintext.split_off(ASSET_TYPE_LENGTH);
-            Some(asset_type)
+            Some(AssetType(asset_type))
         } else {
             None
         };
@@ -120,15 +154,29 @@ impl TracerMemo {
         for attr_byte in plaintext.chunks(U32_BYTES) {
             attrs.push(u8_be_slice_to_u32(attr_byte));
         }
+        Ok((amount, asset_type, attrs))
+    }
 
+    // Check the decrypted values against the ciphertexts of the memo.
+    fn check_decrypted(
+        &self,
+        dec_key: &AssetTracerDecKeys,
+        (amount, asset_type, attrs): &DecryptedAssetMemo,
+    ) -> Result<()> {
+        if let Some(amount) = amount {
+            self.verify_amount(&dec_key.record_data_dec_key, *amount)?;
+        }
+        if let Some(asset_type) = asset_type {
+            self.verify_asset_type(&dec_key.record_data_dec_key, asset_type)?;
+        }
         if !self
-            .verify_identity_attributes(&dec_key.attrs_dec_key, &attrs)?
+            .verify_identity_attributes(&dec_key.attrs_dec_key, attrs)?
             .iter()
             .all(|&x| x)
         {
             return Err(NoahError::BogusAssetTracerMemo);
         }
-        Ok((amount, asset_type, attrs))
+        Ok(())
     }
 
     /// Check if the amount encrypted in self.lock_amount is expected.
@@ -225,7 +273,7 @@ mod tests {
     use crate::xfr::structs::{AssetTracerKeyPair, AssetType, TracerMemo};
     use noah_algebra::bn254::BN254Scalar;
     use noah_algebra::{prelude::*, ristretto::RistrettoScalar};
-    use noah_crypto::elgamal::elgamal_encrypt;
+    use noah_crypto::{elgamal::elgamal_encrypt, threshold_encryption::threshold_key_gen};
 
     #[test]
     fn extract_amount_from_tracer_memo() {
@@ -389,4 +437,63 @@ mod tests {
             vec![false, false, false]
         );
     }
+    #[test]
+    fn decrypt_tracer_memo_by_committee() {
+        let mut prng = test_rng();
+        let tracer_keys = AssetTracerKeyPair::generate(&mut prng);
+        let (committee, key_shares) = threshold_key_gen(&mut prng, 2, 3).unwrap();
+
+        let amount = (1u64 << 40) + 500;
+        let (low, high) = u64_to_u32_pair(amount);
+        let asset_type = AssetType::from_identical_byte(2u8);
+        let attrs_and_ctexts = [1u32, 2, 3]
+            .iter()
+            .map(|x| {
+                (
+                    *x,
+                    elgamal_encrypt(
+                        &BN254Scalar::from(*x),
+                        &BN254Scalar::from(1000u32),
+                        &tracer_keys.enc_key.attrs_enc_key,
+                    ),
+                )
+            })
+            .collect_vec();
+        let memo = TracerMemo::new(
+            &mut prng,
+            &tracer_keys.enc_key,
+            Some((
+                low,
+                high,
+                &RistrettoScalar::from(191919u32),
+                &RistrettoScalar::from(2222u32),
+            )),
+            Some((&asset_type, &RistrettoScalar::from(191919u32))),
+            &attrs_and_ctexts,
+        );
+
+        let committee_memo = memo
+            .lock_to_committee(&mut prng, &tracer_keys.dec_key, &committee)
+            .unwrap();
+        let shares = key_shares
+            .iter()
+            .map(|key_share| committee_memo.decrypt_share(&mut prng, key_share))
+            .collect_vec();
+        assert_eq!(
+            memo.decrypt_by_committee(&committee_memo, &committee, &shares[1..])
+                .unwrap(),
+            memo.decrypt(&tracer_keys.dec_key).unwrap()
+        );
+
+        // a single member cannot decrypt the memo
+        assert!(memo
+            .decrypt_by_committee(&committee_memo, &committee, &shares[..1])
+            .is_err());
+
+        // and other tracers cannot lock the memo to the committee
+        let other_keys = AssetTracerKeyPair::generate(&mut prng);
+        assert!(memo
+            .lock_to_committee(&mut prng, &other_keys.dec_key, &committee)
+            .is_err());
+    }
 }
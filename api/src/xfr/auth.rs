@@ -0,0 +1,515 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{PublicKey, Signature, SignatureList};
+use crate::parameters::bulletproofs::BulletproofParams;
+use crate::xfr::{
+    batch_verify_xfr_bodies, check_xfr_body_memos,
+    structs::{XfrBody, XfrNote},
+    xfr_body_auth_message, XfrNotePoliciesRef,
+};
+use ark_std::boxed::Box;
+use noah_algebra::{
+    collections::{BTreeMap, BTreeSet},
+    prelude::*,
+};
+use noah_crypto::bls_sig::{
+    bls_aggregate, bls_fast_aggregate_verify, bls_sign, bls_verify_possession, BlsPublicKey,
+    BlsSecretKey, BlsSignature,
+};
+
+/// The authorization proof for spending the inputs of a confidential transfer.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AuthProof {
+    /// One signature from the owner of each input.
+    Signatures(SignatureList),
+    /// Signatures from a threshold of the signers of a multisig account.
+    Multisig(MultisigProof),
+    /// A proof of an account policy (e.g., a SNARK), checked by the verifier registered for the scheme.
+    Policy {
+        /// The identifier of the policy scheme.
+        scheme: u32,
+        /// The serialized proof.
+        proof: Vec<u8>,
+    },
+    /// One BLS signature aggregated over the BLS keys registered for the distinct owners,
+    /// which is verified with two pairings however many the inputs are.
+    BlsAggregate(BlsSignature),
+}
+
+/// The signatures of a multisig account, each attached to the index of its signer.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MultisigProof {
+    /// The signatures with the indices of the signers.
+    pub signatures: Vec<(u32, Signature)>,
+}
+
+/// The policy of a multisig account.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MultisigPolicy {
+    /// The number of signatures required.
+    pub threshold: u32,
+    /// The signers.
+    pub signers: Vec<PublicKey>,
+}
+
+/// A verifier of the authorization proofs under a policy scheme.
+pub trait PolicyVerifier: Send + Sync {
+    /// Verify that the proof authorizes the owners to spend, over the message.
+    fn verify(&self, owners: &[&PublicKey], message: &[u8], proof: &[u8]) -> Result<()>;
+}
+
+/// The registry of the multisig accounts and the policy verifiers.
+#[derive(Default)]
+pub struct AuthRegistry {
+    multisig_accounts: BTreeMap<Vec<u8>, MultisigPolicy>,
+    policy_verifiers: BTreeMap<u32, Box<dyn PolicyVerifier>>,
+    bls_keys: BTreeMap<Vec<u8>, BlsPublicKey>,
+}
+
+/// A confidential transfer note whose spending is authorized by an authorization proof.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AuthXfrNote {
+    /// The confidential transfer body.
+    pub body: XfrBody,
+    /// The authorization proof.
+    pub auth: AuthProof,
+}
+
+impl From<XfrNote> for AuthXfrNote {
+    fn from(note: XfrNote) -> Self {
+        AuthXfrNote {
+            body: note.body,
+            auth: AuthProof::Signatures(note.multisig),
+        }
+    }
+}
+
+impl MultisigPolicy {
+    /// Verify the multisig proof over the message.
+    pub fn verify(&self, message: &[u8], proof: &MultisigProof) -> Result<()> {
+        if self.threshold == 0 || (proof.signatures.len() as u64) < self.threshold as u64 {
+            return Err(NoahError::SignatureError);
+        }
+
+        let mut used = vec![false; self.signers.len()];
+        for (index, signature) in proof.signatures.iter() {
+            let index = *index as usize;
+            if index >= self.signers.len() || used[index] {
+                return Err(NoahError::SignatureError);
+            }
+            used[index] = true;
+            self.signers[index].verify(message, signature)?;
+        }
+        Ok(())
+    }
+}
+
+impl AuthRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a multisig account, which is identified by its public key. The signers must be
+    /// distinct, and an account cannot be registered twice.
+    pub fn register_multisig_account(
+        &mut self,
+        account: &PublicKey,
+        policy: MultisigPolicy,
+    ) -> Result<()> {
+        if policy.threshold == 0 || policy.threshold as usize > policy.signers.len() {
+            return Err(NoahError::ParameterError);
+        }
+        let signers = policy
+            .signers
+            .iter()
+            .map(|signer| signer.noah_to_bytes())
+            .collect::<BTreeSet<_>>();
+        if signers.len() != policy.signers.len() {
+            return Err(NoahError::ParameterError);
+        }
+
+        let account = account.noah_to_bytes();
+        if self.multisig_accounts.contains_key(&account) {
+            return Err(NoahError::ParameterError);
+        }
+        self.multisig_accounts.insert(account, policy);
+        Ok(())
+    }
+
+    /// Register the verifier of a policy scheme.
+    pub fn register_policy_verifier(
+        &mut self,
+        scheme: u32,
+        verifier: Box<dyn PolicyVerifier>,
+    ) -> Result<()> {
+        if self.policy_verifiers.contains_key(&scheme) {
+            return Err(NoahError::ParameterError);
+        }
+        self.policy_verifiers.insert(scheme, verifier);
+        Ok(())
+    }
+
+    /// Register the BLS key of an account, which is identified by its public key,
+    /// with the proof of possession of the BLS key.
+    pub fn register_bls_key(
+        &mut self,
+        account: &PublicKey,
+        bls_key: BlsPublicKey,
+        proof_of_possession: &BlsSignature,
+    ) -> Result<()> {
+        bls_verify_possession(&bls_key, proof_of_possession)?;
+        self.bls_keys.insert(account.noah_to_bytes(), bls_key);
+        Ok(())
+    }
+
+    /// Verify that the authorization proof allows the owners to spend, over the message.
+    pub fn verify(&self, auth: &AuthProof, owners: &[&PublicKey], message: &[u8]) -> Result<()> {
+        match auth {
+            AuthProof::Signatures(signatures) => signatures.verify(owners, message),
+            AuthProof::Multisig(proof) => {
+                // All the inputs must belong to the same multisig account.
+                let account = owners.first().ok_or(NoahError::ParameterError)?;
+                if owners.iter().any(|owner| owner != account) {
+                    return Err(NoahError::SignatureError);
+                }
+                let policy = self
+                    .multisig_accounts
+                    .get(&account.noah_to_bytes())
+                    .ok_or(NoahError::SignatureError)?;
+                policy.verify(message, proof)
+            }
+            AuthProof::Policy { scheme, proof } => {
+                let verifier = self
+                    .policy_verifiers
+                    .get(scheme)
+                    .ok_or(NoahError::SignatureError)?;
+                verifier.verify(owners, message, proof)
+            }
+            AuthProof::BlsAggregate(signature) => {
+                let mut bls_keys = BTreeMap::new();
+                for owner in owners {
+                    let account = owner.noah_to_bytes();
+                    let bls_key = self
+                        .bls_keys
+                        .get(&account)
+                        .ok_or(NoahError::SignatureError)?;
+                    bls_keys.insert(account, *bls_key);
+                }
+                let bls_keys = bls_keys.into_values().collect_vec();
+                bls_fast_aggregate_verify(&bls_keys, message, signature)
+                    .map_err(|_| NoahError::SignatureError)
+            }
+        }
+    }
+}
+
+/// Compute the aggregated BLS signature over the body, with the BLS keys of the distinct owners.
+pub fn compute_bls_aggregate_auth(body: &XfrBody, bls_keys: &[&BlsSecretKey]) -> Result<AuthProof> {
+    let message = xfr_body_auth_message(body)?;
+    let signatures = bls_keys
+        .iter()
+        .map(|sk| bls_sign(sk, &message))
+        .collect_vec();
+    Ok(AuthProof::BlsAggregate(bls_aggregate(&signatures)?))
+}
+
+/// Verify a confidential transfer note with an authorization proof.
+pub fn verify_auth_xfr_note<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    params: &mut BulletproofParams,
+    registry: &AuthRegistry,
+    note: &AuthXfrNote,
+    policies: &XfrNotePoliciesRef<'_>,
+) -> Result<()> {
+    check_xfr_body_memos(&note.body)?;
+
+    let message = xfr_body_auth_message(&note.body)?;
+    let owners = note
+        .body
+        .inputs
+        .iter()
+        .map(|input| &input.public_key)
+        .collect_vec();
+    registry.verify(&note.auth, &owners, &message)?;
+
+    batch_verify_xfr_bodies(prng, params, &[&note.body], &[policies])
+}
+
+#[cfg(test)]
+mod test {
+    use crate::errors::{NoahError, Result};
+    use crate::keys::{KeyPair, PublicKey, SignatureList};
+    use crate::parameters::bulletproofs::BulletproofParams;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::xfr::{
+        asset_record::AssetRecordType,
+        auth::{
+            compute_bls_aggregate_auth, verify_auth_xfr_note, AuthProof, AuthRegistry, AuthXfrNote,
+            MultisigPolicy, MultisigProof, PolicyVerifier,
+        },
+        gen_xfr_body,
+        structs::{AssetRecord, AssetRecordTemplate, AssetType},
+        xfr_body_auth_message, XfrNotePolicies,
+    };
+    use ark_std::boxed::Box;
+    use noah_algebra::prelude::*;
+    use noah_crypto::bls_sig::{bls_key_gen, bls_prove_possession};
+
+    struct OwnerPolicyVerifier;
+
+    impl PolicyVerifier for OwnerPolicyVerifier {
+        fn verify(&self, owners: &[&PublicKey], message: &[u8], proof: &[u8]) -> Result<()> {
+            // A toy policy: the proof is the message itself, and there is only one owner.
+            if owners.len() == 1 && proof == message {
+                Ok(())
+            } else {
+                Err(NoahError::SignatureError)
+            }
+        }
+    }
+
+    #[test]
+    fn test_multisig_account() {
+        let mut prng = test_rng();
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+
+        let account = KeyPair::sample(&mut prng, SECP256K1);
+        let signers = (0..3)
+            .map(|i| KeyPair::sample(&mut prng, if i % 2 == 0 { ED25519 } else { SECP256K1 }))
+            .collect_vec();
+
+        let mut registry = AuthRegistry::new();
+        registry
+            .register_multisig_account(
+                &account.get_pk(),
+                MultisigPolicy {
+                    threshold: 2,
+                    signers: signers.iter().map(|kp| kp.get_pk()).collect(),
+                },
+            )
+            .unwrap();
+        registry
+            .register_policy_verifier(1, Box::new(OwnerPolicyVerifier))
+            .unwrap();
+
+        // an account cannot be registered again
+        assert!(registry
+            .register_multisig_account(
+                &account.get_pk(),
+                MultisigPolicy {
+                    threshold: 1,
+                    signers: vec![signers[0].get_pk()],
+                },
+            )
+            .is_err());
+
+        // one signer cannot count twice towards the threshold
+        let other_account = KeyPair::sample(&mut prng, ED25519);
+        assert!(registry
+            .register_multisig_account(
+                &other_account.get_pk(),
+                MultisigPolicy {
+                    threshold: 2,
+                    signers: vec![signers[0].get_pk(), signers[0].get_pk()],
+                },
+            )
+            .is_err());
+
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            account.get_pk(),
+        );
+        let input = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let receiver = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            receiver.get_pk(),
+        );
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+
+        let body = gen_xfr_body(&mut prng, &[input], &[output]).unwrap();
+        let message = xfr_body_auth_message(&body).unwrap();
+
+        let mut params = BulletproofParams::default();
+        let policies = XfrNotePolicies::empty_policies(1, 1);
+
+        // two out of three signers
+        let note = AuthXfrNote {
+            body: body.clone(),
+            auth: AuthProof::Multisig(MultisigProof {
+                signatures: vec![
+                    (0, signers[0].sign(&message).unwrap()),
+                    (2, signers[2].sign(&message).unwrap()),
+                ],
+            }),
+        };
+        assert!(
+            verify_auth_xfr_note(&mut prng, &mut params, &registry, &note, &policies.to_ref())
+                .is_ok()
+        );
+
+        // not enough signers
+        let note = AuthXfrNote {
+            body: body.clone(),
+            auth: AuthProof::Multisig(MultisigProof {
+                signatures: vec![(1, signers[1].sign(&message).unwrap())],
+            }),
+        };
+        assert!(
+            verify_auth_xfr_note(&mut prng, &mut params, &registry, &note, &policies.to_ref())
+                .is_err()
+        );
+
+        // the same signer twice
+        let note = AuthXfrNote {
+            body: body.clone(),
+            auth: AuthProof::Multisig(MultisigProof {
+                signatures: vec![
+                    (1, signers[1].sign(&message).unwrap()),
+                    (1, signers[1].sign(&message).unwrap()),
+                ],
+            }),
+        };
+        assert!(
+            verify_auth_xfr_note(&mut prng, &mut params, &registry, &note, &policies.to_ref())
+                .is_err()
+        );
+
+        // a registered policy scheme
+        let note = AuthXfrNote {
+            body: body.clone(),
+            auth: AuthProof::Policy {
+                scheme: 1,
+                proof: message.clone(),
+            },
+        };
+        assert!(
+            verify_auth_xfr_note(&mut prng, &mut params, &registry, &note, &policies.to_ref())
+                .is_ok()
+        );
+
+        // an unknown policy scheme
+        let note = AuthXfrNote {
+            body: body.clone(),
+            auth: AuthProof::Policy {
+                scheme: 2,
+                proof: message.clone(),
+            },
+        };
+        assert!(
+            verify_auth_xfr_note(&mut prng, &mut params, &registry, &note, &policies.to_ref())
+                .is_err()
+        );
+
+        // the owner's own signature still works
+        let note = AuthXfrNote {
+            body,
+            auth: AuthProof::Signatures(SignatureList::sign(&[&account], &message).unwrap()),
+        };
+        assert!(
+            verify_auth_xfr_note(&mut prng, &mut params, &registry, &note, &policies.to_ref())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_bls_aggregate_auth() {
+        let mut prng = test_rng();
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+
+        let owners = (0..3)
+            .map(|i| KeyPair::sample(&mut prng, if i % 2 == 0 { ED25519 } else { SECP256K1 }))
+            .collect_vec();
+        let bls_keys = (0..3).map(|_| bls_key_gen(&mut prng)).collect_vec();
+
+        let mut registry = AuthRegistry::new();
+        // a proof of possession of another key is rejected
+        assert!(registry
+            .register_bls_key(
+                &owners[1].get_pk(),
+                bls_keys[1].1,
+                &bls_prove_possession(&bls_keys[0].0)
+            )
+            .is_err());
+        for (owner, (sk, pk)) in owners.iter().zip(bls_keys.iter()) {
+            registry
+                .register_bls_key(&owner.get_pk(), *pk, &bls_prove_possession(sk))
+                .unwrap();
+        }
+
+        // the first owner spends two inputs
+        let input_owners = [&owners[0], &owners[0], &owners[1], &owners[2]];
+        let inputs = input_owners
+            .iter()
+            .map(|owner| {
+                let template = AssetRecordTemplate::with_no_asset_tracing(
+                    10,
+                    asset_type,
+                    record_type,
+                    owner.get_pk(),
+                );
+                AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap()
+            })
+            .collect_vec();
+        let receiver = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            40,
+            asset_type,
+            record_type,
+            receiver.get_pk(),
+        );
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let body = gen_xfr_body(&mut prng, &inputs, &[output]).unwrap();
+
+        let mut params = BulletproofParams::default();
+        let policies = XfrNotePolicies::empty_policies(4, 1);
+
+        let secret_keys = bls_keys.iter().map(|(sk, _)| sk).collect_vec();
+        let note = AuthXfrNote {
+            body: body.clone(),
+            auth: compute_bls_aggregate_auth(&body, &secret_keys).unwrap(),
+        };
+        assert!(
+            verify_auth_xfr_note(&mut prng, &mut params, &registry, &note, &policies.to_ref())
+                .is_ok()
+        );
+
+        // a missing signer
+        let note = AuthXfrNote {
+            body: body.clone(),
+            auth: compute_bls_aggregate_auth(&body, &secret_keys[1..]).unwrap(),
+        };
+        assert!(
+            verify_auth_xfr_note(&mut prng, &mut params, &registry, &note, &policies.to_ref())
+                .is_err()
+        );
+
+        // an owner without a registered BLS key
+        let mut unregistered = AuthRegistry::new();
+        unregistered
+            .register_bls_key(
+                &owners[0].get_pk(),
+                bls_keys[0].1,
+                &bls_prove_possession(&bls_keys[0].0),
+            )
+            .unwrap();
+        let note = AuthXfrNote {
+            body: body.clone(),
+            auth: compute_bls_aggregate_auth(&body, &secret_keys).unwrap(),
+        };
+        assert!(verify_auth_xfr_note(
+            &mut prng,
+            &mut params,
+            &unregistered,
+            &note,
+            &policies.to_ref()
+        )
+        .is_err());
+    }
+}
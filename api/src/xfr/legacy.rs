@@ -0,0 +1,229 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{batch_verify_signatures, KeyType, PublicKeyInner, SignatureList};
+use crate::parameters::bulletproofs::BulletproofParams;
+use crate::xfr::{
+    check_xfr_body_memos,
+    structs::{
+        BlindAssetRecord, OwnerMemo, OwnerMemoVersion, TracerMemo, XfrBody, XfrNote, XfrProofs,
+    },
+    verify_xfr_body, XfrNotePoliciesRef,
+};
+use noah_algebra::{prelude::*, ristretto::CompressedEdwardsY};
+use noah_crypto::hybrid_encryption::NoahHybridCiphertext;
+use serde::ser::Serialize;
+
+/// The owner memo of zei v0.1, which is always for an ed25519 public key.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LegacyOwnerMemo {
+    /// The random point used to compute the shared point.
+    pub blind_share: CompressedEdwardsY,
+    /// The hybrid ciphertext of the memo information.
+    pub lock: NoahHybridCiphertext,
+}
+
+impl From<LegacyOwnerMemo> for OwnerMemo {
+    fn from(memo: LegacyOwnerMemo) -> Self {
+        OwnerMemo {
+            key_type: KeyType::Ed25519,
+            blind_share_bytes: CompactByteArray(memo.blind_share.to_bytes().to_vec()),
+            lock_bytes: CompactByteArray(memo.lock.noah_to_bytes()),
+            version: OwnerMemoVersion::Legacy,
+        }
+    }
+}
+
+/// The confidential transfer body of zei v0.1.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LegacyXfrBody {
+    /// The list of input (blind) asset records.
+    pub inputs: Vec<BlindAssetRecord>,
+    /// The list of output (blind) asset records.
+    pub outputs: Vec<BlindAssetRecord>,
+    /// The list of proofs.
+    pub proofs: XfrProofs,
+    /// The memos for access tracers.
+    pub asset_tracing_memos: Vec<Vec<TracerMemo>>,
+    /// The memos for the recipients.
+    pub owners_memos: Vec<Option<LegacyOwnerMemo>>,
+}
+
+impl From<LegacyXfrBody> for XfrBody {
+    fn from(body: LegacyXfrBody) -> Self {
+        XfrBody {
+            inputs: body.inputs,
+            outputs: body.outputs,
+            proofs: body.proofs,
+            asset_tracing_memos: body.asset_tracing_memos,
+            owners_memos: body
+                .owners_memos
+                .into_iter()
+                .map(|memo| memo.map(OwnerMemo::from))
+                .collect(),
+        }
+    }
+}
+
+/// The confidential transfer note of zei v0.1.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LegacyXfrNote {
+    /// The confidential transfer body.
+    pub body: LegacyXfrBody,
+    /// The multisignature of the senders.
+    pub multisig: SignatureList,
+}
+
+impl From<LegacyXfrNote> for XfrNote {
+    /// Note: the multisignature is over the legacy body and does not verify
+    /// against the converted body, so the converted note is only for reading.
+    fn from(note: LegacyXfrNote) -> Self {
+        XfrNote {
+            body: note.body.into(),
+            multisig: note.multisig,
+        }
+    }
+}
+
+/// Return the message signed by the senders of a legacy transfer body.
+pub fn legacy_xfr_body_auth_message(body: &LegacyXfrBody) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    body.serialize(&mut rmp_serde::Serializer::new(&mut bytes))
+        .map_err(|_| NoahError::SerializationError)?;
+    Ok(bytes)
+}
+
+/// Verify a confidential transfer note of zei v0.1, whose signatures are over the legacy body
+/// and whose proofs are verified as those of the converted body.
+pub fn verify_legacy_xfr_note<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    params: &mut BulletproofParams,
+    xfr_note: &LegacyXfrNote,
+    policies: &XfrNotePoliciesRef<'_>,
+) -> Result<()> {
+    // zei v0.1 only had ed25519 keys.
+    if xfr_note
+        .body
+        .inputs
+        .iter()
+        .chain(xfr_note.body.outputs.iter())
+        .any(|record| !matches!(record.public_key.inner(), PublicKeyInner::Ed25519(_)))
+    {
+        return Err(NoahError::ParameterError);
+    }
+
+    let message = legacy_xfr_body_auth_message(&xfr_note.body)?;
+    let pubkeys = xfr_note
+        .body
+        .inputs
+        .iter()
+        .map(|input| &input.public_key)
+        .collect_vec();
+    let instances = xfr_note
+        .multisig
+        .pair_with(&pubkeys)?
+        .into_iter()
+        .map(|(pk, sig)| (pk, message.as_slice(), sig))
+        .collect_vec();
+    batch_verify_signatures(prng, &instances)?;
+
+    let body = XfrBody::from(xfr_note.body.clone());
+    check_xfr_body_memos(&body)?;
+    verify_xfr_body(prng, params, &body, policies)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::keys::{KeyPair, SignatureList};
+    use crate::parameters::{bulletproofs::BulletproofParams, AddressFormat::ED25519};
+    use crate::xfr::{
+        asset_record::{open_blind_asset_record, AssetRecordType},
+        gen_xfr_note,
+        legacy::{
+            legacy_xfr_body_auth_message, verify_legacy_xfr_note, LegacyOwnerMemo, LegacyXfrBody,
+            LegacyXfrNote,
+        },
+        structs::{AssetRecord, AssetRecordTemplate, AssetType, XfrNote},
+        verify_xfr_note, XfrNotePolicies,
+    };
+    use noah_algebra::{prelude::*, ristretto::CompressedEdwardsY};
+    use noah_crypto::hybrid_encryption::NoahHybridCiphertext;
+
+    #[test]
+    fn test_legacy_xfr_note() {
+        let mut prng = test_rng();
+        let mut params = BulletproofParams::default();
+        let sender = KeyPair::sample(&mut prng, ED25519);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::ConfidentialAmount_ConfidentialAssetType;
+
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let input = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            receiver.get_pk(),
+        );
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let note = gen_xfr_note(&mut prng, &[input], &[output], &[&sender]).unwrap();
+
+        // rebuild the note in the layout of zei v0.1
+        let owners_memos = note
+            .body
+            .owners_memos
+            .iter()
+            .map(|memo| {
+                memo.as_ref().map(|memo| LegacyOwnerMemo {
+                    blind_share: CompressedEdwardsY::from_slice(&memo.blind_share_bytes.0),
+                    lock: NoahHybridCiphertext::noah_from_bytes(&memo.lock_bytes.0).unwrap(),
+                })
+            })
+            .collect_vec();
+        let body = LegacyXfrBody {
+            inputs: note.body.inputs.clone(),
+            outputs: note.body.outputs.clone(),
+            proofs: note.body.proofs.clone(),
+            asset_tracing_memos: note.body.asset_tracing_memos.clone(),
+            owners_memos,
+        };
+        let message = legacy_xfr_body_auth_message(&body).unwrap();
+        let legacy_note = LegacyXfrNote {
+            body,
+            multisig: SignatureList::sign(&[&sender], &message).unwrap(),
+        };
+
+        // the legacy note survives the storage of the old ledgers
+        let bytes = rmp_serde::to_vec(&legacy_note).unwrap();
+        let legacy_note: LegacyXfrNote = rmp_serde::from_slice(&bytes).unwrap();
+
+        let policies = XfrNotePolicies::empty_policies(1, 1);
+        assert!(
+            verify_legacy_xfr_note(&mut prng, &mut params, &legacy_note, &policies.to_ref())
+                .is_ok()
+        );
+
+        // the converted note keeps the records and the memos, but not a valid multisignature
+        let converted = XfrNote::from(legacy_note.clone());
+        assert_eq!(converted.body, note.body);
+        let record = open_blind_asset_record(
+            &converted.body.outputs[0],
+            &converted.body.owners_memos[0],
+            &receiver,
+        )
+        .unwrap();
+        assert_eq!(record.amount, 10);
+        assert!(verify_xfr_note(&mut prng, &mut params, &converted, &policies.to_ref()).is_err());
+
+        // a tampered legacy note is rejected
+        let mut tampered = legacy_note;
+        tampered.body.owners_memos[0] = None;
+        assert!(
+            verify_legacy_xfr_note(&mut prng, &mut params, &tampered, &policies.to_ref()).is_err()
+        );
+    }
+}
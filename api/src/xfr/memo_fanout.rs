@@ -0,0 +1,169 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey};
+use crate::serialization::to_bincode;
+use crate::xfr::{structs::XfrBody, xfr_hybrid_decrypt, xfr_hybrid_encrypt};
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes256Gcm,
+};
+use digest::{generic_array::GenericArray, Digest, KeyInit};
+use noah_algebra::prelude::*;
+use sha2::Sha256;
+
+/// The domain separator of the binding of a memo to an output of a transfer.
+const MEMO_FANOUT_BINDING_DOMAIN: &[u8] = b"Noah Memo Fan-out Binding";
+
+/// The key of the payload encrypted to a recipient of the memo.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MemoRecipientKey {
+    /// The public key of the recipient, e.g., the receiver, an auditor, or a co-signer.
+    pub recipient: PublicKey,
+    /// The hybrid encryption of the payload key to the recipient.
+    pub key_ctext: CompactByteArray,
+}
+
+/// A memo whose payload is encrypted once under a fresh symmetric key, which is in turn
+/// encrypted to each of the recipients, so that disclosing to more parties costs a key
+/// ciphertext per party instead of a copy of the payload.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FanOutMemo {
+    /// The AES-256-GCM encryption of the payload, with the binding as the associated data.
+    pub payload: CompactByteArray,
+    /// The payload key encrypted to each recipient.
+    pub recipients: Vec<MemoRecipientKey>,
+}
+
+impl FanOutMemo {
+    /// Encrypt the payload, e.g., the lock plaintext of an `OwnerMemo`, to the recipients.
+    /// The memo can only be opened with the same binding, e.g., the one of `output_binding`.
+    pub fn new<R: CryptoRng + RngCore>(
+        prng: &mut R,
+        payload: &[u8],
+        recipients: &[PublicKey],
+        binding: &[u8],
+    ) -> Result<Self> {
+        if recipients.is_empty() {
+            return Err(NoahError::ParameterError);
+        }
+
+        // the key is used once, so the nonce can be fixed
+        let mut key = [0u8; 32];
+        prng.fill_bytes(&mut key);
+        let payload = Aes256Gcm::new_from_slice(&key)
+            .map_err(|_| NoahError::EncryptionError)?
+            .encrypt(
+                GenericArray::from_slice(&[0u8; 12]),
+                Payload {
+                    msg: payload,
+                    aad: binding,
+                },
+            )
+            .map_err(|_| NoahError::EncryptionError)?;
+
+        let recipients = recipients
+            .iter()
+            .map(|recipient| {
+                Ok(MemoRecipientKey {
+                    recipient: *recipient,
+                    key_ctext: CompactByteArray(xfr_hybrid_encrypt(recipient, prng, &key)?),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            payload: CompactByteArray(payload),
+            recipients,
+        })
+    }
+
+    /// Decrypt the payload with the key pair of one of the recipients.
+    pub fn decrypt(&self, keypair: &KeyPair, binding: &[u8]) -> Result<Vec<u8>> {
+        let pk = keypair.get_pk();
+        let entry = self
+            .recipients
+            .iter()
+            .find(|entry| entry.recipient == pk)
+            .ok_or(NoahError::DecryptionError)?;
+
+        let key = xfr_hybrid_decrypt(&keypair.get_sk(), &entry.key_ctext.0)?;
+        Aes256Gcm::new_from_slice(&key)
+            .map_err(|_| NoahError::DecryptionError)?
+            .decrypt(
+                GenericArray::from_slice(&[0u8; 12]),
+                Payload {
+                    msg: &self.payload.0,
+                    aad: binding,
+                },
+            )
+            .map_err(|_| NoahError::DecryptionError)
+    }
+
+    /// Return the public keys of the recipients.
+    pub fn recipients(&self) -> Vec<PublicKey> {
+        self.recipients
+            .iter()
+            .map(|entry| entry.recipient)
+            .collect()
+    }
+}
+
+/// Compute the binding of a memo to an output of the transfer body, which covers the output
+/// record and its owner memo, so that the memo cannot be moved to another note or output.
+pub fn output_binding(body: &XfrBody, output_index: usize) -> Result<[u8; 32]> {
+    let output = body
+        .outputs
+        .get(output_index)
+        .ok_or(NoahError::ParameterError)?;
+    let owner_memo = body
+        .owners_memos
+        .get(output_index)
+        .ok_or(NoahError::ParameterError)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(MEMO_FANOUT_BINDING_DOMAIN);
+    hasher.update((output_index as u64).to_le_bytes());
+    hasher.update(to_bincode(&(output, owner_memo))?);
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::xfr::memo_fanout::FanOutMemo;
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_fanout_memo() {
+        let mut prng = test_rng();
+        let receiver = KeyPair::sample(&mut prng, SECP256K1);
+        let auditor = KeyPair::sample(&mut prng, ED25519);
+        let cosigner = KeyPair::sample(&mut prng, SECP256K1);
+        let outsider = KeyPair::sample(&mut prng, ED25519);
+
+        let payload = b"amount and asset type";
+        let binding = [7u8; 32];
+        let memo = FanOutMemo::new(
+            &mut prng,
+            payload,
+            &[receiver.get_pk(), auditor.get_pk(), cosigner.get_pk()],
+            &binding,
+        )
+        .unwrap();
+        assert_eq!(memo.recipients().len(), 3);
+
+        for keypair in [&receiver, &auditor, &cosigner] {
+            assert_eq!(memo.decrypt(keypair, &binding).unwrap(), payload);
+        }
+        assert!(memo.decrypt(&outsider, &binding).is_err());
+
+        // the memo does not open under another binding
+        assert!(memo.decrypt(&receiver, &[8u8; 32]).is_err());
+
+        let mut tampered = memo.clone();
+        tampered.payload.0[0] ^= 1;
+        assert!(tampered.decrypt(&auditor, &binding).is_err());
+
+        assert!(FanOutMemo::new(&mut prng, payload, &[], &binding).is_err());
+    }
+}
@@ -4,7 +4,7 @@ use ark_std::boxed::Box;
 use digest::{generic_array::GenericArray, Digest, KeyInit};
 use noah_algebra::secp256k1::{SECP256K1Scalar, SECP256K1G1};
 use noah_algebra::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     prelude::*,
     ristretto::{CompressedRistretto, PedersenCommitmentRistretto, RistrettoScalar},
     traits::PedersenCommitment,
@@ -20,8 +20,22 @@ pub mod asset_mixer;
 pub mod asset_record;
 /// Module for asset tracing.
 pub mod asset_tracer;
+/// Module for authorization proofs of spending.
+pub mod auth;
+/// Module for the legacy note formats of zei v0.1.
+pub mod legacy;
+/// Module for memos encrypted to multiple recipients.
+pub mod memo_fanout;
+/// Module for MuSig2 multisignatures of the groups that own BARs.
+pub mod multisig;
+/// Module for building transfer notes collaboratively among several parties.
+pub mod note_builder;
 /// Module for zero-knowledge proofs.
 pub mod proofs;
+/// Module for the signature schemes of transfer notes.
+pub mod sig;
+/// Module for one-time stealth addresses.
+pub mod stealth;
 /// Module for shared structures.
 pub mod structs;
 
@@ -30,7 +44,9 @@ pub mod structs;
 pub(crate) mod tests;
 
 use crate::anon_creds::{ACCommitment, Attr};
-use crate::keys::{KeyPair, PublicKey, PublicKeyInner, SecretKey, SignatureList};
+use crate::keys::{
+    batch_verify_signatures, KeyPair, PublicKey, PublicKeyInner, SecretKey, SignatureList,
+};
 use crate::parameters::bulletproofs::BulletproofParams;
 
 use self::{
@@ -477,31 +493,36 @@ fn check_asset_amount(inputs: &[AssetRecord], outputs: &[AssetRecord]) -> Result
     Ok(())
 }
 
+/// Compute the message that authorizes spending the inputs of the body.
+pub fn xfr_body_auth_message(body: &XfrBody) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    body.serialize(&mut rmp_serde::Serializer::new(&mut bytes))
+        .map_err(|_| NoahError::SerializationError)?;
+    Ok(bytes)
+}
+
 /// Compute a multisignature over the body.
 pub(crate) fn compute_transfer_multisig(
     body: &XfrBody,
     keys: &[&KeyPair],
 ) -> Result<SignatureList> {
-    let mut bytes = vec![];
-    body.serialize(&mut rmp_serde::Serializer::new(&mut bytes))
-        .map_err(|_| NoahError::SerializationError)?;
+    let bytes = xfr_body_auth_message(body)?;
     SignatureList::sign(keys, &bytes)
 }
 
-/// Verify the multisignature over the body.
-pub(crate) fn verify_transfer_multisig(xfr_note: &XfrNote) -> Result<()> {
-    let mut bytes = vec![];
-    xfr_note
-        .body
-        .serialize(&mut rmp_serde::Serializer::new(&mut bytes))
-        .map_err(|_| NoahError::SerializationError)?;
-    let pubkeys = xfr_note
-        .body
-        .inputs
-        .iter()
-        .map(|input| &input.public_key)
-        .collect_vec();
-    xfr_note.multisig.verify(&pubkeys, &bytes)
+/// Check the number and the sizes of the memos in the body.
+pub(crate) fn check_xfr_body_memos(body: &XfrBody) -> Result<()> {
+    if body.outputs.len() != body.owners_memos.len() {
+        return Err(NoahError::AXfrVerifierParamsError);
+    }
+    #[cfg(not(feature = "xfr-tracing"))]
+    if body.asset_tracing_memos.iter().any(|x| !x.is_empty()) {
+        return Err(NoahError::AXfrVerificationError);
+    }
+    for (output, memo) in body.outputs.iter().zip(body.owners_memos.iter()) {
+        check_memo_size(output, memo)?
+    }
+    Ok(())
 }
 
 /// Verify a confidential transfer note.
@@ -521,38 +542,50 @@ pub fn batch_verify_xfr_notes<R: CryptoRng + RngCore>(
     params: &mut BulletproofParams,
     notes: &[&XfrNote],
     policies: &[&XfrNotePoliciesRef<'_>],
+) -> Result<()> {
+    batch_verify_xfr_notes_with(prng, params, notes, policies, false)
+}
+
+/// Batch-verify confidential transfer notes. With `full_evaluation`, the bodies are verified
+/// even if the signatures are invalid, and the first error is returned, so that the time taken
+/// does not tell which of the checks failed.
+pub(crate) fn batch_verify_xfr_notes_with<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    params: &mut BulletproofParams,
+    notes: &[&XfrNote],
+    policies: &[&XfrNotePoliciesRef<'_>],
+    full_evaluation: bool,
 ) -> Result<()> {
     // Check the memo size.
     for xfr_note in notes {
-        if xfr_note.body.outputs.len() != xfr_note.body.owners_memos.len() {
-            return Err(NoahError::AXfrVerifierParamsError);
-        }
-        #[cfg(not(feature = "xfr-tracing"))]
-        if xfr_note
-            .body
-            .asset_tracing_memos
-            .iter()
-            .any(|x| !x.is_empty())
-        {
-            return Err(NoahError::AXfrVerificationError);
-        }
-        for (output, memo) in xfr_note
+        check_xfr_body_memos(&xfr_note.body)?;
+    }
+
+    // Batch-verify the multisignatures of all the notes.
+    let messages = notes
+        .iter()
+        .map(|xfr_note| xfr_body_auth_message(&xfr_note.body))
+        .collect::<Result<Vec<_>>>()?;
+    let mut signatures = vec![];
+    for (xfr_note, message) in notes.iter().zip(messages.iter()) {
+        let pubkeys = xfr_note
             .body
-            .outputs
+            .inputs
             .iter()
-            .zip(xfr_note.body.owners_memos.iter())
-        {
-            check_memo_size(output, memo)?
+            .map(|input| &input.public_key)
+            .collect_vec();
+        for (pk, sig) in xfr_note.multisig.pair_with(&pubkeys)? {
+            signatures.push((pk, message.as_slice(), sig));
         }
     }
-
-    // Verify each note's multisignature, one by one.
-    for xfr_note in notes {
-        verify_transfer_multisig(xfr_note)?;
+    let signatures_res = batch_verify_signatures(prng, &signatures);
+    if !full_evaluation && signatures_res.is_err() {
+        return signatures_res;
     }
 
     let bodies = notes.iter().map(|note| &note.body).collect_vec();
-    batch_verify_xfr_bodies(prng, params, &bodies, policies)
+    let bodies_res = batch_verify_xfr_bodies(prng, params, &bodies, policies);
+    signatures_res.and(bodies_res)
 }
 
 /// Verify xfr body
@@ -783,7 +816,8 @@ fn verify_plain_asset(inputs: &[BlindAssetRecord], outputs: &[BlindAssetRecord])
 }
 
 fn verify_plain_asset_mix(inputs: &[BlindAssetRecord], outputs: &[BlindAssetRecord]) -> Result<()> {
-    let mut amounts = HashMap::new();
+    // A sorted map, so that the verification does not depend on the iteration order.
+    let mut amounts = BTreeMap::new();
 
     for record in inputs.iter() {
         match amounts.get_mut(
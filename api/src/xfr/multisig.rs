@@ -0,0 +1,403 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey, PublicKeyInner, SecretKey, Signature};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+    traits::Identity,
+};
+use ed25519_dalek::{
+    ExpandedSecretKey, PublicKey as Ed25519PublicKey, Signature as Ed25519Signature,
+};
+use noah_algebra::prelude::*;
+use sha2::{Digest, Sha512};
+
+/// The domain separator of the coefficients of the key aggregation.
+const MUSIG2_KEY_AGG_DOMAIN: &[u8] = b"Noah MuSig2 Key Aggregation";
+/// The domain separator of the coefficient of the second nonces.
+const MUSIG2_NONCE_DOMAIN: &[u8] = b"Noah MuSig2 Nonce Coefficient";
+/// The domain separator of the secret nonces.
+const MUSIG2_NONCE_GEN_DOMAIN: &[u8] = b"Noah MuSig2 Nonce Generation";
+
+/// The aggregation of the ed25519 keys of an n-of-n group, whose aggregated key owns
+/// the BARs and verifies the aggregated signatures as an ordinary ed25519 key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MuSig2KeyAgg {
+    /// The keys of the signers, sorted by their encodings.
+    keys: Vec<[u8; 32]>,
+    /// The coefficients of the keys.
+    coefficients: Vec<Scalar>,
+    /// The aggregated key.
+    agg_key: EdwardsPoint,
+}
+
+/// The secret nonces of a signer for one signing session, which must never be reused.
+pub struct MuSig2SecretNonce {
+    r1: Scalar,
+    r2: Scalar,
+    pub_key: [u8; 32],
+}
+
+/// The public nonces of a signer, sent to the other signers in the first round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuSig2PublicNonce {
+    /// The first nonce.
+    pub r1: [u8; 32],
+    /// The second nonce.
+    pub r2: [u8; 32],
+}
+
+/// The sum of the public nonces of all the signers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MuSig2AggNonce {
+    r1: EdwardsPoint,
+    r2: EdwardsPoint,
+}
+
+/// The partial signature of a signer, sent to the aggregator in the second round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuSig2PartialSignature(pub [u8; 32]);
+
+impl MuSig2KeyAgg {
+    /// Aggregate the ed25519 keys of the signers, in any order.
+    pub fn new(pub_keys: &[PublicKey]) -> Result<Self> {
+        if pub_keys.is_empty() {
+            return Err(NoahError::ParameterError);
+        }
+        let mut keys = pub_keys
+            .iter()
+            .map(|pk| match pk.inner() {
+                PublicKeyInner::Ed25519(pk) => Ok(pk.to_bytes()),
+                _ => Err(NoahError::ParameterError),
+            })
+            .collect::<Result<Vec<[u8; 32]>>>()?;
+        keys.sort_unstable();
+        if keys.windows(2).any(|w| w[0] == w[1]) {
+            return Err(NoahError::ParameterError);
+        }
+
+        let mut hasher = Sha512::new();
+        hasher.update(MUSIG2_KEY_AGG_DOMAIN);
+        for key in keys.iter() {
+            hasher.update(key);
+        }
+        let keys_hash = hasher.finalize();
+
+        let mut coefficients = Vec::with_capacity(keys.len());
+        let mut agg_key = EdwardsPoint::identity();
+        for key in keys.iter() {
+            let point = decompress(key)?;
+            let coefficient = hash_to_scalar(&[MUSIG2_KEY_AGG_DOMAIN, &keys_hash, key]);
+            agg_key += coefficient * point;
+            coefficients.push(coefficient);
+        }
+        if agg_key.is_small_order() {
+            return Err(NoahError::ParameterError);
+        }
+
+        Ok(Self {
+            keys,
+            coefficients,
+            agg_key,
+        })
+    }
+
+    /// Return the aggregated key, which owns the BARs of the group.
+    pub fn public_key(&self) -> Result<PublicKey> {
+        let pk = Ed25519PublicKey::from_bytes(self.agg_key.compress().as_bytes())
+            .map_err(|_| NoahError::DecompressElementError)?;
+        Ok(PublicKey(PublicKeyInner::Ed25519(pk)))
+    }
+
+    fn coefficient(&self, key: &[u8; 32]) -> Result<Scalar> {
+        let index = self
+            .keys
+            .binary_search(key)
+            .map_err(|_| NoahError::ParameterError)?;
+        Ok(self.coefficients[index])
+    }
+}
+
+/// Generate the nonces of the signer for a signing session.
+pub fn musig2_nonce_gen<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    keypair: &KeyPair,
+) -> Result<(MuSig2SecretNonce, MuSig2PublicNonce)> {
+    let pub_key = ed25519_pub_key(keypair.get_pk_ref())?;
+    let mut seed = [0u8; 32];
+    prng.fill_bytes(&mut seed);
+
+    let r1 = hash_to_scalar(&[MUSIG2_NONCE_GEN_DOMAIN, &seed, &pub_key, &[1u8]]);
+    let r2 = hash_to_scalar(&[MUSIG2_NONCE_GEN_DOMAIN, &seed, &pub_key, &[2u8]]);
+    let public_nonce = MuSig2PublicNonce {
+        r1: (r1 * ED25519_BASEPOINT_POINT).compress().to_bytes(),
+        r2: (r2 * ED25519_BASEPOINT_POINT).compress().to_bytes(),
+    };
+    Ok((MuSig2SecretNonce { r1, r2, pub_key }, public_nonce))
+}
+
+/// Sum the public nonces of all the signers.
+pub fn musig2_nonce_agg(nonces: &[MuSig2PublicNonce]) -> Result<MuSig2AggNonce> {
+    if nonces.is_empty() {
+        return Err(NoahError::ParameterError);
+    }
+    let mut r1 = EdwardsPoint::identity();
+    let mut r2 = EdwardsPoint::identity();
+    for nonce in nonces.iter() {
+        r1 += decompress(&nonce.r1)?;
+        r2 += decompress(&nonce.r2)?;
+    }
+    Ok(MuSig2AggNonce { r1, r2 })
+}
+
+/// Compute the partial signature of the signer over the message, consuming the secret nonces.
+pub fn musig2_partial_sign(
+    key_agg: &MuSig2KeyAgg,
+    agg_nonce: &MuSig2AggNonce,
+    secret_nonce: MuSig2SecretNonce,
+    keypair: &KeyPair,
+    message: &[u8],
+) -> Result<MuSig2PartialSignature> {
+    let pub_key = ed25519_pub_key(keypair.get_pk_ref())?;
+    if pub_key != secret_nonce.pub_key {
+        return Err(NoahError::ParameterError);
+    }
+    let sk = match keypair.get_sk_ref() {
+        SecretKey::Ed25519(sk) => {
+            let mut bytes = [0u8; 32];
+            bytes.copy_from_slice(&ExpandedSecretKey::from(sk).to_bytes()[..32]);
+            Scalar::from_bytes_mod_order(bytes)
+        }
+        _ => return Err(NoahError::ParameterError),
+    };
+
+    let a = key_agg.coefficient(&pub_key)?;
+    let (b, r) = session_nonce(key_agg, agg_nonce, message);
+    let c = challenge(&r, &key_agg.agg_key, message);
+
+    let s = secret_nonce.r1 + b * secret_nonce.r2 + c * a * sk;
+    Ok(MuSig2PartialSignature(s.to_bytes()))
+}
+
+/// Verify the partial signature of a signer, so that the aggregator can blame a faulty signer.
+pub fn musig2_partial_verify(
+    key_agg: &MuSig2KeyAgg,
+    agg_nonce: &MuSig2AggNonce,
+    public_nonce: &MuSig2PublicNonce,
+    pub_key: &PublicKey,
+    message: &[u8],
+    partial_signature: &MuSig2PartialSignature,
+) -> Result<()> {
+    let pub_key = ed25519_pub_key(pub_key)?;
+    let a = key_agg.coefficient(&pub_key)?;
+    let (b, r) = session_nonce(key_agg, agg_nonce, message);
+    let c = challenge(&r, &key_agg.agg_key, message);
+
+    let s = canonical_scalar(&partial_signature.0)?;
+    let expected = decompress(&public_nonce.r1)?
+        + b * decompress(&public_nonce.r2)?
+        + (c * a) * decompress(&pub_key)?;
+    if s * ED25519_BASEPOINT_POINT == expected {
+        Ok(())
+    } else {
+        Err(NoahError::SignatureError)
+    }
+}
+
+/// Aggregate the partial signatures into an ed25519 signature under the aggregated key.
+pub fn musig2_partial_sig_agg(
+    key_agg: &MuSig2KeyAgg,
+    agg_nonce: &MuSig2AggNonce,
+    message: &[u8],
+    partial_signatures: &[MuSig2PartialSignature],
+) -> Result<Signature> {
+    if partial_signatures.len() != key_agg.keys.len() {
+        return Err(NoahError::ParameterError);
+    }
+    let (_, r) = session_nonce(key_agg, agg_nonce, message);
+    let mut s = Scalar::zero();
+    for partial_signature in partial_signatures.iter() {
+        s += canonical_scalar(&partial_signature.0)?;
+    }
+
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(r.compress().as_bytes());
+    bytes[32..].copy_from_slice(s.as_bytes());
+    let signature = Ed25519Signature::from_bytes(&bytes).map_err(|_| NoahError::SignatureError)?;
+    Ok(Signature::Ed25519(signature))
+}
+
+/// Return the coefficient of the second nonces and the nonce of the signature.
+fn session_nonce(
+    key_agg: &MuSig2KeyAgg,
+    agg_nonce: &MuSig2AggNonce,
+    message: &[u8],
+) -> (Scalar, EdwardsPoint) {
+    let b = hash_to_scalar(&[
+        MUSIG2_NONCE_DOMAIN,
+        key_agg.agg_key.compress().as_bytes(),
+        agg_nonce.r1.compress().as_bytes(),
+        agg_nonce.r2.compress().as_bytes(),
+        message,
+    ]);
+    (b, agg_nonce.r1 + b * agg_nonce.r2)
+}
+
+/// Compute the challenge of ed25519, as in RFC 8032.
+fn challenge(r: &EdwardsPoint, agg_key: &EdwardsPoint, message: &[u8]) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(r.compress().as_bytes());
+    hasher.update(agg_key.compress().as_bytes());
+    hasher.update(message);
+    let mut bytes = [0u8; 64];
+    bytes.copy_from_slice(&hasher.finalize());
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+fn hash_to_scalar(inputs: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    for input in inputs.iter() {
+        hasher.update((input.len() as u64).to_le_bytes());
+        hasher.update(input);
+    }
+    let mut bytes = [0u8; 64];
+    bytes.copy_from_slice(&hasher.finalize());
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+fn canonical_scalar(bytes: &[u8; 32]) -> Result<Scalar> {
+    Scalar::from_canonical_bytes(*bytes).ok_or(NoahError::SignatureError)
+}
+
+fn decompress(bytes: &[u8; 32]) -> Result<EdwardsPoint> {
+    CompressedEdwardsY(*bytes)
+        .decompress()
+        .ok_or(NoahError::DecompressElementError)
+}
+
+fn ed25519_pub_key(pub_key: &PublicKey) -> Result<[u8; 32]> {
+    match pub_key.inner() {
+        PublicKeyInner::Ed25519(pk) => Ok(pk.to_bytes()),
+        _ => Err(NoahError::ParameterError),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::keys::{KeyPair, SignatureList};
+    use crate::parameters::bulletproofs::BulletproofParams;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::xfr::{
+        asset_record::AssetRecordType,
+        gen_xfr_body,
+        multisig::{
+            musig2_nonce_agg, musig2_nonce_gen, musig2_partial_sig_agg, musig2_partial_sign,
+            musig2_partial_verify, MuSig2KeyAgg,
+        },
+        structs::{AssetRecord, AssetRecordTemplate, AssetType, XfrNote},
+        verify_xfr_note, xfr_body_auth_message, XfrNotePolicies,
+    };
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_musig2() {
+        let mut prng = test_rng();
+        let signers = (0..3)
+            .map(|_| KeyPair::sample(&mut prng, ED25519))
+            .collect_vec();
+        let pub_keys = signers.iter().map(|kp| kp.get_pk()).collect_vec();
+        let key_agg = MuSig2KeyAgg::new(&pub_keys).unwrap();
+        let group_key = key_agg.public_key().unwrap();
+
+        // the order of the keys does not matter
+        let mut reversed = pub_keys.clone();
+        reversed.reverse();
+        assert_eq!(MuSig2KeyAgg::new(&reversed).unwrap(), key_agg);
+        assert!(MuSig2KeyAgg::new(&[pub_keys[0], pub_keys[0]]).is_err());
+        let secp256k1 = KeyPair::sample(&mut prng, SECP256K1);
+        assert!(MuSig2KeyAgg::new(&[pub_keys[0], secp256k1.get_pk()]).is_err());
+
+        // a BAR owned by the group
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::ConfidentialAmount_ConfidentialAssetType;
+        let template =
+            AssetRecordTemplate::with_no_asset_tracing(10, asset_type, record_type, group_key);
+        let input = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            receiver.get_pk(),
+        );
+        let output = AssetRecord::from_template_no_identity_tracing(&mut prng, &template).unwrap();
+        let body = gen_xfr_body(&mut prng, &[input], &[output]).unwrap();
+        let message = xfr_body_auth_message(&body).unwrap();
+
+        // the first round
+        let (secret_nonces, public_nonces): (Vec<_>, Vec<_>) = signers
+            .iter()
+            .map(|kp| musig2_nonce_gen(&mut prng, kp).unwrap())
+            .unzip();
+        let agg_nonce = musig2_nonce_agg(&public_nonces).unwrap();
+
+        // the second round
+        let partial_signatures = secret_nonces
+            .into_iter()
+            .zip(signers.iter())
+            .map(|(nonce, kp)| {
+                musig2_partial_sign(&key_agg, &agg_nonce, nonce, kp, &message).unwrap()
+            })
+            .collect_vec();
+        for ((kp, nonce), partial_signature) in signers
+            .iter()
+            .zip(public_nonces.iter())
+            .zip(partial_signatures.iter())
+        {
+            assert!(musig2_partial_verify(
+                &key_agg,
+                &agg_nonce,
+                nonce,
+                kp.get_pk_ref(),
+                &message,
+                partial_signature
+            )
+            .is_ok());
+        }
+        assert!(musig2_partial_verify(
+            &key_agg,
+            &agg_nonce,
+            &public_nonces[0],
+            signers[1].get_pk_ref(),
+            &message,
+            &partial_signatures[0]
+        )
+        .is_err());
+
+        let signature =
+            musig2_partial_sig_agg(&key_agg, &agg_nonce, &message, &partial_signatures).unwrap();
+        assert!(group_key.verify(&message, &signature).is_ok());
+        assert!(
+            musig2_partial_sig_agg(&key_agg, &agg_nonce, &message, &partial_signatures[1..])
+                .is_err()
+        );
+
+        // the note looks like one spent by a single owner
+        let note = XfrNote {
+            body,
+            multisig: SignatureList {
+                signatures: vec![signature],
+            },
+        };
+        let mut params = BulletproofParams::default();
+        let policies = XfrNotePolicies::empty_policies(1, 1);
+        assert!(verify_xfr_note(&mut prng, &mut params, &note, &policies.to_ref()).is_ok());
+
+        // a missing partial signature does not verify
+        let mut partial_signatures = partial_signatures;
+        partial_signatures[2] = partial_signatures[1];
+        let signature =
+            musig2_partial_sig_agg(&key_agg, &agg_nonce, &message, &partial_signatures).unwrap();
+        assert!(group_key.verify(&message, &signature).is_err());
+    }
+}
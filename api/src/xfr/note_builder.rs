@@ -0,0 +1,247 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey, Signature, SignatureList};
+use crate::xfr::{
+    gen_xfr_body,
+    structs::{AssetRecord, XfrBody, XfrNote},
+    xfr_body_auth_message,
+};
+use noah_algebra::prelude::*;
+
+/// A partially built transparent transfer, in the spirit of a PSBT, which several parties
+/// complete offline by passing its serialization around.
+///
+/// The parties first add their inputs and outputs. One of them then builds the body, which
+/// freezes the inputs and outputs, and each party signs the body for the inputs that it owns.
+/// Partial states with the same body can be combined, and the note is assembled once every
+/// input is signed.
+///
+/// The inputs and the outputs carry their openings, which the proofs of the body need, so the
+/// intermediate state must only be shared among the parties of the transfer.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct XfrNoteBuilder {
+    /// The inputs, with their openings.
+    inputs: Vec<AssetRecord>,
+    /// The outputs, with their openings.
+    outputs: Vec<AssetRecord>,
+    /// The body, once built.
+    body: Option<XfrBody>,
+    /// The signature of the owner of each input, once signed.
+    signatures: Vec<Option<Signature>>,
+}
+
+impl XfrNoteBuilder {
+    /// Start an empty transfer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an input, which a party spends.
+    pub fn add_input(&mut self, input: AssetRecord) -> Result<&mut Self> {
+        if self.body.is_some() {
+            return Err(NoahError::ParameterError);
+        }
+        self.inputs.push(input);
+        Ok(self)
+    }
+
+    /// Add an output.
+    pub fn add_output(&mut self, output: AssetRecord) -> Result<&mut Self> {
+        if self.body.is_some() {
+            return Err(NoahError::ParameterError);
+        }
+        self.outputs.push(output);
+        Ok(self)
+    }
+
+    /// Return the inputs.
+    pub fn inputs(&self) -> &[AssetRecord] {
+        &self.inputs
+    }
+
+    /// Return the outputs.
+    pub fn outputs(&self) -> &[AssetRecord] {
+        &self.outputs
+    }
+
+    /// Return the body, if built.
+    pub fn body(&self) -> Option<&XfrBody> {
+        self.body.as_ref()
+    }
+
+    /// Build the body from the inputs and the outputs, after which they can no longer change.
+    pub fn build_body<R: CryptoRng + RngCore>(&mut self, prng: &mut R) -> Result<&XfrBody> {
+        if self.body.is_some() {
+            return Err(NoahError::ParameterError);
+        }
+        let body = gen_xfr_body(prng, &self.inputs, &self.outputs)?;
+        self.signatures = vec![None; self.inputs.len()];
+        Ok(self.body.insert(body))
+    }
+
+    /// Sign the body for every input owned by the key pair, returning the number of inputs
+    /// signed. The body is checked against the inputs and outputs, so a party signs only
+    /// what it has seen.
+    pub fn sign(&mut self, keypair: &KeyPair) -> Result<usize> {
+        let body = self.body.as_ref().ok_or(NoahError::ParameterError)?;
+        self.check_body(body)?;
+
+        let message = xfr_body_auth_message(body)?;
+        let mut signature = None;
+        let mut count = 0;
+        for (input, slot) in self.inputs.iter().zip(self.signatures.iter_mut()) {
+            if input.open_asset_record.blind_asset_record.public_key != keypair.pub_key {
+                continue;
+            }
+            if signature.is_none() {
+                signature = Some(keypair.sign(&message)?);
+            }
+            *slot = signature.clone();
+            count += 1;
+        }
+
+        if count == 0 {
+            return Err(NoahError::ParameterError);
+        }
+        Ok(count)
+    }
+
+    /// Combine the signatures of another partial state of the same transfer.
+    pub fn combine(&mut self, other: &XfrNoteBuilder) -> Result<()> {
+        if self.body.is_none() || self.body != other.body {
+            return Err(NoahError::ParameterError);
+        }
+        for (slot, other_slot) in self.signatures.iter_mut().zip(other.signatures.iter()) {
+            if slot.is_none() {
+                *slot = other_slot.clone();
+            }
+        }
+        Ok(())
+    }
+
+    /// Return whether every input is signed.
+    pub fn is_fully_signed(&self) -> bool {
+        self.body.is_some() && self.signatures.iter().all(|s| s.is_some())
+    }
+
+    /// Assemble the note, after checking every signature.
+    pub fn finalize(self) -> Result<XfrNote> {
+        let body = self.body.ok_or(NoahError::ParameterError)?;
+        let message = xfr_body_auth_message(&body)?;
+
+        let mut signed = self
+            .inputs
+            .iter()
+            .map(|input| &input.open_asset_record.blind_asset_record.public_key)
+            .zip(self.signatures)
+            .map(|(pk, signature)| {
+                let signature = signature.ok_or(NoahError::SignatureError)?;
+                pk.verify(&message, &signature)?;
+                Ok((pk, signature))
+            })
+            .collect::<Result<Vec<(&PublicKey, Signature)>>>()?;
+
+        // in the order of `SignatureList`
+        signed.sort_by_key(|(pk, _)| pk.noah_to_bytes());
+        let multisig = SignatureList {
+            signatures: signed.into_iter().map(|(_, signature)| signature).collect(),
+        };
+        Ok(XfrNote { body, multisig })
+    }
+
+    /// Check that the body transfers the inputs to the outputs.
+    fn check_body(&self, body: &XfrBody) -> Result<()> {
+        let blind_records = |records: &[AssetRecord]| {
+            records
+                .iter()
+                .map(|r| &r.open_asset_record.blind_asset_record)
+                .collect_vec()
+        };
+        if body.inputs.iter().collect_vec() != blind_records(&self.inputs)
+            || body.outputs.iter().collect_vec() != blind_records(&self.outputs)
+        {
+            return Err(NoahError::ParameterError);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::keys::KeyPair;
+    use crate::parameters::{
+        bulletproofs::BulletproofParams,
+        AddressFormat::{ED25519, SECP256K1},
+    };
+    use crate::xfr::{
+        asset_record::AssetRecordType,
+        note_builder::XfrNoteBuilder,
+        structs::{AssetRecord, AssetRecordTemplate, AssetType},
+        verify_xfr_note, XfrNotePolicies,
+    };
+    use noah_algebra::prelude::*;
+
+    fn record<R: CryptoRng + RngCore>(prng: &mut R, amount: u64, keypair: &KeyPair) -> AssetRecord {
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            amount,
+            AssetType::from_identical_byte(0u8),
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+            keypair.get_pk(),
+        );
+        AssetRecord::from_template_no_identity_tracing(prng, &template).unwrap()
+    }
+
+    #[test]
+    fn test_collaborative_xfr_note() {
+        let mut prng = test_rng();
+        let mut params = BulletproofParams::default();
+
+        let alice = KeyPair::sample(&mut prng, SECP256K1);
+        let bob = KeyPair::sample(&mut prng, ED25519);
+        let alice_inputs = [record(&mut prng, 10, &alice), record(&mut prng, 5, &alice)];
+        let bob_input = record(&mut prng, 20, &bob);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let alice_output = record(&mut prng, 15, &receiver);
+        let receiver = KeyPair::sample(&mut prng, SECP256K1);
+        let bob_output = record(&mut prng, 20, &receiver);
+
+        // alice starts the transfer, and bob adds his part from the serialization
+        let mut builder = XfrNoteBuilder::new();
+        for input in alice_inputs.iter() {
+            builder.add_input(input.clone()).unwrap();
+        }
+        builder.add_output(alice_output).unwrap();
+        let bytes = serde_json::to_vec(&builder).unwrap();
+
+        let mut builder: XfrNoteBuilder = serde_json::from_slice(&bytes).unwrap();
+        builder
+            .add_input(bob_input)
+            .unwrap()
+            .add_output(bob_output)
+            .unwrap();
+        builder.build_body(&mut prng).unwrap();
+        let extra = record(&mut prng, 1, &bob);
+        assert!(builder.add_output(extra).is_err());
+
+        // both sign their own copies, which are then combined
+        let bytes = serde_json::to_vec(&builder).unwrap();
+        let mut alice_copy: XfrNoteBuilder = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(alice_copy.sign(&alice).unwrap(), 2);
+        assert_eq!(builder.sign(&bob).unwrap(), 1);
+        let outsider = KeyPair::sample(&mut prng, ED25519);
+        assert!(builder.sign(&outsider).is_err());
+        assert!(!builder.is_fully_signed());
+        assert!(builder.clone().finalize().is_err());
+
+        builder.combine(&alice_copy).unwrap();
+        assert!(builder.is_fully_signed());
+        let note = builder.finalize().unwrap();
+
+        let policies = XfrNotePolicies::empty_policies(3, 2);
+        assert!(verify_xfr_note(&mut prng, &mut params, &note, &policies.to_ref()).is_ok());
+
+        // a state of another transfer does not combine
+        let mut other = XfrNoteBuilder::new();
+        other.add_input(alice_inputs[0].clone()).unwrap();
+        assert!(other.combine(&alice_copy).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::anon_creds::{ac_confidential_verify, ACCommitment, ACConfidentialRevealProof};
 use crate::errors::{NoahError, Result};
 use crate::parameters::bulletproofs::BulletproofParams;
-use crate::parameters::params::{BULLET_PROOF_RANGE, MAX_CONFIDENTIAL_RECORD_NUMBER};
+use crate::parameters::{BULLET_PROOF_RANGE, MAX_CONFIDENTIAL_RECORD_NUMBER};
 use crate::xfr::{
     asset_record::AssetRecordType,
     asset_tracer::RecordDataEncKey,
@@ -15,6 +15,7 @@ use bulletproofs::RangeProof;
 use linear_map::LinearMap;
 use merlin::Transcript;
 use noah_algebra::{
+    cfg_into_iter,
     prelude::*,
     ristretto::{
         CompressedRistretto, PedersenCommitmentRistretto, RistrettoPoint, RistrettoScalar,
@@ -36,6 +37,8 @@ use noah_crypto::{
         },
     },
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 
 const POW_2_32: u64 = 0xFFFF_FFFFu64 + 1;
 
@@ -578,14 +581,12 @@ pub(crate) fn batch_verify_confidential_amount<R: CryptoRng + RngCore>(
     // The transcript header is unchanged for compatibility.
     let mut transcripts = vec![Transcript::new(b"Zei Range Proof"); instances.len()];
     let proofs: Vec<&RangeProof> = instances.iter().map(|(_, _, pf)| &pf.range_proof).collect();
-    let mut commitments = vec![];
-    for (input, output, proof) in instances {
-        commitments.push(extract_value_commitments(
-            input.as_slice(),
-            output.as_slice(),
-            proof,
-        )?);
-    }
+    let commitments = cfg_into_iter!(0..instances.len())
+        .map(|i| {
+            let (input, output, proof) = instances[i];
+            extract_value_commitments(input.as_slice(), output.as_slice(), proof)
+        })
+        .collect::<Result<Vec<_>>>()?;
     let value_commitments = commitments.iter().map(|c| c.as_slice()).collect_vec();
     Ok(batch_verify_ranges(
         prng,
@@ -0,0 +1,138 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{batch_verify_signatures, KeyPair, PublicKeyInner, Signature, SignatureList};
+use crate::xfr::{
+    compute_transfer_multisig,
+    structs::{XfrBody, XfrNote},
+    xfr_body_auth_message,
+};
+use noah_algebra::prelude::*;
+
+/// The signature schemes for the multisignatures of confidential transfer notes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum XfrSignatureScheme {
+    /// The signatures of the key types of the inputs, where ed25519 signatures
+    /// may be batch-verified with the cofactored equation.
+    Noah,
+    /// ed25519 signatures of RFC 8032 only, each verified with the strict equation,
+    /// so that the signatures verify natively on the chains that verify ed25519.
+    Ed25519,
+}
+
+impl Default for XfrSignatureScheme {
+    fn default() -> Self {
+        XfrSignatureScheme::Noah
+    }
+}
+
+/// Sign the body of a confidential transfer under the signature scheme.
+pub fn sign_xfr_body(
+    scheme: XfrSignatureScheme,
+    body: &XfrBody,
+    keys: &[&KeyPair],
+) -> Result<SignatureList> {
+    if scheme == XfrSignatureScheme::Ed25519
+        && keys
+            .iter()
+            .any(|key| !matches!(key.get_pk_ref().inner(), PublicKeyInner::Ed25519(_)))
+    {
+        return Err(NoahError::ParameterError);
+    }
+    compute_transfer_multisig(body, keys)
+}
+
+/// Verify the multisignature of a confidential transfer note under the signature scheme.
+pub fn verify_xfr_note_signatures<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    scheme: XfrSignatureScheme,
+    xfr_note: &XfrNote,
+) -> Result<()> {
+    let message = xfr_body_auth_message(&xfr_note.body)?;
+    let pubkeys = xfr_note
+        .body
+        .inputs
+        .iter()
+        .map(|input| &input.public_key)
+        .collect_vec();
+    let pairs = xfr_note.multisig.pair_with(&pubkeys)?;
+
+    match scheme {
+        XfrSignatureScheme::Noah => {
+            let instances = pairs
+                .into_iter()
+                .map(|(pk, sig)| (pk, message.as_slice(), sig))
+                .collect_vec();
+            batch_verify_signatures(prng, &instances)
+        }
+        XfrSignatureScheme::Ed25519 => {
+            for (pk, sig) in pairs {
+                match (pk.inner(), sig) {
+                    (PublicKeyInner::Ed25519(pk), Signature::Ed25519(sig)) => pk
+                        .verify_strict(&message, sig)
+                        .map_err(|_| NoahError::SignatureError)?,
+                    _ => return Err(NoahError::SignatureError),
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::xfr::{
+        asset_record::AssetRecordType,
+        gen_xfr_note,
+        sig::{sign_xfr_body, verify_xfr_note_signatures, XfrSignatureScheme},
+        structs::{AssetRecord, AssetRecordTemplate, AssetType, XfrNote},
+    };
+    use noah_algebra::prelude::*;
+
+    fn gen_note<R: CryptoRng + RngCore>(prng: &mut R, sender: &KeyPair) -> XfrNote {
+        let asset_type = AssetType::from_identical_byte(0u8);
+        let record_type = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+        let receiver = KeyPair::sample(prng, ED25519);
+
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            sender.get_pk(),
+        );
+        let input = AssetRecord::from_template_no_identity_tracing(prng, &template).unwrap();
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            asset_type,
+            record_type,
+            receiver.get_pk(),
+        );
+        let output = AssetRecord::from_template_no_identity_tracing(prng, &template).unwrap();
+        gen_xfr_note(prng, &[input], &[output], &[sender]).unwrap()
+    }
+
+    #[test]
+    fn test_xfr_signature_schemes() {
+        let mut prng = test_rng();
+        let ed25519 = KeyPair::sample(&mut prng, ED25519);
+        let secp256k1 = KeyPair::sample(&mut prng, SECP256K1);
+
+        // the RFC 8032 signatures are also valid under the default scheme
+        let mut note = gen_note(&mut prng, &ed25519);
+        note.multisig =
+            sign_xfr_body(XfrSignatureScheme::Ed25519, &note.body, &[&ed25519]).unwrap();
+        assert!(verify_xfr_note_signatures(&mut prng, XfrSignatureScheme::Ed25519, &note).is_ok());
+        assert!(verify_xfr_note_signatures(&mut prng, XfrSignatureScheme::Noah, &note).is_ok());
+
+        // a signature of another body is rejected
+        let other_note = gen_note(&mut prng, &ed25519);
+        note.body = other_note.body;
+        assert!(verify_xfr_note_signatures(&mut prng, XfrSignatureScheme::Ed25519, &note).is_err());
+
+        // secp256k1 keys only sign under the default scheme
+        let note = gen_note(&mut prng, &secp256k1);
+        assert!(sign_xfr_body(XfrSignatureScheme::Ed25519, &note.body, &[&secp256k1]).is_err());
+        assert!(verify_xfr_note_signatures(&mut prng, XfrSignatureScheme::Noah, &note).is_ok());
+        assert!(verify_xfr_note_signatures(&mut prng, XfrSignatureScheme::Ed25519, &note).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::errors::{NoahError, Result};
+use crate::keys::{KeyPair, PublicKey, PublicKeyInner, SecretKey};
+use digest::Digest;
+use libsecp256k1::{PublicKey as Secp256k1PublicKey, SecretKey as Secp256k1SecretKey};
+use noah_algebra::prelude::*;
+use sha2::Sha256;
+
+/// The domain separator for the tweak of stealth addresses.
+const STEALTH_ADDRESS_TWEAK_DOMAIN: &[u8] = b"Noah Stealth Address Tweak";
+
+/// A one-time address derived from the spending key and the viewing key of a recipient.
+///
+/// The sender publishes the ephemeral key `R = r * G` next to the one-time key
+/// `P' = P + H(r * V, R) * G`, where `P` and `V` are the spending and the viewing public keys,
+/// so that only the holder of `v` can link `P'` to the recipient, and only the holder of `p`
+/// can spend with `p + H(v * R, R)`. Only secp256k1 keys are supported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StealthAddress {
+    /// The one-time public key that receives the payment.
+    pub one_time_key: PublicKey,
+    /// The ephemeral public key `R = r * G` of the sender.
+    pub ephemeral_key: PublicKey,
+}
+
+impl PublicKey {
+    /// Derive a one-time stealth address of this spending key, for the viewing key of the
+    /// recipient, with the ephemeral secret `r` of the sender.
+    pub fn derive_stealth(&self, view_key: &PublicKey, r: &SecretKey) -> Result<StealthAddress> {
+        let spend_pk = as_secp256k1_pk(self)?;
+        let r = as_secp256k1_sk(r)?;
+
+        let ephemeral_pk = Secp256k1PublicKey::from_secret_key(r);
+        let mut shared_point = *as_secp256k1_pk(view_key)?;
+        shared_point
+            .tweak_mul_assign(r)
+            .map_err(|_| NoahError::ParameterError)?;
+
+        let tweak = stealth_tweak(&shared_point, &ephemeral_pk)?;
+        let mut one_time_pk = *spend_pk;
+        one_time_pk
+            .tweak_add_assign(&tweak)
+            .map_err(|_| NoahError::ParameterError)?;
+
+        Ok(StealthAddress {
+            one_time_key: PublicKey(PublicKeyInner::Secp256k1(one_time_pk)),
+            ephemeral_key: PublicKey(PublicKeyInner::Secp256k1(ephemeral_pk)),
+        })
+    }
+
+    /// Derive a one-time stealth address of this spending key with a random ephemeral secret.
+    pub fn sample_stealth<R: CryptoRng + RngCore>(
+        &self,
+        prng: &mut R,
+        view_key: &PublicKey,
+    ) -> Result<StealthAddress> {
+        let r = SecretKey::Secp256k1(Secp256k1SecretKey::random(prng));
+        self.derive_stealth(view_key, &r)
+    }
+}
+
+/// Check with the viewing secret key whether the stealth address belongs to the spending key.
+pub fn scan_stealth_address(
+    view_sk: &SecretKey,
+    spend_pk: &PublicKey,
+    stealth: &StealthAddress,
+) -> Result<bool> {
+    let one_time_pk = derive_one_time_pk(view_sk, spend_pk, stealth)?;
+    Ok(stealth.one_time_key == PublicKey(PublicKeyInner::Secp256k1(one_time_pk)))
+}
+
+/// Recover the key pair that spends from the stealth address, given the viewing secret key
+/// and the spending key pair of the recipient.
+pub fn recover_stealth_keypair(
+    view_sk: &SecretKey,
+    spend_keypair: &KeyPair,
+    stealth: &StealthAddress,
+) -> Result<KeyPair> {
+    if !scan_stealth_address(view_sk, spend_keypair.get_pk_ref(), stealth)? {
+        return Err(NoahError::ParameterError);
+    }
+
+    let ephemeral_pk = as_secp256k1_pk(&stealth.ephemeral_key)?;
+    let tweak = stealth_tweak(&shared_point(view_sk, ephemeral_pk)?, ephemeral_pk)?;
+
+    let mut one_time_sk = *as_secp256k1_sk(spend_keypair.get_sk_ref())?;
+    one_time_sk
+        .tweak_add_assign(&tweak)
+        .map_err(|_| NoahError::ParameterError)?;
+
+    Ok(SecretKey::Secp256k1(one_time_sk).into_keypair())
+}
+
+/// Compute the one-time public key that the stealth address should have, as the recipient.
+fn derive_one_time_pk(
+    view_sk: &SecretKey,
+    spend_pk: &PublicKey,
+    stealth: &StealthAddress,
+) -> Result<Secp256k1PublicKey> {
+    let ephemeral_pk = as_secp256k1_pk(&stealth.ephemeral_key)?;
+    let tweak = stealth_tweak(&shared_point(view_sk, ephemeral_pk)?, ephemeral_pk)?;
+
+    let mut one_time_pk = *as_secp256k1_pk(spend_pk)?;
+    one_time_pk
+        .tweak_add_assign(&tweak)
+        .map_err(|_| NoahError::ParameterError)?;
+    Ok(one_time_pk)
+}
+
+/// Compute the shared point `v * R` as the recipient.
+fn shared_point(
+    view_sk: &SecretKey,
+    ephemeral_pk: &Secp256k1PublicKey,
+) -> Result<Secp256k1PublicKey> {
+    let mut shared_point = *ephemeral_pk;
+    shared_point
+        .tweak_mul_assign(as_secp256k1_sk(view_sk)?)
+        .map_err(|_| NoahError::ParameterError)?;
+    Ok(shared_point)
+}
+
+/// Hash the shared point and the ephemeral key to the tweak of the spending key.
+fn stealth_tweak(
+    shared_point: &Secp256k1PublicKey,
+    ephemeral_pk: &Secp256k1PublicKey,
+) -> Result<Secp256k1SecretKey> {
+    let mut hasher = Sha256::new();
+    hasher.update(STEALTH_ADDRESS_TWEAK_DOMAIN);
+    hasher.update(shared_point.serialize_compressed());
+    hasher.update(ephemeral_pk.serialize_compressed());
+
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&hasher.finalize());
+    Secp256k1SecretKey::parse(&bytes).map_err(|_| NoahError::ParameterError)
+}
+
+/// Return the secp256k1 public key, as only secp256k1 keys are supported.
+fn as_secp256k1_pk(pk: &PublicKey) -> Result<&Secp256k1PublicKey> {
+    match pk.inner() {
+        PublicKeyInner::Secp256k1(pk) => Ok(pk),
+        _ => Err(NoahError::ParameterError),
+    }
+}
+
+/// Return the secp256k1 secret key, as only secp256k1 keys are supported.
+fn as_secp256k1_sk(sk: &SecretKey) -> Result<&Secp256k1SecretKey> {
+    match sk {
+        SecretKey::Secp256k1(sk) => Ok(sk),
+        _ => Err(NoahError::ParameterError),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::keys::KeyPair;
+    use crate::parameters::AddressFormat::{ED25519, SECP256K1};
+    use crate::xfr::stealth::{recover_stealth_keypair, scan_stealth_address};
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_stealth_address() {
+        let mut prng = test_rng();
+        let spend = KeyPair::sample(&mut prng, SECP256K1);
+        let view = KeyPair::sample(&mut prng, SECP256K1);
+
+        let stealth = spend
+            .get_pk()
+            .sample_stealth(&mut prng, view.get_pk_ref())
+            .unwrap();
+        let other_stealth = spend
+            .get_pk()
+            .sample_stealth(&mut prng, view.get_pk_ref())
+            .unwrap();
+
+        // paying the same recipient twice does not reuse the address
+        assert_ne!(stealth.one_time_key, spend.get_pk());
+        assert_ne!(stealth.one_time_key, other_stealth.one_time_key);
+
+        // the recipient finds both payments
+        assert!(scan_stealth_address(view.get_sk_ref(), spend.get_pk_ref(), &stealth).unwrap());
+        assert!(
+            scan_stealth_address(view.get_sk_ref(), spend.get_pk_ref(), &other_stealth).unwrap()
+        );
+
+        // and can spend from them
+        let one_time = recover_stealth_keypair(view.get_sk_ref(), &spend, &stealth).unwrap();
+        assert_eq!(one_time.get_pk(), stealth.one_time_key);
+        let sig = one_time.sign(b"spend").unwrap();
+        assert!(stealth.one_time_key.verify(b"spend", &sig).is_ok());
+
+        // someone else does not recognize the payment
+        let stranger = KeyPair::sample(&mut prng, SECP256K1);
+        assert!(
+            !scan_stealth_address(stranger.get_sk_ref(), spend.get_pk_ref(), &stealth).unwrap()
+        );
+        assert!(recover_stealth_keypair(stranger.get_sk_ref(), &spend, &stealth).is_err());
+
+        // ed25519 keys are not supported
+        let ed25519 = KeyPair::sample(&mut prng, ED25519);
+        assert!(ed25519
+            .get_pk()
+            .sample_stealth(&mut prng, view.get_pk_ref())
+            .is_err());
+    }
+}
@@ -6,27 +6,40 @@ use crate::errors::{NoahError, Result};
 use crate::keys::{KeyPair, KeyType, PublicKey, PublicKeyInner, SignatureList};
 use crate::xfr::{
     asset_mixer::AssetMixProof,
-    asset_record::AssetRecordType,
+    asset_record::{open_blind_asset_record, AssetRecordType},
     asset_tracer::{RecordDataCiphertext, RecordDataDecKey, RecordDataEncKey},
-    xfr_hybrid_decrypt, xfr_hybrid_encrypt,
 };
 use ark_std::boxed::Box;
 use bulletproofs::RangeProof;
 use digest::Digest;
+use merlin::Transcript;
 use noah_algebra::{
     ed25519::{Ed25519Point, Ed25519Scalar},
     prelude::*,
     ristretto::{
-        CompressedEdwardsY, CompressedRistretto, PedersenCommitmentRistretto, RistrettoScalar,
+        CompressedEdwardsY, CompressedRistretto, PedersenCommitmentRistretto, RistrettoPoint,
+        RistrettoScalar,
     },
     secp256k1::{SECP256K1Scalar, SECP256K1G1},
     traits::PedersenCommitment,
 };
 use noah_crypto::{
     chaum_pedersen::ChaumPedersenProofX,
-    elgamal::elgamal_key_gen,
-    hybrid_encryption::{NoahHybridCiphertext, XPublicKey, XSecretKey},
-    pedersen_elgamal::PedersenElGamalEqProof,
+    elgamal::{elgamal_encrypt, elgamal_key_gen},
+    hpke::{hpke_open, hpke_seal, HPKE_ENC_LEN, HPKE_TAG_LEN},
+    hybrid_encryption::{
+        hybrid_decrypt_with_x25519_secret_key, hybrid_encrypt_x25519, NoahHybridCiphertext,
+        XPublicKey, XSecretKey,
+    },
+    pedersen_elgamal::{
+        pedersen_elgamal_aggregate_eq_proof, pedersen_elgamal_aggregate_eq_verify,
+        PedersenElGamalEqProof,
+    },
+    threshold_encryption::{
+        combine_decryption_shares, threshold_decrypt_share, threshold_encrypt,
+        verify_decryption_share, DecryptionShare, ThresholdCiphertext, ThresholdDecKeyShare,
+        ThresholdEncKey,
+    },
 };
 use sha2::Sha512;
 
@@ -405,6 +418,51 @@ pub struct TracerMemo {
     pub lock_info: NoahHybridCiphertext,
 }
 
+/// The info of the HPKE encryption of the lock of owner memos.
+const OWNER_MEMO_HPKE_INFO: &[u8] = b"Noah OwnerMemo Lock";
+/// The info of the HPKE encryption of the padded lock of owner memos.
+const OWNER_MEMO_PADDED_HPKE_INFO: &[u8] = b"Noah OwnerMemo Padded Lock";
+
+/// The sizes to which the plaintext of a padded lock can be padded, all of which fit the
+/// longest plaintext, "amount || asset type", with its padding.
+pub const OWNER_MEMO_PADDING_BUCKETS: [usize; 3] = [48, 64, 128];
+
+/// The versions of the encryption of the lock of owner memos.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OwnerMemoVersion {
+    /// The hybrid encryption of the key type.
+    Legacy,
+    /// HPKE of RFC 9180 to the X25519 form of an ed25519 key, in the base mode of
+    /// DHKEM(X25519, HKDF-SHA256), HKDF-SHA256, and ChaCha20Poly1305,
+    /// with the blind share as the associated data.
+    Hpke,
+    /// HPKE as in `Hpke`, over the plaintext padded to the given size in
+    /// `OWNER_MEMO_PADDING_BUCKETS` with a 0x80 byte followed by zeros, so that the locks
+    /// of all the records have the same size whichever fields are confidential.
+    PaddedHpke(usize),
+}
+
+impl Default for OwnerMemoVersion {
+    fn default() -> Self {
+        OwnerMemoVersion::Legacy
+    }
+}
+
+impl OwnerMemoVersion {
+    /// Return the padded size of the plaintext, if any, after checking that it is allowed.
+    pub fn padded_size(&self) -> Result<Option<usize>> {
+        match self {
+            OwnerMemoVersion::PaddedHpke(size) => {
+                if !OWNER_MEMO_PADDING_BUCKETS.contains(size) {
+                    return Err(NoahError::ParameterError);
+                }
+                Ok(Some(*size))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
 /// Information directed to the recipient.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub struct OwnerMemo {
@@ -414,6 +472,8 @@ pub struct OwnerMemo {
     pub blind_share_bytes: CompactByteArray,
     /// The ciphertext of the memo information.
     pub lock_bytes: CompactByteArray,
+    /// The version of the encryption of the lock.
+    pub version: OwnerMemoVersion,
 }
 
 impl OwnerMemo {
@@ -422,18 +482,36 @@ impl OwnerMemo {
         prng: &mut R,
         amount: u64,
         pub_key: &PublicKey,
+    ) -> Result<(Self, (RistrettoScalar, RistrettoScalar))> {
+        OwnerMemo::from_amount_with_version(prng, amount, pub_key, OwnerMemoVersion::Legacy)
+    }
+
+    /// Construct an `OwnerMemo` for an asset record with only confidential amount,
+    /// with the lock encrypted in the given version.
+    pub fn from_amount_with_version<R: CryptoRng + RngCore>(
+        prng: &mut R,
+        amount: u64,
+        pub_key: &PublicKey,
+        version: OwnerMemoVersion,
     ) -> Result<(Self, (RistrettoScalar, RistrettoScalar))> {
         let (key_type, r, blind_share_bytes) = pub_key.random_scalar_with_compressed_point(prng);
         let shared_point =
             OwnerMemo::derive_shared_point(&key_type, &r, &pub_key.as_compressed_point()?)?;
         let amount_blinds = OwnerMemo::calc_amount_blinds(&shared_point);
 
-        let lock_bytes = xfr_hybrid_encrypt(pub_key, prng, &amount.to_be_bytes())?;
+        let lock_bytes = OwnerMemo::encrypt(
+            prng,
+            version,
+            pub_key,
+            &blind_share_bytes,
+            &amount.to_be_bytes(),
+        )?;
         Ok((
             OwnerMemo {
                 key_type,
                 blind_share_bytes: CompactByteArray(blind_share_bytes),
                 lock_bytes: CompactByteArray(lock_bytes),
+                version,
             },
             amount_blinds,
         ))
@@ -444,18 +522,31 @@ impl OwnerMemo {
         prng: &mut R,
         asset_type: &AssetType,
         pub_key: &PublicKey,
+    ) -> Result<(Self, RistrettoScalar)> {
+        OwnerMemo::from_asset_type_with_version(prng, asset_type, pub_key, OwnerMemoVersion::Legacy)
+    }
+
+    /// Construct an `OwnerMemo` for an asset record with only confidential asset type,
+    /// with the lock encrypted in the given version.
+    pub fn from_asset_type_with_version<R: CryptoRng + RngCore>(
+        prng: &mut R,
+        asset_type: &AssetType,
+        pub_key: &PublicKey,
+        version: OwnerMemoVersion,
     ) -> Result<(Self, RistrettoScalar)> {
         let (key_type, r, blind_share_bytes) = pub_key.random_scalar_with_compressed_point(prng);
         let shared_point =
             OwnerMemo::derive_shared_point(&key_type, &r, &pub_key.as_compressed_point()?)?;
         let asset_type_blind = OwnerMemo::calc_asset_type_blind(&shared_point);
 
-        let lock_bytes = xfr_hybrid_encrypt(pub_key, prng, &asset_type.0)?;
+        let lock_bytes =
+            OwnerMemo::encrypt(prng, version, pub_key, &blind_share_bytes, &asset_type.0)?;
         Ok((
             OwnerMemo {
                 key_type,
                 blind_share_bytes: CompactByteArray(blind_share_bytes),
                 lock_bytes: CompactByteArray(lock_bytes),
+                version,
             },
             asset_type_blind,
         ))
@@ -467,6 +558,24 @@ impl OwnerMemo {
         amount: u64,
         asset_type: &AssetType,
         pub_key: &PublicKey,
+    ) -> Result<(Self, (RistrettoScalar, RistrettoScalar), RistrettoScalar)> {
+        OwnerMemo::from_amount_and_asset_type_with_version(
+            prng,
+            amount,
+            asset_type,
+            pub_key,
+            OwnerMemoVersion::Legacy,
+        )
+    }
+
+    /// Construct an `OwnerMemo` for an asset record with both confidential amount and
+    /// confidential asset type, with the lock encrypted in the given version.
+    pub fn from_amount_and_asset_type_with_version<R: CryptoRng + RngCore>(
+        prng: &mut R,
+        amount: u64,
+        asset_type: &AssetType,
+        pub_key: &PublicKey,
+        version: OwnerMemoVersion,
     ) -> Result<(Self, (RistrettoScalar, RistrettoScalar), RistrettoScalar)> {
         let (key_type, r, blind_share_bytes) = pub_key.random_scalar_with_compressed_point(prng);
         let shared_point =
@@ -477,12 +586,19 @@ impl OwnerMemo {
         let mut amount_asset_type_plaintext = vec![];
         amount_asset_type_plaintext.extend_from_slice(&amount.to_be_bytes()[..]);
         amount_asset_type_plaintext.extend_from_slice(&asset_type.0[..]);
-        let lock_bytes = xfr_hybrid_encrypt(pub_key, prng, &amount_asset_type_plaintext)?;
+        let lock_bytes = OwnerMemo::encrypt(
+            prng,
+            version,
+            pub_key,
+            &blind_share_bytes,
+            &amount_asset_type_plaintext,
+        )?;
         Ok((
             OwnerMemo {
                 key_type,
                 blind_share_bytes: CompactByteArray(blind_share_bytes),
                 lock_bytes: CompactByteArray(lock_bytes),
+                version,
             },
             amount_blinds,
             asset_type_blind,
@@ -553,9 +669,73 @@ impl OwnerMemo {
 }
 
 impl OwnerMemo {
+    // Encrypt the lock.
+    fn encrypt<R: CryptoRng + RngCore>(
+        prng: &mut R,
+        version: OwnerMemoVersion,
+        pub_key: &PublicKey,
+        blind_share_bytes: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>> {
+        match version {
+            OwnerMemoVersion::Legacy => xfr_hybrid_encrypt(pub_key, prng, plaintext),
+            OwnerMemoVersion::Hpke => Ok(hpke_seal(
+                prng,
+                &pub_key.to_hpke_public_key()?,
+                OWNER_MEMO_HPKE_INFO,
+                blind_share_bytes,
+                plaintext,
+            )?),
+            OwnerMemoVersion::PaddedHpke(size) => {
+                version.padded_size()?;
+                // ISO/IEC 7816-4 padding, which always adds at least one byte
+                if plaintext.len() >= size {
+                    return Err(NoahError::ParameterError);
+                }
+                let mut padded = plaintext.to_vec();
+                padded.push(0x80);
+                padded.resize(size, 0u8);
+                Ok(hpke_seal(
+                    prng,
+                    &pub_key.to_hpke_public_key()?,
+                    OWNER_MEMO_PADDED_HPKE_INFO,
+                    blind_share_bytes,
+                    &padded,
+                )?)
+            }
+        }
+    }
+
     // Decrypt the lock.
     fn decrypt(&self, keypair: &KeyPair) -> Result<Vec<u8>> {
-        xfr_hybrid_decrypt(&keypair.sec_key, &self.lock_bytes.0)
+        match self.version {
+            OwnerMemoVersion::Legacy => xfr_hybrid_decrypt(&keypair.sec_key, &self.lock_bytes.0),
+            OwnerMemoVersion::Hpke => hpke_open(
+                &keypair.sec_key.to_hpke_secret_key()?,
+                OWNER_MEMO_HPKE_INFO,
+                &self.blind_share_bytes.0,
+                &self.lock_bytes.0,
+            )
+            .map_err(|_| NoahError::DecryptionError),
+            OwnerMemoVersion::PaddedHpke(_) => {
+                let mut padded = hpke_open(
+                    &keypair.sec_key.to_hpke_secret_key()?,
+                    OWNER_MEMO_PADDED_HPKE_INFO,
+                    &self.blind_share_bytes.0,
+                    &self.lock_bytes.0,
+                )
+                .map_err(|_| NoahError::DecryptionError)?;
+                let end = padded
+                    .iter()
+                    .rposition(|b| *b != 0)
+                    .ok_or(NoahError::DecryptionError)?;
+                if padded[end] != 0x80 {
+                    return Err(NoahError::DecryptionError);
+                }
+                padded.truncate(end);
+                Ok(padded)
+            }
+        }
     }
 
     // Given a shared point, calculate the amount blinds.
@@ -599,6 +779,274 @@ impl OwnerMemo {
     }
 }
 
+/// The length of the opening of an asset record: amount || asset type || amount blinds || asset type blind.
+const RECORD_OPENING_LENGTH: usize = 8 + ASSET_TYPE_LENGTH + 3 * 32;
+
+/// The opening of an asset record: the amount, the asset type, the amount blinds, and the asset type blind.
+pub type RecordOpening = (
+    u64,
+    AssetType,
+    (RistrettoScalar, RistrettoScalar),
+    RistrettoScalar,
+);
+
+/// Serialize the opening of an asset record.
+fn record_opening_to_bytes(record: &OpenAssetRecord) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(RECORD_OPENING_LENGTH);
+    bytes.extend_from_slice(&record.amount.to_be_bytes());
+    bytes.extend_from_slice(&record.asset_type.0);
+    bytes.extend_from_slice(&record.amount_blinds.0.to_bytes());
+    bytes.extend_from_slice(&record.amount_blinds.1.to_bytes());
+    bytes.extend_from_slice(&record.type_blind.to_bytes());
+    bytes
+}
+
+/// Deserialize the opening of an asset record.
+fn record_opening_from_bytes(bytes: &[u8]) -> Result<RecordOpening> {
+    if bytes.len() != RECORD_OPENING_LENGTH {
+        return Err(NoahError::InconsistentStructureError);
+    }
+
+    let mut amt_be_bytes: [u8; 8] = Default::default();
+    amt_be_bytes.copy_from_slice(&bytes[..8]);
+    let mut asset_type_bytes: [u8; ASSET_TYPE_LENGTH] = Default::default();
+    asset_type_bytes.copy_from_slice(&bytes[8..8 + ASSET_TYPE_LENGTH]);
+
+    let blinds = &bytes[8 + ASSET_TYPE_LENGTH..];
+    let amount_blind_low = RistrettoScalar::from_bytes(&blinds[..32])?;
+    let amount_blind_high = RistrettoScalar::from_bytes(&blinds[32..64])?;
+    let asset_type_blind = RistrettoScalar::from_bytes(&blinds[64..])?;
+
+    Ok((
+        u64::from_be_bytes(amt_be_bytes),
+        AssetType(asset_type_bytes),
+        (amount_blind_low, amount_blind_high),
+        asset_type_blind,
+    ))
+}
+
+/// Information directed to a t-of-n committee, which learns what the recipient learns from
+/// the `OwnerMemo`, or what the tracer learns from the `TracerMemo`, only when at least t
+/// members cooperate.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CommitteeMemo {
+    /// The threshold ciphertext of the record opening, or of the lock info of a tracer memo.
+    pub lock: ThresholdCiphertext,
+}
+
+impl CommitteeMemo {
+    /// Construct a `CommitteeMemo` that locks the opening of an asset record to a committee.
+    pub fn new<R: CryptoRng + RngCore>(
+        prng: &mut R,
+        committee: &ThresholdEncKey,
+        record: &OpenAssetRecord,
+    ) -> Self {
+        CommitteeMemo {
+            lock: threshold_encrypt(prng, committee, &record_opening_to_bytes(record)),
+        }
+    }
+
+    /// Compute the decryption share of a committee member.
+    pub fn decrypt_share<R: CryptoRng + RngCore>(
+        &self,
+        prng: &mut R,
+        key_share: &ThresholdDecKeyShare,
+    ) -> DecryptionShare {
+        threshold_decrypt_share(prng, &self.lock, key_share)
+    }
+
+    /// Verify the decryption share of a committee member.
+    pub fn verify_share(&self, committee: &ThresholdEncKey, share: &DecryptionShare) -> Result<()> {
+        verify_decryption_share(committee, &self.lock, share).map_err(NoahError::from)
+    }
+
+    /// Combine the decryption shares of the committee members for the opening of a record,
+    /// and return the amount, the asset type, the amount blinds, and the asset type blind.
+    pub fn combine(
+        &self,
+        committee: &ThresholdEncKey,
+        shares: &[DecryptionShare],
+    ) -> Result<RecordOpening> {
+        let plaintext = combine_decryption_shares(committee, &self.lock, shares)?;
+        record_opening_from_bytes(&plaintext)
+    }
+}
+
+/// Information forwarded by the recipient of an asset record to a second viewer, e.g., an accountant,
+/// after the payment. The amount and the asset type are also locked with ElGamal to the viewer, with
+/// a proof that the locks match the commitments of the record, so that anyone can check that the memo
+/// encrypts what the `OwnerMemo` of the record encrypts without decrypting it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ViewerMemo {
+    /// The encryption keys of the viewer.
+    pub enc_key: AssetTracerEncKeys,
+    /// The ciphertexts of the amount, in two 32-bit limbs, if the amount is confidential.
+    pub lock_amount: Option<(RecordDataCiphertext, RecordDataCiphertext)>,
+    /// The ciphertext of the asset type, if the asset type is confidential.
+    pub lock_asset_type: Option<RecordDataCiphertext>,
+    /// The ciphertext of the amount, the asset type, and their blinding factors.
+    pub lock_info: NoahHybridCiphertext,
+    /// The proof that the ciphertexts encrypt the values committed in the record.
+    pub proof: PedersenElGamalEqProof,
+}
+
+impl OwnerMemo {
+    /// Forward the memo of the record to a second viewer, after checking that the memo opens the record.
+    pub fn add_viewer<R: CryptoRng + RngCore>(
+        &self,
+        prng: &mut R,
+        keypair: &KeyPair,
+        record: &BlindAssetRecord,
+        viewer: &AssetTracerEncKeys,
+    ) -> Result<ViewerMemo> {
+        let open_record = open_blind_asset_record(record, &Some(self.clone()), keypair)?;
+
+        let mut m = vec![];
+        let mut r = vec![];
+        let lock_amount = if record.amount.is_confidential() {
+            let (low, high) = u64_to_u32_pair(open_record.amount);
+            let (blind_low, blind_high) = &open_record.amount_blinds;
+            m.push(RistrettoScalar::from(low));
+            m.push(RistrettoScalar::from(high));
+            r.push(*blind_low);
+            r.push(*blind_high);
+            Some((
+                elgamal_encrypt(&m[0], blind_low, &viewer.record_data_enc_key),
+                elgamal_encrypt(&m[1], blind_high, &viewer.record_data_enc_key),
+            ))
+        } else {
+            None
+        };
+        let lock_asset_type = if record.asset_type.is_confidential() {
+            m.push(open_record.asset_type.as_scalar());
+            r.push(open_record.type_blind);
+            Some(elgamal_encrypt(
+                &open_record.asset_type.as_scalar(),
+                &open_record.type_blind,
+                &viewer.record_data_enc_key,
+            ))
+        } else {
+            None
+        };
+        if m.is_empty() {
+            return Err(NoahError::ParameterError); // nothing to prove
+        }
+
+        let (ctexts, commitments) =
+            viewer_memo_ciphertexts_and_commitments(record, &lock_amount, &lock_asset_type)?;
+        let mut transcript = Transcript::new(VIEWER_MEMO_PROOF_DOMAIN);
+        let proof = pedersen_elgamal_aggregate_eq_proof(
+            &mut transcript,
+            prng,
+            &m,
+            &r,
+            &viewer.record_data_enc_key,
+            &ctexts,
+            &commitments,
+        );
+        let lock_info = hybrid_encrypt_x25519(
+            prng,
+            &viewer.lock_info_enc_key,
+            &record_opening_to_bytes(&open_record),
+        );
+
+        Ok(ViewerMemo {
+            enc_key: viewer.clone(),
+            lock_amount,
+            lock_asset_type,
+            lock_info,
+            proof,
+        })
+    }
+
+    /// Lock the opening of the record to a t-of-n committee, after checking that the memo
+    /// opens the record, so that the committee can open it without a single decryptor.
+    pub fn lock_to_committee<R: CryptoRng + RngCore>(
+        &self,
+        prng: &mut R,
+        keypair: &KeyPair,
+        record: &BlindAssetRecord,
+        committee: &ThresholdEncKey,
+    ) -> Result<CommitteeMemo> {
+        let open_record = open_blind_asset_record(record, &Some(self.clone()), keypair)?;
+        Ok(CommitteeMemo::new(prng, committee, &open_record))
+    }
+}
+
+const VIEWER_MEMO_PROOF_DOMAIN: &[u8] = b"ViewerMemoProof";
+
+impl ViewerMemo {
+    /// Verify the proof that the memo locks the amount and the asset type committed in the record.
+    pub fn verify<R: CryptoRng + RngCore>(
+        &self,
+        prng: &mut R,
+        record: &BlindAssetRecord,
+    ) -> Result<()> {
+        let (ctexts, commitments) = viewer_memo_ciphertexts_and_commitments(
+            record,
+            &self.lock_amount,
+            &self.lock_asset_type,
+        )?;
+        if ctexts.is_empty() {
+            return Err(NoahError::InconsistentStructureError);
+        }
+        let transcript = Transcript::new(VIEWER_MEMO_PROOF_DOMAIN);
+        Ok(pedersen_elgamal_aggregate_eq_verify(
+            &transcript,
+            prng,
+            &self.enc_key.record_data_enc_key,
+            &ctexts,
+            &commitments,
+            &self.proof,
+        )?)
+    }
+
+    /// Decrypt the opening of the record with the keys of the viewer, without checking it.
+    pub fn decrypt(&self, dec_key: &AssetTracerDecKeys) -> Result<RecordOpening> {
+        let plaintext =
+            hybrid_decrypt_with_x25519_secret_key(&self.lock_info, &dec_key.lock_info_dec_key);
+        record_opening_from_bytes(&plaintext)
+    }
+}
+
+// Pair the ElGamal ciphertexts of a viewer memo with the commitments of the record, requiring
+// a ciphertext exactly for the confidential amount and asset type.
+fn viewer_memo_ciphertexts_and_commitments(
+    record: &BlindAssetRecord,
+    lock_amount: &Option<(RecordDataCiphertext, RecordDataCiphertext)>,
+    lock_asset_type: &Option<RecordDataCiphertext>,
+) -> Result<(Vec<RecordDataCiphertext>, Vec<RistrettoPoint>)> {
+    let mut ctexts = vec![];
+    let mut commitments = vec![];
+    match (&record.amount, lock_amount) {
+        (XfrAmount::Confidential((com_low, com_high)), Some((lock_low, lock_high))) => {
+            ctexts.push(lock_low.clone());
+            ctexts.push(lock_high.clone());
+            commitments.push(
+                com_low
+                    .decompress()
+                    .ok_or(NoahError::DecompressElementError)?,
+            );
+            commitments.push(
+                com_high
+                    .decompress()
+                    .ok_or(NoahError::DecompressElementError)?,
+            );
+        }
+        (XfrAmount::NonConfidential(_), None) => {}
+        _ => return Err(NoahError::InconsistentStructureError),
+    }
+    match (&record.asset_type, lock_asset_type) {
+        (XfrAssetType::Confidential(com), Some(lock)) => {
+            ctexts.push(lock.clone());
+            commitments.push(com.decompress().ok_or(NoahError::DecompressElementError)?);
+        }
+        (XfrAssetType::NonConfidential(_), None) => {}
+        _ => return Err(NoahError::InconsistentStructureError),
+    }
+    Ok((ctexts, commitments))
+}
+
 /// Check memo size.
 pub fn check_memo_size(output: &BlindAssetRecord, memo: &Option<OwnerMemo>) -> Result<()> {
     if !output.amount.is_confidential() && !output.asset_type.is_confidential() {
@@ -610,18 +1058,35 @@ pub fn check_memo_size(output: &BlindAssetRecord, memo: &Option<OwnerMemo>) -> R
 
     let memo = memo.as_ref().unwrap(); //safety unwrap
 
+    // HPKE replaces the ephemeral key of the legacy lock by its own, and adds the tag.
+    let hpke_overhead = match memo.version {
+        OwnerMemoVersion::Legacy => 0,
+        OwnerMemoVersion::Hpke | OwnerMemoVersion::PaddedHpke(_) => HPKE_TAG_LEN,
+    };
+
     match (&memo.key_type, output.public_key.inner()) {
         (KeyType::Ed25519, PublicKeyInner::Ed25519(_)) => {
+            // a padded lock must have exactly the size of an allowed bucket
+            if let OwnerMemoVersion::PaddedHpke(size) = memo.version {
+                if memo.version.padded_size().is_err()
+                    || memo.blind_share_bytes.0.len() != Ed25519Point::COMPRESSED_LEN
+                    || memo.lock_bytes.0.len() != HPKE_ENC_LEN + size + hpke_overhead
+                {
+                    return Err(NoahError::AXfrVerifierParamsError);
+                }
+                return Ok(());
+            }
+
             if memo.blind_share_bytes.0.len() != Ed25519Point::COMPRESSED_LEN
                 || (output.amount.is_confidential()
                     && output.asset_type.is_confidential()
-                    && memo.lock_bytes.0.len() > MAX_LOCK_BYTES_CON_CON_ED25519)
+                    && memo.lock_bytes.0.len() > MAX_LOCK_BYTES_CON_CON_ED25519 + hpke_overhead)
                 || (!output.amount.is_confidential()
                     && output.asset_type.is_confidential()
-                    && memo.lock_bytes.0.len() > MAX_LOCK_BYTES_NON_CON_ED25519)
+                    && memo.lock_bytes.0.len() > MAX_LOCK_BYTES_NON_CON_ED25519 + hpke_overhead)
                 || (output.amount.is_confidential()
                     && !output.asset_type.is_confidential()
-                    && memo.lock_bytes.0.len() > MAX_LOCK_BYTES_CON_NON_ED25519)
+                    && memo.lock_bytes.0.len() > MAX_LOCK_BYTES_CON_NON_ED25519 + hpke_overhead)
             {
                 return Err(NoahError::AXfrVerifierParamsError);
             }
@@ -629,7 +1094,8 @@ pub fn check_memo_size(output: &BlindAssetRecord, memo: &Option<OwnerMemo>) -> R
             Ok(())
         }
         (KeyType::Secp256k1, PublicKeyInner::Secp256k1(_)) => {
-            if memo.blind_share_bytes.0.len() != SECP256K1G1::COMPRESSED_LEN
+            if memo.version != OwnerMemoVersion::Legacy
+                || memo.blind_share_bytes.0.len() != SECP256K1G1::COMPRESSED_LEN
                 || (output.amount.is_confidential()
                     && output.asset_type.is_confidential()
                     && memo.lock_bytes.0.len() > MAX_LOCK_BYTES_CON_CON_SECP256K1)
@@ -714,6 +1180,10 @@ pub struct AssetRecordTemplate {
     pub asset_record_type: AssetRecordType,
     /// The tracing polices for this asset.
     pub asset_tracing_policies: TracingPolicies,
+    /// The version of the encryption of the lock of the owner memo, which the constructors
+    /// set to `Legacy` and `with_owner_memo_padding` changes.
+    #[serde(default)]
+    pub(crate) owner_memo_version: OwnerMemoVersion,
 }
 
 /// The amount and asset type part proof for confidential transfer.
@@ -774,27 +1244,6 @@ impl PartialEq for XfrRangeProof {
 
 impl Eq for XfrRangeProof {}
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-enum CompatibleKeyType {
-    Old,
-    New(KeyType),
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-enum CompatibleBlindShare {
-    Old(CompressedEdwardsY),
-    New(Vec<u8>),
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-enum CompatibleLock {
-    Old(NoahHybridCiphertext),
-    New(Vec<u8>),
-}
-
 use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
 
 impl<'de> Deserialize<'de> for OwnerMemo {
@@ -808,6 +1257,7 @@ impl<'de> Deserialize<'de> for OwnerMemo {
             BlindShareBytes,
             Lock,
             LockBytes,
+            Version,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -821,7 +1271,7 @@ impl<'de> Deserialize<'de> for OwnerMemo {
                     type Value = Field;
 
                     fn expecting(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
-                        formatter.write_str("`blind_share` or `lock` or `key_type`")
+                        formatter.write_str("`blind_share` or `lock` or `key_type` or `version`")
                     }
 
                     fn visit_str<E>(self, value: &str) -> core::result::Result<Field, E>
@@ -834,6 +1284,7 @@ impl<'de> Deserialize<'de> for OwnerMemo {
                             "blind_share_bytes" => Ok(Field::BlindShareBytes),
                             "lock" => Ok(Field::Lock),
                             "lock_bytes" => Ok(Field::LockBytes),
+                            "version" => Ok(Field::Version),
                             _ => Err(de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -856,31 +1307,24 @@ impl<'de> Deserialize<'de> for OwnerMemo {
             where
                 V: SeqAccess<'de>,
             {
-                let com_key_type = seq
-                    .next_element::<CompatibleKeyType>()?
+                let key_type = seq
+                    .next_element::<KeyType>()?
                     .ok_or_else(|| de::Error::invalid_length(0, &self))?;
-                let key_type = match com_key_type {
-                    CompatibleKeyType::Old => KeyType::Ed25519,
-                    CompatibleKeyType::New(k) => k,
-                };
-                let com_blind_share = seq
-                    .next_element::<CompatibleBlindShare>()?
+                let blind_share_bytes = seq
+                    .next_element::<CompactByteArray>()?
                     .ok_or_else(|| de::Error::invalid_length(1, &self))?;
-                let blind_share_bytes = match com_blind_share {
-                    CompatibleBlindShare::Old(k) => k.0.to_bytes().to_vec(),
-                    CompatibleBlindShare::New(k) => k,
-                };
-                let com_lock = seq
-                    .next_element::<CompatibleLock>()?
+                let lock_bytes = seq
+                    .next_element::<CompactByteArray>()?
                     .ok_or_else(|| de::Error::invalid_length(2, &self))?;
-                let lock_bytes = match com_lock {
-                    CompatibleLock::Old(k) => k.noah_to_bytes(),
-                    CompatibleLock::New(k) => k,
-                };
+                // the memos encoded before the version was added have three fields, which
+                // only self-describing formats can tell; the zei v0.1 memos are decoded
+                // through `LegacyOwnerMemo`
+                let version = seq.next_element::<OwnerMemoVersion>()?.unwrap_or_default();
                 Ok(OwnerMemo {
                     key_type,
-                    blind_share_bytes: CompactByteArray(blind_share_bytes),
-                    lock_bytes: CompactByteArray(lock_bytes),
+                    blind_share_bytes,
+                    lock_bytes,
+                    version,
                 })
             }
 
@@ -891,6 +1335,7 @@ impl<'de> Deserialize<'de> for OwnerMemo {
                 let mut key_type = None;
                 let mut blind_share_bytes = None;
                 let mut lock_bytes = None;
+                let mut version = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::KeyType => {
@@ -929,6 +1374,12 @@ impl<'de> Deserialize<'de> for OwnerMemo {
                             let tmp = map.next_value::<CompactByteArray>()?;
                             lock_bytes = Some(tmp.noah_to_bytes().to_vec());
                         }
+                        Field::Version => {
+                            if version.is_some() {
+                                return Err(de::Error::duplicate_field("version"));
+                            }
+                            version = Some(map.next_value()?);
+                        }
                     }
                 }
                 let key_type = key_type.unwrap_or(KeyType::Ed25519);
@@ -939,6 +1390,7 @@ impl<'de> Deserialize<'de> for OwnerMemo {
                     key_type,
                     blind_share_bytes: CompactByteArray(blind_share_bytes),
                     lock_bytes: CompactByteArray(lock_bytes),
+                    version: version.unwrap_or_default(),
                 })
             }
         }
@@ -949,6 +1401,7 @@ impl<'de> Deserialize<'de> for OwnerMemo {
             "blind_share_bytes",
             "lock",
             "lock_bytes",
+            "version",
         ];
         deserializer.deserialize_struct("OwnerMemo", FIELDS, OwnerMemoVisitor)
     }
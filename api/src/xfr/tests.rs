@@ -126,6 +126,19 @@ fn do_transfer_tests_single_asset(
     )
     .unwrap();
 
+    // 1.2 one note with an invalid signature fails the batch
+    let mut forged_note = xfr_note.clone();
+    forged_note.multisig.signatures[0] = inkeys_ref[0].sign(b"forged").unwrap();
+    assert_eq!(
+        batch_verify_xfr_notes(
+            &mut prng,
+            params,
+            &[&xfr_note, &forged_note, &xfr_note],
+            &[&policies.to_ref(); 3],
+        ),
+        Err(NoahError::SignatureError)
+    );
+
     // test 2: overflow transfer
     let old_output3: AssetRecord = outputs[3].clone();
     let asset_record = AssetRecordTemplate::with_no_asset_tracing(
@@ -1693,3 +1706,203 @@ mod asset_tracing {
         assert_eq!(v1, v2);
     }
 }
+
+mod owner_memo_versions {
+    use super::*;
+    use crate::parameters::AddressFormat::ED25519;
+    use crate::serialization::{from_bincode, to_bincode};
+    use crate::xfr::{
+        asset_record::{build_blind_asset_record, open_blind_asset_record},
+        structs::{check_memo_size, OwnerMemo, OwnerMemoVersion, OWNER_MEMO_PADDING_BUCKETS},
+    };
+    use noah_crypto::hpke::hpke_open;
+
+    #[test]
+    fn test_hpke_owner_memo() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, ED25519);
+        let other_keypair = KeyPair::sample(&mut prng, ED25519);
+        let asset_type = AssetType::from_identical_byte(3u8);
+
+        let (memo, amount_blinds, asset_type_blind) =
+            OwnerMemo::from_amount_and_asset_type_with_version(
+                &mut prng,
+                100u64,
+                &asset_type,
+                &keypair.pub_key,
+                OwnerMemoVersion::Hpke,
+            )
+            .unwrap();
+        assert_eq!(
+            memo.decrypt_amount_and_asset_type(&keypair).unwrap(),
+            (100u64, asset_type)
+        );
+        assert_eq!(memo.derive_amount_blinds(&keypair).unwrap(), amount_blinds);
+        assert_eq!(
+            memo.derive_asset_type_blind(&keypair).unwrap(),
+            asset_type_blind
+        );
+        assert!(memo.decrypt_amount_and_asset_type(&other_keypair).is_err());
+
+        // the exported keys open the lock with any HPKE implementation
+        let plaintext = hpke_open(
+            &keypair.sec_key.to_hpke_secret_key().unwrap(),
+            b"Noah OwnerMemo Lock",
+            &memo.blind_share_bytes.0,
+            &memo.lock_bytes.0,
+        )
+        .unwrap();
+        assert_eq!(&plaintext[..8], &100u64.to_be_bytes());
+        assert_eq!(&plaintext[8..], &asset_type.0);
+
+        let json = serde_json::to_string(&memo).unwrap();
+        assert_eq!(serde_json::from_str::<OwnerMemo>(&json).unwrap(), memo);
+        let mut bytes = vec![];
+        memo.serialize(&mut Serializer::new(&mut bytes)).unwrap();
+        let mut de = Deserializer::new(&bytes[..]);
+        assert_eq!(OwnerMemo::deserialize(&mut de).unwrap(), memo);
+
+        // HPKE is only for ed25519 keys
+        let secp256k1 = KeyPair::sample(&mut prng, SECP256K1);
+        assert!(OwnerMemo::from_amount_with_version(
+            &mut prng,
+            100u64,
+            &secp256k1.pub_key,
+            OwnerMemoVersion::Hpke,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_legacy_owner_memo_encoding() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, ED25519);
+        let (memo, _) = OwnerMemo::from_amount(&mut prng, 100u64, &keypair.pub_key).unwrap();
+        assert_eq!(memo.version, OwnerMemoVersion::Legacy);
+
+        let json = serde_json::to_string(&memo).unwrap();
+        assert_eq!(serde_json::from_str::<OwnerMemo>(&json).unwrap(), memo);
+        let mut bytes = vec![];
+        memo.serialize(&mut Serializer::new(&mut bytes)).unwrap();
+        let mut de = Deserializer::new(&bytes[..]);
+        assert_eq!(OwnerMemo::deserialize(&mut de).unwrap(), memo);
+        assert_eq!(memo.decrypt_amount(&keypair).unwrap(), 100u64);
+
+        // the memos encoded before the version was added are legacy memos
+        let mut value = serde_json::to_value(&memo).unwrap();
+        value.as_object_mut().unwrap().remove("version");
+        assert_eq!(serde_json::from_value::<OwnerMemo>(value).unwrap(), memo);
+        let mut bytes = vec![];
+        (&memo.key_type, &memo.blind_share_bytes, &memo.lock_bytes)
+            .serialize(&mut Serializer::new(&mut bytes))
+            .unwrap();
+        let mut de = Deserializer::new(&bytes[..]);
+        assert_eq!(OwnerMemo::deserialize(&mut de).unwrap(), memo);
+    }
+
+    #[test]
+    fn test_legacy_owner_memos_bincode() {
+        let mut prng = test_rng();
+        let sender = KeyPair::sample(&mut prng, ED25519);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let asset_type = AssetType::from_identical_byte(3u8);
+        let record_type = AssetRecordType::ConfidentialAmount_ConfidentialAssetType;
+        let input = AssetRecordTemplate::with_no_asset_tracing(
+            100u64,
+            asset_type,
+            record_type,
+            sender.pub_key,
+        );
+        let outputs = [40u64, 60u64].map(|amount| {
+            AssetRecordTemplate::with_no_asset_tracing(
+                amount,
+                asset_type,
+                record_type,
+                receiver.pub_key,
+            )
+        });
+        let (note, _, _) = create_xfr(&mut prng, &[input], &outputs, &[&sender]);
+        let body = note.body;
+        assert_eq!(body.owners_memos.len(), 2);
+        assert!(body
+            .owners_memos
+            .iter()
+            .all(|memo| memo.as_ref().unwrap().version == OwnerMemoVersion::Legacy));
+
+        // each memo is read up to its own end, and not into the next one
+        let bytes = to_bincode(&body).unwrap();
+        let decoded = from_bincode::<XfrBody>(&bytes).unwrap();
+        assert_eq!(decoded, body);
+        for (record, memo) in decoded.outputs.iter().zip(decoded.owners_memos.iter()) {
+            let oar = open_blind_asset_record(record, memo, &receiver).unwrap();
+            assert_eq!(oar.asset_type, asset_type);
+        }
+    }
+
+    #[test]
+    fn test_padded_owner_memo() {
+        let mut prng = test_rng();
+        let pc_gens = PedersenCommitmentRistretto::default();
+        let keypair = KeyPair::sample(&mut prng, ED25519);
+        let asset_type = AssetType::from_identical_byte(3u8);
+        let bucket = OWNER_MEMO_PADDING_BUCKETS[0];
+
+        let mut sizes = vec![];
+        for record_type in [
+            AssetRecordType::ConfidentialAmount_NonConfidentialAssetType,
+            AssetRecordType::NonConfidentialAmount_ConfidentialAssetType,
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+        ] {
+            let template = AssetRecordTemplate::with_no_asset_tracing(
+                100u64,
+                asset_type,
+                record_type,
+                keypair.pub_key,
+            )
+            .with_owner_memo_padding(bucket)
+            .unwrap();
+            let (bar, _, memo) = build_blind_asset_record(&mut prng, &pc_gens, &template, vec![]);
+            let mut memo = memo.unwrap();
+            assert_eq!(memo.version, OwnerMemoVersion::PaddedHpke(bucket));
+            sizes.push(memo.lock_bytes.0.len());
+
+            let oar = open_blind_asset_record(&bar, &Some(memo.clone()), &keypair).unwrap();
+            assert_eq!(oar.amount, 100u64);
+            assert_eq!(oar.asset_type, asset_type);
+            check_memo_size(&bar, &Some(memo.clone())).unwrap();
+
+            let json = serde_json::to_string(&memo).unwrap();
+            assert_eq!(serde_json::from_str::<OwnerMemo>(&json).unwrap(), memo);
+            let mut bytes = vec![];
+            memo.serialize(&mut Serializer::new(&mut bytes)).unwrap();
+            let mut de = Deserializer::new(&bytes[..]);
+            assert_eq!(OwnerMemo::deserialize(&mut de).unwrap(), memo);
+
+            // the verifiers only accept the allowed sizes
+            memo.version = OwnerMemoVersion::PaddedHpke(bucket + 1);
+            assert!(check_memo_size(&bar, &Some(memo.clone())).is_err());
+            memo.version = OwnerMemoVersion::PaddedHpke(bucket);
+            memo.lock_bytes.0.pop();
+            assert!(check_memo_size(&bar, &Some(memo)).is_err());
+        }
+        // the size of the memo does not depend on the record type
+        assert!(sizes.iter().all(|size| *size == sizes[0]));
+
+        // only the allowed sizes and ed25519 addresses can be padded
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            100u64,
+            asset_type,
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+            keypair.pub_key,
+        );
+        assert!(template.with_owner_memo_padding(bucket + 1).is_err());
+        let secp256k1 = KeyPair::sample(&mut prng, SECP256K1);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            100u64,
+            asset_type,
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+            secp256k1.pub_key,
+        );
+        assert!(template.with_owner_memo_padding(bucket).is_err());
+    }
+}
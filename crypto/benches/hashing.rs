@@ -1,5 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use noah_algebra::ed25519::{Ed25519Fq, Ed25519Point};
+use noah_algebra::goldilocks::GoldilocksScalar;
 use noah_algebra::prelude::{test_rng, Scalar};
 use noah_algebra::secp256k1::{SECP256K1Fq, SECP256K1G1};
 use noah_crypto::hashing_to_the_curve::ed25519::elligator::Ed25519ElligatorParameters;
@@ -11,6 +12,7 @@ use noah_crypto::hashing_to_the_curve::models::sw::SWMap;
 use noah_crypto::hashing_to_the_curve::secp256k1::sswu::Secp256k1SSWUParameters;
 use noah_crypto::hashing_to_the_curve::secp256k1::sw::Secp256k1SWParameters;
 use noah_crypto::hashing_to_the_curve::traits::HashingToCurve;
+use noah_crypto::rescue_prime::{rescue_prime_goldilocks, RESCUE_PRIME_GOLDILOCKS_WIDTH};
 
 fn bench_ed25519_elligator(c: &mut Criterion) {
     let mut single_group = c.benchmark_group("ed25519_elligator");
@@ -82,12 +84,30 @@ fn bench_secp256k1_sw(c: &mut Criterion) {
     single_group.finish();
 }
 
+fn bench_rescue_prime_goldilocks(c: &mut Criterion) {
+    let params = rescue_prime_goldilocks();
+    let mut rng = test_rng();
+    let mut state: Vec<GoldilocksScalar> = (0..RESCUE_PRIME_GOLDILOCKS_WIDTH)
+        .map(|_| GoldilocksScalar::random(&mut rng))
+        .collect();
+
+    let mut single_group = c.benchmark_group("rescue_prime_goldilocks");
+    single_group.bench_function(
+        "Rescue-Prime permutation over Goldilocks".to_string(),
+        |b| {
+            b.iter(|| params.permute(&mut state));
+        },
+    );
+    single_group.finish();
+}
+
 criterion_group!(
     benches,
     bench_ed25519_elligator,
     bench_ed25519_sswu_wb,
     bench_ed25519_sw,
     bench_secp256k1_sswu_wb,
-    bench_secp256k1_sw
+    bench_secp256k1_sw,
+    bench_rescue_prime_goldilocks
 );
 criterion_main!(benches);
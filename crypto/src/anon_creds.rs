@@ -511,10 +511,11 @@ pub(crate) fn verify_pok<P: Pairing>(
     }
     let p = P::G2::multi_exp(scalars.as_slice(), elems.as_slice());
 
-    let lhs = P::pairing(&cm.0.sigma1, &p);
-    let rhs = P::pairing(&cm.0.sigma2.mul(challenge), &ipk.gen2);
+    // e(sigma1, p) * e(-c * sigma2, g2) = 1
+    let sigma2_times_challenge = cm.0.sigma2.mul(challenge).neg();
+    let product = P::multi_pairing(&[(&cm.0.sigma1, &p), (&sigma2_times_challenge, &ipk.gen2)]);
 
-    if lhs == rhs {
+    if product == P::Gt::get_identity() {
         Ok(())
     } else {
         Err(CryptoError::IdentityRevealVerifyError)
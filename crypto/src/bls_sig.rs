@@ -0,0 +1,291 @@
+use crate::errors::{CryptoError, Result};
+use noah_algebra::{
+    bls12_381::{BLSGt, BLSPairingEngine, BLSScalar, BLSG1, BLSG2},
+    prelude::*,
+    traits::Pairing,
+};
+use sha2::{Digest, Sha512};
+
+/// The domain separator of the hash of the messages.
+const BLS_SIG_DST: &[u8] = b"Noah BLS12-381 Signature";
+/// The domain separator of the hash of the proofs of possession.
+const BLS_POP_DST: &[u8] = b"Noah BLS12-381 Proof of Possession";
+
+/// The BLS secret key.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlsSecretKey(pub(crate) BLSScalar);
+
+/// The BLS public key, in G2.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlsPublicKey(pub BLSG2);
+
+/// The BLS signature, in G1, which can be a single signature or the aggregation of signatures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlsSignature(pub BLSG1);
+
+impl NoahFromToBytes for BlsPublicKey {
+    fn noah_to_bytes(&self) -> Vec<u8> {
+        self.0.to_compressed_bytes()
+    }
+
+    fn noah_from_bytes(bytes: &[u8]) -> core::result::Result<Self, AlgebraError> {
+        Ok(BlsPublicKey(BLSG2::from_compressed_bytes(bytes)?))
+    }
+}
+
+impl NoahFromToBytes for BlsSignature {
+    fn noah_to_bytes(&self) -> Vec<u8> {
+        self.0.to_compressed_bytes()
+    }
+
+    fn noah_from_bytes(bytes: &[u8]) -> core::result::Result<Self, AlgebraError> {
+        Ok(BlsSignature(BLSG1::from_compressed_bytes(bytes)?))
+    }
+}
+
+/// The factor that blinds a message, kept by the requester of a blind signature.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlsBlindingFactor(pub(crate) BLSScalar);
+
+/// A blinded message, in G1, which the signer signs without learning the message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlsBlindedMessage(pub BLSG1);
+
+/// The signature of a blinded message, which the requester unblinds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlsBlindedSignature(pub BLSG1);
+
+/// Return a BLS key pair as `(sk, pk = sk * G2)`.
+pub fn bls_key_gen<R: CryptoRng + RngCore>(prng: &mut R) -> (BlsSecretKey, BlsPublicKey) {
+    let sk = BLSScalar::random(prng);
+    let pk = BLSG2::get_base().mul(&sk);
+    (BlsSecretKey(sk), BlsPublicKey(pk))
+}
+
+/// Return the public key of the secret key.
+pub fn bls_public_key(sk: &BlsSecretKey) -> BlsPublicKey {
+    BlsPublicKey(BLSG2::get_base().mul(&sk.0))
+}
+
+/// Sign the message.
+pub fn bls_sign(sk: &BlsSecretKey, message: &[u8]) -> BlsSignature {
+    BlsSignature(hash_to_g1(BLS_SIG_DST, message).mul(&sk.0))
+}
+
+/// Verify the signature of the message.
+pub fn bls_verify(pk: &BlsPublicKey, message: &[u8], signature: &BlsSignature) -> Result<()> {
+    check_pairing(&hash_to_g1(BLS_SIG_DST, message), &pk.0, &signature.0)
+}
+
+/// Blind the message as `r * H(m)` for a random `r`, which is uniform in G1 whatever the message.
+pub fn bls_blind<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    message: &[u8],
+) -> (BlsBlindingFactor, BlsBlindedMessage) {
+    let r = BLSScalar::random(prng);
+    let blinded = hash_to_g1(BLS_SIG_DST, message).mul(&r);
+    (BlsBlindingFactor(r), BlsBlindedMessage(blinded))
+}
+
+/// Sign a blinded message.
+pub fn bls_sign_blinded(sk: &BlsSecretKey, blinded: &BlsBlindedMessage) -> BlsBlindedSignature {
+    BlsBlindedSignature(blinded.0.mul(&sk.0))
+}
+
+/// Unblind the signature of a blinded message into the signature of the message,
+/// which is checked under the public key of the signer.
+pub fn bls_unblind(
+    pk: &BlsPublicKey,
+    message: &[u8],
+    factor: &BlsBlindingFactor,
+    blinded_signature: &BlsBlindedSignature,
+) -> Result<BlsSignature> {
+    let signature = BlsSignature(blinded_signature.0.mul(&factor.0.inv()?));
+    bls_verify(pk, message, &signature)?;
+    Ok(signature)
+}
+
+/// Prove the possession of the secret key, which must be checked before the public key
+/// is used in `bls_fast_aggregate_verify`, against the rogue-key attacks.
+pub fn bls_prove_possession(sk: &BlsSecretKey) -> BlsSignature {
+    let pk = bls_public_key(sk);
+    BlsSignature(hash_to_g1(BLS_POP_DST, &pk.noah_to_bytes()).mul(&sk.0))
+}
+
+/// Verify the proof of possession of the public key.
+pub fn bls_verify_possession(pk: &BlsPublicKey, proof: &BlsSignature) -> Result<()> {
+    check_pairing(
+        &hash_to_g1(BLS_POP_DST, &pk.noah_to_bytes()),
+        &pk.0,
+        &proof.0,
+    )
+}
+
+/// Aggregate the signatures into one signature.
+pub fn bls_aggregate(signatures: &[BlsSignature]) -> Result<BlsSignature> {
+    if signatures.is_empty() {
+        return Err(CryptoError::ParameterError);
+    }
+    Ok(BlsSignature(
+        signatures
+            .iter()
+            .fold(BLSG1::get_identity(), |acc, sig| acc.add(&sig.0)),
+    ))
+}
+
+/// Verify the aggregated signature of the same message under the public keys,
+/// with two pairings however many the signers are.
+/// All the public keys must come with a verified proof of possession.
+pub fn bls_fast_aggregate_verify(
+    pks: &[BlsPublicKey],
+    message: &[u8],
+    signature: &BlsSignature,
+) -> Result<()> {
+    if pks.is_empty() {
+        return Err(CryptoError::ParameterError);
+    }
+    let pk = pks
+        .iter()
+        .fold(BLSG2::get_identity(), |acc, pk| acc.add(&pk.0));
+    check_pairing(&hash_to_g1(BLS_SIG_DST, message), &pk, &signature.0)
+}
+
+/// Verify the aggregated signature of distinct messages, one under each public key.
+pub fn bls_aggregate_verify(
+    pks: &[BlsPublicKey],
+    messages: &[&[u8]],
+    signature: &BlsSignature,
+) -> Result<()> {
+    if pks.is_empty() || pks.len() != messages.len() {
+        return Err(CryptoError::ParameterError);
+    }
+    // The messages must be distinct, since no proof of possession is assumed.
+    let mut sorted = messages.to_vec();
+    sorted.sort_unstable();
+    if sorted.windows(2).any(|w| w[0] == w[1]) {
+        return Err(CryptoError::ParameterError);
+    }
+
+    let hashes = messages
+        .iter()
+        .map(|m| hash_to_g1(BLS_SIG_DST, m))
+        .collect_vec();
+    let neg_sig = signature.0.neg();
+    let base = BLSG2::get_base();
+    let mut pairs = hashes.iter().zip(pks.iter().map(|pk| &pk.0)).collect_vec();
+    pairs.push((&neg_sig, &base));
+    if BLSPairingEngine::multi_pairing(&pairs) == BLSGt::get_identity() {
+        Ok(())
+    } else {
+        Err(CryptoError::SignatureError)
+    }
+}
+
+/// Check that `e(h, pk) = e(sig, G2)`.
+fn check_pairing(h: &BLSG1, pk: &BLSG2, sig: &BLSG1) -> Result<()> {
+    if pk == &BLSG2::get_identity() {
+        return Err(CryptoError::SignatureError);
+    }
+    let neg_sig = sig.neg();
+    let base = BLSG2::get_base();
+    if BLSPairingEngine::multi_pairing(&[(h, pk), (&neg_sig, &base)]) == BLSGt::get_identity() {
+        Ok(())
+    } else {
+        Err(CryptoError::SignatureError)
+    }
+}
+
+fn hash_to_g1(dst: &[u8], message: &[u8]) -> BLSG1 {
+    let mut hasher = Sha512::new();
+    hasher.update(dst);
+    hasher.update(message);
+    BLSG1::from_hash(hasher)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls_sig::{
+        bls_aggregate, bls_aggregate_verify, bls_blind, bls_fast_aggregate_verify, bls_key_gen,
+        bls_prove_possession, bls_sign, bls_sign_blinded, bls_unblind, bls_verify,
+        bls_verify_possession, BlsPublicKey, BlsSignature,
+    };
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_bls_sig() {
+        let mut prng = test_rng();
+        let (sk, pk) = bls_key_gen(&mut prng);
+        let (_, other_pk) = bls_key_gen(&mut prng);
+
+        let sig = bls_sign(&sk, b"message");
+        assert!(bls_verify(&pk, b"message", &sig).is_ok());
+        assert!(bls_verify(&pk, b"other message", &sig).is_err());
+        assert!(bls_verify(&other_pk, b"message", &sig).is_err());
+
+        let proof = bls_prove_possession(&sk);
+        assert!(bls_verify_possession(&pk, &proof).is_ok());
+        assert!(bls_verify_possession(&other_pk, &proof).is_err());
+        // a signature is not a proof of possession
+        let sig_of_pk = bls_sign(&sk, &pk.noah_to_bytes());
+        assert!(bls_verify_possession(&pk, &sig_of_pk).is_err());
+
+        let pk_bytes = pk.noah_to_bytes();
+        assert_eq!(BlsPublicKey::noah_from_bytes(&pk_bytes).unwrap(), pk);
+        let sig_bytes = sig.noah_to_bytes();
+        assert_eq!(BlsSignature::noah_from_bytes(&sig_bytes).unwrap(), sig);
+    }
+
+    #[test]
+    fn test_bls_aggregate() {
+        let mut prng = test_rng();
+        let keys = (0..4).map(|_| bls_key_gen(&mut prng)).collect_vec();
+        let pks = keys.iter().map(|(_, pk)| *pk).collect_vec();
+
+        let sigs = keys
+            .iter()
+            .map(|(sk, _)| bls_sign(sk, b"message"))
+            .collect_vec();
+        let agg = bls_aggregate(&sigs).unwrap();
+        assert!(bls_fast_aggregate_verify(&pks, b"message", &agg).is_ok());
+        assert!(bls_fast_aggregate_verify(&pks[1..], b"message", &agg).is_err());
+        assert!(bls_fast_aggregate_verify(&pks, b"other message", &agg).is_err());
+        assert!(bls_aggregate(&[]).is_err());
+
+        let messages: Vec<&[u8]> = vec![b"m0", b"m1", b"m2", b"m3"];
+        let sigs = keys
+            .iter()
+            .zip(messages.iter())
+            .map(|((sk, _), m)| bls_sign(sk, m))
+            .collect_vec();
+        let agg = bls_aggregate(&sigs).unwrap();
+        assert!(bls_aggregate_verify(&pks, &messages, &agg).is_ok());
+        let mut swapped = messages.clone();
+        swapped.swap(0, 1);
+        assert!(bls_aggregate_verify(&pks, &swapped, &agg).is_err());
+        let repeated: Vec<&[u8]> = vec![b"m0", b"m0", b"m2", b"m3"];
+        assert!(bls_aggregate_verify(&pks, &repeated, &agg).is_err());
+    }
+
+    #[test]
+    fn test_bls_blind_sig() {
+        let mut prng = test_rng();
+        let (sk, pk) = bls_key_gen(&mut prng);
+        let (_, other_pk) = bls_key_gen(&mut prng);
+
+        let (factor, blinded) = bls_blind(&mut prng, b"message");
+        let (_, other_blinded) = bls_blind(&mut prng, b"message");
+        // the blinding of the same message is different each time
+        assert_ne!(blinded, other_blinded);
+
+        let blinded_sig = bls_sign_blinded(&sk, &blinded);
+        let sig = bls_unblind(&pk, b"message", &factor, &blinded_sig).unwrap();
+        // the unblinded signature is the ordinary signature of the message
+        assert_eq!(sig, bls_sign(&sk, b"message"));
+        assert!(bls_verify(&pk, b"message", &sig).is_ok());
+
+        assert!(bls_unblind(&pk, b"other message", &factor, &blinded_sig).is_err());
+        assert!(bls_unblind(&other_pk, b"message", &factor, &blinded_sig).is_err());
+        let other_sig = bls_sign_blinded(&sk, &other_blinded);
+        assert!(bls_unblind(&pk, b"message", &factor, &other_sig).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::errors::{CryptoError, Result};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305,
+};
+use hkdf::Hkdf;
+use noah_algebra::prelude::*;
+use sha2::Sha256;
+
+/// The number of bytes of the nonce of XChaCha20Poly1305.
+pub const COMMITTING_AEAD_NONCE_LEN: usize = 24;
+/// The number of bytes of the key commitment.
+pub const COMMITTING_AEAD_COMMITMENT_LEN: usize = 32;
+/// The number of bytes of the authentication tag of XChaCha20Poly1305.
+pub const COMMITTING_AEAD_TAG_LEN: usize = 16;
+/// The number of bytes that the encryption adds to the plaintext.
+pub const COMMITTING_AEAD_OVERHEAD: usize =
+    COMMITTING_AEAD_NONCE_LEN + COMMITTING_AEAD_COMMITMENT_LEN + COMMITTING_AEAD_TAG_LEN;
+
+/// The info of the derivation of the encryption key.
+const COMMITTING_AEAD_KEY_INFO: &[u8] = b"Noah Committing AEAD Key";
+/// The info of the derivation of the key commitment.
+const COMMITTING_AEAD_COMMITMENT_INFO: &[u8] = b"Noah Committing AEAD Commitment";
+
+/// Encrypt the plaintext with XChaCha20Poly1305 under a key derived from the key and a random
+/// nonce, and return the nonce, the commitment to the key, and the ciphertext.
+///
+/// Poly1305 alone does not commit to the key, so a ciphertext could be crafted to decrypt
+/// under several keys. The commitment, derived from the same key and nonce, is checked before
+/// decryption, so that a ciphertext decrypts under a single key.
+pub fn committing_encrypt<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    key: &[u8; 32],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>> {
+    let mut nonce = [0u8; COMMITTING_AEAD_NONCE_LEN];
+    prng.fill_bytes(&mut nonce);
+    let (enc_key, commitment) = derive_keys(key, &nonce)?;
+
+    let cipher =
+        XChaCha20Poly1305::new_from_slice(&enc_key).map_err(|_| CryptoError::ParameterError)?;
+    let mut ctext = cipher
+        .encrypt(
+            (&nonce).into(),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| CryptoError::ParameterError)?;
+
+    let mut res = nonce.to_vec();
+    res.extend_from_slice(&commitment);
+    res.append(&mut ctext);
+    Ok(res)
+}
+
+/// Decrypt the output of `committing_encrypt`, rejecting it if it does not commit to the key.
+pub fn committing_decrypt(key: &[u8; 32], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    if ciphertext.len() < COMMITTING_AEAD_OVERHEAD {
+        return Err(CryptoError::DecryptionError);
+    }
+    let (nonce, rest) = ciphertext.split_at(COMMITTING_AEAD_NONCE_LEN);
+    let (commitment, ctext) = rest.split_at(COMMITTING_AEAD_COMMITMENT_LEN);
+
+    let mut nonce_bytes = [0u8; COMMITTING_AEAD_NONCE_LEN];
+    nonce_bytes.copy_from_slice(nonce);
+    let (enc_key, expected) = derive_keys(key, &nonce_bytes)?;
+
+    // compare in constant time, as the commitment is a function of the key
+    let diff = expected
+        .iter()
+        .zip(commitment.iter())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    if diff != 0 {
+        return Err(CryptoError::DecryptionError);
+    }
+
+    let cipher =
+        XChaCha20Poly1305::new_from_slice(&enc_key).map_err(|_| CryptoError::ParameterError)?;
+    cipher
+        .decrypt((&nonce_bytes).into(), Payload { msg: ctext, aad })
+        .map_err(|_| CryptoError::DecryptionError)
+}
+
+/// Derive the encryption key and the key commitment from the key and the nonce.
+fn derive_keys(
+    key: &[u8; 32],
+    nonce: &[u8; COMMITTING_AEAD_NONCE_LEN],
+) -> Result<([u8; 32], [u8; COMMITTING_AEAD_COMMITMENT_LEN])> {
+    let hkdf = Hkdf::<Sha256>::new(Some(nonce), key);
+
+    let mut enc_key = [0u8; 32];
+    hkdf.expand(COMMITTING_AEAD_KEY_INFO, &mut enc_key)
+        .map_err(|_| CryptoError::ParameterError)?;
+    let mut commitment = [0u8; COMMITTING_AEAD_COMMITMENT_LEN];
+    hkdf.expand(COMMITTING_AEAD_COMMITMENT_INFO, &mut commitment)
+        .map_err(|_| CryptoError::ParameterError)?;
+    Ok((enc_key, commitment))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::committing_aead::{
+        committing_decrypt, committing_encrypt, COMMITTING_AEAD_NONCE_LEN, COMMITTING_AEAD_OVERHEAD,
+    };
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_committing_aead() {
+        let mut prng = test_rng();
+        let mut key = [0u8; 32];
+        prng.fill_bytes(&mut key);
+        let msg = b"this is a memo";
+
+        let ctext = committing_encrypt(&mut prng, &key, b"aad", msg).unwrap();
+        assert_eq!(ctext.len(), msg.len() + COMMITTING_AEAD_OVERHEAD);
+        assert_eq!(committing_decrypt(&key, b"aad", &ctext).unwrap(), msg);
+
+        // the nonce is random
+        let other = committing_encrypt(&mut prng, &key, b"aad", msg).unwrap();
+        assert_ne!(ctext, other);
+
+        // the key, the associated data, the commitment, and the ciphertext are all checked
+        let mut other_key = key;
+        other_key[0] ^= 1;
+        assert!(committing_decrypt(&other_key, b"aad", &ctext).is_err());
+        assert!(committing_decrypt(&key, b"other aad", &ctext).is_err());
+        for i in [0, COMMITTING_AEAD_NONCE_LEN, ctext.len() - 1] {
+            let mut tampered = ctext.clone();
+            tampered[i] ^= 1;
+            assert!(committing_decrypt(&key, b"aad", &tampered).is_err());
+        }
+        assert!(committing_decrypt(&key, b"aad", &ctext[..COMMITTING_AEAD_OVERHEAD - 1]).is_err());
+    }
+}
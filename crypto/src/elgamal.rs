@@ -1,6 +1,7 @@
 use crate::errors::{CryptoError, Result};
 use noah_algebra::ristretto::RistrettoPoint;
 use noah_algebra::{
+    fixed_base::mul_base,
     hash::{Hash, Hasher},
     prelude::*,
 };
@@ -58,9 +59,8 @@ pub fn elgamal_encrypt<G: Group>(
     r: &G::ScalarType,
     pub_key: &ElGamalEncKey<G>,
 ) -> ElGamalCiphertext<G> {
-    let base = G::get_base();
-    let e1 = base.mul(r);
-    let e2 = base.mul(m).add(&(pub_key.0).mul(r));
+    let e1 = mul_base::<G>(r);
+    let e2 = mul_base::<G>(m).add(&(pub_key.0).mul(r));
 
     ElGamalCiphertext::<G> { e1, e2 }
 }
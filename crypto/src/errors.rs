@@ -15,6 +15,7 @@ pub enum CryptoError {
     ElGamalVerificationError,
     ZKProofVerificationError,
     ZKProofBatchVerificationError,
+    DecryptionError,
     Algebra(AlgebraError),
     R1CS(R1CSError),
     Bulletproofs(ProofError),
@@ -33,6 +34,7 @@ impl fmt::Display for CryptoError {
             ElGamalVerificationError => "ElGamal Ciphertext not valid for proposed scalar message",
             ZKProofVerificationError => "Invalid proof",
             ZKProofBatchVerificationError => "Batch proof instance contains an error",
+            DecryptionError => "Decryption failed",
             Algebra(e) => Box::leak(format!("Algebra: {}", e).into_boxed_str()),
             R1CS(e) => Box::leak(format!("R1CS: {}", e).into_boxed_str()),
             Bulletproofs(e) => Box::leak(format!("Bulletproofs: {}", e).into_boxed_str()),
@@ -0,0 +1,190 @@
+use crate::field_simulation::{SimFr, SimFrMul, SimFrParams, SimReducibility};
+use noah_algebra::prelude::*;
+use num_bigint::{BigUint, RandBigInt};
+use num_integer::Integer;
+
+/// The largest product of the numbers of additions accepted by `enforce_zero`.
+const MAX_PROD_OF_NUM_OF_ADDITIONS: u32 = 31;
+
+/// The product of the numbers of additions added by subtracting a simulated field element.
+const PROD_OF_NUM_OF_ADDITIONS_PER_SUB: u32 = 12;
+
+/// An operation on the accumulator of a random sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimFrOp {
+    /// Add the product of the two operands to the accumulator.
+    AddMul,
+    /// Subtract the operand from the accumulator.
+    Sub,
+}
+
+/// A step of a random sequence of operations on simulated field elements.
+#[derive(Clone)]
+pub struct SimFrTestVector<F: Scalar, P: SimFrParams<F>> {
+    /// The operation.
+    pub op: SimFrOp,
+    /// The operands, two for `AddMul` and one for `Sub`.
+    pub operands: Vec<SimFr<F, P>>,
+    /// The value of the accumulator after this step, reduced modulo the simulated field.
+    pub expected: BigUint,
+}
+
+/// Sample a random simulated field element with at most four additions over the normal form,
+/// which is either in the normal form, a witness `x + p`, or the difference of two such elements.
+pub fn random_sim_fr<F: Scalar, P: SimFrParams<F>, R: CryptoRng + RngCore>(
+    prng: &mut R,
+) -> SimFr<F, P> {
+    match prng.gen_range(0..3) {
+        0 => random_reduced_sim_fr(prng),
+        1 => random_witness_sim_fr(prng),
+        _ => {
+            let lhs = if prng.gen() {
+                random_reduced_sim_fr::<F, P, R>(prng)
+            } else {
+                random_witness_sim_fr::<F, P, R>(prng)
+            };
+            let rhs = if prng.gen() {
+                random_reduced_sim_fr::<F, P, R>(prng)
+            } else {
+                random_witness_sim_fr::<F, P, R>(prng)
+            };
+            &lhs - &rhs
+        }
+    }
+}
+
+fn random_reduced_sim_fr<F: Scalar, P: SimFrParams<F>, R: CryptoRng + RngCore>(
+    prng: &mut R,
+) -> SimFr<F, P> {
+    let r_biguint = P::scalar_field_in_biguint();
+    SimFr::from(&prng.gen_biguint_range(&BigUint::zero(), &r_biguint))
+}
+
+fn random_witness_sim_fr<F: Scalar, P: SimFrParams<F>, R: CryptoRng + RngCore>(
+    prng: &mut R,
+) -> SimFr<F, P> {
+    let r_biguint = P::scalar_field_in_biguint();
+    let x = prng.gen_biguint_range(&BigUint::zero(), &r_biguint);
+    let mut res = SimFr::<F, P>::from(&(x + &r_biguint));
+    res.num_of_additions_over_normal_form = SimReducibility::AtMostReducibleByOne;
+    res
+}
+
+/// Generate a random sequence of at most `max_len` operations, as long as the accumulator
+/// minus its reduced value can still be enforced to be zero.
+pub fn random_sim_fr_test_vectors<F: Scalar, P: SimFrParams<F>, R: CryptoRng + RngCore>(
+    prng: &mut R,
+    max_len: usize,
+) -> Vec<SimFrTestVector<F, P>> {
+    let r_biguint = P::scalar_field_in_biguint();
+    let mut budget = MAX_PROD_OF_NUM_OF_ADDITIONS - PROD_OF_NUM_OF_ADDITIONS_PER_SUB;
+    let mut expected = BigUint::zero();
+    let mut vectors = Vec::new();
+
+    while vectors.len() < max_len {
+        if prng.gen() {
+            let lhs = random_sim_fr::<F, P, R>(prng);
+            let rhs = random_sim_fr::<F, P, R>(prng);
+            let cost = (lhs.num_of_additions() + BigUint::one())
+                * (rhs.num_of_additions() + BigUint::one());
+            if cost > BigUint::from(budget) {
+                break;
+            }
+            budget -= u32::try_from(cost).unwrap();
+
+            expected = (expected + &lhs.val * &rhs.val) % &r_biguint;
+            vectors.push(SimFrTestVector {
+                op: SimFrOp::AddMul,
+                operands: vec![lhs, rhs],
+                expected: expected.clone(),
+            });
+        } else {
+            if budget < PROD_OF_NUM_OF_ADDITIONS_PER_SUB {
+                break;
+            }
+            budget -= PROD_OF_NUM_OF_ADDITIONS_PER_SUB;
+
+            let operand = random_sim_fr::<F, P, R>(prng);
+            let (_, operand_reduced) = operand.val.div_rem(&r_biguint);
+            expected = (expected + &r_biguint - operand_reduced) % &r_biguint;
+            vectors.push(SimFrTestVector {
+                op: SimFrOp::Sub,
+                operands: vec![operand],
+                expected: expected.clone(),
+            });
+        }
+    }
+    vectors
+}
+
+/// Replay the sequence on the simulated arithmetic, asserting after each step that the limbs
+/// are within their tracked bounds and that the accumulator agrees with the `BigUint` reference,
+/// and at the end that the accumulator minus its reduced value is enforced to be zero.
+pub fn check_sim_fr_test_vectors<F: Scalar, P: SimFrParams<F>>(vectors: &[SimFrTestVector<F, P>]) {
+    let r_biguint = P::scalar_field_in_biguint();
+    let mut acc = SimFrMul::<F, P>::default();
+
+    for (i, vector) in vectors.iter().enumerate() {
+        for operand in vector.operands.iter() {
+            operand.assert_limb_bounds();
+        }
+        acc = match vector.op {
+            SimFrOp::AddMul => &acc + &(&vector.operands[0] * &vector.operands[1]),
+            SimFrOp::Sub => &acc - &vector.operands[0],
+        };
+
+        acc.assert_limb_bounds();
+        let (_, acc_reduced) = acc.val.div_rem(&r_biguint);
+        assert_eq!(
+            acc_reduced, vector.expected,
+            "the simulated arithmetic diverges from the reference at step {}",
+            i
+        );
+    }
+
+    let expected = vectors
+        .last()
+        .map(|vector| vector.expected.clone())
+        .unwrap_or_default();
+    let zero = &acc - &SimFr::<F, P>::from(&expected);
+    zero.assert_limb_bounds();
+    assert!(zero.can_enforce_zero());
+    zero.enforce_zero();
+}
+
+#[cfg(test)]
+mod test {
+    use crate::field_simulation::{
+        check_sim_fr_test_vectors, random_sim_fr, random_sim_fr_test_vectors, SimFr, SimFrParams,
+        SimFrParamsBLSRistretto, SimFrParamsBLSSecq256k1, SimFrParamsBLSZorro,
+        SimFrParamsBN254Ristretto, SimFrParamsBN254Secq256k1, SimFrParamsBN254Zorro,
+    };
+    use noah_algebra::{bls12_381::BLSScalar, bn254::BN254Scalar, prelude::*};
+    use num_bigint::BigUint;
+
+    fn fuzz_sim_fr<F: Scalar, P: SimFrParams<F>>() {
+        let mut prng = test_rng();
+        for _ in 0..200 {
+            let vectors = random_sim_fr_test_vectors::<F, P, _>(&mut prng, 8);
+            check_sim_fr_test_vectors(&vectors);
+        }
+
+        // a carry moved into a limb beyond its bound is detected
+        let mut a: SimFr<F, P> = random_sim_fr(&mut prng);
+        assert!(a.check_limb_bounds());
+        let carry = a.num_of_additions() + BigUint::one();
+        a.limbs[0] = a.limbs[0].add(&F::from(&(&carry << P::BIT_PER_LIMB)));
+        a.limbs[1] = a.limbs[1].sub(&F::from(&carry));
+        assert!(!a.check_limb_bounds());
+    }
+
+    #[test]
+    fn test_fuzz_sim_fr() {
+        fuzz_sim_fr::<BN254Scalar, SimFrParamsBN254Ristretto>();
+        fuzz_sim_fr::<BN254Scalar, SimFrParamsBN254Secq256k1>();
+        fuzz_sim_fr::<BN254Scalar, SimFrParamsBN254Zorro>();
+        fuzz_sim_fr::<BLSScalar, SimFrParamsBLSRistretto>();
+        fuzz_sim_fr::<BLSScalar, SimFrParamsBLSSecq256k1>();
+        fuzz_sim_fr::<BLSScalar, SimFrParamsBLSZorro>();
+    }
+}
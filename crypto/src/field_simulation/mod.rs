@@ -8,6 +8,11 @@ pub use bls12_381::*;
 mod bn254;
 pub use bn254::*;
 
+#[cfg(any(test, feature = "fuzzing"))]
+mod fuzz;
+#[cfg(any(test, feature = "fuzzing"))]
+pub use fuzz::*;
+
 /// The trait for parameters for field simulation.
 pub trait SimFrParams<F: Scalar>: Clone + Default {
     /// The number of limbs in the simulated field element representation.
@@ -107,6 +112,7 @@ impl<F: Scalar, P: SimFrParams<F>> Sub<&SimFr<F, P>> for &SimFr<F, P> {
         res.num_of_additions_over_normal_form = SimReducibility::Others(
             BigUint::from(&self.num_of_additions_over_normal_form) + BigUint::from(3u32),
         );
+        debug_assert!(res.check_limb_bounds());
 
         res
     }
@@ -127,6 +133,7 @@ impl<F: Scalar, P: SimFrParams<F>> Mul<&SimFr<F, P>> for &SimFr<F, P> {
         mul_res.prod_of_num_of_additions = BigUint::from(&self.num_of_additions_over_normal_form)
             .add(&BigUint::one())
             .mul(&BigUint::from(&rhs.num_of_additions_over_normal_form).add(&BigUint::one()));
+        debug_assert!(mul_res.check_limb_bounds());
 
         mul_res
     }
@@ -178,6 +185,51 @@ impl<F: Scalar, P: SimFrParams<F>> SimFr<F, P> {
         let (_, rem) = self_biguint.div_rem(&r_biguint);
         rem.is_zero()
     }
+
+    /// Return the number of additions over the normal form tracked for this element.
+    pub fn num_of_additions(&self) -> BigUint {
+        BigUint::from(&self.num_of_additions_over_normal_form)
+    }
+
+    /// Return the inclusive upper bound of each limb implied by the number of additions,
+    /// i.e., `(num_of_additions + 1) * 2^{BIT_PER_LIMB} - 1`, as every addition is
+    /// either a normal form or a sub pad, each of whose limbs is less than `2^{BIT_PER_LIMB + 1}`.
+    pub fn limb_bound(&self) -> BigUint {
+        ((self.num_of_additions() + BigUint::one()) << P::BIT_PER_LIMB) - BigUint::one()
+    }
+
+    /// Check that every limb is within the tracked bound, that the bound is small enough
+    /// for the limbs not to wrap around the modulus of `F`, and that the limbs represent
+    /// the actual value.
+    pub fn check_limb_bounds(&self) -> bool {
+        let bound = self.limb_bound();
+        self.limbs.len() == P::NUM_OF_LIMBS
+            && (bound.bits() as usize) < F::capacity()
+            && self.limbs.iter().all(|limb| {
+                let limb: BigUint = (*limb).into();
+                limb <= bound
+            })
+            && limbs_to_biguint::<F, P>(&self.limbs) == self.val
+    }
+
+    /// Assert that the limbs are within the tracked bound, see `check_limb_bounds`.
+    pub fn assert_limb_bounds(&self) {
+        assert!(
+            self.check_limb_bounds(),
+            "the limbs of the simulated field element exceed the bound of {} additions",
+            self.num_of_additions()
+        );
+    }
+}
+
+/// Recombine the limbs into a `BigUint`, without checking the actual value.
+fn limbs_to_biguint<F: Scalar, P: SimFrParams<F>>(limbs: &[F]) -> BigUint {
+    let mut res = BigUint::zero();
+    for limb in limbs.iter().rev() {
+        res <<= P::BIT_PER_LIMB;
+        res += <F as Into<BigUint>>::into(*limb);
+    }
+    res
 }
 
 /// `SimFrMul` is the intermediate representation for the product of two simulated
@@ -230,6 +282,7 @@ impl<F: Scalar, P: SimFrParams<F>> Add<&SimFrMul<F, P>> for &SimFrMul<F, P> {
         res.val = &res.val + &rhs.val;
         res.prod_of_num_of_additions =
             &res.prod_of_num_of_additions + &rhs.prod_of_num_of_additions;
+        debug_assert!(res.check_limb_bounds());
 
         res
     }
@@ -258,12 +311,49 @@ impl<F: Scalar, P: SimFrParams<F>> Sub<&SimFr<F, P>> for &SimFrMul<F, P> {
         }
         res.val = &res.val + &r_biguint + &r_biguint + &r_biguint + &r_biguint - &rhs.val;
         res.prod_of_num_of_additions = &res.prod_of_num_of_additions + &BigUint::from(12u32);
+        debug_assert!(res.check_limb_bounds());
 
         res
     }
 }
 
 impl<F: Scalar, P: SimFrParams<F>> SimFrMul<F, P> {
+    /// Return the inclusive upper bound of each limb implied by the tracked product of the
+    /// numbers of additions, i.e., `NUM_OF_LIMBS * prod_of_num_of_additions * 2^{2 * BIT_PER_LIMB}`,
+    /// as each limb sums at most `NUM_OF_LIMBS` products of two limbs.
+    pub fn limb_bound(&self) -> BigUint {
+        (BigUint::from(P::NUM_OF_LIMBS) * &self.prod_of_num_of_additions) << (2 * P::BIT_PER_LIMB)
+    }
+
+    /// Check that every limb is within the tracked bound, that the bound is small enough
+    /// for the limbs not to wrap around the modulus of `F`, and that the limbs represent
+    /// the actual value.
+    pub fn check_limb_bounds(&self) -> bool {
+        let bound = self.limb_bound();
+        self.limbs.len() == P::NUM_OF_LIMBS_MUL
+            && (bound.bits() as usize) < F::capacity()
+            && self.limbs.iter().all(|limb| {
+                let limb: BigUint = (*limb).into();
+                limb <= bound
+            })
+            && limbs_to_biguint::<F, P>(&self.limbs) == self.val
+    }
+
+    /// Assert that the limbs are within the tracked bound, see `check_limb_bounds`.
+    pub fn assert_limb_bounds(&self) {
+        assert!(
+            self.check_limb_bounds(),
+            "the limbs of the simulated product exceed the bound of {} additions",
+            self.prod_of_num_of_additions
+        );
+    }
+
+    /// Return whether the product of the numbers of additions is still small enough
+    /// for `enforce_zero`.
+    pub fn can_enforce_zero(&self) -> bool {
+        self.prod_of_num_of_additions.bits() as usize <= 5
+    }
+
     /// The `enforce_zero` function uses the techniques from two works:
     ///
     /// [KPS18](https://akosba.github.io/papers/xjsnark.pdf):
@@ -281,7 +371,7 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrMul<F, P> {
     pub fn enforce_zero(&self) {
         // For safety, since in our use case we are only doing very few algebraic operations,
         // we limit the `prod_of_num_of_additions` to be smaller than 32.
-        assert!(self.prod_of_num_of_additions.bits() as usize <= 5);
+        assert!(self.can_enforce_zero());
         let surfeit = 5; // for safety
 
         let cur_val: BigUint = self.into();
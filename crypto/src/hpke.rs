@@ -0,0 +1,221 @@
+use crate::errors::{CryptoError, Result};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305,
+};
+use hkdf::Hkdf;
+use noah_algebra::prelude::*;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// The identifier of DHKEM(X25519, HKDF-SHA256).
+const KEM_ID: u16 = 0x0020;
+/// The identifier of HKDF-SHA256.
+const KDF_ID: u16 = 0x0001;
+/// The identifier of ChaCha20Poly1305.
+const AEAD_ID: u16 = 0x0003;
+/// The identifier of the base mode.
+const MODE_BASE: u8 = 0x00;
+
+/// The version label of RFC 9180.
+const HPKE_VERSION_LABEL: &[u8] = b"HPKE-v1";
+
+/// The number of bytes of the encapsulated key, i.e., the ephemeral X25519 public key.
+pub const HPKE_ENC_LEN: usize = 32;
+/// The number of bytes of the authentication tag of ChaCha20Poly1305.
+pub const HPKE_TAG_LEN: usize = 16;
+
+/// Encrypt the plaintext to the X25519 public key with HPKE of RFC 9180, in the base mode
+/// of DHKEM(X25519, HKDF-SHA256), HKDF-SHA256, and ChaCha20Poly1305, as a single-shot
+/// encryption, and return the encapsulated key followed by the ciphertext.
+pub fn hpke_seal<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    pk_r: &[u8; 32],
+    info: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>> {
+    let mut sk_e = [0u8; 32];
+    prng.fill_bytes(&mut sk_e);
+    let (shared_secret, enc) = encap(&StaticSecret::from(sk_e), &PublicKey::from(*pk_r))?;
+
+    let (key, nonce) = key_schedule(&shared_secret, info)?;
+    let cipher = ChaCha20Poly1305::new_from_slice(&key).map_err(|_| CryptoError::ParameterError)?;
+    let mut ctext = cipher
+        .encrypt(
+            (&nonce).into(),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| CryptoError::ParameterError)?;
+
+    let mut res = enc.to_vec();
+    res.append(&mut ctext);
+    Ok(res)
+}
+
+/// Decrypt the output of `hpke_seal` with the X25519 secret key.
+pub fn hpke_open(sk_r: &[u8; 32], info: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    if ciphertext.len() < HPKE_ENC_LEN + HPKE_TAG_LEN {
+        return Err(CryptoError::DecryptionError);
+    }
+    let mut enc = [0u8; HPKE_ENC_LEN];
+    enc.copy_from_slice(&ciphertext[..HPKE_ENC_LEN]);
+    let shared_secret = decap(&enc, &StaticSecret::from(*sk_r))?;
+
+    let (key, nonce) = key_schedule(&shared_secret, info)?;
+    let cipher = ChaCha20Poly1305::new_from_slice(&key).map_err(|_| CryptoError::ParameterError)?;
+    cipher
+        .decrypt(
+            (&nonce).into(),
+            Payload {
+                msg: &ciphertext[HPKE_ENC_LEN..],
+                aad,
+            },
+        )
+        .map_err(|_| CryptoError::DecryptionError)
+}
+
+/// Return the X25519 public key of the secret key, as encoded in HPKE.
+pub fn hpke_public_key(sk: &[u8; 32]) -> [u8; 32] {
+    PublicKey::from(&StaticSecret::from(*sk)).to_bytes()
+}
+
+fn encap(sk_e: &StaticSecret, pk_r: &PublicKey) -> Result<([u8; 32], [u8; 32])> {
+    let enc = PublicKey::from(sk_e).to_bytes();
+    let dh = sk_e.diffie_hellman(pk_r);
+    let shared_secret = extract_and_expand(dh.as_bytes(), &enc, pk_r.as_bytes())?;
+    Ok((shared_secret, enc))
+}
+
+fn decap(enc: &[u8; 32], sk_r: &StaticSecret) -> Result<[u8; 32]> {
+    let dh = sk_r.diffie_hellman(&PublicKey::from(*enc));
+    let pk_r = PublicKey::from(sk_r).to_bytes();
+    extract_and_expand(dh.as_bytes(), enc, &pk_r)
+}
+
+/// Derive the shared secret of the KEM from the Diffie-Hellman output,
+/// rejecting the all-zero output of a small-order point.
+fn extract_and_expand(dh: &[u8; 32], enc: &[u8], pk_r: &[u8]) -> Result<[u8; 32]> {
+    if dh.iter().all(|b| *b == 0) {
+        return Err(CryptoError::DecryptionError);
+    }
+    let suite_id = kem_suite_id();
+
+    let mut kem_context = enc.to_vec();
+    kem_context.extend_from_slice(pk_r);
+
+    let eae_prk = labeled_extract(&suite_id, &[], b"eae_prk", dh);
+    let mut shared_secret = [0u8; 32];
+    labeled_expand(
+        &suite_id,
+        &eae_prk,
+        b"shared_secret",
+        &kem_context,
+        &mut shared_secret,
+    )?;
+    Ok(shared_secret)
+}
+
+/// Derive the key and the nonce of the first message in the base mode.
+fn key_schedule(shared_secret: &[u8], info: &[u8]) -> Result<([u8; 32], [u8; 12])> {
+    let suite_id = hpke_suite_id();
+
+    let psk_id_hash = labeled_extract(&suite_id, &[], b"psk_id_hash", &[]);
+    let info_hash = labeled_extract(&suite_id, &[], b"info_hash", info);
+    let mut key_schedule_context = vec![MODE_BASE];
+    key_schedule_context.extend_from_slice(&psk_id_hash);
+    key_schedule_context.extend_from_slice(&info_hash);
+
+    let secret = labeled_extract(&suite_id, shared_secret, b"secret", &[]);
+
+    let mut key = [0u8; 32];
+    labeled_expand(&suite_id, &secret, b"key", &key_schedule_context, &mut key)?;
+    let mut nonce = [0u8; 12];
+    labeled_expand(
+        &suite_id,
+        &secret,
+        b"base_nonce",
+        &key_schedule_context,
+        &mut nonce,
+    )?;
+    Ok((key, nonce))
+}
+
+fn kem_suite_id() -> Vec<u8> {
+    let mut suite_id = b"KEM".to_vec();
+    suite_id.extend_from_slice(&KEM_ID.to_be_bytes());
+    suite_id
+}
+
+fn hpke_suite_id() -> Vec<u8> {
+    let mut suite_id = b"HPKE".to_vec();
+    suite_id.extend_from_slice(&KEM_ID.to_be_bytes());
+    suite_id.extend_from_slice(&KDF_ID.to_be_bytes());
+    suite_id.extend_from_slice(&AEAD_ID.to_be_bytes());
+    suite_id
+}
+
+fn labeled_extract(suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> Vec<u8> {
+    let mut labeled_ikm = HPKE_VERSION_LABEL.to_vec();
+    labeled_ikm.extend_from_slice(suite_id);
+    labeled_ikm.extend_from_slice(label);
+    labeled_ikm.extend_from_slice(ikm);
+    let (prk, _) = Hkdf::<Sha256>::extract(Some(salt), &labeled_ikm);
+    prk.to_vec()
+}
+
+fn labeled_expand(
+    suite_id: &[u8],
+    prk: &[u8],
+    label: &[u8],
+    info: &[u8],
+    out: &mut [u8],
+) -> Result<()> {
+    let mut labeled_info = (out.len() as u16).to_be_bytes().to_vec();
+    labeled_info.extend_from_slice(HPKE_VERSION_LABEL);
+    labeled_info.extend_from_slice(suite_id);
+    labeled_info.extend_from_slice(label);
+    labeled_info.extend_from_slice(info);
+    Hkdf::<Sha256>::from_prk(prk)
+        .map_err(|_| CryptoError::ParameterError)?
+        .expand(&labeled_info, out)
+        .map_err(|_| CryptoError::ParameterError)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hpke::{hpke_open, hpke_public_key, hpke_seal, HPKE_ENC_LEN, HPKE_TAG_LEN};
+    use noah_algebra::prelude::*;
+
+    #[test]
+    fn test_hpke() {
+        let mut prng = test_rng();
+        let mut sk = [0u8; 32];
+        prng.fill_bytes(&mut sk);
+        let pk = hpke_public_key(&sk);
+        let msg = b"this is a memo";
+
+        let ctext = hpke_seal(&mut prng, &pk, b"info", b"aad", msg).unwrap();
+        assert_eq!(ctext.len(), HPKE_ENC_LEN + msg.len() + HPKE_TAG_LEN);
+        assert_eq!(hpke_open(&sk, b"info", b"aad", &ctext).unwrap(), msg);
+
+        // the info, the associated data, the key, and the ciphertext are all authenticated
+        assert!(hpke_open(&sk, b"other info", b"aad", &ctext).is_err());
+        assert!(hpke_open(&sk, b"info", b"other aad", &ctext).is_err());
+        let mut other_sk = [0u8; 32];
+        prng.fill_bytes(&mut other_sk);
+        assert!(hpke_open(&other_sk, b"info", b"aad", &ctext).is_err());
+        let mut tampered = ctext.clone();
+        tampered[HPKE_ENC_LEN] ^= 1;
+        assert!(hpke_open(&sk, b"info", b"aad", &tampered).is_err());
+        assert!(hpke_open(&sk, b"info", b"aad", &ctext[..HPKE_ENC_LEN]).is_err());
+
+        // a small-order encapsulated key is rejected
+        let mut small_order = ctext;
+        small_order[..HPKE_ENC_LEN].copy_from_slice(&[0u8; HPKE_ENC_LEN]);
+        assert!(hpke_open(&sk, b"info", b"aad", &small_order).is_err());
+    }
+}
@@ -4,16 +4,23 @@ use aes::{
 };
 use curve25519_dalek::edwards::CompressedEdwardsY;
 use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey};
+use hkdf::Hkdf;
 use noah_algebra::prelude::*;
-use noah_algebra::ristretto::RistrettoScalar;
+use noah_algebra::{
+    ed25519::Ed25519Point,
+    jubjub::JubjubPoint,
+    ristretto::{RistrettoPoint, RistrettoScalar},
+    secp256k1::SECP256K1G1,
+};
 use serde::Serializer;
-use sha2::Digest;
+use sha2::{Digest, Sha256, Sha512};
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
 type Aes256Ctr = ctr::Ctr64BE<Aes256>;
 type Result<T> = core::result::Result<T, AlgebraError>;
 
-#[wasm_bindgen]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Debug, Clone)]
 /// The public key for the hybrid encryption scheme.
 pub struct XPublicKey {
@@ -57,7 +64,7 @@ impl PartialEq for XPublicKey {
 
 impl Eq for XPublicKey {}
 
-#[wasm_bindgen]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Clone)]
 /// The secret key for the hybrid encryption scheme.
 pub struct XSecretKey {
@@ -177,9 +184,239 @@ pub fn hybrid_decrypt_with_ed25519_secret_key(
     symmetric_decrypt(&key, &ctext.ciphertext)
 }
 
+/// The domain separator in the info of the key derivation of the generic hybrid encryption.
+const HYBRID_ENCRYPTION_KDF_DOMAIN: &[u8] = b"Noah Hybrid Encryption KDF";
+
+/// The version of the format of the generic hybrid ciphertexts.
+pub const HYBRID_CIPHERTEXT_VERSION: u8 = 1;
+
+/// The identifier of AES-256 in the counter mode, the only symmetric cipher of the suites.
+const HYBRID_CIPHER_AES256_CTR: u8 = 1;
+
+/// A group over which the generic hybrid encryption can be instantiated.
+pub trait HybridEncryptionGroup: Group {
+    /// The identifier of the group in the suite identifiers.
+    const GROUP_ID: u8;
+}
+
+impl HybridEncryptionGroup for RistrettoPoint {
+    const GROUP_ID: u8 = 1;
+}
+
+impl HybridEncryptionGroup for Ed25519Point {
+    const GROUP_ID: u8 = 2;
+}
+
+impl HybridEncryptionGroup for JubjubPoint {
+    const GROUP_ID: u8 = 3;
+}
+
+impl HybridEncryptionGroup for SECP256K1G1 {
+    const GROUP_ID: u8 = 4;
+}
+
+/// The key derivation function of the generic hybrid encryption.
+pub trait HybridKdf {
+    /// The identifier of the key derivation function in the suite identifiers.
+    const KDF_ID: u8;
+
+    /// Derive a symmetric key from the shared secret, bound to the info.
+    fn derive_key(info: &[u8], shared_secret: &[u8]) -> [u8; 32];
+}
+
+/// HKDF with SHA-256.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha256Kdf;
+
+impl HybridKdf for Sha256Kdf {
+    const KDF_ID: u8 = 1;
+
+    fn derive_key(info: &[u8], shared_secret: &[u8]) -> [u8; 32] {
+        let mut symmetric_key = [0u8; 32];
+        Hkdf::<Sha256>::new(None, shared_secret)
+            .expand(info, &mut symmetric_key)
+            .unwrap(); // safe unwrap, as 32 bytes are within the output limit
+        symmetric_key
+    }
+}
+
+/// HKDF with SHA-512.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha512Kdf;
+
+impl HybridKdf for Sha512Kdf {
+    const KDF_ID: u8 = 2;
+
+    fn derive_key(info: &[u8], shared_secret: &[u8]) -> [u8; 32] {
+        let mut symmetric_key = [0u8; 32];
+        Hkdf::<Sha512>::new(None, shared_secret)
+            .expand(info, &mut symmetric_key)
+            .unwrap(); // safe unwrap, as 32 bytes are within the output limit
+        symmetric_key
+    }
+}
+
+/// The identifiers of the group, the key derivation function, and the symmetric cipher
+/// of a hybrid ciphertext.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HybridSuite {
+    /// The identifier of the group.
+    pub group_id: u8,
+    /// The identifier of the key derivation function.
+    pub kdf_id: u8,
+    /// The identifier of the symmetric cipher.
+    pub cipher_id: u8,
+}
+
+impl HybridSuite {
+    /// The number of bytes of the suite identifiers.
+    pub const LEN: usize = 3;
+
+    /// Return the suite of the group and the key derivation function.
+    pub fn new<G: HybridEncryptionGroup, K: HybridKdf>() -> Self {
+        Self {
+            group_id: G::GROUP_ID,
+            kdf_id: K::KDF_ID,
+            cipher_id: HYBRID_CIPHER_AES256_CTR,
+        }
+    }
+
+    /// Return the bytes of the suite identifiers.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        [self.group_id, self.kdf_id, self.cipher_id]
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+/// A ciphertext of the hybrid encryption over a generic group.
+pub struct HybridCiphertext<G> {
+    /// The version of the format.
+    pub version: u8,
+    /// The suite that the ciphertext was produced with.
+    pub suite: HybridSuite,
+    /// The ephemeral public key `r * G` of the sender.
+    pub ephemeral_public_key: G,
+    pub(crate) ciphertext: CompactByteArray,
+}
+
+impl<G: HybridEncryptionGroup> NoahFromToBytes for HybridCiphertext<G> {
+    fn noah_to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.version];
+        bytes.extend_from_slice(&self.suite.to_bytes());
+        bytes.append(&mut self.ephemeral_public_key.to_compressed_bytes());
+        bytes.append(&mut self.ciphertext.noah_to_bytes());
+        bytes
+    }
+
+    fn noah_from_bytes(bytes: &[u8]) -> Result<Self> {
+        let point_start = 1 + HybridSuite::LEN;
+        let point_end = point_start + G::COMPRESSED_LEN;
+        if bytes.len() < point_end || bytes[0] != HYBRID_CIPHERTEXT_VERSION {
+            return Err(AlgebraError::DeserializationError);
+        }
+        let suite = HybridSuite {
+            group_id: bytes[1],
+            kdf_id: bytes[2],
+            cipher_id: bytes[3],
+        };
+        let ephemeral_public_key = G::from_compressed_bytes(&bytes[point_start..point_end])?;
+        let ciphertext = CompactByteArray::noah_from_bytes(&bytes[point_end..])?;
+        Ok(Self {
+            version: bytes[0],
+            suite,
+            ephemeral_public_key,
+            ciphertext,
+        })
+    }
+}
+
+/// Encrypt a message to the public key `sk * G` over a generic group, with the key derivation function.
+///
+/// The key is bound to the application context, so that the ciphertext only decrypts
+/// under the same context.
+pub fn hybrid_encrypt<G: HybridEncryptionGroup, K: HybridKdf, R: CryptoRng + RngCore>(
+    prng: &mut R,
+    pub_key: &G,
+    context: &[u8],
+    message: &[u8],
+) -> Result<HybridCiphertext<G>> {
+    if *pub_key == G::get_identity() {
+        return Err(AlgebraError::ParameterError);
+    }
+
+    let suite = HybridSuite::new::<G, K>();
+    let r = G::ScalarType::random(prng);
+    let ephemeral_public_key = G::get_base().mul(&r);
+    let shared_point = pub_key.mul(&r);
+
+    let key = derive_hybrid_key::<G, K>(
+        HYBRID_CIPHERTEXT_VERSION,
+        &suite,
+        &ephemeral_public_key,
+        pub_key,
+        context,
+        &shared_point,
+    );
+    Ok(HybridCiphertext {
+        version: HYBRID_CIPHERTEXT_VERSION,
+        suite,
+        ephemeral_public_key,
+        ciphertext: symmetric_encrypt(&key, message),
+    })
+}
+
+/// Decrypt a hybrid ciphertext over a generic group, which must have been produced
+/// with the same group, key derivation function, and application context.
+pub fn hybrid_decrypt<G: HybridEncryptionGroup, K: HybridKdf>(
+    ctext: &HybridCiphertext<G>,
+    sec_key: &G::ScalarType,
+    context: &[u8],
+) -> Result<Vec<u8>> {
+    if ctext.version != HYBRID_CIPHERTEXT_VERSION || ctext.suite != HybridSuite::new::<G, K>() {
+        return Err(AlgebraError::ParameterError);
+    }
+
+    let shared_point = ctext.ephemeral_public_key.mul(sec_key);
+    if shared_point == G::get_identity() {
+        return Err(AlgebraError::ParameterError);
+    }
+    let pub_key = G::get_base().mul(sec_key);
+
+    let key = derive_hybrid_key::<G, K>(
+        ctext.version,
+        &ctext.suite,
+        &ctext.ephemeral_public_key,
+        &pub_key,
+        context,
+        &shared_point,
+    );
+    Ok(symmetric_decrypt(&key, &ctext.ciphertext))
+}
+
+/// Derive the symmetric key from the shared point, with the transcript of the key exchange,
+/// i.e., the version, the suite, both public keys, and the length-prefixed application context,
+/// as the info of the key derivation function.
+fn derive_hybrid_key<G: HybridEncryptionGroup, K: HybridKdf>(
+    version: u8,
+    suite: &HybridSuite,
+    ephemeral_public_key: &G,
+    pub_key: &G,
+    context: &[u8],
+    shared_point: &G,
+) -> [u8; 32] {
+    let mut info = HYBRID_ENCRYPTION_KDF_DOMAIN.to_vec();
+    info.push(version);
+    info.extend_from_slice(&suite.to_bytes());
+    info.append(&mut ephemeral_public_key.to_compressed_bytes());
+    info.append(&mut pub_key.to_compressed_bytes());
+    info.extend_from_slice(&(context.len() as u64).to_le_bytes());
+    info.extend_from_slice(context);
+    K::derive_key(&info, &shared_point.to_compressed_bytes())
+}
+
 /// Convert the shared secret to a symmetric key
 fn shared_secret_to_symmetric_key(shared_secret: &x25519_dalek::SharedSecret) -> [u8; 32] {
-    let mut hasher = sha2::Sha256::new();
+    let mut hasher = Sha256::new();
     hasher.update(shared_secret.as_bytes());
     let hash = hasher.finalize();
     let mut symmetric_key = [0u8; 32];
@@ -246,7 +483,7 @@ fn symmetric_key_from_ed25519_secret_key(
     symmetric_key_from_x25519_secret_key(&x_secret, ephemeral_public_key)
 }
 
-fn symmetric_encrypt(key: &[u8; 32], plaintext: &[u8]) -> CompactByteArray {
+pub(crate) fn symmetric_encrypt(key: &[u8; 32], plaintext: &[u8]) -> CompactByteArray {
     let kkey = GenericArray::from_slice(key);
     let ctr = GenericArray::from_slice(&[0u8; 16]); // counter can be zero because key is fresh
     let mut ctext_vec = plaintext.to_vec();
@@ -255,7 +492,7 @@ fn symmetric_encrypt(key: &[u8; 32], plaintext: &[u8]) -> CompactByteArray {
     CompactByteArray(ctext_vec)
 }
 
-fn symmetric_decrypt(key: &[u8; 32], ciphertext: &CompactByteArray) -> Vec<u8> {
+pub(crate) fn symmetric_decrypt(key: &[u8; 32], ciphertext: &CompactByteArray) -> Vec<u8> {
     let kkey = GenericArray::from_slice(key);
     let ctr = GenericArray::from_slice(&[0u8; 16]);
     let mut plaintext_vec = ciphertext.0.clone();
@@ -303,4 +540,56 @@ mod test {
         let plaintext = hybrid_decrypt_with_ed25519_secret_key(&cipherbox, &key_pair.secret_key());
         assert_eq!(msg, plaintext.as_slice());
     }
+
+    fn check_generic_hybrid_cipher<G: HybridEncryptionGroup>() {
+        let mut prng = test_rng();
+        let sec_key = G::ScalarType::random(&mut prng);
+        let pub_key = G::get_base().mul(&sec_key);
+        let msg = b"this is a message to a generic key";
+        let context = b"owner memo";
+
+        let ctext = hybrid_encrypt::<G, Sha256Kdf, _>(&mut prng, &pub_key, context, msg).unwrap();
+        assert_eq!(ctext.version, HYBRID_CIPHERTEXT_VERSION);
+        assert_eq!(ctext.suite, HybridSuite::new::<G, Sha256Kdf>());
+        let plaintext = hybrid_decrypt::<G, Sha256Kdf>(&ctext, &sec_key, context).unwrap();
+        assert_eq!(msg, plaintext.as_slice());
+
+        let bytes = ctext.noah_to_bytes();
+        let ctext_de = HybridCiphertext::<G>::noah_from_bytes(&bytes).unwrap();
+        assert_eq!(ctext, ctext_de);
+
+        // an unknown version is rejected
+        let mut bytes = bytes;
+        bytes[0] = HYBRID_CIPHERTEXT_VERSION + 1;
+        assert!(HybridCiphertext::<G>::noah_from_bytes(&bytes).is_err());
+
+        // another context does not decrypt
+        let plaintext = hybrid_decrypt::<G, Sha256Kdf>(&ctext, &sec_key, b"asset tracing").unwrap();
+        assert_ne!(msg, plaintext.as_slice());
+
+        // another key derivation function
+        let ctext = hybrid_encrypt::<G, Sha512Kdf, _>(&mut prng, &pub_key, context, msg).unwrap();
+        let plaintext = hybrid_decrypt::<G, Sha512Kdf>(&ctext, &sec_key, context).unwrap();
+        assert_eq!(msg, plaintext.as_slice());
+
+        // the suite must match
+        assert!(hybrid_decrypt::<G, Sha256Kdf>(&ctext, &sec_key, context).is_err());
+
+        // another key does not decrypt
+        let other_key = G::ScalarType::random(&mut prng);
+        let plaintext = hybrid_decrypt::<G, Sha512Kdf>(&ctext, &other_key, context).unwrap();
+        assert_ne!(msg, plaintext.as_slice());
+
+        assert!(
+            hybrid_encrypt::<G, Sha256Kdf, _>(&mut prng, &G::get_identity(), context, msg).is_err()
+        );
+    }
+
+    #[test]
+    fn generic_hybrid_cipher() {
+        check_generic_hybrid_cipher::<RistrettoPoint>();
+        check_generic_hybrid_cipher::<Ed25519Point>();
+        check_generic_hybrid_cipher::<JubjubPoint>();
+        check_generic_hybrid_cipher::<SECP256K1G1>();
+    }
 }
@@ -29,10 +29,14 @@ extern crate serde_derive;
 pub mod anemoi_jive;
 /// The module for anonymous credentials.
 pub mod anon_creds;
+/// The module for BLS signatures over BLS12-381 and their aggregation.
+pub mod bls_sig;
 /// The library for Bulletproofs.
 pub mod bulletproofs;
 /// The module for the Chaum-Pedersen protocol.
 pub mod chaum_pedersen;
+/// The module for the key-committing authenticated encryption.
+pub mod committing_aead;
 /// The module for confidential anonymous credentials.
 pub mod confidential_anon_creds;
 /// The module for the delegated Schnorr protocol.
@@ -49,11 +53,19 @@ pub mod field_simulation;
 pub mod gapdh;
 /// The module for hashing to the curve.
 pub mod hashing_to_the_curve;
+/// The module for HPKE of RFC 9180.
+pub mod hpke;
 /// The module for hybrid encryption.
 pub mod hybrid_encryption;
 /// The module for the matrix Sigma protocol.
 pub mod matrix_sigma;
 /// The module for the equality proof between a Pedersen commitment and an ElGamal ciphertext.
 pub mod pedersen_elgamal;
+/// The module for the Poseidon hash function.
+pub mod poseidon;
+/// The module for the Rescue-Prime hash function.
+pub mod rescue_prime;
 /// The module that contains some useful Schnorr gadgets.
 pub mod schnorr_gadgets;
+/// The module for the threshold ElGamal encryption with Shamir shares.
+pub mod threshold_encryption;
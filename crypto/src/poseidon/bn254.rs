@@ -0,0 +1,68 @@
+use crate::poseidon::PoseidonParams;
+use noah_algebra::bn254::BN254Scalar;
+
+/// The number of field elements in the state of Poseidon over BN254.
+pub const POSEIDON_BN254_WIDTH: usize = 3;
+
+/// The rate of Poseidon over BN254, which leaves a capacity of one field element.
+pub const POSEIDON_BN254_RATE: usize = 2;
+
+/// The number of full rounds of Poseidon over BN254.
+pub const POSEIDON_BN254_NUM_FULL_ROUNDS: usize = 8;
+
+/// The number of partial rounds of Poseidon over BN254.
+pub const POSEIDON_BN254_NUM_PARTIAL_ROUNDS: usize = 57;
+
+/// The S-Box exponent, the smallest integer coprime to `p - 1` for the BN254 scalar field.
+pub const POSEIDON_BN254_ALPHA: u64 = 5;
+
+/// The parameters of Poseidon over the BN254 scalar field, with the numbers of rounds
+/// for width three and 128-bit security. The MDS matrix and the round constants are not
+/// those of any other implementation.
+pub fn poseidon_bn254() -> PoseidonParams<BN254Scalar> {
+    PoseidonParams::new(
+        b"BN254-3-2-8-57",
+        POSEIDON_BN254_WIDTH,
+        POSEIDON_BN254_RATE,
+        POSEIDON_BN254_NUM_FULL_ROUNDS,
+        POSEIDON_BN254_NUM_PARTIAL_ROUNDS,
+        POSEIDON_BN254_ALPHA,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::poseidon::{poseidon_bn254, POSEIDON_BN254_WIDTH};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    #[test]
+    fn test_poseidon_bn254() {
+        let params = poseidon_bn254();
+        assert_eq!(params, poseidon_bn254());
+        assert_eq!(params.num_rounds(), 65);
+        assert!(params.is_full_round(3));
+        assert!(!params.is_full_round(4));
+        assert!(params.is_full_round(61));
+
+        let mut prng = test_rng();
+        let input: Vec<BN254Scalar> = (0..POSEIDON_BN254_WIDTH)
+            .map(|_| BN254Scalar::random(&mut prng))
+            .collect();
+
+        let mut state = input.clone();
+        params.permute(&mut state);
+        assert_ne!(state, input);
+
+        let digest = params.hash(&input, 1);
+        assert_eq!(digest.len(), 1);
+        assert_eq!(digest, params.hash(&input, 1));
+        assert_ne!(digest, params.hash(&input[..2], 1));
+
+        // the padding separates inputs that end with zeros
+        assert_ne!(
+            params.hash(&[BN254Scalar::one()], 1),
+            params.hash(&[BN254Scalar::one(), BN254Scalar::zero()], 1)
+        );
+        assert_eq!(params.hash(&input, 5).len(), 5);
+    }
+}
@@ -0,0 +1,143 @@
+use noah_algebra::prelude::*;
+use sha2::{Digest, Sha512};
+
+/// The module for the Poseidon parameters over the BN254 scalar field.
+mod bn254;
+
+pub use bn254::*;
+
+/// The parameters of the Poseidon permutation and of the sponge built on it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonParams<F: Scalar> {
+    /// The number of field elements in the state.
+    pub width: usize,
+    /// The number of field elements absorbed or squeezed per permutation.
+    pub rate: usize,
+    /// The number of full rounds, half of which are before the partial rounds.
+    pub num_full_rounds: usize,
+    /// The number of partial rounds, which apply the S-Box to the first element only.
+    pub num_partial_rounds: usize,
+    /// The exponent of the S-Box.
+    pub alpha: u64,
+    /// The MDS matrix.
+    pub mds: Vec<Vec<F>>,
+    /// The round constants, a vector of `width` elements per round.
+    pub round_constants: Vec<Vec<F>>,
+}
+
+impl<F: Scalar> PoseidonParams<F> {
+    /// Generate the parameters with a Cauchy MDS matrix and with the round constants
+    /// derived from the label by SHA-512.
+    pub fn new(
+        label: &[u8],
+        width: usize,
+        rate: usize,
+        num_full_rounds: usize,
+        num_partial_rounds: usize,
+        alpha: u64,
+    ) -> Self {
+        assert!(rate < width);
+        assert_eq!(num_full_rounds % 2, 0);
+
+        // M[i][j] = 1 / (i + width + j), whose entries are all distinct and nonzero
+        let mds = (0..width)
+            .map(|i| {
+                (0..width)
+                    .map(|j| F::from((i + width + j) as u64).inv().unwrap())
+                    .collect()
+            })
+            .collect();
+
+        let round_constants = (0..num_full_rounds + num_partial_rounds)
+            .map(|r| {
+                (0..width)
+                    .map(|i| {
+                        let mut hasher = Sha512::new();
+                        hasher.update(b"Poseidon round constant");
+                        hasher.update(label);
+                        hasher.update((r as u64).to_le_bytes());
+                        hasher.update((i as u64).to_le_bytes());
+                        F::from_hash(hasher)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            width,
+            rate,
+            num_full_rounds,
+            num_partial_rounds,
+            alpha,
+            mds,
+            round_constants,
+        }
+    }
+
+    /// Return the total number of rounds.
+    pub fn num_rounds(&self) -> usize {
+        self.num_full_rounds + self.num_partial_rounds
+    }
+
+    /// Return true if the round applies the S-Box to the whole state.
+    pub fn is_full_round(&self, round: usize) -> bool {
+        round < self.num_full_rounds / 2
+            || round >= self.num_full_rounds / 2 + self.num_partial_rounds
+    }
+
+    /// Apply the Poseidon permutation to the state.
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width);
+
+        for round in 0..self.num_rounds() {
+            for (x, c) in state.iter_mut().zip(self.round_constants[round].iter()) {
+                x.add_assign(c);
+            }
+
+            if self.is_full_round(round) {
+                state.iter_mut().for_each(|x| *x = x.pow(&[self.alpha]));
+            } else {
+                state[0] = state[0].pow(&[self.alpha]);
+            }
+
+            let new_state: Vec<F> = self
+                .mds
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .zip(state.iter())
+                        .fold(F::zero(), |acc, (m, x)| acc.add(&m.mul(x)))
+                })
+                .collect();
+            state.copy_from_slice(&new_state);
+        }
+    }
+
+    /// Hash the input into `output_len` field elements with the sponge. The input is padded
+    /// with a one and then with zeros to a multiple of the rate.
+    pub fn hash(&self, input: &[F], output_len: usize) -> Vec<F> {
+        let mut padded = input.to_vec();
+        padded.push(F::one());
+        while padded.len() % self.rate != 0 {
+            padded.push(F::zero());
+        }
+
+        let mut state = vec![F::zero(); self.width];
+        for chunk in padded.chunks(self.rate) {
+            for (x, y) in state.iter_mut().zip(chunk.iter()) {
+                x.add_assign(y);
+            }
+            self.permute(&mut state);
+        }
+
+        let mut output = Vec::with_capacity(output_len);
+        loop {
+            output.extend_from_slice(&state[..self.rate]);
+            if output.len() >= output_len {
+                output.truncate(output_len);
+                return output;
+            }
+            self.permute(&mut state);
+        }
+    }
+}
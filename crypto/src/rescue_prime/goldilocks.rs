@@ -0,0 +1,78 @@
+use crate::rescue_prime::RescuePrimeParams;
+use ark_std::vec;
+use noah_algebra::goldilocks::GoldilocksScalar;
+
+/// The number of field elements in the state of Rescue-Prime over Goldilocks.
+pub const RESCUE_PRIME_GOLDILOCKS_WIDTH: usize = 12;
+
+/// The rate of Rescue-Prime over Goldilocks, which leaves a capacity of four field elements,
+/// i.e., 256 bits.
+pub const RESCUE_PRIME_GOLDILOCKS_RATE: usize = 8;
+
+/// The number of rounds of Rescue-Prime over Goldilocks.
+pub const RESCUE_PRIME_GOLDILOCKS_NUM_ROUNDS: usize = 7;
+
+/// The S-Box exponent, the smallest integer coprime to `p - 1` for the Goldilocks field.
+pub const RESCUE_PRIME_GOLDILOCKS_ALPHA: u64 = 7;
+
+/// The inverse S-Box exponent, the inverse of seven modulo `p - 1`.
+pub const RESCUE_PRIME_GOLDILOCKS_ALPHA_INV: u64 = 10540996611094048183;
+
+/// The proof-of-concept parameters of Rescue-Prime over the Goldilocks field, for research and
+/// benchmarks. The round constants are not those of any other implementation.
+pub fn rescue_prime_goldilocks() -> RescuePrimeParams<GoldilocksScalar> {
+    RescuePrimeParams::new(
+        b"Goldilocks-12-8-7",
+        RESCUE_PRIME_GOLDILOCKS_WIDTH,
+        RESCUE_PRIME_GOLDILOCKS_RATE,
+        RESCUE_PRIME_GOLDILOCKS_NUM_ROUNDS,
+        RESCUE_PRIME_GOLDILOCKS_ALPHA,
+        vec![RESCUE_PRIME_GOLDILOCKS_ALPHA_INV],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rescue_prime::{
+        rescue_prime_goldilocks, RESCUE_PRIME_GOLDILOCKS_ALPHA, RESCUE_PRIME_GOLDILOCKS_ALPHA_INV,
+        RESCUE_PRIME_GOLDILOCKS_WIDTH,
+    };
+    use noah_algebra::{goldilocks::GoldilocksScalar, prelude::*};
+
+    #[test]
+    fn test_sbox_inverse() {
+        let mut prng = test_rng();
+        for _ in 0..10 {
+            let x = GoldilocksScalar::random(&mut prng);
+            let y = x.pow(&[RESCUE_PRIME_GOLDILOCKS_ALPHA]);
+            assert_eq!(y.pow(&[RESCUE_PRIME_GOLDILOCKS_ALPHA_INV]), x);
+        }
+    }
+
+    #[test]
+    fn test_rescue_prime_goldilocks() {
+        let params = rescue_prime_goldilocks();
+        assert_eq!(params, rescue_prime_goldilocks());
+
+        let mut prng = test_rng();
+        let input: Vec<GoldilocksScalar> = (0..RESCUE_PRIME_GOLDILOCKS_WIDTH)
+            .map(|_| GoldilocksScalar::random(&mut prng))
+            .collect();
+
+        let mut state = input.clone();
+        params.permute(&mut state);
+        assert_ne!(state, input);
+
+        let digest = params.hash(&input, 4);
+        assert_eq!(digest.len(), 4);
+        assert_eq!(digest, params.hash(&input, 4));
+        assert_ne!(digest, params.hash(&input[..11], 4));
+
+        // the padding separates inputs that end with zeros
+        assert_ne!(
+            params.hash(&[GoldilocksScalar::one()], 4),
+            params.hash(&[GoldilocksScalar::one(), GoldilocksScalar::zero()], 4)
+        );
+        assert_eq!(params.hash(&input, 20).len(), 20);
+    }
+}
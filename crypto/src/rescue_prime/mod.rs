@@ -0,0 +1,131 @@
+use noah_algebra::prelude::*;
+use sha2::{Digest, Sha512};
+
+/// The module for the Rescue-Prime parameters over the Goldilocks field.
+mod goldilocks;
+
+pub use goldilocks::*;
+
+/// The parameters of the Rescue-Prime permutation and of the sponge built on it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RescuePrimeParams<F: Scalar> {
+    /// The number of field elements in the state.
+    pub width: usize,
+    /// The number of field elements absorbed or squeezed per permutation.
+    pub rate: usize,
+    /// The number of rounds.
+    pub num_rounds: usize,
+    /// The exponent of the S-Box.
+    pub alpha: u64,
+    /// The exponent of the inverse S-Box, in little-endian u64 limbs.
+    pub alpha_inv: Vec<u64>,
+    /// The MDS matrix.
+    pub mds: Vec<Vec<F>>,
+    /// The round constants, two vectors of `width` elements per round.
+    pub round_constants: Vec<Vec<F>>,
+}
+
+impl<F: Scalar> RescuePrimeParams<F> {
+    /// Generate the parameters with a Cauchy MDS matrix and with the round constants
+    /// derived from the label by SHA-512.
+    pub fn new(
+        label: &[u8],
+        width: usize,
+        rate: usize,
+        num_rounds: usize,
+        alpha: u64,
+        alpha_inv: Vec<u64>,
+    ) -> Self {
+        assert!(rate < width);
+
+        // M[i][j] = 1 / (i + width + j), whose entries are all distinct and nonzero
+        let mds = (0..width)
+            .map(|i| {
+                (0..width)
+                    .map(|j| F::from((i + width + j) as u64).inv().unwrap())
+                    .collect()
+            })
+            .collect();
+
+        let round_constants = (0..2 * num_rounds)
+            .map(|r| {
+                (0..width)
+                    .map(|i| {
+                        let mut hasher = Sha512::new();
+                        hasher.update(b"Rescue-Prime round constant");
+                        hasher.update(label);
+                        hasher.update((r as u64).to_le_bytes());
+                        hasher.update((i as u64).to_le_bytes());
+                        F::from_hash(hasher)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            width,
+            rate,
+            num_rounds,
+            alpha,
+            alpha_inv,
+            mds,
+            round_constants,
+        }
+    }
+
+    /// Multiply the state by the MDS matrix and add the round constants.
+    fn linear_layer(&self, state: &mut [F], round_constants: &[F]) {
+        let new_state: Vec<F> = self
+            .mds
+            .iter()
+            .zip(round_constants.iter())
+            .map(|(row, c)| {
+                row.iter()
+                    .zip(state.iter())
+                    .fold(*c, |acc, (m, x)| acc.add(&m.mul(x)))
+            })
+            .collect();
+        state.copy_from_slice(&new_state);
+    }
+
+    /// Apply the Rescue-Prime permutation to the state.
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width);
+
+        for round in 0..self.num_rounds {
+            state.iter_mut().for_each(|x| *x = x.pow(&[self.alpha]));
+            self.linear_layer(state, &self.round_constants[2 * round]);
+
+            state.iter_mut().for_each(|x| *x = x.pow(&self.alpha_inv));
+            self.linear_layer(state, &self.round_constants[2 * round + 1]);
+        }
+    }
+
+    /// Hash the input into `output_len` field elements with the sponge. The input is padded
+    /// with a one and then with zeros to a multiple of the rate.
+    pub fn hash(&self, input: &[F], output_len: usize) -> Vec<F> {
+        let mut padded = input.to_vec();
+        padded.push(F::one());
+        while padded.len() % self.rate != 0 {
+            padded.push(F::zero());
+        }
+
+        let mut state = vec![F::zero(); self.width];
+        for chunk in padded.chunks(self.rate) {
+            for (x, y) in state.iter_mut().zip(chunk.iter()) {
+                x.add_assign(y);
+            }
+            self.permute(&mut state);
+        }
+
+        let mut output = Vec::with_capacity(output_len);
+        loop {
+            output.extend_from_slice(&state[..self.rate]);
+            if output.len() >= output_len {
+                output.truncate(output_len);
+                return output;
+            }
+            self.permute(&mut state);
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use crate::errors::{CryptoError, Result};
+use crate::hybrid_encryption::{symmetric_decrypt, symmetric_encrypt};
+use crate::matrix_sigma::SigmaTranscript;
+use merlin::Transcript;
+use noah_algebra::{
+    bls12_381::{BLSScalar, BLSG1},
+    prelude::*,
+};
+use sha2::Digest;
+
+/// The domain separator for the proofs of decryption shares.
+const DECRYPTION_SHARE_PROOF_DOMAIN: &[u8] = b"Threshold ElGamal Decryption Share Proof";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// The encryption key of a t-of-n committee.
+pub struct ThresholdEncKey {
+    /// The joint public key `s * G`.
+    pub public_key: BLSG1,
+    /// The number of decryption shares needed to decrypt.
+    pub threshold: u32,
+    /// The verification keys `s_i * G` of the members, where the i-th member has index `i + 1`.
+    pub verification_keys: Vec<BLSG1>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// The Shamir share `s_i = f(i)` of the decryption key held by a committee member.
+pub struct ThresholdDecKeyShare {
+    /// The index of the member, starting from 1.
+    pub index: u32,
+    pub(crate) share: BLSScalar,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// A ciphertext to a committee.
+pub struct ThresholdCiphertext {
+    /// `e1` = `r * G`
+    pub e1: BLSG1,
+    /// The message encrypted under the symmetric key derived from `r * pk`.
+    pub ciphertext: CompactByteArray,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// A Chaum-Pedersen proof that a decryption share and a verification key have the same discrete logarithm.
+pub struct DecryptionShareProof {
+    /// The commitment `r * G`.
+    pub commitment_base: BLSG1,
+    /// The commitment `r * e1`.
+    pub commitment_e1: BLSG1,
+    /// The response `r + c * s_i`.
+    pub response: BLSScalar,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// A decryption share `s_i * e1` of a member, with the proof of its correctness.
+pub struct DecryptionShare {
+    /// The index of the member.
+    pub index: u32,
+    /// The partial decryption `s_i * e1`.
+    pub point: BLSG1,
+    /// The proof of the partial decryption.
+    pub proof: DecryptionShareProof,
+}
+
+impl ThresholdEncKey {
+    /// Return the number of members of the committee.
+    pub fn num_members(&self) -> usize {
+        self.verification_keys.len()
+    }
+
+    /// Return the verification key of the member with the index.
+    pub fn get_verification_key(&self, index: u32) -> Result<&BLSG1> {
+        if index == 0 {
+            return Err(CryptoError::ParameterError);
+        }
+        self.verification_keys
+            .get(index as usize - 1)
+            .ok_or(CryptoError::ParameterError)
+    }
+}
+
+/// Generate a t-of-n committee key with a trusted dealer, returning the encryption key and the n key shares.
+pub fn threshold_key_gen<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    threshold: u32,
+    num_members: u32,
+) -> Result<(ThresholdEncKey, Vec<ThresholdDecKeyShare>)> {
+    if threshold == 0 || threshold > num_members {
+        return Err(CryptoError::ParameterError);
+    }
+
+    // f(x) = a_0 + a_1 * x + ... + a_{t-1} * x^{t-1}, where a_0 is the decryption key.
+    let coefficients = (0..threshold)
+        .map(|_| BLSScalar::random(prng))
+        .collect_vec();
+
+    let base = BLSG1::get_base();
+    let public_key = base.mul(&coefficients[0]);
+
+    let mut shares = Vec::with_capacity(num_members as usize);
+    let mut verification_keys = Vec::with_capacity(num_members as usize);
+    for index in 1..=num_members {
+        let x = BLSScalar::from(index);
+        let mut share = BLSScalar::zero();
+        for coefficient in coefficients.iter().rev() {
+            share = share.mul(&x).add(coefficient);
+        }
+        verification_keys.push(base.mul(&share));
+        shares.push(ThresholdDecKeyShare { index, share });
+    }
+
+    Ok((
+        ThresholdEncKey {
+            public_key,
+            threshold,
+            verification_keys,
+        },
+        shares,
+    ))
+}
+
+/// Encrypt a message to the committee.
+pub fn threshold_encrypt<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    enc_key: &ThresholdEncKey,
+    message: &[u8],
+) -> ThresholdCiphertext {
+    let r = BLSScalar::random(prng);
+    let e1 = BLSG1::get_base().mul(&r);
+    let key = symmetric_key_from_point(&enc_key.public_key.mul(&r));
+
+    ThresholdCiphertext {
+        e1,
+        ciphertext: symmetric_encrypt(&key, message),
+    }
+}
+
+/// Compute the decryption share of a member, with the proof of its correctness.
+pub fn threshold_decrypt_share<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    ctext: &ThresholdCiphertext,
+    key_share: &ThresholdDecKeyShare,
+) -> DecryptionShare {
+    let base = BLSG1::get_base();
+    let verification_key = base.mul(&key_share.share);
+    let point = ctext.e1.mul(&key_share.share);
+
+    let r = BLSScalar::random(prng);
+    let commitment_base = base.mul(&r);
+    let commitment_e1 = ctext.e1.mul(&r);
+
+    let c = decryption_share_challenge(
+        key_share.index,
+        &ctext.e1,
+        &verification_key,
+        &point,
+        &commitment_base,
+        &commitment_e1,
+    );
+    let response = r.add(&c.mul(&key_share.share));
+
+    DecryptionShare {
+        index: key_share.index,
+        point,
+        proof: DecryptionShareProof {
+            commitment_base,
+            commitment_e1,
+            response,
+        },
+    }
+}
+
+/// Verify the decryption share of a member against its verification key.
+pub fn verify_decryption_share(
+    enc_key: &ThresholdEncKey,
+    ctext: &ThresholdCiphertext,
+    share: &DecryptionShare,
+) -> Result<()> {
+    let verification_key = enc_key.get_verification_key(share.index)?;
+    let proof = &share.proof;
+
+    let c = decryption_share_challenge(
+        share.index,
+        &ctext.e1,
+        verification_key,
+        &share.point,
+        &proof.commitment_base,
+        &proof.commitment_e1,
+    );
+
+    let base = BLSG1::get_base();
+    if base.mul(&proof.response) != proof.commitment_base.add(&verification_key.mul(&c)) {
+        return Err(CryptoError::ZKProofVerificationError);
+    }
+    if ctext.e1.mul(&proof.response) != proof.commitment_e1.add(&share.point.mul(&c)) {
+        return Err(CryptoError::ZKProofVerificationError);
+    }
+    Ok(())
+}
+
+/// Verify the decryption shares and combine the first `threshold` of them to decrypt the ciphertext.
+pub fn combine_decryption_shares(
+    enc_key: &ThresholdEncKey,
+    ctext: &ThresholdCiphertext,
+    shares: &[DecryptionShare],
+) -> Result<Vec<u8>> {
+    let threshold = enc_key.threshold as usize;
+    if shares.len() < threshold {
+        return Err(CryptoError::ParameterError);
+    }
+    let shares = &shares[..threshold];
+
+    let mut indices = shares.iter().map(|share| share.index).collect_vec();
+    indices.sort_unstable();
+    indices.dedup();
+    if indices.len() != threshold {
+        return Err(CryptoError::ParameterError);
+    }
+
+    for share in shares.iter() {
+        verify_decryption_share(enc_key, ctext, share)?;
+    }
+
+    // Lagrange interpolation at zero: s * e1 = \sum_i \lambda_i * s_i * e1.
+    let mut lambdas = Vec::with_capacity(threshold);
+    for share in shares.iter() {
+        let x_i = BLSScalar::from(share.index);
+        let mut numerator = BLSScalar::one();
+        let mut denominator = BLSScalar::one();
+        for other in shares.iter().filter(|other| other.index != share.index) {
+            let x_j = BLSScalar::from(other.index);
+            numerator.mul_assign(&x_j);
+            denominator.mul_assign(&x_j.sub(&x_i));
+        }
+        lambdas.push(numerator.mul(&denominator.inv()?));
+    }
+
+    let shared_point = BLSG1::multi_exp(
+        &lambdas.iter().collect_vec(),
+        &shares.iter().map(|share| &share.point).collect_vec(),
+    );
+    let key = symmetric_key_from_point(&shared_point);
+
+    Ok(symmetric_decrypt(&key, &ctext.ciphertext))
+}
+
+/// Derive the symmetric key from the shared point.
+fn symmetric_key_from_point(point: &BLSG1) -> [u8; 32] {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(point.to_compressed_bytes());
+    let mut key = [0u8; 32];
+    key.copy_from_slice(hasher.finalize().as_slice());
+    key
+}
+
+/// Compute the Fiat-Shamir challenge of the proof of a decryption share.
+fn decryption_share_challenge(
+    index: u32,
+    e1: &BLSG1,
+    verification_key: &BLSG1,
+    point: &BLSG1,
+    commitment_base: &BLSG1,
+    commitment_e1: &BLSG1,
+) -> BLSScalar {
+    let mut transcript = Transcript::new(DECRYPTION_SHARE_PROOF_DOMAIN);
+    transcript.append_u64(b"index", index as u64);
+    transcript.append_group_element(b"e1", e1);
+    transcript.append_group_element(b"verification key", verification_key);
+    transcript.append_group_element(b"decryption share", point);
+    transcript.append_proof_commitment(commitment_base);
+    transcript.append_proof_commitment(commitment_e1);
+    transcript.get_challenge::<BLSScalar>()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::errors::CryptoError;
+    use crate::threshold_encryption::{
+        combine_decryption_shares, threshold_decrypt_share, threshold_encrypt, threshold_key_gen,
+        verify_decryption_share,
+    };
+    use noah_algebra::{bls12_381::BLSG1, prelude::*};
+
+    #[test]
+    fn test_threshold_decryption() {
+        let mut prng = test_rng();
+        let (enc_key, key_shares) = threshold_key_gen(&mut prng, 3, 5).unwrap();
+        assert_eq!(enc_key.num_members(), 5);
+
+        let msg = b"the amount and the asset type";
+        let ctext = threshold_encrypt(&mut prng, &enc_key, msg);
+
+        let shares = key_shares
+            .iter()
+            .map(|key_share| threshold_decrypt_share(&mut prng, &ctext, key_share))
+            .collect_vec();
+        for share in shares.iter() {
+            verify_decryption_share(&enc_key, &ctext, share).unwrap();
+        }
+
+        // any three members can decrypt
+        let plaintext = combine_decryption_shares(
+            &enc_key,
+            &ctext,
+            &[shares[0].clone(), shares[2].clone(), shares[4].clone()],
+        )
+        .unwrap();
+        assert_eq!(plaintext.as_slice(), &msg[..]);
+        let plaintext = combine_decryption_shares(&enc_key, &ctext, &shares[1..4]).unwrap();
+        assert_eq!(plaintext.as_slice(), &msg[..]);
+
+        // two members cannot
+        assert_eq!(
+            combine_decryption_shares(&enc_key, &ctext, &shares[0..2]),
+            Err(CryptoError::ParameterError)
+        );
+
+        // the same member cannot be counted twice
+        assert_eq!(
+            combine_decryption_shares(
+                &enc_key,
+                &ctext,
+                &[shares[0].clone(), shares[0].clone(), shares[1].clone()]
+            ),
+            Err(CryptoError::ParameterError)
+        );
+
+        // a wrong decryption share is rejected
+        let mut bad_share = shares[3].clone();
+        bad_share.point = bad_share.point.add(&BLSG1::get_base());
+        assert_eq!(
+            verify_decryption_share(&enc_key, &ctext, &bad_share),
+            Err(CryptoError::ZKProofVerificationError)
+        );
+        assert!(combine_decryption_shares(
+            &enc_key,
+            &ctext,
+            &[shares[0].clone(), shares[1].clone(), bad_share]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_threshold_key_gen_parameters() {
+        let mut prng = test_rng();
+        assert!(threshold_key_gen(&mut prng, 0, 3).is_err());
+        assert!(threshold_key_gen(&mut prng, 4, 3).is_err());
+        assert!(threshold_key_gen(&mut prng, 1, 1).is_ok());
+    }
+}
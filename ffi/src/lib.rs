@@ -0,0 +1,534 @@
+//! The C bindings of the Noah library for mobile wallets.
+//!
+//! Key pairs and prover parameters are opaque handles that are released with their `_free`
+//! functions. Public keys are passed as bytes, records and memos as their JSON serialization,
+//! and notes as their MessagePack serialization, which is the format accepted by
+//! `noah::consensus::verify_note_bytes`. Every function returns `NOAH_OK` or an error code,
+//! and writes its results through the output pointers only on success.
+//!
+//! The header `include/noah_ffi.h` is generated by cbindgen when the crate is built.
+#![deny(unused_import_braces, unused_qualifications, trivial_casts)]
+#![deny(trivial_numeric_casts)]
+#![deny(stable_features, unreachable_pub, non_shorthand_field_patterns)]
+#![deny(unused_attributes, unused_imports, unused_mut, missing_docs)]
+#![deny(renamed_and_removed_lints, stable_features, unused_allocation)]
+#![deny(unused_comparisons, bare_trait_objects, unused_must_use)]
+#![deny(unsafe_op_in_unsafe_fn)]
+
+use noah::anon_xfr::{
+    ar_to_abar::gen_ar_to_abar_note,
+    bar_to_abar::gen_bar_to_abar_note,
+    structs::{AnonAssetRecord, AxfrOwnerMemo, OpenAnonAssetRecordBuilder},
+};
+use noah::keys::{KeyPair, PublicKey};
+use noah::parameters::{
+    params::ProverParams,
+    AddressFormat::{ED25519, SECP256K1},
+};
+use noah::xfr::{
+    asset_record::open_blind_asset_record,
+    structs::{BlindAssetRecord, OpenAssetRecord, OwnerMemo},
+};
+use noah_algebra::prelude::*;
+use rand_chacha::ChaChaRng;
+use serde::de::DeserializeOwned;
+use std::panic::{catch_unwind, UnwindSafe};
+use std::ptr;
+
+/// The call succeeded.
+pub const NOAH_OK: i32 = 0;
+/// A required pointer is null.
+pub const NOAH_ERR_NULL_POINTER: i32 = 1;
+/// An input cannot be deserialized.
+pub const NOAH_ERR_DESERIALIZATION: i32 = 2;
+/// An output cannot be serialized.
+pub const NOAH_ERR_SERIALIZATION: i32 = 3;
+/// The record cannot be opened with the key pair.
+pub const NOAH_ERR_NOT_OWNED: i32 = 4;
+/// The note cannot be built from the inputs.
+pub const NOAH_ERR_NOTE: i32 = 5;
+/// The parameters cannot be generated.
+pub const NOAH_ERR_PARAMETERS: i32 = 6;
+/// The call panicked.
+pub const NOAH_ERR_PANIC: i32 = 7;
+
+/// A byte buffer allocated by the library, which must be released with `noah_buffer_free`.
+#[repr(C)]
+pub struct NoahBuffer {
+    /// The pointer to the bytes.
+    pub data: *mut u8,
+    /// The number of bytes.
+    pub len: usize,
+}
+
+impl NoahBuffer {
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        let bytes = Box::leak(bytes.into_boxed_slice());
+        NoahBuffer {
+            data: bytes.as_mut_ptr(),
+            len: bytes.len(),
+        }
+    }
+}
+
+/// The opaque handle of a key pair.
+pub struct NoahKeyPair(KeyPair);
+
+/// The opaque handle of prover parameters.
+pub struct NoahProverParams(ProverParams);
+
+/// Run the body of an exported function, turning a panic into `NOAH_ERR_PANIC`.
+fn guard<F: FnOnce() -> Result<(), i32> + UnwindSafe>(f: F) -> i32 {
+    match catch_unwind(f) {
+        Ok(Ok(())) => NOAH_OK,
+        Ok(Err(code)) => code,
+        Err(_) => NOAH_ERR_PANIC,
+    }
+}
+
+/// Borrow the input bytes, where a null pointer is only allowed for an empty input.
+unsafe fn input<'a>(data: *const u8, len: usize) -> Result<&'a [u8], i32> {
+    if data.is_null() {
+        return if len == 0 {
+            Ok(&[])
+        } else {
+            Err(NOAH_ERR_NULL_POINTER)
+        };
+    }
+    // SAFETY: the caller guarantees that `data` points to `len` readable bytes.
+    Ok(unsafe { std::slice::from_raw_parts(data, len) })
+}
+
+/// Borrow the value behind a handle.
+unsafe fn handle<'a, T>(value: *const T) -> Result<&'a T, i32> {
+    // SAFETY: the caller guarantees that a non-null handle was returned by this library.
+    unsafe { value.as_ref() }.ok_or(NOAH_ERR_NULL_POINTER)
+}
+
+/// Write the output through the pointer.
+unsafe fn output<T>(out: *mut T, value: T) -> Result<(), i32> {
+    if out.is_null() {
+        return Err(NOAH_ERR_NULL_POINTER);
+    }
+    // SAFETY: the caller guarantees that a non-null `out` is writable.
+    unsafe { ptr::write(out, value) };
+    Ok(())
+}
+
+fn from_json<T: DeserializeOwned>(json: &[u8]) -> Result<T, i32> {
+    serde_json::from_slice(json).map_err(|_| NOAH_ERR_DESERIALIZATION)
+}
+
+/// Release a buffer returned by the library.
+///
+/// # Safety
+/// `buffer` must have been returned by this library and not released before.
+#[no_mangle]
+pub unsafe extern "C" fn noah_buffer_free(buffer: NoahBuffer) {
+    if !buffer.data.is_null() {
+        // SAFETY: the buffer was leaked from a boxed slice in `NoahBuffer::from_vec`.
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)) });
+    }
+}
+
+/// Generate a key pair from the randomness of the environment.
+///
+/// # Safety
+/// `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn noah_keypair_generate(secp256k1: bool, out: *mut *mut NoahKeyPair) -> i32 {
+    guard(|| {
+        let mut prng = ChaChaRng::from_entropy();
+        let address_format = if secp256k1 { SECP256K1 } else { ED25519 };
+        let keypair = KeyPair::sample(&mut prng, address_format);
+        // SAFETY: guaranteed by the caller.
+        unsafe { output(out, Box::into_raw(Box::new(NoahKeyPair(keypair)))) }
+    })
+}
+
+/// Restore a key pair from its serialization.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn noah_keypair_from_bytes(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut NoahKeyPair,
+) -> i32 {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let bytes = unsafe { input(data, len) }?;
+        let keypair = KeyPair::noah_from_bytes(bytes).map_err(|_| NOAH_ERR_DESERIALIZATION)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { output(out, Box::into_raw(Box::new(NoahKeyPair(keypair)))) }
+    })
+}
+
+/// Return the serialization of the key pair.
+///
+/// # Safety
+/// `keypair` must be a handle returned by this library, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn noah_keypair_to_bytes(
+    keypair: *const NoahKeyPair,
+    out: *mut NoahBuffer,
+) -> i32 {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let keypair = unsafe { handle(keypair) }?;
+        let bytes = NoahBuffer::from_vec(keypair.0.noah_to_bytes());
+        // SAFETY: guaranteed by the caller.
+        unsafe { output(out, bytes) }
+    })
+}
+
+/// Return the serialization of the public key of the key pair.
+///
+/// # Safety
+/// `keypair` must be a handle returned by this library, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn noah_keypair_public_key(
+    keypair: *const NoahKeyPair,
+    out: *mut NoahBuffer,
+) -> i32 {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let keypair = unsafe { handle(keypair) }?;
+        let bytes = NoahBuffer::from_vec(keypair.0.get_pk().noah_to_bytes());
+        // SAFETY: guaranteed by the caller.
+        unsafe { output(out, bytes) }
+    })
+}
+
+/// Release a key pair.
+///
+/// # Safety
+/// `keypair` must be null or a handle returned by this library and not released before.
+#[no_mangle]
+pub unsafe extern "C" fn noah_keypair_free(keypair: *mut NoahKeyPair) {
+    if !keypair.is_null() {
+        // SAFETY: the handle was created by `Box::into_raw`.
+        drop(unsafe { Box::from_raw(keypair) });
+    }
+}
+
+/// Open a confidential or transparent record, given as JSON, with its owner memo, if any,
+/// and write its amount and its 32-byte asset type.
+///
+/// # Safety
+/// The inputs must point to readable bytes of their lengths, where the owner memo may be null,
+/// `keypair` must be a handle returned by this library, `amount` must be valid for writes,
+/// and `asset_type` must be valid for writes of 32 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noah_open_blind_asset_record(
+    record: *const u8,
+    record_len: usize,
+    owner_memo: *const u8,
+    owner_memo_len: usize,
+    keypair: *const NoahKeyPair,
+    amount: *mut u64,
+    asset_type: *mut [u8; 32],
+) -> i32 {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let (record, owner_memo, keypair) = unsafe {
+            (
+                input(record, record_len)?,
+                input(owner_memo, owner_memo_len)?,
+                handle(keypair)?,
+            )
+        };
+        let oar = open_record(record, owner_memo, &keypair.0)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe {
+            output(amount, *oar.get_amount())?;
+            output(asset_type, oar.get_asset_type().0)
+        }
+    })
+}
+
+fn open_record(
+    record: &[u8],
+    owner_memo: &[u8],
+    keypair: &KeyPair,
+) -> Result<OpenAssetRecord, i32> {
+    let record: BlindAssetRecord = from_json(record)?;
+    let owner_memo: Option<OwnerMemo> = if owner_memo.is_empty() {
+        None
+    } else {
+        Some(from_json(owner_memo)?)
+    };
+    open_blind_asset_record(&record, &owner_memo, keypair).map_err(|_| NOAH_ERR_NOT_OWNED)
+}
+
+/// Check whether an anonymous record, given as JSON with its owner memo, belongs to the key pair,
+/// and write its amount and its 32-byte asset type. A record of another owner gives
+/// `NOAH_ERR_NOT_OWNED`, so that a wallet can scan the records of a block one by one.
+///
+/// # Safety
+/// The inputs must point to readable bytes of their lengths, `keypair` must be a handle
+/// returned by this library, `amount` must be valid for writes, and `asset_type` must be
+/// valid for writes of 32 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noah_scan_anon_asset_record(
+    abar: *const u8,
+    abar_len: usize,
+    owner_memo: *const u8,
+    owner_memo_len: usize,
+    keypair: *const NoahKeyPair,
+    amount: *mut u64,
+    asset_type: *mut [u8; 32],
+) -> i32 {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let (abar, owner_memo, keypair) = unsafe {
+            (
+                input(abar, abar_len)?,
+                input(owner_memo, owner_memo_len)?,
+                handle(keypair)?,
+            )
+        };
+        let abar: AnonAssetRecord = from_json(abar)?;
+        let owner_memo: AxfrOwnerMemo = from_json(owner_memo)?;
+        let oabar = OpenAnonAssetRecordBuilder::from_abar(&abar, owner_memo, &keypair.0)
+            .and_then(|builder| builder.build())
+            .map_err(|_| NOAH_ERR_NOT_OWNED)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe {
+            output(amount, oabar.get_amount())?;
+            output(asset_type, oabar.get_asset_type().0)
+        }
+    })
+}
+
+/// Generate the prover parameters of the notes converting a transparent record into
+/// an anonymous record. This is slow, and the handle should be kept for the later notes.
+///
+/// # Safety
+/// `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn noah_prover_params_ar_to_abar(out: *mut *mut NoahProverParams) -> i32 {
+    guard(|| {
+        let params = ProverParams::gen_ar_to_abar().map_err(|_| NOAH_ERR_PARAMETERS)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { output(out, Box::into_raw(Box::new(NoahProverParams(params)))) }
+    })
+}
+
+/// Generate the prover parameters of the notes converting a confidential record into
+/// an anonymous record. This is slow, and the handle should be kept for the later notes.
+///
+/// # Safety
+/// `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn noah_prover_params_bar_to_abar(out: *mut *mut NoahProverParams) -> i32 {
+    guard(|| {
+        let params = ProverParams::gen_bar_to_abar().map_err(|_| NOAH_ERR_PARAMETERS)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { output(out, Box::into_raw(Box::new(NoahProverParams(params)))) }
+    })
+}
+
+/// Release prover parameters.
+///
+/// # Safety
+/// `params` must be null or a handle returned by this library and not released before.
+#[no_mangle]
+pub unsafe extern "C" fn noah_prover_params_free(params: *mut NoahProverParams) {
+    if !params.is_null() {
+        // SAFETY: the handle was created by `Box::into_raw`.
+        drop(unsafe { Box::from_raw(params) });
+    }
+}
+
+/// Build the note converting a record of the key pair, given as JSON with its owner memo, if any,
+/// into an anonymous record for the public key, and write its MessagePack serialization.
+/// A transparent record needs the parameters of `noah_prover_params_ar_to_abar`,
+/// and a confidential record those of `noah_prover_params_bar_to_abar`.
+///
+/// # Safety
+/// The inputs must point to readable bytes of their lengths, where the owner memo may be null,
+/// `params` and `keypair` must be handles returned by this library, and `out` must be valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn noah_build_to_abar_note(
+    params: *const NoahProverParams,
+    record: *const u8,
+    record_len: usize,
+    owner_memo: *const u8,
+    owner_memo_len: usize,
+    keypair: *const NoahKeyPair,
+    public_key: *const u8,
+    public_key_len: usize,
+    out: *mut NoahBuffer,
+) -> i32 {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let (params, record, owner_memo, keypair, public_key) = unsafe {
+            (
+                handle(params)?,
+                input(record, record_len)?,
+                input(owner_memo, owner_memo_len)?,
+                handle(keypair)?,
+                input(public_key, public_key_len)?,
+            )
+        };
+        let oar = open_record(record, owner_memo, &keypair.0)?;
+        let public_key =
+            PublicKey::noah_from_bytes(public_key).map_err(|_| NOAH_ERR_DESERIALIZATION)?;
+
+        let mut prng = ChaChaRng::from_entropy();
+        let record_type = oar.get_record_type();
+        let note = if record_type.is_confidential_amount()
+            || record_type.is_confidential_asset_type()
+        {
+            let note = gen_bar_to_abar_note(&mut prng, &params.0, &oar, &keypair.0, &public_key)
+                .map_err(|_| NOAH_ERR_NOTE)?;
+            rmp_serde::to_vec(&note)
+        } else {
+            let note = gen_ar_to_abar_note(&mut prng, &params.0, &oar, &keypair.0, &public_key)
+                .map_err(|_| NOAH_ERR_NOTE)?;
+            rmp_serde::to_vec(&note)
+        }
+        .map_err(|_| NOAH_ERR_SERIALIZATION)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { output(out, NoahBuffer::from_vec(note)) }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        noah_buffer_free, noah_keypair_free, noah_keypair_from_bytes, noah_keypair_generate,
+        noah_keypair_public_key, noah_keypair_to_bytes, noah_open_blind_asset_record,
+        noah_scan_anon_asset_record, NoahBuffer, NoahKeyPair, NOAH_ERR_NOT_OWNED,
+        NOAH_ERR_NULL_POINTER, NOAH_OK,
+    };
+    use noah::anon_xfr::structs::{AnonAssetRecord, OpenAnonAssetRecordBuilder};
+    use noah::keys::KeyPair;
+    use noah::xfr::{
+        asset_record::{build_blind_asset_record, AssetRecordType},
+        structs::{AssetRecordTemplate, AssetType},
+    };
+    use noah_algebra::{prelude::*, ristretto::PedersenCommitmentRistretto};
+    use std::ptr;
+
+    fn empty_buffer() -> NoahBuffer {
+        NoahBuffer {
+            data: ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    #[test]
+    fn test_keypair() {
+        unsafe {
+            let mut keypair: *mut NoahKeyPair = ptr::null_mut();
+            assert_eq!(noah_keypair_generate(true, &mut keypair), NOAH_OK);
+
+            let mut bytes = empty_buffer();
+            assert_eq!(noah_keypair_to_bytes(keypair, &mut bytes), NOAH_OK);
+            let mut restored: *mut NoahKeyPair = ptr::null_mut();
+            assert_eq!(
+                noah_keypair_from_bytes(bytes.data, bytes.len, &mut restored),
+                NOAH_OK
+            );
+            assert_eq!((*keypair).0, (*restored).0);
+
+            let mut public_key = empty_buffer();
+            assert_eq!(noah_keypair_public_key(restored, &mut public_key), NOAH_OK);
+            assert_eq!(
+                std::slice::from_raw_parts(public_key.data, public_key.len),
+                &(*keypair).0.get_pk().noah_to_bytes()[..]
+            );
+
+            assert_eq!(
+                noah_keypair_public_key(ptr::null(), &mut public_key),
+                NOAH_ERR_NULL_POINTER
+            );
+
+            noah_buffer_free(bytes);
+            noah_buffer_free(public_key);
+            noah_keypair_free(keypair);
+            noah_keypair_free(restored);
+        }
+    }
+
+    #[test]
+    fn test_open_records() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, noah::parameters::AddressFormat::ED25519);
+        let other = KeyPair::sample(&mut prng, noah::parameters::AddressFormat::SECP256K1);
+        let (keypair, other) = (NoahKeyPair(keypair), NoahKeyPair(other));
+
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            AssetType::from_identical_byte(1),
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+            keypair.0.get_pk(),
+        );
+        let pc_gens = PedersenCommitmentRistretto::default();
+        let (record, _, owner_memo) =
+            build_blind_asset_record(&mut prng, &pc_gens, &template, vec![]);
+        let record = serde_json::to_vec(&record).unwrap();
+        let owner_memo = serde_json::to_vec(&owner_memo.unwrap()).unwrap();
+
+        let oabar = OpenAnonAssetRecordBuilder::new()
+            .amount(20)
+            .asset_type(AssetType::from_identical_byte(2))
+            .pub_key(&keypair.0.get_pk())
+            .finalize(&mut prng)
+            .unwrap()
+            .build()
+            .unwrap();
+        let abar = serde_json::to_vec(&AnonAssetRecord::from_oabar(&oabar)).unwrap();
+        let abar_memo = serde_json::to_vec(&oabar.get_owner_memo().unwrap()).unwrap();
+
+        let mut amount = 0u64;
+        let mut asset_type = [0u8; 32];
+        unsafe {
+            assert_eq!(
+                noah_open_blind_asset_record(
+                    record.as_ptr(),
+                    record.len(),
+                    owner_memo.as_ptr(),
+                    owner_memo.len(),
+                    &keypair,
+                    &mut amount,
+                    &mut asset_type,
+                ),
+                NOAH_OK
+            );
+            assert_eq!((amount, asset_type), (10, [1u8; 32]));
+
+            assert_eq!(
+                noah_scan_anon_asset_record(
+                    abar.as_ptr(),
+                    abar.len(),
+                    abar_memo.as_ptr(),
+                    abar_memo.len(),
+                    &keypair,
+                    &mut amount,
+                    &mut asset_type,
+                ),
+                NOAH_OK
+            );
+            assert_eq!((amount, asset_type), (20, [2u8; 32]));
+
+            // the records of another owner are skipped
+            assert_eq!(
+                noah_scan_anon_asset_record(
+                    abar.as_ptr(),
+                    abar.len(),
+                    abar_memo.as_ptr(),
+                    abar_memo.len(),
+                    &other,
+                    &mut amount,
+                    &mut asset_type,
+                ),
+                NOAH_ERR_NOT_OWNED
+            );
+        }
+    }
+}
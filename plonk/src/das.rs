@@ -0,0 +1,293 @@
+use crate::errors::{PlonkError, Result};
+use crate::kzg::KZGParams;
+use crate::poly_commit::{
+    field_polynomial::FpPolynomial,
+    kzg_poly_com::{KZGCommitment, KZGOpenProof},
+};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use noah_algebra::{
+    prelude::*,
+    traits::{Domain, Pairing},
+};
+
+/// The commitment to an erasure-coded blob. The blob is split into chunks, which are the
+/// evaluations of the committed polynomial over a domain, and the polynomial is extended
+/// to twice as many evaluations, so that any half of them recovers the blob.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobCommitment<G> {
+    /// The commitment to the polynomial.
+    pub commitment: KZGCommitment<G>,
+    /// The number of chunks of the blob, a power of two.
+    pub num_chunks: u64,
+    /// The length of the blob in bytes.
+    pub len: u64,
+}
+
+impl<G> BlobCommitment<G> {
+    /// Return the number of evaluations in the extended blob, which are indexed for sampling.
+    pub fn num_samples(&self) -> u64 {
+        2 * self.num_chunks
+    }
+}
+
+/// The erasure-coded blob, as kept by the nodes that serve the samples.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodedBlob<F> {
+    /// The coefficients of the polynomial.
+    pub coefs: Vec<F>,
+    /// The evaluations of the polynomial over the extended domain.
+    pub extended: Vec<F>,
+}
+
+/// An evaluation of the extended blob with the proof that it is committed at the index.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobSample<G, F> {
+    /// The index in the extended blob.
+    pub index: u64,
+    /// The evaluation.
+    pub value: F,
+    /// The proof of the evaluation.
+    pub proof: KZGOpenProof<G>,
+}
+
+/// Return the number of bytes of the blob held in one chunk, so that each chunk is a field element.
+fn chunk_len<F: Scalar>() -> usize {
+    F::capacity() / 8
+}
+
+/// Return the domain with the size.
+fn domain<F: Domain>(size: u64) -> Result<Radix2EvaluationDomain<F::Field>> {
+    let size = usize::try_from(size).map_err(|_| PlonkError::DegreeError)?;
+    if !size.is_power_of_two() {
+        return Err(PlonkError::FuncParamsError);
+    }
+    FpPolynomial::<F>::evaluation_domain(size).ok_or(PlonkError::DegreeError)
+}
+
+impl<P: Pairing> KZGParams<P> {
+    /// Encode the blob with the Reed-Solomon code of rate 1/2 and commit to it.
+    pub fn encode_blob(
+        &self,
+        blob: &[u8],
+    ) -> Result<(BlobCommitment<P::G1>, EncodedBlob<P::ScalarField>)> {
+        let chunk_len = chunk_len::<P::ScalarField>();
+        let num_chunks = ((blob.len() + chunk_len - 1) / chunk_len)
+            .max(1)
+            .next_power_of_two();
+        if num_chunks > self.max_degree() + 1 {
+            return Err(PlonkError::DegreeError);
+        }
+
+        let mut chunks = blob
+            .chunks(chunk_len)
+            .map(P::ScalarField::from_bytes)
+            .collect::<core::result::Result<Vec<_>, _>>()?;
+        chunks.resize(num_chunks, P::ScalarField::zero());
+
+        let polynomial =
+            FpPolynomial::ifft_with_domain(&domain::<P::ScalarField>(num_chunks as u64)?, &chunks);
+        let extended =
+            polynomial.fft_with_domain(&domain::<P::ScalarField>(2 * num_chunks as u64)?);
+
+        let commitment = BlobCommitment {
+            commitment: self.commit(&polynomial.coefs)?,
+            num_chunks: num_chunks as u64,
+            len: blob.len() as u64,
+        };
+        let encoded = EncodedBlob {
+            coefs: polynomial.coefs,
+            extended,
+        };
+        Ok((commitment, encoded))
+    }
+
+    /// Prove the evaluation of the extended blob at the index.
+    pub fn prove_sample(
+        &self,
+        encoded: &EncodedBlob<P::ScalarField>,
+        index: u64,
+    ) -> Result<BlobSample<P::G1, P::ScalarField>> {
+        let value = *encoded
+            .extended
+            .get(usize::try_from(index).map_err(|_| PlonkError::FuncParamsError)?)
+            .ok_or(PlonkError::FuncParamsError)?;
+        let point = P::ScalarField::from_field(
+            domain::<P::ScalarField>(encoded.extended.len() as u64)?.element(index as usize),
+        );
+        let (_, proof) = self.open(&encoded.coefs, &point)?;
+        Ok(BlobSample {
+            index,
+            value,
+            proof,
+        })
+    }
+
+    /// Check that the sample is the evaluation of the committed extended blob at its index.
+    pub fn verify_sample(
+        &self,
+        commitment: &BlobCommitment<P::G1>,
+        sample: &BlobSample<P::G1, P::ScalarField>,
+    ) -> Result<()> {
+        if sample.index >= commitment.num_samples() {
+            return Err(PlonkError::FuncParamsError);
+        }
+        let point = P::ScalarField::from_field(
+            domain::<P::ScalarField>(commitment.num_samples())?.element(sample.index as usize),
+        );
+        self.verify(&commitment.commitment, &point, &sample.value, &sample.proof)
+    }
+
+    /// Check the availability of the blob by fetching and verifying the samples at `num_samples`
+    /// random indices. If less than half of the extended blob is available, each sample is missing
+    /// with probability at least 1/2, so the check passes with probability at most 2^-num_samples.
+    pub fn check_availability<R, S>(
+        &self,
+        prng: &mut R,
+        commitment: &BlobCommitment<P::G1>,
+        num_samples: usize,
+        mut fetch: S,
+    ) -> Result<()>
+    where
+        R: CryptoRng + RngCore,
+        S: FnMut(u64) -> Option<BlobSample<P::G1, P::ScalarField>>,
+    {
+        for _ in 0..num_samples {
+            let index = prng.next_u64() % commitment.num_samples();
+            let sample = fetch(index).ok_or(PlonkError::VerificationError)?;
+            if sample.index != index {
+                return Err(PlonkError::VerificationError);
+            }
+            self.verify_sample(commitment, &sample)?;
+        }
+        Ok(())
+    }
+
+    /// Recover the blob from any half of the samples of the extended blob.
+    /// The samples are verified, and the duplicates are ignored.
+    pub fn reconstruct_blob(
+        &self,
+        commitment: &BlobCommitment<P::G1>,
+        samples: &[BlobSample<P::G1, P::ScalarField>],
+    ) -> Result<Vec<u8>> {
+        let extended_domain = domain::<P::ScalarField>(commitment.num_samples())?;
+        let num_chunks = commitment.num_chunks as usize;
+
+        let mut points = Vec::with_capacity(num_chunks);
+        let mut values = Vec::with_capacity(num_chunks);
+        let mut indices = Vec::with_capacity(num_chunks);
+        for sample in samples.iter() {
+            if indices.len() == num_chunks {
+                break;
+            }
+            if indices.contains(&sample.index) {
+                continue;
+            }
+            self.verify_sample(commitment, sample)?;
+            indices.push(sample.index);
+            points.push(P::ScalarField::from_field(
+                extended_domain.element(sample.index as usize),
+            ));
+            values.push(sample.value);
+        }
+        if indices.len() < num_chunks {
+            return Err(PlonkError::FuncParamsError);
+        }
+
+        // the Lagrange interpolation over the points of the samples
+        let vanishing = FpPolynomial::from_zeroes(&points);
+        let mut polynomial = FpPolynomial::zero();
+        for (point, value) in points.iter().zip(values.iter()) {
+            let (basis, _) = vanishing.div_rem(&FpPolynomial::from_zeroes(&[*point]));
+            let denominator = basis.eval(point).inv()?;
+            polynomial.add_assign(&basis.mul_scalar(&value.mul(&denominator)));
+        }
+
+        let chunk_len = chunk_len::<P::ScalarField>();
+        let chunks = polynomial.fft_with_domain(&domain::<P::ScalarField>(num_chunks as u64)?);
+        let mut blob = Vec::with_capacity(num_chunks * chunk_len);
+        for chunk in chunks.iter() {
+            blob.extend_from_slice(&chunk.to_bytes()[..chunk_len]);
+        }
+        let len = usize::try_from(commitment.len).map_err(|_| PlonkError::DegreeError)?;
+        if len > blob.len() {
+            return Err(PlonkError::FuncParamsError);
+        }
+        blob.truncate(len);
+        Ok(blob)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::kzg::KZGParams;
+    use crate::poly_commit::kzg_poly_com::KZGCommitmentSchemeBLS;
+    use noah_algebra::{
+        bls12_381::{BLSPairingEngine, BLSScalar},
+        prelude::*,
+    };
+
+    #[test]
+    fn test_data_availability_sampling() {
+        let mut prng = test_rng();
+        let params =
+            KZGParams::<BLSPairingEngine>::from_srs(KZGCommitmentSchemeBLS::new(8, &mut prng))
+                .unwrap();
+        let verifier_params = params.verifier_params();
+
+        let mut blob = vec![0u8; 100];
+        prng.fill_bytes(&mut blob);
+        let (commitment, encoded) = params.encode_blob(&blob).unwrap();
+        assert_eq!(commitment.num_chunks, 4);
+        assert_eq!(commitment.num_samples(), 8);
+
+        let samples = (0..8)
+            .map(|index| params.prove_sample(&encoded, index).unwrap())
+            .collect::<Vec<_>>();
+        for sample in samples.iter() {
+            assert!(verifier_params.verify_sample(&commitment, sample).is_ok());
+        }
+        let mut wrong_sample = samples[0].clone();
+        wrong_sample.value = wrong_sample.value + BLSScalar::one();
+        assert!(verifier_params
+            .verify_sample(&commitment, &wrong_sample)
+            .is_err());
+
+        // the availability check passes when the samples are served, and fails when they are not
+        assert!(verifier_params
+            .check_availability(&mut prng, &commitment, 10, |index| Some(
+                samples[index as usize].clone()
+            ))
+            .is_ok());
+        assert!(verifier_params
+            .check_availability(&mut prng, &commitment, 10, |index| if index < 4 {
+                Some(samples[index as usize].clone())
+            } else {
+                None
+            })
+            .is_err());
+
+        // any half of the samples recovers the blob, and fewer do not
+        let half = [
+            &samples[1],
+            &samples[3],
+            &samples[3],
+            &samples[6],
+            &samples[7],
+        ]
+        .iter()
+        .map(|sample| (*sample).clone())
+        .collect::<Vec<_>>();
+        assert_eq!(
+            verifier_params
+                .reconstruct_blob(&commitment, &half)
+                .unwrap(),
+            blob
+        );
+        assert!(verifier_params
+            .reconstruct_blob(&commitment, &half[..3])
+            .is_err());
+
+        // the blobs beyond the degree are rejected
+        assert!(params.encode_blob(&[0u8; 1000]).is_err());
+    }
+}
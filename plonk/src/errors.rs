@@ -34,6 +34,8 @@ pub enum PlonkError {
     PCSProveEvalError,
     /// The degree of the polynomial is higher than the maximum degree supported.
     DegreeError,
+    /// The parameters were generated for a different circuit.
+    ParamsMismatch,
 }
 
 impl fmt::Display for PlonkError {
@@ -56,6 +58,7 @@ impl fmt::Display for PlonkError {
             DegreeError => {
                 "The degree of the polynomial is higher than the maximum degree supported."
             }
+            ParamsMismatch => "The parameters were generated for a different circuit.",
         })
     }
 }
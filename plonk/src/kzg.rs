@@ -0,0 +1,342 @@
+use crate::errors::{PlonkError, Result};
+use crate::poly_commit::{
+    field_polynomial::FpPolynomial,
+    kzg_poly_com::{KZGCommitment, KZGCommitmentScheme, KZGOpenProof},
+    pcs::PolyComScheme,
+};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use merlin::Transcript;
+use noah_algebra::{
+    prelude::*,
+    traits::{Domain, Pairing},
+};
+
+/// The parameters for committing to polynomials up to a degree and checking their openings.
+/// They are taken from an SRS, e.g., the one distributed for the circuits, so that applications
+/// can commit to their own data without another setup.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KZGParams<P: Pairing> {
+    /// The SRS, which only keeps the first element of group 1 for the verifier.
+    srs: KZGCommitmentScheme<P>,
+    /// The maximal degree of the polynomials.
+    max_degree: usize,
+}
+
+/// The commitment to a vector, whose entries are the evaluations of the committed polynomial
+/// over the smallest power-of-two domain that holds the vector.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KZGVectorCommitment<G> {
+    /// The commitment to the polynomial.
+    pub commitment: KZGCommitment<G>,
+    /// The length of the vector.
+    pub len: u64,
+}
+
+impl<P: Pairing> KZGParams<P> {
+    /// Take the parameters from the SRS.
+    pub fn from_srs(srs: KZGCommitmentScheme<P>) -> Result<Self> {
+        if srs.public_parameter_group_1.is_empty() || srs.public_parameter_group_2.len() < 2 {
+            return Err(PlonkError::SetupError);
+        }
+        let max_degree = srs.public_parameter_group_1.len() - 1;
+        Ok(Self { srs, max_degree })
+    }
+
+    /// Take the parameters from the SRS in the unchecked bytes in which it is distributed.
+    pub fn from_srs_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_srs(KZGCommitmentScheme::from_unchecked_bytes(bytes)?)
+    }
+
+    /// Keep the part of the parameters for polynomials up to the degree.
+    pub fn trim(&self, max_degree: usize) -> Result<Self> {
+        if max_degree >= self.srs.public_parameter_group_1.len() {
+            return Err(PlonkError::DegreeError);
+        }
+        Ok(Self {
+            srs: KZGCommitmentScheme {
+                public_parameter_group_1: self.srs.public_parameter_group_1[..=max_degree].to_vec(),
+                public_parameter_group_2: self.srs.public_parameter_group_2[..2].to_vec(),
+            },
+            max_degree,
+        })
+    }
+
+    /// Keep the part of the parameters for checking the openings, which is of constant size.
+    pub fn verifier_params(&self) -> Self {
+        Self {
+            srs: self.srs.shrink_to_verifier_only(),
+            max_degree: self.max_degree,
+        }
+    }
+
+    /// Return the maximal degree of the polynomials.
+    pub fn max_degree(&self) -> usize {
+        self.max_degree
+    }
+
+    /// Commit to the polynomial with the coefficients, from the lowest degree.
+    pub fn commit(&self, coefs: &[P::ScalarField]) -> Result<KZGCommitment<P::G1>> {
+        if coefs.len() > self.max_degree + 1 {
+            return Err(PlonkError::DegreeError);
+        }
+        self.srs.commit(&FpPolynomial::from_coefs(coefs.to_vec()))
+    }
+
+    /// Open the polynomial with the coefficients at the point, and return the value with the proof.
+    pub fn open(
+        &self,
+        coefs: &[P::ScalarField],
+        point: &P::ScalarField,
+    ) -> Result<(P::ScalarField, KZGOpenProof<P::G1>)> {
+        let polynomial = FpPolynomial::from_coefs(coefs.to_vec());
+        let value = polynomial.eval(point);
+        let proof = self.srs.prove(&polynomial, point, self.max_degree)?;
+        Ok((value, KZGOpenProof(proof.0)))
+    }
+
+    /// Check that the committed polynomial takes the value at the point.
+    pub fn verify(
+        &self,
+        commitment: &KZGCommitment<P::G1>,
+        point: &P::ScalarField,
+        value: &P::ScalarField,
+        proof: &KZGOpenProof<P::G1>,
+    ) -> Result<()> {
+        self.srs.verify(
+            commitment,
+            self.max_degree,
+            point,
+            value,
+            &KZGCommitment(proof.0),
+        )
+    }
+
+    /// Open the polynomials with the coefficients at the same point with one proof,
+    /// and return the values with the proof. The transcript must be the same for `batch_verify`.
+    pub fn batch_open(
+        &self,
+        transcript: &mut Transcript,
+        polys_coefs: &[&[P::ScalarField]],
+        point: &P::ScalarField,
+    ) -> Result<(Vec<P::ScalarField>, KZGOpenProof<P::G1>)> {
+        if polys_coefs.is_empty() {
+            return Err(PlonkError::FuncParamsError);
+        }
+        let polys = polys_coefs
+            .iter()
+            .map(|coefs| FpPolynomial::from_coefs(coefs.to_vec()))
+            .collect::<Vec<_>>();
+        if polys.iter().any(|poly| poly.degree() > self.max_degree) {
+            return Err(PlonkError::DegreeError);
+        }
+
+        let values = polys.iter().map(|poly| poly.eval(point)).collect();
+        let polys_ref = polys.iter().collect::<Vec<_>>();
+        let proof = self
+            .srs
+            .batch_prove(transcript, None, &polys_ref, point, self.max_degree)?;
+        Ok((values, KZGOpenProof(proof.0)))
+    }
+
+    /// Check that the committed polynomials take the values at the same point.
+    pub fn batch_verify(
+        &self,
+        transcript: &mut Transcript,
+        commitments: &[&KZGCommitment<P::G1>],
+        point: &P::ScalarField,
+        values: &[P::ScalarField],
+        proof: &KZGOpenProof<P::G1>,
+    ) -> Result<()> {
+        if commitments.is_empty() || commitments.len() != values.len() {
+            return Err(PlonkError::FuncParamsError);
+        }
+        self.srs.batch_verify(
+            transcript,
+            commitments,
+            self.max_degree,
+            point,
+            values,
+            &KZGCommitment(proof.0),
+        )
+    }
+
+    /// Return the domain over which a vector of the length is interpolated.
+    fn vector_domain(
+        &self,
+        len: usize,
+    ) -> Result<Radix2EvaluationDomain<<P::ScalarField as Domain>::Field>> {
+        let size = len.max(1).next_power_of_two();
+        if len == 0 || size > self.max_degree + 1 {
+            return Err(PlonkError::DegreeError);
+        }
+        FpPolynomial::<P::ScalarField>::evaluation_domain(size).ok_or(PlonkError::SetupError)
+    }
+
+    /// Return the coefficients of the polynomial that takes the entries of the vector,
+    /// padded with zeroes, over the domain.
+    fn vector_coefs(&self, values: &[P::ScalarField]) -> Result<Vec<P::ScalarField>> {
+        let domain = self.vector_domain(values.len())?;
+        let mut values = values.to_vec();
+        values.resize(domain.size(), P::ScalarField::zero());
+        Ok(FpPolynomial::ifft_with_domain(&domain, &values).coefs)
+    }
+
+    /// Commit to the vector, so that each entry can be opened on its own.
+    pub fn commit_vector(&self, values: &[P::ScalarField]) -> Result<KZGVectorCommitment<P::G1>> {
+        let coefs = self.vector_coefs(values)?;
+        Ok(KZGVectorCommitment {
+            commitment: self.commit(&coefs)?,
+            len: values.len() as u64,
+        })
+    }
+
+    /// Open the entry of the vector at the index.
+    pub fn open_vector(
+        &self,
+        values: &[P::ScalarField],
+        index: usize,
+    ) -> Result<KZGOpenProof<P::G1>> {
+        if index >= values.len() {
+            return Err(PlonkError::FuncParamsError);
+        }
+        let point = P::ScalarField::from_field(self.vector_domain(values.len())?.element(index));
+        let (_, proof) = self.open(&self.vector_coefs(values)?, &point)?;
+        Ok(proof)
+    }
+
+    /// Check that the entry of the committed vector at the index is the value.
+    /// The point of the opening is fixed by the index, so that the proof binds the value
+    /// to the position as well.
+    pub fn verify_vector(
+        &self,
+        commitment: &KZGVectorCommitment<P::G1>,
+        index: usize,
+        value: &P::ScalarField,
+        proof: &KZGOpenProof<P::G1>,
+    ) -> Result<()> {
+        if index as u64 >= commitment.len {
+            return Err(PlonkError::FuncParamsError);
+        }
+        let len = usize::try_from(commitment.len).map_err(|_| PlonkError::DegreeError)?;
+        let point = P::ScalarField::from_field(self.vector_domain(len)?.element(index));
+        self.verify(&commitment.commitment, &point, value, proof)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::kzg::KZGParams;
+    use crate::poly_commit::{
+        kzg_poly_com::{KZGCommitment, KZGCommitmentSchemeBN254, KZGOpenProof},
+        pcs::ToBytes,
+    };
+    use merlin::Transcript;
+    use noah_algebra::{
+        bn254::{BN254PairingEngine, BN254Scalar},
+        prelude::*,
+    };
+
+    #[test]
+    fn test_kzg_params() {
+        let mut prng = test_rng();
+        let srs = KZGCommitmentSchemeBN254::new(16, &mut prng);
+        let params =
+            KZGParams::<BN254PairingEngine>::from_srs_bytes(&srs.to_unchecked_bytes().unwrap())
+                .unwrap()
+                .trim(8)
+                .unwrap();
+        let verifier_params = params.verifier_params();
+        assert_eq!(verifier_params.max_degree(), 8);
+
+        let data = (0..9u32).map(BN254Scalar::from).collect::<Vec<_>>();
+        let commitment = params.commit(&data).unwrap();
+        let point = BN254Scalar::random(&mut prng);
+        let (value, proof) = params.open(&data, &point).unwrap();
+        assert!(verifier_params
+            .verify(&commitment, &point, &value, &proof)
+            .is_ok());
+        assert!(verifier_params
+            .verify(&commitment, &point, &(value + BN254Scalar::one()), &proof)
+            .is_err());
+
+        // the commitments and the proofs are serialized in their compressed forms
+        let commitment = KZGCommitment::from_compressed_bytes(&commitment.to_bytes()).unwrap();
+        let proof = KZGOpenProof::from_compressed_bytes(&proof.to_bytes()).unwrap();
+        assert!(verifier_params
+            .verify(&commitment, &point, &value, &proof)
+            .is_ok());
+
+        // the data beyond the degree are rejected
+        assert!(params
+            .commit(&[data.clone(), data.clone()].concat())
+            .is_err());
+
+        let other_data = (9..13u32).map(BN254Scalar::from).collect::<Vec<_>>();
+        let other_commitment = params.commit(&other_data).unwrap();
+        let (values, proof) = params
+            .batch_open(
+                &mut Transcript::new(b"Test"),
+                &[&data[..], &other_data[..]],
+                &point,
+            )
+            .unwrap();
+        assert_eq!(values[0], value);
+        assert!(verifier_params
+            .batch_verify(
+                &mut Transcript::new(b"Test"),
+                &[&commitment, &other_commitment],
+                &point,
+                &values,
+                &proof,
+            )
+            .is_ok());
+        assert!(verifier_params
+            .batch_verify(
+                &mut Transcript::new(b"Test"),
+                &[&other_commitment, &commitment],
+                &point,
+                &values,
+                &proof,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_kzg_vector_commitment() {
+        let mut prng = test_rng();
+        let params =
+            KZGParams::<BN254PairingEngine>::from_srs(KZGCommitmentSchemeBN254::new(16, &mut prng))
+                .unwrap();
+        let verifier_params = params.verifier_params();
+
+        let values = (0..5)
+            .map(|_| BN254Scalar::random(&mut prng))
+            .collect::<Vec<_>>();
+        let commitment = params.commit_vector(&values).unwrap();
+        assert_eq!(commitment.len, 5);
+
+        for (index, value) in values.iter().enumerate() {
+            let proof = params.open_vector(&values, index).unwrap();
+            assert!(verifier_params
+                .verify_vector(&commitment, index, value, &proof)
+                .is_ok());
+
+            // the proof is bound to the position
+            let other = (index + 1) % values.len();
+            assert!(verifier_params
+                .verify_vector(&commitment, other, value, &proof)
+                .is_err());
+        }
+
+        // the padding beyond the length cannot be opened
+        let proof = params.open_vector(&values, 0).unwrap();
+        assert!(verifier_params
+            .verify_vector(&commitment, 5, &BN254Scalar::zero(), &proof)
+            .is_err());
+        assert!(params.open_vector(&values, 5).is_err());
+
+        // the vectors beyond the degree are rejected
+        assert!(params.commit_vector(&[values.clone(); 4].concat()).is_err());
+        assert!(params.commit_vector(&[]).is_err());
+    }
+}
@@ -21,9 +21,15 @@
 #[macro_use]
 extern crate serde_derive;
 
+/// Module for the erasure coding of blobs with the sampling of their availability.
+pub mod das;
+
 /// Module for error handling
 pub mod errors;
 
+/// Module for the public API of the KZG polynomial commitment, for committing to application data.
+pub mod kzg;
+
 /// Module for the TurboPlonk systems.
 pub mod plonk;
 
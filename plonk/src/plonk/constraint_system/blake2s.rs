@@ -0,0 +1,189 @@
+use crate::plonk::constraint_system::{uint32::UInt32, CircuitBuilder, VarIndex};
+use noah_algebra::prelude::*;
+
+/// The initialization vector of BLAKE2s.
+const BLAKE2S_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The message permutations of the rounds of BLAKE2s.
+const BLAKE2S_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The length of the digest in bytes.
+const BLAKE2S_DIGEST_LEN: u32 = 32;
+
+/// Apply the mixing function G of BLAKE2s to the four words of the working vector.
+fn blake2s_mix<F: Scalar, CS: CircuitBuilder<F>>(
+    cs: &mut CS,
+    v: &mut [UInt32; 16],
+    [a, b, c, d]: [usize; 4],
+    x: &UInt32,
+    y: &UInt32,
+) {
+    v[a] = UInt32::add_many(cs, &[v[a], v[b], *x]);
+    v[d] = v[d].xor(cs, &v[a]).rotr(16);
+    v[c] = UInt32::add_many(cs, &[v[c], v[d]]);
+    v[b] = v[b].xor(cs, &v[c]).rotr(12);
+    v[a] = UInt32::add_many(cs, &[v[a], v[b], *y]);
+    v[d] = v[d].xor(cs, &v[a]).rotr(8);
+    v[c] = UInt32::add_many(cs, &[v[c], v[d]]);
+    v[b] = v[b].xor(cs, &v[c]).rotr(7);
+}
+
+/// Apply the compression function of BLAKE2s to the state with the block of 16 words,
+/// where `counter` is the number of bytes hashed so far, including those of the block.
+fn blake2s_compress<F: Scalar, CS: CircuitBuilder<F>>(
+    cs: &mut CS,
+    state: &[UInt32; 8],
+    block: &[UInt32],
+    counter: u64,
+    is_last: bool,
+) -> [UInt32; 8] {
+    let mut iv = BLAKE2S_IV;
+    iv[4] ^= counter as u32;
+    iv[5] ^= (counter >> 32) as u32;
+    if is_last {
+        iv[6] ^= u32::MAX;
+    }
+
+    let mut v = [state[0]; 16];
+    v[..8].copy_from_slice(state);
+    for (x, c) in v[8..].iter_mut().zip(iv.iter()) {
+        *x = UInt32::constant(cs, *c);
+    }
+
+    for s in BLAKE2S_SIGMA.iter() {
+        blake2s_mix(cs, &mut v, [0, 4, 8, 12], &block[s[0]], &block[s[1]]);
+        blake2s_mix(cs, &mut v, [1, 5, 9, 13], &block[s[2]], &block[s[3]]);
+        blake2s_mix(cs, &mut v, [2, 6, 10, 14], &block[s[4]], &block[s[5]]);
+        blake2s_mix(cs, &mut v, [3, 7, 11, 15], &block[s[6]], &block[s[7]]);
+        blake2s_mix(cs, &mut v, [0, 5, 10, 15], &block[s[8]], &block[s[9]]);
+        blake2s_mix(cs, &mut v, [1, 6, 11, 12], &block[s[10]], &block[s[11]]);
+        blake2s_mix(cs, &mut v, [2, 7, 8, 13], &block[s[12]], &block[s[13]]);
+        blake2s_mix(cs, &mut v, [3, 4, 9, 14], &block[s[14]], &block[s[15]]);
+    }
+
+    let mut new_state = *state;
+    for (i, x) in new_state.iter_mut().enumerate() {
+        let t = x.xor(cs, &v[i]);
+        *x = t.xor(cs, &v[i + 8]);
+    }
+    new_state
+}
+
+/// Create constraints for the BLAKE2s hash of the message, without a key and with a 32-byte digest,
+/// and return the bits of the digest. The bits of the message, which are boolean-constrained
+/// by the caller and whose number is a multiple of eight, and those of the digest are in the order
+/// of the bit string, i.e., from the most significant bit of the first byte.
+pub fn blake2s<F: Scalar, CS: CircuitBuilder<F>>(
+    cs: &mut CS,
+    message: &[VarIndex],
+) -> Vec<VarIndex> {
+    assert_eq!(message.len() % 8, 0, "the message is not made of bytes");
+    let zero_var = cs.zero_var();
+
+    let mut padded = message.to_vec();
+    if padded.is_empty() || padded.len() % 512 != 0 {
+        padded.resize((padded.len() / 512 + 1) * 512, zero_var);
+    }
+
+    // the words are read from the bytes in little-endian order
+    let words = padded
+        .chunks(32)
+        .map(|chunk| {
+            let mut bits = [zero_var; 32];
+            for (i, bit) in bits.iter_mut().enumerate() {
+                *bit = chunk[i / 8 * 8 + 7 - i % 8];
+            }
+            UInt32 { bits }
+        })
+        .collect::<Vec<_>>();
+
+    let mut iv = BLAKE2S_IV;
+    iv[0] ^= 0x01010000 ^ BLAKE2S_DIGEST_LEN;
+    let mut state = iv.map(|x| UInt32::constant(cs, x));
+
+    let n_blocks = words.len() / 16;
+    let len = (message.len() / 8) as u64;
+    for (i, block) in words.chunks(16).enumerate() {
+        let is_last = i + 1 == n_blocks;
+        let counter = if is_last { len } else { (i as u64 + 1) * 64 };
+        state = blake2s_compress(cs, &state, block, counter, is_last);
+    }
+
+    state
+        .iter()
+        .flat_map(|word| (0..32).map(|i| word.bits[i / 8 * 8 + 7 - i % 8]))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::plonk::constraint_system::{blake2s::blake2s, TurboCS, VarIndex};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    fn check(message: &[u8], expected: [u8; 32]) {
+        let mut cs = TurboCS::<BN254Scalar>::new();
+        let mut bits: Vec<VarIndex> = Vec::with_capacity(message.len() * 8);
+        for byte in message.iter() {
+            for i in (0..8).rev() {
+                let bit = cs.new_variable(BN254Scalar::from(((byte >> i) & 1) as u64));
+                cs.insert_boolean_gate(bit);
+                bits.push(bit);
+            }
+        }
+        let digest = blake2s(&mut cs, &bits);
+        assert_eq!(digest.len(), 256);
+
+        let mut witness = cs.get_and_clear_witness();
+        let bytes = digest
+            .chunks(8)
+            .map(|chunk| {
+                chunk.iter().fold(0u8, |acc, bit| {
+                    (acc << 1) | (!witness[*bit].is_zero()) as u8
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(bytes, expected);
+        cs.verify_witness(&witness, &[]).unwrap();
+
+        witness[digest[0]] = BN254Scalar::one() - witness[digest[0]];
+        assert!(cs.verify_witness(&witness, &[]).is_err());
+    }
+
+    #[test]
+    fn test_blake2s() {
+        check(
+            b"",
+            [
+                105, 33, 122, 48, 121, 144, 128, 148, 225, 17, 33, 208, 66, 53, 74, 124, 31, 85,
+                182, 72, 44, 161, 165, 30, 27, 37, 13, 253, 30, 208, 238, 249,
+            ],
+        );
+        check(
+            b"abc",
+            [
+                80, 140, 94, 140, 50, 124, 20, 226, 225, 167, 43, 163, 78, 235, 69, 47, 55, 69,
+                139, 32, 158, 214, 58, 41, 77, 153, 155, 76, 134, 103, 89, 130,
+            ],
+        );
+        check(
+            &[b'a'; 100],
+            [
+                33, 79, 36, 254, 17, 24, 235, 133, 68, 80, 35, 142, 17, 190, 190, 34, 210, 227,
+                147, 126, 216, 92, 124, 150, 198, 192, 16, 16, 107, 117, 42, 211,
+            ],
+        );
+    }
+}
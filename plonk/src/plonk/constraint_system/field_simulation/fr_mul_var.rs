@@ -1,4 +1,4 @@
-use crate::plonk::constraint_system::{field_simulation::SimFrVar, TurboCS, VarIndex};
+use crate::plonk::constraint_system::{field_simulation::SimFrVar, CircuitBuilder, WireId};
 use core::{
     cmp::{max, min},
     ops::{Shl, Shr},
@@ -9,26 +9,30 @@ use num_bigint::BigUint;
 use num_integer::Integer;
 
 /// `SimFrMulVar` is the variable for `SimFrMul` in
-/// `TurboConstraintSystem<F>`
+/// a constraint system that implements `CircuitBuilder<F>`
 #[derive(Clone)]
 pub struct SimFrMulVar<F: Scalar, P: SimFrParams<F>> {
     /// the `SimFrMul` value.
     pub val: SimFrMul<F, P>,
     /// the `SimFrMul` variables.
-    pub var: Vec<VarIndex>,
+    pub var: Vec<WireId>,
 }
 
 impl<F: Scalar, P: SimFrParams<F>> SimFrMulVar<F, P> {
     /// Create a zero `SimFrMul`.
-    pub fn new(cs: &TurboCS<F>) -> Self {
+    pub fn new<CS: CircuitBuilder<F>>(cs: &CS) -> Self {
         Self {
             val: SimFrMul::<F, P>::default(),
-            var: vec![cs.zero_var(); P::NUM_OF_LIMBS_MUL],
+            var: vec![cs.wire(cs.zero_var()); P::NUM_OF_LIMBS_MUL],
         }
     }
 
     /// the Add operation.
-    pub fn add(&self, cs: &mut TurboCS<F>, other: &SimFrMulVar<F, P>) -> SimFrMulVar<F, P> {
+    pub fn add<CS: CircuitBuilder<F>>(
+        &self,
+        cs: &mut CS,
+        other: &SimFrMulVar<F, P>,
+    ) -> SimFrMulVar<F, P> {
         let mut res = (*self).clone();
         res.val = &self.val + &other.val;
 
@@ -37,30 +41,30 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrMulVar<F, P> {
         let one = F::one();
 
         for i in 0..P::NUM_OF_LIMBS_MUL {
-            res.var[i] = cs.new_variable(res.val.limbs[i]);
+            res.var[i] = cs.new_wire(res.val.limbs[i]);
 
             // The following gate represents
             // res.var[i] := self.var[i] + other.var[i]
 
-            cs.push_add_selectors(one, zero, one, zero);
-            cs.push_mul_selectors(zero, zero);
-            cs.push_constant_selector(zero);
-            cs.push_ecc_selector(zero);
-            cs.push_out_selector(one);
-
-            cs.wiring[0].push(self.var[i]);
-            cs.wiring[1].push(zero_var);
-            cs.wiring[2].push(other.var[i]);
-            cs.wiring[3].push(zero_var);
-            cs.wiring[4].push(res.var[i]);
-            cs.finish_new_gate();
+            let wires = [
+                cs.var(self.var[i]),
+                zero_var,
+                cs.var(other.var[i]),
+                zero_var,
+                cs.var(res.var[i]),
+            ];
+            cs.insert_turbo_gate(wires, [one, zero, one, zero, zero, zero, zero, zero, one]);
         }
 
         res
     }
 
     /// the Sub operation.
-    pub fn sub(&self, cs: &mut TurboCS<F>, other: &SimFrVar<F, P>) -> SimFrMulVar<F, P> {
+    pub fn sub<CS: CircuitBuilder<F>>(
+        &self,
+        cs: &mut CS,
+        other: &SimFrVar<F, P>,
+    ) -> SimFrMulVar<F, P> {
         let mut res = (*self).clone();
         res.val = &self.val - &other.val;
 
@@ -73,30 +77,39 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrMulVar<F, P> {
         let r_limbs = P::scalar_field_sub_pad_in_limbs();
 
         for (i, r_limb) in r_limbs.iter().enumerate().take(P::NUM_OF_LIMBS) {
-            res.var[i] = cs.new_variable(res.val.limbs[i]);
+            res.var[i] = cs.new_wire(res.val.limbs[i]);
 
             // The following gate represents
             // res.var[i] := self.var[i] - other.var[i] + r_limbs[i] * 4
 
-            cs.push_add_selectors(one, zero, minus_one, zero);
-            cs.push_mul_selectors(zero, zero);
-            cs.push_constant_selector(r_limb.add(r_limb).add(r_limb).add(r_limb));
-            cs.push_ecc_selector(zero);
-            cs.push_out_selector(one);
-
-            cs.wiring[0].push(self.var[i]);
-            cs.wiring[1].push(zero_var);
-            cs.wiring[2].push(other.var[i]);
-            cs.wiring[3].push(zero_var);
-            cs.wiring[4].push(res.var[i]);
-            cs.finish_new_gate();
+            let wires = [
+                cs.var(self.var[i]),
+                zero_var,
+                cs.var(other.var[i]),
+                zero_var,
+                cs.var(res.var[i]),
+            ];
+            cs.insert_turbo_gate(
+                wires,
+                [
+                    one,
+                    zero,
+                    minus_one,
+                    zero,
+                    zero,
+                    zero,
+                    r_limb.add(r_limb).add(r_limb).add(r_limb),
+                    zero,
+                    one,
+                ],
+            );
         }
 
         res
     }
 
     /// Enforce a zero constraint.
-    pub fn enforce_zero(&self, cs: &mut TurboCS<F>) {
+    pub fn enforce_zero<CS: CircuitBuilder<F>>(&self, cs: &mut CS) {
         assert!(self.val.prod_of_num_of_additions.bits() as usize <= 5);
         let surfeit = 5;
 
@@ -188,13 +201,13 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrMulVar<F, P> {
                 let res = self.val.limbs[2 * i].add(&self.val.limbs[2 * i + 1].mul(&step));
                 left_group.push(res);
 
-                let var = cs.linear_combine(
-                    &[self.var[2 * i], zero_var, self.var[2 * i + 1], zero_var],
-                    one,
-                    zero,
-                    step,
-                    zero,
-                );
+                let wires = [
+                    cs.var(self.var[2 * i]),
+                    zero_var,
+                    cs.var(self.var[2 * i + 1]),
+                    zero_var,
+                ];
+                let var = cs.linear_combine(&wires, one, zero, step, zero);
                 left_var_group.push(var);
 
                 let res = rk_limbs[2 * i].add(&rk_limbs[2 * i + 1].mul(&step));
@@ -217,7 +230,7 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrMulVar<F, P> {
                 num_limbs_in_group.push(2);
             } else {
                 left_group.push(self.val.limbs[2 * i]);
-                left_var_group.push(self.var[2 * i]);
+                left_var_group.push(cs.var(self.var[2 * i]));
 
                 right_group.push(rk_limbs[2 * i]);
                 right_var_group.push(rk_limbs_var[2 * i]);
@@ -277,18 +290,26 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrMulVar<F, P> {
                 // The following gate represents
                 // - left_group_limb - carry_in + right_group_limb_var
                 // - pad_limb + carry_shift * carry_var + remainder = 0
-                cs.push_add_selectors(minus_one, minus_one, one, carry_shift);
-                cs.push_mul_selectors(zero, zero);
-                cs.push_constant_selector(pad_limb.neg().add(&remainder));
-                cs.push_ecc_selector(zero);
-                cs.push_out_selector(zero);
-
-                cs.wiring[0].push(*left_group_limb_var);
-                cs.wiring[1].push(carry_in_var);
-                cs.wiring[2].push(*right_group_limb_var);
-                cs.wiring[3].push(carry_var);
-                cs.wiring[4].push(zero_var);
-                cs.finish_new_gate();
+                cs.insert_turbo_gate(
+                    [
+                        *left_group_limb_var,
+                        carry_in_var,
+                        *right_group_limb_var,
+                        carry_var,
+                        zero_var,
+                    ],
+                    [
+                        minus_one,
+                        minus_one,
+                        one,
+                        carry_shift,
+                        zero,
+                        zero,
+                        pad_limb.neg().add(&remainder),
+                        zero,
+                        zero,
+                    ],
+                );
             }
 
             accumulated_extra = new_accumulated_extra;
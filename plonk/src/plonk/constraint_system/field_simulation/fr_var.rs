@@ -1,4 +1,6 @@
-use crate::plonk::constraint_system::{field_simulation::SimFrMulVar, TurboCS, VarIndex};
+use crate::plonk::constraint_system::{
+    field_simulation::SimFrMulVar, CircuitBuilder, VarIndex, WireId,
+};
 use noah_algebra::{
     cmp::{max, min},
     prelude::*,
@@ -6,26 +8,30 @@ use noah_algebra::{
 use noah_crypto::field_simulation::{SimFr, SimFrParams, SimReducibility};
 
 /// `SimFrVar` is the variable for `SimFr` in
-/// `TurboConstraintSystem<BLSScalar>`
+/// a constraint system that implements `CircuitBuilder<F>`
 #[derive(Clone)]
 pub struct SimFrVar<F: Scalar, P: SimFrParams<F>> {
     /// the `SimFr` value.
     pub val: SimFr<F, P>,
     /// the `SimFr` variables.
-    pub var: Vec<VarIndex>,
+    pub var: Vec<WireId>,
 }
 
 impl<F: Scalar, P: SimFrParams<F>> SimFrVar<F, P> {
     /// Create a zero `SimFr`.
-    pub fn new(cs: &TurboCS<F>) -> Self {
+    pub fn new<CS: CircuitBuilder<F>>(cs: &CS) -> Self {
         Self {
             val: SimFr::<F, P>::default(),
-            var: vec![cs.zero_var(); P::NUM_OF_LIMBS],
+            var: vec![cs.wire(cs.zero_var()); P::NUM_OF_LIMBS],
         }
     }
 
     /// the Sub operation.
-    pub fn sub(&self, cs: &mut TurboCS<F>, other: &SimFrVar<F, P>) -> SimFrVar<F, P> {
+    pub fn sub<CS: CircuitBuilder<F>>(
+        &self,
+        cs: &mut CS,
+        other: &SimFrVar<F, P>,
+    ) -> SimFrVar<F, P> {
         let mut res = SimFrVar::<F, P>::new(cs);
         res.val = &self.val - &other.val;
 
@@ -40,27 +46,30 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrVar<F, P> {
         // The following gate represents
         // res.var[i] := self.var[i] - other.var[i] + r_limbs[i]
         for (i, r_limb) in r_limbs.iter().enumerate().take(P::NUM_OF_LIMBS) {
-            res.var[i] = cs.new_variable(res.val.limbs[i]);
-
-            cs.push_add_selectors(one, zero, minus_one, zero);
-            cs.push_mul_selectors(zero, zero);
-            cs.push_constant_selector(*r_limb);
-            cs.push_ecc_selector(zero);
-            cs.push_out_selector(one);
+            res.var[i] = cs.new_wire(res.val.limbs[i]);
 
-            cs.wiring[0].push(self.var[i]);
-            cs.wiring[1].push(zero_var);
-            cs.wiring[2].push(other.var[i]);
-            cs.wiring[3].push(zero_var);
-            cs.wiring[4].push(res.var[i]);
-            cs.finish_new_gate();
+            let wires = [
+                cs.var(self.var[i]),
+                zero_var,
+                cs.var(other.var[i]),
+                zero_var,
+                cs.var(res.var[i]),
+            ];
+            cs.insert_turbo_gate(
+                wires,
+                [one, zero, minus_one, zero, zero, zero, *r_limb, zero, one],
+            );
         }
 
         res
     }
 
     /// the Mul operation.
-    pub fn mul(&self, cs: &mut TurboCS<F>, other: &SimFrVar<F, P>) -> SimFrMulVar<F, P> {
+    pub fn mul<CS: CircuitBuilder<F>>(
+        &self,
+        cs: &mut CS,
+        other: &SimFrVar<F, P>,
+    ) -> SimFrMulVar<F, P> {
         let mut res = SimFrMulVar::<F, P>::new(cs);
         res.val = &self.val * &other.val;
 
@@ -85,51 +94,51 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrVar<F, P> {
                 // The following gate represents
                 // res := prior_res + self.var[left] * other.var[i - left]
 
-                cs.push_add_selectors(one, zero, zero, zero);
-                cs.push_mul_selectors(zero, one);
-                cs.push_constant_selector(zero);
-                cs.push_ecc_selector(zero);
-                cs.push_out_selector(one);
-
-                cs.wiring[0].push(prior_res);
-                cs.wiring[1].push(zero_var);
-                cs.wiring[2].push(self.var[left]);
-                cs.wiring[3].push(other.var[i - left]);
-                cs.wiring[4].push(res);
-                cs.finish_new_gate();
+                let wires = [
+                    prior_res,
+                    zero_var,
+                    cs.var(self.var[left]),
+                    cs.var(other.var[i - left]),
+                    res,
+                ];
+                cs.insert_turbo_gate(wires, [one, zero, zero, zero, zero, one, zero, zero, one]);
 
                 prior_res = res;
                 prior_res_val = res_val;
             }
 
-            res.var[i] = prior_res;
+            res.var[i] = cs.wire(prior_res);
         }
         res
     }
 
     /// Alloc a constant gate.
-    pub fn alloc_constant(cs: &mut TurboCS<F>, val: &SimFr<F, P>) -> Self {
+    pub fn alloc_constant<CS: CircuitBuilder<F>>(cs: &mut CS, val: &SimFr<F, P>) -> Self {
         let mut res = Self::new(cs);
         res.val = (*val).clone();
         for i in 0..P::NUM_OF_LIMBS {
-            res.var[i] = cs.new_variable(val.limbs[i]);
-            cs.insert_constant_gate(res.var[i], val.limbs[i]);
+            let var = cs.new_variable(val.limbs[i]);
+            cs.insert_constant_gate(var, val.limbs[i]);
+            res.var[i] = cs.wire(var);
         }
         res
     }
 
     /// Alloc an input variable.
-    pub fn alloc_input(cs: &mut TurboCS<F>, val: &SimFr<F, P>) -> Self {
+    pub fn alloc_input<CS: CircuitBuilder<F>>(cs: &mut CS, val: &SimFr<F, P>) -> Self {
         let mut res = Self::new(cs);
         res.val = (*val).clone();
         for i in 0..P::NUM_OF_LIMBS {
-            res.var[i] = cs.new_variable(val.limbs[i]);
+            res.var[i] = cs.new_wire(val.limbs[i]);
         }
         res
     }
 
     /// Alloc a witness variable and range check gate.
-    pub fn alloc_witness(cs: &mut TurboCS<F>, val: &SimFr<F, P>) -> (Self, Vec<VarIndex>) {
+    pub fn alloc_witness<CS: CircuitBuilder<F>>(
+        cs: &mut CS,
+        val: &SimFr<F, P>,
+    ) -> (Self, Vec<VarIndex>) {
         assert!(val.num_of_additions_over_normal_form == SimReducibility::StrictlyNotReducible);
 
         let mut res = Self::new(cs);
@@ -139,20 +148,21 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrVar<F, P> {
         let mut bits = Vec::new();
 
         for i in 0..P::NUM_OF_LIMBS {
-            res.var[i] = cs.new_variable(val.limbs[i]);
+            let var = cs.new_variable(val.limbs[i]);
+            res.var[i] = cs.wire(var);
 
             if i == P::NUM_OF_LIMBS - 1 {
-                bits.extend_from_slice(&cs.range_check(res.var[i], P::BIT_IN_TOP_LIMB));
+                bits.extend_from_slice(&cs.range_check(var, P::BIT_IN_TOP_LIMB));
             } else {
-                bits.extend_from_slice(&cs.range_check(res.var[i], P::BIT_PER_LIMB));
+                bits.extend_from_slice(&cs.range_check(var, P::BIT_PER_LIMB));
             }
         }
         (res, bits)
     }
 
     /// Alloc a witness variable and range check gate with bounded.
-    pub fn alloc_witness_bounded_total_bits(
-        cs: &mut TurboCS<F>,
+    pub fn alloc_witness_bounded_total_bits<CS: CircuitBuilder<F>>(
+        cs: &mut CS,
         val: &SimFr<F, P>,
         total_bits: usize,
     ) -> (Self, Vec<VarIndex>) {
@@ -170,12 +180,13 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrVar<F, P> {
 
         for i in 0..P::NUM_OF_LIMBS {
             if remaining_bits != 0 {
-                res.var[i] = cs.new_variable(val.limbs[i]);
+                let var = cs.new_variable(val.limbs[i]);
+                res.var[i] = cs.wire(var);
                 let bit_limit = min(remaining_bits, P::BIT_PER_LIMB);
-                bits.extend_from_slice(&cs.range_check(res.var[i], bit_limit));
+                bits.extend_from_slice(&cs.range_check(var, bit_limit));
                 remaining_bits -= bit_limit;
             } else {
-                res.var[i] = cs.zero_var();
+                res.var[i] = cs.wire(cs.zero_var());
             }
         }
         (res, bits)
@@ -186,7 +197,7 @@ impl<F: Scalar, P: SimFrParams<F>> SimFrVar<F, P> {
 mod test_ristretto_bls12_381 {
     use crate::plonk::constraint_system::{
         field_simulation::{SimFrMulVar, SimFrVar},
-        TurboCS,
+        CircuitBuilder, TurboCS,
     };
     use noah_algebra::{bls12_381::BLSScalar, ops::Shl, prelude::*};
     use noah_crypto::field_simulation::{SimFr, SimFrParams, SimFrParamsBLSRistretto};
@@ -199,7 +210,8 @@ mod test_ristretto_bls12_381 {
     fn test_sim_fr_equality(cs: TurboCS<BLSScalar>, val: &SimFrVarTest) {
         let mut cs = cs;
         for i in 0..SimFrParamsBLSRistretto::NUM_OF_LIMBS {
-            cs.insert_constant_gate(val.var[i], val.val.limbs[i]);
+            let var = cs.var(val.var[i]);
+            cs.insert_constant_gate(var, val.val.limbs[i]);
         }
 
         let witness = cs.get_and_clear_witness();
@@ -209,7 +221,8 @@ mod test_ristretto_bls12_381 {
     fn test_sim_fr_mul_equality(cs: TurboCS<BLSScalar>, val: &SimFrMulVarTest) {
         let mut cs = cs;
         for i in 0..SimFrParamsBLSRistretto::NUM_OF_LIMBS_MUL {
-            cs.insert_constant_gate(val.var[i], val.val.limbs[i]);
+            let var = cs.var(val.var[i]);
+            cs.insert_constant_gate(var, val.val.limbs[i]);
         }
 
         let witness = cs.get_and_clear_witness();
@@ -337,7 +350,7 @@ mod test_ristretto_bls12_381 {
 mod test_secq256k1_bls12_381 {
     use crate::plonk::constraint_system::{
         field_simulation::{SimFrMulVar, SimFrVar},
-        TurboCS,
+        CircuitBuilder, TurboCS,
     };
     use noah_algebra::{bls12_381::BLSScalar, ops::Shl, prelude::*};
     use noah_crypto::field_simulation::{SimFr, SimFrParams, SimFrParamsBLSSecq256k1};
@@ -350,7 +363,8 @@ mod test_secq256k1_bls12_381 {
     fn test_sim_fr_equality(cs: TurboCS<BLSScalar>, val: &SimFrVarTest) {
         let mut cs = cs;
         for i in 0..SimFrParamsBLSSecq256k1::NUM_OF_LIMBS {
-            cs.insert_constant_gate(val.var[i], val.val.limbs[i]);
+            let var = cs.var(val.var[i]);
+            cs.insert_constant_gate(var, val.val.limbs[i]);
         }
 
         let witness = cs.get_and_clear_witness();
@@ -360,7 +374,8 @@ mod test_secq256k1_bls12_381 {
     fn test_sim_fr_mul_equality(cs: TurboCS<BLSScalar>, val: &SimFrMulVarTest) {
         let mut cs = cs;
         for i in 0..SimFrParamsBLSSecq256k1::NUM_OF_LIMBS_MUL {
-            cs.insert_constant_gate(val.var[i], val.val.limbs[i]);
+            let var = cs.var(val.var[i]);
+            cs.insert_constant_gate(var, val.val.limbs[i]);
         }
 
         let witness = cs.get_and_clear_witness();
@@ -488,7 +503,7 @@ mod test_secq256k1_bls12_381 {
 mod test_ristretto_bn254 {
     use crate::plonk::constraint_system::{
         field_simulation::{SimFrMulVar, SimFrVar},
-        TurboCS,
+        CircuitBuilder, TurboCS,
     };
     use noah_algebra::{bn254::BN254Scalar, ops::Shl, prelude::*};
     use noah_crypto::field_simulation::{SimFr, SimFrParams, SimFrParamsBN254Ristretto};
@@ -501,7 +516,8 @@ mod test_ristretto_bn254 {
     fn test_sim_fr_equality(cs: TurboCS<BN254Scalar>, val: &SimFrVarTest) {
         let mut cs = cs;
         for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS {
-            cs.insert_constant_gate(val.var[i], val.val.limbs[i]);
+            let var = cs.var(val.var[i]);
+            cs.insert_constant_gate(var, val.val.limbs[i]);
         }
 
         let witness = cs.get_and_clear_witness();
@@ -511,7 +527,8 @@ mod test_ristretto_bn254 {
     fn test_sim_fr_mul_equality(cs: TurboCS<BN254Scalar>, val: &SimFrMulVarTest) {
         let mut cs = cs;
         for i in 0..SimFrParamsBN254Ristretto::NUM_OF_LIMBS_MUL {
-            cs.insert_constant_gate(val.var[i], val.val.limbs[i]);
+            let var = cs.var(val.var[i]);
+            cs.insert_constant_gate(var, val.val.limbs[i]);
         }
 
         let witness = cs.get_and_clear_witness();
@@ -639,7 +656,7 @@ mod test_ristretto_bn254 {
 mod test_secq256k1_bn254 {
     use crate::plonk::constraint_system::{
         field_simulation::{SimFrMulVar, SimFrVar},
-        TurboCS,
+        CircuitBuilder, TurboCS,
     };
     use noah_algebra::{bn254::BN254Scalar, ops::Shl, prelude::*};
     use noah_crypto::field_simulation::{SimFr, SimFrParams, SimFrParamsBN254Secq256k1};
@@ -652,7 +669,8 @@ mod test_secq256k1_bn254 {
     fn test_sim_fr_equality(cs: TurboCS<BN254Scalar>, val: &SimFrVarTest) {
         let mut cs = cs;
         for i in 0..SimFrParamsBN254Secq256k1::NUM_OF_LIMBS {
-            cs.insert_constant_gate(val.var[i], val.val.limbs[i]);
+            let var = cs.var(val.var[i]);
+            cs.insert_constant_gate(var, val.val.limbs[i]);
         }
 
         let witness = cs.get_and_clear_witness();
@@ -662,7 +680,8 @@ mod test_secq256k1_bn254 {
     fn test_sim_fr_mul_equality(cs: TurboCS<BN254Scalar>, val: &SimFrMulVarTest) {
         let mut cs = cs;
         for i in 0..SimFrParamsBN254Secq256k1::NUM_OF_LIMBS_MUL {
-            cs.insert_constant_gate(val.var[i], val.val.limbs[i]);
+            let var = cs.var(val.var[i]);
+            cs.insert_constant_gate(var, val.val.limbs[i]);
         }
 
         let witness = cs.get_and_clear_witness();
@@ -0,0 +1,402 @@
+//! Module for the reusable gadgets.
+//!
+//! A gadget is a struct of its input variables, which is built into any `CircuitBuilder`
+//! by `build`. Its `cost` is the number of constraints that `build` adds with the
+//! TurboPLONK gates.
+use super::{CircuitBuilder, VarIndex};
+use noah_algebra::prelude::*;
+
+/// Trait for the gadgets.
+pub trait Gadget<F: Scalar> {
+    /// The variables that the gadget outputs.
+    type Output;
+
+    /// Return the number of constraints of the gadget.
+    fn cost(&self) -> usize;
+
+    /// Add the constraints of the gadget to the constraint system.
+    fn build<CS: CircuitBuilder<F>>(&self, cs: &mut CS) -> Self::Output;
+}
+
+/// Constrain `var` to be zero or one.
+///
+/// Cost: 1 constraint.
+#[derive(Clone, Copy, Debug)]
+pub struct Boolean {
+    /// The variable.
+    pub var: VarIndex,
+}
+
+impl<F: Scalar> Gadget<F> for Boolean {
+    type Output = ();
+
+    fn cost(&self) -> usize {
+        1
+    }
+
+    fn build<CS: CircuitBuilder<F>>(&self, cs: &mut CS) {
+        cs.insert_boolean_gate(self.var);
+    }
+}
+
+/// Constrain three variables to be bits of which exactly one is one.
+///
+/// Cost: 1 constraint.
+#[derive(Clone, Copy, Debug)]
+pub struct OneHot {
+    /// The bits.
+    pub bits: [VarIndex; 3],
+}
+
+impl<F: Scalar> Gadget<F> for OneHot {
+    type Output = ();
+
+    fn cost(&self) -> usize {
+        1
+    }
+
+    fn build<CS: CircuitBuilder<F>>(&self, cs: &mut CS) {
+        let zero = F::zero();
+        let one = F::one();
+        let zero_var = cs.zero_var();
+        // bits[0] + bits[1] + bits[2] - 1 = 0, with the three wires boolean constrained
+        cs.insert_turbo_gate(
+            [zero_var, self.bits[0], self.bits[1], self.bits[2], zero_var],
+            [zero, one, one, one, zero, zero, one.neg(), zero, zero],
+        );
+        cs.attach_boolean_constraint_to_gate();
+    }
+}
+
+/// Constrain `0 <= var < 2^n_bits` for any `n_bits`, and output the binary vector
+/// of `var` in little endian form.
+///
+/// Cost: 1 constraint if `n_bits` is at most one, and `2 + (n_bits - 2) / 3` constraints otherwise.
+#[derive(Clone, Copy, Debug)]
+pub struct RangeCheck {
+    /// The variable.
+    pub var: VarIndex,
+    /// The number of bits.
+    pub n_bits: usize,
+}
+
+impl<F: Scalar> Gadget<F> for RangeCheck {
+    type Output = Vec<VarIndex>;
+
+    fn cost(&self) -> usize {
+        match self.n_bits {
+            0 | 1 => 1,
+            n_bits => 2 + (n_bits - 2) / 3,
+        }
+    }
+
+    fn build<CS: CircuitBuilder<F>>(&self, cs: &mut CS) -> Vec<VarIndex> {
+        match self.n_bits {
+            0 => {
+                cs.insert_constant_gate(self.var, F::zero());
+                vec![]
+            }
+            1 => {
+                cs.insert_boolean_gate(self.var);
+                vec![self.var]
+            }
+            n_bits => cs.range_check(self.var, n_bits),
+        }
+    }
+}
+
+/// Output `var0` if `bit` is zero and `var1` if `bit` is one,
+/// as `bit * var1 - bit * var0 + var0`. The bit is not constrained to be boolean.
+///
+/// Cost: 1 constraint.
+#[derive(Clone, Copy, Debug)]
+pub struct Select {
+    /// The output when the bit is zero.
+    pub var0: VarIndex,
+    /// The output when the bit is one.
+    pub var1: VarIndex,
+    /// The bit.
+    pub bit: VarIndex,
+}
+
+impl<F: Scalar> Gadget<F> for Select {
+    type Output = VarIndex;
+
+    fn cost(&self) -> usize {
+        1
+    }
+
+    fn build<CS: CircuitBuilder<F>>(&self, cs: &mut CS) -> VarIndex {
+        let zero = F::zero();
+        let one = F::one();
+        let out = if cs.witness_value(self.bit).is_zero() {
+            cs.witness_value(self.var0)
+        } else {
+            cs.witness_value(self.var1)
+        };
+        let out_var = cs.new_variable(out);
+        cs.insert_turbo_gate(
+            [self.bit, self.var0, self.bit, self.var1, out_var],
+            [zero, one, zero, zero, one.neg(), one, zero, zero, one],
+        );
+        out_var
+    }
+}
+
+/// Output `(var0, var1)` if `bit` is zero and `(var1, var0)` if `bit` is one.
+/// The bit is not constrained to be boolean.
+///
+/// Cost: 2 constraints.
+#[derive(Clone, Copy, Debug)]
+pub struct CondSwap {
+    /// The first variable.
+    pub var0: VarIndex,
+    /// The second variable.
+    pub var1: VarIndex,
+    /// The bit.
+    pub bit: VarIndex,
+}
+
+impl<F: Scalar> Gadget<F> for CondSwap {
+    type Output = (VarIndex, VarIndex);
+
+    fn cost(&self) -> usize {
+        2
+    }
+
+    fn build<CS: CircuitBuilder<F>>(&self, cs: &mut CS) -> (VarIndex, VarIndex) {
+        let first = Select {
+            var0: self.var0,
+            var1: self.var1,
+            bit: self.bit,
+        }
+        .build(cs);
+        // second = var0 + var1 - first
+        let zero_var = cs.zero_var();
+        let one = F::one();
+        let second = cs.linear_combine(
+            &[self.var0, self.var1, first, zero_var],
+            one,
+            one,
+            one.neg(),
+            F::zero(),
+        );
+        (first, second)
+    }
+}
+
+/// Output a bit that is one if and only if `var` is zero.
+///
+/// Cost: 2 constraints.
+#[derive(Clone, Copy, Debug)]
+pub struct IsZero {
+    /// The variable.
+    pub var: VarIndex,
+}
+
+impl<F: Scalar> Gadget<F> for IsZero {
+    type Output = VarIndex;
+
+    fn cost(&self) -> usize {
+        2
+    }
+
+    fn build<CS: CircuitBuilder<F>>(&self, cs: &mut CS) -> VarIndex {
+        let zero = F::zero();
+        let one = F::one();
+        let zero_var = cs.zero_var();
+
+        let value = cs.witness_value(self.var);
+        // `inv` can be arbitrary when `var` is zero
+        let inv = cs.new_variable(value.inv().unwrap_or_else(|_| F::zero()));
+        let is_zero = cs.new_variable(if value.is_zero() { one } else { zero });
+
+        // is_zero = 1 - var * inv
+        cs.insert_turbo_gate(
+            [self.var, inv, zero_var, zero_var, is_zero],
+            [zero, zero, zero, zero, one.neg(), zero, one, zero, one],
+        );
+        // var * is_zero = 0, without which `is_zero` could be zero when `var` is zero
+        cs.insert_mul_gate(self.var, is_zero, zero_var);
+        is_zero
+    }
+}
+
+/// Output a bit that is one if and only if `left < right`, for `left` and `right`
+/// that are already known to be less than `2^n_bits`, where `n_bits + 1` is less than
+/// the capacity of the field.
+///
+/// Cost: `2 + RangeCheck { n_bits: n_bits + 1 }.cost()` constraints.
+#[derive(Clone, Copy, Debug)]
+pub struct LessThan {
+    /// The left variable.
+    pub left: VarIndex,
+    /// The right variable.
+    pub right: VarIndex,
+    /// The number of bits of the variables.
+    pub n_bits: usize,
+}
+
+impl<F: Scalar> Gadget<F> for LessThan {
+    type Output = VarIndex;
+
+    fn cost(&self) -> usize {
+        let range_check = RangeCheck {
+            var: self.left,
+            n_bits: self.n_bits + 1,
+        };
+        2 + <RangeCheck as Gadget<F>>::cost(&range_check)
+    }
+
+    fn build<CS: CircuitBuilder<F>>(&self, cs: &mut CS) -> VarIndex {
+        let zero = F::zero();
+        let one = F::one();
+        let zero_var = cs.zero_var();
+        let one_var = cs.one_var();
+
+        // diff = 2^n_bits + left - right, whose bit `n_bits` is one if and only if left >= right
+        let shift = F::from(2u32).pow(&[self.n_bits as u64]);
+        let diff = cs.linear_combine(
+            &[one_var, self.left, self.right, zero_var],
+            shift,
+            one,
+            one.neg(),
+            zero,
+        );
+        let bits = RangeCheck {
+            var: diff,
+            n_bits: self.n_bits + 1,
+        }
+        .build(cs);
+        // less_than = 1 - bits[n_bits]
+        cs.linear_combine(
+            &[one_var, bits[self.n_bits], zero_var, zero_var],
+            one,
+            one.neg(),
+            zero,
+            zero,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::plonk::constraint_system::{
+        gadgets::{Boolean, CondSwap, Gadget, IsZero, LessThan, OneHot, RangeCheck, Select},
+        TurboCS, VarIndex,
+    };
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    type F = BN254Scalar;
+
+    /// Build the gadget, check its cost, and return its output with the witness.
+    fn build<G: Gadget<F>>(cs: &mut TurboCS<F>, gadget: G) -> G::Output {
+        let size = cs.size;
+        let output = gadget.build(cs);
+        assert_eq!(cs.size - size, gadget.cost());
+        output
+    }
+
+    fn verify(cs: &TurboCS<F>, changes: &[(VarIndex, u64)]) -> bool {
+        let mut witness = cs.witness.clone();
+        for (var, value) in changes.iter() {
+            witness[*var] = F::from(*value);
+        }
+        cs.verify_witness(&witness, &[]).is_ok()
+    }
+
+    #[test]
+    fn test_boolean_and_one_hot() {
+        let mut cs = TurboCS::<F>::new();
+        let bits: Vec<_> = [0u64, 1, 0]
+            .iter()
+            .map(|b| cs.new_variable(F::from(*b)))
+            .collect();
+        build(&mut cs, Boolean { var: bits[1] });
+        build(
+            &mut cs,
+            OneHot {
+                bits: [bits[0], bits[1], bits[2]],
+            },
+        );
+        assert!(verify(&cs, &[]));
+        assert!(!verify(&cs, &[(bits[1], 2)]));
+        assert!(!verify(&cs, &[(bits[0], 1)]));
+    }
+
+    #[test]
+    fn test_range_check() {
+        for n_bits in 0..12 {
+            let mut cs = TurboCS::<F>::new();
+            let max = (1u64 << n_bits) - 1;
+            let var = cs.new_variable(F::from(max));
+            let bits = build(&mut cs, RangeCheck { var, n_bits });
+            assert_eq!(bits.len(), n_bits);
+            assert!(verify(&cs, &[]));
+            assert!(!verify(&cs, &[(var, max + 1)]));
+        }
+    }
+
+    #[test]
+    fn test_select_and_cond_swap() {
+        let mut cs = TurboCS::<F>::new();
+        let var0 = cs.new_variable(F::from(10u64));
+        let var1 = cs.new_variable(F::from(20u64));
+        let bit = cs.new_variable(F::one());
+
+        let selected = build(&mut cs, Select { var0, var1, bit });
+        assert_eq!(cs.witness[selected], F::from(20u64));
+
+        let (first, second) = build(&mut cs, CondSwap { var0, var1, bit });
+        assert_eq!(cs.witness[first], F::from(20u64));
+        assert_eq!(cs.witness[second], F::from(10u64));
+
+        assert!(verify(&cs, &[]));
+        assert!(!verify(&cs, &[(first, 10)]));
+        assert!(!verify(&cs, &[(bit, 0)]));
+    }
+
+    #[test]
+    fn test_is_zero() {
+        let mut cs = TurboCS::<F>::new();
+        let zero = cs.new_variable(F::zero());
+        let five = cs.new_variable(F::from(5u64));
+        let zero_is_zero = build(&mut cs, IsZero { var: zero });
+        let five_is_zero = build(&mut cs, IsZero { var: five });
+        assert_eq!(cs.witness[zero_is_zero], F::one());
+        assert_eq!(cs.witness[five_is_zero], F::zero());
+
+        assert!(verify(&cs, &[]));
+        assert!(!verify(&cs, &[(zero_is_zero, 0)]));
+        assert!(!verify(&cs, &[(five_is_zero, 1)]));
+    }
+
+    #[test]
+    fn test_less_than() {
+        let mut cs = TurboCS::<F>::new();
+        let values = [0u64, 7, 200, 255];
+        let vars: Vec<_> = values
+            .iter()
+            .map(|v| cs.new_variable(F::from(*v)))
+            .collect();
+        for (i, left) in vars.iter().enumerate() {
+            for (j, right) in vars.iter().enumerate() {
+                let less_than = build(
+                    &mut cs,
+                    LessThan {
+                        left: *left,
+                        right: *right,
+                        n_bits: 8,
+                    },
+                );
+                let expected = if values[i] < values[j] {
+                    F::one()
+                } else {
+                    F::zero()
+                };
+                assert_eq!(cs.witness[less_than], expected);
+            }
+        }
+        assert!(verify(&cs, &[]));
+    }
+}
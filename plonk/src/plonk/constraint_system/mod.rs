@@ -13,6 +13,21 @@ pub mod ecc;
 /// Module for the Anemoi-Jive hash function.
 pub mod anemoi_jive;
 
+/// Module for the Poseidon hash function.
+pub mod poseidon;
+
+/// Module for the 32-bit words of the hash functions over bits.
+pub mod uint32;
+
+/// Module for the SHA-256 hash function.
+pub mod sha256;
+
+/// Module for the BLAKE2s hash function.
+pub mod blake2s;
+
+/// Module for the reusable gadgets.
+pub mod gadgets;
+
 /// Default used constraint system.
 #[doc(hidden)]
 pub use turbo::TurboCS;
@@ -23,6 +38,31 @@ pub type VarIndex = usize;
 /// Constraint index
 pub type CsIndex = usize;
 
+/// A typed handle of a variable. With the `debug` feature, it also records the constraint
+/// system that created it, so that using it in another constraint system panics immediately.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WireId {
+    var: VarIndex,
+    #[cfg(feature = "debug")]
+    owner: u64,
+}
+
+impl WireId {
+    /// Return the variable index, without checking the constraint system that owns it.
+    pub fn index(&self) -> VarIndex {
+        self.var
+    }
+}
+
+/// Return a fresh identifier for a constraint system.
+#[cfg(feature = "debug")]
+pub(crate) fn next_circuit_id() -> u64 {
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    static NEXT_CIRCUIT_ID: AtomicU64 = AtomicU64::new(1);
+    NEXT_CIRCUIT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
 /// Trait for PLONK constraint systems.
 pub trait ConstraintSystem: Sized {
     /// Type of scalar field.
@@ -135,3 +175,281 @@ pub trait ConstraintSystem: Sized {
     /// Get the hiding degree for each witness polynomial.
     fn get_hiding_degree(&self, idx: usize) -> usize;
 }
+
+/// Trait for building circuits, implemented by `TurboCS`, so that a gadget can be written
+/// independently of the constraint system that it is built for.
+pub trait CircuitBuilder<F: Scalar> {
+    /// Return the variable that is always zero.
+    fn zero_var(&self) -> VarIndex;
+
+    /// Return the variable that is always one.
+    fn one_var(&self) -> VarIndex;
+
+    /// Return the value of the variable.
+    fn witness_value(&self, var: VarIndex) -> F;
+
+    /// Add a variable (with actual value `value`) into the constraint system.
+    fn new_variable(&mut self, value: F) -> VarIndex;
+
+    /// Return the identifier of the constraint system, which tags its wires in the `debug` mode.
+    fn circuit_id(&self) -> u64;
+
+    /// Wrap a variable of the constraint system into a typed handle.
+    fn wire(&self, var: VarIndex) -> WireId {
+        WireId {
+            var,
+            #[cfg(feature = "debug")]
+            owner: self.circuit_id(),
+        }
+    }
+
+    /// Return the variable of a typed handle, which, in the `debug` mode, panics if the handle
+    /// was created by another constraint system.
+    fn var(&self, wire: WireId) -> VarIndex {
+        #[cfg(feature = "debug")]
+        assert_eq!(
+            wire.owner,
+            self.circuit_id(),
+            "the wire belongs to another constraint system"
+        );
+        wire.var
+    }
+
+    /// Add a variable (with actual value `value`) into the constraint system, and return its handle.
+    fn new_wire(&mut self, value: F) -> WireId {
+        let var = self.new_variable(value);
+        self.wire(var)
+    }
+
+    /// Add a TurboPLONK gate on the wires `(w1, w2, w3, w4, wo)`, with the selectors
+    /// `(q1, q2, q3, q4, qm1, qm2, qc, q_ecc, qo)` in this order:
+    /// ```text
+    ///     q1*w1 + q2*w2 + q3*w3 + q4*w4 + qm1(w1*w2) + qm2(w3*w4) + qc
+    ///     + q_ecc*[w1*w2*w3*w4*wo]
+    ///     - qo * wo = 0
+    /// ```
+    fn insert_turbo_gate(
+        &mut self,
+        wires: [VarIndex; turbo::N_WIRES_PER_GATE],
+        selectors: [F; turbo::N_SELECTORS],
+    );
+
+    /// Add constraint that the second, third, and fourth wires of the last gate must be one or zero.
+    fn attach_boolean_constraint_to_gate(&mut self);
+
+    /// Add a constant constraint: wo = constant.
+    fn insert_constant_gate(&mut self, var: VarIndex, constant: F);
+
+    /// Add constraint of a public IO value to be decided online.
+    fn prepare_pi_variable(&mut self, var: VarIndex);
+
+    /// Add a linear combination gate: wo = w1 * q1 + w2 * q2 + w3 * q3 + w4 * q4.
+    fn insert_lc_gate(
+        &mut self,
+        wires_in: &[VarIndex; 4],
+        wire_out: VarIndex,
+        q1: F,
+        q2: F,
+        q3: F,
+        q4: F,
+    ) {
+        let zero = F::zero();
+        self.insert_turbo_gate(
+            [wires_in[0], wires_in[1], wires_in[2], wires_in[3], wire_out],
+            [q1, q2, q3, q4, zero, zero, zero, zero, F::one()],
+        );
+    }
+
+    /// Add a Mul gate. (left, right, out).
+    fn insert_mul_gate(&mut self, left_var: VarIndex, right_var: VarIndex, out_var: VarIndex) {
+        let zero = F::zero();
+        let zero_var = self.zero_var();
+        self.insert_turbo_gate(
+            [left_var, right_var, zero_var, zero_var, out_var],
+            [zero, zero, zero, zero, F::one(), zero, zero, zero, F::one()],
+        );
+    }
+
+    /// Create an output variable and insert a linear combination gate.
+    fn linear_combine(&mut self, wires_in: &[VarIndex; 4], q1: F, q2: F, q3: F, q4: F) -> VarIndex {
+        let mut lc = self.witness_value(wires_in[0]).mul(&q1);
+        lc.add_assign(&self.witness_value(wires_in[1]).mul(&q2));
+        lc.add_assign(&self.witness_value(wires_in[2]).mul(&q3));
+        lc.add_assign(&self.witness_value(wires_in[3]).mul(&q4));
+        let wire_out = self.new_variable(lc);
+        self.insert_lc_gate(wires_in, wire_out, q1, q2, q3, q4);
+        wire_out
+    }
+
+    /// Create an output variable and insert an addition gate.
+    fn add(&mut self, left_var: VarIndex, right_var: VarIndex) -> VarIndex {
+        let zero_var = self.zero_var();
+        let (zero, one) = (F::zero(), F::one());
+        self.linear_combine(
+            &[left_var, right_var, zero_var, zero_var],
+            one,
+            one,
+            zero,
+            zero,
+        )
+    }
+
+    /// Create an output variable and insert a subraction gate.
+    fn sub(&mut self, left_var: VarIndex, right_var: VarIndex) -> VarIndex {
+        let zero_var = self.zero_var();
+        let (zero, one) = (F::zero(), F::one());
+        self.linear_combine(
+            &[left_var, right_var, zero_var, zero_var],
+            one,
+            one.neg(),
+            zero,
+            zero,
+        )
+    }
+
+    /// Create an output variable and insert a multiplication gate.
+    fn mul(&mut self, left_var: VarIndex, right_var: VarIndex) -> VarIndex {
+        let value = self
+            .witness_value(left_var)
+            .mul(&self.witness_value(right_var));
+        let out_var = self.new_variable(value);
+        self.insert_mul_gate(left_var, right_var, out_var);
+        out_var
+    }
+
+    /// Add a constraint that `left_var` and `right_var` have the same value.
+    fn equal(&mut self, left_var: VarIndex, right_var: VarIndex) {
+        let zero_var = self.zero_var();
+        let (zero, one) = (F::zero(), F::one());
+        self.insert_lc_gate(
+            &[left_var, right_var, zero_var, zero_var],
+            zero_var,
+            one,
+            one.neg(),
+            zero,
+            zero,
+        );
+    }
+
+    /// Add a Boolean constrain `var` by adding a multiplication gate:
+    /// `witness[var] * witness[var] = witness[var]`
+    fn insert_boolean_gate(&mut self, var: VarIndex) {
+        self.insert_mul_gate(var, var, var);
+    }
+
+    /// Enforce a range constraint: `0 < witness[var] < 2^n_bits`, and return
+    /// the witness indices of the binary vector of `witness[var]` in little endian form.
+    fn range_check(&mut self, var: VarIndex, n_bits: usize) -> Vec<VarIndex> {
+        assert!(n_bits >= 2, "the number of bits is less than two");
+        let witness_bytes = self.witness_value(var).to_bytes();
+        let mut binary_repr = compute_binary_le::<F>(&witness_bytes);
+        while binary_repr.len() < n_bits {
+            binary_repr.push(F::zero());
+        }
+
+        let b: Vec<VarIndex> = binary_repr
+            .into_iter()
+            .take(n_bits)
+            .map(|val| self.new_variable(val))
+            .collect();
+
+        let one = F::one();
+        let two = one.add(&one);
+        let four = two.add(&two);
+        let eight = four.add(&four);
+        let bin = [one, two, four, eight];
+
+        let mut acc = b[n_bits - 1];
+        self.insert_boolean_gate(b[n_bits - 1]);
+        let m = (n_bits - 2) / 3;
+        for i in 0..m {
+            acc = self.linear_combine(
+                &[
+                    acc,
+                    b[n_bits - 1 - i * 3 - 1],
+                    b[n_bits - 1 - i * 3 - 2],
+                    b[n_bits - 1 - i * 3 - 3],
+                ],
+                bin[3],
+                bin[2],
+                bin[1],
+                bin[0],
+            );
+            self.attach_boolean_constraint_to_gate();
+        }
+        let zero = F::zero();
+        let zero_var = self.zero_var();
+        match (n_bits - 1) - 3 * m {
+            1 => self.insert_lc_gate(
+                &[acc, b[0], zero_var, zero_var],
+                var,
+                bin[1],
+                bin[0],
+                zero,
+                zero,
+            ),
+            2 => self.insert_lc_gate(
+                &[acc, b[1], b[0], zero_var],
+                var,
+                bin[2],
+                bin[1],
+                bin[0],
+                zero,
+            ),
+            _ => self.insert_lc_gate(
+                &[acc, b[2], b[1], b[0]],
+                var,
+                bin[3],
+                bin[2],
+                bin[1],
+                bin[0],
+            ),
+        }
+        self.attach_boolean_constraint_to_gate();
+        b
+    }
+}
+
+/// Compute the binary representation of the bytes in little endian form.
+pub(crate) fn compute_binary_le<F: Scalar>(bytes: &[u8]) -> Vec<F> {
+    let mut res = vec![];
+    for byte in bytes.iter() {
+        let mut tmp = *byte;
+        for _ in 0..8 {
+            if (tmp & 1) == 0 {
+                res.push(F::zero());
+            } else {
+                res.push(F::one());
+            }
+            tmp >>= 1;
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use crate::plonk::constraint_system::{CircuitBuilder, TurboCS};
+    use noah_algebra::bn254::BN254Scalar;
+
+    #[test]
+    fn test_wire_handles() {
+        let mut cs = TurboCS::<BN254Scalar>::new();
+        let wire = cs.new_wire(BN254Scalar::from(5u32));
+        let var = cs.var(wire);
+        assert_eq!(var, wire.index());
+        assert_eq!(cs.witness_value(var), BN254Scalar::from(5u32));
+        assert_eq!(cs.wire(var), wire);
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    #[should_panic(expected = "the wire belongs to another constraint system")]
+    fn test_wire_from_another_cs() {
+        let mut cs = TurboCS::<BN254Scalar>::new();
+        let wire = cs.new_wire(BN254Scalar::from(5u32));
+
+        let other = TurboCS::<BN254Scalar>::new();
+        other.var(wire);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::plonk::constraint_system::{TurboCS, VarIndex};
+use noah_algebra::prelude::*;
+use noah_crypto::poseidon::PoseidonParams;
+
+impl<F: Scalar> TurboCS<F> {
+    /// Create an output variable and insert a gate: wo = w1 * q1 + w2 * q2 + w3 * q3 + w4 * q4 + qc.
+    fn poseidon_linear_gate(&mut self, wires_in: &[VarIndex; 4], q: &[F; 4], q_c: F) -> VarIndex {
+        let mut value = q_c;
+        for (wire, coef) in wires_in.iter().zip(q.iter()) {
+            value.add_assign(&self.witness[*wire].mul(coef));
+        }
+        let wire_out = self.new_variable(value);
+
+        let zero = F::zero();
+        self.push_add_selectors(q[0], q[1], q[2], q[3]);
+        self.push_mul_selectors(zero, zero);
+        self.push_constant_selector(q_c);
+        self.push_ecc_selector(zero);
+        self.push_out_selector(F::one());
+
+        for (i, wire) in wires_in.iter().enumerate() {
+            self.wiring[i].push(*wire);
+        }
+        self.wiring[4].push(wire_out);
+        self.finish_new_gate();
+        wire_out
+    }
+
+    /// Multiply the state by the MDS matrix and add the constants, with one gate per element.
+    fn poseidon_linear_layer(
+        &mut self,
+        params: &PoseidonParams<F>,
+        state: &[VarIndex],
+        constants: Option<&[F]>,
+    ) -> Vec<VarIndex> {
+        let zero_var = self.zero_var();
+        let mut wires_in = [zero_var; 4];
+        wires_in[..state.len()].copy_from_slice(state);
+
+        (0..params.width)
+            .map(|i| {
+                let mut q = [F::zero(); 4];
+                q[..params.width].copy_from_slice(&params.mds[i]);
+                let q_c = constants.map_or(F::zero(), |c| c[i]);
+                self.poseidon_linear_gate(&wires_in, &q, q_c)
+            })
+            .collect()
+    }
+
+    /// Create constraints for the S-Box `x^alpha`, for an exponent of at least two.
+    fn poseidon_sbox(&mut self, params: &PoseidonParams<F>, var: VarIndex) -> VarIndex {
+        assert!(params.alpha >= 2);
+
+        // square-and-multiply from the most significant bit
+        let mut acc = var;
+        for i in (0..63 - params.alpha.leading_zeros()).rev() {
+            acc = self.mul(acc, acc);
+            if (params.alpha >> i) & 1 == 1 {
+                acc = self.mul(acc, var);
+            }
+        }
+        acc
+    }
+
+    /// Create constraints for the Poseidon permutation and return the output state.
+    /// The width is at most four, so that the linear layer fits one gate per element.
+    pub fn poseidon_permutation(
+        &mut self,
+        params: &PoseidonParams<F>,
+        state: &[VarIndex],
+    ) -> Vec<VarIndex> {
+        assert!(params.width <= 4);
+        assert_eq!(state.len(), params.width);
+
+        // the round constants of each round are added in the linear layer of the previous round
+        let zero_var = self.zero_var();
+        let mut state: Vec<VarIndex> = state
+            .iter()
+            .zip(params.round_constants[0].iter())
+            .map(|(var, c)| {
+                self.poseidon_linear_gate(
+                    &[*var, zero_var, zero_var, zero_var],
+                    &[F::one(), F::zero(), F::zero(), F::zero()],
+                    *c,
+                )
+            })
+            .collect();
+
+        for round in 0..params.num_rounds() {
+            if params.is_full_round(round) {
+                for var in state.iter_mut() {
+                    *var = self.poseidon_sbox(params, *var);
+                }
+            } else {
+                state[0] = self.poseidon_sbox(params, state[0]);
+            }
+
+            let constants = params.round_constants.get(round + 1).map(|c| c.as_slice());
+            state = self.poseidon_linear_layer(params, &state, constants);
+        }
+        state
+    }
+
+    /// Create constraints for the Poseidon sponge and return the `output_len` output variables.
+    /// The input is padded in the same way as `PoseidonParams::hash`.
+    pub fn poseidon_hash(
+        &mut self,
+        params: &PoseidonParams<F>,
+        input: &[VarIndex],
+        output_len: usize,
+    ) -> Vec<VarIndex> {
+        let zero_var = self.zero_var();
+        let one_var = self.one_var();
+
+        let mut padded = input.to_vec();
+        padded.push(one_var);
+        while padded.len() % params.rate != 0 {
+            padded.push(zero_var);
+        }
+
+        let mut state = vec![zero_var; params.width];
+        for chunk in padded.chunks(params.rate) {
+            for (x, y) in state.iter_mut().zip(chunk.iter()) {
+                if *y != zero_var {
+                    *x = if *x == zero_var { *y } else { self.add(*x, *y) };
+                }
+            }
+            state = self.poseidon_permutation(params, &state);
+        }
+
+        let mut output = Vec::with_capacity(output_len);
+        loop {
+            output.extend_from_slice(&state[..params.rate]);
+            if output.len() >= output_len {
+                output.truncate(output_len);
+                return output;
+            }
+            state = self.poseidon_permutation(params, &state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::plonk::constraint_system::TurboCS;
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+    use noah_crypto::poseidon::poseidon_bn254;
+
+    #[test]
+    fn test_poseidon_constraint_system() {
+        let params = poseidon_bn254();
+        let mut prng = test_rng();
+        let input = (0..3)
+            .map(|_| BN254Scalar::random(&mut prng))
+            .collect::<Vec<_>>();
+        let expected = params.hash(&input, 3);
+
+        let mut cs = TurboCS::<BN254Scalar>::new();
+        let input_vars = input
+            .iter()
+            .map(|x| cs.new_variable(*x))
+            .collect::<Vec<_>>();
+        let output_vars = cs.poseidon_hash(&params, &input_vars, 3);
+
+        let mut witness = cs.get_and_clear_witness();
+        for (var, value) in output_vars.iter().zip(expected.iter()) {
+            assert_eq!(witness[*var], *value);
+        }
+        cs.verify_witness(&witness, &[]).unwrap();
+
+        witness[output_vars[0]] = witness[output_vars[0]] + BN254Scalar::one();
+        assert!(cs.verify_witness(&witness, &[]).is_err());
+    }
+}
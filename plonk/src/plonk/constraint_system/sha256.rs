@@ -0,0 +1,187 @@
+use crate::plonk::constraint_system::{uint32::UInt32, CircuitBuilder, VarIndex};
+use noah_algebra::prelude::*;
+
+/// The initial hash value of SHA-256.
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The round constants of SHA-256.
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Return the exclusive or of the three rotations of the word.
+fn sigma<F: Scalar, CS: CircuitBuilder<F>>(cs: &mut CS, x: &UInt32, r: [usize; 3]) -> UInt32 {
+    let t = x.rotr(r[0]).xor(cs, &x.rotr(r[1]));
+    t.xor(cs, &x.rotr(r[2]))
+}
+
+/// Return the exclusive or of the two rotations and the shift of the word.
+fn small_sigma<F: Scalar, CS: CircuitBuilder<F>>(
+    cs: &mut CS,
+    x: &UInt32,
+    r: [usize; 2],
+    s: usize,
+) -> UInt32 {
+    let t = x.rotr(r[0]).xor(cs, &x.rotr(r[1]));
+    let shifted = x.shr(cs, s);
+    t.xor(cs, &shifted)
+}
+
+/// Apply the compression function of SHA-256 to the state with the block of 16 words.
+fn sha256_compress<F: Scalar, CS: CircuitBuilder<F>>(
+    cs: &mut CS,
+    state: &[UInt32; 8],
+    block: &[UInt32],
+) -> [UInt32; 8] {
+    let mut w = block.to_vec();
+    for t in 16..64 {
+        let s0 = small_sigma(cs, &w[t - 15], [7, 18], 3);
+        let s1 = small_sigma(cs, &w[t - 2], [17, 19], 10);
+        let word = UInt32::add_many(cs, &[s1, w[t - 7], s0, w[t - 16]]);
+        w.push(word);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for t in 0..64 {
+        let s1 = sigma(cs, &e, [6, 11, 25]);
+        let ch = UInt32::choose(cs, &e, &f, &g);
+        let k = UInt32::constant(cs, SHA256_K[t]);
+        let s0 = sigma(cs, &a, [2, 13, 22]);
+        let maj = UInt32::majority(cs, &a, &b, &c);
+
+        let new_e = UInt32::add_many(cs, &[d, h, s1, ch, k, w[t]]);
+        let new_a = UInt32::add_many(cs, &[h, s1, ch, k, w[t], s0, maj]);
+        h = g;
+        g = f;
+        f = e;
+        e = new_e;
+        d = c;
+        c = b;
+        b = a;
+        a = new_a;
+    }
+
+    let mut new_state = *state;
+    for (x, y) in new_state.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+        *x = UInt32::add_many(cs, &[*x, *y]);
+    }
+    new_state
+}
+
+/// Create constraints for the SHA-256 hash of the message and return the bits of the digest.
+/// The bits of the message, which are boolean-constrained by the caller, and those of the digest
+/// are in the order of the bit string, i.e., from the most significant bit of the first byte.
+pub fn sha256<F: Scalar, CS: CircuitBuilder<F>>(
+    cs: &mut CS,
+    message: &[VarIndex],
+) -> Vec<VarIndex> {
+    let zero_var = cs.zero_var();
+    let one_var = cs.one_var();
+
+    // the padding of a one, the zeros, and the length of the message in 64 bits
+    let mut padded = message.to_vec();
+    padded.push(one_var);
+    while padded.len() % 512 != 448 {
+        padded.push(zero_var);
+    }
+    let len = message.len() as u64;
+    padded.extend((0..64).rev().map(|i| {
+        if (len >> i) & 1 == 1 {
+            one_var
+        } else {
+            zero_var
+        }
+    }));
+
+    let words = padded
+        .chunks(32)
+        .map(|chunk| {
+            let mut bits = [zero_var; 32];
+            for (bit, var) in bits.iter_mut().zip(chunk.iter().rev()) {
+                *bit = *var;
+            }
+            UInt32 { bits }
+        })
+        .collect::<Vec<_>>();
+
+    let mut state = SHA256_IV.map(|x| UInt32::constant(cs, x));
+    for block in words.chunks(16) {
+        state = sha256_compress(cs, &state, block);
+    }
+
+    state
+        .iter()
+        .flat_map(|word| word.bits.into_iter().rev())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::plonk::constraint_system::{sha256::sha256, TurboCS, VarIndex};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    /// Allocate the bits of the bytes, from the most significant bit of the first byte.
+    fn alloc_bits(cs: &mut TurboCS<BN254Scalar>, bytes: &[u8]) -> Vec<VarIndex> {
+        let mut bits = Vec::with_capacity(bytes.len() * 8);
+        for byte in bytes.iter() {
+            for i in (0..8).rev() {
+                let bit = cs.new_variable(BN254Scalar::from(((byte >> i) & 1) as u64));
+                cs.insert_boolean_gate(bit);
+                bits.push(bit);
+            }
+        }
+        bits
+    }
+
+    /// Return the bytes of the bits in the witness.
+    fn bytes_of(witness: &[BN254Scalar], bits: &[VarIndex]) -> Vec<u8> {
+        bits.chunks(8)
+            .map(|chunk| {
+                chunk.iter().fold(0u8, |acc, bit| {
+                    (acc << 1) | (!witness[*bit].is_zero()) as u8
+                })
+            })
+            .collect()
+    }
+
+    fn check(message: &[u8], expected: [u8; 32]) {
+        let mut cs = TurboCS::<BN254Scalar>::new();
+        let bits = alloc_bits(&mut cs, message);
+        let digest = sha256(&mut cs, &bits);
+        assert_eq!(digest.len(), 256);
+
+        let mut witness = cs.get_and_clear_witness();
+        assert_eq!(bytes_of(&witness, &digest), expected);
+        cs.verify_witness(&witness, &[]).unwrap();
+
+        witness[digest[0]] = BN254Scalar::one() - witness[digest[0]];
+        assert!(cs.verify_witness(&witness, &[]).is_err());
+    }
+
+    #[test]
+    fn test_sha256() {
+        check(
+            b"abc",
+            [
+                186, 120, 22, 191, 143, 1, 207, 234, 65, 65, 64, 222, 93, 174, 34, 35, 176, 3, 97,
+                163, 150, 23, 122, 156, 180, 16, 255, 97, 242, 0, 21, 173,
+            ],
+        );
+        check(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            [
+                36, 141, 106, 97, 210, 6, 56, 184, 229, 192, 38, 147, 12, 62, 96, 57, 163, 60, 228,
+                89, 100, 255, 33, 103, 246, 236, 237, 212, 25, 219, 6, 193,
+            ],
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! It also implements a set of arithmetic/boolean/range gates that
 //! will be used in anonymous transfer.
-use super::{ConstraintSystem, CsIndex, VarIndex};
+use super::{CircuitBuilder, ConstraintSystem, CsIndex, VarIndex};
 use crate::errors::{PlonkError, Result};
 use ark_std::{borrow::ToOwned, format};
 use noah_algebra::prelude::*;
@@ -50,6 +50,10 @@ pub struct TurboCS<F> {
     #[cfg(feature = "debug")]
     #[serde(skip)]
     pub witness_backtrace: HashMap<VarIndex, std::backtrace::Backtrace>,
+    /// the identifier that tags the wires of the circuit.
+    #[cfg(feature = "debug")]
+    #[serde(skip)]
+    pub id: u64,
 }
 
 impl<F: Scalar> ConstraintSystem for TurboCS<F> {
@@ -190,6 +194,8 @@ impl<F: Scalar> ConstraintSystem for TurboCS<F> {
 
             #[cfg(feature = "debug")]
             witness_backtrace: HashMap::new(),
+            #[cfg(feature = "debug")]
+            id: self.id,
         }
     }
 
@@ -228,31 +234,71 @@ impl<F: Scalar> ConstraintSystem for TurboCS<F> {
     }
 }
 
-/// A helper function that computes the little-endian binary
-/// representation of a value. Each bit is represented as a field
-/// element.
-fn compute_binary_le<F: Scalar>(bytes: &[u8]) -> Vec<F> {
-    let mut res = vec![];
-    for byte in bytes.iter() {
-        let mut tmp = *byte;
-        for _ in 0..8 {
-            if (tmp & 1) == 0 {
-                res.push(F::zero());
-            } else {
-                res.push(F::one());
-            }
-            tmp >>= 1;
-        }
-    }
-    res
-}
-
 impl<F: Scalar> Default for TurboCS<F> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl<F: Scalar> CircuitBuilder<F> for TurboCS<F> {
+    fn zero_var(&self) -> VarIndex {
+        TurboCS::zero_var(self)
+    }
+
+    fn one_var(&self) -> VarIndex {
+        TurboCS::one_var(self)
+    }
+
+    fn witness_value(&self, var: VarIndex) -> F {
+        self.witness[var]
+    }
+
+    fn new_variable(&mut self, value: F) -> VarIndex {
+        TurboCS::new_variable(self, value)
+    }
+
+    fn circuit_id(&self) -> u64 {
+        #[cfg(feature = "debug")]
+        {
+            self.id
+        }
+        #[cfg(not(feature = "debug"))]
+        {
+            0
+        }
+    }
+
+    fn insert_turbo_gate(
+        &mut self,
+        wires: [VarIndex; N_WIRES_PER_GATE],
+        selectors: [F; N_SELECTORS],
+    ) {
+        assert!(
+            wires.iter().all(|&x| x < self.num_vars),
+            "wire index out of bound"
+        );
+        for (selector, value) in self.selectors.iter_mut().zip(selectors) {
+            selector.push(value);
+        }
+        for (wiring, wire) in self.wiring.iter_mut().zip(wires) {
+            wiring.push(wire);
+        }
+        self.finish_new_gate();
+    }
+
+    fn attach_boolean_constraint_to_gate(&mut self) {
+        TurboCS::attach_boolean_constraint_to_gate(self)
+    }
+
+    fn insert_constant_gate(&mut self, var: VarIndex, constant: F) {
+        TurboCS::insert_constant_gate(self, var, constant)
+    }
+
+    fn prepare_pi_variable(&mut self, var: VarIndex) {
+        TurboCS::prepare_pi_variable(self, var)
+    }
+}
+
 impl<F: Scalar> TurboCS<F> {
     /// Create a TurboPLONK constraint system with a certain field size.
     /// With default witness [F::zero(), F::one()].
@@ -276,6 +322,8 @@ impl<F: Scalar> TurboCS<F> {
 
             #[cfg(feature = "debug")]
             witness_backtrace: HashMap::new(),
+            #[cfg(feature = "debug")]
+            id: super::next_circuit_id(),
         };
 
         cs.insert_constant_gate(cs.zero_var(), F::zero());
@@ -548,58 +596,7 @@ impl<F: Scalar> TurboCS<F> {
     ///    vector is in little endian form.
     pub fn range_check(&mut self, var: VarIndex, n_bits: usize) -> Vec<VarIndex> {
         assert!(var < self.num_vars, "var index out of bound");
-        assert!(n_bits >= 2, "the number of bits is less than two");
-        let witness_bytes = self.witness[var].to_bytes();
-        let mut binary_repr = compute_binary_le::<F>(&witness_bytes);
-        while binary_repr.len() < n_bits {
-            binary_repr.push(F::zero());
-        }
-
-        let b: Vec<VarIndex> = binary_repr
-            .into_iter()
-            .take(n_bits)
-            .map(|val| self.new_variable(val))
-            .collect();
-
-        let one = F::one();
-        let two = one.add(&one);
-        let four = two.add(&two);
-        let eight = four.add(&four);
-        let bin = [one, two, four, eight];
-
-        let mut acc = b[n_bits - 1];
-        self.insert_boolean_gate(b[n_bits - 1]);
-        let m = (n_bits - 2) / 3;
-        for i in 0..m {
-            acc = self.linear_combine(
-                &[
-                    acc,
-                    b[n_bits - 1 - i * 3 - 1],
-                    b[n_bits - 1 - i * 3 - 2],
-                    b[n_bits - 1 - i * 3 - 3],
-                ],
-                bin[3],
-                bin[2],
-                bin[1],
-                bin[0],
-            );
-            self.attach_boolean_constraint_to_gate();
-        }
-        let zero = F::zero();
-        match (n_bits - 1) - 3 * m {
-            1 => self.insert_lc_gate(&[acc, b[0], 0, 0], var, bin[1], bin[0], zero, zero),
-            2 => self.insert_lc_gate(&[acc, b[1], b[0], 0], var, bin[2], bin[1], bin[0], zero),
-            _ => self.insert_lc_gate(
-                &[acc, b[2], b[1], b[0]],
-                var,
-                bin[3],
-                bin[2],
-                bin[1],
-                bin[0],
-            ),
-        }
-        self.attach_boolean_constraint_to_gate();
-        b
+        CircuitBuilder::range_check(self, var, n_bits)
     }
 
     /// Given two variables `var0` and `var1` and a boolean variable `bit`, return var_bit.
@@ -1455,6 +1452,23 @@ macro_rules! _test_turbo {
             )
             .is_ok());
 
+            // the proof does not depend on the scheduling of the parallel prover
+            let proofs = (0..2)
+                .map(|_| {
+                    let mut transcript = Transcript::new(b"TestTurboPlonk");
+                    prover(
+                        &mut test_rng(),
+                        &mut transcript,
+                        pcs,
+                        cs,
+                        &prover_params,
+                        witness,
+                    )
+                    .unwrap()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(proofs[0], proofs[1]);
+
             let prover_cs = cs.shrink_to_verifier_only();
 
             let mut transcript = Transcript::new(b"TestTurboPlonk");
@@ -0,0 +1,301 @@
+use crate::plonk::constraint_system::{turbo::N_SELECTORS, CircuitBuilder, VarIndex};
+use noah_algebra::prelude::*;
+
+/// A 32-bit word as its bits in little-endian order, for the hash functions over words.
+/// The bits are boolean-constrained when they are allocated, and a bit that is the zero
+/// or the one variable of the constraint system is a constant, for which no gate is added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UInt32 {
+    /// The bits, from the least significant one.
+    pub bits: [VarIndex; 32],
+}
+
+/// Return the selectors `(q1, q2, q3, q4, qm1, qm2, qc, q_ecc, qo)` of a gate with `qo = 1`.
+fn selectors<F: Scalar>(q: [F; 4], qm: [F; 2], qc: F) -> [F; N_SELECTORS] {
+    [
+        q[0],
+        q[1],
+        q[2],
+        q[3],
+        qm[0],
+        qm[1],
+        qc,
+        F::zero(),
+        F::one(),
+    ]
+}
+
+/// Return the constant of the bit, if it is one.
+fn constant_bit<F: Scalar, CS: CircuitBuilder<F>>(cs: &CS, bit: VarIndex) -> Option<bool> {
+    if bit == cs.zero_var() {
+        Some(false)
+    } else if bit == cs.one_var() {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Return the zero or the one variable for the constant bit.
+fn bit_var<F: Scalar, CS: CircuitBuilder<F>>(cs: &CS, bit: bool) -> VarIndex {
+    if bit {
+        cs.one_var()
+    } else {
+        cs.zero_var()
+    }
+}
+
+/// Create an output variable for the value of the gate, and insert the gate.
+fn gate<F: Scalar, CS: CircuitBuilder<F>>(
+    cs: &mut CS,
+    wires: [VarIndex; 4],
+    selectors: [F; N_SELECTORS],
+) -> VarIndex {
+    let w = wires.map(|var| cs.witness_value(var));
+    let mut value = selectors[6];
+    for (x, q) in w.iter().zip(selectors.iter()) {
+        value.add_assign(&x.mul(q));
+    }
+    value.add_assign(&w[0].mul(&w[1]).mul(&selectors[4]));
+    value.add_assign(&w[2].mul(&w[3]).mul(&selectors[5]));
+    let out = cs.new_variable(value);
+    cs.insert_turbo_gate([wires[0], wires[1], wires[2], wires[3], out], selectors);
+    out
+}
+
+/// Return the negation of the bit.
+fn not_bit<F: Scalar, CS: CircuitBuilder<F>>(cs: &mut CS, a: VarIndex) -> VarIndex {
+    if let Some(a) = constant_bit(cs, a) {
+        return bit_var(cs, !a);
+    }
+    let zero_var = cs.zero_var();
+    let (zero, one) = (F::zero(), F::one());
+    gate(
+        cs,
+        [a, zero_var, zero_var, zero_var],
+        selectors([one.neg(), zero, zero, zero], [zero, zero], one),
+    )
+}
+
+/// Return the exclusive or of the bits, `a + b - 2ab`.
+fn xor_bit<F: Scalar, CS: CircuitBuilder<F>>(cs: &mut CS, a: VarIndex, b: VarIndex) -> VarIndex {
+    match (constant_bit(cs, a), constant_bit(cs, b)) {
+        (Some(false), _) => b,
+        (_, Some(false)) => a,
+        (Some(true), _) => not_bit(cs, b),
+        (_, Some(true)) => not_bit(cs, a),
+        _ if a == b => cs.zero_var(),
+        _ => {
+            let zero_var = cs.zero_var();
+            let (zero, one) = (F::zero(), F::one());
+            gate(
+                cs,
+                [a, b, zero_var, zero_var],
+                selectors([one, one, zero, zero], [one.double().neg(), zero], zero),
+            )
+        }
+    }
+}
+
+/// Return the sum of the terms with the coefficients, with a gate per three terms.
+fn linear_sum<F: Scalar, CS: CircuitBuilder<F>>(cs: &mut CS, terms: &[(VarIndex, F)]) -> VarIndex {
+    let zero_var = cs.zero_var();
+    let (zero, one) = (F::zero(), F::one());
+    let mut acc = (zero_var, zero);
+    for chunk in terms.chunks(3) {
+        let mut wires = [acc.0, zero_var, zero_var, zero_var];
+        let mut coefs = [acc.1, zero, zero, zero];
+        for (i, (var, coef)) in chunk.iter().enumerate() {
+            wires[i + 1] = *var;
+            coefs[i + 1] = *coef;
+        }
+        acc = (
+            cs.linear_combine(&wires, coefs[0], coefs[1], coefs[2], coefs[3]),
+            one,
+        );
+    }
+    acc.0
+}
+
+impl UInt32 {
+    /// Return the word of the constant, which adds no variable.
+    pub fn constant<F: Scalar, CS: CircuitBuilder<F>>(cs: &CS, value: u32) -> Self {
+        let mut bits = [cs.zero_var(); 32];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit = bit_var(cs, (value >> i) & 1 == 1);
+        }
+        Self { bits }
+    }
+
+    /// Allocate the word with boolean-constrained bits.
+    pub fn alloc<F: Scalar, CS: CircuitBuilder<F>>(cs: &mut CS, value: u32) -> Self {
+        let mut bits = [cs.zero_var(); 32];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit = cs.new_variable(F::from(((value >> i) & 1) as u64));
+            cs.insert_boolean_gate(*bit);
+        }
+        Self { bits }
+    }
+
+    /// Return the value of the word in the witness.
+    pub fn value<F: Scalar, CS: CircuitBuilder<F>>(&self, cs: &CS) -> u32 {
+        self.bits
+            .iter()
+            .enumerate()
+            .filter(|(_, bit)| !cs.witness_value(**bit).is_zero())
+            .fold(0, |acc, (i, _)| acc | (1 << i))
+    }
+
+    /// Rotate the word to the right, which adds no constraint.
+    pub fn rotr(&self, n: usize) -> Self {
+        let mut bits = self.bits;
+        bits.rotate_left(n % 32);
+        Self { bits }
+    }
+
+    /// Shift the word to the right, which adds no constraint.
+    pub fn shr<F: Scalar, CS: CircuitBuilder<F>>(&self, cs: &CS, n: usize) -> Self {
+        let mut bits = [cs.zero_var(); 32];
+        bits[..32 - n].copy_from_slice(&self.bits[n..]);
+        Self { bits }
+    }
+
+    /// Return the exclusive or of the words, with a constraint per non-constant bit.
+    pub fn xor<F: Scalar, CS: CircuitBuilder<F>>(&self, cs: &mut CS, other: &Self) -> Self {
+        let mut bits = self.bits;
+        for (bit, other_bit) in bits.iter_mut().zip(other.bits.iter()) {
+            *bit = xor_bit(cs, *bit, *other_bit);
+        }
+        Self { bits }
+    }
+
+    /// Return the word whose bits are taken from `b` where the bits of `a` are one, and from `c`
+    /// elsewhere, i.e., `(a & b) ^ (!a & c)` = `c + a * b - a * c`, with a constraint per bit.
+    pub fn choose<F: Scalar, CS: CircuitBuilder<F>>(
+        cs: &mut CS,
+        a: &Self,
+        b: &Self,
+        c: &Self,
+    ) -> Self {
+        let (zero, one) = (F::zero(), F::one());
+        let bits = core::array::from_fn(|i| match constant_bit(cs, a.bits[i]) {
+            Some(true) => b.bits[i],
+            Some(false) => c.bits[i],
+            None => gate(
+                cs,
+                [a.bits[i], b.bits[i], a.bits[i], c.bits[i]],
+                selectors([zero, zero, zero, one], [one, one.neg()], zero),
+            ),
+        });
+        Self { bits }
+    }
+
+    /// Return the bitwise majority of the words, `(a & b) ^ (a & c) ^ (b & c)` = `a * b + c * (a ^ b)`.
+    pub fn majority<F: Scalar, CS: CircuitBuilder<F>>(
+        cs: &mut CS,
+        a: &Self,
+        b: &Self,
+        c: &Self,
+    ) -> Self {
+        let (zero, one) = (F::zero(), F::one());
+        let bits = core::array::from_fn(|i| {
+            let constants = (
+                constant_bit(cs, a.bits[i]),
+                constant_bit(cs, b.bits[i]),
+                constant_bit(cs, c.bits[i]),
+            );
+            if let (Some(x), Some(y), Some(z)) = constants {
+                bit_var(cs, (x & y) ^ (x & z) ^ (y & z))
+            } else {
+                let x = xor_bit(cs, a.bits[i], b.bits[i]);
+                gate(
+                    cs,
+                    [a.bits[i], b.bits[i], c.bits[i], x],
+                    selectors([zero; 4], [one, one], zero),
+                )
+            }
+        });
+        Self { bits }
+    }
+
+    /// Return the sum of the words modulo 2^32. The sum is decomposed into bits, of which the
+    /// carries beyond the 32 bits are dropped.
+    pub fn add_many<F: Scalar, CS: CircuitBuilder<F>>(cs: &mut CS, words: &[Self]) -> Self {
+        assert!(!words.is_empty());
+
+        let mut constant = 0u64;
+        let mut terms = Vec::new();
+        for word in words.iter() {
+            let mut coef = F::one();
+            for (i, bit) in word.bits.iter().enumerate() {
+                match constant_bit(cs, *bit) {
+                    Some(true) => constant += 1 << i,
+                    Some(false) => {}
+                    None => terms.push((*bit, coef)),
+                }
+                coef = coef.double();
+            }
+        }
+        if terms.is_empty() {
+            return Self::constant(cs, constant as u32);
+        }
+        if constant != 0 {
+            terms.push((cs.one_var(), F::from(constant)));
+        }
+
+        let sum = linear_sum(cs, &terms);
+        let n_bits = 32 + (usize::BITS - (words.len() - 1).leading_zeros()) as usize;
+        let sum_bits = cs.range_check(sum, n_bits);
+        let mut bits = [cs.zero_var(); 32];
+        bits.copy_from_slice(&sum_bits[..32]);
+        Self { bits }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::plonk::constraint_system::{uint32::UInt32, TurboCS};
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    #[test]
+    fn test_uint32() {
+        let mut prng = test_rng();
+        let (x, y, z) = (prng.next_u32(), prng.next_u32(), prng.next_u32());
+
+        let mut cs = TurboCS::<BN254Scalar>::new();
+        let a = UInt32::alloc(&mut cs, x);
+        let b = UInt32::alloc(&mut cs, y);
+        let c = UInt32::constant(&cs, z);
+
+        assert_eq!(a.rotr(7).value(&cs), x.rotate_right(7));
+        assert_eq!(a.shr(&cs, 10).value(&cs), x >> 10);
+        assert_eq!(a.xor(&mut cs, &c).value(&cs), x ^ z);
+        assert_eq!(
+            UInt32::choose(&mut cs, &a, &b, &c).value(&cs),
+            (x & y) ^ (!x & z)
+        );
+        assert_eq!(
+            UInt32::majority(&mut cs, &a, &b, &c).value(&cs),
+            (x & y) ^ (x & z) ^ (y & z)
+        );
+        let sum = UInt32::add_many(&mut cs, &[a, b, c, a]);
+        assert_eq!(
+            sum.value(&cs),
+            x.wrapping_add(y).wrapping_add(z).wrapping_add(x)
+        );
+
+        // the constants add no constraint
+        let size = cs.size;
+        let d = UInt32::constant(&cs, y);
+        assert_eq!(
+            UInt32::add_many(&mut cs, &[c, d]).value(&cs),
+            z.wrapping_add(y)
+        );
+        assert_eq!(cs.size, size);
+
+        let mut witness = cs.get_and_clear_witness();
+        cs.verify_witness(&witness, &[]).unwrap();
+        witness[sum.bits[0]] = BN254Scalar::one() - witness[sum.bits[0]];
+        assert!(cs.verify_witness(&witness, &[]).is_err());
+    }
+}
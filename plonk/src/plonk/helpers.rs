@@ -741,19 +741,21 @@ pub(super) fn eval_pi_poly<PCS: PolyComScheme>(
     eval_point: &PCS::Field,
     root: &PCS::Field,
 ) -> PCS::Field {
-    let mut eval = PCS::Field::zero();
-
-    for ((constraint_index, public_value), lagrange_constant) in verifier_params
+    // X - \omega^j j-th Lagrange denominator
+    let mut denominators_inv = verifier_params
         .public_vars_constraint_indices
+        .iter()
+        .map(|constraint_index| eval_point.sub(&root.pow(&[*constraint_index as u64])))
+        .collect::<Vec<PCS::Field>>();
+    PCS::Field::batch_invert(&mut denominators_inv).unwrap();
+
+    let mut eval = PCS::Field::zero();
+    for ((denominator_inv, public_value), lagrange_constant) in denominators_inv
         .iter()
         .zip(public_inputs)
         .zip(verifier_params.lagrange_constants.iter())
     {
-        // X - \omega^j j-th Lagrange denominator
-        let root_to_j = root.pow(&[*constraint_index as u64]);
-        let denominator = eval_point.sub(&root_to_j);
-        let denominator_inv = denominator.inv().unwrap();
-        let lagrange_i = lagrange_constant.mul(&denominator_inv);
+        let lagrange_i = lagrange_constant.mul(denominator_inv);
         eval.add_assign(&lagrange_i.mul(public_value));
     }
 
@@ -771,34 +773,45 @@ pub(super) fn eval_pi_poly<PCS: PolyComScheme>(
     eval_point: &PCS::Field,
     root: &PCS::Field,
 ) -> PCS::Field {
-    verifier_params
+    // X - \omega^j j-th Lagrange denominator
+    let mut denominators_inv = verifier_params
         .public_vars_constraint_indices
+        .par_iter()
+        .map(|constraint_index| eval_point.sub(&root.pow(&[*constraint_index as u64])))
+        .collect::<Vec<PCS::Field>>();
+    PCS::Field::batch_invert(&mut denominators_inv).unwrap();
+
+    denominators_inv
         .par_iter()
         .zip(public_inputs)
         .zip(&verifier_params.lagrange_constants)
-        .map(|((constraint_index, public_value), lagrange_constant)| {
-            let root_to_j = root.pow(&[*constraint_index as u64]);
-            let denominator = eval_point.sub(&root_to_j);
-            let denominator_inv = denominator.inv().unwrap();
-            let lagrange_i = lagrange_constant.mul(&denominator_inv);
+        .map(|((denominator_inv, public_value), lagrange_constant)| {
+            let lagrange_i = lagrange_constant.mul(denominator_inv);
             lagrange_i.mul(public_value)
         })
         .reduce(|| PCS::Field::zero(), |x, y| x.add(y))
         .mul(z_h_eval_zeta)
 }
 
-/// Compute constant c_j such that 1 = c_j * prod_{i != j} (\omega^j - \omega^i).
+/// Compute constants c_j such that 1 = c_j * prod_{i != j} (\omega^j - \omega^i).
 /// In such case, j-th lagrange base can be represented
 /// by L_j(X) = c_j (X^n-1) / (X- \omega^j)
-pub(super) fn compute_lagrange_constant<F: Scalar>(group: &[F], base_index: usize) -> F {
-    let mut constant_inv = F::one();
-    for (i, elem) in group.iter().enumerate() {
-        if i == base_index {
-            continue;
-        }
-        constant_inv.mul_assign(&group[base_index].sub(elem));
-    }
-    constant_inv.inv().unwrap()
+pub(super) fn compute_lagrange_constants<F: Scalar>(group: &[F], base_indices: &[usize]) -> Vec<F> {
+    let mut constants = base_indices
+        .iter()
+        .map(|base_index| {
+            let mut constant_inv = F::one();
+            for (i, elem) in group.iter().enumerate() {
+                if i == *base_index {
+                    continue;
+                }
+                constant_inv.mul_assign(&group[*base_index].sub(elem));
+            }
+            constant_inv
+        })
+        .collect::<Vec<F>>();
+    F::batch_invert(&mut constants).unwrap();
+    constants
 }
 
 /// Evaluate the r polynomial at point \zeta.
@@ -887,8 +900,7 @@ pub(crate) fn split_t_and_commit<R: CryptoRng + RngCore, PCS: PolyComScheme>(
     n_wires_per_gate: usize,
     n: usize,
 ) -> Result<(Vec<PCS::Commitment>, Vec<FpPolynomial<PCS::Field>>)> {
-    let mut cm_t_vec = vec![];
-    let mut t_polys = vec![];
+    let mut t_chunks = vec![];
     let coefs_len = t.get_coefs_ref().len();
 
     let zero = PCS::Field::zero();
@@ -920,46 +932,53 @@ pub(crate) fn split_t_and_commit<R: CryptoRng + RngCore, PCS: PolyComScheme>(
         }
         prev_coef = rand;
 
-        let (cm_t, t_poly) = if let Some(lagrange_pcs) = lagrange_pcs {
-            let degree = coefs.len();
-            let mut max_power_of_2 = degree;
-            for i in (0..=degree).rev() {
-                if (i & (i - 1)) == 0 {
-                    max_power_of_2 = i;
-                    break;
-                }
-            }
-
-            let mut blinds = vec![];
-            for i in &coefs[max_power_of_2..] {
-                blinds.push(i.neg());
-            }
-
-            let mut new_coefs = coefs[..max_power_of_2].to_vec();
-            for (i, v) in blinds.iter().enumerate() {
-                new_coefs[i] -= v;
-            }
-
-            let sub_q = FpPolynomial::from_coefs(new_coefs);
-            let q_eval = FpPolynomial::fft(&sub_q, max_power_of_2).ok_or(PlonkError::ProofError)?;
-            let q_eval = FpPolynomial::from_coefs(q_eval);
-
-            let cm = lagrange_pcs.commit(&q_eval)?;
-            let cm_t = pcs.apply_blind_factors(&cm, &blinds, max_power_of_2);
-            (cm_t, FpPolynomial::from_coefs(coefs))
-        } else {
-            let t_poly = FpPolynomial::from_coefs(coefs);
-            let cm_t = pcs
-                .commit(&t_poly)
-                .map_err(|_| PlonkError::CommitmentError)?;
-            (cm_t, t_poly)
-        };
-
-        cm_t_vec.push(cm_t);
-        t_polys.push(t_poly);
+        t_chunks.push(coefs);
     }
 
-    Ok((cm_t_vec, t_polys))
+    // The chunks are blinded in order above, and committed in parallel with the feature `parallel`.
+    let cm_and_t_polys = cfg_into_iter!(t_chunks)
+        .map(
+            |coefs| -> Result<(PCS::Commitment, FpPolynomial<PCS::Field>)> {
+                if let Some(lagrange_pcs) = lagrange_pcs {
+                    let degree = coefs.len();
+                    let mut max_power_of_2 = degree;
+                    for i in (0..=degree).rev() {
+                        if (i & (i - 1)) == 0 {
+                            max_power_of_2 = i;
+                            break;
+                        }
+                    }
+
+                    let mut blinds = vec![];
+                    for i in &coefs[max_power_of_2..] {
+                        blinds.push(i.neg());
+                    }
+
+                    let mut new_coefs = coefs[..max_power_of_2].to_vec();
+                    for (i, v) in blinds.iter().enumerate() {
+                        new_coefs[i] -= v;
+                    }
+
+                    let sub_q = FpPolynomial::from_coefs(new_coefs);
+                    let q_eval =
+                        FpPolynomial::fft(&sub_q, max_power_of_2).ok_or(PlonkError::ProofError)?;
+                    let q_eval = FpPolynomial::from_coefs(q_eval);
+
+                    let cm = lagrange_pcs.commit(&q_eval)?;
+                    let cm_t = pcs.apply_blind_factors(&cm, &blinds, max_power_of_2);
+                    Ok((cm_t, FpPolynomial::from_coefs(coefs)))
+                } else {
+                    let t_poly = FpPolynomial::from_coefs(coefs);
+                    let cm_t = pcs
+                        .commit(&t_poly)
+                        .map_err(|_| PlonkError::CommitmentError)?;
+                    Ok((cm_t, t_poly))
+                }
+            },
+        )
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(cm_and_t_polys.into_iter().unzip())
 }
 
 /// for a evaluation domain H, when x = 1, L_1(x) = (x^n-1) / (x-1) != 0,
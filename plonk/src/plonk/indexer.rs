@@ -1,7 +1,12 @@
 use crate::errors::{PlonkError, Result};
-use crate::plonk::{constraint_system::ConstraintSystem, helpers::compute_lagrange_constant};
-use crate::poly_commit::{field_polynomial::FpPolynomial, pcs::PolyComScheme};
+use crate::plonk::{constraint_system::ConstraintSystem, helpers::compute_lagrange_constants};
+use crate::poly_commit::{
+    field_polynomial::FpPolynomial,
+    pcs::{PolyComScheme, ToBytes},
+    transcript::PolyComTranscript,
+};
 use ark_poly::EvaluationDomain;
+use merlin::Transcript;
 use noah_algebra::{prelude::*, traits::Domain};
 use rand_chacha::ChaChaRng;
 
@@ -117,6 +122,52 @@ pub struct PlonkVerifierParams<C, F> {
 pub type PlonkVK<PCS> =
     PlonkVerifierParams<<PCS as PolyComScheme>::Commitment, <PCS as PolyComScheme>::Field>;
 
+/// The identifier of a circuit, which is the hash of the layout of its constraint system
+/// as fixed by the verifier parameters.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct CircuitId(pub [u8; 32]);
+
+impl<C: ToBytes, F: Scalar> PlonkVerifierParams<C, F> {
+    /// Compute the identifier of the circuit that these parameters were generated for.
+    pub fn circuit_id(&self) -> CircuitId {
+        let mut transcript = Transcript::new(b"Plonk circuit id");
+        transcript.append_u64(b"cs size", self.cs_size as u64);
+        transcript.append_u64(
+            b"number of public inputs",
+            self.public_vars_constraint_indices.len() as u64,
+        );
+        for index in self.public_vars_constraint_indices.iter() {
+            transcript.append_u64(b"public constraint index", *index as u64);
+        }
+        for cm in self
+            .cm_q_vec
+            .iter()
+            .chain(self.cm_s_vec.iter())
+            .chain([&self.cm_qb])
+            .chain(self.cm_prk_vec.iter())
+        {
+            transcript.append_commitment(cm);
+        }
+        for elem in [&self.anemoi_generator, &self.anemoi_generator_inv]
+            .into_iter()
+            .chain(self.k.iter())
+        {
+            transcript.append_field_elem(elem);
+        }
+
+        let mut id = [0u8; 32];
+        transcript.challenge_bytes(b"circuit id", &mut id);
+        CircuitId(id)
+    }
+}
+
+impl<O, C: ToBytes, F: Scalar> PlonkProverParams<O, C, F> {
+    /// Compute the identifier of the circuit that these parameters were generated for.
+    pub fn circuit_id(&self) -> CircuitId {
+        self.verifier_params.circuit_id()
+    }
+}
+
 /// Encode the permutation value, from an index to a group element.
 pub fn encode_perm_to_group<F: Scalar>(group: &[F], perm: &[usize], k: &[F]) -> Vec<F> {
     let n = group.len();
@@ -268,9 +319,8 @@ pub fn indexer_with_lagrange<PCS: PolyComScheme, CS: ConstraintSystem<Field = PC
     // Step 4: compute the Lagrange interpolation constants.
     let mut lagrange_constants = vec![];
     if no_verifier {
-        for constraint_index in cs.public_vars_constraint_indices().iter() {
-            lagrange_constants.push(compute_lagrange_constant(&group, *constraint_index));
-        }
+        lagrange_constants =
+            compute_lagrange_constants(&group, cs.public_vars_constraint_indices());
     }
 
     // Step 5: commit `boolean_constraint_indices`.
@@ -17,3 +17,6 @@ pub mod transcript;
 
 /// Module for verifier.
 pub mod verifier;
+
+/// Module for checking that the proofs do not reuse randomness.
+pub mod zk_check;
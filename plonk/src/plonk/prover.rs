@@ -18,7 +18,11 @@ use crate::poly_commit::{
 use ark_poly::Radix2EvaluationDomain;
 use ark_std::{end_timer, start_timer};
 use merlin::Transcript;
-use noah_algebra::{prelude::*, traits::Domain};
+use noah_algebra::{cfg_into_iter, prelude::*, traits::Domain};
+use rand_chacha::ChaChaRng;
+
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 #[cfg(target_arch = "wasm32")]
 use {noah_algebra::bls12_381::init_fast_msm_wasm, wasm_bindgen::prelude::*};
@@ -108,6 +112,17 @@ pub fn prover_with_lagrange<
     if cs.is_verifier_only() {
         return Err(PlonkError::FuncParamsError);
     }
+    if cs.size() != prover_params.verifier_params.cs_size
+        || cs.public_vars_constraint_indices()
+            != prover_params
+                .verifier_params
+                .public_vars_constraint_indices
+                .as_slice()
+        || prover_params.permutation.len() != cs.size() * CS::n_wires_per_gate()
+        || w.len() != cs.num_vars()
+    {
+        return Err(PlonkError::ParamsMismatch);
+    }
 
     let prover_timer = start_timer!(|| "TurboPlonk::Prover");
 
@@ -151,66 +166,47 @@ pub fn prover_with_lagrange<
 
     // 1. build witness polynomials, hide them and commit
     let n_wires_per_gate = CS::n_wires_per_gate();
-    let mut w_polys = vec![];
-    let mut cm_w_vec = vec![];
 
     let w_timer = start_timer!(|| "Round 1: witness polynomials");
-    if let Some(lagrange_pcs) = lagrange_pcs {
-        for i in 0..n_wires_per_gate {
-            let this_w_timer = start_timer!(|| format!("Round 1: processing wire {}", i));
-
-            let this_w_poly_timer = start_timer!(|| "Prepare the polynomial");
-            let f_eval = FpPolynomial::from_coefs(
-                extended_witness[i * n_constraints..(i + 1) * n_constraints].to_vec(),
-            );
-            let mut f_coefs = FpPolynomial::ifft_with_domain(
-                &domain,
-                &extended_witness[i * n_constraints..(i + 1) * n_constraints],
-            );
-
-            let blinds =
-                hide_polynomial(prng, &mut f_coefs, cs.get_hiding_degree(i), n_constraints);
-            end_timer!(this_w_poly_timer);
-
-            let this_w_comm_timer = start_timer!(|| "Commit the polynomial");
-
-            let cm_w = lagrange_pcs
-                .commit(&f_eval)
-                .map_err(|_| PlonkError::CommitmentError)?;
-            let cm_w = pcs.apply_blind_factors(&cm_w, &blinds, n_constraints);
-            transcript.append_commitment::<PCS::Commitment>(&cm_w);
-            end_timer!(this_w_comm_timer);
-
-            w_polys.push(f_coefs);
-            cm_w_vec.push(cm_w);
-
-            end_timer!(this_w_timer);
-        }
-    } else {
-        for i in 0..n_wires_per_gate {
-            let this_w_timer = start_timer!(|| format!("Round 1: processing wire {}", i));
-
-            let this_w_poly_timer = start_timer!(|| "Prepare the polynomial");
-            let mut f_coefs = FpPolynomial::ifft_with_domain(
+    // The wires are interpolated and committed in parallel with the feature `parallel`,
+    // while the blinds are sampled in the order of the wires.
+    let w_poly_timer = start_timer!(|| "Prepare the polynomials");
+    let mut w_polys: Vec<FpPolynomial<PCS::Field>> = cfg_into_iter!(0..n_wires_per_gate)
+        .map(|i| {
+            FpPolynomial::ifft_with_domain(
                 &domain,
                 &extended_witness[i * n_constraints..(i + 1) * n_constraints],
-            );
-            let _ = hide_polynomial(prng, &mut f_coefs, cs.get_hiding_degree(i), n_constraints);
-            end_timer!(this_w_poly_timer);
-
-            let this_w_comm_timer = start_timer!(|| "Commit the polynomial");
-            let cm_w = pcs
-                .commit(&f_coefs)
-                .map_err(|_| PlonkError::CommitmentError)?;
-            transcript.append_commitment::<PCS::Commitment>(&cm_w);
-            end_timer!(this_w_comm_timer);
-
-            w_polys.push(f_coefs);
-            cm_w_vec.push(cm_w);
-
-            end_timer!(this_w_timer);
-        }
+            )
+        })
+        .collect();
+    let w_blinds: Vec<Vec<PCS::Field>> = w_polys
+        .iter_mut()
+        .enumerate()
+        .map(|(i, f_coefs)| hide_polynomial(prng, f_coefs, cs.get_hiding_degree(i), n_constraints))
+        .collect();
+    end_timer!(w_poly_timer);
+
+    let w_comm_timer = start_timer!(|| "Commit the polynomials");
+    let cm_w_vec: Vec<PCS::Commitment> = cfg_into_iter!(0..n_wires_per_gate)
+        .map(|i| -> Result<PCS::Commitment> {
+            if let Some(lagrange_pcs) = lagrange_pcs {
+                let f_eval = FpPolynomial::from_coefs(
+                    extended_witness[i * n_constraints..(i + 1) * n_constraints].to_vec(),
+                );
+                let cm_w = lagrange_pcs
+                    .commit(&f_eval)
+                    .map_err(|_| PlonkError::CommitmentError)?;
+                Ok(pcs.apply_blind_factors(&cm_w, &w_blinds[i], n_constraints))
+            } else {
+                pcs.commit(&w_polys[i])
+                    .map_err(|_| PlonkError::CommitmentError)
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for cm_w in cm_w_vec.iter() {
+        transcript.append_commitment::<PCS::Commitment>(cm_w);
     }
+    end_timer!(w_comm_timer);
     end_timer!(w_timer);
 
     // 2. get challenges beta and gamma
@@ -288,13 +284,11 @@ pub fn prover_with_lagrange<
     // evaluate the opening of z(X) at point \omega * \zeta.
     let r_timer = start_timer!(|| "Round 4: r polynomial and the rest");
     let eval_timer = start_timer!(|| "Compute the evaluation of polynomials");
-    let w_polys_eval_zeta: Vec<PCS::Field> =
-        w_polys.iter().map(|poly| pcs.eval(poly, &zeta)).collect();
-    let s_polys_eval_zeta: Vec<PCS::Field> = prover_params
-        .s_polys
-        .iter()
-        .take(n_wires_per_gate - 1)
-        .map(|poly| pcs.eval(poly, &zeta))
+    let w_polys_eval_zeta: Vec<PCS::Field> = cfg_into_iter!(0..n_wires_per_gate)
+        .map(|i| pcs.eval(&w_polys[i], &zeta))
+        .collect();
+    let s_polys_eval_zeta: Vec<PCS::Field> = cfg_into_iter!(0..n_wires_per_gate - 1)
+        .map(|i| pcs.eval(&prover_params.s_polys[i], &zeta))
         .collect();
 
     let prk_3_poly_eval_zeta = pcs.eval(&prover_params.q_prk_polys[2], &zeta);
@@ -409,8 +403,121 @@ pub fn prover_with_lagrange<
     })
 }
 
+/// PLONK Prover whose randomness is derived from the transcript, the parameters, the public
+/// inputs, and the witness, as the nonces of RFC 6979, so that proving the same witness twice
+/// gives byte-identical proofs, e.g., for reproducible artifacts or for deduplicating jobs.
+///
+/// The blinds are pseudorandom only to a verifier who cannot guess the witness, so this
+/// should not be used for witnesses of low entropy.
+pub fn deterministic_prover<PCS: PolyComScheme, CS: ConstraintSystem<Field = PCS::Field>>(
+    transcript: &mut Transcript,
+    pcs: &PCS,
+    lagrange_pcs: Option<&PCS>,
+    cs: &CS,
+    prover_params: &PlonkPK<PCS>,
+    w: &[PCS::Field],
+) -> Result<PlonkPf<PCS>> {
+    if w.len() != cs.num_vars() {
+        return Err(PlonkError::ParamsMismatch);
+    }
+    let domain = FpPolynomial::<PCS::Field>::evaluation_domain(cs.size())
+        .ok_or(PlonkError::GroupNotFound(cs.size()))?;
+    let root = PCS::Field::from_field(domain.group_gen);
+    let online_values: Vec<PCS::Field> = cs
+        .public_vars_witness_indices()
+        .iter()
+        .map(|index| w[*index])
+        .collect();
+
+    // Bind the randomness to the statement as the prover does, and then to the witness.
+    let mut rng_transcript = transcript.clone();
+    rng_transcript.append_message(b"New Domain", b"Deterministic PLONK prover");
+    transcript_init_plonk::<_, PCS::Field>(
+        &mut rng_transcript,
+        &prover_params.verifier_params,
+        &online_values,
+        &root,
+    );
+    let witness_bytes = w.iter().flat_map(|x| x.to_bytes()).collect_vec();
+    let mut prng = rng_transcript
+        .build_rng()
+        .rekey_with_witness_bytes(b"witness", &witness_bytes)
+        .finalize(&mut ChaChaRng::from_seed([0u8; 32]));
+
+    prover_with_lagrange(
+        &mut prng,
+        transcript,
+        pcs,
+        lagrange_pcs,
+        cs,
+        prover_params,
+        w,
+    )
+}
+
 #[cfg(target_arch = "wasm32")]
 /// Init prover
 pub async fn init_prover() -> core::result::Result<(), JsValue> {
     init_fast_msm_wasm().await
 }
+
+#[cfg(test)]
+mod test {
+    use crate::plonk::{
+        constraint_system::TurboCS, indexer::indexer, prover::deterministic_prover,
+        verifier::verifier,
+    };
+    use crate::poly_commit::kzg_poly_com::KZGCommitmentSchemeBN254;
+    use merlin::Transcript;
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn test_deterministic_prover() {
+        let mut prng = ChaChaRng::from_seed([0u8; 32]);
+        let pcs = KZGCommitmentSchemeBN254::new(20, &mut prng);
+
+        let build = |x: u32, y: u32| {
+            let mut cs = TurboCS::new();
+            let x = cs.new_variable(BN254Scalar::from(x));
+            let y = cs.new_variable(BN254Scalar::from(y));
+            let _ = cs.mul(x, y);
+            cs.pad();
+            let witness = cs.get_and_clear_witness();
+            (cs, witness)
+        };
+        let (cs, witness) = build(3, 7);
+        let params = indexer(&cs, &pcs).unwrap();
+        let prove = |label: &'static [u8], witness: &[BN254Scalar]| {
+            deterministic_prover(
+                &mut Transcript::new(label),
+                &pcs,
+                None,
+                &cs,
+                &params,
+                witness,
+            )
+            .unwrap()
+        };
+
+        let proof = prove(b"Test", &witness);
+        assert_eq!(
+            serde_json::to_vec(&proof).unwrap(),
+            serde_json::to_vec(&prove(b"Test", &witness)).unwrap()
+        );
+        verifier(
+            &mut Transcript::new(b"Test"),
+            &pcs,
+            &cs,
+            &params.get_verifier_params(),
+            &[],
+            &proof,
+        )
+        .unwrap();
+
+        // the randomness depends on the transcript and on the witness
+        assert_ne!(proof, prove(b"Other", &witness));
+        let (_, other_witness) = build(7, 3);
+        assert_ne!(proof, prove(b"Test", &other_witness));
+    }
+}
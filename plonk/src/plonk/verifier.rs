@@ -1,5 +1,7 @@
 use crate::errors::{PlonkError, Result};
-use crate::poly_commit::{pcs::PolyComScheme, transcript::PolyComTranscript};
+use crate::poly_commit::{
+    kzg_poly_com::KZGCommitmentScheme, pcs::PolyComScheme, transcript::PolyComTranscript,
+};
 use crate::{
     plonk::{
         constraint_system::ConstraintSystem,
@@ -14,7 +16,31 @@ use crate::{
     poly_commit::field_polynomial::FpPolynomial,
 };
 use merlin::Transcript;
-use noah_algebra::{prelude::*, traits::Domain};
+use noah_algebra::{
+    prelude::*,
+    traits::{Domain, Pairing},
+};
+
+/// The domain separator for the batch verification of Plonk proofs.
+const PLONK_BATCH_VERIFICATION_TRANSCRIPT: &[u8] = b"Plonk Batch Verification";
+
+/// The openings at two points that a proof reduces to, which are left to the polynomial
+/// commitment scheme to check, possibly together with the openings of other proofs.
+#[derive(Debug, Clone)]
+pub struct PlonkDeferredOpening<PCS: PolyComScheme> {
+    /// The batched commitments, at \zeta and at \zeta * \omega.
+    pub cm_vec: Vec<PCS::Commitment>,
+    /// The maximal degree of the polynomials.
+    pub degree: usize,
+    /// The points, \zeta and \zeta * \omega.
+    pub point_vec: Vec<PCS::Field>,
+    /// The batched evaluations at the points.
+    pub eval_vec: Vec<PCS::Field>,
+    /// The opening proofs at the points.
+    pub proofs: Vec<PCS::Commitment>,
+    /// The challenge that combines the openings.
+    pub challenge: PCS::Field,
+}
 
 /// Verify a proof.
 pub fn verifier<PCS: PolyComScheme, CS: ConstraintSystem<Field = PCS::Field>>(
@@ -25,6 +51,94 @@ pub fn verifier<PCS: PolyComScheme, CS: ConstraintSystem<Field = PCS::Field>>(
     pi: &[PCS::Field],
     proof: &PlonkPf<PCS>,
 ) -> Result<()> {
+    let opening = verifier_deferred(transcript, pcs, cs, verifier_params, pi, proof)?;
+    pcs.batch_verify_diff_points(
+        transcript,
+        &opening.cm_vec,
+        opening.degree,
+        &opening.point_vec,
+        &opening.eval_vec,
+        &opening.proofs,
+        &opening.challenge,
+    )
+    .map_err(|_| PlonkError::VerificationError)
+}
+
+/// Verify many independent proofs, each with its own transcript, constraint system, verifier
+/// parameters and public inputs, over the same SRS. The opening checks of all the proofs are
+/// combined with random scalars, derived from all the openings, into one multi-pairing.
+pub fn batch_verify<P: Pairing, CS: ConstraintSystem<Field = P::ScalarField>>(
+    transcripts: &mut [Transcript],
+    pcs: &KZGCommitmentScheme<P>,
+    cs: &[&CS],
+    verifier_params: &[&PlonkVK<KZGCommitmentScheme<P>>],
+    public_inputs: &[&[P::ScalarField]],
+    proofs: &[&PlonkPf<KZGCommitmentScheme<P>>],
+) -> Result<()> {
+    let n = proofs.len();
+    if n == 0
+        || transcripts.len() != n
+        || cs.len() != n
+        || verifier_params.len() != n
+        || public_inputs.len() != n
+    {
+        return Err(PlonkError::FuncParamsError);
+    }
+
+    let mut batch_transcript = Transcript::new(PLONK_BATCH_VERIFICATION_TRANSCRIPT);
+    batch_transcript.append_u64(b"Number of proofs", n as u64);
+
+    let mut accumulators = Vec::with_capacity(n);
+    for ((((transcript, cs), verifier_params), pi), proof) in transcripts
+        .iter_mut()
+        .zip(cs.iter())
+        .zip(verifier_params.iter())
+        .zip(public_inputs.iter())
+        .zip(proofs.iter())
+    {
+        let opening = verifier_deferred(transcript, pcs, *cs, verifier_params, pi, proof)?;
+        let (left, right) = pcs.accumulate(
+            &opening.cm_vec,
+            &opening.point_vec,
+            &opening.eval_vec,
+            &opening.proofs,
+            &opening.challenge,
+        );
+
+        batch_transcript.append_message(b"left", &left.to_compressed_bytes());
+        batch_transcript.append_message(b"right", &right.to_compressed_bytes());
+        accumulators.push((left, right));
+    }
+
+    let rho = batch_transcript.get_challenge_field_elem::<P::ScalarField>(b"rho");
+    let mut multiplier = P::ScalarField::one();
+    let mut left = P::G1::get_identity();
+    let mut right = P::G1::get_identity();
+    for (l, r) in accumulators.iter() {
+        left.add_assign(&l.mul(&multiplier));
+        right.add_assign(&r.mul(&multiplier));
+        multiplier.mul_assign(&rho);
+    }
+
+    pcs.check_accumulator(&left, &right)
+        .map_err(|_| PlonkError::VerificationError)
+}
+
+/// Run the verifier up to the check of the openings, and return the openings instead.
+pub fn verifier_deferred<PCS: PolyComScheme, CS: ConstraintSystem<Field = PCS::Field>>(
+    transcript: &mut Transcript,
+    pcs: &PCS,
+    cs: &CS,
+    verifier_params: &PlonkVK<PCS>,
+    pi: &[PCS::Field],
+    proof: &PlonkPf<PCS>,
+) -> Result<PlonkDeferredOpening<PCS>> {
+    if cs.size() != verifier_params.cs_size
+        || pi.len() != verifier_params.public_vars_constraint_indices.len()
+    {
+        return Err(PlonkError::ParamsMismatch);
+    }
+
     let domain = FpPolynomial::<PCS::Field>::evaluation_domain(cs.size())
         .ok_or(PlonkError::GroupNotFound(cs.size()))?;
     let root = PCS::Field::from_field(domain.group_gen);
@@ -127,19 +241,17 @@ pub fn verifier<PCS: PolyComScheme, CS: ConstraintSystem<Field = PCS::Field>>(
         ],
     );
 
-    pcs.batch_verify_diff_points(
-        transcript,
-        &[comm, comm_omega],
-        verifier_params.cs_size + 32,
-        &[*zeta, zeta_omega],
-        &[val, val_omega],
-        &[
+    Ok(PlonkDeferredOpening {
+        cm_vec: vec![comm, comm_omega],
+        degree: verifier_params.cs_size + 32,
+        point_vec: vec![*zeta, zeta_omega],
+        eval_vec: vec![val, val_omega],
+        proofs: vec![
             proof.opening_witness_zeta.clone(),
             proof.opening_witness_zeta_omega.clone(),
         ],
-        challenges.get_u().unwrap(),
-    )
-    .map_err(|_| PlonkError::VerificationError)
+        challenge: *challenges.get_u().unwrap(),
+    })
 }
 
 fn compute_challenges<PCS: PolyComScheme>(
@@ -185,3 +297,93 @@ fn compute_challenges<PCS: PolyComScheme>(
     let u = transcript_get_plonk_challenge_u(transcript, group_order);
     challenges.insert_u(u).unwrap();
 }
+
+#[cfg(test)]
+mod test {
+    use crate::plonk::{
+        constraint_system::TurboCS, indexer::indexer, prover::prover, verifier::batch_verify,
+    };
+    use crate::poly_commit::kzg_poly_com::KZGCommitmentSchemeBN254;
+    use merlin::Transcript;
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+
+    #[test]
+    fn test_batch_verify() {
+        let mut prng = test_rng();
+        let pcs = KZGCommitmentSchemeBN254::new(40, &mut prng);
+
+        // two circuits: x * y = z and x + y = z, with z public
+        let mut cs_mul = TurboCS::new();
+        let x = cs_mul.new_variable(BN254Scalar::from(3u32));
+        let y = cs_mul.new_variable(BN254Scalar::from(7u32));
+        let z = cs_mul.mul(x, y);
+        cs_mul.prepare_pi_variable(z);
+        cs_mul.pad();
+
+        let mut cs_add = TurboCS::new();
+        let x = cs_add.new_variable(BN254Scalar::from(3u32));
+        let y = cs_add.new_variable(BN254Scalar::from(7u32));
+        let z = cs_add.add(x, y);
+        cs_add.prepare_pi_variable(z);
+        cs_add.pad();
+
+        let mut proofs = vec![];
+        let mut params = vec![];
+        for cs in [&mut cs_mul, &mut cs_add] {
+            let witness = cs.get_and_clear_witness();
+            let prover_params = indexer(cs, &pcs).unwrap();
+            let proof = prover(
+                &mut prng,
+                &mut Transcript::new(b"Test"),
+                &pcs,
+                cs,
+                &prover_params,
+                &witness,
+            )
+            .unwrap();
+            proofs.push(proof);
+            params.push(prover_params.get_verifier_params());
+        }
+
+        let cs = [&cs_mul, &cs_add];
+        let vks = [&params[0], &params[1]];
+        let pfs = [&proofs[0], &proofs[1]];
+        let transcripts = || [Transcript::new(b"Test"), Transcript::new(b"Test")];
+
+        let pi_mul = [BN254Scalar::from(21u32)];
+        let pi_add = [BN254Scalar::from(10u32)];
+        assert!(batch_verify(
+            &mut transcripts(),
+            &pcs,
+            &cs,
+            &vks,
+            &[&pi_mul[..], &pi_add[..]],
+            &pfs
+        )
+        .is_ok());
+
+        // one wrong public input fails the whole batch
+        let wrong_pi_add = [BN254Scalar::from(11u32)];
+        assert!(batch_verify(
+            &mut transcripts(),
+            &pcs,
+            &cs,
+            &vks,
+            &[&pi_mul[..], &wrong_pi_add[..]],
+            &pfs
+        )
+        .is_err());
+
+        // so does a proof verified against another transcript
+        let mut wrong_transcripts = [Transcript::new(b"Test"), Transcript::new(b"Other")];
+        assert!(batch_verify(
+            &mut wrong_transcripts,
+            &pcs,
+            &cs,
+            &vks,
+            &[&pi_mul[..], &pi_add[..]],
+            &pfs
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::plonk::indexer::PlonkProof;
+use noah_algebra::prelude::*;
+
+/// Collect the non-trivial elements of a proof, with the names of the fields that hold them.
+/// The identity commitment and the scalars zero and one are trivial, since they can appear
+/// in any proof, e.g., for a selector that is never used.
+fn nontrivial_elements<C: Default + PartialEq, F: Scalar>(
+    proof: &PlonkProof<C, F>,
+) -> (Vec<(&'static str, &C)>, Vec<(&'static str, &F)>) {
+    let mut commitments = Vec::new();
+    commitments.extend(proof.cm_w_vec.iter().map(|c| ("cm_w_vec", c)));
+    commitments.extend(proof.cm_t_vec.iter().map(|c| ("cm_t_vec", c)));
+    commitments.push(("cm_z", &proof.cm_z));
+    commitments.push(("opening_witness_zeta", &proof.opening_witness_zeta));
+    commitments.push((
+        "opening_witness_zeta_omega",
+        &proof.opening_witness_zeta_omega,
+    ));
+    commitments.retain(|(_, c)| **c != C::default());
+
+    let mut scalars = Vec::new();
+    scalars.push(("prk_3_poly_eval_zeta", &proof.prk_3_poly_eval_zeta));
+    scalars.push(("prk_4_poly_eval_zeta", &proof.prk_4_poly_eval_zeta));
+    scalars.extend(
+        proof
+            .w_polys_eval_zeta
+            .iter()
+            .map(|v| ("w_polys_eval_zeta", v)),
+    );
+    scalars.extend(
+        proof
+            .w_polys_eval_zeta_omega
+            .iter()
+            .map(|v| ("w_polys_eval_zeta_omega", v)),
+    );
+    scalars.push(("z_eval_zeta_omega", &proof.z_eval_zeta_omega));
+    scalars.extend(
+        proof
+            .s_polys_eval_zeta
+            .iter()
+            .map(|v| ("s_polys_eval_zeta", v)),
+    );
+    scalars.retain(|(_, v)| !v.is_zero() && !v.is_one());
+
+    (commitments, scalars)
+}
+
+/// Return the names of the fields of `a` whose non-trivial elements also appear anywhere in `b`.
+/// Two proofs of the same statement with independent randomness share no such elements,
+/// so a non-empty result means that the prover reuses randomness across proofs.
+pub fn shared_elements<C: Default + PartialEq, F: Scalar>(
+    a: &PlonkProof<C, F>,
+    b: &PlonkProof<C, F>,
+) -> Vec<&'static str> {
+    let (a_commitments, a_scalars) = nontrivial_elements(a);
+    let (b_commitments, b_scalars) = nontrivial_elements(b);
+
+    let mut shared = Vec::new();
+    for (name, c) in a_commitments.iter() {
+        if b_commitments.iter().any(|(_, d)| c == d) && !shared.contains(name) {
+            shared.push(*name);
+        }
+    }
+    for (name, v) in a_scalars.iter() {
+        if b_scalars.iter().any(|(_, w)| v == w) && !shared.contains(name) {
+            shared.push(*name);
+        }
+    }
+    shared
+}
+
+/// Check that the two proofs have the same structure, i.e., the same number of elements in each field,
+/// so that the proofs, or a real and a simulated proof, cannot be told apart by their shapes.
+pub fn same_structure<C, F>(a: &PlonkProof<C, F>, b: &PlonkProof<C, F>) -> bool {
+    a.cm_w_vec.len() == b.cm_w_vec.len()
+        && a.cm_t_vec.len() == b.cm_t_vec.len()
+        && a.w_polys_eval_zeta.len() == b.w_polys_eval_zeta.len()
+        && a.w_polys_eval_zeta_omega.len() == b.w_polys_eval_zeta_omega.len()
+        && a.s_polys_eval_zeta.len() == b.s_polys_eval_zeta.len()
+}
+
+/// Prove the same statement twice with `prove`, which is given a fresh call for each proof,
+/// and panic if the two proofs are linkable or differ in structure.
+pub fn assert_transcript_independence<C, F, P>(mut prove: P)
+where
+    C: Default + PartialEq,
+    F: Scalar,
+    P: FnMut() -> PlonkProof<C, F>,
+{
+    let a = prove();
+    let b = prove();
+
+    assert!(
+        same_structure(&a, &b),
+        "the two proofs of the same statement differ in structure"
+    );
+    let shared = shared_elements(&a, &b);
+    assert!(
+        shared.is_empty(),
+        "the two proofs of the same statement share elements in {:?}",
+        shared
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use crate::plonk::{
+        constraint_system::TurboCS,
+        indexer::indexer,
+        prover::prover,
+        zk_check::{assert_transcript_independence, same_structure, shared_elements},
+    };
+    use crate::poly_commit::kzg_poly_com::KZGCommitmentSchemeBN254;
+    use merlin::Transcript;
+    use noah_algebra::{bn254::BN254Scalar, prelude::*};
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn test_transcript_independence() {
+        let mut prng = ChaChaRng::from_seed([0u8; 32]);
+        let pcs = KZGCommitmentSchemeBN254::new(20, &mut prng);
+
+        let mut cs = TurboCS::new();
+        let x = cs.new_variable(BN254Scalar::from(3u32));
+        let y = cs.new_variable(BN254Scalar::from(7u32));
+        let _ = cs.mul(x, y);
+        cs.pad();
+
+        let witness = cs.get_and_clear_witness();
+        let params = indexer(&cs, &pcs).unwrap();
+        let prove = |prng: &mut ChaChaRng| {
+            prover(
+                prng,
+                &mut Transcript::new(b"Test"),
+                &pcs,
+                &cs,
+                &params,
+                &witness,
+            )
+            .unwrap()
+        };
+
+        // the randomness of each proof is drawn afresh from the stream
+        assert_transcript_independence(|| prove(&mut prng));
+
+        // reusing the randomness is detected
+        let a = prove(&mut ChaChaRng::from_seed([1u8; 32]));
+        let b = prove(&mut ChaChaRng::from_seed([1u8; 32]));
+        assert!(same_structure(&a, &b));
+        assert!(shared_elements(&a, &b).contains(&"cm_w_vec"));
+    }
+}
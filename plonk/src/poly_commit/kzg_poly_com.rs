@@ -7,6 +7,7 @@ use merlin::Transcript;
 use noah_algebra::bls12_381::BLSPairingEngine;
 use noah_algebra::bn254::BN254PairingEngine;
 use noah_algebra::{
+    msm::msm,
     prelude::*,
     traits::{Domain, Pairing},
 };
@@ -24,6 +25,13 @@ where
     }
 }
 
+impl<G: Group> KZGCommitment<G> {
+    /// Deserialize the commitment from the compressed bytes given by `to_bytes`.
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(KZGCommitment(G::from_compressed_bytes(bytes)?))
+    }
+}
+
 impl<G: Group> HomomorphicPolyComElem<G::ScalarType> for KZGCommitment<G> {
     fn get_base() -> Self {
         KZGCommitment(G::get_base())
@@ -108,6 +116,13 @@ impl<G: Group> ToBytes for KZGOpenProof<G> {
     }
 }
 
+impl<G: Group> KZGOpenProof<G> {
+    /// Deserialize the proof from the compressed bytes given by `to_bytes`.
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(KZGOpenProof(G::from_compressed_bytes(bytes)?))
+    }
+}
+
 /// KZG commitment scheme about `PairingEngine`.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct KZGCommitmentScheme<P: Pairing> {
@@ -197,6 +212,60 @@ impl<P: Pairing> KZGCommitmentScheme<P> {
             public_parameter_group_2: p2,
         })
     }
+
+    /// Reduce the openings at different points to a pair `(left, right)` of G1 elements,
+    /// such that the openings are valid if and only if `e(left, [x]_2) = e(right, [1]_2)`.
+    pub fn accumulate(
+        &self,
+        cm_vec: &[KZGCommitment<P::G1>],
+        point_vec: &[P::ScalarField],
+        eval_vec: &[P::ScalarField],
+        proofs: &[KZGCommitment<P::G1>],
+        challenge: &P::ScalarField,
+    ) -> (P::G1, P::G1) {
+        assert!(!proofs.is_empty());
+        assert_eq!(proofs.len(), point_vec.len());
+        assert_eq!(proofs.len(), eval_vec.len());
+        assert_eq!(proofs.len(), cm_vec.len());
+
+        let g1_0 = self.public_parameter_group_1[0];
+
+        let mut left_first = proofs[0].0;
+        let mut right_first = proofs[0].0.mul(&point_vec[0]);
+        let mut right_first_val = eval_vec[0];
+        let mut right_first_comm = cm_vec[0].0;
+
+        let mut cur_challenge = *challenge;
+        for i in 1..proofs.len() {
+            let new_comm = proofs[i].0.mul(&cur_challenge);
+
+            left_first.add_assign(&new_comm);
+            right_first.add_assign(&new_comm.mul(&point_vec[i]));
+            right_first_val.add_assign(&eval_vec[i].mul(&cur_challenge));
+            right_first_comm.add_assign(&cm_vec[i].0.mul(&cur_challenge));
+
+            cur_challenge.mul_assign(challenge);
+        }
+        right_first.sub_assign(&g1_0.mul(&right_first_val));
+        right_first.add_assign(&right_first_comm);
+
+        (left_first, right_first)
+    }
+
+    /// Check the pair obtained from `accumulate`, i.e., `e(left, [x]_2) = e(right, [1]_2)`.
+    pub fn check_accumulator(&self, left: &P::G1, right: &P::G1) -> Result<()> {
+        let g2_0 = self.public_parameter_group_2[0];
+        let g2_1 = self.public_parameter_group_2[1];
+
+        let right = right.neg();
+        let pairing_eval = P::multi_pairing(&[(left, &g2_1), (&right, &g2_0)]);
+
+        if pairing_eval == P::Gt::get_identity() {
+            Ok(())
+        } else {
+            Err(PlonkError::PCSProveEvalError)
+        }
+    }
 }
 
 impl<P: Pairing> PolyComScheme for KZGCommitmentScheme<P> {
@@ -221,8 +290,8 @@ impl<P: Pairing> PolyComScheme for KZGCommitmentScheme<P> {
             .iter()
             .collect();
 
-        let commitment_value =
-            P::G1::multi_exp(&coefs_poly_scalar_ref[..], &pub_param_group_1_as_ref[..]);
+        // The commitment uses the backend of the group, which may be an external kernel.
+        let commitment_value = msm(&coefs_poly_scalar_ref[..], &pub_param_group_1_as_ref[..])?;
 
         Ok(KZGCommitment::<P::G1>(commitment_value))
     }
@@ -273,17 +342,22 @@ impl<P: Pairing> PolyComScheme for KZGCommitmentScheme<P> {
         let g2_0 = self.public_parameter_group_2[0];
         let g2_1 = self.public_parameter_group_2[1];
 
-        let x_minus_point_group_element_group_2 = &g2_1.sub(&g2_0.mul(point));
+        let x_minus_point_group_element_group_2 = g2_1.sub(&g2_0.mul(point));
 
-        let left_pairing_eval = if value.is_zero() {
-            P::pairing(&commitment.0, &g2_0)
+        let left_first = if value.is_zero() {
+            commitment.0
         } else {
-            P::pairing(&commitment.0.sub(&g1_0.mul(value)), &g2_0)
+            commitment.0.sub(&g1_0.mul(value))
         };
+        let right_first = proof.0.neg();
 
-        let right_pairing_eval = P::pairing(&proof.0, x_minus_point_group_element_group_2);
+        // e(C - v * G1, G2) * e(-pi, (x - z) * G2) = 1
+        let pairing_eval = P::multi_pairing(&[
+            (&left_first, &g2_0),
+            (&right_first, &x_minus_point_group_element_group_2),
+        ]);
 
-        if left_pairing_eval == right_pairing_eval {
+        if pairing_eval == P::Gt::get_identity() {
             Ok(())
         } else {
             Err(PlonkError::PCSProveEvalError)
@@ -316,47 +390,8 @@ impl<P: Pairing> PolyComScheme for KZGCommitmentScheme<P> {
         proofs: &[Self::Commitment],
         challenge: &Self::Field,
     ) -> Result<()> {
-        assert!(!proofs.is_empty());
-        assert_eq!(proofs.len(), point_vec.len());
-        assert_eq!(proofs.len(), eval_vec.len());
-        assert_eq!(proofs.len(), cm_vec.len());
-
-        let g1_0 = self.public_parameter_group_1[0];
-        let g2_0 = self.public_parameter_group_2[0];
-        let g2_1 = self.public_parameter_group_2[1];
-
-        let left_second = g2_1;
-        let right_second = g2_0;
-
-        let mut left_first = proofs[0].0;
-        let mut right_first = proofs[0].0.mul(&point_vec[0]);
-        let mut right_first_val = eval_vec[0];
-        let mut right_first_comm = cm_vec[0].0;
-
-        let mut cur_challenge = *challenge;
-        for i in 1..proofs.len() {
-            let new_comm = proofs[i].0.mul(&cur_challenge);
-
-            left_first.add_assign(&new_comm);
-            right_first.add_assign(&new_comm.mul(&point_vec[i]));
-            right_first_val.add_assign(&eval_vec[i].mul(&cur_challenge));
-            right_first_comm.add_assign(&cm_vec[i].0.mul(&cur_challenge));
-
-            cur_challenge.mul_assign(challenge);
-        }
-        right_first.sub_assign(&g1_0.mul(&right_first_val));
-        right_first.add_assign(&right_first_comm);
-
-        let pairing_eval = P::product_of_pairings(
-            &[left_first, right_first.neg()],
-            &[left_second, right_second],
-        );
-
-        if pairing_eval == P::Gt::get_identity() {
-            Ok(())
-        } else {
-            Err(PlonkError::PCSProveEvalError)
-        }
+        let (left, right) = self.accumulate(cm_vec, point_vec, eval_vec, proofs, challenge);
+        self.check_accumulator(&left, &right)
     }
 
     fn shrink_to_verifier_only(&self) -> Self {
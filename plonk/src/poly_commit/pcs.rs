@@ -40,7 +40,7 @@ pub trait HomomorphicPolyComElem<F: Scalar>: ToBytes + Clone + Sync + Send + Def
 }
 
 /// Trait for polynomial commitment scheme.
-pub trait PolyComScheme: Sized {
+pub trait PolyComScheme: Sized + Sync {
     /// Type of prime field.
     type Field: Copy + Domain + Debug + Sync + Send;
 
@@ -7,18 +7,29 @@ mod smoke_axfr {
     use noah::parameters::AddressFormat::{ED25519, SECP256K1};
     use noah::{
         anon_xfr::{
+            abar_burn::*,
             abar_to_abar::*,
             abar_to_ar::*,
             abar_to_bar::*,
             ar_to_abar::*,
+            asset_tracing::{
+                add_axfr_tracer_memos, verify_axfr_tracing, AXfrTracerKeyPair, AXfrTracerMemo,
+            },
             bar_to_abar::*,
+            coinjoin::*,
+            disclosure::*,
+            fee_sponsorship::*,
+            proofs::*,
             structs::{
-                AnonAssetRecord, MTLeafInfo, MTNode, MTPath, OpenAnonAssetRecord,
-                OpenAnonAssetRecordBuilder,
+                AnonAssetRecord, AxfrOutputPayload, MTLeafInfo, MTNode, MTPath,
+                OpenAnonAssetRecord, OpenAnonAssetRecordBuilder,
             },
+            swap::*,
             FEE_TYPE,
         },
-        keys::{KeyPair, KeyType, PublicKey},
+        consensus::{NoteTrait, NoteVerifyContext},
+        keys::{KeyPair, KeyType, PublicKey, SignableBody},
+        serialization::to_bincode,
         xfr::{
             asset_record::{build_blind_asset_record, open_blind_asset_record, AssetRecordType},
             structs::{
@@ -27,8 +38,12 @@ mod smoke_axfr {
         },
     };
     use noah_accumulators::merkle_tree::{PersistentMerkleTree, Proof, TreePath};
-    use noah_algebra::{bn254::BN254Scalar, prelude::*, ristretto::PedersenCommitmentRistretto};
+    use noah_algebra::{
+        baby_jubjub::BabyJubjubPoint, bn254::BN254Scalar, prelude::*,
+        ristretto::PedersenCommitmentRistretto,
+    };
     use noah_crypto::anemoi_jive::{AnemoiJive, AnemoiJive254};
+    use noah_plonk::plonk::zk_check::assert_transcript_independence;
     use parking_lot::RwLock;
     use sha2::Sha512;
     use std::sync::Arc;
@@ -163,6 +178,31 @@ mod smoke_axfr {
         assert_eq!(oabar.get_asset_type(), ASSET);
     }
 
+    #[test]
+    fn ar_to_abar_proofs_are_unlinkable() {
+        let mut prng = test_rng();
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let receiver = KeyPair::sample(&mut prng, SECP256K1);
+        let pc_gens = PedersenCommitmentRistretto::default();
+        let params = ProverParams::gen_ar_to_abar().unwrap();
+
+        let (bar, memo) = build_bar(
+            &sender.get_pk(),
+            &mut prng,
+            &pc_gens,
+            AMOUNT,
+            ASSET,
+            AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
+        );
+        let obar = open_blind_asset_record(&bar, &memo, &sender).unwrap();
+
+        assert_transcript_independence(|| {
+            gen_ar_to_abar_body(&mut prng, &params, &obar, &receiver.get_pk())
+                .unwrap()
+                .proof
+        });
+    }
+
     #[test]
     fn bar_to_abar_secp256k1() {
         let mut prng = test_rng();
@@ -205,6 +245,14 @@ mod smoke_axfr {
         err_note.signature = bad_sig;
         assert!(verify_bar_to_abar_note(&verify_params, &err_note, &sender.get_pk()).is_err());
 
+        // sign the body elsewhere, as a hardware wallet would
+        let body =
+            gen_bar_to_abar_body_unsigned(&mut prng, &params, &obar, &receiver.get_pk()).unwrap();
+        let signature = sender.sign(&body.signing_payload().unwrap()).unwrap();
+        assert!(attach_signature(body.clone(), err_note.signature.clone()).is_err());
+        let external_note = attach_signature(body, signature).unwrap();
+        assert!(verify_bar_to_abar_note(&verify_params, &external_note, &sender.get_pk()).is_ok());
+
         #[cfg(feature = "parallel")]
         {
             let mut notes = vec![&note; 6];
@@ -338,6 +386,90 @@ mod smoke_axfr {
         assert_eq!(*obar.get_asset_type(), ASSET);
     }
 
+    #[test]
+    fn abar_burn_secp256k1() {
+        let mut prng = test_rng();
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        abar_burn(sender);
+    }
+
+    #[test]
+    fn abar_burn_ed25519() {
+        let mut prng = test_rng();
+        let sender = KeyPair::sample(&mut prng, ED25519);
+        abar_burn(sender);
+    }
+
+    fn abar_burn(sender: KeyPair) {
+        let mut prng = test_rng();
+
+        let address_format = match sender.get_sk_ref() {
+            SecretKey::Ed25519(_) => ED25519,
+            SecretKey::Secp256k1(_) => SECP256K1,
+        };
+
+        let params = ProverParams::gen_abar_to_ar(address_format).unwrap();
+        let verify_params = VerifierParams::get_abar_to_ar(address_format).unwrap();
+
+        let fdb = MemoryDB::new();
+        let cs = Arc::new(RwLock::new(ChainState::new(fdb, "abar_burn".to_owned(), 0)));
+        let mut state = State::new(cs, false);
+        let store = PrefixedStore::new("my_store", &mut state);
+        let mut mt = PersistentMerkleTree::new(store).unwrap();
+
+        let mut oabar = build_oabar(&mut prng, AMOUNT, ASSET, &sender);
+        let abar = AnonAssetRecord::from_oabar(&oabar);
+        mt.add_commitment_hash(hash_abar(0, &abar)).unwrap();
+        mt.commit().unwrap();
+        let proof = mt.generate_proof(0).unwrap();
+        oabar.update_mt_leaf_info(build_mt_leaf_info_from_proof(proof.clone(), 0));
+
+        let destination = b"0x52908400098527886E0F7030069857D2E4169EE7";
+        let hash = random_hasher(&mut prng);
+        let note = gen_abar_burn_note(
+            &mut prng,
+            &params,
+            &oabar,
+            &sender,
+            destination,
+            hash.clone(),
+        )
+        .unwrap();
+        assert_eq!(note.body.amount, AMOUNT);
+        assert_eq!(note.body.asset_type, ASSET);
+        verify_abar_burn_note(&verify_params, &note, &proof.root, hash.clone()).unwrap();
+
+        let err_root = BN254Scalar::random(&mut prng);
+        assert!(verify_abar_burn_note(&verify_params, &note, &err_root, hash.clone()).is_err());
+
+        let err_hash = random_hasher(&mut prng);
+        assert!(verify_abar_burn_note(&verify_params, &note, &proof.root, err_hash).is_err());
+
+        // the revealed amount, the asset type, and the destination are all bound
+        let mut err_amount = note.clone();
+        err_amount.body.amount += 1;
+        assert!(
+            verify_abar_burn_note(&verify_params, &err_amount, &proof.root, hash.clone()).is_err()
+        );
+
+        let mut err_asset_type = note.clone();
+        err_asset_type.body.asset_type = AssetType::from_identical_byte(2u8);
+        assert!(
+            verify_abar_burn_note(&verify_params, &err_asset_type, &proof.root, hash.clone())
+                .is_err()
+        );
+
+        let mut err_destination = note.clone();
+        err_destination.body.destination = b"another address".to_vec();
+        assert!(
+            verify_abar_burn_note(&verify_params, &err_destination, &proof.root, hash.clone())
+                .is_err()
+        );
+
+        let oversized = vec![0u8; MAX_ABAR_BURN_DESTINATION_SIZE + 1];
+        assert!(gen_abar_burn_note(&mut prng, &params, &oabar, &sender, &oversized, hash).is_err());
+    }
+
     #[test]
     fn abar_to_bar_secp256k1() {
         let mut prng = test_rng();
@@ -571,6 +703,738 @@ mod smoke_axfr {
         }
     }
 
+    #[test]
+    fn abar_1in_1out_sponsored_fee() {
+        let mut prng = test_rng();
+        let pc_gens = PedersenCommitmentRistretto::default();
+        let params = ProverParams::gen_abar_to_abar(1, 1, SECP256K1).unwrap();
+        let verifier_params = VerifierParams::load_abar_to_abar(1, 1, SECP256K1).unwrap();
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let fee_payer = KeyPair::sample(&mut prng, SECP256K1);
+
+        let mut oabar = build_oabar(&mut prng, AMOUNT, ASSET, &sender);
+        let abar = AnonAssetRecord::from_oabar(&oabar);
+
+        let fdb = MemoryDB::new();
+        let cs = Arc::new(RwLock::new(ChainState::new(
+            fdb,
+            "abar-sponsored".to_owned(),
+            0,
+        )));
+        let mut state = State::new(cs, false);
+        let store = PrefixedStore::new("my_store", &mut state);
+        let mut mt = PersistentMerkleTree::new(store).unwrap();
+        let uid = mt
+            .add_commitment_hash(hash_abar(mt.entry_count(), &abar))
+            .unwrap();
+        mt.commit().unwrap();
+        let root = mt.get_root().unwrap();
+        let proof = mt.generate_proof(uid).unwrap();
+        oabar.update_mt_leaf_info(build_mt_leaf_info_from_proof(proof, uid));
+
+        // the sender pays no fee in the note
+        let oabar_out = build_oabar(&mut prng, AMOUNT, ASSET, &receiver);
+        let pre_note =
+            init_anon_xfr_note(&[oabar.clone()], &[oabar_out.clone()], 0, &sender).unwrap();
+        let hash = random_hasher(&mut prng);
+        let note = finish_anon_xfr_note(&mut prng, &params, pre_note, hash.clone()).unwrap();
+
+        let fee = mock_fee(1, 1) as u64;
+        let (fee_input, _) = build_bar(
+            &fee_payer.get_pk(),
+            &mut prng,
+            &pc_gens,
+            fee,
+            FEE_TYPE,
+            AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
+        );
+        let sponsored_note = sponsor_anon_xfr_note(&fee_payer, &fee_input, note).unwrap();
+        assert!(verify_sponsored_anon_xfr_note(
+            &verifier_params,
+            &sponsored_note,
+            &root,
+            hash.clone(),
+            fee
+        )
+        .is_ok());
+
+        // the sponsored fee must cover the required fee
+        assert!(verify_sponsored_anon_xfr_note(
+            &verifier_params,
+            &sponsored_note,
+            &root,
+            hash.clone(),
+            fee + 1
+        )
+        .is_err());
+
+        // the sponsorship cannot be reused for another note
+        let pre_note = init_anon_xfr_note(&[oabar], &[oabar_out], 0, &sender).unwrap();
+        let other_hash = random_hasher(&mut prng);
+        let other_note =
+            finish_anon_xfr_note(&mut prng, &params, pre_note, other_hash.clone()).unwrap();
+        let reused_note = SponsoredAXfrNote {
+            note: other_note,
+            sponsorship: sponsored_note.sponsorship.clone(),
+        };
+        assert!(verify_sponsored_anon_xfr_note(
+            &verifier_params,
+            &reused_note,
+            &root,
+            other_hash,
+            fee
+        )
+        .is_err());
+
+        // the fee input must be transparent
+        let (confidential_fee_input, _) = build_bar(
+            &fee_payer.get_pk(),
+            &mut prng,
+            &pc_gens,
+            fee,
+            FEE_TYPE,
+            AssetRecordType::ConfidentialAmount_NonConfidentialAssetType,
+        );
+        assert!(sponsor_anon_xfr_note(
+            &fee_payer,
+            &confidential_fee_input,
+            sponsored_note.note.clone()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn abar_1in_2out_disclosure() {
+        let mut prng = test_rng();
+        let params = ProverParams::gen_abar_to_abar(1, 2, SECP256K1).unwrap();
+        let disclosure_params = ProverParams::gen_abar_disclosure().unwrap();
+        let disclosure_verifier_params = VerifierParams::get_abar_disclosure().unwrap();
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let auditor = KeyPair::sample(&mut prng, SECP256K1);
+
+        let fee = mock_fee(1, 2);
+        let mut oabar = build_oabar(&mut prng, AMOUNT + fee as u64, FEE_TYPE, &sender);
+        let abar = AnonAssetRecord::from_oabar(&oabar);
+
+        let fdb = MemoryDB::new();
+        let cs = Arc::new(RwLock::new(ChainState::new(
+            fdb,
+            "abar-disclosure".to_owned(),
+            0,
+        )));
+        let mut state = State::new(cs, false);
+        let store = PrefixedStore::new("my_store", &mut state);
+        let mut mt = PersistentMerkleTree::new(store).unwrap();
+        let uid = mt
+            .add_commitment_hash(hash_abar(mt.entry_count(), &abar))
+            .unwrap();
+        mt.commit().unwrap();
+        let proof = mt.generate_proof(uid).unwrap();
+        oabar.update_mt_leaf_info(build_mt_leaf_info_from_proof(proof, uid));
+
+        let payment = build_oabar(&mut prng, AMOUNT - 3, FEE_TYPE, &receiver);
+        let change = build_oabar(&mut prng, 3, FEE_TYPE, &sender);
+        let pre_note =
+            init_anon_xfr_note(&[oabar], &[payment.clone(), change.clone()], fee, &sender).unwrap();
+        let hash = random_hasher(&mut prng);
+        let note = finish_anon_xfr_note(&mut prng, &params, pre_note, hash).unwrap();
+
+        // the sender discloses the amount and the recipient of the payment
+        let disclosure_proof = note
+            .disclose(
+                &mut prng,
+                &disclosure_params,
+                &sender,
+                &payment,
+                &[AXfrDisclosedField::Amount, AXfrDisclosedField::Recipient],
+            )
+            .unwrap();
+        let payment_index = note
+            .body
+            .outputs
+            .iter()
+            .position(|abar| *abar == AnonAssetRecord::from_oabar(&payment))
+            .unwrap();
+        assert_eq!(
+            disclosure_proof.disclosure.output_index as usize,
+            payment_index
+        );
+        assert_eq!(disclosure_proof.disclosure.amount, Some(AMOUNT - 3));
+        assert_eq!(disclosure_proof.disclosure.asset_type, None);
+        assert_eq!(
+            disclosure_proof.disclosure.recipient,
+            Some(receiver.get_pk())
+        );
+        assert!(
+            verify_disclosure_proof(&disclosure_verifier_params, &note, &disclosure_proof).is_ok()
+        );
+
+        // a different amount cannot be claimed
+        let mut wrong_proof = disclosure_proof.clone();
+        wrong_proof.disclosure.amount = Some(AMOUNT);
+        assert!(verify_disclosure_proof(&disclosure_verifier_params, &note, &wrong_proof).is_err());
+
+        // the disclosure cannot be attributed to someone else
+        let mut wrong_proof = disclosure_proof.clone();
+        wrong_proof.discloser = auditor.get_pk();
+        assert!(verify_disclosure_proof(&disclosure_verifier_params, &note, &wrong_proof).is_err());
+
+        // the disclosure is bound to its output
+        let mut wrong_proof = disclosure_proof;
+        wrong_proof.disclosure.output_index = 1 - payment_index as u32;
+        assert!(verify_disclosure_proof(&disclosure_verifier_params, &note, &wrong_proof).is_err());
+
+        // an output that is not in the note cannot be disclosed
+        let other = build_oabar(&mut prng, AMOUNT, FEE_TYPE, &receiver);
+        assert!(note
+            .disclose(
+                &mut prng,
+                &disclosure_params,
+                &sender,
+                &other,
+                &[AXfrDisclosedField::Amount],
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn abar_amount_range_proof() {
+        let mut prng = test_rng();
+        let params = ProverParams::gen_abar_amount_range().unwrap();
+        let verifier_params = VerifierParams::get_abar_amount_range().unwrap();
+
+        let owner = KeyPair::sample(&mut prng, SECP256K1);
+        let oabar = build_oabar(&mut prng, 1500, FEE_TYPE, &owner);
+        let abar = AnonAssetRecord::from_oabar(&oabar);
+
+        // balance >= 1000
+        let range_proof = prove_amount_range(&mut prng, &params, &oabar, 1000, u64::MAX).unwrap();
+        assert!(abar
+            .verify_amount_range(&verifier_params, &range_proof)
+            .is_ok());
+        assert!(
+            verify_amount_range_proof(&verifier_params, &abar.commitment, &range_proof).is_ok()
+        );
+
+        // a tighter bound cannot be claimed
+        let mut wrong_proof = range_proof.clone();
+        wrong_proof.lower = 2000;
+        assert!(abar
+            .verify_amount_range(&verifier_params, &wrong_proof)
+            .is_err());
+
+        // the proof is bound to the commitment
+        let other = AnonAssetRecord::from_oabar(&build_oabar(&mut prng, 1500, FEE_TYPE, &owner));
+        assert!(other
+            .verify_amount_range(&verifier_params, &range_proof)
+            .is_err());
+
+        // an amount outside the range cannot be proven
+        assert!(prove_amount_range(&mut prng, &params, &oabar, 0, 1000).is_err());
+        assert!(prove_amount_range(&mut prng, &params, &oabar, 2000, 1000).is_err());
+    }
+
+    #[test]
+    fn abar_swap() {
+        let mut prng = test_rng();
+        let other_asset = AssetType::from_identical_byte(2u8);
+
+        let alice = KeyPair::sample(&mut prng, ED25519);
+        let bob = KeyPair::sample(&mut prng, SECP256K1);
+
+        // alice gives 100 FRA for 30 of another asset, and pays the fee
+        let fee = mock_fee(2, 2);
+        let mut alice_inputs = vec![build_oabar(&mut prng, 100 + fee as u64, FEE_TYPE, &alice)];
+        let mut bob_inputs = vec![build_oabar(&mut prng, 30, other_asset, &bob)];
+
+        let fdb = MemoryDB::new();
+        let cs = Arc::new(RwLock::new(ChainState::new(fdb, "abar_swap".to_owned(), 0)));
+        let mut state = State::new(cs, false);
+        let store = PrefixedStore::new("my_store", &mut state);
+        let mut mt = PersistentMerkleTree::new(store).unwrap();
+        let mut uids = vec![];
+        for oabar in alice_inputs.iter().chain(bob_inputs.iter()) {
+            let abar = AnonAssetRecord::from_oabar(oabar);
+            uids.push(
+                mt.add_commitment_hash(hash_abar(mt.entry_count(), &abar))
+                    .unwrap(),
+            );
+        }
+        mt.commit().unwrap();
+        let root = mt.get_root().unwrap();
+        for (oabar, uid) in alice_inputs
+            .iter_mut()
+            .chain(bob_inputs.iter_mut())
+            .zip(uids)
+        {
+            let proof = mt.generate_proof(uid).unwrap();
+            oabar.update_mt_leaf_info(build_mt_leaf_info_from_proof(proof, uid));
+        }
+
+        let outputs = vec![
+            build_oabar(&mut prng, 100, FEE_TYPE, &bob),
+            build_oabar(&mut prng, 30, other_asset, &alice),
+        ];
+        let pre_note = init_anon_xfr_swap_note(
+            [&alice_inputs[..], &bob_inputs[..]],
+            &outputs,
+            fee,
+            [&alice, &bob],
+        )
+        .unwrap();
+
+        // a party cannot spend the inputs of the other
+        assert!(init_anon_xfr_swap_note(
+            [&alice_inputs[..], &bob_inputs[..]],
+            &outputs,
+            fee,
+            [&bob, &alice],
+        )
+        .is_err());
+
+        // each party checks what it receives before the swap is finished
+        let alice_terms = AXfrSwapTerms {
+            asset_type: other_asset,
+            amount: 30,
+        };
+        let bob_terms = AXfrSwapTerms {
+            asset_type: FEE_TYPE,
+            amount: 100,
+        };
+        check_anon_xfr_swap_terms(&pre_note.body, &alice, &alice_terms).unwrap();
+        check_anon_xfr_swap_terms(&pre_note.body, &bob, &bob_terms).unwrap();
+        assert!(check_anon_xfr_swap_terms(&pre_note.body, &alice, &bob_terms).is_err());
+
+        let hash = random_hasher(&mut prng);
+        let params = ProverParams::gen_abar_swap([1, 1], 2, [ED25519, SECP256K1]).unwrap();
+        let note = finish_anon_xfr_swap_note(&mut prng, &params, pre_note, hash.clone()).unwrap();
+
+        let verifier_params =
+            VerifierParams::get_abar_swap([1, 1], 2, [ED25519, SECP256K1]).unwrap();
+        verify_anon_xfr_swap_note(&verifier_params, &note, &root, hash.clone()).unwrap();
+        assert!(verify_anon_xfr_swap_note(
+            &verifier_params,
+            &note,
+            &root,
+            random_hasher(&mut prng)
+        )
+        .is_err());
+
+        // the proof covers the whole body
+        let mut other_note = note.clone();
+        other_note.body.fee += 1;
+        assert!(
+            verify_anon_xfr_swap_note(&verifier_params, &other_note, &root, hash.clone()).is_err()
+        );
+
+        // and the inputs of each party under its own key
+        let mut other_note = note;
+        other_note.body.inputs.swap(0, 1);
+        other_note.folding_instances.swap(0, 1);
+        assert!(verify_anon_xfr_swap_note(&verifier_params, &other_note, &root, hash).is_err());
+    }
+
+    #[test]
+    fn abar_tracing() {
+        let mut prng = test_rng();
+        let params = ProverParams::gen_abar_to_abar(1, 1, SECP256K1).unwrap();
+        let verifier_params = VerifierParams::load_abar_to_abar(1, 1, SECP256K1).unwrap();
+        let tracing_params = ProverParams::gen_abar_tracing().unwrap();
+        let tracing_verifier_params = VerifierParams::get_abar_tracing().unwrap();
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let fee = mock_fee(1, 1);
+        let mut oabar = build_oabar(&mut prng, AMOUNT + fee as u64, FEE_TYPE, &sender);
+
+        let fdb = MemoryDB::new();
+        let cs = Arc::new(RwLock::new(ChainState::new(
+            fdb,
+            "abar_tracing".to_owned(),
+            0,
+        )));
+        let mut state = State::new(cs, false);
+        let store = PrefixedStore::new("my_store", &mut state);
+        let mut mt = PersistentMerkleTree::new(store).unwrap();
+        let abar = AnonAssetRecord::from_oabar(&oabar);
+        let uid = mt
+            .add_commitment_hash(hash_abar(mt.entry_count(), &abar))
+            .unwrap();
+        mt.commit().unwrap();
+        let root = mt.get_root().unwrap();
+        let proof = mt.generate_proof(uid).unwrap();
+        oabar.update_mt_leaf_info(build_mt_leaf_info_from_proof(proof, uid));
+
+        let oabar_out = build_oabar(&mut prng, AMOUNT, FEE_TYPE, &receiver);
+        let mut pre_note = init_anon_xfr_note(&[oabar], &[oabar_out], fee, &sender).unwrap();
+        let tracer = AXfrTracerKeyPair::generate(&mut prng);
+        add_axfr_tracer_memos(&mut prng, &tracing_params, &mut pre_note, &tracer.enc_key).unwrap();
+        let hash = random_hasher(&mut prng);
+        let note = finish_anon_xfr_note(&mut prng, &params, pre_note, hash.clone()).unwrap();
+
+        verify_anon_xfr_note(&verifier_params, &note, &root, hash.clone()).unwrap();
+        verify_axfr_tracing(tracing_verifier_params, &note.body, &tracer.enc_key).unwrap();
+        let record = note.body.tracer_memos[0]
+            .as_ref()
+            .unwrap()
+            .decrypt(&tracer.dec_key)
+            .unwrap();
+        assert_eq!(record.amount, AMOUNT);
+        assert!(record.is_owned_by(&receiver.get_pk()));
+
+        // another tracer cannot trace the note
+        let other_tracer = AXfrTracerKeyPair::generate(&mut prng);
+        assert!(
+            verify_axfr_tracing(tracing_verifier_params, &note.body, &other_tracer.enc_key)
+                .is_err()
+        );
+
+        // a tampered memo is rejected, although the Plonk proof of the note does not cover it
+        let mut err_note = note.clone();
+        let memo = err_note.body.tracer_memos[0].as_mut().unwrap();
+        memo.ciphertext[0] = memo.ciphertext[0] + BN254Scalar::one();
+        assert!(verify_anon_xfr_note(&verifier_params, &err_note, &root, hash.clone()).is_err());
+        assert!(
+            verify_axfr_tracing(tracing_verifier_params, &err_note.body, &tracer.enc_key).is_err()
+        );
+
+        let mut err_note = note.clone();
+        err_note.body.tracer_memos[0].as_mut().unwrap().enc_key = other_tracer.enc_key;
+        assert!(verify_anon_xfr_note(&verifier_params, &err_note, &root, hash.clone()).is_err());
+
+        // a note without the memo still verifies, but is not traceable
+        let mut untraced_note = note;
+        untraced_note.body.tracer_memos = vec![None];
+        verify_anon_xfr_note(&verifier_params, &untraced_note, &root, hash).unwrap();
+        assert!(verify_axfr_tracing(
+            tracing_verifier_params,
+            &untraced_note.body,
+            &tracer.enc_key
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn abar_output_payloads() {
+        let mut prng = test_rng();
+        let params = ProverParams::gen_abar_to_abar(1, 2, SECP256K1).unwrap();
+        let verifier_params = VerifierParams::load_abar_to_abar(1, 2, SECP256K1).unwrap();
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let receivers = [
+            KeyPair::sample(&mut prng, ED25519),
+            KeyPair::sample(&mut prng, SECP256K1),
+        ];
+        let fee = mock_fee(1, 2);
+        let mut oabar = build_oabar(&mut prng, 2 * AMOUNT + fee as u64, FEE_TYPE, &sender);
+
+        let fdb = MemoryDB::new();
+        let cs = Arc::new(RwLock::new(ChainState::new(
+            fdb,
+            "abar_output_payloads".to_owned(),
+            0,
+        )));
+        let mut state = State::new(cs, false);
+        let store = PrefixedStore::new("my_store", &mut state);
+        let mut mt = PersistentMerkleTree::new(store).unwrap();
+        let abar = AnonAssetRecord::from_oabar(&oabar);
+        let uid = mt
+            .add_commitment_hash(hash_abar(mt.entry_count(), &abar))
+            .unwrap();
+        mt.commit().unwrap();
+        let root = mt.get_root().unwrap();
+        let proof = mt.generate_proof(uid).unwrap();
+        oabar.update_mt_leaf_info(build_mt_leaf_info_from_proof(proof, uid));
+
+        let oabars_out = receivers
+            .iter()
+            .map(|receiver| build_oabar(&mut prng, AMOUNT, FEE_TYPE, receiver))
+            .collect_vec();
+        let mut pre_note = init_anon_xfr_note(&[oabar], &oabars_out, fee, &sender).unwrap();
+
+        // the payloads are attached to the outputs by their records, whatever their order
+        let tags: [&[u8]; 2] = [b"deposit tag", b"another deposit tag"];
+        for (oabar_out, tag) in oabars_out.iter().zip(tags.iter()) {
+            let output = AnonAssetRecord::from_oabar(oabar_out);
+            add_axfr_output_payload(&mut prng, &mut pre_note, &output, tag).unwrap();
+        }
+        let other = build_oabar(&mut prng, AMOUNT, FEE_TYPE, &receivers[0]);
+        assert!(add_axfr_output_payload(
+            &mut prng,
+            &mut pre_note,
+            &AnonAssetRecord::from_oabar(&other),
+            tags[0]
+        )
+        .is_err());
+
+        let hash = random_hasher(&mut prng);
+        let note = finish_anon_xfr_note(&mut prng, &params, pre_note, hash.clone()).unwrap();
+        verify_anon_xfr_note(&verifier_params, &note, &root, hash.clone()).unwrap();
+
+        let bytes = to_bincode(&note).unwrap();
+        let note = AXfrNote::from_bincode(&bytes).unwrap();
+        let bytes = rmp_serde::to_vec(&note).unwrap();
+        let note: AXfrNote = rmp_serde::from_slice(&bytes).unwrap();
+        verify_anon_xfr_note(&verifier_params, &note, &root, hash.clone()).unwrap();
+
+        for ((receiver, oabar_out), tag) in receivers.iter().zip(oabars_out.iter()).zip(tags) {
+            let j = note
+                .body
+                .outputs
+                .iter()
+                .position(|abar| *abar == AnonAssetRecord::from_oabar(oabar_out))
+                .unwrap();
+            let payload = note.body.output_payloads[j].as_ref().unwrap();
+            assert_eq!(payload.decrypt(&receiver.get_sk()).unwrap(), tag);
+        }
+
+        // the payloads are checked for their sizes
+        let mut err_note = note;
+        err_note.body.output_payloads.push(None);
+        assert!(verify_anon_xfr_note(&verifier_params, &err_note, &root, hash).is_err());
+    }
+
+    #[test]
+    fn abar_outputs_order_activation() {
+        let mut prng = test_rng();
+        let params = ProverParams::gen_abar_to_abar(1, 2, SECP256K1).unwrap();
+        let verifier_params = VerifierParams::load_abar_to_abar(1, 2, SECP256K1).unwrap();
+
+        let sender = KeyPair::sample(&mut prng, SECP256K1);
+        let receiver = KeyPair::sample(&mut prng, ED25519);
+        let fee = mock_fee(1, 2);
+        let mut oabar = build_oabar(&mut prng, 2 * AMOUNT + fee as u64, FEE_TYPE, &sender);
+
+        let fdb = MemoryDB::new();
+        let cs = Arc::new(RwLock::new(ChainState::new(
+            fdb,
+            "abar_outputs_order_activation".to_owned(),
+            0,
+        )));
+        let mut state = State::new(cs, false);
+        let store = PrefixedStore::new("my_store", &mut state);
+        let mut mt = PersistentMerkleTree::new(store).unwrap();
+        let abar = AnonAssetRecord::from_oabar(&oabar);
+        let uid = mt
+            .add_commitment_hash(hash_abar(mt.entry_count(), &abar))
+            .unwrap();
+        mt.commit().unwrap();
+        let root = mt.get_root().unwrap();
+        let proof = mt.generate_proof(uid).unwrap();
+        oabar.update_mt_leaf_info(build_mt_leaf_info_from_proof(proof, uid));
+
+        let oabars_out = [
+            build_oabar(&mut prng, AMOUNT, FEE_TYPE, &receiver),
+            build_oabar(&mut prng, AMOUNT, FEE_TYPE, &receiver),
+        ];
+        let hash_input = b"transaction without the note";
+        let mut hash = Sha512::new();
+        hash.update(hash_input);
+
+        // a note as created before the outputs were shuffled, in the order of the sender
+        let mut pre_note = init_anon_xfr_note(&[oabar.clone()], &oabars_out, fee, &sender).unwrap();
+        pre_note.body.outputs.reverse();
+        pre_note.body.owner_memos.reverse();
+        pre_note.witness.payees_witnesses.reverse();
+        pre_note.output_commitments_traces.reverse();
+        let unshuffled_note =
+            finish_anon_xfr_note(&mut prng, &params, pre_note, hash.clone()).unwrap();
+        assert!(check_anon_xfr_outputs_order(&unshuffled_note.body).is_err());
+
+        let pre_note = init_anon_xfr_note(&[oabar], &oabars_out, fee, &sender).unwrap();
+        let note = finish_anon_xfr_note(&mut prng, &params, pre_note, hash).unwrap();
+
+        let mut ctx = NoteVerifyContext {
+            verifier_params: Some(&verifier_params),
+            bulletproof_params: None,
+            merkle_root: Some(root),
+            hash_input,
+            disabled: None,
+            normalize_errors: false,
+            check_outputs_order: false,
+        };
+        // before the activation, both notes are accepted
+        assert!(note.verify(&mut ctx).is_ok());
+        assert!(unshuffled_note.verify(&mut ctx).is_ok());
+
+        // after, only the note in the canonical order is
+        ctx.check_outputs_order = true;
+        assert!(note.verify(&mut ctx).is_ok());
+        assert!(unshuffled_note.verify(&mut ctx).is_err());
+    }
+
+    #[test]
+    fn abar_coinjoin() {
+        let mut prng = test_rng();
+
+        let participants = [
+            KeyPair::sample(&mut prng, ED25519),
+            KeyPair::sample(&mut prng, SECP256K1),
+            KeyPair::sample(&mut prng, ED25519),
+        ];
+        let receivers = [
+            KeyPair::sample(&mut prng, SECP256K1),
+            KeyPair::sample(&mut prng, ED25519),
+            KeyPair::sample(&mut prng, SECP256K1),
+        ];
+
+        let fdb = MemoryDB::new();
+        let cs = Arc::new(RwLock::new(ChainState::new(
+            fdb,
+            "abar_coinjoin".to_owned(),
+            0,
+        )));
+        let mut state = State::new(cs, false);
+        let store = PrefixedStore::new("my_store", &mut state);
+        let mut mt = PersistentMerkleTree::new(store).unwrap();
+        let mut inputs = participants
+            .iter()
+            .map(|keypair| build_oabar(&mut prng, AMOUNT, ASSET, keypair))
+            .collect_vec();
+        let mut uids = vec![];
+        for oabar in inputs.iter() {
+            let abar = AnonAssetRecord::from_oabar(oabar);
+            uids.push(
+                mt.add_commitment_hash(hash_abar(mt.entry_count(), &abar))
+                    .unwrap(),
+            );
+        }
+        mt.commit().unwrap();
+        let root = mt.get_root().unwrap();
+        let root_version = mt.version();
+        for (oabar, uid) in inputs.iter_mut().zip(uids) {
+            let proof = mt.generate_proof(uid).unwrap();
+            oabar.update_mt_leaf_info(build_mt_leaf_info_from_proof(proof, uid));
+        }
+
+        let input_params = |keypair: &KeyPair| match keypair.get_sk_ref() {
+            SecretKey::Ed25519(_) => ProverParams::gen_abar_to_ar(ED25519).unwrap(),
+            SecretKey::Secp256k1(_) => ProverParams::gen_abar_to_ar(SECP256K1).unwrap(),
+        };
+        let output_params = ProverParams::gen_ar_to_abar().unwrap();
+        let verifier_params = CoinJoinVerifierParams::get().unwrap();
+
+        let mut coordinator =
+            CoinJoinCoordinator::new(&mut prng, ASSET, AMOUNT, root, root_version);
+        let round = *coordinator.round();
+
+        // the input registrations
+        let mut tickets = vec![];
+        let mut registrations: Vec<CoinJoinInputRegistration> = vec![];
+        for ((oabar, keypair), receiver) in
+            inputs.iter().zip(participants.iter()).zip(receivers.iter())
+        {
+            let (registration, ticket) = register_coinjoin_input(
+                &mut prng,
+                &input_params(keypair),
+                &output_params,
+                &round,
+                oabar,
+                keypair,
+                &receiver.get_pk(),
+            )
+            .unwrap();
+            // the proof of ownership does not carry over to another blinded token
+            if let Some(previous) = registrations.last() {
+                let mut other_registration = registration.clone();
+                other_registration.blinded_token = previous.blinded_token;
+                assert!(coordinator
+                    .register_input(&verifier_params, &other_registration)
+                    .is_err());
+            }
+            let blinded_signature = coordinator
+                .register_input(&verifier_params, &registration)
+                .unwrap();
+            tickets.push((ticket, blinded_signature));
+            registrations.push(registration);
+        }
+        // an input registers only once
+        assert!(coordinator
+            .register_input(&verifier_params, &registrations[0])
+            .is_err());
+        coordinator.close_input_registration().unwrap();
+
+        // the output registrations, in another order
+        let mut outputs = vec![];
+        for (ticket, blinded_signature) in tickets.into_iter().rev() {
+            let output_registration = ticket
+                .output_registration(&round, &blinded_signature)
+                .unwrap();
+            outputs.push(output_registration.output.output.clone());
+            coordinator
+                .register_output(&verifier_params, &output_registration)
+                .unwrap();
+            // a token is used once
+            assert!(coordinator
+                .register_output(&verifier_params, &output_registration)
+                .is_err());
+        }
+        outputs.reverse();
+        let body = coordinator.close_output_registration().unwrap().clone();
+        assert_eq!(body.inputs.len(), 3);
+
+        // each participant signs for its input after finding its output
+        let hash = random_hasher(&mut prng);
+        for (((oabar, keypair), output), registration) in inputs
+            .iter()
+            .zip(participants.iter())
+            .zip(outputs.iter())
+            .zip(registrations.iter())
+        {
+            let proof = sign_coinjoin_input(
+                &mut prng,
+                &input_params(keypair),
+                &body,
+                oabar,
+                keypair,
+                output,
+                hash.clone(),
+            )
+            .unwrap();
+            coordinator
+                .add_input_proof(&verifier_params, &registration.input, proof, hash.clone())
+                .unwrap();
+        }
+        let note = coordinator.finalize().unwrap();
+        verify_coinjoin_note(&verifier_params, &note, &root, hash.clone()).unwrap();
+
+        // each receiver opens one of the outputs
+        for receiver in receivers.iter() {
+            let opened = note
+                .body
+                .outputs
+                .iter()
+                .filter_map(|o| {
+                    OpenAnonAssetRecordBuilder::from_abar(&o.output, o.memo.clone(), receiver).ok()
+                })
+                .map(|builder| builder.build().unwrap())
+                .collect_vec();
+            assert_eq!(opened.len(), 1);
+            assert_eq!(opened[0].get_amount(), AMOUNT);
+            assert_eq!(opened[0].get_asset_type(), ASSET);
+        }
+
+        assert!(
+            verify_coinjoin_note(&verifier_params, &note, &root, random_hasher(&mut prng)).is_err()
+        );
+
+        // the inputs are bound to every output
+        let mut err_note = note.clone();
+        let other_output = build_oabar(&mut prng, AMOUNT, ASSET, &receivers[0]);
+        err_note.body.outputs[0].output = AnonAssetRecord::from_oabar(&other_output);
+        assert!(verify_coinjoin_note(&verifier_params, &err_note, &root, hash.clone()).is_err());
+
+        let mut err_note = note.clone();
+        err_note.body.outputs.pop();
+        err_note.body.inputs.pop();
+        err_note.input_proofs.pop();
+        assert!(verify_coinjoin_note(&verifier_params, &err_note, &root, hash).is_err());
+    }
+
     fn test_abar(
         inputs: Vec<(u64, AssetType)>,
         outputs: Vec<(u64, AssetType)>,
@@ -646,30 +1510,121 @@ mod smoke_axfr {
 
         verify_anon_xfr_note(&verifier_params, &note, &root, hash.clone()).unwrap();
 
+        // a note in the layout from before the tracer memos keeps its digest and decodes
+        let legacy_bytes = to_bincode(&(
+            (
+                &note.body.inputs,
+                &note.body.outputs,
+                &note.body.merkle_root,
+                &note.body.merkle_root_version,
+                &note.body.fee,
+                &note.body.owner_memos,
+            ),
+            &note.proof,
+            &note.folding_instance,
+        ))
+        .unwrap();
+        let mut hasher = Sha512::new();
+        hasher.update(b"Anon Xfr Note Digest");
+        hasher.update(&legacy_bytes);
+        assert_eq!(note.digest().unwrap()[..], hasher.finalize()[..]);
+        assert_eq!(AXfrNote::from_bincode(&legacy_bytes).unwrap(), note);
+        assert_eq!(
+            AXfrNote::from_bincode(&to_bincode(&note).unwrap()).unwrap(),
+            note
+        );
+
+        // a note with tracer memos, in the layout from before the output payloads, keeps its
+        // digest and decodes, while a note with output payloads is hashed in full
+        let tracer = AXfrTracerKeyPair::generate(&mut prng);
+        let mut traced_note = note.clone();
+        traced_note.body.tracer_memos = vec![
+            Some(AXfrTracerMemo {
+                enc_key: tracer.enc_key,
+                dh_point_div_by_cofactor: BabyJubjubPoint::get_base(),
+                ciphertext: vec![],
+                proof: note.proof.clone(),
+            });
+            outputs.len()
+        ];
+        let traced_bytes = to_bincode(&(
+            (
+                &traced_note.body.inputs,
+                &traced_note.body.outputs,
+                &traced_note.body.merkle_root,
+                &traced_note.body.merkle_root_version,
+                &traced_note.body.fee,
+                &traced_note.body.owner_memos,
+                &traced_note.body.tracer_memos,
+            ),
+            &traced_note.proof,
+            &traced_note.folding_instance,
+        ))
+        .unwrap();
+        let mut hasher = Sha512::new();
+        hasher.update(b"Anon Xfr Note Digest");
+        hasher.update(&traced_bytes);
+        assert_eq!(traced_note.digest().unwrap()[..], hasher.finalize()[..]);
+        assert_eq!(AXfrNote::from_bincode(&traced_bytes).unwrap(), traced_note);
+        assert_eq!(
+            AXfrNote::from_bincode(&to_bincode(&traced_note).unwrap()).unwrap(),
+            traced_note
+        );
+
+        let mut paid_note = traced_note.clone();
+        paid_note.body.output_payloads = receivers
+            .iter()
+            .map(|receiver| {
+                Some(AxfrOutputPayload::new(&mut prng, &receiver.get_pk(), b"deposit tag").unwrap())
+            })
+            .collect();
+        let paid_bytes = to_bincode(&paid_note).unwrap();
+        let mut hasher = Sha512::new();
+        hasher.update(b"Anon Xfr Note Digest");
+        hasher.update(&paid_bytes);
+        assert_eq!(paid_note.digest().unwrap()[..], hasher.finalize()[..]);
+        assert_eq!(AXfrNote::from_bincode(&paid_bytes).unwrap(), paid_note);
+
         #[cfg(feature = "parallel")]
         {
             let verifiers_params = vec![&verifier_params; 6];
             let notes = vec![&note; 6];
             let merkle_roots = vec![&root; 6];
             let hashes = vec![hash.clone(); 6];
-            assert!(
-                batch_verify_anon_xfr_note(&verifiers_params, &notes, &merkle_roots, hashes)
-                    .is_ok()
-            );
+            assert!(batch_verify_anon_xfr_note(
+                &verifiers_params,
+                &notes,
+                &merkle_roots,
+                hashes.clone()
+            )
+            .is_ok());
+            assert!(batch_verify_anon_xfr_note(
+                &verifiers_params[..5],
+                &notes,
+                &merkle_roots,
+                hashes
+            )
+            .is_err());
         }
 
-        // check abar
-        for i in 0..note.body.outputs.len() {
+        // check abar, where the outputs are shuffled by the note
+        for (i, oabar_out) in oabars_out.iter().enumerate() {
+            let j = note
+                .body
+                .outputs
+                .iter()
+                .position(|abar| *abar == AnonAssetRecord::from_oabar(oabar_out))
+                .unwrap();
             let oabar = OpenAnonAssetRecordBuilder::from_abar(
-                &note.body.outputs[i],
-                note.body.owner_memos[i].clone(),
+                &note.body.outputs[j],
+                note.body.owner_memos[j].clone(),
                 &receivers[i],
             )
             .unwrap()
             .build()
             .unwrap();
-            assert_eq!(oabars_out[i].get_amount(), oabar.get_amount());
-            assert_eq!(oabars_out[i].get_asset_type(), oabar.get_asset_type());
+            assert_eq!(oabar_out.get_amount(), oabar.get_amount());
+            assert_eq!(oabar_out.get_asset_type(), oabar.get_asset_type());
         }
     }
 }
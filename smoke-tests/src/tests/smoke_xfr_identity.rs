@@ -26,13 +26,12 @@ mod smoke_xfr_identity {
         asset_type: AssetType,
     ) -> (BlindAssetRecord, OwnerMemo) {
         let mut prng = test_rng();
-        let template = AssetRecordTemplate {
+        let template = AssetRecordTemplate::with_no_asset_tracing(
             amount,
             asset_type,
-            public_key: key.clone(),
-            asset_record_type: AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
-            asset_tracing_policies: Default::default(),
-        };
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+            key.clone(),
+        );
         let (bar, _, owner) = build_blind_asset_record(
             &mut prng,
             &PedersenCommitmentRistretto::default(),
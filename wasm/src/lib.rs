@@ -0,0 +1,344 @@
+//! The WASM bindings of the Noah library for wallets.
+//!
+//! Keys, asset types, and commitments are passed as byte arrays (`Uint8Array` in JavaScript),
+//! records and memos as their JSON serialization, and notes as their MessagePack serialization,
+//! which is the format accepted by `noah::consensus::verify_note_bytes`.
+#![deny(unused_import_braces, unused_qualifications, trivial_casts)]
+#![deny(trivial_numeric_casts)]
+#![deny(stable_features, unreachable_pub, non_shorthand_field_patterns)]
+#![deny(unused_attributes, unused_imports, unused_mut, missing_docs)]
+#![deny(renamed_and_removed_lints, stable_features, unused_allocation)]
+#![deny(unused_comparisons, bare_trait_objects, unused_must_use)]
+#![forbid(unsafe_code)]
+
+use noah::anon_xfr::{
+    abar_to_abar::AXfrNote,
+    abar_to_ar::AbarToArNote,
+    abar_to_bar::AbarToBarNote,
+    ar_to_abar::ArToAbarNote,
+    bar_to_abar::BarToAbarNote,
+    structs::{AnonAssetRecord, AxfrOwnerMemo, OpenAnonAssetRecord, OpenAnonAssetRecordBuilder},
+};
+use noah::consensus::{
+    verify_note_bytes, NoteTrait, NoteVerifyContext, NOTE_KIND_ABAR_TO_ABAR, NOTE_KIND_ABAR_TO_AR,
+    NOTE_KIND_ABAR_TO_BAR, NOTE_KIND_AR_TO_ABAR, NOTE_KIND_BAR_TO_ABAR, NOTE_KIND_XFR,
+    VERIFY_ERR_DESERIALIZATION,
+};
+use noah::keys::{KeyPair, PublicKey};
+use noah::parameters::{
+    bulletproofs::BulletproofParams,
+    params::VerifierParams,
+    AddressFormat::{ED25519, SECP256K1},
+};
+use noah::xfr::{
+    asset_record::open_blind_asset_record as open_bar,
+    structs::{AssetType, BlindAssetRecord, OwnerMemo},
+};
+use noah_algebra::{bn254::BN254Scalar, prelude::*};
+use rand_chacha::ChaChaRng;
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::prelude::*;
+
+fn js_err<E: ToString>(e: E) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, JsValue> {
+    serde_json::from_str(json).map_err(js_err)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, JsValue> {
+    serde_json::to_string(value).map_err(js_err)
+}
+
+fn keypair_from_bytes(keypair: &[u8]) -> Result<KeyPair, JsValue> {
+    KeyPair::noah_from_bytes(keypair).map_err(js_err)
+}
+
+/// Encode the bytes in hex.
+#[wasm_bindgen]
+pub fn to_hex(bytes: &[u8]) -> String {
+    hex::encode(bytes)
+}
+
+/// Decode the bytes from hex.
+#[wasm_bindgen]
+pub fn from_hex(s: &str) -> Result<Vec<u8>, JsValue> {
+    hex::decode(s).map_err(js_err)
+}
+
+/// Generate a key pair from the randomness of the environment,
+/// and return its serialization.
+#[wasm_bindgen]
+pub fn generate_keypair(secp256k1: bool) -> Vec<u8> {
+    let mut prng = ChaChaRng::from_entropy();
+    let address_format = if secp256k1 { SECP256K1 } else { ED25519 };
+    KeyPair::sample(&mut prng, address_format).noah_to_bytes()
+}
+
+/// Return the serialization of the public key of the serialized key pair.
+#[wasm_bindgen]
+pub fn keypair_public_key(keypair: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Ok(keypair_from_bytes(keypair)?.get_pk().noah_to_bytes())
+}
+
+/// An opened confidential or transparent record.
+#[wasm_bindgen]
+pub struct OpenedRecord {
+    amount: u64,
+    asset_type: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl OpenedRecord {
+    /// The amount.
+    #[wasm_bindgen(getter)]
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    /// The asset type.
+    #[wasm_bindgen(getter)]
+    pub fn asset_type(&self) -> Vec<u8> {
+        self.asset_type.clone()
+    }
+}
+
+/// Open a confidential or transparent record with its owner memo, if any.
+#[wasm_bindgen]
+pub fn open_blind_asset_record(
+    record: &str,
+    owner_memo: Option<String>,
+    keypair: &[u8],
+) -> Result<OpenedRecord, JsValue> {
+    let record: BlindAssetRecord = from_json(record)?;
+    let owner_memo: Option<OwnerMemo> = owner_memo.as_deref().map(from_json).transpose()?;
+    let keypair = keypair_from_bytes(keypair)?;
+
+    let oar = open_bar(&record, &owner_memo, &keypair).map_err(js_err)?;
+    Ok(OpenedRecord {
+        amount: *oar.get_amount(),
+        asset_type: oar.get_asset_type().0.to_vec(),
+    })
+}
+
+/// An opened anonymous record, with its record and owner memo.
+#[wasm_bindgen]
+pub struct AnonRecord {
+    amount: u64,
+    asset_type: Vec<u8>,
+    commitment: Vec<u8>,
+    abar: String,
+    owner_memo: String,
+    oabar: String,
+}
+
+#[wasm_bindgen]
+impl AnonRecord {
+    /// The amount.
+    #[wasm_bindgen(getter)]
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    /// The asset type.
+    #[wasm_bindgen(getter)]
+    pub fn asset_type(&self) -> Vec<u8> {
+        self.asset_type.clone()
+    }
+
+    /// The commitment of the record.
+    #[wasm_bindgen(getter)]
+    pub fn commitment(&self) -> Vec<u8> {
+        self.commitment.clone()
+    }
+
+    /// The JSON of the anonymous record.
+    #[wasm_bindgen(getter)]
+    pub fn abar(&self) -> String {
+        self.abar.clone()
+    }
+
+    /// The JSON of the owner memo.
+    #[wasm_bindgen(getter)]
+    pub fn owner_memo(&self) -> String {
+        self.owner_memo.clone()
+    }
+
+    /// The JSON of the opened anonymous record, which is the input of the note builders.
+    #[wasm_bindgen(getter)]
+    pub fn oabar(&self) -> String {
+        self.oabar.clone()
+    }
+}
+
+impl AnonRecord {
+    fn new(oabar: &OpenAnonAssetRecord) -> Result<Self, JsValue> {
+        let abar = AnonAssetRecord::from_oabar(oabar);
+        let owner_memo = oabar
+            .get_owner_memo()
+            .ok_or_else(|| js_err("The record has no owner memo"))?;
+        Ok(AnonRecord {
+            amount: oabar.get_amount(),
+            asset_type: oabar.get_asset_type().0.to_vec(),
+            commitment: abar.commitment.to_bytes(),
+            abar: to_json(&abar)?,
+            owner_memo: to_json(&owner_memo)?,
+            oabar: to_json(oabar)?,
+        })
+    }
+}
+
+/// Build a new anonymous record of the amount and the asset type for the public key.
+#[wasm_bindgen]
+pub fn build_anon_asset_record(
+    amount: u64,
+    asset_type: &[u8],
+    public_key: &[u8],
+) -> Result<AnonRecord, JsValue> {
+    let asset_type = AssetType::noah_from_bytes(asset_type).map_err(js_err)?;
+    let public_key = PublicKey::noah_from_bytes(public_key).map_err(js_err)?;
+
+    let mut prng = ChaChaRng::from_entropy();
+    let oabar = OpenAnonAssetRecordBuilder::new()
+        .amount(amount)
+        .asset_type(asset_type)
+        .pub_key(&public_key)
+        .finalize(&mut prng)
+        .and_then(|builder| builder.build())
+        .map_err(js_err)?;
+    AnonRecord::new(&oabar)
+}
+
+/// Open an anonymous record with its owner memo.
+#[wasm_bindgen]
+pub fn open_anon_asset_record(
+    abar: &str,
+    owner_memo: &str,
+    keypair: &[u8],
+) -> Result<AnonRecord, JsValue> {
+    let abar: AnonAssetRecord = from_json(abar)?;
+    let owner_memo: AxfrOwnerMemo = from_json(owner_memo)?;
+    let keypair = keypair_from_bytes(keypair)?;
+
+    let oabar = OpenAnonAssetRecordBuilder::from_abar(&abar, owner_memo, &keypair)
+        .and_then(|builder| builder.build())
+        .map_err(js_err)?;
+    AnonRecord::new(&oabar)
+}
+
+/// Verify the MessagePack serialization of a note of the kind, against the Merkle root
+/// for the notes spending anonymous records, and return the code of
+/// `noah::consensus::verify_note_bytes`, which is 0 if the note is valid.
+#[wasm_bindgen]
+pub fn verify_note(kind: u32, note: &[u8], merkle_root: Option<Vec<u8>>, hash_input: &[u8]) -> u32 {
+    let merkle_root = match merkle_root
+        .map(|bytes| BN254Scalar::from_bytes(&bytes))
+        .transpose()
+    {
+        Ok(merkle_root) => merkle_root,
+        Err(_) => return VERIFY_ERR_DESERIALIZATION,
+    };
+
+    let verifier_params = match kind {
+        NOTE_KIND_ABAR_TO_ABAR => verifier_params_of::<AXfrNote>(note),
+        NOTE_KIND_AR_TO_ABAR => verifier_params_of::<ArToAbarNote>(note),
+        NOTE_KIND_BAR_TO_ABAR => verifier_params_of::<BarToAbarNote>(note),
+        NOTE_KIND_ABAR_TO_AR => verifier_params_of::<AbarToArNote>(note),
+        NOTE_KIND_ABAR_TO_BAR => verifier_params_of::<AbarToBarNote>(note),
+        _ => None,
+    };
+    let mut bulletproof_params = (kind == NOTE_KIND_XFR).then(BulletproofParams::default);
+
+    let mut ctx = NoteVerifyContext {
+        verifier_params: verifier_params.as_ref(),
+        bulletproof_params: bulletproof_params.as_mut(),
+        merkle_root,
+        hash_input,
+        disabled: None,
+        normalize_errors: false,
+        check_outputs_order: true,
+    };
+    verify_note_bytes(kind, note, &mut ctx)
+}
+
+/// Return the verifier parameters of the circuit of the note. A note that cannot be
+/// decoded gets none, and is then rejected by `verify_note_bytes`.
+fn verifier_params_of<T: NoteTrait>(note: &[u8]) -> Option<VerifierParams> {
+    let note: T = rmp_serde::from_slice(note).ok()?;
+    note.verifier_params().ok().flatten()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        build_anon_asset_record, from_hex, generate_keypair, keypair_public_key,
+        open_anon_asset_record, open_blind_asset_record, to_hex, verify_note,
+    };
+    use noah::consensus::{VERIFY_ERR_DESERIALIZATION, VERIFY_ERR_UNKNOWN_KIND};
+    use noah::keys::KeyPair;
+    use noah::xfr::{
+        asset_record::{build_blind_asset_record, AssetRecordType},
+        structs::{AssetRecordTemplate, AssetType},
+    };
+    use noah_algebra::{prelude::*, ristretto::PedersenCommitmentRistretto};
+
+    #[test]
+    fn test_anon_asset_record() {
+        let keypair = generate_keypair(false);
+        let public_key = keypair_public_key(&keypair).unwrap();
+        let asset_type = [7u8; 32];
+
+        let built = build_anon_asset_record(100, &asset_type, &public_key).unwrap();
+        let opened = open_anon_asset_record(&built.abar(), &built.owner_memo(), &keypair).unwrap();
+        assert_eq!(opened.amount(), 100);
+        assert_eq!(opened.asset_type(), asset_type.to_vec());
+        assert_eq!(opened.commitment(), built.commitment());
+        assert_eq!(
+            from_hex(&to_hex(&opened.commitment())).unwrap(),
+            built.commitment()
+        );
+
+        // another key cannot open the record
+        let other = generate_keypair(true);
+        assert!(crate::OpenAnonAssetRecordBuilder::from_abar(
+            &serde_json::from_str(&built.abar()).unwrap(),
+            serde_json::from_str(&built.owner_memo()).unwrap(),
+            &KeyPair::noah_from_bytes(&other).unwrap(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_blind_asset_record() {
+        let mut prng = test_rng();
+        let keypair = KeyPair::sample(&mut prng, noah::parameters::AddressFormat::ED25519);
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            10,
+            AssetType::from_identical_byte(1),
+            AssetRecordType::ConfidentialAmount_ConfidentialAssetType,
+            keypair.get_pk(),
+        );
+        let pc_gens = PedersenCommitmentRistretto::default();
+        let (record, _, owner_memo) =
+            build_blind_asset_record(&mut prng, &pc_gens, &template, vec![]);
+
+        let opened = open_blind_asset_record(
+            &serde_json::to_string(&record).unwrap(),
+            owner_memo.map(|memo| serde_json::to_string(&memo).unwrap()),
+            &keypair.noah_to_bytes(),
+        )
+        .unwrap();
+        assert_eq!(opened.amount(), 10);
+        assert_eq!(opened.asset_type(), vec![1u8; 32]);
+    }
+
+    #[test]
+    fn test_verify_note() {
+        assert_eq!(verify_note(0, &[1], None, &[]), VERIFY_ERR_UNKNOWN_KIND);
+        assert_eq!(
+            verify_note(3, &[0xc1], None, &[]),
+            VERIFY_ERR_DESERIALIZATION
+        );
+    }
+}